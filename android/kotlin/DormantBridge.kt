@@ -34,4 +34,46 @@ object DormantBridge {
      * @param storagePath Absolute path to app's files directory
      */
     external fun initDormantStorage(storagePath: String)
+
+    /**
+     * Reset all app data to a fresh install.
+     *
+     * Calls Rust's storage::clear_all() which removes saved addresses,
+     * resets settings to defaults and clears notification history.
+     * initDormantStorage must have been called first.
+     *
+     * @return true if everything was reset, false on failure
+     */
+    external fun rustClearAllData(): Boolean
+
+    /**
+     * Report whether the app is visible.
+     *
+     * Calls Rust's geo::set_app_foreground(). The geo watch only reads the
+     * device location while the app is in the foreground.
+     *
+     * @param foreground true from onResume, false from onPause
+     */
+    external fun setAppForeground(foreground: Boolean)
+
+    /**
+     * Count the parking spaces across the active saved addresses.
+     *
+     * Calls Rust's stats::total_affected_spaces(). Addresses without a known
+     * space count are reported separately instead of being summed.
+     * initDormantStorage must have been called first.
+     *
+     * @return JSON string: {"spaces":42,"counted":5,"unknown":1}
+     */
+    external fun trackedSpaces(): String
+
+    /**
+     * Ask the UI to expand a panel.
+     *
+     * Calls Rust's notifications::request_panel(); MainActivity passes the
+     * panel name a tapped notification was posted with.
+     *
+     * @param panel Bucket name, e.g. "Within6Hours"
+     */
+    external fun openPanel(panel: String)
 }
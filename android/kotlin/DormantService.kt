@@ -183,7 +183,8 @@ class DormantService : Service() {
      *
      * 1. Calls DormantBridge.dormantCheck(storagePath) → JSON string
      * 2. Parses JSON array of notification objects
-     * 3. Sends each notification via NotificationHelper
+     * 3. Sends each notification via NotificationHelper, delaying those with a
+     *    "fire_at" timestamp until that instant
      */
     private fun performDormantCheck() {
         Log.i(TAG, "Performing dormant check...")
@@ -203,9 +204,21 @@ class DormantService : Service() {
                 val notificationId = notif.getInt("notification_id")
                 val title = notif.getString("title")
                 val body = notif.getString("body")
+                val panel = if (notif.has("panel")) notif.getString("panel") else null
+
+                if (notif.has("fire_at")) {
+                    val delayMs = notif.getLong("fire_at") - System.currentTimeMillis()
+                    if (delayMs > 0) {
+                        Log.d(TAG, "Scheduling notification in ${delayMs / 60_000} min: channel=$channelId, id=$notificationId")
+                        handler.postDelayed({
+                            NotificationHelper.showNotification(this, channelId, notificationId, title, body, panel)
+                        }, delayMs)
+                        continue
+                    }
+                }
 
                 Log.d(TAG, "Sending notification: channel=$channelId, id=$notificationId, title=$title")
-                NotificationHelper.showNotification(this, channelId, notificationId, title, body)
+                NotificationHelper.showNotification(this, channelId, notificationId, title, body, panel)
             }
         } catch (e: Exception) {
             Log.e(TAG, "Dormant check failed", e)
@@ -5,7 +5,9 @@ import android.os.Bundle
 import android.util.Log
 import android.webkit.WebView
 import se.malmo.skaggbyran.amp.BuildConfig
+import se.malmo.skaggbyran.amp.DormantBridge
 import se.malmo.skaggbyran.amp.FilePickerHelper
+import se.malmo.skaggbyran.amp.NotificationHelper
 // REMOVED: import se.malmo.skaggbyran.amp.WebViewConfigurator
 // Using reflection instead to avoid class verification failure
 
@@ -49,6 +51,40 @@ class MainActivity : WryActivity() {
 
     override fun onCreate(savedInstanceState: Bundle?) {
         super.onCreate(savedInstanceState)
+        handleOpenPanel(intent)
+    }
+
+    override fun onNewIntent(intent: Intent) {
+        super.onNewIntent(intent)
+        setIntent(intent)
+        handleOpenPanel(intent)
+    }
+
+    /**
+     * Forward the panel named by a tapped notification to Rust.
+     *
+     * The extra is removed afterwards so a recreated activity does not
+     * reopen the panel.
+     */
+    private fun handleOpenPanel(intent: Intent?) {
+        val panel = intent?.getStringExtra(NotificationHelper.EXTRA_OPEN_PANEL) ?: return
+        intent.removeExtra(NotificationHelper.EXTRA_OPEN_PANEL)
+        Log.i(TAG, "Opening panel from notification: $panel")
+        try {
+            DormantBridge.openPanel(panel)
+        } catch (e: UnsatisfiedLinkError) {
+            Log.e(TAG, "Native library not loaded, cannot open panel", e)
+        }
+    }
+
+    override fun onResume() {
+        super.onResume()
+        DormantBridge.setAppForeground(true)
+    }
+
+    override fun onPause() {
+        DormantBridge.setAppForeground(false)
+        super.onPause()
     }
 
     companion object {
@@ -4,7 +4,9 @@ import android.Manifest
 import android.app.Activity
 import android.app.NotificationChannel
 import android.app.NotificationManager
+import android.app.PendingIntent
 import android.content.Context
+import android.content.Intent
 import android.content.pm.PackageManager
 import android.os.Build
 import androidx.core.app.ActivityCompat
@@ -37,6 +39,9 @@ object NotificationHelper {
     private const val CHANNEL_SIX_HOURS = "amp_six_hours"
     private const val CHANNEL_ONE_DAY = "amp_one_day"
 
+    /** Intent extra naming the panel (e.g. "Within6Hours") to open on tap */
+    const val EXTRA_OPEN_PANEL = "se.malmo.skaggbyran.amp.OPEN_PANEL"
+
     /**
      * Request notification permission from user.
      *
@@ -163,18 +168,21 @@ object NotificationHelper {
      * @param notificationId Unique ID for this notification (use address ID)
      * @param title Notification title text
      * @param body Notification body/content text
+     * @param panel Bucket name whose panel opens when tapped, or null to just open the app
      *
      * @throws SecurityException if notification permission not granted (Android 13+)
      *
      * @JvmStatic annotation makes this callable from JNI
      */
     @JvmStatic
+    @JvmOverloads
     fun showNotification(
         context: Context,
         channelId: String,
         notificationId: Int,
         title: String,
-        body: String
+        body: String,
+        panel: String? = null
     ) {
         Log.d(TAG, "Showing notification: channel=$channelId, id=$notificationId, title='$title'")
 
@@ -200,6 +208,20 @@ object NotificationHelper {
                 .setAutoCancel(true) // Dismiss when tapped
                 .setCategory(NotificationCompat.CATEGORY_REMINDER)
 
+            // Open the app on tap, asking it to expand the notification's panel
+            context.packageManager.getLaunchIntentForPackage(context.packageName)?.let { launch ->
+                launch.addFlags(Intent.FLAG_ACTIVITY_SINGLE_TOP or Intent.FLAG_ACTIVITY_CLEAR_TOP)
+                panel?.let { launch.putExtra(EXTRA_OPEN_PANEL, it) }
+                builder.setContentIntent(
+                    PendingIntent.getActivity(
+                        context,
+                        notificationId,
+                        launch,
+                        PendingIntent.FLAG_UPDATE_CURRENT or PendingIntent.FLAG_IMMUTABLE
+                    )
+                )
+            }
+
             // For active notifications, show as heads-up
             if (channelId == CHANNEL_ACTIVE) {
                 builder.setVisibility(NotificationCompat.VISIBILITY_PUBLIC)
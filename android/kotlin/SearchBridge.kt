@@ -0,0 +1,69 @@
+package se.malmo.skaggbyran.amp
+
+/**
+ * JNI bridge to the Rust address search.
+ *
+ * Backs the search bar autocomplete with Rust's ranked fuzzy search over
+ * the embedded reference addresses.
+ */
+object SearchBridge {
+
+    init {
+        System.loadLibrary("dioxusmain")
+    }
+
+    /**
+     * Search addresses by partial or misspelled text.
+     *
+     * Calls Rust's search_addresses() which ranks exact and prefix matches
+     * first, then substring matches, then matches within a few typos.
+     *
+     * @param query Text typed by the user
+     * @param limit Maximum number of results
+     * @return JSON string: [{"adress":"...","postnummer":"..."}], best match first
+     */
+    external fun searchAddresses(query: String, limit: Int): String
+
+    /**
+     * List other addresses on the same street cleaning segment.
+     *
+     * Calls Rust's addresses_sharing_segment() for the matched entry of the
+     * given address.
+     *
+     * @param street Street name
+     * @param streetNumber Street number
+     * @param postalCode Postal code, may be empty
+     * @return JSON string: ["Storgatan 2","Storgatan 9B"], empty array if unmatched
+     */
+    external fun addressesSharingSegment(
+        street: String,
+        streetNumber: String,
+        postalCode: String
+    ): String
+
+    /**
+     * Match a whole list of addresses in one call.
+     *
+     * Calls Rust's match_addresses_json(), which tries an exact lookup for
+     * each address and falls back to fuzzy matching. Use this to re-validate
+     * saved addresses after a data update instead of one call per address.
+     *
+     * @param jsonArray JSON string: [{"street":"...","number":"...","postal":"..."}]
+     * @return JSON string: [{"valid":true,"match_reason":"exact","adress":"...",...}],
+     *         one entry per input address in the same order, empty array on bad input
+     */
+    external fun rustSearchAddresses(jsonArray: String): String
+
+    /**
+     * Find the saved-address candidate closest to a GPS position.
+     *
+     * Calls Rust's nearest_address() over the embedded reference addresses,
+     * for prefilling the Add Address form from the device location.
+     *
+     * @param lat Latitude in decimal degrees (WGS84)
+     * @param lon Longitude in decimal degrees (WGS84)
+     * @return JSON string: {"street":"...","street_number":"...","postal_code":"...","distance":12.3},
+     *         or "null" if no address is close enough
+     */
+    external fun rustNearestAddress(lat: Double, lon: Double): String
+}
@@ -57,6 +57,14 @@
 }
 -keepnames class se.malmo.skaggbyran.amp.DormantBridge
 
+# Keep SearchBridge - JNI bridge to Rust address search
+-keep public class se.malmo.skaggbyran.amp.SearchBridge {
+    public <methods>;
+    public <fields>;
+    native <methods>;
+}
+-keepnames class se.malmo.skaggbyran.amp.SearchBridge
+
 # Keep custom MainActivity - extends WryActivity
 -keep public class dev.dioxus.main.MainActivity {
     public <methods>;
@@ -136,13 +136,14 @@ pub fn initialize_notification_channels_jni() {
 /// * `notification_id` - Unique ID for this notification (typically address ID)
 /// * `title` - Notification title text
 /// * `body` - Notification body/content text
+/// * `panel` - Bucket name (e.g. "Within6Hours") whose panel opens on tap
 ///
 /// # Platform Behavior
 /// - **Android**: Uses NotificationManagerCompat to display notification
 /// - **Other platforms**: Mock implementation logs parameters
 ///
 /// # JNI Integration
-/// Calls `se.malmo.skaggbyran.amp.NotificationHelper.showNotification(Context, String, int, String, String)`
+/// Calls `se.malmo.skaggbyran.amp.NotificationHelper.showNotification(Context, String, int, String, String, String)`
 ///
 /// # Examples
 /// ```no_run
@@ -152,13 +153,20 @@ pub fn initialize_notification_channels_jni() {
 ///     "amp_active",
 ///     1,
 ///     "Street cleaning NOW!",
-///     "Your car is in an active zone"
+///     "Your car is in an active zone",
+///     Some("Now"),
 /// );
 /// ```
-pub fn send_notification_jni(channel_id: &str, notification_id: i32, title: &str, body: &str) {
+pub fn send_notification_jni(
+    channel_id: &str,
+    notification_id: i32,
+    title: &str,
+    body: &str,
+    panel: Option<&str>,
+) {
     #[cfg(target_os = "android")]
     {
-        match show_notification(channel_id, notification_id, title, body) {
+        match show_notification(channel_id, notification_id, title, body, panel) {
             Ok(()) => {
                 eprintln!(
                     "[Android Bridge] Notification sent: channel={}, id={}, title='{}'",
@@ -176,8 +184,8 @@ pub fn send_notification_jni(channel_id: &str, notification_id: i32, title: &str
     #[cfg(not(target_os = "android"))]
     {
         eprintln!(
-            "[Mock Android Bridge] Would send notification: channel='{}', id={}, title='{}', body='{}'",
-            channel_id, notification_id, title, body,
+            "[Mock Android Bridge] Would send notification: channel='{}', id={}, title='{}', body='{}', panel={:?}",
+            channel_id, notification_id, title, body, panel,
         );
     }
 }
@@ -294,6 +302,7 @@ fn create_notification_channels() -> Result<(), String> {
 /// * `notification_id` - Unique notification ID
 /// * `title` - Notification title
 /// * `body` - Notification body text
+/// * `panel` - Panel to open on tap, passed as `null` when absent
 ///
 /// # Returns
 /// Result indicating success or error message
@@ -301,14 +310,15 @@ fn create_notification_channels() -> Result<(), String> {
 /// # JNI Call Structure
 /// - Class: `se/malmo/skaggbyran/amp/NotificationHelper`
 /// - Method: `showNotification`
-/// - Signature: `(Landroid/content/Context;Ljava/lang/String;ILjava/lang/String;Ljava/lang/String;)V`
-/// - Parameters: Context, channelId, notificationId, title, body
+/// - Signature: `(Landroid/content/Context;Ljava/lang/String;ILjava/lang/String;Ljava/lang/String;Ljava/lang/String;)V`
+/// - Parameters: Context, channelId, notificationId, title, body, panel
 #[cfg(target_os = "android")]
 fn show_notification(
     channel_id: &str,
     notification_id: i32,
     title: &str,
     body: &str,
+    panel: Option<&str>,
 ) -> Result<(), String> {
     let mut env = get_jni_env()?;
     let context = get_android_context()?;
@@ -321,6 +331,13 @@ fn show_notification(
     let j_body = env
         .new_string(body)
         .map_err(|e| format!("Failed to create Java string for body: {:?}", e))?;
+    let j_panel = match panel {
+        Some(panel) => JObject::from(
+            env.new_string(panel)
+                .map_err(|e| format!("Failed to create Java string for panel: {:?}", e))?,
+        ),
+        None => JObject::null(),
+    };
     let class_loader = env
         .call_method(&context, "getClassLoader", "()Ljava/lang/ClassLoader;", &[])
         .map_err(|e| format!("Failed to get ClassLoader: {:?}", e))?
@@ -343,13 +360,14 @@ fn show_notification(
     env.call_static_method(
         helper_class,
         "showNotification",
-        "(Landroid/content/Context;Ljava/lang/String;ILjava/lang/String;Ljava/lang/String;)V",
+        "(Landroid/content/Context;Ljava/lang/String;ILjava/lang/String;Ljava/lang/String;Ljava/lang/String;)V",
         &[
             JValue::Object(&context),
             JValue::Object(&j_channel_id),
             JValue::Int(notification_id as jint),
             JValue::Object(&j_title),
             JValue::Object(&j_body),
+            JValue::Object(&j_panel),
         ],
     )
     .map_err(|e| format!("Failed to call showNotification: {:?}", e))?;
@@ -524,6 +542,65 @@ fn get_android_location() -> Result<(f64, f64), String> {
         .map_err(|e| format!("Failed to parse longitude: {:?}", e))?;
     Ok((lat, lon))
 }
+/// Check whether location permission is granted, without prompting
+///
+/// Unlike [`read_device_gps_location`], this never shows the system
+/// permission dialog, so it is safe to call from background polling.
+///
+/// # Returns
+/// - `true` if fine or coarse location is granted
+/// - `false` if it is denied, the check fails, or on non-Android platforms
+#[allow(dead_code)]
+pub fn has_location_permission() -> bool {
+    #[cfg(target_os = "android")]
+    {
+        match check_android_location_permission() {
+            Ok(granted) => granted,
+            Err(e) => {
+                eprintln!("[Android Bridge] Permission check error: {}", e);
+                false
+            }
+        }
+    }
+    #[cfg(not(target_os = "android"))]
+    {
+        false
+    }
+}
+/// Call `se.malmo.skaggbyran.amp.LocationHelper.hasPermission(Context)` via JNI.
+#[cfg(target_os = "android")]
+fn check_android_location_permission() -> Result<bool, String> {
+    let mut env = get_jni_env()?;
+    let context = get_android_context()?;
+    let class_loader = env
+        .call_method(&context, "getClassLoader", "()Ljava/lang/ClassLoader;", &[])
+        .map_err(|e| format!("Failed to get ClassLoader: {:?}", e))?
+        .l()
+        .map_err(|e| format!("ClassLoader not an object: {:?}", e))?;
+    let j_class_name = env
+        .new_string("se.malmo.skaggbyran.amp.LocationHelper")
+        .map_err(|e| format!("Failed to create LocationHelper class name string: {:?}", e))?;
+    let class_obj = env
+        .call_method(
+            class_loader,
+            "loadClass",
+            "(Ljava/lang/String;)Ljava/lang/Class;",
+            &[JValue::Object(&j_class_name.into())],
+        )
+        .map_err(|e| format!("Failed to load LocationHelper: {:?}", e))?
+        .l()
+        .map_err(|e| format!("Loaded LocationHelper class not an object: {:?}", e))?;
+    let helper_class = JClass::from(class_obj);
+    env.call_static_method(
+        helper_class,
+        "hasPermission",
+        "(Landroid/content/Context;)Z",
+        &[JValue::Object(&context)],
+    )
+    .map_err(|e| format!("Failed to call LocationHelper.hasPermission: {:?}", e))?
+    .z()
+    .map_err(|e| format!("hasPermission did not return a boolean: {:?}", e))
+}
 /// Get device model and manufacturer information
 ///
 /// # Returns
@@ -765,6 +842,10 @@ mod tests {
         assert_eq!(result, None);
     }
     #[test]
+    fn test_location_permission_non_android() {
+        assert!(!has_location_permission());
+    }
+    #[test]
     fn test_device_info() {
         let info = get_device_info();
         assert!(!info.is_empty());
@@ -775,6 +856,13 @@ mod tests {
     }
     #[test]
     fn test_send_notification_no_panic() {
-        send_notification_jni("amp_active", 1, "Test Title", "Test Body");
+        send_notification_jni("amp_active", 1, "Test Title", "Test Body", None);
+        send_notification_jni(
+            "amp_six_hours",
+            2,
+            "Test Title",
+            "Test Body",
+            Some("Within6Hours"),
+        );
     }
 }
@@ -1,45 +1,392 @@
 //! Address fuzzy matching utilities
 //!
 //! Provides helper functions for fuzzy string matching and normalization
-//! to improve address lookup accuracy.
+//! to improve address lookup accuracy, and a ranked [`search_addresses`]
+//! used for search-bar autocomplete (exposed to Kotlin as
+//! `SearchBridge.searchAddresses`).
 //!
 //! # Examples
 //! ```
 //! use amp_android::address_utils::normalize_string;
 //!
-//! let normalized = normalize_string("  STORGATAN  ");
+//! let normalized = normalize_string("  STORGATAN  ", false);
 //! assert_eq!(normalized, "storgatan");
 //! ```
+#[cfg(target_os = "android")]
+use crate::components::static_data::load_ref_data;
+use amp_core::structs::AdressClean;
+#[cfg(target_os = "android")]
+use jni::JNIEnv;
+#[cfg(target_os = "android")]
+use jni::objects::{JClass, JString};
+#[cfg(target_os = "android")]
+use jni::sys::{jint, jstring};
+#[cfg(target_os = "android")]
+use serde::Serialize;
 /// Normalize string for comparison
 ///
-/// Converts to lowercase and trims whitespace.
+/// Converts to lowercase and trims whitespace. With `transliterate` set,
+/// also folds å and ä to a and ö to o, matching what users type on
+/// keyboards without Swedish keys.
 ///
 /// # Arguments
 /// * `s` - String to normalize
+/// * `transliterate` - Fold Swedish characters to ASCII; `false` keeps exact mode
 ///
 /// # Returns
-/// Normalized string (lowercase, trimmed)
+/// Normalized string (lowercase, trimmed, optionally transliterated)
 ///
 /// # Examples
 /// ```
 /// use amp_android::address_utils::normalize_string;
 ///
-/// assert_eq!(normalize_string("  STORGATAN  "), "storgatan");
-/// assert_eq!(normalize_string("Test Street"), "test street");
-/// assert_eq!(normalize_string("  "), "");
+/// assert_eq!(normalize_string("  STORGATAN  ", false), "storgatan");
+/// assert_eq!(normalize_string("Test Street", false), "test street");
+/// assert_eq!(normalize_string("  ", false), "");
+/// assert_eq!(normalize_string("Östra Förstadsgatan", false), "östra förstadsgatan");
+/// assert_eq!(normalize_string("Östra Förstadsgatan", true), "ostra forstadsgatan");
 /// ```
-pub fn normalize_string(s: &str) -> String {
-    s.trim().to_lowercase()
+pub fn normalize_string(s: &str, transliterate: bool) -> String {
+    let lower = s.trim().to_lowercase();
+    if !transliterate {
+        return lower;
+    }
+    lower
+        .chars()
+        .map(|c| match c {
+            'å' | 'ä' => 'a',
+            'ö' => 'o',
+            other => other,
+        })
+        .collect()
+}
+/// Compare a queried street name with a database one
+///
+/// Names match if they are equal or one contains the other after
+/// normalization. Exact forms are tried first, then transliterated ones,
+/// so "ostra kristinelundsvagen" still finds "Östra Kristinelundsvägen".
+/// An empty name never matches, it would be a substring of every street.
+///
+/// # Returns
+/// How the names matched, for logging, or `None`
+///
+/// # Examples
+/// ```
+/// use amp_android::address_utils::match_street_name;
+///
+/// assert_eq!(match_street_name("STORGATAN", "Storgatan").as_deref(), Some("normalized"));
+/// assert!(match_street_name("ostra kristinelundsvagen", "Östra Kristinelundsvägen").is_some());
+/// assert!(match_street_name("Storgatan", "Lilla Torg").is_none());
+/// ```
+pub fn match_street_name(query: &str, entry: &str) -> Option<String> {
+    [false, true].into_iter().find_map(|transliterate| {
+        let query = normalize_string(query, transliterate);
+        let entry = normalize_string(entry, transliterate);
+        if query.is_empty() || entry.is_empty() {
+            return None;
+        }
+        let reason = if query == entry {
+            "normalized".to_string()
+        } else if entry.contains(&query) || query.contains(&entry) {
+            format!("substring dist={}", strsim::levenshtein(&query, &entry))
+        } else {
+            return None;
+        };
+        Some(if transliterate {
+            format!("{} transliterated", reason)
+        } else {
+            reason
+        })
+    })
+}
+/// How a queried street number relates to a database one
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NumberMatch {
+    /// Same number and letter suffix
+    Exact,
+    /// Same number, different or missing letter suffix, e.g. "10A" and "10"
+    SameNumber,
+}
+/// Split a street number into its numeric prefix and the rest
+///
+/// # Examples
+/// ```
+/// use amp_android::address_utils::split_street_number;
+///
+/// assert_eq!(split_street_number("18C"), ("18", "C"));
+/// assert_eq!(split_street_number("10"), ("10", ""));
+/// ```
+pub fn split_street_number(number: &str) -> (&str, &str) {
+    let number = number.trim();
+    let end = number
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(number.len());
+    (&number[..end], number[end..].trim())
+}
+/// Compare two street numbers, tolerating a letter suffix difference
+///
+/// Letters in a suffix usually name entrances of the same building, which
+/// sits on the same cleaning segment. Both numbers are normalized first.
+///
+/// # Returns
+/// - `Some(NumberMatch::Exact)` if the numbers are equal
+/// - `Some(NumberMatch::SameNumber)` if only the letter suffix differs
+/// - `None` otherwise, including numbers without a numeric prefix
+///
+/// # Examples
+/// ```
+/// use amp_android::address_utils::{NumberMatch, match_street_number};
+///
+/// assert_eq!(match_street_number("10A", "10"), Some(NumberMatch::SameNumber));
+/// assert_eq!(match_street_number("11", "10"), None);
+/// ```
+pub fn match_street_number(query: &str, entry: &str) -> Option<NumberMatch> {
+    let query = normalize_string(query, false);
+    let entry = normalize_string(entry, false);
+    if query == entry {
+        return Some(NumberMatch::Exact);
+    }
+    let (query_digits, _) = split_street_number(&query);
+    let (entry_digits, _) = split_street_number(&entry);
+    (!query_digits.is_empty() && query_digits == entry_digits).then_some(NumberMatch::SameNumber)
+}
+/// Upper bound on typos tolerated by [`search_addresses`]
+///
+/// Short queries get proportionally fewer, see [`max_search_distance`].
+const MAX_SEARCH_DISTANCE: usize = 3;
+/// Typo budget for a normalized query of `len` characters
+///
+/// One edit per four characters, at least one and at most
+/// [`MAX_SEARCH_DISTANCE`], so "kor" doesn't match every street.
+fn max_search_distance(len: usize) -> usize {
+    (len / 4).clamp(1, MAX_SEARCH_DISTANCE)
+}
+/// Rank of a single address for a normalized query, lower is better
+///
+/// Returns `None` if the address doesn't match at all. Tiers, best first:
+/// 0. exact address, 1. address prefix, 2. street prefix,
+/// 3. substring of the address, 4. within the typo budget of the address
+/// prefix or street name. The second element is the edit distance used to
+/// order matches within the fuzzy tier.
+fn search_rank(query: &str, address: &AdressClean) -> Option<(u8, usize)> {
+    let adress = normalize_string(&address.adress, false);
+    let gata = normalize_string(&address.gata, false);
+    if adress == query {
+        return Some((0, 0));
+    }
+    if adress.starts_with(query) {
+        return Some((1, 0));
+    }
+    if !gata.is_empty() && gata.starts_with(query) {
+        return Some((2, 0));
+    }
+    if adress.contains(query) {
+        return Some((3, 0));
+    }
+    let query_len = query.chars().count();
+    let prefix: String = adress.chars().take(query_len).collect();
+    let distance = strsim::levenshtein(query, &prefix).min(strsim::levenshtein(query, &gata));
+    (distance <= max_search_distance(query_len)).then_some((4, distance))
+}
+/// Ranked fuzzy search over addresses for autocomplete
+///
+/// Matches the query against both the full address (`adress`) and the street
+/// name (`gata`), case-insensitively. Exact and prefix matches rank above
+/// substring matches, which rank above typo-tolerant Levenshtein matches.
+/// Ties are broken alphabetically so results are stable while typing.
+///
+/// # Arguments
+/// * `query` - Text typed by the user, possibly partial or misspelled
+/// * `addresses` - Candidate addresses, e.g. the embedded reference data
+/// * `limit` - Maximum number of results
+///
+/// # Returns
+/// Up to `limit` best matches, best first. Empty for a blank query.
+///
+/// # Examples
+/// ```no_run
+/// use amp_android::components::address_utils::search_addresses;
+/// use amp_android::components::static_data::load_ref_data;
+///
+/// for hit in search_addresses("kornetsgatan 1", load_ref_data(), 5) {
+///     println!("{} ({:?})", hit.adress, hit.postnummer);
+/// }
+/// ```
+#[allow(dead_code)]
+pub fn search_addresses<'a>(
+    query: &str,
+    addresses: &'a [AdressClean],
+    limit: usize,
+) -> Vec<&'a AdressClean> {
+    let query = normalize_string(query, false);
+    if query.is_empty() || limit == 0 {
+        return Vec::new();
+    }
+    let mut ranked: Vec<((u8, usize), &AdressClean)> = addresses
+        .iter()
+        .filter_map(|address| search_rank(&query, address).map(|rank| (rank, address)))
+        .collect();
+    ranked.sort_by(|(rank_a, a), (rank_b, b)| {
+        rank_a.cmp(rank_b).then_with(|| a.adress.cmp(&b.adress))
+    });
+    ranked
+        .into_iter()
+        .take(limit)
+        .map(|(_, address)| address)
+        .collect()
+}
+/// One autocomplete result as sent over JNI
+#[cfg(target_os = "android")]
+#[derive(Serialize)]
+struct SearchHit<'a> {
+    adress: &'a str,
+    postnummer: Option<&'a str>,
+}
+/// JNI: Called by SearchBridge.searchAddresses(query, limit)
+///
+/// Searches the embedded reference addresses and returns a JSON array:
+/// `[{"adress":"Kornettsgatan 18C","postnummer":"21438"}]`
+#[cfg(target_os = "android")]
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_se_malmo_skaggbyran_amp_SearchBridge_searchAddresses<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    query: JString<'local>,
+    limit: jint,
+) -> jstring {
+    let query: String = match env.get_string(&query) {
+        Ok(s) => s.into(),
+        Err(e) => {
+            eprintln!("[Search JNI] Failed to get query string: {:?}", e);
+            String::new()
+        }
+    };
+    let hits: Vec<SearchHit> = search_addresses(&query, load_ref_data(), limit.max(0) as usize)
+        .into_iter()
+        .map(|address| SearchHit {
+            adress: &address.adress,
+            postnummer: address.postnummer.as_deref(),
+        })
+        .collect();
+    let json = serde_json::to_string(&hits).unwrap_or_else(|e| {
+        eprintln!("[Search JNI] Failed to serialize results: {:?}", e);
+        "[]".to_string()
+    });
+    env.new_string(&json)
+        .expect("Failed to create JSON output string")
+        .into_raw()
 }
 #[cfg(test)]
 mod tests {
     use super::*;
     #[test]
     fn test_normalize_string() {
-        assert_eq!(normalize_string("Storgatan"), "storgatan");
-        assert_eq!(normalize_string("  STORGATAN  "), "storgatan");
-        assert_eq!(normalize_string("Test Street"), "test street");
-        assert_eq!(normalize_string(""), "");
-        assert_eq!(normalize_string("  "), "");
+        assert_eq!(normalize_string("Storgatan", false), "storgatan");
+        assert_eq!(normalize_string("  STORGATAN  ", false), "storgatan");
+        assert_eq!(normalize_string("Test Street", false), "test street");
+        assert_eq!(normalize_string("", false), "");
+        assert_eq!(normalize_string("  ", false), "");
+    }
+    #[test]
+    fn test_normalize_string_transliterate() {
+        assert_eq!(
+            normalize_string("Östra Kristinelundsvägen", true),
+            "ostra kristinelundsvagen"
+        );
+        assert_eq!(normalize_string("ÅSTORP", true), "astorp");
+        assert_eq!(
+            normalize_string("Östra Kristinelundsvägen", false),
+            "östra kristinelundsvägen"
+        );
+    }
+    #[test]
+    fn test_match_street_name_transliterated() {
+        assert_eq!(
+            match_street_name("ostra kristinelundsvagen", "Östra Kristinelundsvägen").as_deref(),
+            Some("normalized transliterated")
+        );
+        assert_eq!(
+            match_street_name("Östra Kristinelundsvägen", "Östra Kristinelundsvägen").as_deref(),
+            Some("normalized")
+        );
+        assert!(
+            match_street_name("kristinelundsvagen", "Östra Kristinelundsvägen")
+                .is_some_and(|reason| reason.starts_with("substring"))
+        );
+        assert!(match_street_name("ostra kristinelundsvagen", "Västra Rönneholmsvägen").is_none());
+    }
+    #[test]
+    fn test_match_street_name_rejects_empty() {
+        assert!(match_street_name("", "Värnhemsgatan").is_none());
+        assert!(match_street_name("  ", "Värnhemsgatan").is_none());
+        assert!(match_street_name("Värnhemsgatan", "").is_none());
+    }
+    #[test]
+    fn test_match_street_number_suffix() {
+        assert_eq!(
+            match_street_number("10A", "10"),
+            Some(NumberMatch::SameNumber)
+        );
+        assert_eq!(
+            match_street_number("10", "10A"),
+            Some(NumberMatch::SameNumber)
+        );
+        assert_eq!(
+            match_street_number("10 a", "10A"),
+            Some(NumberMatch::SameNumber)
+        );
+        assert_eq!(match_street_number("18c", "18C"), Some(NumberMatch::Exact));
+        assert_eq!(match_street_number("11", "10"), None);
+        assert_eq!(match_street_number("101", "10"), None);
+        assert_eq!(match_street_number("A", "B"), None);
+    }
+    fn addr(adress: &str, gata: &str) -> AdressClean {
+        AdressClean {
+            adress: adress.to_string(),
+            gata: gata.to_string(),
+            ..AdressClean::default()
+        }
+    }
+    fn sample() -> Vec<AdressClean> {
+        vec![
+            addr("Kornettsgatan 18C", "Kornettsgatan"),
+            addr("Kornettsgatan 2", "Kornettsgatan"),
+            addr("Claesgatan 2B", "Claesgatan"),
+            addr("Celsiusgatan 13A", "Celsiusgatan"),
+            addr("Docentgatan 1B", "Docentgatan"),
+            addr("Storgatan 10", "Storgatan"),
+        ]
+    }
+    fn names(hits: &[&AdressClean]) -> Vec<String> {
+        hits.iter().map(|a| a.adress.clone()).collect()
+    }
+    #[test]
+    fn test_search_partial_query() {
+        let data = sample();
+        let hits = search_addresses("korn", &data, 10);
+        assert_eq!(names(&hits), vec!["Kornettsgatan 18C", "Kornettsgatan 2"],);
+        let hits = search_addresses("gatan 2", &data, 10);
+        assert_eq!(names(&hits), vec!["Claesgatan 2B", "Kornettsgatan 2"]);
+    }
+    #[test]
+    fn test_search_misspelled_query() {
+        let data = sample();
+        let hits = search_addresses("Kornetsgatan 18", &data, 10);
+        assert_eq!(
+            hits.first().map(|a| a.adress.as_str()),
+            Some("Kornettsgatan 18C")
+        );
+        let hits = search_addresses("celsiusgtan", &data, 10);
+        assert_eq!(names(&hits), vec!["Celsiusgatan 13A"]);
+        assert!(search_addresses("xyzxyzxyz", &data, 10).is_empty());
+    }
+    #[test]
+    fn test_search_ranks_exact_first_and_respects_limit() {
+        let data = sample();
+        let hits = search_addresses("kornettsgatan 2", &data, 10);
+        assert_eq!(hits[0].adress, "Kornettsgatan 2");
+        assert_eq!(search_addresses("gatan", &data, 2).len(), 2);
+        assert!(search_addresses("  ", &data, 10).is_empty());
+        assert!(search_addresses("korn", &data, 0).is_empty());
     }
 }
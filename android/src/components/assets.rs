@@ -0,0 +1,84 @@
+//! Checksum verification for embedded data assets
+//!
+//! The parquet files under `assets/data/` are compiled into the binary with
+//! `include_bytes!`. Whatever writes them (the server's `output` and
+//! `create-adresser-parquet` commands, the debug script) also records their
+//! SHA-256 checksums in the committed `assets/data/SHA256SUMS`, and
+//! [`verify_manifest`] compares the bytes actually embedded against that file
+//! using [`amp_core::checksum::verify_embedded`]. A mismatch means an asset was
+//! swapped or corrupted (e.g. a Git LFS pointer instead of the file) outside
+//! the generators, so the app and its data have drifted apart.
+//!
+//! # Examples
+//! ```no_run
+//! use amp_android::components::assets::verify_manifest;
+//!
+//! if let Err(e) = verify_manifest() {
+//!     eprintln!("Embedded data is inconsistent: {}", e);
+//! }
+//! ```
+use crate::components::debug::DEBUG_PARQUET;
+use crate::components::static_data::{PARQUET_BYTES, PARQUET_REF_BYTES};
+use amp_core::checksum::{parse_asset_checksums, verify_embedded};
+use std::sync::OnceLock;
+/// Checksums recorded by the asset generators, in `sha256sum` format
+const ASSET_CHECKSUMS: &str = include_str!("../../assets/data/SHA256SUMS");
+/// Cached verification result, computed once on first call
+static VERIFIED: OnceLock<Result<(), String>> = OnceLock::new();
+/// Embedded asset bytes keyed by the file name used in `SHA256SUMS`
+fn embedded_assets() -> [(&'static str, &'static [u8]); 3] {
+    [
+        ("db.parquet", PARQUET_BYTES),
+        ("adresser.parquet", PARQUET_REF_BYTES),
+        ("debug.parquet", DEBUG_PARQUET),
+    ]
+}
+/// Verify embedded assets against the committed `SHA256SUMS`
+///
+/// Hashes each embedded parquet file and compares it to the checksum
+/// recorded when it was generated. The result is cached, so repeated calls
+/// are cheap.
+///
+/// # Returns
+/// - `Ok(())` if every embedded asset matches `SHA256SUMS`
+/// - `Err(message)` naming the first asset that is missing or mismatched
+pub fn verify_manifest() -> Result<(), String> {
+    VERIFIED
+        .get_or_init(|| {
+            let checksums = parse_asset_checksums(ASSET_CHECKSUMS);
+            for (name, bytes) in embedded_assets() {
+                let expected = checksums
+                    .iter()
+                    .find(|(asset, _)| *asset == name)
+                    .map(|(_, checksum)| *checksum)
+                    .ok_or_else(|| format!("[Assets] {} missing from SHA256SUMS", name))?;
+                verify_embedded(bytes, expected)
+                    .map_err(|e| format!("[Assets] {}: {}", name, e))?;
+            }
+            eprintln!(
+                "[Assets] Verified {} embedded asset(s) against SHA256SUMS",
+                checksums.len(),
+            );
+            Ok(())
+        })
+        .clone()
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn test_manifest_covers_embedded_assets() {
+        let checksums = parse_asset_checksums(ASSET_CHECKSUMS);
+        for (name, _) in embedded_assets() {
+            assert!(
+                checksums.iter().any(|(asset, _)| *asset == name),
+                "{} should be in SHA256SUMS",
+                name,
+            );
+        }
+    }
+    #[test]
+    fn test_verify_manifest() {
+        assert_eq!(verify_manifest(), Ok(()));
+    }
+}
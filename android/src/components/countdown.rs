@@ -8,9 +8,15 @@
 //! The old `parse_time_interval` function has been removed as time parsing
 //! is now handled by `DB::from_dag_tid()`.
 //!
+//! [`TimeBucket`] and the bucketing and countdown rules themselves live in
+//! [`amp_core::buckets`], shared with the server; this module re-exports them
+//! and adds wrappers that read the clock.
+//!
 //! # Examples
 //! ```no_run
 //! use amp_android::countdown::{remaining_duration, format_countdown, bucket_for};
+//! use amp_android::Language;
+//! use amp_core::buckets::BucketSettings;
 //! use amp_core::structs::DB;
 //! use chrono::Utc;
 //!
@@ -36,16 +42,22 @@
 //! }
 //!
 //! // Format as string
-//! if let Some(countdown) = format_countdown(&db) {
+//! let settings = BucketSettings::default();
+//! if let Some(countdown) = format_countdown(&db, &settings, &Language::Svenska) {
 //!     println!("Countdown: {}", countdown);
 //! }
 //!
 //! // Categorize by urgency
-//! let bucket = bucket_for(&db);
+//! let bucket = bucket_for(&db, &settings);
 //! println!("Urgency: {:?}", bucket);
 //! ```
+use crate::components::settings::Language;
+use crate::components::translations::t;
+use amp_core::buckets;
+use amp_core::buckets::BucketSettings;
+pub use amp_core::buckets::{TimeBucket, time_until_next_occurrence_at, time_until_next_start_at};
 use amp_core::structs::DB;
-use chrono::{Datelike, Duration, Utc};
+use chrono::{Duration, Utc};
 /// Calculate remaining duration until parking restriction ends
 ///
 /// Uses the DB struct's `time_until_end` method to calculate the duration
@@ -76,8 +88,7 @@ use chrono::{Datelike, Duration, Utc};
 /// ```
 #[allow(unused)]
 pub fn remaining_duration(restriction: &DB) -> Option<Duration> {
-    let now = Utc::now();
-    restriction.time_until_end(now)
+    buckets::remaining_duration(restriction, Utc::now())
 }
 /// Calculate duration until next occurrence of this restriction
 ///
@@ -110,33 +121,7 @@ pub fn remaining_duration(restriction: &DB) -> Option<Duration> {
 /// }
 /// ```
 pub fn time_until_next_occurrence(restriction: &DB) -> Option<Duration> {
-    let now = Utc::now();
-    if let Some(duration) = restriction.time_until_end(now) {
-        return Some(duration);
-    }
-    let current_date = now.date_naive();
-    let restriction_day = restriction.start_time_swedish().day();
-    let mut next_month = current_date.month() + 1;
-    let mut next_year = current_date.year();
-    if next_month > 12 {
-        next_month = 1;
-        next_year += 1;
-    }
-    use chrono::NaiveDate;
-    let next_date = NaiveDate::from_ymd_opt(next_year, next_month, restriction_day)?;
-    let start_time = restriction.start_time_swedish().time();
-    let next_datetime = next_date.and_time(start_time);
-    use amp_core::structs::SWEDISH_TZ;
-    use chrono::TimeZone;
-    let next_start = SWEDISH_TZ
-        .from_local_datetime(&next_datetime)
-        .single()?
-        .with_timezone(&Utc);
-    if now < next_start {
-        Some(next_start - now)
-    } else {
-        None
-    }
+    time_until_next_occurrence_at(restriction, Utc::now())
 }
 /// Calculate duration until the next start of this restriction.
 ///
@@ -144,33 +129,7 @@ pub fn time_until_next_occurrence(restriction: &DB) -> Option<Duration> {
 /// If the start time has already passed this month (restriction is active or ended),
 /// calculates the next month's start time.
 pub fn time_until_next_start(restriction: &DB) -> Option<Duration> {
-    let now = Utc::now();
-    if let Some(duration) = restriction.time_until_start(now) {
-        return Some(duration);
-    }
-    let current_date = now.date_naive();
-    let restriction_day = restriction.start_time_swedish().day();
-    let mut next_month = current_date.month() + 1;
-    let mut next_year = current_date.year();
-    if next_month > 12 {
-        next_month = 1;
-        next_year += 1;
-    }
-    use chrono::NaiveDate;
-    let next_date = NaiveDate::from_ymd_opt(next_year, next_month, restriction_day)?;
-    let start_time = restriction.start_time_swedish().time();
-    let next_datetime = next_date.and_time(start_time);
-    use amp_core::structs::SWEDISH_TZ;
-    use chrono::TimeZone;
-    let next_start = SWEDISH_TZ
-        .from_local_datetime(&next_datetime)
-        .single()?
-        .with_timezone(&Utc);
-    if now < next_start {
-        Some(next_start - now)
-    } else {
-        None
-    }
+    time_until_next_start_at(restriction, Utc::now())
 }
 /// Format countdown as human-readable string with adaptive granularity
 ///
@@ -178,10 +137,16 @@ pub fn time_until_next_start(restriction: &DB) -> Option<Duration> {
 /// the format based on the time bucket:
 /// - Active (< 4h): Show hours, minutes, and seconds for urgency
 /// - Within 6 hours: Show hours and minutes only
-/// - Longer durations: Show days, hours, and minutes
+/// - Longer durations: Show days, hours, and minutes, see
+///   [`format_remaining`]
+///
+/// Both layouts come from the `duration.*` translations for `lang`.
 ///
 /// # Arguments
 /// * `restriction` - DB entry with parking restriction timestamps
+/// * `settings` - Bucket bounds from the user's lead time, see
+///   [`NotificationSettings::bucket_settings`]
+/// * `lang` - Display language
 ///
 /// # Returns
 /// Formatted string like "3h 25m 10s", "12h 30m", or "2d 06h 30m"
@@ -190,6 +155,8 @@ pub fn time_until_next_start(restriction: &DB) -> Option<Duration> {
 /// # Examples
 /// ```no_run
 /// use amp_android::countdown::format_countdown;
+/// use amp_android::Language;
+/// use amp_core::buckets::BucketSettings;
 /// use amp_core::structs::DB;
 ///
 /// # let db = DB::from_dag_tid(
@@ -200,12 +167,18 @@ pub fn time_until_next_start(restriction: &DB) -> Option<Duration> {
 /// #     None, None, None,
 /// #     2024, 1,
 /// # ).unwrap();
-/// if let Some(countdown) = format_countdown(&db) {
+/// if let Some(countdown) = format_countdown(&db, &BucketSettings::default(), &Language::English) {
 ///     println!("Time remaining: {}", countdown);
 /// }
 /// ```
-pub fn format_countdown(restriction: &DB) -> Option<String> {
-    let bucket = bucket_for(restriction);
+///
+/// [`NotificationSettings::bucket_settings`]: crate::components::settings::NotificationSettings::bucket_settings
+pub fn format_countdown(
+    restriction: &DB,
+    settings: &BucketSettings,
+    lang: &Language,
+) -> Option<String> {
+    let bucket = bucket_for(restriction, settings);
     let remaining = match bucket {
         TimeBucket::Now => time_until_next_occurrence(restriction)?,
         TimeBucket::Invalid => return None,
@@ -216,34 +189,63 @@ pub fn format_countdown(restriction: &DB) -> Option<String> {
             let hours = remaining.num_hours();
             let minutes = remaining.num_minutes() % 60;
             let seconds = remaining.num_seconds() % 60;
-            Some(format!("{}h {:02}m {:02}s", hours, minutes, seconds))
-        }
-        _ => {
-            let days = remaining.num_days();
-            let hours = remaining.num_hours() % 24;
-            let minutes = remaining.num_minutes() % 60;
-            Some(format!("{}d {:02}h {:02}m", days, hours, minutes))
+            Some(fill_duration(
+                t("duration.hours_minutes_seconds", lang),
+                [0, hours, minutes, seconds],
+            ))
         }
+        _ => Some(format_remaining(remaining, lang)),
     }
 }
-/// Time bucket categories for grouping parking restrictions
+/// Format a remaining duration in days, hours and minutes for `lang`
+///
+/// The layout is the `duration.*` translation for the largest non-zero
+/// unit: Swedish spells the units out and leaves off leading zero units
+/// ("2 dagar 3 tim 15 min", "40 min"), English uses the compact
+/// "2d 03h 15m". Seconds are dropped, so anything under a minute shows as
+/// "< 1 min"/"< 1m", and a duration that has run out as "Nu"/"Now".
 ///
-/// Categorizes restrictions by urgency based on time remaining.
-/// Used for sorting and filtering addresses by deadline proximity.
-#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
-pub enum TimeBucket {
-    /// Restriction ends within 4 hours (highest urgency)
-    Now,
-    /// Restriction ends within 6 hours
-    Within6Hours,
-    /// Restriction ends within 1 day
-    Within1Day,
-    /// Restriction ends within 1 month (30 days)
-    Within1Month,
-    /// Restriction ends more than 1 month away (>30 days)
-    MoreThan1Month,
-    /// Invalid, expired, or far-future restriction
-    Invalid,
+/// # Examples
+/// ```
+/// use amp_android::components::countdown::format_remaining;
+/// use amp_android::Language;
+/// use chrono::Duration;
+///
+/// let d = Duration::days(2) + Duration::hours(3) + Duration::minutes(15);
+/// assert_eq!(format_remaining(d, &Language::English), "2d 03h 15m");
+/// assert_eq!(format_remaining(d, &Language::Svenska), "2 dagar 3 tim 15 min");
+/// assert_eq!(format_remaining(Duration::zero(), &Language::Svenska), "Nu");
+/// ```
+pub fn format_remaining(d: Duration, lang: &Language) -> String {
+    if d <= Duration::zero() {
+        return t("duration.now", lang).to_string();
+    }
+    if d < Duration::minutes(1) {
+        return t("duration.under_minute", lang).to_string();
+    }
+    let days = d.num_days();
+    let hours = d.num_hours() % 24;
+    let minutes = d.num_minutes() % 60;
+    let key = match (days, hours) {
+        (1, _) => "duration.day_hours_minutes",
+        (2.., _) => "duration.days_hours_minutes",
+        (_, 1..) => "duration.hours_minutes",
+        _ => "duration.minutes",
+    };
+    fill_duration(t(key, lang), [days, hours, minutes, 0])
+}
+/// Fill a `duration.*` translation with `[days, hours, minutes, seconds]`
+///
+/// Placeholders are `{d}`, `{h}`, `{m}` and `{s}`; `{h:02}` etc. pad the
+/// value to two digits.
+fn fill_duration(template: &str, values: [i64; 4]) -> String {
+    let mut filled = template.to_string();
+    for (unit, value) in ["d", "h", "m", "s"].into_iter().zip(values) {
+        filled = filled
+            .replace(&format!("{{{}:02}}", unit), &format!("{:02}", value))
+            .replace(&format!("{{{}}}", unit), &value.to_string());
+    }
+    filled
 }
 /// Categorize restriction by time remaining until deadline
 ///
@@ -253,6 +255,9 @@ pub enum TimeBucket {
 ///
 /// # Arguments
 /// * `restriction` - DB entry containing restriction timestamps
+/// * `settings` - Bucket bounds, normally
+///   [`NotificationSettings::bucket_settings`] so the user's lead time moves
+///   the early warning bound
 ///
 /// # Returns
 /// TimeBucket representing urgency of the restriction
@@ -260,6 +265,7 @@ pub enum TimeBucket {
 /// # Examples
 /// ```no_run
 /// use amp_android::countdown::{bucket_for, TimeBucket};
+/// use amp_core::buckets::BucketSettings;
 /// use amp_core::structs::DB;
 ///
 /// # let db = DB::from_dag_tid(
@@ -270,27 +276,13 @@ pub enum TimeBucket {
 /// #     None, None, None,
 /// #     2024, 1,
 /// # ).unwrap();
-/// let bucket = bucket_for(&db);
+/// let bucket = bucket_for(&db, &BucketSettings::default());
 /// println!("Urgency: {:?}", bucket);
 /// ```
-pub fn bucket_for(restriction: &DB) -> TimeBucket {
-    let now = Utc::now();
-    if restriction.is_active(now) {
-        return TimeBucket::Now;
-    }
-    let remaining = match time_until_next_start(restriction) {
-        Some(d) => d,
-        None => return TimeBucket::Invalid,
-    };
-    if remaining <= Duration::hours(6) {
-        TimeBucket::Within6Hours
-    } else if remaining <= Duration::days(1) {
-        TimeBucket::Within1Day
-    } else if remaining <= Duration::days(30) {
-        TimeBucket::Within1Month
-    } else {
-        TimeBucket::MoreThan1Month
-    }
+///
+/// [`NotificationSettings::bucket_settings`]: crate::components::settings::NotificationSettings::bucket_settings
+pub fn bucket_for(restriction: &DB, settings: &BucketSettings) -> TimeBucket {
+    buckets::bucket_for(restriction, Utc::now(), settings)
 }
 #[cfg(test)]
 mod tests {
@@ -323,16 +315,66 @@ mod tests {
     #[test]
     fn test_format_countdown() {
         let db = create_test_db(15, "0800-1200");
-        let result = format_countdown(&db);
+        let result = format_countdown(&db, &BucketSettings::default(), &Language::English);
         if let Some(s) = result {
             assert!(s.contains("h"));
             assert!(s.contains("m"));
         }
     }
     #[test]
+    fn test_format_remaining_per_language() {
+        let d = Duration::days(2) + Duration::hours(3) + Duration::minutes(15);
+        assert_eq!(
+            format_remaining(d, &Language::Svenska),
+            "2 dagar 3 tim 15 min"
+        );
+        assert_eq!(format_remaining(d, &Language::English), "2d 03h 15m");
+        assert_eq!(
+            format_remaining(d, &Language::Espanol),
+            format_remaining(d, &Language::Svenska)
+        );
+        let d = Duration::days(1) + Duration::minutes(5);
+        assert_eq!(format_remaining(d, &Language::Svenska), "1 dag 0 tim 5 min");
+        assert_eq!(format_remaining(d, &Language::English), "1d 00h 05m");
+        let d = Duration::minutes(40);
+        assert_eq!(format_remaining(d, &Language::Svenska), "40 min");
+        assert_eq!(format_remaining(d, &Language::English), "0d 00h 40m");
+    }
+    #[test]
+    fn test_format_remaining_now() {
+        for d in [Duration::zero(), Duration::seconds(-90)] {
+            assert_eq!(format_remaining(d, &Language::Svenska), "Nu");
+            assert_eq!(format_remaining(d, &Language::English), "Now");
+        }
+    }
+    #[test]
+    fn test_hours_minutes_seconds_per_language() {
+        let values = [0, 3, 5, 9];
+        assert_eq!(
+            fill_duration(
+                t("duration.hours_minutes_seconds", &Language::English),
+                values
+            ),
+            "3h 05m 09s"
+        );
+        assert_eq!(
+            fill_duration(
+                t("duration.hours_minutes_seconds", &Language::Svenska),
+                values
+            ),
+            "3 tim 05 min 09 s"
+        );
+    }
+    #[test]
+    fn test_format_remaining_sub_minute() {
+        let d = Duration::seconds(42);
+        assert_eq!(format_remaining(d, &Language::Svenska), "< 1 min");
+        assert_eq!(format_remaining(d, &Language::English), "< 1m");
+    }
+    #[test]
     fn test_bucket_for() {
         let db = create_test_db(15, "0800-1200");
-        let bucket = bucket_for(&db);
+        let bucket = bucket_for(&db, &BucketSettings::default());
         assert!(matches!(
             bucket,
             TimeBucket::Now
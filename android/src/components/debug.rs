@@ -7,7 +7,7 @@
 //! - Loads debug addresses from embedded debug.parquet asset (minimal format with address + postal code)
 //! - Read-only: never writes to storage
 //! - Can be toggled on/off via settings
-//! - Uses fuzzy matching via StoredAddress::new() to match against static parking database
+//! - Resolves all addresses in one pass with `match_many`, falling back to fuzzy matching per address
 //! - Mimics clicking "Add Address" button multiple times with different addresses
 //!
 //! # Usage
@@ -23,18 +23,24 @@
 //!     addr.active = !addr.active;
 //! }
 //! ```
+use crate::components::assets::verify_manifest;
+use crate::components::matching::{get_parking_data, match_many};
 use crate::ui::StoredAddress;
 use amp_core::parquet::load_debug_addresses as load_from_parquet;
 /// Debug parquet file embedded in the app
 /// Contains 'adress' and 'postnummer' fields - all other fields are NULL
 /// This mimics user input via "Add Address" button
-static DEBUG_PARQUET: &[u8] = include_bytes!("../../assets/data/debug.parquet");
+pub(crate) static DEBUG_PARQUET: &[u8] = include_bytes!("../../assets/data/debug.parquet");
 /// Load debug addresses from embedded debug.parquet file
 ///
-/// Reads the minimal debug.parquet file that contains address strings and postal codes.
-/// Each address is then processed using StoredAddress::new() which performs
-/// fuzzy matching against the static parking database (just like clicking
-/// "Add Address" in the UI).
+/// Reads the minimal debug.parquet file that contains address strings and postal codes,
+/// after checking the embedded assets against `SHA256SUMS` with
+/// [`verify_manifest`] so a corrupted build yields no debug addresses
+/// instead of garbage.
+/// All addresses are matched against the static parking database in one
+/// pass with [`match_many`]; each then goes through
+/// [`StoredAddress::from_match`], which falls back to fuzzy matching just
+/// like clicking "Add Address" in the UI.
 ///
 /// # Returns
 /// Vector of debug addresses with fuzzy matching applied, empty if loading fails
@@ -52,13 +58,17 @@ static DEBUG_PARQUET: &[u8] = include_bytes!("../../assets/data/debug.parquet");
 /// ```
 pub fn load_debug_addresses() -> Vec<StoredAddress> {
     eprintln!("[Debug] Loading debug addresses from embedded parquet");
+    if let Err(e) = verify_manifest() {
+        eprintln!("[Debug] {}, skipping debug addresses", e);
+        return Vec::new();
+    }
     match load_from_parquet(DEBUG_PARQUET) {
         Ok(debug_addresses) => {
             eprintln!(
                 "[Debug] Successfully loaded {} debug addresses from minimal parquet",
                 debug_addresses.len(),
             );
-            let ui_addresses: Vec<StoredAddress> = debug_addresses
+            let parsed: Vec<(String, String, String)> = debug_addresses
                 .into_iter()
                 .map(|debug_addr| {
                     let (street, street_number) = parse_address(&debug_addr.adress);
@@ -66,7 +76,19 @@ pub fn load_debug_addresses() -> Vec<StoredAddress> {
                         "[Debug] Creating address: '{}' + '{}' (postal: {})",
                         street, street_number, debug_addr.postnummer,
                     );
-                    StoredAddress::new(street, street_number, debug_addr.postnummer)
+                    (street, street_number, debug_addr.postnummer)
+                })
+                .collect();
+            let queries: Vec<(&str, &str, &str)> = parsed
+                .iter()
+                .map(|(street, number, postal)| (street.as_str(), number.as_str(), postal.as_str()))
+                .collect();
+            let matches = match_many(&queries, get_parking_data().values());
+            let ui_addresses: Vec<StoredAddress> = parsed
+                .into_iter()
+                .zip(matches)
+                .map(|((street, street_number, postal_code), exact)| {
+                    StoredAddress::from_match(street, street_number, postal_code, exact)
                 })
                 .collect();
             let valid_count = ui_addresses.iter().filter(|a| a.valid).count();
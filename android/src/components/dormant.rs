@@ -8,10 +8,19 @@
 //! `DormantService (Kotlin)` → JNI → `dormant_hourly_check (Rust)`
 //! → reads parquet, detects transitions → returns JSON
 //! → `DormantService` → `NotificationHelper.showNotification()`
-use crate::components::countdown::TimeBucket;
+//!
+//! Precise start reminders that fall before the next hourly check are
+//! returned with a `fire_at` timestamp so Kotlin can post them at the
+//! exact minute instead of on the hour. The same timestamp holds back
+//! notifications that fall in quiet hours until the window ends.
+use crate::components::notifications::{
+    PRECISE_REMINDER_LEAD_MINUTES, batch_text, bucket_enabled, channel_for, precise_reminder_at,
+    relevant_at, starting_soon_text,
+};
 use crate::components::settings::load_settings;
 use crate::components::storage::read_addresses_from_device;
-use crate::components::transitions::detect_transitions;
+use crate::components::transitions::{batch_transitions, bucket_to_string, detect_transitions};
+use chrono::{Duration, Utc};
 use serde::Serialize;
 /// Notification data returned to Kotlin for display
 #[derive(Clone, Debug, Serialize)]
@@ -21,6 +30,12 @@ pub struct DormantNotification {
     pub notification_id: i32,
     pub title: String,
     pub body: String,
+    /// Epoch milliseconds to show the notification at; `None` means immediately
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fire_at: Option<i64>,
+    /// Bucket whose panel opens when the notification is tapped
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub panel: Option<String>,
 }
 /// Run the hourly dormant check
 ///
@@ -28,7 +43,9 @@ pub struct DormantNotification {
 /// 2. Reads stored addresses from parquet
 /// 3. Filters to active addresses with matched entries
 /// 4. Detects bucket transitions
-/// 5. Maps transitions to notification data (respecting user settings)
+/// 5. Batches transitions per bucket and maps them to notification data
+///    (respecting user settings)
+/// 6. Adds precise start reminders due before the next hourly check
 ///
 /// Returns a list of notifications to send.
 #[allow(dead_code)]
@@ -67,54 +84,84 @@ pub fn dormant_hourly_check(storage_path: &str) -> Vec<DormantNotification> {
     let transitions = detect_transitions(&active);
     eprintln!("[Dormant] Detected {} transitions", transitions.len());
     let settings = load_settings();
+    let quiet_hours = settings.notifications.quiet_hours;
+    let now = Utc::now();
     let mut notifications = Vec::new();
-    for (addr, _prev, new_bucket) in transitions {
-        let (channel_id, title, body, should_send) = match new_bucket {
-            TimeBucket::Now => (
-                "amp_active".to_string(),
-                "Städning NU!".to_string(),
-                format!(
-                    "Städning pågående på {}. Din bil på {} {} kan få böter!",
-                    addr.street, addr.street, addr.street_number,
-                ),
-                settings.notifications.stadning_nu,
-            ),
-            TimeBucket::Within6Hours => (
-                "amp_six_hours".to_string(),
-                "Städning om 6 timmar".to_string(),
-                format!(
-                    "Städning börjar snart på {}. Du bör flytta din bil från {} {}.",
-                    addr.street, addr.street, addr.street_number,
-                ),
-                settings.notifications.sex_timmar,
-            ),
-            TimeBucket::Within1Day => (
-                "amp_one_day".to_string(),
-                "Städning inom 1 dygn".to_string(),
-                format!(
-                    "Städning inom 1 dygn på {}. Planera att flytta din bil från {} {}.",
-                    addr.street, addr.street, addr.street_number,
-                ),
-                settings.notifications.en_dag,
-            ),
-            _ => continue,
+    if settings.notifications.stadning_nu {
+        let lead = Duration::minutes(PRECISE_REMINDER_LEAD_MINUTES);
+        for addr in &active {
+            let Some((reminder_at, start)) = precise_reminder_at(addr, lead, now) else {
+                continue;
+            };
+            if reminder_at < now || reminder_at >= now + Duration::hours(1) {
+                continue;
+            }
+            let fire_at = quiet_hours.map_or(reminder_at, |quiet| quiet.defer(reminder_at));
+            if fire_at >= start {
+                eprintln!(
+                    "[Dormant] Dropping start reminder for {} {} (quiet hours until after start)",
+                    addr.street, addr.street_number,
+                );
+                continue;
+            }
+            let (title, body) = starting_soon_text(addr, start - fire_at);
+            eprintln!(
+                "[Dormant] Queuing start reminder at {} for {} {}",
+                fire_at, addr.street, addr.street_number,
+            );
+            notifications.push(DormantNotification {
+                channel_id: "amp_active".to_string(),
+                notification_id: addr.id as i32,
+                title,
+                body,
+                fire_at: Some(fire_at.timestamp_millis()),
+                panel: None,
+            });
+        }
+    }
+    let send_at = quiet_hours.map_or(now, |quiet| quiet.defer(now));
+    for batch in batch_transitions(transitions, now) {
+        let Some(channel_id) = channel_for(&batch.bucket) else {
+            continue;
         };
-        if !should_send {
+        if !bucket_enabled(&settings.notifications, &batch.bucket) {
             eprintln!(
-                "[Dormant] Skipping notification for {} {} (disabled in settings)",
-                addr.street, addr.street_number,
+                "[Dormant] Skipping {:?} notification for {} addresses (disabled in settings)",
+                batch.bucket,
+                batch.addresses.len(),
             );
             continue;
         }
+        let batch = if send_at > now {
+            let Some(relevant) =
+                relevant_at(&batch, send_at, &settings.notifications.bucket_settings())
+            else {
+                eprintln!(
+                    "[Dormant] Dropping {:?} notification, no address still in it after quiet hours",
+                    batch.bucket,
+                );
+                continue;
+            };
+            relevant
+        } else {
+            batch
+        };
+        let Some((title, body)) = batch_text(&batch.bucket, &batch.addresses) else {
+            continue;
+        };
         eprintln!(
-            "[Dormant] Queuing notification: channel={}, title={}, addr={} {}",
-            channel_id, title, addr.street, addr.street_number,
+            "[Dormant] Queuing notification: channel={}, title={}, addresses={}",
+            channel_id,
+            title,
+            batch.addresses.len(),
         );
         notifications.push(DormantNotification {
-            channel_id,
-            notification_id: addr.id as i32,
+            channel_id: channel_id.to_string(),
+            notification_id: batch.notification_id as i32,
             title,
             body,
+            fire_at: (send_at > now).then(|| send_at.timestamp_millis()),
+            panel: Some(bucket_to_string(&batch.bucket).to_string()),
         });
     }
     eprintln!("[Dormant] Returning {} notifications", notifications.len());
@@ -130,6 +177,10 @@ use jni::sys::jstring;
 ///
 /// Returns a JSON string with notification data:
 /// `[{"channel_id":"amp_active","notification_id":123,"title":"...","body":"..."}]`
+///
+/// Precise start reminders and notifications held by quiet hours additionally
+/// carry `"fire_at"` (epoch milliseconds),
+/// bucket notifications carry `"panel"` (e.g. `"Within6Hours"`).
 #[cfg(target_os = "android")]
 #[unsafe(no_mangle)]
 pub extern "system" fn Java_se_malmo_skaggbyran_amp_DormantBridge_dormantCheck<'local>(
@@ -191,11 +242,15 @@ mod tests {
             notification_id: 42,
             title: "Test".to_string(),
             body: "Test body".to_string(),
+            fire_at: None,
+            panel: None,
         };
         let json = serde_json::to_string(&[notif]).unwrap();
         assert!(json.contains("amp_active"));
         assert!(json.contains("42"));
         assert!(json.contains("Test"));
+        assert!(!json.contains("fire_at"));
+        assert!(!json.contains("panel"));
     }
     #[test]
     fn test_dormant_check_empty_storage() {
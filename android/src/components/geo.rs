@@ -9,7 +9,8 @@
 //!
 //! 1. Build: map each address point to its grid cell (rayon par_iter)
 //! 2. Lookup: get cell for GPS coordinate, expand to 3×3 neighbourhood,
-//!    check all address points in those cells with haversine distance
+//!    check all address points in those cells, measured as
+//!    [`amp_core::correlation::nearest_address`] measures them
 //! 3. Apply 20 m cutoff — same threshold used in the server correlation
 //!
 //! # Examples
@@ -18,15 +19,50 @@
 //!     println!("Nearest address: {} {}", addr.gata, addr.gatunummer);
 //! }
 //! ```
+//!
+//! # Watching the Nearest Saved Address
+//!
+//! [`watch`] polls the device location on a background thread and reports
+//! which saved address the user is closest to, and whether its restriction
+//! is active right now, as a [`NearestUpdate`]. Saved addresses are placed
+//! using their coordinates in adresser.parquet, and the nearest one is found
+//! with the same distance check and cutoff as the grid lookup.
+//!
+//! To save battery, polling is skipped while the app is in the background
+//! (see [`set_app_foreground`]), and the interval is never shorter than
+//! [`MIN_WATCH_INTERVAL`]. Without location permission every tick reports
+//! [`NearestUpdate::Disabled`]; the system dialog is never shown from the
+//! watch.
+//!
+//! ```no_run
+//! use amp_android::components::geo::{self, NearestUpdate};
+//! use std::time::Duration;
+//!
+//! let watch = geo::watch(Duration::from_secs(60), |update| match update {
+//!     NearestUpdate::Near { address, restricted: true, .. } => {
+//!         println!("Parked near an active restriction at {}", address.street);
+//!     }
+//!     other => println!("{:?}", other),
+//! });
+//! // Polling stops when the handle is dropped
+//! drop(watch);
+//! ```
+use crate::android_bridge::{has_location_permission, read_device_gps_location};
+use crate::components::address_utils::normalize_string;
 use crate::components::static_data::load_ref_data;
-use amp_core::correlation_algorithms::common::{
-    CELL_SIZE, get_cell, get_nearby_cells, haversine_distance,
-};
+use crate::components::storage::read_addresses_from_device;
+use crate::ui::StoredAddress;
+use amp_core::correlation_algorithms::common::{CELL_SIZE, get_cell, get_nearby_cells, is_closer};
+use amp_core::crs::CoordinateSpace;
 use amp_core::structs::AdressClean;
+use chrono::{DateTime, Utc};
 use rayon::prelude::*;
 use rust_decimal::prelude::ToPrimitive;
 use std::collections::HashMap;
-use std::sync::OnceLock;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
 /// Cutoff for GPS-to-address-centroid matching.
 ///
 /// Uses 100 m rather than the server's 20 m line-to-point threshold because:
@@ -34,6 +70,10 @@ use std::sync::OnceLock;
 /// - Phone GPS accuracy is typically 5–20 m (worse indoors/near buildings)
 /// - The stored coordinate is the property centroid, not the street kerb
 const GPS_CUTOFF_METERS: f64 = 50.0;
+/// Shortest polling interval accepted by [`watch`].
+pub const MIN_WATCH_INTERVAL: Duration = Duration::from_secs(15);
+/// Whether the app is in the foreground; [`watch`] only polls while it is.
+static APP_IN_FOREGROUND: AtomicBool = AtomicBool::new(true);
 /// Cached grid spatial index over all address points.
 static ADDRESS_GRID: OnceLock<AddressGrid> = OnceLock::new();
 /// Grid-based spatial index for point-to-point nearest-neighbour queries.
@@ -60,7 +100,7 @@ impl AddressGrid {
             .collect();
         let mut grid: HashMap<(i32, i32), Vec<usize>> = HashMap::new();
         for (idx, &point) in points.iter().enumerate() {
-            if point[0] == 0.0 && point[1] == 0.0 {
+            if !addresses[idx].has_location() {
                 continue;
             }
             let cell = get_cell(point, CELL_SIZE);
@@ -72,20 +112,39 @@ impl AddressGrid {
     fn find_nearest(&self, lat: f64, lon: f64) -> Option<usize> {
         let query = [lon, lat];
         let cell = get_cell(query, CELL_SIZE);
-        let nearby = get_nearby_cells(cell);
-        let mut best: Option<(f64, usize)> = None;
-        for check_cell in nearby {
-            if let Some(indices) = self.grid.get(&check_cell) {
-                for &idx in indices {
-                    let dist = haversine_distance(query, self.points[idx]);
-                    if dist <= GPS_CUTOFF_METERS && best.is_none_or(|(d, _)| dist < d) {
-                        best = Some((dist, idx));
-                    }
-                }
-            }
+        let candidates = get_nearby_cells(cell)
+            .into_iter()
+            .filter_map(|check_cell| self.grid.get(&check_cell))
+            .flatten()
+            .map(|&idx| (idx, self.points[idx]));
+        nearest_point(query, candidates).map(|(idx, _)| idx)
+    }
+}
+/// Closest candidate to `query` within `GPS_CUTOFF_METERS`.
+///
+/// Shared by the grid lookup and [`watch`] so both agree on what "near" means,
+/// and measured like [`amp_core::correlation::nearest_address`] so the grid
+/// finds the same address as the JNI lookup.
+///
+/// # Arguments
+/// * `query` - [longitude, latitude] of the device
+/// * `candidates` - (index, [longitude, latitude]) pairs to compare against
+///
+/// # Returns
+/// `(index, distance in meters)` of the closest candidate, or `None` if all
+/// are beyond the cutoff.
+fn nearest_point(
+    query: [f64; 2],
+    candidates: impl IntoIterator<Item = (usize, [f64; 2])>,
+) -> Option<(usize, f64)> {
+    let mut best: Option<(usize, f64)> = None;
+    for (idx, point) in candidates {
+        let dist = CoordinateSpace::Wgs84.distance_point_to_line(query, point, point);
+        if dist <= GPS_CUTOFF_METERS && is_closer((idx, dist), best) {
+            best = Some((idx, dist));
         }
-        best.map(|(_, idx)| idx)
     }
+    best
 }
 fn get_address_grid() -> &'static AddressGrid {
     ADDRESS_GRID.get_or_init(|| {
@@ -122,9 +181,283 @@ pub fn find_address_by_coordinates(lat: f64, lon: f64) -> Option<AdressClean> {
     }
     result
 }
+/// Result of one [`watch`] tick
+#[derive(Debug, Clone, PartialEq)]
+pub enum NearestUpdate {
+    /// Location permission is not granted
+    Disabled,
+    /// Permission is granted but the device has no location fix
+    NoFix,
+    /// No saved address within `GPS_CUTOFF_METERS` of the device
+    NoneNearby,
+    /// Nearest saved address to the device
+    Near {
+        address: Box<StoredAddress>,
+        /// Distance from the device to the address in meters
+        distance_m: f64,
+        /// Whether the address is valid and its restriction is active now
+        restricted: bool,
+    },
+}
+/// Handle to a running [`watch`]; polling stops when it is dropped.
+pub struct GeoWatch {
+    stopped: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+impl GeoWatch {
+    /// Stop polling. The callback is not invoked again once this returns,
+    /// except for a tick that is already in progress.
+    #[allow(dead_code)]
+    pub fn stop(self) {
+        drop(self);
+    }
+}
+impl Drop for GeoWatch {
+    fn drop(&mut self) {
+        self.stopped.store(true, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            thread.thread().unpark();
+        }
+    }
+}
+/// Tell [`watch`] whether the app is visible.
+///
+/// Called from `MainActivity.onResume` / `onPause` through
+/// `DormantBridge.setAppForeground`. While backgrounded no location reads
+/// are made and no updates are emitted.
+#[allow(dead_code)]
+pub fn set_app_foreground(foreground: bool) {
+    eprintln!("[Geo] App foreground: {}", foreground);
+    APP_IN_FOREGROUND.store(foreground, Ordering::Relaxed);
+}
+/// Periodically report the nearest saved address to the device.
+///
+/// Spawns a background thread that, every `interval` while the app is in
+/// the foreground, reads the device location and the saved addresses and
+/// passes the resulting [`NearestUpdate`] to `on_update`. The first tick
+/// runs immediately.
+///
+/// # Arguments
+/// * `interval` - Time between location reads, raised to [`MIN_WATCH_INTERVAL`] if shorter
+/// * `on_update` - Called from the watch thread with each update
+///
+/// # Returns
+/// A [`GeoWatch`] handle; dropping it stops the thread.
+#[allow(dead_code)]
+pub fn watch<F>(interval: Duration, mut on_update: F) -> GeoWatch
+where
+    F: FnMut(NearestUpdate) + Send + 'static,
+{
+    let interval = interval.max(MIN_WATCH_INTERVAL);
+    let stopped = Arc::new(AtomicBool::new(false));
+    let thread_stopped = Arc::clone(&stopped);
+    let thread = thread::spawn(move || {
+        eprintln!("[Geo] Watch started, interval {:?}", interval);
+        let mut locations: HashMap<(String, String, String), Option<[f64; 2]>> = HashMap::new();
+        while !thread_stopped.load(Ordering::Relaxed) {
+            if APP_IN_FOREGROUND.load(Ordering::Relaxed) {
+                let update = if !has_location_permission() {
+                    NearestUpdate::Disabled
+                } else if let Some((lat, lon)) = read_device_gps_location() {
+                    let addresses = read_addresses_from_device();
+                    let points: Vec<Option<[f64; 2]>> = addresses
+                        .iter()
+                        .map(|address| {
+                            let key = (
+                                address.street.clone(),
+                                address.street_number.clone(),
+                                address.postal_code.clone(),
+                            );
+                            *locations
+                                .entry(key)
+                                .or_insert_with(|| locate_saved_address(address, load_ref_data()))
+                        })
+                        .collect();
+                    nearest_saved_address(&addresses, &points, lat, lon, Utc::now())
+                } else {
+                    NearestUpdate::NoFix
+                };
+                if !thread_stopped.load(Ordering::Relaxed) {
+                    on_update(update);
+                }
+            }
+            let deadline = Instant::now() + interval;
+            while !thread_stopped.load(Ordering::Relaxed) {
+                let now = Instant::now();
+                if now >= deadline {
+                    break;
+                }
+                thread::park_timeout(deadline - now);
+            }
+        }
+        eprintln!("[Geo] Watch stopped");
+    });
+    GeoWatch {
+        stopped,
+        thread: Some(thread),
+    }
+}
+/// Coordinates of a saved address in the reference data, as [longitude, latitude].
+///
+/// Street, number and postal code are compared case-insensitively, and the
+/// postal code also ignores spaces ("211 22" matches "21122").
+fn locate_saved_address(address: &StoredAddress, refs: &[AdressClean]) -> Option<[f64; 2]> {
+    let street = normalize_string(&address.street, false);
+    let number = normalize_string(&address.street_number, false);
+    let postal_code: String = address.postal_code.split_whitespace().collect();
+    refs.iter()
+        .find(|entry| {
+            normalize_string(&entry.gata, false) == street
+                && normalize_string(&entry.gatunummer, false) == number
+                && entry.postnummer.as_deref().is_none_or(|p| {
+                    postal_code.is_empty()
+                        || p.split_whitespace().collect::<String>() == postal_code
+                })
+        })
+        .filter(|entry| entry.has_location())
+        .map(|entry| {
+            [
+                entry.coordinates[0].to_f64().unwrap_or(0.0),
+                entry.coordinates[1].to_f64().unwrap_or(0.0),
+            ]
+        })
+}
+/// Build the [`NearestUpdate`] for a location fix.
+///
+/// # Arguments
+/// * `addresses` - Saved addresses
+/// * `points` - Coordinates of each saved address, `None` if it could not be placed
+/// * `lat`, `lon` - Device location
+/// * `now` - Time used to decide whether the restriction is active
+fn nearest_saved_address(
+    addresses: &[StoredAddress],
+    points: &[Option<[f64; 2]>],
+    lat: f64,
+    lon: f64,
+    now: DateTime<Utc>,
+) -> NearestUpdate {
+    let candidates = points
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, point)| point.map(|point| (idx, point)));
+    match nearest_point([lon, lat], candidates) {
+        Some((idx, distance_m)) => {
+            let address = Box::new(addresses[idx].clone());
+            let restricted = address.valid
+                && address
+                    .matched_entry
+                    .as_ref()
+                    .is_some_and(|entry| entry.is_active(now));
+            NearestUpdate::Near {
+                address,
+                distance_m,
+                restricted,
+            }
+        }
+        None => NearestUpdate::NoneNearby,
+    }
+}
+#[cfg(target_os = "android")]
+use jni::JNIEnv;
+#[cfg(target_os = "android")]
+use jni::objects::JClass;
+#[cfg(target_os = "android")]
+use jni::sys::{jboolean, jdouble, jstring};
+/// JNI: Called by DormantBridge.setAppForeground(foreground)
+///
+/// Forwards activity visibility to [`set_app_foreground`].
+#[cfg(target_os = "android")]
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_se_malmo_skaggbyran_amp_DormantBridge_setAppForeground<'local>(
+    _env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    foreground: jboolean,
+) {
+    set_app_foreground(foreground != 0);
+}
+/// JNI: Called by SearchBridge.rustNearestAddress(lat, lon)
+///
+/// Finds the closest reference address with
+/// [`amp_core::correlation::nearest_address`] so "use my location" can
+/// prefill the Add Address form. A single scan is cheaper than building the
+/// address grid for one lookup. Returns
+/// `{"street":"Kornettsgatan","street_number":"18C","postal_code":"21438","distance":12.3}`,
+/// or `null` if nothing is within [`GPS_CUTOFF_METERS`].
+#[cfg(target_os = "android")]
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_se_malmo_skaggbyran_amp_SearchBridge_rustNearestAddress<'local>(
+    env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    lat: jdouble,
+    lon: jdouble,
+) -> jstring {
+    use rust_decimal::prelude::FromPrimitive;
+    let addresses = load_ref_data();
+    let nearest = rust_decimal::Decimal::from_f64(lon)
+        .zip(rust_decimal::Decimal::from_f64(lat))
+        .and_then(|(lon, lat)| {
+            amp_core::correlation::nearest_address([lon, lat], addresses, GPS_CUTOFF_METERS)
+        });
+    let json = match nearest {
+        Some((idx, distance)) => {
+            let address = &addresses[idx];
+            eprintln!(
+                "[Geo JNI] Nearest address: {} ({:.1} m)",
+                address.adress, distance
+            );
+            serde_json::json!({
+                "street": address.gata,
+                "street_number": address.gatunummer,
+                "postal_code": address.postnummer,
+                "distance": distance,
+            })
+            .to_string()
+        }
+        None => {
+            eprintln!("[Geo JNI] No address within {} m", GPS_CUTOFF_METERS);
+            "null".to_string()
+        }
+    };
+    env.new_string(&json)
+        .expect("Failed to create JSON output string")
+        .into_raw()
+}
 #[cfg(test)]
 mod tests {
     use super::*;
+    use amp_core::structs::{DB, DBParams};
+    use chrono::TimeZone;
+    use rust_decimal::Decimal;
+    use std::str::FromStr;
+    fn saved(id: u64, street: &str, tid: &str) -> StoredAddress {
+        StoredAddress {
+            id,
+            street: street.to_string(),
+            street_number: "1".to_string(),
+            postal_code: "211 22".to_string(),
+            valid: true,
+            active: true,
+            matched_entry: DB::from_params(DBParams {
+                postnummer: Some("21122".to_string()),
+                adress: format!("{} 1", street),
+                gata: Some(street.to_string()),
+                gatunummer: Some("1".to_string()),
+                info: None,
+                dag: 15,
+                tid: tid.to_string(),
+                taxa: None,
+                antal_platser: None,
+                typ_av_parkering: None,
+                year: 2024,
+                month: 1,
+            }),
+            parking_info: None,
+            match_reason: None,
+            approximate: false,
+            invalid_reason: None,
+            snoozed_until: None,
+        }
+    }
     #[test]
     fn test_find_address_no_crash() {
         let result = find_address_by_coordinates(0.0, 0.0);
@@ -137,4 +470,108 @@ mod tests {
         assert!(!grid.grid.is_empty(), "Grid should have entries");
         assert_eq!(grid.points.len(), addresses.len());
     }
+    #[test]
+    fn test_address_grid_agrees_with_nearest_address() {
+        let address = |lon: i64, gatunummer: &str| AdressClean {
+            coordinates: [Decimal::new(lon, 6), Decimal::new(55_600_000, 6)],
+            postnummer: Some("21438".to_string()),
+            adress: format!("Storgatan {}", gatunummer),
+            gata: "Storgatan".to_string(),
+            gatunummer: gatunummer.to_string(),
+        };
+        let addresses = vec![
+            address(13_000_000, "1"),
+            address(13_000_300, "3"),
+            address(13_000_600, "5"),
+        ];
+        let grid = AddressGrid::build(&addresses);
+        for lon in [13_000_010, 13_000_140, 13_000_320, 13_000_460, 13_000_900] {
+            let gps = [Decimal::new(lon, 6), Decimal::new(55_600_050, 6)];
+            let expected =
+                amp_core::correlation::nearest_address(gps, &addresses, GPS_CUTOFF_METERS)
+                    .map(|(idx, _)| idx);
+            assert_eq!(grid.find_nearest(55.60005, lon as f64 / 1e6), expected);
+        }
+    }
+    #[test]
+    fn test_nearest_saved_address_reports_restriction() {
+        // Restriction 08:00-12:00 Swedish time on 2024-01-15 (07:00-11:00 UTC)
+        let addresses = vec![
+            saved(1, "Storgatan", "0800-1200"),
+            saved(2, "Lillgatan", "0800-1200"),
+        ];
+        let points = vec![Some([13.0000, 55.6000]), Some([13.0000, 55.6002])];
+        let during = Utc.with_ymd_and_hms(2024, 1, 15, 8, 0, 0).unwrap();
+        let after = Utc.with_ymd_and_hms(2024, 1, 15, 12, 0, 0).unwrap();
+        match nearest_saved_address(&addresses, &points, 55.60019, 13.0, during) {
+            NearestUpdate::Near {
+                address,
+                distance_m,
+                restricted,
+            } => {
+                assert_eq!(address.id, 2);
+                assert!(distance_m < 5.0, "{distance_m}");
+                assert!(restricted);
+            }
+            other => panic!("expected Near, got {:?}", other),
+        }
+        assert!(matches!(
+            nearest_saved_address(&addresses, &points, 55.60019, 13.0, after),
+            NearestUpdate::Near {
+                restricted: false,
+                ..
+            }
+        ));
+    }
+    #[test]
+    fn test_nearest_saved_address_respects_cutoff() {
+        let addresses = vec![
+            saved(1, "Storgatan", "0800-1200"),
+            saved(2, "Lillgatan", "0800-1200"),
+        ];
+        let now = Utc.with_ymd_and_hms(2024, 1, 15, 8, 0, 0).unwrap();
+        // ~111 m north of the only placed address
+        let points = vec![Some([13.0, 55.6]), None];
+        assert_eq!(
+            nearest_saved_address(&addresses, &points, 55.601, 13.0, now),
+            NearestUpdate::NoneNearby
+        );
+        assert_eq!(
+            nearest_saved_address(&addresses, &[None, None], 55.6, 13.0, now),
+            NearestUpdate::NoneNearby
+        );
+    }
+    #[test]
+    fn test_locate_saved_address_ignores_case_and_postal_spacing() {
+        let refs = vec![AdressClean {
+            coordinates: [
+                Decimal::from_str("13.0001").unwrap(),
+                Decimal::from_str("55.6001").unwrap(),
+            ],
+            postnummer: Some("21122".to_string()),
+            adress: "Storgatan 1".to_string(),
+            gata: "Storgatan".to_string(),
+            gatunummer: "1".to_string(),
+        }];
+        let mut address = saved(1, "storgatan", "0800-1200");
+        assert_eq!(
+            locate_saved_address(&address, &refs),
+            Some([13.0001, 55.6001])
+        );
+        address.postal_code = "21123".to_string();
+        assert_eq!(locate_saved_address(&address, &refs), None);
+    }
+    #[test]
+    fn test_watch_stops_on_drop() {
+        let ticks = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let counter = Arc::clone(&ticks);
+        let watch = watch(Duration::ZERO, move |update| {
+            assert_eq!(update, NearestUpdate::Disabled);
+            counter.fetch_add(1, Ordering::Relaxed);
+        });
+        thread::sleep(Duration::from_millis(200));
+        watch.stop();
+        // Interval is raised to MIN_WATCH_INTERVAL, so only the first tick ran
+        assert_eq!(ticks.load(Ordering::Relaxed), 1);
+    }
 }
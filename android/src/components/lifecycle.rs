@@ -10,7 +10,7 @@
 //!
 //! ## Daily Operations
 //! - **00:00 (midnight)**: Read storage, check validity, write if changed
-//! - **Every 60 seconds**: Check for panel transitions and send notifications
+//! - **Every 60 seconds**: Check for panel transitions and precise start reminders
 //! - **On state change**: Write to storage immediately
 //! - **On crash/exit**: Write to storage (graceful shutdown)
 //!
@@ -38,13 +38,15 @@
 //! // On app shutdown
 //! manager.shutdown();
 //! ```
-use crate::components::countdown::TimeBucket;
-use crate::components::notifications::{notify_active, notify_one_day, notify_six_hours};
+use crate::components::notifications::{
+    PRECISE_REMINDER_LEAD_MINUTES, notify_batch, notify_starting_soon, send_deferred,
+};
+use crate::components::settings::load_settings;
 use crate::components::storage::{read_addresses_from_device, write_addresses_to_device};
-use crate::components::transitions::detect_transitions;
-use crate::components::validity::check_and_update_validity;
+use crate::components::transitions::{batch_transitions, detect_transitions};
+use crate::components::validity::recompute_validity;
 use crate::ui::StoredAddress;
-use chrono::{DateTime, Local};
+use chrono::{DateTime, Duration, Local, Utc};
 use std::sync::{Arc, Mutex};
 /// Lifecycle manager for background operations
 ///
@@ -111,6 +113,8 @@ impl LifecycleManager {
     /// - Load current addresses from storage
     /// - Detect transitions between time panels
     /// - Send appropriate notifications based on new panel
+    /// - Send the precise start reminder for active addresses whose start is
+    ///   within [`PRECISE_REMINDER_LEAD_MINUTES`]
     ///
     /// # Returns
     /// Number of notifications sent
@@ -129,37 +133,23 @@ impl LifecycleManager {
     pub fn check_and_send_notifications(&self) -> usize {
         eprintln!("[Lifecycle] Checking for notification-worthy transitions");
         let addresses = read_addresses_from_device();
+        let now = Utc::now();
+        let mut sent_count = send_precise_reminders(&addresses) + send_deferred(now);
         let transitions = detect_transitions(&addresses);
         if transitions.is_empty() {
             eprintln!("[Lifecycle] No transitions detected");
-            return 0;
+            return sent_count;
         }
         eprintln!("[Lifecycle] Processing {} transition(s)", transitions.len());
-        let mut sent_count = 0;
-        for (addr, prev_bucket, new_bucket) in transitions {
+        for (addr, prev_bucket, new_bucket) in &transitions {
             eprintln!(
                 "[Lifecycle] Transition: {} {} (id={}) {:?} → {:?}",
                 addr.street, addr.street_number, addr.id, prev_bucket, new_bucket,
             );
-            match new_bucket {
-                TimeBucket::Within1Day => {
-                    notify_one_day(&addr);
-                    sent_count += 1;
-                }
-                TimeBucket::Within6Hours => {
-                    notify_six_hours(&addr);
-                    sent_count += 1;
-                }
-                TimeBucket::Now => {
-                    notify_active(&addr);
-                    sent_count += 1;
-                }
-                _ => {
-                    eprintln!(
-                        "[Lifecycle] Bucket {:?} does not trigger notification",
-                        new_bucket,
-                    );
-                }
+        }
+        for batch in batch_transitions(transitions, now) {
+            if notify_batch(&batch, now) {
+                sent_count += 1;
             }
         }
         eprintln!("[Lifecycle] Sent {} notification(s)", sent_count);
@@ -218,8 +208,15 @@ impl LifecycleManager {
     fn perform_daily_tasks(&self) {
         eprintln!("[Lifecycle] Running daily tasks at {}", Local::now());
         let mut addresses = read_addresses_from_device();
-        let validity_changed = check_and_update_validity(&mut addresses);
-        if validity_changed {
+        let bounds = load_settings().notifications.bucket_settings();
+        let changes = recompute_validity(&mut addresses, Utc::now(), &bounds);
+        for change in &changes {
+            eprintln!(
+                "[Lifecycle] Address {} validity {} -> {}, bucket {:?} -> {:?}",
+                change.id, change.old_valid, change.new_valid, change.old_bucket, change.new_bucket,
+            );
+        }
+        if !changes.is_empty() {
             if let Err(e) = write_addresses_to_device(&addresses) {
                 eprintln!("[Lifecycle] Failed to save after validity update: {}", e);
             } else {
@@ -307,30 +304,32 @@ pub fn handle_active_toggle(addresses: &[StoredAddress]) {
 /// # Returns
 /// Number of notifications sent
 fn check_and_send_notifications_standalone(addresses: &[StoredAddress]) -> usize {
+    let now = Utc::now();
+    let mut sent_count = send_precise_reminders(addresses) + send_deferred(now);
     let transitions = detect_transitions(addresses);
     if transitions.is_empty() {
-        return 0;
+        return sent_count;
     }
-    let mut sent_count = 0;
-    for (addr, _prev_bucket, new_bucket) in transitions {
-        match new_bucket {
-            TimeBucket::Within1Day => {
-                notify_one_day(&addr);
-                sent_count += 1;
-            }
-            TimeBucket::Within6Hours => {
-                notify_six_hours(&addr);
-                sent_count += 1;
-            }
-            TimeBucket::Now => {
-                notify_active(&addr);
-                sent_count += 1;
-            }
-            _ => {}
+    for batch in batch_transitions(transitions, now) {
+        if notify_batch(&batch, now) {
+            sent_count += 1;
         }
     }
     sent_count
 }
+/// Send precise start reminders for active, matched addresses
+///
+/// # Returns
+/// Number of reminders sent
+fn send_precise_reminders(addresses: &[StoredAddress]) -> usize {
+    let now = Utc::now();
+    let lead = Duration::minutes(PRECISE_REMINDER_LEAD_MINUTES);
+    addresses
+        .iter()
+        .filter(|a| a.active)
+        .filter(|a| notify_starting_soon(a, lead, now))
+        .count()
+}
 #[cfg(test)]
 mod tests {
     use super::*;
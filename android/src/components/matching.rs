@@ -20,8 +20,10 @@
 //! }
 //! ```
 use crate::components::static_data::{get_address_data, get_static_data};
+use crate::ui::FuzzyMatch;
 use amp_core::structs::DB;
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::fmt;
 /// Validation errors for address input
 #[derive(Debug, Clone, PartialEq)]
@@ -238,14 +240,48 @@ pub fn validate_input(
 /// }
 /// ```
 ///
+/// # Postal Code Boundaries
+/// Without a postal code, the same street and number may exist under several
+/// postal codes. The lowest postal code is picked deterministically; use
+/// [`match_address_all`] to get every candidate.
+///
 /// # Performance
-/// This is an O(1) HashMap lookup operation after validation.
+/// This is an O(1) HashMap lookup operation after validation when a postal
+/// code is given, and a linear scan otherwise.
 pub fn match_address(street: &str, street_number: &str, postal_code: &str) -> MatchResult {
     if let Err(e) = validate_input(street, street_number, postal_code) {
         eprintln!("[Matching] Validation error: {}", e);
         return MatchResult::Invalid;
     }
     let postal_normalized = postal_code.trim().replace(' ', "");
+    if postal_normalized.is_empty() {
+        let candidates = exact_candidates(
+            get_static_data().values(),
+            street,
+            street_number,
+            &postal_normalized,
+        );
+        let postal_codes: HashSet<_> = candidates.iter().map(|c| &c.postnummer).collect();
+        if postal_codes.len() > 1 {
+            eprintln!(
+                "[Matching] {} {} exists under {} postal codes, picking {:?}; use match_address_all for every candidate",
+                street,
+                street_number,
+                postal_codes.len(),
+                candidates[0].postnummer,
+            );
+        }
+        return match candidates.first() {
+            Some(entry) => MatchResult::Valid(Box::from((*entry).clone())),
+            None => {
+                eprintln!(
+                    "[Matching] Address not found: {} {} {}",
+                    street, street_number, postal_code,
+                );
+                MatchResult::Invalid
+            }
+        };
+    }
     match get_address_data(street, street_number, &postal_normalized) {
         Some(entry) => {
             eprintln!(
@@ -263,6 +299,328 @@ pub fn match_address(street: &str, street_number: &str, postal_code: &str) -> Ma
         }
     }
 }
+/// Collect every exact street + number match, optionally narrowed by postal code
+///
+/// Streets can cross postal-code boundaries, so the same `gata` and
+/// `gatunummer` may appear under more than one `postnummer`. With an empty
+/// `postal_normalized` all such candidates are kept; otherwise only those
+/// whose postal code matches are returned.
+///
+/// Candidates are sorted by postal code and then start time so the result
+/// does not depend on HashMap iteration order.
+fn exact_candidates<'a>(
+    entries: impl IntoIterator<Item = &'a DB>,
+    street: &str,
+    street_number: &str,
+    postal_normalized: &str,
+) -> Vec<&'a DB> {
+    let street_lower = street.trim().to_lowercase();
+    let number_lower = street_number.trim().to_lowercase();
+    let mut candidates: Vec<&DB> = entries
+        .into_iter()
+        .filter(|entry| {
+            entry.gata.as_deref().map(str::to_lowercase).as_deref() == Some(street_lower.as_str())
+                && entry
+                    .gatunummer
+                    .as_deref()
+                    .map(str::to_lowercase)
+                    .as_deref()
+                    == Some(number_lower.as_str())
+        })
+        .filter(|entry| {
+            postal_normalized.is_empty()
+                || entry
+                    .postnummer
+                    .as_deref()
+                    .is_some_and(|p| p.replace(' ', "") == postal_normalized)
+        })
+        .collect();
+    candidates.sort_by(|a, b| {
+        a.postnummer
+            .cmp(&b.postnummer)
+            .then(a.start_time.cmp(&b.start_time))
+    });
+    candidates
+}
+/// Match user input address and return every candidate entry
+///
+/// Unlike [`match_address`], which returns a single entry, this returns all
+/// exact matches for the street and number. When `postal_code` is empty the
+/// result may span several postal codes (a street crossing a boundary); when
+/// it is given, only entries under that postal code are returned.
+///
+/// # Arguments
+/// * `street` - Street name (e.g., "Storgatan")
+/// * `street_number` - Street number (e.g., "10" or "10A")
+/// * `postal_code` - Swedish postal code, or empty to match any
+///
+/// # Returns
+/// Matching entries sorted by postal code, empty if validation fails or
+/// nothing matches
+///
+/// # Examples
+/// ```no_run
+/// use amp_android::matching::match_address_all;
+///
+/// let candidates = match_address_all("Storgatan", "10", "");
+/// for entry in &candidates {
+///     println!("{} {:?}", entry.adress, entry.postnummer);
+/// }
+/// ```
+#[allow(dead_code)]
+pub fn match_address_all(street: &str, street_number: &str, postal_code: &str) -> Vec<DB> {
+    if let Err(e) = validate_input(street, street_number, postal_code) {
+        eprintln!("[Matching] Validation error: {}", e);
+        return Vec::new();
+    }
+    let postal_normalized = postal_code.trim().replace(' ', "");
+    exact_candidates(
+        get_static_data().values(),
+        street,
+        street_number,
+        &postal_normalized,
+    )
+    .into_iter()
+    .cloned()
+    .collect()
+}
+/// Match many addresses at once against a shared index
+///
+/// Bulk flows such as loading the debug addresses would otherwise scan the
+/// whole database once per address. This builds a street + number index over
+/// `entries` once and resolves every query against it, so the cost is
+/// O(entries + queries) instead of O(entries × queries).
+///
+/// Each query resolves to the entry [`match_address`] picks: without a postal
+/// code the lowest postal code (then earliest start), with one the entry with
+/// the lowest restriction day under that postal code.
+///
+/// # Arguments
+/// * `queries` - `(street, street_number, postal_code)` triples
+/// * `entries` - Database to match against, e.g. [`get_parking_data`]`().values()`
+///
+/// # Returns
+/// One [`MatchResult`] per query, in the same order
+///
+/// # Examples
+/// ```no_run
+/// use amp_android::matching::{get_parking_data, match_many, MatchResult};
+///
+/// let queries = [("Storgatan", "10", "22100"), ("Lilla Torg", "1", "")];
+/// let results = match_many(&queries, get_parking_data().values());
+/// let found = results
+///     .iter()
+///     .filter(|r| matches!(r, MatchResult::Valid(_)))
+///     .count();
+/// println!("{}/{} addresses matched", found, queries.len());
+/// ```
+pub fn match_many<'a>(
+    queries: &[(&str, &str, &str)],
+    entries: impl IntoIterator<Item = &'a DB>,
+) -> Vec<MatchResult> {
+    let mut index: HashMap<(String, String), Vec<&DB>> = HashMap::new();
+    for entry in entries {
+        if let (Some(gata), Some(gatunummer)) = (&entry.gata, &entry.gatunummer) {
+            index
+                .entry((gata.to_lowercase(), gatunummer.to_lowercase()))
+                .or_default()
+                .push(entry);
+        }
+    }
+    for candidates in index.values_mut() {
+        candidates.sort_by(|a, b| {
+            a.postnummer
+                .cmp(&b.postnummer)
+                .then(a.start_time.cmp(&b.start_time))
+        });
+    }
+    let results: Vec<MatchResult> = queries
+        .iter()
+        .map(|&(street, street_number, postal_code)| {
+            if let Err(e) = validate_input(street, street_number, postal_code) {
+                eprintln!("[Matching] Validation error: {}", e);
+                return MatchResult::Invalid;
+            }
+            let key = (
+                street.trim().to_lowercase(),
+                street_number.trim().to_lowercase(),
+            );
+            let candidates = index.get(&key).map(Vec::as_slice).unwrap_or_default();
+            let postal_normalized = postal_code.trim().replace(' ', "");
+            match pick_candidate(candidates, &postal_normalized) {
+                Some(entry) => MatchResult::Valid(Box::from(entry.clone())),
+                None => MatchResult::Invalid,
+            }
+        })
+        .collect();
+    eprintln!(
+        "[Matching] Bulk matched {}/{} addresses",
+        results
+            .iter()
+            .filter(|r| matches!(r, MatchResult::Valid(_)))
+            .count(),
+        queries.len(),
+    );
+    results
+}
+/// Pick the entry [`match_address`] would return from sorted exact candidates
+///
+/// `candidates` must be ordered as [`exact_candidates`] orders them. Without a
+/// postal code the first one wins; with one, the entry with the lowest
+/// restriction day under that postal code, mirroring the day-by-day key
+/// lookup in [`get_address_data`].
+fn pick_candidate<'a>(candidates: &[&'a DB], postal_normalized: &str) -> Option<&'a DB> {
+    if postal_normalized.is_empty() {
+        return candidates.first().copied();
+    }
+    candidates
+        .iter()
+        .copied()
+        .filter(|entry| {
+            entry
+                .postnummer
+                .as_deref()
+                .is_some_and(|p| p.replace(' ', "") == postal_normalized)
+        })
+        .min_by_key(|entry| entry.dagar.first().copied().unwrap_or(entry.dag()))
+}
+/// One address in a [`match_addresses_json`] request
+///
+/// Field names follow the Kotlin side: `{"street":"Storgatan","number":"10","postal":"22100"}`.
+/// `postal` may be empty or left out.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AddressQuery {
+    pub street: String,
+    pub number: String,
+    #[serde(default)]
+    pub postal: String,
+}
+/// Result of matching one [`AddressQuery`]
+///
+/// Restriction fields are copied from the matched [`DB`] entry and are all
+/// empty when `valid` is `false`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AddressMatch {
+    pub valid: bool,
+    /// Matching stage that succeeded, as returned by `fuzzy_match_address`
+    pub match_reason: Option<String>,
+    /// Only the numeric part of the street number matched, e.g. "10A" to "10"
+    pub approximate: bool,
+    pub adress: Option<String>,
+    pub postnummer: Option<String>,
+    pub gata: Option<String>,
+    pub gatunummer: Option<String>,
+    pub info: Option<String>,
+    /// Time range in HHMM-HHMM format, see [`DB::tid`]
+    pub tid: Option<String>,
+    pub dagar: Vec<u8>,
+    pub taxa: Option<String>,
+    pub antal_platser: Option<u64>,
+    pub typ_av_parkering: Option<String>,
+}
+impl AddressMatch {
+    fn found(found: FuzzyMatch) -> Self {
+        let entry = &found.entry;
+        Self {
+            valid: true,
+            approximate: found.approximate,
+            match_reason: Some(found.reason.clone()),
+            adress: Some(entry.adress.clone()),
+            postnummer: entry.postnummer.clone(),
+            gata: entry.gata.clone(),
+            gatunummer: entry.gatunummer.clone(),
+            info: entry.info.clone(),
+            tid: Some(entry.tid()),
+            dagar: entry.dagar.clone(),
+            taxa: entry.taxa.clone(),
+            antal_platser: entry.antal_platser,
+            typ_av_parkering: entry.typ_av_parkering.clone(),
+        }
+    }
+}
+/// Match several addresses at once, exact lookup first and then fuzzy
+///
+/// Exact matches come from [`match_many`], which indexes the parking data
+/// once for the whole list; misses fall back to the same fuzzy matching the
+/// add-address form uses.
+///
+/// # Returns
+/// One [`AddressMatch`] per query, in the same order
+pub fn match_addresses(queries: &[AddressQuery]) -> Vec<AddressMatch> {
+    let tuples: Vec<(&str, &str, &str)> = queries
+        .iter()
+        .map(|q| (q.street.as_str(), q.number.as_str(), q.postal.as_str()))
+        .collect();
+    let exact = match_many(&tuples, get_parking_data().values());
+    queries
+        .iter()
+        .zip(exact)
+        .map(|(query, exact)| {
+            crate::ui::fuzzy_match_address(exact, &query.street, &query.number, &query.postal)
+                .map(AddressMatch::found)
+                .unwrap_or_default()
+        })
+        .collect()
+}
+/// [`match_addresses`] over a JSON array of [`AddressQuery`]
+///
+/// # Returns
+/// JSON array of [`AddressMatch`], or an error if the input isn't a valid
+/// query array
+///
+/// # Examples
+/// ```no_run
+/// use amp_android::matching::match_addresses_json;
+///
+/// let json = match_addresses_json(r#"[{"street":"Storgatan","number":"10","postal":"22100"}]"#)?;
+/// println!("{}", json);
+/// # Ok::<(), String>(())
+/// ```
+#[allow(dead_code)]
+pub fn match_addresses_json(json_array: &str) -> Result<String, String> {
+    let queries: Vec<AddressQuery> = serde_json::from_str(json_array)
+        .map_err(|e| format!("Invalid address query array: {}", e))?;
+    let results = match_addresses(&queries);
+    eprintln!(
+        "[Matching] Batch search matched {}/{} addresses",
+        results.iter().filter(|r| r.valid).count(),
+        results.len(),
+    );
+    serde_json::to_string(&results).map_err(|e| format!("Failed to serialize matches: {}", e))
+}
+#[cfg(target_os = "android")]
+use jni::JNIEnv;
+#[cfg(target_os = "android")]
+use jni::objects::{JClass, JString};
+#[cfg(target_os = "android")]
+use jni::sys::jstring;
+/// JNI: Called by SearchBridge.rustSearchAddresses(jsonArray)
+///
+/// Runs [`match_addresses_json`] so the Kotlin side can re-validate a whole
+/// saved list in one call after a data update. Returns `[]` if the input
+/// can't be parsed.
+#[cfg(target_os = "android")]
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_se_malmo_skaggbyran_amp_SearchBridge_rustSearchAddresses<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    json_array: JString<'local>,
+) -> jstring {
+    let input: String = match env.get_string(&json_array) {
+        Ok(s) => s.into(),
+        Err(e) => {
+            eprintln!("[Matching JNI] Failed to get string argument: {:?}", e);
+            String::new()
+        }
+    };
+    let json = match_addresses_json(&input).unwrap_or_else(|e| {
+        eprintln!("[Matching JNI] {}", e);
+        "[]".to_string()
+    });
+    env.new_string(&json)
+        .expect("Failed to create JSON output string")
+        .into_raw()
+}
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -317,4 +675,124 @@ mod tests {
         let err = ValidationError::InvalidPostalCodeFormat("1234".to_string());
         assert!(format!("{}", err).contains("1234"));
     }
+    fn entry(postnummer: &str, dag: u8) -> DB {
+        DB::from_dag_tid(
+            Some(postnummer.to_string()),
+            "Gränsgatan 5".to_string(),
+            Some("Gränsgatan".to_string()),
+            Some("5".to_string()),
+            Some("Städning".to_string()),
+            dag,
+            "0800-1200",
+            None,
+            None,
+            None,
+            2024,
+            1,
+        )
+        .unwrap()
+    }
+    #[test]
+    fn test_exact_candidates_cross_boundary_street() {
+        let entries = [entry("21439", 3), entry("214 38", 10), entry("21438", 2)];
+        let all = exact_candidates(entries.iter(), "gränsgatan", "5", "");
+        let postal: Vec<_> = all.iter().map(|e| e.postnummer.clone().unwrap()).collect();
+        assert_eq!(postal, vec!["214 38", "21438", "21439"]);
+        let narrowed = exact_candidates(entries.iter(), "Gränsgatan", "5", "21439");
+        assert_eq!(narrowed.len(), 1);
+        assert_eq!(narrowed[0].postnummer.as_deref(), Some("21439"));
+        let spaced = exact_candidates(entries.iter(), "Gränsgatan", "5", "21438");
+        assert_eq!(spaced.len(), 2);
+    }
+    #[test]
+    fn test_match_many_agrees_with_single_lookups() {
+        let mut other = entry("21120", 20);
+        other.adress = "Storgatan 10".to_string();
+        other.gata = Some("Storgatan".to_string());
+        other.gatunummer = Some("10".to_string());
+        let entries = [
+            entry("21439", 3),
+            entry("214 38", 10),
+            entry("21438", 2),
+            entry("21439", 1),
+            other,
+        ];
+        let queries = [
+            ("Gränsgatan", "5", ""),
+            ("gränsgatan", "5", "21439"),
+            ("Gränsgatan", "5", "214 38"),
+            (" STORGATAN ", "10", "21120"),
+            ("Storgatan", "10", "21100"),
+            ("Gränsgatan", "7", ""),
+            ("", "5", "21439"),
+            ("Gränsgatan", "5", "1234"),
+        ];
+        let results = match_many(&queries, entries.iter());
+        assert_eq!(results.len(), queries.len());
+        for (&(street, number, postal), result) in queries.iter().zip(&results) {
+            let expected = if validate_input(street, number, postal).is_ok() {
+                let postal_normalized = postal.trim().replace(' ', "");
+                let candidates =
+                    exact_candidates(entries.iter(), street, number, &postal_normalized);
+                pick_candidate(&candidates, &postal_normalized)
+            } else {
+                None
+            };
+            let got = match result {
+                MatchResult::Valid(db) => Some(db.as_ref()),
+                MatchResult::Invalid => None,
+            };
+            assert_eq!(got, expected, "query {:?}", (street, number, postal));
+        }
+        let day = |i: usize| match &results[i] {
+            MatchResult::Valid(db) => (db.postnummer.clone().unwrap(), db.dag()),
+            MatchResult::Invalid => panic!("query {} should match", i),
+        };
+        assert_eq!(day(0), ("214 38".to_string(), 10));
+        assert_eq!(day(1), ("21439".to_string(), 1));
+        assert_eq!(day(2), ("21438".to_string(), 2));
+        assert_eq!(day(3), ("21120".to_string(), 20));
+        assert!(
+            results[4..]
+                .iter()
+                .all(|r| matches!(r, MatchResult::Invalid))
+        );
+    }
+    #[test]
+    fn test_exact_candidates_no_match() {
+        let entries = [entry("21438", 2)];
+        assert!(exact_candidates(entries.iter(), "Gränsgatan", "7", "").is_empty());
+        assert!(exact_candidates(entries.iter(), "Gränsgatan", "5", "21100").is_empty());
+    }
+    #[test]
+    fn test_match_addresses_json_round_trip() {
+        let queries = vec![
+            AddressQuery {
+                street: "Storgatan".to_string(),
+                number: "10".to_string(),
+                postal: "22100".to_string(),
+            },
+            AddressQuery {
+                street: String::new(),
+                number: "1".to_string(),
+                postal: String::new(),
+            },
+        ];
+        let input = serde_json::to_string(&queries).unwrap();
+        let parsed: Vec<AddressQuery> = serde_json::from_str(&input).unwrap();
+        assert_eq!(parsed, queries);
+        let output = match_addresses_json(&input).unwrap();
+        let results: Vec<AddressMatch> = serde_json::from_str(&output).unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[1], AddressMatch::default());
+        for result in &results {
+            assert_eq!(result.valid, result.adress.is_some());
+            assert_eq!(result.valid, result.match_reason.is_some());
+        }
+        let without_postal: Vec<AddressQuery> =
+            serde_json::from_str(r#"[{"street":"Storgatan","number":"10"}]"#).unwrap();
+        assert_eq!(without_postal[0].postal, "");
+        assert!(match_addresses_json("not json").is_err());
+        assert_eq!(match_addresses_json("[]").unwrap(), "[]");
+    }
 }
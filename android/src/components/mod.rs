@@ -9,11 +9,13 @@
 //! - [`storage`]: Persistent data storage using Parquet files
 //! - [`static_data`]: Embedded parking restriction database
 //! - [`settings`]: User preferences and app configuration
+//! - [`assets`]: Build-time checksum verification of embedded data
 //!
 //! ## Address Processing
 //! - [`matching`]: Address validation and parking lookup
 //! - [`address_utils`]: Address string manipulation utilities
 //! - [`validity`]: Date-dependent restriction validity checking
+//! - [`stats`]: Totals over saved addresses, such as tracked parking spaces
 //!
 //! ## Time Management
 //! - [`countdown`]: Real-time countdown to parking expiry
@@ -90,6 +92,7 @@
 //! - Code reuse across platforms
 //! - Clear dependency boundaries
 pub mod address_utils;
+pub mod assets;
 pub mod countdown;
 pub mod debug;
 pub mod dormant;
@@ -99,6 +102,7 @@ pub mod matching;
 pub mod notifications;
 pub mod settings;
 pub mod static_data;
+pub mod stats;
 pub mod storage;
 pub mod transitions;
 pub mod translations;
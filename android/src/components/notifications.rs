@@ -8,6 +8,36 @@
 //! - **amp_six_hours**: High priority, sound + vibration (6-hour warnings)
 //! - **amp_one_day**: Low priority, silent (1-day reminders)
 //!
+//! # Precise Start Reminders
+//! Bucket transitions only have hour-level granularity. For the moment a
+//! restriction begins, [`precise_reminder_at`] computes the exact start from
+//! `dag` + `tid` in Swedish time and [`notify_starting_soon`] fires on the
+//! active channel [`PRECISE_REMINDER_LEAD_MINUTES`] before it.
+//!
+//! # Scheduling
+//! [`schedule_for`] turns an address's current bucket into a
+//! [`ScheduledNotification`]: the exact instant it enters the next bucket
+//! that notifies, with the text to show. Android can register that with
+//! AlarmManager rather than waiting for the app to be foregrounded and
+//! notice the transition.
+//!
+//! # Batches and Opening Panels
+//! [`notify_batch`] sends a [`BatchedNotification`]: a single address gets
+//! the usual per-address text, several get one summary such as "3 adresser
+//! behöver flyttas inom 6 timmar". Bucket notifications carry the bucket
+//! name, so tapping one opens the app with that panel expanded: Kotlin hands
+//! the name back through `DormantBridge.openPanel` and the UI picks it up
+//! with [`take_requested_panel`].
+//!
+//! # Quiet Hours
+//! A bucket notification due inside [`NotificationSettings::quiet_hours`] is
+//! held until the window ends and then sent only to the addresses still in
+//! that bucket; one that has moved on (e.g. the 6-hour warning for cleaning
+//! that already started) is dropped, since the newer bucket notifies on its
+//! own. Call [`send_deferred`] periodically to release them. Precise start
+//! reminders are simply skipped while quiet and fire on the next check after
+//! the window if the start is still ahead.
+//!
 //! # Examples
 //! ```no_run
 //! use amp_android::components::notifications::{initialize_notification_channels, notify_active};
@@ -28,12 +58,46 @@
 //! };
 //! notify_active(&address);
 //! ```
-use crate::components::settings::load_settings;
+use crate::components::countdown::TimeBucket;
+use crate::components::settings::{NotificationSettings, load_settings};
+use crate::components::transitions::{BatchedNotification, bucket_to_string};
 use crate::ui::StoredAddress;
+use amp_core::buckets::{self, BucketSettings};
+use chrono::{DateTime, Duration, Utc};
+use std::collections::HashMap;
+use std::sync::Mutex;
 /// Notification channel IDs
 const CHANNEL_ACTIVE: &str = "amp_active";
 const CHANNEL_SIX_HOURS: &str = "amp_six_hours";
 const CHANNEL_ONE_DAY: &str = "amp_one_day";
+/// Default number of minutes before the exact start to send the precise reminder
+pub const PRECISE_REMINDER_LEAD_MINUTES: i64 = 5;
+/// Start instant of the last precise reminder sent per address ID
+///
+/// Keeps the 60-second in-app check from repeating a reminder within the
+/// same lead window.
+static PRECISE_SENT: Mutex<Option<HashMap<u64, DateTime<Utc>>>> = Mutex::new(None);
+/// Panel a tapped notification asked to open, until the UI takes it
+static REQUESTED_PANEL: Mutex<Option<TimeBucket>> = Mutex::new(None);
+/// Batches held back by quiet hours, with the instant they may be sent
+static DEFERRED: Mutex<Vec<(DateTime<Utc>, BatchedNotification)>> = Mutex::new(Vec::new());
+/// Addresses named in a summary before the rest are only counted
+const BATCH_NAMED_ADDRESSES: usize = 3;
+/// A notification to deliver at a fixed instant, see [`schedule_for`]
+#[allow(dead_code)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScheduledNotification {
+    /// [`StoredAddress::id`] of the address, also used as notification ID
+    pub address_id: u64,
+    /// When to notify (UTC)
+    pub at: DateTime<Utc>,
+    /// Bucket the address enters at `at`, deciding the channel
+    pub bucket: TimeBucket,
+    /// Title as [`batch_text`] gives it for the address alone
+    pub title: String,
+    /// Body as [`batch_text`] gives it for the address alone
+    pub body: String,
+}
 /// Initialize notification channels on app startup (Android 8+)
 ///
 /// Creates three notification channels with different importance levels:
@@ -93,12 +157,17 @@ pub fn notify_one_day(address: &StoredAddress) {
         );
         return;
     }
-    let title = "Städning inom 1 dygn";
-    let body = format!(
-        "Städning inom 1 dygn på {}. Planera att flytta din bil från {} {}.",
-        address.street, address.street, address.street_number,
+    let Some((title, body)) = batch_text(&TimeBucket::Within1Day, std::slice::from_ref(address))
+    else {
+        return;
+    };
+    send_notification(
+        CHANNEL_ONE_DAY,
+        &title,
+        &body,
+        address.id,
+        Some(&TimeBucket::Within1Day),
     );
-    send_notification(CHANNEL_ONE_DAY, title, &body, address.id);
 }
 /// Send notification when address enters "6 hours" panel
 ///
@@ -134,12 +203,17 @@ pub fn notify_six_hours(address: &StoredAddress) {
         );
         return;
     }
-    let title = "Städning om 6 timmar";
-    let body = format!(
-        "Städning börjar snart på {}. Du bör flytta din bil från {} {}.",
-        address.street, address.street, address.street_number,
+    let Some((title, body)) = batch_text(&TimeBucket::Within6Hours, std::slice::from_ref(address))
+    else {
+        return;
+    };
+    send_notification(
+        CHANNEL_SIX_HOURS,
+        &title,
+        &body,
+        address.id,
+        Some(&TimeBucket::Within6Hours),
     );
-    send_notification(CHANNEL_SIX_HOURS, title, &body, address.id);
 }
 /// Send notification when address enters "active now" panel
 ///
@@ -175,12 +249,395 @@ pub fn notify_active(address: &StoredAddress) {
         );
         return;
     }
-    let title = "Städning NU!";
+    let Some((title, body)) = batch_text(&TimeBucket::Now, std::slice::from_ref(address)) else {
+        return;
+    };
+    send_notification(
+        CHANNEL_ACTIVE,
+        &title,
+        &body,
+        address.id,
+        Some(&TimeBucket::Now),
+    );
+}
+/// Send the notification for a batch from [`batch_transitions`]
+///
+/// A batch posted under its address ID (batching off) goes through
+/// [`notify_one_day`], [`notify_six_hours`] or [`notify_active`] unchanged.
+/// Otherwise the batch is sent under its shared ID with [`batch_text`], so a
+/// growing batch replaces its previous summary. Inside quiet hours the batch
+/// is held for [`send_deferred`] instead.
+///
+/// # Returns
+/// `true` if a notification was sent
+///
+/// [`batch_transitions`]: crate::components::transitions::batch_transitions
+pub fn notify_batch(batch: &BatchedNotification, now: DateTime<Utc>) -> bool {
+    notify_batch_with(batch, &load_settings().notifications, now)
+}
+/// [`notify_batch`] with explicit settings
+fn notify_batch_with(
+    batch: &BatchedNotification,
+    settings: &NotificationSettings,
+    now: DateTime<Utc>,
+) -> bool {
+    let Some(channel_id) = channel_for(&batch.bucket) else {
+        return false;
+    };
+    if !bucket_enabled(settings, &batch.bucket) {
+        eprintln!(
+            "[Notifications] Skipping {:?} batch of {} (disabled in settings)",
+            batch.bucket,
+            batch.addresses.len(),
+        );
+        return false;
+    }
+    if let Some(quiet) = settings.quiet_hours
+        && quiet.contains(now)
+    {
+        let until = quiet.defer(now);
+        eprintln!(
+            "[Notifications] Quiet hours: holding {:?} batch of {} until {}",
+            batch.bucket,
+            batch.addresses.len(),
+            until,
+        );
+        defer_batch(until, batch.clone());
+        return false;
+    }
+    if let [address] = batch.addresses.as_slice()
+        && address.id == batch.notification_id
+    {
+        match batch.bucket {
+            TimeBucket::Now => notify_active(address),
+            TimeBucket::Within6Hours => notify_six_hours(address),
+            _ => notify_one_day(address),
+        }
+        return true;
+    }
+    let Some((title, body)) = batch_text(&batch.bucket, &batch.addresses) else {
+        return false;
+    };
+    send_notification(
+        channel_id,
+        &title,
+        &body,
+        batch.notification_id,
+        Some(&batch.bucket),
+    );
+    true
+}
+/// Hold `batch` until `until`, replacing a held batch with the same ID
+fn defer_batch(until: DateTime<Utc>, batch: BatchedNotification) {
+    let mut deferred = DEFERRED.lock().unwrap();
+    deferred.retain(|(_, held)| held.notification_id != batch.notification_id);
+    deferred.push((until, batch));
+}
+/// Remove and return the held batches due at `now`
+fn take_due(now: DateTime<Utc>) -> Vec<BatchedNotification> {
+    let mut deferred = DEFERRED.lock().unwrap();
+    let (due, later): (Vec<_>, Vec<_>) = deferred.drain(..).partition(|(until, _)| *until <= now);
+    *deferred = later;
+    due.into_iter().map(|(_, batch)| batch).collect()
+}
+/// Send batches held back by quiet hours whose window has ended
+///
+/// Each batch is narrowed with [`relevant_at`] first and dropped if no
+/// address is left.
+///
+/// # Returns
+/// Number of notifications sent
+pub fn send_deferred(now: DateTime<Utc>) -> usize {
+    let bounds = load_settings().notifications.bucket_settings();
+    let mut sent = 0;
+    for batch in take_due(now) {
+        match relevant_at(&batch, now, &bounds) {
+            Some(batch) => {
+                if notify_batch(&batch, now) {
+                    sent += 1;
+                }
+            }
+            None => eprintln!(
+                "[Notifications] Dropping held {:?} batch, no address is still in it",
+                batch.bucket,
+            ),
+        }
+    }
+    sent
+}
+/// The part of `batch` still worth sending at `at`
+///
+/// Keeps the addresses whose restriction is still in the batch's bucket
+/// under `bounds` and that were not snoozed in the meantime. Used when a
+/// notification was held back, so a warning is not sent after the situation
+/// it warns about has moved on.
+///
+/// # Returns
+/// `None` if no address is left
+pub(crate) fn relevant_at(
+    batch: &BatchedNotification,
+    at: DateTime<Utc>,
+    bounds: &BucketSettings,
+) -> Option<BatchedNotification> {
+    let addresses: Vec<StoredAddress> = batch
+        .addresses
+        .iter()
+        .filter(|addr| !addr.is_snoozed(at))
+        .filter(|addr| {
+            addr.matched_entry
+                .as_ref()
+                .is_some_and(|entry| buckets::bucket_for(entry, at, bounds) == batch.bucket)
+        })
+        .cloned()
+        .collect();
+    (!addresses.is_empty()).then(|| BatchedNotification {
+        addresses,
+        ..batch.clone()
+    })
+}
+/// Channel for notifications about `bucket`, `None` if it never notifies
+pub(crate) fn channel_for(bucket: &TimeBucket) -> Option<&'static str> {
+    match bucket {
+        TimeBucket::Now => Some(CHANNEL_ACTIVE),
+        TimeBucket::Within6Hours => Some(CHANNEL_SIX_HOURS),
+        TimeBucket::Within1Day => Some(CHANNEL_ONE_DAY),
+        _ => None,
+    }
+}
+/// Whether the user enabled notifications for `bucket`
+pub(crate) fn bucket_enabled(settings: &NotificationSettings, bucket: &TimeBucket) -> bool {
+    match bucket {
+        TimeBucket::Now => settings.stadning_nu,
+        TimeBucket::Within6Hours => settings.sex_timmar,
+        TimeBucket::Within1Day => settings.en_dag,
+        _ => false,
+    }
+}
+/// Title and body for addresses that entered `bucket`
+///
+/// One address reads like the per-address notifications; several get a
+/// summary naming the first [`BATCH_NAMED_ADDRESSES`]. Shared with the
+/// dormant check so both paths send identical text.
+///
+/// # Returns
+/// `None` for buckets that never notify or an empty batch
+pub(crate) fn batch_text(
+    bucket: &TimeBucket,
+    addresses: &[StoredAddress],
+) -> Option<(String, String)> {
+    if let [address] = addresses {
+        let name = format!("{} {}", address.street, address.street_number);
+        return match bucket {
+            TimeBucket::Now => Some((
+                "Städning NU!".to_string(),
+                format!(
+                    "Städning pågående på {}. Din bil på {} kan få böter!",
+                    address.street, name,
+                ),
+            )),
+            TimeBucket::Within6Hours => Some((
+                "Städning om 6 timmar".to_string(),
+                format!(
+                    "Städning börjar snart på {}. Du bör flytta din bil från {}.",
+                    address.street, name,
+                ),
+            )),
+            TimeBucket::Within1Day => Some((
+                "Städning inom 1 dygn".to_string(),
+                format!(
+                    "Städning inom 1 dygn på {}. Planera att flytta din bil från {}.",
+                    address.street, name,
+                ),
+            )),
+            _ => None,
+        };
+    }
+    let count = addresses.len();
+    let (title, body) = match bucket {
+        _ if count == 0 => return None,
+        TimeBucket::Now => (
+            format!("Städning NU på {} adresser", count),
+            format!("Städning pågår på {} adresser", count),
+        ),
+        TimeBucket::Within6Hours => (
+            format!("Städning om 6 timmar på {} adresser", count),
+            format!("{} adresser behöver flyttas inom 6 timmar", count),
+        ),
+        TimeBucket::Within1Day => (
+            format!("Städning inom 1 dygn på {} adresser", count),
+            format!("{} adresser behöver flyttas inom 1 dygn", count),
+        ),
+        _ => return None,
+    };
+    let mut names: Vec<String> = addresses
+        .iter()
+        .take(BATCH_NAMED_ADDRESSES)
+        .map(|addr| format!("{} {}", addr.street, addr.street_number))
+        .collect();
+    if count > BATCH_NAMED_ADDRESSES {
+        names.push(format!("och {} till", count - BATCH_NAMED_ADDRESSES));
+    }
+    Some((title, format!("{}: {}.", body, names.join(", "))))
+}
+/// Remember that a tapped notification asked to open `bucket`'s panel
+#[allow(dead_code)]
+pub fn request_panel(bucket: TimeBucket) {
+    eprintln!("[Notifications] Panel requested: {:?}", bucket);
+    *REQUESTED_PANEL.lock().unwrap() = Some(bucket);
+}
+/// Take the panel requested by a tapped notification, if any
+///
+/// Polled by the UI; each request is returned once.
+pub fn take_requested_panel() -> Option<TimeBucket> {
+    REQUESTED_PANEL.lock().unwrap().take()
+}
+/// Schedule the next bucket notification for an address in `bucket`
+///
+/// [`schedule_at`] for the current time.
+#[allow(dead_code)]
+pub fn schedule_for(
+    addr: &StoredAddress,
+    bucket: TimeBucket,
+    settings: &NotificationSettings,
+) -> Option<ScheduledNotification> {
+    schedule_at(addr, bucket, Utc::now(), settings)
+}
+/// Schedule the next bucket notification for an address in `bucket` at `now`
+///
+/// The next notifying bucket follows from the current one:
+/// - [`TimeBucket::Within1Day`] → [`TimeBucket::Within6Hours`], 6 hours before the start
+/// - [`TimeBucket::Within6Hours`] → [`TimeBucket::Now`], at the start
+/// - Any other bucket with an upcoming start → [`TimeBucket::Within1Day`],
+///   a day before it. For [`TimeBucket::Now`] that is the next occurrence.
+///
+/// The offsets are the bounds from [`NotificationSettings::bucket_settings`],
+/// so the alarm fires exactly when the transition check would move the
+/// address under the user's lead time. Whether the bucket is enabled is left
+/// to the sender, as for immediate notifications.
+///
+/// An address snoozed at `now` gets no alarm before its snooze ends; the
+/// next one is scheduled from the snooze end instead.
+///
+/// # Returns
+/// `None` for [`TimeBucket::Invalid`] and addresses without a matched
+/// restriction
+#[allow(dead_code)]
+pub fn schedule_at(
+    addr: &StoredAddress,
+    bucket: TimeBucket,
+    now: DateTime<Utc>,
+    settings: &NotificationSettings,
+) -> Option<ScheduledNotification> {
+    let bounds = settings.bucket_settings();
+    let (next, lead) = match bucket {
+        TimeBucket::Invalid => return None,
+        TimeBucket::Within1Day => (TimeBucket::Within6Hours, bounds.within_hours),
+        TimeBucket::Within6Hours => (TimeBucket::Now, Duration::zero()),
+        TimeBucket::Now | TimeBucket::Within1Month | TimeBucket::MoreThan1Month => {
+            (TimeBucket::Within1Day, bounds.within_day)
+        }
+    };
+    let entry = addr.matched_entry.as_ref()?;
+    let mut start = entry.next_start_after(now)?;
+    if let Some(until) = addr.snoozed_until.filter(|_| addr.is_snoozed(now)) {
+        let until = DateTime::from_timestamp(until, 0)?;
+        while start - lead < until {
+            start = entry.next_start_after(start)?;
+        }
+    }
+    let (title, body) = batch_text(&next, std::slice::from_ref(addr))?;
+    Some(ScheduledNotification {
+        address_id: addr.id,
+        at: start - lead,
+        bucket: next,
+        title,
+        body,
+    })
+}
+/// Compute when the precise start reminder for an address should fire
+///
+/// Asks the matched entry for its next exact start after `now`
+/// ([`DB::next_start_after`]), so DST transitions, short months, holidays
+/// and restrictions on several days are handled in one place.
+///
+/// [`DB::next_start_after`]: amp_core::structs::DB::next_start_after
+///
+/// # Arguments
+/// * `address` - Address with a matched restriction
+/// * `lead` - How long before the start the reminder should fire
+/// * `now` - Reference instant
+///
+/// # Returns
+/// `Some((reminder_at, start))`, or `None` for addresses without a match
+pub fn precise_reminder_at(
+    address: &StoredAddress,
+    lead: Duration,
+    now: DateTime<Utc>,
+) -> Option<(DateTime<Utc>, DateTime<Utc>)> {
+    let next_start = address.matched_entry.as_ref()?.next_start_after(now)?;
+    Some((next_start - lead, next_start))
+}
+/// Send the precise start reminder if its lead window has been reached
+///
+/// Fires once per restriction start: when `now` lies within `lead` of the
+/// next start and no reminder has been sent for that start yet. Respects
+/// the `stadning_nu` setting like [`notify_active`]. Inside quiet hours it
+/// is skipped without being marked as sent, so a later check can still fire
+/// it if the start is ahead.
+///
+/// # Arguments
+/// * `address` - Address to check
+/// * `lead` - How long before the start the reminder should fire
+/// * `now` - Reference instant
+///
+/// # Returns
+/// `true` if a notification was sent
+pub fn notify_starting_soon(address: &StoredAddress, lead: Duration, now: DateTime<Utc>) -> bool {
+    let Some((reminder_at, start)) = precise_reminder_at(address, lead, now) else {
+        return false;
+    };
+    if now < reminder_at || address.is_snoozed(now) {
+        return false;
+    }
+    let mut guard = PRECISE_SENT.lock().unwrap();
+    let sent = guard.get_or_insert_with(HashMap::new);
+    if sent.get(&address.id) == Some(&start) {
+        return false;
+    }
+    let settings = load_settings().notifications;
+    if !settings.stadning_nu {
+        eprintln!(
+            "[Notifications] Skipping start reminder for {} {} (disabled in settings)",
+            address.street, address.street_number,
+        );
+        return false;
+    }
+    if settings
+        .quiet_hours
+        .is_some_and(|quiet| quiet.contains(now))
+    {
+        return false;
+    }
+    sent.insert(address.id, start);
+    let (title, body) = starting_soon_text(address, start - now);
+    send_notification(CHANNEL_ACTIVE, &title, &body, address.id, None);
+    true
+}
+/// Title and body for a precise start reminder
+///
+/// Shared with the dormant check so both paths send identical text.
+pub(crate) fn starting_soon_text(address: &StoredAddress, remaining: Duration) -> (String, String) {
+    let minutes = (remaining.num_seconds() + 59) / 60;
+    let title = if minutes <= 0 {
+        "Städning börjar nu".to_string()
+    } else {
+        format!("Städning om {} min", minutes)
+    };
     let body = format!(
-        "Städning pågående på {}. Din bil på {} {} kan få böter!",
+        "Städningen på {} börjar snart. Flytta din bil från {} {}.",
         address.street, address.street, address.street_number,
     );
-    send_notification(CHANNEL_ACTIVE, title, &body, address.id);
+    (title, body)
 }
 /// Internal: Send notification via android_bridge to JNI
 ///
@@ -192,12 +649,47 @@ pub fn notify_active(address: &StoredAddress) {
 /// * `title` - Notification title text
 /// * `body` - Notification body text
 /// * `notification_id` - Unique ID for this notification (typically address.id)
-fn send_notification(channel_id: &str, title: &str, body: &str, notification_id: usize) {
+/// * `panel` - Bucket whose panel tapping the notification should open
+fn send_notification(
+    channel_id: &str,
+    title: &str,
+    body: &str,
+    notification_id: u64,
+    panel: Option<&TimeBucket>,
+) {
     eprintln!(
         "[Notifications] Sending: channel={}, title={}, id={}",
         channel_id, title, notification_id,
     );
-    crate::android_bridge::send_notification_jni(channel_id, notification_id as i32, title, body);
+    crate::android_bridge::send_notification_jni(
+        channel_id,
+        notification_id as i32,
+        title,
+        body,
+        panel.map(bucket_to_string),
+    );
+}
+#[cfg(target_os = "android")]
+use jni::JNIEnv;
+#[cfg(target_os = "android")]
+use jni::objects::{JClass, JString};
+/// JNI: Called by DormantBridge.openPanel(panel)
+///
+/// MainActivity forwards the panel name from a tapped notification's
+/// intent; it is stored for [`take_requested_panel`].
+#[cfg(target_os = "android")]
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_se_malmo_skaggbyran_amp_DormantBridge_openPanel<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    panel: JString<'local>,
+) {
+    match env.get_string(&panel) {
+        Ok(name) => request_panel(crate::components::transitions::bucket_from_string(
+            &String::from(name),
+        )),
+        Err(e) => eprintln!("[Notifications JNI] Failed to get panel string: {:?}", e),
+    }
 }
 #[cfg(test)]
 mod tests {
@@ -223,13 +715,297 @@ mod tests {
             active: false,
             matched_entry: None,
             parking_info: None,
+            match_reason: None,
+            approximate: false,
+            invalid_reason: None,
+            snoozed_until: None,
         };
         notify_one_day(&address);
         notify_six_hours(&address);
         notify_active(&address);
     }
     #[test]
+    fn test_precise_reminder_at_uses_exact_start() {
+        use amp_core::structs::DB;
+        use chrono::TimeZone;
+        let entry = DB::from_dag_tid(
+            Some("21438".to_string()),
+            "Storgatan 10".to_string(),
+            Some("Storgatan".to_string()),
+            Some("10".to_string()),
+            None,
+            15,
+            "0800-1200",
+            None,
+            None,
+            None,
+            2024,
+            1,
+        )
+        .unwrap();
+        let address = StoredAddress {
+            id: 7,
+            street: "Storgatan".to_string(),
+            street_number: "10".to_string(),
+            postal_code: "21438".to_string(),
+            valid: true,
+            active: true,
+            matched_entry: Some(entry),
+            parking_info: None,
+            match_reason: None,
+            approximate: false,
+            invalid_reason: None,
+            snoozed_until: None,
+        };
+        let now = Utc.with_ymd_and_hms(2024, 6, 1, 12, 0, 0).unwrap();
+        let lead = Duration::minutes(PRECISE_REMINDER_LEAD_MINUTES);
+        let (reminder_at, start) = precise_reminder_at(&address, lead, now).unwrap();
+        // June is CEST, so 08:00 local is 06:00 UTC
+        assert_eq!(start, Utc.with_ymd_and_hms(2024, 6, 15, 6, 0, 0).unwrap());
+        assert_eq!(start - reminder_at, lead);
+        let (title, _) = starting_soon_text(&address, Duration::seconds(4 * 60 + 30));
+        assert_eq!(title, "Städning om 5 min");
+    }
+    #[test]
+    fn test_schedule_within_one_day_fires_six_hours_before() {
+        use chrono::TimeZone;
+        let address = StoredAddress {
+            matched_entry: amp_core::structs::DB::from_dag_tid(
+                Some("21438".to_string()),
+                "Storgatan 4".to_string(),
+                Some("Storgatan".to_string()),
+                Some("4".to_string()),
+                Some("Städning".to_string()),
+                15,
+                "0800-1200",
+                None,
+                None,
+                None,
+                2024,
+                6,
+            ),
+            ..batch_address(4, "Storgatan")
+        };
+        // 08:00 CEST on 15 June is 06:00 UTC, 18 hours after `now`
+        let now = Utc.with_ymd_and_hms(2024, 6, 14, 12, 0, 0).unwrap();
+        let entry = address.matched_entry.as_ref().unwrap();
+        let settings = NotificationSettings::default();
+        assert_eq!(
+            buckets::bucket_for(entry, now, &settings.bucket_settings()),
+            TimeBucket::Within1Day
+        );
+        let scheduled = schedule_at(&address, TimeBucket::Within1Day, now, &settings).unwrap();
+        assert_eq!(scheduled.address_id, 4);
+        assert_eq!(
+            scheduled.at,
+            Utc.with_ymd_and_hms(2024, 6, 15, 0, 0, 0).unwrap()
+        );
+        assert_eq!(scheduled.bucket, TimeBucket::Within6Hours);
+        assert_eq!(scheduled.title, "Städning om 6 timmar");
+        assert!(scheduled.body.contains("Storgatan 4"));
+        let at_start = schedule_at(&address, TimeBucket::Within6Hours, now, &settings).unwrap();
+        assert_eq!(
+            at_start.at,
+            Utc.with_ymd_and_hms(2024, 6, 15, 6, 0, 0).unwrap()
+        );
+        assert_eq!(at_start.bucket, TimeBucket::Now);
+    }
+    #[test]
+    fn test_schedule_uses_configured_lead_time() {
+        use chrono::TimeZone;
+        let address = cleaning_at(4, "0800-1200");
+        let now = Utc.with_ymd_and_hms(2024, 1, 14, 12, 0, 0).unwrap();
+        let settings = NotificationSettings {
+            lead_time: Duration::hours(2),
+            ..NotificationSettings::default()
+        };
+        let scheduled = schedule_at(&address, TimeBucket::Within1Day, now, &settings).unwrap();
+        // Two hours before 08:00 CET, which is 07:00 UTC
+        assert_eq!(
+            scheduled.at,
+            Utc.with_ymd_and_hms(2024, 1, 15, 5, 0, 0).unwrap()
+        );
+        assert_eq!(scheduled.bucket, TimeBucket::Within6Hours);
+    }
+    #[test]
+    fn test_snoozed_address_is_dropped_and_scheduled_after_snooze() {
+        use chrono::TimeZone;
+        let now = Utc.with_ymd_and_hms(2024, 1, 14, 12, 0, 0).unwrap();
+        let mut snoozed = cleaning_at(4, "0800-1200");
+        snoozed.snoozed_until = Some(
+            Utc.with_ymd_and_hms(2024, 2, 15, 7, 0, 0)
+                .unwrap()
+                .timestamp(),
+        );
+        let batch = BatchedNotification {
+            bucket: TimeBucket::Within1Day,
+            addresses: vec![snoozed.clone(), cleaning_at(5, "0800-1200")],
+            notification_id: 903,
+        };
+        let kept = relevant_at(&batch, now, &BucketSettings::default()).unwrap();
+        let ids: Vec<u64> = kept.addresses.iter().map(|addr| addr.id).collect();
+        assert_eq!(ids, vec![5]);
+        let scheduled = schedule_at(
+            &snoozed,
+            TimeBucket::Within1Day,
+            now,
+            &NotificationSettings::default(),
+        )
+        .unwrap();
+        // The 15 January and 15 February alarms fall inside the snooze;
+        // 08:00 CET on 15 March is 07:00 UTC, six hours later than the alarm
+        assert_eq!(
+            scheduled.at,
+            Utc.with_ymd_and_hms(2024, 3, 15, 1, 0, 0).unwrap()
+        );
+    }
+    #[test]
+    fn test_schedule_invalid_or_unmatched_is_none() {
+        use chrono::TimeZone;
+        let now = Utc.with_ymd_and_hms(2024, 6, 14, 12, 0, 0).unwrap();
+        assert_eq!(
+            schedule_at(
+                &cleaning_at(4, "0800-1200"),
+                TimeBucket::Invalid,
+                now,
+                &NotificationSettings::default()
+            ),
+            None
+        );
+        assert_eq!(
+            schedule_at(
+                &batch_address(5, "Storgatan"),
+                TimeBucket::Within1Day,
+                now,
+                &NotificationSettings::default()
+            ),
+            None
+        );
+    }
+    fn batch_address(id: u64, street: &str) -> StoredAddress {
+        StoredAddress {
+            id,
+            street: street.to_string(),
+            street_number: id.to_string(),
+            postal_code: "21438".to_string(),
+            valid: true,
+            active: true,
+            matched_entry: None,
+            parking_info: None,
+            match_reason: None,
+            approximate: false,
+            invalid_reason: None,
+            snoozed_until: None,
+        }
+    }
+    #[test]
+    fn test_batch_text_single_and_summary() {
+        let one = [batch_address(1, "Storgatan")];
+        let (title, body) = batch_text(&TimeBucket::Within6Hours, &one).unwrap();
+        assert_eq!(title, "Städning om 6 timmar");
+        assert!(body.contains("Storgatan 1"));
+        let many: Vec<_> = (1..=5).map(|id| batch_address(id, "Lillgatan")).collect();
+        let (title, body) = batch_text(&TimeBucket::Within6Hours, &many).unwrap();
+        assert_eq!(title, "Städning om 6 timmar på 5 adresser");
+        assert_eq!(
+            body,
+            "5 adresser behöver flyttas inom 6 timmar: \
+             Lillgatan 1, Lillgatan 2, Lillgatan 3, och 2 till."
+        );
+        assert!(batch_text(&TimeBucket::Within1Month, &many).is_none());
+        assert!(batch_text(&TimeBucket::Now, &[]).is_none());
+    }
+    fn cleaning_at(id: u64, tid: &str) -> StoredAddress {
+        StoredAddress {
+            matched_entry: amp_core::structs::DB::from_dag_tid(
+                Some("21438".to_string()),
+                format!("Storgatan {}", id),
+                Some("Storgatan".to_string()),
+                Some(id.to_string()),
+                Some("Städning".to_string()),
+                15,
+                tid,
+                None,
+                None,
+                None,
+                2024,
+                1,
+            ),
+            ..batch_address(id, "Storgatan")
+        }
+    }
+    fn night_batch(id: u64) -> BatchedNotification {
+        BatchedNotification {
+            bucket: TimeBucket::Within6Hours,
+            addresses: vec![cleaning_at(1, "0800-1200"), cleaning_at(2, "0600-0700")],
+            notification_id: id,
+        }
+    }
+    fn quiet_settings() -> NotificationSettings {
+        NotificationSettings {
+            quiet_hours: amp_core::structs::QuietHours::from_minutes(22 * 60, 7 * 60),
+            ..NotificationSettings::default()
+        }
+    }
+    #[test]
+    #[serial_test::serial]
+    fn test_notify_batch_inside_quiet_hours_is_held() {
+        use chrono::TimeZone;
+        take_due(DateTime::<Utc>::MAX_UTC);
+        // 03:00 Swedish time (CET); quiet hours end 07:00 = 06:00 UTC
+        let night = Utc.with_ymd_and_hms(2024, 1, 15, 2, 0, 0).unwrap();
+        let morning = Utc.with_ymd_and_hms(2024, 1, 15, 6, 0, 0).unwrap();
+        assert!(!notify_batch_with(
+            &night_batch(900),
+            &quiet_settings(),
+            night
+        ));
+        assert!(!notify_batch_with(
+            &night_batch(900),
+            &quiet_settings(),
+            night
+        ));
+        assert!(take_due(morning - Duration::minutes(1)).is_empty());
+        let due = take_due(morning);
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].notification_id, 900);
+    }
+    #[test]
+    #[serial_test::serial]
+    fn test_notify_batch_outside_quiet_hours_is_sent() {
+        use chrono::TimeZone;
+        take_due(DateTime::<Utc>::MAX_UTC);
+        let afternoon = Utc.with_ymd_and_hms(2024, 1, 14, 14, 0, 0).unwrap();
+        assert!(notify_batch_with(
+            &night_batch(901),
+            &quiet_settings(),
+            afternoon
+        ));
+        assert!(take_due(DateTime::<Utc>::MAX_UTC).is_empty());
+    }
+    #[test]
+    fn test_relevant_at_drops_addresses_that_moved_on() {
+        use chrono::TimeZone;
+        let batch = night_batch(902);
+        let bounds = BucketSettings::default();
+        let night = Utc.with_ymd_and_hms(2024, 1, 15, 2, 0, 0).unwrap();
+        assert_eq!(relevant_at(&batch, night, &bounds), Some(batch.clone()));
+        // By 07:00 the 06:00-07:00 cleaning is over; 08:00 is still ahead
+        let morning = Utc.with_ymd_and_hms(2024, 1, 15, 6, 0, 0).unwrap();
+        let kept = relevant_at(&batch, morning, &bounds).unwrap();
+        assert_eq!(kept.addresses.len(), 1);
+        assert_eq!(kept.addresses[0].id, 1);
+        let evening = Utc.with_ymd_and_hms(2024, 1, 15, 18, 0, 0).unwrap();
+        assert_eq!(relevant_at(&batch, evening, &bounds), None);
+    }
+    #[test]
+    fn test_requested_panel_taken_once() {
+        request_panel(TimeBucket::Within1Day);
+        assert_eq!(take_requested_panel(), Some(TimeBucket::Within1Day));
+        assert_eq!(take_requested_panel(), None);
+    }
+    #[test]
     fn test_send_notification_internal() {
-        send_notification(CHANNEL_ACTIVE, "Test", "Body", 999);
+        send_notification(CHANNEL_ACTIVE, "Test", "Body", 999, None);
     }
 }
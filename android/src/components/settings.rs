@@ -4,10 +4,44 @@
 //! - Notification settings (städas nu, 6 hours, 1 day before)
 //! - Theme preference (dark/light mode)
 //! - Language selection
+//! - Panel layout (flat time buckets or grouped by street)
+//! - Per-bucket notification cooldowns
+//! - Quiet hours
 //!
 //! Settings are stored in a Parquet file (settings.parquet) for efficient binary storage.
+//!
+//! # Notification Cooldowns
+//! [`NotificationSettings::notification_cooldowns`] holds the minimum time
+//! between two notifications for the same address and bucket. They are
+//! persisted as whole minutes (`cooldown_*_minutes` columns) and default to
+//! 4 hours for "active now" and 24 hours for the 6-hour and 1-day warnings.
+//! The last-fired timestamps they are checked against live in
+//! `notification_fired.parquet`, managed by the transitions module.
+//!
+//! # Batched Notifications
+//! [`NotificationSettings::batch_window`] coalesces addresses that enter the
+//! same bucket close together into one summary notification ("3 adresser
+//! ..."). Zero, the default, keeps one notification per address; the toggle
+//! in the settings panel switches between zero and [`DEFAULT_BATCH_WINDOW_MINUTES`].
+//!
+//! # Quiet Hours
+//! [`NotificationSettings::quiet_hours`] is a daily window in Swedish local
+//! time, possibly crossing midnight, in which nothing is shown. The settings
+//! toggle switches between off and [`DEFAULT_QUIET_HOURS_START_MINUTES`] to
+//! [`DEFAULT_QUIET_HOURS_END_MINUTES`] (22:00–07:00). How held-back
+//! notifications are handled is described in the notifications module.
+//!
+//! # Previewing Changes
+//! [`preview`] counts how many saved addresses each notification type would
+//! reach under a candidate [`NotificationSettings`], without saving anything,
+//! so the UI can say "this will affect N addresses" before a toggle commits.
+use crate::components::countdown::TimeBucket;
+use crate::ui::StoredAddress;
+use amp_core::buckets::{self, BucketSettings};
 use amp_core::parquet::{build_settings_parquet, read_settings_parquet};
-use amp_core::structs::SettingsData;
+use amp_core::structs::{QuietHours, SettingsData, settings_from_json};
+use chrono::{DateTime, Duration, Utc};
+use std::collections::HashMap;
 use std::fs::File;
 use std::path::PathBuf;
 use std::sync::Mutex;
@@ -26,6 +60,31 @@ pub struct NotificationSettings {
     pub sex_timmar: bool,
     /// Notify 1 day before cleaning
     pub en_dag: bool,
+    /// Minimum time between two notifications for the same address and bucket
+    pub notification_cooldowns: HashMap<TimeBucket, Duration>,
+    /// Transitions into the same bucket within this window share one
+    /// notification; zero disables batching
+    pub batch_window: Duration,
+    /// Daily window in which notifications are held back; `None` when off
+    pub quiet_hours: Option<QuietHours>,
+    /// How far ahead of a restriction the early warning fires
+    pub lead_time: Duration,
+}
+impl NotificationSettings {
+    /// Bucket bounds with [`Self::lead_time`] as the early warning bound
+    pub fn bucket_settings(&self) -> BucketSettings {
+        BucketSettings::from_settings(&SettingsData {
+            lead_minutes: self.lead_time.num_minutes().clamp(0, u32::MAX as i64) as u32,
+            ..SettingsData::default()
+        })
+    }
+    /// Cooldown for `bucket`, or zero if none is configured
+    pub fn cooldown_for(&self, bucket: &TimeBucket) -> Duration {
+        self.notification_cooldowns
+            .get(bucket)
+            .copied()
+            .unwrap_or_else(Duration::zero)
+    }
 }
 impl Default for NotificationSettings {
     fn default() -> Self {
@@ -33,9 +92,93 @@ impl Default for NotificationSettings {
             stadning_nu: true,
             sex_timmar: true,
             en_dag: true,
+            notification_cooldowns: cooldowns_from_settings_data(&SettingsData::default()),
+            batch_window: Duration::zero(),
+            quiet_hours: None,
+            lead_time: Duration::minutes(SettingsData::default().lead_minutes as i64),
         }
     }
 }
+/// Batch window used when batching is switched on from the settings panel
+pub const DEFAULT_BATCH_WINDOW_MINUTES: i64 = 30;
+/// Quiet hours start (22:00) used when switched on from the settings panel
+pub const DEFAULT_QUIET_HOURS_START_MINUTES: u32 = 22 * 60;
+/// Quiet hours end (07:00) used when switched on from the settings panel
+pub const DEFAULT_QUIET_HOURS_END_MINUTES: u32 = 7 * 60;
+/// Build the cooldown map from the persisted per-bucket minutes
+fn cooldowns_from_settings_data(data: &SettingsData) -> HashMap<TimeBucket, Duration> {
+    HashMap::from([
+        (
+            TimeBucket::Now,
+            Duration::minutes(data.cooldown_now_minutes as i64),
+        ),
+        (
+            TimeBucket::Within6Hours,
+            Duration::minutes(data.cooldown_six_hours_minutes as i64),
+        ),
+        (
+            TimeBucket::Within1Day,
+            Duration::minutes(data.cooldown_one_day_minutes as i64),
+        ),
+    ])
+}
+/// Addresses reached by each notification type under candidate settings
+///
+/// Produced by [`preview`]. A count is zero when its notification type is
+/// disabled in the candidate settings.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PreviewSummary {
+    /// Addresses that would get "städas nu" notifications
+    pub stadning_nu: usize,
+    /// Addresses that would get 6-hour warnings
+    pub sex_timmar: usize,
+    /// Addresses that would get 1-day warnings
+    pub en_dag: usize,
+    /// Addresses that would get at least one notification type
+    pub affected: usize,
+}
+/// Preview which saved addresses `candidate` would notify about
+///
+/// Only addresses shown in panels with a matched restriction that has an
+/// upcoming (or ongoing) occurrence at `now` can trigger notifications, so
+/// only those are counted. Every cleaning passes through all three windows,
+/// which means each enabled type reaches the same set of addresses; the
+/// counts differ only by which types are switched on. Cooldowns are not
+/// considered since they delay notifications rather than prevent them.
+///
+/// Pure: nothing is read from or written to storage.
+///
+/// # Arguments
+/// * `addresses` - The user's saved addresses
+/// * `candidate` - Notification settings to evaluate, not yet saved
+/// * `now` - Reference time for deciding whether a restriction recurs
+///
+/// # Returns
+/// Per-type address counts and the number of addresses affected at all
+#[allow(dead_code)]
+pub fn preview(
+    addresses: &[StoredAddress],
+    candidate: &NotificationSettings,
+    now: DateTime<Utc>,
+) -> PreviewSummary {
+    let bucket_settings = candidate.bucket_settings();
+    let notifiable = addresses
+        .iter()
+        .filter(|addr| addr.active)
+        .filter_map(|addr| addr.matched_entry.as_ref())
+        .filter(|entry| {
+            buckets::bucket_for(entry, now, &bucket_settings) != TimeBucket::Invalid
+        })
+        .count();
+    let count_if = |enabled: bool| if enabled { notifiable } else { 0 };
+    let any_enabled = candidate.stadning_nu || candidate.sex_timmar || candidate.en_dag;
+    PreviewSummary {
+        stadning_nu: count_if(candidate.stadning_nu),
+        sex_timmar: count_if(candidate.sex_timmar),
+        en_dag: count_if(candidate.en_dag),
+        affected: count_if(any_enabled),
+    }
+}
 /// Theme preference
 #[derive(Clone, Debug, PartialEq, Default)]
 pub enum Theme {
@@ -133,6 +276,8 @@ pub struct AppSettings {
     pub theme: Theme,
     pub language: Language,
     pub autocomplete_source: AutocompleteSource,
+    /// Group saved addresses by street instead of by time bucket
+    pub group_by_street: bool,
 }
 /// Convert SettingsData from Parquet to AppSettings
 fn from_settings_data(data: SettingsData) -> AppSettings {
@@ -141,14 +286,23 @@ fn from_settings_data(data: SettingsData) -> AppSettings {
             stadning_nu: data.stadning_nu,
             sex_timmar: data.sex_timmar,
             en_dag: data.en_dag,
+            notification_cooldowns: cooldowns_from_settings_data(&data),
+            batch_window: Duration::minutes(data.notification_batch_minutes as i64),
+            quiet_hours: data.quiet_hours(),
+            lead_time: Duration::minutes(data.lead_minutes as i64),
         },
         theme: Theme::from_string(&data.theme),
         language: Language::from_string(&data.language),
         autocomplete_source: AutocompleteSource::from_string(&data.autocomplete_source),
+        group_by_street: data.group_by_street,
     }
 }
 /// Convert AppSettings to SettingsData for Parquet serialization
 fn to_settings_data(settings: &AppSettings) -> SettingsData {
+    let (quiet_start, quiet_end) = settings
+        .notifications
+        .quiet_hours
+        .map_or((0, 0), |quiet| quiet.to_minutes());
     SettingsData {
         stadning_nu: settings.notifications.stadning_nu,
         sex_timmar: settings.notifications.sex_timmar,
@@ -156,8 +310,32 @@ fn to_settings_data(settings: &AppSettings) -> SettingsData {
         theme: settings.theme.to_string(),
         language: settings.language.to_string(),
         autocomplete_source: settings.autocomplete_source.to_string(),
+        group_by_street: settings.group_by_street,
+        cooldown_now_minutes: cooldown_minutes(settings, TimeBucket::Now),
+        cooldown_six_hours_minutes: cooldown_minutes(settings, TimeBucket::Within6Hours),
+        cooldown_one_day_minutes: cooldown_minutes(settings, TimeBucket::Within1Day),
+        notification_batch_minutes: settings
+            .notifications
+            .batch_window
+            .num_minutes()
+            .clamp(0, u32::MAX as i64) as u32,
+        quiet_hours_start_minutes: quiet_start,
+        quiet_hours_end_minutes: quiet_end,
+        lead_minutes: settings
+            .notifications
+            .lead_time
+            .num_minutes()
+            .clamp(0, u32::MAX as i64) as u32,
     }
 }
+/// Cooldown for `bucket` as whole minutes for persistence
+fn cooldown_minutes(settings: &AppSettings, bucket: TimeBucket) -> u32 {
+    settings
+        .notifications
+        .cooldown_for(&bucket)
+        .num_minutes()
+        .clamp(0, u32::MAX as i64) as u32
+}
 /// Get app-specific storage directory that's writable on Android
 #[cfg(target_os = "android")]
 fn get_storage_dir() -> Result<PathBuf, String> {
@@ -283,6 +461,36 @@ pub fn save_settings(settings: &AppSettings) {
         }
     }
 }
+/// Reset settings to defaults (thread-safe)
+///
+/// Unlike [`save_settings`] this reports failure. The defaults are written to
+/// a temporary file first and renamed over settings.parquet, so the old
+/// settings stay intact if anything goes wrong.
+///
+/// # Returns
+/// - `Ok(())` if the defaults are now persisted
+/// - `Err(message)` if building or writing the file failed
+pub fn reset_settings() -> Result<(), String> {
+    let _lock = SETTINGS_LOCK.lock().unwrap();
+    let path = get_settings_path()?;
+    if let Some(parent) = path.parent()
+        && !parent.exists()
+    {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("[Settings] Failed to create directory {:?}: {}", parent, e))?;
+    }
+    let parquet_bytes = build_settings_parquet(vec![to_settings_data(&AppSettings::default())])
+        .map_err(|e| format!("[Settings] Failed to build parquet: {}", e))?;
+    let staged = path.with_extension("parquet.tmp");
+    std::fs::write(&staged, parquet_bytes)
+        .map_err(|e| format!("[Settings] Failed to write file {:?}: {}", staged, e))?;
+    std::fs::rename(&staged, &path).map_err(|e| {
+        let _ = std::fs::remove_file(&staged);
+        format!("[Settings] Failed to replace {:?}: {}", path, e)
+    })?;
+    eprintln!("[Settings] Reset to defaults at {:?}", path);
+    Ok(())
+}
 /// Get the path to the settings.parquet storage file
 ///
 /// Returns the absolute path to the user's settings data file.
@@ -298,10 +506,12 @@ pub fn get_settings_storage_path() -> Result<String, String> {
         .ok_or_else(|| "Path contains invalid UTF-8".to_string())?
         .to_string())
 }
-/// Import settings from an external parquet file
+/// Import settings from an external parquet or JSON file
 ///
-/// Validates the file schema by attempting to read it with `read_settings_parquet()`,
-/// then copies it over the current settings.parquet.
+/// Parquet files are validated by attempting to read them with
+/// `read_settings_parquet()` and then copied over the current settings.parquet.
+/// Anything that is not valid parquet is parsed as JSON with
+/// `settings_from_json()` and written back out as parquet.
 ///
 /// # Arguments
 /// * `temp_path` - Path to the imported temp file (from SAF file picker)
@@ -316,11 +526,20 @@ pub fn import_settings_from_path(temp_path: &str) -> Result<(), String> {
         return Err("Importfilen hittades inte".to_string());
     }
     let file = File::open(temp).map_err(|e| format!("Kunde inte öppna filen: {}", e))?;
-    read_settings_parquet(file)
-        .map_err(|_| "Ogiltig fil: schemat matchar inte inställningsdatabasen".to_string())?;
     let settings_path = get_settings_path()?;
-    std::fs::copy(temp, &settings_path)
-        .map_err(|e| format!("Kunde inte kopiera importfil: {}", e))?;
+    if read_settings_parquet(file).is_ok() {
+        std::fs::copy(temp, &settings_path)
+            .map_err(|e| format!("Kunde inte kopiera importfil: {}", e))?;
+    } else {
+        let json = std::fs::read_to_string(temp)
+            .map_err(|_| "Ogiltig fil: varken parquet eller JSON".to_string())?;
+        let data = settings_from_json(&json)
+            .map_err(|e| format!("Ogiltig fil: JSON matchar inte inställningarna: {}", e))?;
+        let buffer = build_settings_parquet(vec![data])
+            .map_err(|e| format!("Kunde inte bygga parquet: {}", e))?;
+        std::fs::write(&settings_path, buffer)
+            .map_err(|e| format!("Kunde inte skriva inställningar: {}", e))?;
+    }
     eprintln!(
         "[Settings] Successfully imported settings from {}",
         temp_path
@@ -338,6 +557,84 @@ mod tests {
         assert!(settings.notifications.stadning_nu);
         assert!(settings.notifications.sex_timmar);
         assert!(settings.notifications.en_dag);
+        assert_eq!(
+            settings
+                .notifications
+                .cooldown_for(&TimeBucket::Within6Hours),
+            Duration::hours(24)
+        );
+        assert_eq!(
+            settings.notifications.cooldown_for(&TimeBucket::Invalid),
+            Duration::zero()
+        );
+        assert_eq!(settings.notifications.batch_window, Duration::zero());
+        assert_eq!(settings.notifications.quiet_hours, None);
+    }
+    fn preview_address(id: u64, dag: u8, active: bool, matched: bool) -> StoredAddress {
+        let db = amp_core::structs::DB::from_dag_tid(
+            None,
+            format!("Testgatan {}", id),
+            None,
+            None,
+            None,
+            dag,
+            "0800-1200",
+            None,
+            None,
+            None,
+            2024,
+            1,
+        )
+        .unwrap();
+        StoredAddress {
+            id,
+            street: "Testgatan".to_string(),
+            street_number: id.to_string(),
+            postal_code: "21438".to_string(),
+            valid: matched,
+            active,
+            matched_entry: matched.then_some(db),
+            parking_info: None,
+            match_reason: None,
+            approximate: false,
+            invalid_reason: None,
+            snoozed_until: None,
+        }
+    }
+    #[test]
+    fn test_preview_counts_only_notifiable_addresses() {
+        let now = DateTime::parse_from_rfc3339("2024-03-05T12:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let addresses = vec![
+            preview_address(1, 15, true, true),
+            preview_address(2, 20, true, true),
+            preview_address(3, 15, false, true),
+            preview_address(4, 15, true, false),
+            // April has no 31st and March 31 is Easter Sunday, but May 31 is ahead
+            preview_address(5, 31, true, true),
+        ];
+        let mut candidate = NotificationSettings::default();
+        let summary = preview(&addresses, &candidate, now);
+        assert_eq!(
+            summary,
+            PreviewSummary {
+                stadning_nu: 3,
+                sex_timmar: 3,
+                en_dag: 3,
+                affected: 3,
+            }
+        );
+        candidate.sex_timmar = false;
+        candidate.stadning_nu = false;
+        let summary = preview(&addresses, &candidate, now);
+        assert_eq!((summary.sex_timmar, summary.en_dag), (0, 3));
+        assert_eq!(summary.affected, 3);
+        candidate.en_dag = false;
+        assert_eq!(
+            preview(&addresses, &candidate, now),
+            PreviewSummary::default()
+        );
     }
     #[test]
     fn test_theme_display() {
@@ -358,10 +655,22 @@ mod tests {
                 stadning_nu: false,
                 sex_timmar: true,
                 en_dag: true,
+                notification_cooldowns: HashMap::from([
+                    (TimeBucket::Now, Duration::minutes(30)),
+                    (TimeBucket::Within6Hours, Duration::hours(12)),
+                    (TimeBucket::Within1Day, Duration::zero()),
+                ]),
+                batch_window: Duration::minutes(DEFAULT_BATCH_WINDOW_MINUTES),
+                quiet_hours: QuietHours::from_minutes(
+                    DEFAULT_QUIET_HOURS_START_MINUTES,
+                    DEFAULT_QUIET_HOURS_END_MINUTES,
+                ),
+                lead_time: Duration::minutes(120),
             },
             theme: Theme::Dark,
             language: Language::English,
             autocomplete_source: AutocompleteSource::MiljoOnly,
+            group_by_street: true,
         };
         let settings_data = to_settings_data(&original);
         let restored = from_settings_data(settings_data);
@@ -15,7 +15,7 @@
 //!
 //! # Examples
 //! ```no_run
-//! use amp_android::static_data::{get_static_data, get_address_data};
+//! use amp_android::static_data::{addresses_sharing_segment, get_address_data, get_static_data};
 //!
 //! // Get all parking data
 //! let data = get_static_data();
@@ -24,13 +24,25 @@
 //! // Look up specific address
 //! if let Some(entry) = get_address_data("Storgatan", "10", "22100") {
 //!     println!("Found restriction: {}", entry.adress);
+//!     // Neighbours cleaned on the same segment
+//!     for other in addresses_sharing_segment(entry, data) {
+//!         println!("  also: {}", other);
+//!     }
 //! }
 //! ```
+use crate::components::assets::verify_manifest;
 use crate::components::settings::AutocompleteSource;
 use crate::ui::ParkingInfo;
+pub(crate) use amp_core::buckets::{determine_occurrence, determine_year_month};
 use amp_core::structs::{AdressClean, DB};
 use bytes::Bytes;
-use chrono::{Datelike, NaiveDate};
+use chrono::Datelike;
+#[cfg(target_os = "android")]
+use jni::JNIEnv;
+#[cfg(target_os = "android")]
+use jni::objects::{JClass, JString};
+#[cfg(target_os = "android")]
+use jni::sys::jstring;
 use rust_decimal::Decimal;
 use std::collections::{HashMap, HashSet};
 use std::sync::OnceLock;
@@ -44,46 +56,8 @@ static PARKING_ONLY_DATA: OnceLock<HashMap<String, ParkingInfo>> = OnceLock::new
 ///
 /// This is the correlations file generated by:
 /// `cargo run --release -- output --android`
-const PARQUET_BYTES: &[u8] = include_bytes!("../../assets/data/db.parquet");
-const PARQUET_REF_BYTES: &[u8] = include_bytes!("../../assets/data/adresser.parquet");
-/// Determine the best year/month to use for a given day
-///
-/// Strategy:
-/// 1. If the date is valid in current month and hasn't passed yet, use current month
-/// 2. If the date has already passed this month, use next month
-/// 3. If the date is invalid in current month (e.g., Feb 30), use next month
-///
-/// # Arguments
-/// * `day` - Day of month (1-31)
-/// * `current_year` - Current year
-/// * `current_month` - Current month (1-12)
-/// * `current_day` - Current day of month
-///
-/// # Returns
-/// Tuple of (year, month) to use for creating the DB entry
-pub(crate) fn determine_year_month(
-    day: u8,
-    current_year: i32,
-    current_month: u32,
-    current_day: u32,
-) -> (i32, u32) {
-    let current_month_valid =
-        NaiveDate::from_ymd_opt(current_year, current_month, day as u32).is_some();
-    if current_month_valid && (day as u32) >= current_day {
-        return (current_year, current_month);
-    }
-    let mut next_month = current_month + 1;
-    let mut next_year = current_year;
-    if next_month > 12 {
-        next_month = 1;
-        next_year += 1;
-    }
-    if NaiveDate::from_ymd_opt(next_year, next_month, day as u32).is_some() {
-        (next_year, next_month)
-    } else {
-        (current_year, current_month)
-    }
-}
+pub(crate) const PARQUET_BYTES: &[u8] = include_bytes!("../../assets/data/db.parquet");
+pub(crate) const PARQUET_REF_BYTES: &[u8] = include_bytes!("../../assets/data/adresser.parquet");
 /// Load parquet data from embedded bytes
 ///
 /// Uses `amp_core::parquet::read_parquet` to deserialize the DB records.
@@ -93,9 +67,14 @@ pub(crate) fn determine_year_month(
 /// HashMap mapping address keys (format: "postnummer_street_number_day") to DB entries
 ///
 /// # Panics
-/// If the parquet file is corrupted or cannot be parsed
+/// If the embedded assets do not match `SHA256SUMS`, or the parquet file
+/// cannot be parsed
 fn load_parking_data() -> (HashMap<String, DB>, HashMap<String, ParkingInfo>) {
     eprintln!("[StaticData] Loading parking data from embedded parquet...");
+    if let Err(e) = verify_manifest() {
+        eprintln!("[StaticData] FATAL: {}", e);
+        panic!("Embedded assets do not match SHA256SUMS: {}", e);
+    }
     match read_db_parquet_from_bytes(PARQUET_BYTES) {
         Ok(records) => {
             let mut map = HashMap::new();
@@ -110,15 +89,24 @@ fn load_parking_data() -> (HashMap<String, DB>, HashMap<String, ParkingInfo>) {
             );
             for record in records {
                 if let (Some(dag), Some(tid)) = (record.dag, &record.tid) {
-                    let (year, month) =
-                        determine_year_month(dag, current_year, current_month, current_day);
+                    let (year, month, day) = determine_occurrence(
+                        &record.dagar,
+                        current_year,
+                        current_month,
+                        current_day,
+                    )
+                    .unwrap_or_else(|| {
+                        let (year, month) =
+                            determine_year_month(dag, current_year, current_month, current_day);
+                        (year, month, dag)
+                    });
                     if let Some(db) = DB::from_dag_tid(
                         record.postnummer.clone(),
                         record.adress.clone(),
                         Some(record.gata.clone()),
                         Some(record.gatunummer.clone()),
                         record.info.clone(),
-                        dag,
+                        day,
                         tid,
                         record.taxa.clone(),
                         record.antal_platser,
@@ -126,6 +114,10 @@ fn load_parking_data() -> (HashMap<String, DB>, HashMap<String, ParkingInfo>) {
                         year,
                         month,
                     ) {
+                        let db = db
+                            .with_dagar(record.dagar.iter().copied())
+                            .with_match_quality(record.match_quality)
+                            .with_segment_id(record.segment_id);
                         let key = format!(
                             "{}_{}_{}_{}",
                             record.postnummer.as_deref().unwrap_or("unknown"),
@@ -306,6 +298,9 @@ fn read_db_parquet_from_bytes(bytes: &[u8]) -> anyhow::Result<Vec<amp_core::stru
         let taxa = get_string_column("taxa")?;
         let antal_platser = get_u64_column("antal_platser")?;
         let typ_av_parkering = get_string_column("typ_av_parkering")?;
+        let dagar = get_string_column("dagar").ok();
+        let match_quality = get_string_column("match_quality").ok();
+        let segment_id = get_u64_column("segment_id").ok();
         for i in 0..batch.num_rows() {
             let get_optional_string =
                 |arr: &arrow::array::StringArray, idx: usize| -> Option<String> {
@@ -336,6 +331,14 @@ fn read_db_parquet_from_bytes(bytes: &[u8]) -> anyhow::Result<Vec<amp_core::stru
                     Some(arr.value(idx))
                 }
             };
+            let dag = get_optional_u8(dag, i);
+            let mut days = dagar
+                .and_then(|col| get_optional_string(col, i))
+                .map(|days| amp_core::structs::parse_dagar(&days))
+                .unwrap_or_default();
+            if days.is_empty() {
+                days.extend(dag);
+            }
             let entry = amp_core::structs::OutputData {
                 postnummer: get_optional_string(postnummer, i),
                 adress: get_required_string(adress, i),
@@ -343,10 +346,15 @@ fn read_db_parquet_from_bytes(bytes: &[u8]) -> anyhow::Result<Vec<amp_core::stru
                 gatunummer: get_required_string(gatunummer, i),
                 info: get_optional_string(info, i),
                 tid: get_optional_string(tid, i),
-                dag: get_optional_u8(dag, i),
+                dag,
+                dagar: days,
                 taxa: get_optional_string(taxa, i),
                 antal_platser: get_optional_u64(antal_platser, i),
                 typ_av_parkering: get_optional_string(typ_av_parkering, i),
+                match_quality: match_quality
+                    .and_then(|col| get_optional_string(col, i))
+                    .and_then(|code| amp_core::structs::MatchQuality::from_code(&code)),
+                segment_id: segment_id.and_then(|col| get_optional_u64(col, i)),
             };
             result.push(entry);
         }
@@ -543,6 +551,95 @@ pub fn get_parking_only_entry(
     );
     data.get(&key)
 }
+/// Maximum number of addresses returned by [`addresses_sharing_segment`]
+#[allow(dead_code)]
+pub const MAX_SHARING_ADDRESSES: usize = 50;
+/// Other addresses covered by the same street cleaning segment as `entry`.
+///
+/// Two entries share a segment if correlation matched them to the same miljö
+/// feature, i.e. they have the same [`DB::segment_id`]. Used to show "other
+/// addresses affected by this rule".
+///
+/// # Arguments
+/// * `entry` - The address being viewed, e.g. from [`get_address_data`]
+/// * `data` - Parking database, normally [`get_static_data`]
+///
+/// # Returns
+/// Up to [`MAX_SHARING_ADDRESSES`] address strings, excluding `entry`
+/// itself, sorted by postal code, street and house number. Empty if `entry`
+/// has no segment id, which is the case without a miljö match or with a
+/// database built before the ids were stored.
+#[allow(dead_code)]
+pub fn addresses_sharing_segment(entry: &DB, data: &HashMap<String, DB>) -> Vec<String> {
+    let Some(segment_id) = entry.segment_id else {
+        return Vec::new();
+    };
+    let mut sharing: Vec<&DB> = data
+        .values()
+        .filter(|other| other.segment_id == Some(segment_id) && other.adress != entry.adress)
+        .collect();
+    sharing.sort_by(|a, b| {
+        a.postnummer
+            .cmp(&b.postnummer)
+            .then_with(|| a.gata.cmp(&b.gata))
+            .then_with(|| house_number(a).cmp(&house_number(b)))
+            .then_with(|| a.adress.cmp(&b.adress))
+    });
+    let mut addresses: Vec<String> = sharing.into_iter().map(|db| db.adress.clone()).collect();
+    addresses.dedup();
+    addresses.truncate(MAX_SHARING_ADDRESSES);
+    addresses
+}
+/// Leading digits of an entry's street number, so "9B" sorts before "10"
+#[allow(dead_code)]
+fn house_number(entry: &DB) -> u32 {
+    entry
+        .gatunummer
+        .as_deref()
+        .unwrap_or_default()
+        .chars()
+        .take_while(char::is_ascii_digit)
+        .collect::<String>()
+        .parse()
+        .unwrap_or(u32::MAX)
+}
+/// JNI: Called by SearchBridge.addressesSharingSegment(street, streetNumber, postalCode)
+///
+/// Looks the address up with [`get_address_data`] and returns the result of
+/// [`addresses_sharing_segment`] as a JSON array of strings, `[]` if the
+/// address has no match.
+#[cfg(target_os = "android")]
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_se_malmo_skaggbyran_amp_SearchBridge_addressesSharingSegment<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    street: JString<'local>,
+    street_number: JString<'local>,
+    postal_code: JString<'local>,
+) -> jstring {
+    let mut read = |value: &JString<'local>| -> String {
+        match env.get_string(value) {
+            Ok(s) => s.into(),
+            Err(e) => {
+                eprintln!("[StaticData JNI] Failed to get string argument: {:?}", e);
+                String::new()
+            }
+        }
+    };
+    let street = read(&street);
+    let street_number = read(&street_number);
+    let postal_code = read(&postal_code);
+    let addresses = get_address_data(&street, &street_number, &postal_code)
+        .map(|entry| addresses_sharing_segment(entry, get_static_data()))
+        .unwrap_or_default();
+    let json = serde_json::to_string(&addresses).unwrap_or_else(|e| {
+        eprintln!("[StaticData JNI] Failed to serialize results: {:?}", e);
+        "[]".to_string()
+    });
+    env.new_string(&json)
+        .expect("Failed to create JSON output string")
+        .into_raw()
+}
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -608,4 +705,46 @@ mod tests {
         let result2 = get_parking_only_entry("amiralsgatan", "83e", "21437");
         assert!(result2.is_some());
     }
+    fn segment_entry(adress: &str, postnummer: &str, segment_id: Option<u64>) -> DB {
+        let (gata, gatunummer) = adress.rsplit_once(' ').unwrap();
+        DB::from_dag_tid(
+            Some(postnummer.to_string()),
+            adress.to_string(),
+            Some(gata.to_string()),
+            Some(gatunummer.to_string()),
+            Some("Städning".to_string()),
+            15,
+            "0800-1200",
+            None,
+            None,
+            None,
+            2024,
+            1,
+        )
+        .unwrap()
+        .with_segment_id(segment_id)
+    }
+    #[test]
+    fn test_addresses_sharing_segment() {
+        let entries = [
+            segment_entry("Storgatan 10", "21438", Some(3)),
+            segment_entry("Storgatan 9B", "21438", Some(3)),
+            segment_entry("Storgatan 2", "21438", Some(3)),
+            segment_entry("Storgatan 4", "21438", Some(4)),
+            segment_entry("Lillgatan 1", "21438", None),
+        ];
+        let data: HashMap<String, DB> = entries
+            .iter()
+            .map(|db| (db.adress.clone(), db.clone()))
+            .collect();
+        assert_eq!(
+            addresses_sharing_segment(&entries[0], &data),
+            vec!["Storgatan 2", "Storgatan 9B"]
+        );
+        assert_eq!(
+            addresses_sharing_segment(&entries[3], &data),
+            Vec::<String>::new()
+        );
+        assert!(addresses_sharing_segment(&entries[4], &data).is_empty());
+    }
 }
@@ -0,0 +1,157 @@
+//! Small statistics over the user's saved addresses.
+//!
+//! Everything here is derived from data already stored with each address, so
+//! it needs no extra lookups and can be recomputed on every render.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use amp_android::components::stats::total_affected_spaces;
+//! use amp_android::components::storage::read_addresses_from_device;
+//!
+//! let totals = total_affected_spaces(&read_addresses_from_device());
+//! println!("You track {} parking spaces", totals.spaces);
+//! ```
+use crate::ui::StoredAddress;
+use serde::Serialize;
+/// Parking spaces across the user's active addresses.
+///
+/// Addresses whose matched segment has no space count are left out of
+/// `spaces` and counted in `unknown` instead, so the UI can say the total is
+/// a lower bound.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct SpaceTotals {
+    /// Sum of `antal_platser` over active addresses with a known count
+    pub spaces: u64,
+    /// Active addresses that contributed to `spaces`
+    pub counted: usize,
+    /// Active addresses without a known space count
+    pub unknown: usize,
+}
+/// Sum the parking spaces of all active addresses.
+///
+/// The count comes from the matched miljö entry, or from the parking-only
+/// data for addresses without a cleaning schedule. A count of 0 is treated as
+/// unknown: Malmö's data uses it for segments that were never counted.
+///
+/// Addresses on the same segment each add its spaces, since every one of
+/// them is tracked separately.
+pub fn total_affected_spaces(addresses: &[StoredAddress]) -> SpaceTotals {
+    addresses
+        .iter()
+        .filter(|addr| addr.active)
+        .fold(SpaceTotals::default(), |mut totals, addr| {
+            match space_count(addr) {
+                Some(spaces) => {
+                    totals.spaces += spaces;
+                    totals.counted += 1;
+                }
+                None => totals.unknown += 1,
+            }
+            totals
+        })
+}
+/// Known, non-zero space count of an address
+fn space_count(addr: &StoredAddress) -> Option<u64> {
+    addr.matched_entry
+        .as_ref()
+        .and_then(|entry| entry.antal_platser)
+        .or_else(|| addr.parking_info.as_ref()?.antal_platser)
+        .filter(|&spaces| spaces > 0)
+}
+#[cfg(target_os = "android")]
+use jni::JNIEnv;
+#[cfg(target_os = "android")]
+use jni::objects::JClass;
+#[cfg(target_os = "android")]
+use jni::sys::jstring;
+/// JNI: Called by DormantBridge.trackedSpaces()
+///
+/// Reads the saved addresses and returns [`total_affected_spaces`] as JSON,
+/// e.g. `{"spaces":42,"counted":5,"unknown":1}`.
+/// initDormantStorage must have been called first.
+#[cfg(target_os = "android")]
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_se_malmo_skaggbyran_amp_DormantBridge_trackedSpaces<'local>(
+    env: JNIEnv<'local>,
+    _class: JClass<'local>,
+) -> jstring {
+    let addresses = crate::components::storage::read_addresses_from_device();
+    let totals = total_affected_spaces(&addresses);
+    let json = serde_json::to_string(&totals).unwrap_or_else(|e| {
+        eprintln!("[Stats JNI] Failed to serialize totals: {:?}", e);
+        "{}".to_string()
+    });
+    env.new_string(&json)
+        .expect("Failed to create JSON output string")
+        .into_raw()
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::ParkingInfo;
+    use amp_core::structs::DB;
+    fn address(id: u64, active: bool, spaces: Option<u64>) -> StoredAddress {
+        StoredAddress {
+            id,
+            street: "Storgatan".to_string(),
+            street_number: id.to_string(),
+            postal_code: "22100".to_string(),
+            valid: true,
+            active,
+            matched_entry: DB::from_dag_tid(
+                Some("22100".to_string()),
+                format!("Storgatan {}", id),
+                Some("Storgatan".to_string()),
+                Some(id.to_string()),
+                Some("Städning".to_string()),
+                15,
+                "0800-1200",
+                Some("Taxa C".to_string()),
+                spaces,
+                Some("Längsgående 6".to_string()),
+                2024,
+                1,
+            ),
+            parking_info: None,
+            match_reason: None,
+            approximate: false,
+            invalid_reason: None,
+            snoozed_until: None,
+        }
+    }
+    #[test]
+    fn test_total_affected_spaces_mixes_known_and_unknown() {
+        let parking_only = StoredAddress {
+            matched_entry: None,
+            parking_info: Some(ParkingInfo {
+                taxa: Some("Taxa C".to_string()),
+                antal_platser: Some(7),
+                typ_av_parkering: None,
+            }),
+            ..address(5, true, None)
+        };
+        let addresses = vec![
+            address(1, true, Some(26)),
+            address(2, true, None),
+            address(3, true, Some(9)),
+            address(4, true, Some(0)),
+            parking_only,
+            address(6, false, Some(100)),
+        ];
+        assert_eq!(
+            total_affected_spaces(&addresses),
+            SpaceTotals {
+                spaces: 42,
+                counted: 3,
+                unknown: 2,
+            }
+        );
+    }
+    #[test]
+    fn test_total_affected_spaces_without_addresses() {
+        assert_eq!(total_affected_spaces(&[]), SpaceTotals::default());
+        let inactive = vec![address(1, false, Some(10))];
+        assert_eq!(total_affected_spaces(&inactive), SpaceTotals::default());
+    }
+}
@@ -22,6 +22,11 @@
 //! - **Android**: Full file storage using app's internal data directory
 //! - **Other platforms**: In-memory mock storage for testing
 //!
+//! # Factory Reset
+//! [`clear_all`] empties both address files, resets settings.parquet to
+//! defaults and forgets notification history. It is also reachable from
+//! Kotlin as `DormantBridge.rustClearAllData()`.
+//!
 //! # Thread Safety
 //! All storage operations are synchronized using a Mutex to prevent data races
 //! when multiple UI components access storage simultaneously.
@@ -49,6 +54,10 @@
 //! // Save back to storage (thread-safe)
 //! storage::write_addresses_to_device(&addresses).ok();
 //! ```
+use crate::components::settings::reset_settings;
+use crate::components::transitions::clear_panel_state;
+#[cfg(target_os = "android")]
+use crate::components::validity::InvalidReason;
 use crate::ui::StoredAddress;
 #[allow(unused_imports)]
 use amp_core::parquet::{build_local_parquet, read_local_parquet};
@@ -110,14 +119,14 @@ fn get_backup_parquet_path() -> Result<PathBuf, String> {
     path.push(BACKUP_PARQUET_NAME);
     Ok(path)
 }
-/// Create empty parquet file with LocalData schema
+/// Placeholder row written when there are no addresses
 ///
-/// Creates a file with a single dummy row (empty address with valid=false, active=false).
-/// This dummy row is filtered out when reading, resulting in an empty address list.
-/// This approach ensures the parquet file always has valid schema structure.
+/// An empty address with valid=false, active=false. It is filtered out when
+/// reading, so a file holding only this row reads back as an empty list while
+/// still carrying the full LocalData schema.
 #[cfg(target_os = "android")]
-fn create_empty_parquet(path: &PathBuf) -> Result<(), String> {
-    let dummy = LocalData {
+fn empty_placeholder() -> LocalData {
+    LocalData {
         valid: false,
         active: false,
         postnummer: None,
@@ -127,12 +136,27 @@ fn create_empty_parquet(path: &PathBuf) -> Result<(), String> {
         info: None,
         tid: None,
         dag: None,
+        dagar: Vec::new(),
         taxa: None,
         antal_platser: None,
         typ_av_parkering: None,
-    };
-    let buffer = build_local_parquet(vec![dummy])
-        .map_err(|e| format!("[Storage] Failed to build empty parquet: {}", e))?;
+        match_reason: None,
+        match_quality: None,
+        snoozed_until: None,
+    }
+}
+/// Parquet bytes for an address file with no addresses
+#[cfg(target_os = "android")]
+fn empty_parquet_bytes() -> Result<Vec<u8>, String> {
+    build_local_parquet(vec![empty_placeholder()])
+        .map_err(|e| format!("[Storage] Failed to build empty parquet: {}", e))
+}
+/// Create empty parquet file with LocalData schema
+///
+/// See [`empty_placeholder`] for how an empty list is represented.
+#[cfg(target_os = "android")]
+fn create_empty_parquet(path: &PathBuf) -> Result<(), String> {
+    let buffer = empty_parquet_bytes()?;
     fs::write(path, buffer).map_err(|e| {
         format!(
             "[Storage] Failed to write empty parquet to {:?}: {}",
@@ -175,8 +199,12 @@ fn ensure_storage_files() -> Result<(), String> {
 /// - Basic address info (street, number, postal code)
 /// - Match validity and active state
 /// - Parking zone data (taxa, antal_platser, typ_av_parkering)
-/// - Time restrictions (tid, dag) extracted from DB timestamps
+/// - Time restrictions (tid, dag) extracted from DB timestamps, plus every
+///   day of a restriction on several days (dagar)
 /// - Environmental info
+/// - Match reason recorded when the address was matched, and the matched
+///   entry's match quality
+/// - Snooze end (snoozed_until), if the user snoozed the address
 ///
 /// The tid (time range) and dag (day) are extracted from the matched_entry's
 /// start_time and end_time timestamps, ensuring complete persistence of the match.
@@ -184,16 +212,8 @@ fn ensure_storage_files() -> Result<(), String> {
 fn to_local_data(addr: &StoredAddress) -> LocalData {
     let (dag, tid, info, taxa, antal_platser, typ_av_parkering) =
         if let Some(ref entry) = addr.matched_entry {
-            let start_swedish = entry.start_time_swedish();
-            let end_swedish = entry.end_time_swedish();
-            let dag_value = Some(start_swedish.day() as u8);
-            let tid_value = Some(format!(
-                "{:02}{:02}-{:02}{:02}",
-                start_swedish.hour(),
-                start_swedish.minute(),
-                end_swedish.hour(),
-                end_swedish.minute(),
-            ));
+            let dag_value = Some(entry.dag());
+            let tid_value = Some(entry.tid());
             eprintln!(
                 "[Storage::to_local_data] Persisting match data: dag={:?}, tid={:?}, taxa={:?}",
                 dag_value, tid_value, entry.taxa,
@@ -223,6 +243,11 @@ fn to_local_data(addr: &StoredAddress) -> LocalData {
             eprintln!("[Storage::to_local_data] No matched_entry to persist");
             (None, None, None, None, None, None)
         };
+    let dagar = addr
+        .matched_entry
+        .as_ref()
+        .map(|entry| entry.dagar.clone())
+        .unwrap_or_default();
     LocalData {
         valid: addr.valid,
         active: addr.active,
@@ -239,9 +264,16 @@ fn to_local_data(addr: &StoredAddress) -> LocalData {
         info,
         tid,
         dag,
+        dagar,
         taxa,
         antal_platser,
         typ_av_parkering,
+        match_reason: addr.match_reason.clone(),
+        match_quality: addr
+            .matched_entry
+            .as_ref()
+            .and_then(|entry| entry.match_quality),
+        snoozed_until: addr.snoozed_until,
     }
 }
 /// Convert LocalData from parquet to StoredAddress
@@ -265,7 +297,7 @@ fn to_local_data(addr: &StoredAddress) -> LocalData {
 /// # Returns
 /// StoredAddress with reconstructed matched_entry (if match data was persisted)
 #[cfg(target_os = "android")]
-fn from_local_data(data: LocalData, id: usize) -> StoredAddress {
+fn from_local_data(data: LocalData, id: u64) -> StoredAddress {
     eprintln!("[Storage::from_local_data] === START CONVERSION ===");
     let (street, street_number) = if let Some(gata) = &data.gata {
         let street_number = data.gatunummer.clone().unwrap_or_default();
@@ -291,6 +323,8 @@ fn from_local_data(data: LocalData, id: usize) -> StoredAddress {
         }
     };
     let postal_code = data.postnummer.clone().unwrap_or_default();
+    let mut match_reason = data.match_reason.clone();
+    let mut invalid_reason = (!data.valid).then(|| InvalidReason::NoData.code().to_string());
     eprintln!(
         "[Storage::from_local_data] Extracted: street='{}', number='{}', postal='{}'",
         street, street_number, postal_code,
@@ -300,27 +334,39 @@ fn from_local_data(data: LocalData, id: usize) -> StoredAddress {
             "[Storage::from_local_data] Found persisted match data: tid={}, dag={}, taxa={:?}",
             tid, dag, data.taxa,
         );
-        use crate::components::static_data::determine_year_month;
+        use crate::components::static_data::{determine_occurrence, determine_year_month};
         use chrono::Utc;
         let now = Utc::now();
         let current_year = now.year();
         let current_month = now.month();
         let current_day = now.day();
-        let (year, month) = determine_year_month(dag, current_year, current_month, current_day);
+        let (year, month, day) =
+            determine_occurrence(&data.dagar, current_year, current_month, current_day)
+                .unwrap_or_else(|| {
+                    let (year, month) =
+                        determine_year_month(dag, current_year, current_month, current_day);
+                    (year, month, dag)
+                });
         match DB::from_params(DBParams {
             postnummer: data.postnummer.clone(),
             adress: format!("{} {}", street, street_number),
             gata: Some(street.clone()),
             gatunummer: Some(street_number.clone()),
             info: data.info.clone(),
-            dag,
+            dag: day,
             tid: tid.clone(),
             taxa: data.taxa.clone(),
             antal_platser: data.antal_platser,
             typ_av_parkering: data.typ_av_parkering.clone(),
             year,
             month,
-        }) {
+        })
+        .map(|db_entry| {
+            db_entry
+                .with_dagar(data.dagar.iter().copied())
+                .with_match_quality(data.match_quality)
+        })
+        {
             Some(db_entry) => {
                 eprintln!(
                     "[Storage::from_local_data] ✅ Successfully reconstructed DB entry from persisted data",
@@ -332,6 +378,9 @@ fn from_local_data(data: LocalData, id: usize) -> StoredAddress {
                     "[Storage::from_local_data] ⚠️ Failed to reconstruct DB entry from tid={}, dag={}",
                     tid, dag,
                 );
+                if !data.valid {
+                    invalid_reason = Some(InvalidReason::UnparseableTime.code().to_string());
+                }
                 None
             }
         }
@@ -345,6 +394,7 @@ fn from_local_data(data: LocalData, id: usize) -> StoredAddress {
                 eprintln!(
                     "[Storage::from_local_data] ✅ Re-matched successfully via database lookup",
                 );
+                match_reason = Some("exact".to_string());
                 Some(*db_entry)
             }
             crate::components::matching::MatchResult::Invalid => {
@@ -377,6 +427,12 @@ fn from_local_data(data: LocalData, id: usize) -> StoredAddress {
         active: data.active,
         matched_entry,
         parking_info,
+        approximate: match_reason
+            .as_deref()
+            .is_some_and(crate::ui::is_approximate_reason),
+        match_reason,
+        invalid_reason,
+        snoozed_until: data.snoozed_until,
     };
     eprintln!(
         "[Storage::from_local_data] === END CONVERSION (matched={}, parking_info={}) ===",
@@ -494,7 +550,7 @@ pub fn clear_all_addresses() -> Result<(), String> {
     let _lock = STORAGE_LOCK.lock().unwrap();
     #[cfg(target_os = "android")]
     {
-        write_addresses_to_device(&[])?;
+        save_to_parquet(&[])?;
         eprintln!("[Storage] Cleared all addresses");
         Ok(())
     }
@@ -504,6 +560,56 @@ pub fn clear_all_addresses() -> Result<(), String> {
         Ok(())
     }
 }
+/// Reset all stored app data to a fresh install (thread-safe)
+///
+/// Removes every saved address (including the backup copy), resets settings
+/// to the [`AppSettings`] defaults and clears notification tracking state.
+///
+/// The empty address file is staged before settings are touched and only
+/// renamed into place once the settings reset has succeeded, so a failure
+/// leaves saved addresses as they were.
+///
+/// # Returns
+/// - `Ok(())` if all data was reset
+/// - `Err(message)` if a file could not be written; see above for what
+///   remains unchanged
+///
+/// [`AppSettings`]: crate::components::settings::AppSettings
+pub fn clear_all() -> Result<(), String> {
+    let _lock = STORAGE_LOCK.lock().unwrap();
+    #[cfg(target_os = "android")]
+    {
+        let local_path = get_local_parquet_path()?;
+        let backup_path = get_backup_parquet_path()?;
+        let staged = local_path.with_extension("parquet.tmp");
+        fs::write(&staged, empty_parquet_bytes()?).map_err(|e| {
+            format!(
+                "[Storage] Failed to stage empty parquet {:?}: {}",
+                staged, e
+            )
+        })?;
+        if let Err(e) = reset_settings() {
+            let _ = fs::remove_file(&staged);
+            return Err(e);
+        }
+        fs::rename(&staged, &local_path).map_err(|e| {
+            format!(
+                "[Storage] Failed to replace {:?} with empty file: {}",
+                local_path, e
+            )
+        })?;
+        fs::copy(&local_path, &backup_path)
+            .map_err(|e| format!("[Storage] Failed to reset backup: {}", e))?;
+    }
+    #[cfg(not(target_os = "android"))]
+    {
+        reset_settings()?;
+        eprintln!("[Mock Storage] Cleared all addresses");
+    }
+    clear_panel_state();
+    eprintln!("[Storage] Cleared all app data");
+    Ok(())
+}
 /// Get total number of stored addresses without loading them
 ///
 /// Currently loads all data to count. Could be optimized to read only metadata.
@@ -516,9 +622,10 @@ pub fn count_stored_addresses() -> usize {
 /// Internal function that:
 /// 1. Ensures storage files exist
 /// 2. Opens and reads parquet file
-/// 3. Filters out empty/dummy entries
-/// 4. Converts LocalData to StoredAddress
-/// 5. Reconstructs matched_entry from persisted data (no re-matching needed)
+/// 3. Merges duplicate addresses with [`LocalData::dedup_in_place`]
+/// 4. Filters out empty/dummy entries
+/// 5. Converts LocalData to StoredAddress
+/// 6. Reconstructs matched_entry from persisted data (no re-matching needed)
 #[cfg(target_os = "android")]
 fn load_from_parquet() -> Result<Vec<StoredAddress>, String> {
     eprintln!("[Storage::load_from_parquet] Starting load operation");
@@ -535,7 +642,7 @@ fn load_from_parquet() -> Result<Vec<StoredAddress>, String> {
         )
     })?;
     eprintln!("[Storage::load_from_parquet] File opened successfully");
-    let local_data = read_local_parquet(file).map_err(|e| {
+    let mut local_data = read_local_parquet(file).map_err(|e| {
         format!(
             "[Storage] Failed to read parquet data from {:?}: {}",
             local_path, e
@@ -545,6 +652,13 @@ fn load_from_parquet() -> Result<Vec<StoredAddress>, String> {
         "[Storage::load_from_parquet] Read {} LocalData entries from parquet",
         local_data.len(),
     );
+    let removed = LocalData::dedup_in_place(&mut local_data);
+    if removed > 0 {
+        eprintln!(
+            "[Storage::load_from_parquet] Merged {} duplicate address(es)",
+            removed,
+        );
+    }
     let addresses: Vec<StoredAddress> = local_data
         .into_iter()
         .enumerate()
@@ -568,7 +682,7 @@ fn load_from_parquet() -> Result<Vec<StoredAddress>, String> {
                 data.tid.is_some(),
                 data.taxa.is_some(),
             );
-            from_local_data(data, idx)
+            from_local_data(data, idx as u64)
         })
         .collect();
     let matched_count = addresses
@@ -618,20 +732,7 @@ fn save_to_parquet(addresses: &[StoredAddress]) -> Result<(), String> {
         .collect();
     let data_to_write = if local_data.is_empty() {
         eprintln!("[Storage::save_to_parquet] No data to write, creating empty placeholder",);
-        vec![LocalData {
-            valid: false,
-            active: false,
-            postnummer: None,
-            adress: String::new(),
-            gata: None,
-            gatunummer: None,
-            info: None,
-            tid: None,
-            dag: None,
-            taxa: None,
-            antal_platser: None,
-            typ_av_parkering: None,
-        }]
+        vec![empty_placeholder()]
     } else {
         local_data
     };
@@ -760,10 +861,34 @@ pub fn import_local_from_path(temp_path: &str) -> Result<(), String> {
     }
     Ok(())
 }
+#[cfg(target_os = "android")]
+use jni::JNIEnv;
+#[cfg(target_os = "android")]
+use jni::objects::JClass;
+#[cfg(target_os = "android")]
+use jni::sys::{JNI_FALSE, JNI_TRUE, jboolean};
+/// JNI: Called by DormantBridge.rustClearAllData()
+///
+/// Runs [`clear_all`] and returns whether it succeeded.
+#[cfg(target_os = "android")]
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_se_malmo_skaggbyran_amp_DormantBridge_rustClearAllData<'local>(
+    _env: JNIEnv<'local>,
+    _class: JClass<'local>,
+) -> jboolean {
+    match clear_all() {
+        Ok(()) => JNI_TRUE,
+        Err(e) => {
+            eprintln!("[Storage JNI] Clear all data failed: {}", e);
+            JNI_FALSE
+        }
+    }
+}
 #[cfg(test)]
 mod tests {
     #[allow(unused_imports)]
     use super::*;
+    use serial_test::serial;
     /// Test that StoredAddress → LocalData → StoredAddress preserves core fields
     #[test]
     #[cfg(target_os = "android")]
@@ -777,6 +902,10 @@ mod tests {
             active: true,
             matched_entry: None,
             parking_info: None,
+            match_reason: None,
+            approximate: false,
+            invalid_reason: None,
+            snoozed_until: None,
         };
         let local_data = to_local_data(&original);
         let restored = from_local_data(local_data, 1);
@@ -806,6 +935,10 @@ mod tests {
                 active: false,
                 matched_entry: None,
                 parking_info: None,
+                match_reason: None,
+                approximate: false,
+                invalid_reason: None,
+                snoozed_until: None,
             };
             let local_data = to_local_data(&original);
             let restored = from_local_data(local_data, 1);
@@ -836,6 +969,10 @@ mod tests {
                 active: false,
                 matched_entry: None,
                 parking_info: None,
+                match_reason: None,
+                approximate: false,
+                invalid_reason: None,
+                snoozed_until: None,
             },
             StoredAddress {
                 id: 2,
@@ -846,6 +983,10 @@ mod tests {
                 active: true,
                 matched_entry: None,
                 parking_info: None,
+                match_reason: None,
+                approximate: false,
+                invalid_reason: None,
+                snoozed_until: None,
             },
         ];
         let save_result = write_addresses_to_device(&addresses);
@@ -890,6 +1031,28 @@ mod tests {
             "Backup parquet should exist after clear"
         );
     }
+    /// Test that a factory reset leaves no addresses and default settings
+    #[test]
+    #[serial]
+    fn test_clear_all_resets_addresses_and_settings() {
+        use crate::components::settings::{
+            AppSettings, NotificationSettings, Theme, load_settings, save_settings,
+        };
+        let customised = AppSettings {
+            theme: Theme::Dark,
+            notifications: NotificationSettings {
+                sex_timmar: false,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        save_settings(&customised);
+        assert_eq!(load_settings(), customised);
+        let result = clear_all();
+        assert!(result.is_ok(), "Clear all should succeed: {:?}", result);
+        assert!(read_addresses_from_device().is_empty());
+        assert_eq!(load_settings(), AppSettings::default());
+    }
     /// Test that saving/loading a single address works correctly
     #[test]
     #[cfg(target_os = "android")]
@@ -904,6 +1067,10 @@ mod tests {
             active: true,
             matched_entry: None,
             parking_info: None,
+            match_reason: None,
+            approximate: false,
+            invalid_reason: None,
+            snoozed_until: None,
         }];
         let save_result = write_addresses_to_device(&address);
         assert!(save_result.is_ok(), "Save single address should succeed");
@@ -945,6 +1112,10 @@ mod tests {
             active: true,
             matched_entry: Some(db_entry.clone()),
             parking_info: None,
+            match_reason: Some("exact".to_string()),
+            approximate: false,
+            invalid_reason: None,
+            snoozed_until: None,
         };
         let save_result = write_addresses_to_device(&[original.clone()]);
         assert!(save_result.is_ok(), "Save should succeed");
@@ -985,6 +1156,10 @@ mod tests {
             db_entry.gatunummer, restored_entry.gatunummer,
             "Gatunummer should be preserved",
         );
+        assert_eq!(
+            original.match_reason, restored.match_reason,
+            "Match reason should be preserved",
+        );
     }
     /// Test that multiple addresses with mixed match states persist correctly
     #[test]
@@ -1017,6 +1192,10 @@ mod tests {
                 active: true,
                 matched_entry: Some(db_entry),
                 parking_info: None,
+                match_reason: None,
+                approximate: false,
+                invalid_reason: None,
+                snoozed_until: None,
             },
             StoredAddress {
                 id: 2,
@@ -1027,6 +1206,10 @@ mod tests {
                 active: false,
                 matched_entry: None,
                 parking_info: None,
+                match_reason: None,
+                approximate: false,
+                invalid_reason: None,
+                snoozed_until: None,
             },
         ];
         let save_result = write_addresses_to_device(&addresses);
@@ -8,6 +8,29 @@
 //! for each address ID. State is persisted to `notification_state.parquet`
 //! so that notifications are not re-fired after app restart.
 //!
+//! # Cooldowns
+//! A transition only produces a notification if the per-bucket cooldown from
+//! [`NotificationSettings::notification_cooldowns`] has elapsed since the last
+//! notification for the same address and bucket. This keeps an address that
+//! flaps between buckets (e.g. after a data refresh) from notifying repeatedly.
+//! Last-fired times are persisted to `notification_fired.parquet` as Unix
+//! seconds; entries older than the longest configured cooldown are dropped
+//! on save.
+//!
+//! [`NotificationSettings::notification_cooldowns`]: crate::components::settings::NotificationSettings::notification_cooldowns
+//!
+//! # Batching
+//! [`batch_transitions`] turns detected transitions into notifications. With
+//! a non-zero [`NotificationSettings::batch_window`], transitions into the
+//! same bucket are collected into one batch per bucket: the batch opens with
+//! its first transition and, until the window has passed, later transitions
+//! join it. Each time a batch grows it is re-sent under the same
+//! notification ID, so the summary replaces itself instead of stacking. The
+//! first notification is never held back, and a batch that is lost on
+//! restart only means the next transition opens a new one.
+//!
+//! [`NotificationSettings::batch_window`]: crate::components::settings::NotificationSettings::batch_window
+//!
 //! # Transition Rules
 //! Notifications are sent when addresses move to more urgent panels:
 //! - First detection in Within1Day/Within6Hours/Now → notify
@@ -32,10 +55,15 @@
 //! }
 //! ```
 use crate::components::countdown::{TimeBucket, bucket_for};
+use crate::components::settings::{NotificationSettings, load_settings};
 use crate::ui::StoredAddress;
-use amp_core::parquet::{build_notification_state_parquet, read_notification_state_from_bytes};
-use amp_core::structs::{DB, NotificationStateEntry};
-use chrono::{Datelike, Utc};
+use amp_core::buckets::{self, BucketSettings};
+use amp_core::parquet::{
+    build_notification_fired_parquet, build_notification_state_parquet,
+    read_notification_fired_from_bytes, read_notification_state_from_bytes,
+};
+use amp_core::structs::{DB, NotificationFiredEntry, NotificationStateEntry};
+use chrono::{DateTime, Datelike, Duration, Utc};
 use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::Mutex;
@@ -43,10 +71,36 @@ use std::sync::Mutex;
 ///
 /// Maps address ID to its most recently observed TimeBucket.
 /// Protected by Mutex for thread-safe access from UI and background tasks.
-static PANEL_STATE: Mutex<Option<HashMap<usize, TimeBucket>>> = Mutex::new(None);
+static PANEL_STATE: Mutex<Option<HashMap<u64, TimeBucket>>> = Mutex::new(None);
 const NOTIFICATION_STATE_FILE_NAME: &str = "notification_state.parquet";
+/// Last time a notification fired, keyed by (address ID, bucket)
+type FiredMap = HashMap<(u64, TimeBucket), DateTime<Utc>>;
+/// Last time a notification fired for each (address ID, bucket) pair
+static FIRED_STATE: Mutex<Option<FiredMap>> = Mutex::new(None);
+const NOTIFICATION_FIRED_FILE_NAME: &str = "notification_fired.parquet";
+/// Notifications for one or more addresses that entered the same bucket
+#[derive(Clone, Debug, PartialEq)]
+pub struct BatchedNotification {
+    /// Bucket the addresses moved into
+    pub bucket: TimeBucket,
+    /// Every address in the batch so far, in the order they joined
+    pub addresses: Vec<StoredAddress>,
+    /// ID to post under: the address ID when batching is off, otherwise one
+    /// per bucket so a growing batch replaces its previous summary
+    pub notification_id: u64,
+}
+/// A batch that later transitions into the same bucket can still join
+#[derive(Clone, Debug)]
+struct OpenBatch {
+    opened_at: DateTime<Utc>,
+    addresses: Vec<StoredAddress>,
+}
+/// Open batch per bucket, see [`batch_transitions`]
+static BATCH_STATE: Mutex<Option<HashMap<TimeBucket, OpenBatch>>> = Mutex::new(None);
+/// Base of the per-bucket summary notification IDs
+const BATCH_NOTIFICATION_ID_BASE: u64 = 0x7FFF_FF00;
 /// Convert a TimeBucket to its string representation for persistence
-fn bucket_to_string(bucket: &TimeBucket) -> &'static str {
+pub(crate) fn bucket_to_string(bucket: &TimeBucket) -> &'static str {
     match bucket {
         TimeBucket::Now => "Now",
         TimeBucket::Within6Hours => "Within6Hours",
@@ -57,7 +111,7 @@ fn bucket_to_string(bucket: &TimeBucket) -> &'static str {
     }
 }
 /// Convert a string back to a TimeBucket
-fn bucket_from_string(s: &str) -> TimeBucket {
+pub(crate) fn bucket_from_string(s: &str) -> TimeBucket {
     match s {
         "Now" => TimeBucket::Now,
         "Within6Hours" => TimeBucket::Within6Hours,
@@ -95,6 +149,12 @@ fn get_state_file_path() -> Result<PathBuf, String> {
     path.push(NOTIFICATION_STATE_FILE_NAME);
     Ok(path)
 }
+/// Get the last-fired timestamps file path
+fn get_fired_file_path() -> Result<PathBuf, String> {
+    let mut path = get_storage_dir()?;
+    path.push(NOTIFICATION_FIRED_FILE_NAME);
+    Ok(path)
+}
 /// Get current year-month as u32, e.g. 202602
 fn current_year_month() -> u32 {
     let now = Utc::now();
@@ -105,7 +165,7 @@ fn current_year_month() -> u32 {
 ///
 /// Only loads entries matching the current year_month, so state
 /// auto-resets each month.
-fn load_panel_state_from_file() -> HashMap<usize, TimeBucket> {
+fn load_panel_state_from_file() -> HashMap<u64, TimeBucket> {
     let path = match get_state_file_path() {
         Ok(p) => p,
         Err(e) => {
@@ -135,7 +195,7 @@ fn load_panel_state_from_file() -> HashMap<usize, TimeBucket> {
     let mut map = HashMap::new();
     for entry in entries {
         if entry.year_month == ym {
-            map.insert(entry.address_id as usize, bucket_from_string(&entry.bucket));
+            map.insert(entry.address_id, bucket_from_string(&entry.bucket));
         }
     }
     eprintln!(
@@ -146,7 +206,7 @@ fn load_panel_state_from_file() -> HashMap<usize, TimeBucket> {
     map
 }
 /// Save current panel state to parquet file
-fn save_panel_state_to_file(state: &HashMap<usize, TimeBucket>) {
+fn save_panel_state_to_file(state: &HashMap<u64, TimeBucket>) {
     if state.is_empty() {
         if let Ok(path) = get_state_file_path()
             && path.exists()
@@ -159,7 +219,7 @@ fn save_panel_state_to_file(state: &HashMap<usize, TimeBucket>) {
     let entries: Vec<NotificationStateEntry> = state
         .iter()
         .map(|(id, bucket)| NotificationStateEntry {
-            address_id: *id as u64,
+            address_id: *id,
             bucket: bucket_to_string(bucket).to_string(),
             year_month: ym,
         })
@@ -197,6 +257,98 @@ fn save_panel_state_to_file(state: &HashMap<usize, TimeBucket>) {
         eprintln!("[PanelTracker] Saved {} entries to {:?}", state.len(), path);
     }
 }
+/// Load last-fired timestamps from the persisted parquet file
+fn load_fired_state_from_file() -> FiredMap {
+    let path = match get_fired_file_path() {
+        Ok(p) => p,
+        Err(e) => {
+            eprintln!("[PanelTracker] Failed to get fired file path: {}", e);
+            return HashMap::new();
+        }
+    };
+    if !path.exists() {
+        return HashMap::new();
+    }
+    let entries = match std::fs::read(&path)
+        .map_err(|e| e.to_string())
+        .and_then(|bytes| read_notification_fired_from_bytes(&bytes).map_err(|e| e.to_string()))
+    {
+        Ok(e) => e,
+        Err(e) => {
+            eprintln!("[PanelTracker] Failed to load fired file {:?}: {}", path, e);
+            return HashMap::new();
+        }
+    };
+    entries
+        .into_iter()
+        .filter_map(|entry| {
+            let fired_at = DateTime::from_timestamp(entry.fired_at, 0)?;
+            Some((
+                (entry.address_id, bucket_from_string(&entry.bucket)),
+                fired_at,
+            ))
+        })
+        .collect()
+}
+/// Save last-fired timestamps, dropping entries whose cooldown has long expired
+fn save_fired_state_to_file(
+    state: &mut FiredMap,
+    settings: &NotificationSettings,
+    now: DateTime<Utc>,
+) {
+    let retention = settings
+        .notification_cooldowns
+        .values()
+        .copied()
+        .max()
+        .unwrap_or_else(Duration::zero);
+    state.retain(|_, fired_at| now - *fired_at < retention);
+    let path = match get_fired_file_path() {
+        Ok(p) => p,
+        Err(e) => {
+            eprintln!("[PanelTracker] Failed to get fired file path: {}", e);
+            return;
+        }
+    };
+    if state.is_empty() {
+        if path.exists() {
+            let _ = std::fs::remove_file(&path);
+        }
+        return;
+    }
+    let entries: Vec<NotificationFiredEntry> = state
+        .iter()
+        .map(|((id, bucket), fired_at)| NotificationFiredEntry {
+            address_id: *id,
+            bucket: bucket_to_string(bucket).to_string(),
+            fired_at: fired_at.timestamp(),
+        })
+        .collect();
+    let parquet_bytes = match build_notification_fired_parquet(entries) {
+        Ok(b) => b,
+        Err(e) => {
+            eprintln!("[PanelTracker] Failed to build fired parquet: {}", e);
+            return;
+        }
+    };
+    if let Err(e) = std::fs::write(&path, parquet_bytes) {
+        eprintln!(
+            "[PanelTracker] Failed to write fired file {:?}: {}",
+            path, e
+        );
+    }
+}
+/// Whether enough time has passed since the last notification to fire again
+fn cooldown_elapsed(
+    last_fired: Option<DateTime<Utc>>,
+    cooldown: Duration,
+    now: DateTime<Utc>,
+) -> bool {
+    match last_fired {
+        Some(fired_at) => now - fired_at >= cooldown,
+        None => true,
+    }
+}
 /// Initialize the panel state tracker
 ///
 /// Loads persisted state from disk so that previously-seen transitions
@@ -229,7 +381,9 @@ pub fn initialize_panel_tracker() {
 /// bucket, it's included in the returned transitions list.
 ///
 /// State is automatically saved to disk after detection so that
-/// the same transitions are not re-fired on app restart.
+/// the same transitions are not re-fired on app restart. Transitions into
+/// a bucket that already notified for the same address within its
+/// configured cooldown are suppressed.
 ///
 /// # Arguments
 /// * `addresses` - Current list of addresses with their matched parking data
@@ -260,6 +414,18 @@ pub fn initialize_panel_tracker() {
 /// ```
 pub fn detect_transitions(
     addresses: &[StoredAddress],
+) -> Vec<(StoredAddress, Option<TimeBucket>, TimeBucket)> {
+    let settings = load_settings().notifications;
+    detect_transitions_at(addresses, &settings, Utc::now())
+}
+/// [`detect_transitions`] with the notification settings and time given
+///
+/// The settings are loaded by the caller, so no file is read while the
+/// tracker locks are held.
+pub(crate) fn detect_transitions_at(
+    addresses: &[StoredAddress],
+    settings: &NotificationSettings,
+    now: DateTime<Utc>,
 ) -> Vec<(StoredAddress, Option<TimeBucket>, TimeBucket)> {
     let mut state_guard = PANEL_STATE.lock().unwrap();
     if state_guard.is_none() {
@@ -267,6 +433,8 @@ pub fn detect_transitions(
         *state_guard = Some(load_panel_state_from_file());
     }
     let state = state_guard.as_mut().unwrap();
+    let mut fired_guard = FIRED_STATE.lock().unwrap();
+    let fired = fired_guard.get_or_insert_with(load_fired_state_from_file);
     let mut transitions = Vec::new();
     for addr in addresses {
         let matched_entry = match &addr.matched_entry {
@@ -276,7 +444,7 @@ pub fn detect_transitions(
                 continue;
             }
         };
-        let new_bucket = bucket_for(matched_entry);
+        let new_bucket = buckets::bucket_for(matched_entry, now, &settings.bucket_settings());
         let previous_bucket = state.get(&addr.id).cloned();
         let should_notify = matches!(
             (&previous_bucket, &new_bucket),
@@ -293,12 +461,30 @@ pub fn detect_transitions(
                 | (Some(TimeBucket::Within1Day), TimeBucket::Now)
                 | (Some(TimeBucket::Within6Hours), TimeBucket::Now)
         );
-        if should_notify {
+        let key = (addr.id, new_bucket.clone());
+        if should_notify && addr.is_snoozed(now) {
+            eprintln!(
+                "[PanelTracker] Suppressed {:?} for id={}: snoozed",
+                new_bucket, addr.id,
+            );
+        } else if should_notify
+            && !cooldown_elapsed(
+                fired.get(&key).copied(),
+                settings.cooldown_for(&new_bucket),
+                now,
+            )
+        {
+            eprintln!(
+                "[PanelTracker] Suppressed {:?} for id={}: within cooldown",
+                new_bucket, addr.id,
+            );
+        } else if should_notify {
             eprintln!(
                 "[PanelTracker] Transition detected: {} {} (id={}) {:?} → {:?}",
                 addr.street, addr.street_number, addr.id, previous_bucket, new_bucket,
             );
             transitions.push((addr.clone(), previous_bucket, new_bucket.clone()));
+            fired.insert(key, now);
         }
         state.insert(addr.id, new_bucket);
     }
@@ -309,11 +495,99 @@ pub fn detect_transitions(
         );
     }
     save_panel_state_to_file(state);
+    save_fired_state_to_file(fired, settings, now);
     transitions
 }
+/// Group transitions into notifications, coalescing per bucket
+///
+/// Uses the batch window from the saved settings and keeps open batches
+/// between calls. With batching off every transition becomes its own
+/// single-address notification, exactly as before batching existed.
+///
+/// # Arguments
+/// * `transitions` - Output of [`detect_transitions`]
+/// * `now` - Reference instant for opening and expiring batches
+///
+/// # Returns
+/// One notification per bucket that gained addresses (or per transition
+/// with batching off), each listing the whole batch
+///
+/// # Examples
+/// ```no_run
+/// use amp_android::components::transitions::{batch_transitions, detect_transitions};
+/// use amp_android::components::storage::read_addresses_from_device;
+/// use chrono::Utc;
+///
+/// let transitions = detect_transitions(&read_addresses_from_device());
+/// for batch in batch_transitions(transitions, Utc::now()) {
+///     println!("{:?}: {} address(es)", batch.bucket, batch.addresses.len());
+/// }
+/// ```
+pub fn batch_transitions(
+    transitions: Vec<(StoredAddress, Option<TimeBucket>, TimeBucket)>,
+    now: DateTime<Utc>,
+) -> Vec<BatchedNotification> {
+    let window = load_settings().notifications.batch_window;
+    let mut guard = BATCH_STATE.lock().unwrap();
+    let open = guard.get_or_insert_with(HashMap::new);
+    coalesce_transitions(open, transitions, window, now)
+}
+/// Coalesce transitions into the open batches, see [`batch_transitions`]
+fn coalesce_transitions(
+    open: &mut HashMap<TimeBucket, OpenBatch>,
+    transitions: Vec<(StoredAddress, Option<TimeBucket>, TimeBucket)>,
+    window: Duration,
+    now: DateTime<Utc>,
+) -> Vec<BatchedNotification> {
+    if window <= Duration::zero() {
+        open.clear();
+        return transitions
+            .into_iter()
+            .map(|(addr, _, bucket)| BatchedNotification {
+                bucket,
+                notification_id: addr.id,
+                addresses: vec![addr],
+            })
+            .collect();
+    }
+    open.retain(|_, batch| now - batch.opened_at < window);
+    let mut grown: Vec<TimeBucket> = Vec::new();
+    for (addr, _, bucket) in transitions {
+        let batch = open.entry(bucket.clone()).or_insert_with(|| OpenBatch {
+            opened_at: now,
+            addresses: Vec::new(),
+        });
+        batch.addresses.retain(|existing| existing.id != addr.id);
+        batch.addresses.push(addr);
+        if !grown.contains(&bucket) {
+            grown.push(bucket);
+        }
+    }
+    grown
+        .into_iter()
+        .map(|bucket| BatchedNotification {
+            addresses: open[&bucket].addresses.clone(),
+            notification_id: batch_notification_id(&bucket),
+            bucket,
+        })
+        .collect()
+}
+/// Notification ID shared by every summary for `bucket`
+fn batch_notification_id(bucket: &TimeBucket) -> u64 {
+    let offset = match bucket {
+        TimeBucket::Now => 0,
+        TimeBucket::Within6Hours => 1,
+        TimeBucket::Within1Day => 2,
+        TimeBucket::Within1Month => 3,
+        TimeBucket::MoreThan1Month => 4,
+        TimeBucket::Invalid => 5,
+    };
+    BATCH_NOTIFICATION_ID_BASE + offset
+}
 /// Clear the panel state (useful for testing or reset)
 ///
-/// Removes all tracked address states and deletes the persisted file.
+/// Removes all tracked address states, last-fired timestamps and open
+/// notification batches, and deletes the persisted files.
 /// After calling this, the next call to `detect_transitions` will
 /// treat all addresses as new.
 ///
@@ -346,6 +620,13 @@ pub fn clear_panel_state() {
             eprintln!("[PanelTracker] Deleted state file {:?}", path);
         }
     }
+    *FIRED_STATE.lock().unwrap() = Some(HashMap::new());
+    *BATCH_STATE.lock().unwrap() = None;
+    if let Ok(path) = get_fired_file_path()
+        && path.exists()
+    {
+        let _ = std::fs::remove_file(&path);
+    }
 }
 /// Get the number of currently tracked addresses
 ///
@@ -358,7 +639,7 @@ pub fn tracked_address_count() -> usize {
 }
 #[allow(dead_code)]
 pub fn create_test_address_with_bucket(
-    id: usize,
+    id: u64,
     day: u8,
     time: &str,
 ) -> (StoredAddress, TimeBucket) {
@@ -377,7 +658,7 @@ pub fn create_test_address_with_bucket(
         1,
     )
     .expect("Failed to create test DB entry");
-    let bucket = bucket_for(&db);
+    let bucket = bucket_for(&db, &BucketSettings::default());
     let addr = StoredAddress {
         id,
         street: "Test Street".to_string(),
@@ -387,6 +668,10 @@ pub fn create_test_address_with_bucket(
         active: true,
         matched_entry: Some(db),
         parking_info: None,
+        match_reason: None,
+        approximate: false,
+        invalid_reason: None,
+        snoozed_until: None,
     };
     (addr, bucket)
 }
@@ -397,7 +682,7 @@ mod tests {
     use serial_test::serial;
     use std::slice::from_ref;
     /// Helper to create a test address with a specific day/time
-    fn create_test_address(id: usize, day: u8, time: &str) -> StoredAddress {
+    fn create_test_address(id: u64, day: u8, time: &str) -> StoredAddress {
         let db = DB::from_dag_tid(
             Some("22100".to_string()),
             format!("Test Street {}", id),
@@ -422,6 +707,10 @@ mod tests {
             active: true,
             matched_entry: Some(db),
             parking_info: None,
+            match_reason: None,
+            approximate: false,
+            invalid_reason: None,
+            snoozed_until: None,
         }
     }
     #[test]
@@ -491,6 +780,10 @@ mod tests {
             active: false,
             matched_entry: None,
             parking_info: None,
+            match_reason: None,
+            approximate: false,
+            invalid_reason: None,
+            snoozed_until: None,
         };
         let transitions = detect_transitions(&[addr]);
         assert_eq!(
@@ -562,6 +855,129 @@ mod tests {
         clear_panel_state();
     }
     #[test]
+    fn test_cooldown_elapsed() {
+        let now = Utc::now();
+        let cooldown = Duration::hours(4);
+        assert!(cooldown_elapsed(None, cooldown, now));
+        assert!(!cooldown_elapsed(
+            Some(now - Duration::hours(1)),
+            cooldown,
+            now
+        ));
+        assert!(cooldown_elapsed(
+            Some(now - Duration::hours(4)),
+            cooldown,
+            now
+        ));
+        assert!(cooldown_elapsed(Some(now), Duration::zero(), now));
+    }
+    #[test]
+    #[serial]
+    fn test_cooldown_suppresses_refire_after_bucket_flap() {
+        use chrono::TimeZone;
+        clear_panel_state();
+        initialize_panel_tracker();
+        let settings = NotificationSettings::default();
+        // 08:00 CET on 15 January is 19 hours away
+        let now = Utc.with_ymd_and_hms(2024, 1, 14, 12, 0, 0).unwrap();
+        let addr = create_test_address(1, 15, "0800-1200");
+        let first = detect_transitions_at(from_ref(&addr), &settings, now);
+        assert_eq!(first.len(), 1);
+        assert_eq!(first[0].2, TimeBucket::Within1Day);
+        {
+            let mut state = PANEL_STATE.lock().unwrap();
+            state.as_mut().unwrap().remove(&addr.id);
+        }
+        let later = now + Duration::hours(1);
+        let second = detect_transitions_at(from_ref(&addr), &settings, later);
+        assert!(
+            second.is_empty(),
+            "Re-entering Within1Day within its cooldown should not notify again",
+        );
+        clear_panel_state();
+    }
+    fn transition(id: u64, bucket: TimeBucket) -> (StoredAddress, Option<TimeBucket>, TimeBucket) {
+        (
+            create_test_address(id, 1, "0800-1200"),
+            Some(TimeBucket::Within1Month),
+            bucket,
+        )
+    }
+    fn ids(batch: &BatchedNotification) -> Vec<u64> {
+        batch.addresses.iter().map(|addr| addr.id).collect()
+    }
+    #[test]
+    fn test_coalesce_simultaneous_transitions_per_bucket() {
+        let now = Utc::now();
+        let mut open = HashMap::new();
+        let batches = coalesce_transitions(
+            &mut open,
+            vec![
+                transition(1, TimeBucket::Within6Hours),
+                transition(2, TimeBucket::Within1Day),
+                transition(3, TimeBucket::Within6Hours),
+                transition(4, TimeBucket::Within6Hours),
+            ],
+            Duration::minutes(30),
+            now,
+        );
+        assert_eq!(batches.len(), 2);
+        assert_eq!(batches[0].bucket, TimeBucket::Within6Hours);
+        assert_eq!(ids(&batches[0]), vec![1, 3, 4]);
+        assert_eq!(batches[1].bucket, TimeBucket::Within1Day);
+        assert_eq!(ids(&batches[1]), vec![2]);
+        assert_ne!(batches[0].notification_id, batches[1].notification_id);
+    }
+    #[test]
+    fn test_coalesce_grows_batch_within_window_then_starts_new() {
+        let start = Utc::now();
+        let window = Duration::minutes(30);
+        let mut open = HashMap::new();
+        let first = coalesce_transitions(
+            &mut open,
+            vec![transition(1, TimeBucket::Within6Hours)],
+            window,
+            start,
+        );
+        let second = coalesce_transitions(
+            &mut open,
+            vec![
+                transition(2, TimeBucket::Within6Hours),
+                transition(1, TimeBucket::Within6Hours),
+            ],
+            window,
+            start + Duration::minutes(10),
+        );
+        assert_eq!(ids(&second[0]), vec![2, 1]);
+        assert_eq!(second[0].notification_id, first[0].notification_id);
+        let later = coalesce_transitions(
+            &mut open,
+            vec![transition(3, TimeBucket::Within6Hours)],
+            window,
+            start + window,
+        );
+        assert_eq!(ids(&later[0]), vec![3]);
+        assert!(coalesce_transitions(&mut open, Vec::new(), window, start + window).is_empty());
+    }
+    #[test]
+    fn test_coalesce_without_window_keeps_one_per_address() {
+        let mut open = HashMap::new();
+        let batches = coalesce_transitions(
+            &mut open,
+            vec![
+                transition(1, TimeBucket::Now),
+                transition(2, TimeBucket::Now),
+            ],
+            Duration::zero(),
+            Utc::now(),
+        );
+        assert_eq!(batches.len(), 2);
+        assert_eq!(ids(&batches[0]), vec![1]);
+        assert_eq!(batches[0].notification_id, 1);
+        assert_eq!(batches[1].notification_id, 2);
+        assert!(open.is_empty());
+    }
+    #[test]
     #[serial]
     fn test_current_year_month() {
         let ym = current_year_month();
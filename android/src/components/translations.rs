@@ -1,4 +1,5 @@
 use crate::components::settings::Language;
+use crate::components::validity::InvalidReason;
 /// Return the localised string for the given key.
 ///
 /// Falls back to the key itself when no translation is registered.
@@ -8,6 +9,16 @@ pub fn t(key: &str, lang: &Language) -> &'static str {
         _ => t_sv(key),
     }
 }
+/// Return the localised explanation for a stored invalid-reason code.
+///
+/// Unknown codes map to a generic "unknown reason" text rather than leaking
+/// the raw code into the UI.
+pub fn t_invalid_reason(code: &str, lang: &Language) -> &'static str {
+    let key = InvalidReason::from_code(code)
+        .map(|reason| reason.translation_key())
+        .unwrap_or("invalid_reason.unknown");
+    t(key, lang)
+}
 fn t_sv(key: &str) -> &'static str {
     match key {
         "topbar.address_placeholder" => "Adress",
@@ -34,6 +45,9 @@ fn t_sv(key: &str) -> &'static str {
         "settings.import_export.settings_heading" => "Inställningar",
         "settings.import_export.export_sett_desc" => "Spara inställningar till fil",
         "settings.import_export.import_sett_desc" => "Ladda inställningar från fil",
+        "settings.import_export.reset_heading" => "Återställ",
+        "settings.import_export.clear_all_label" => "Rensa all data",
+        "settings.import_export.clear_all_desc" => "Ta bort adresser och återställ inställningar",
         "settings.notifications.title" => "Aviseringar",
         "settings.notifications.now_label" => "Städas nu",
         "settings.notifications.now_desc" => "Avisera när gatustädning pågår",
@@ -41,6 +55,12 @@ fn t_sv(key: &str) -> &'static str {
         "settings.notifications.6h_desc" => "Avisera 6 timmar före gatustädning",
         "settings.notifications.1d_label" => "1 dag",
         "settings.notifications.1d_desc" => "Avisera 1 dag före gatustädning",
+        "settings.notifications.batch_label" => "Samla aviseringar",
+        "settings.notifications.batch_desc" => {
+            "Skicka en sammanfattning när flera adresser ändras inom 30 minuter"
+        }
+        "settings.notifications.quiet_label" => "Tysta timmar",
+        "settings.notifications.quiet_desc" => "Vänta med aviseringar mellan 22:00 och 07:00",
         "settings.settings.title" => "Inställningar",
         "settings.settings.datasource_heading" => "Datakälla",
         "settings.settings.both_label" => "Miljö + Parkering",
@@ -53,6 +73,8 @@ fn t_sv(key: &str) -> &'static str {
         "settings.settings.all_desc" => "Alla ~60k adresser i Malmö (kan vara långsamt)",
         "settings.settings.darkmode_label" => "Mörkt läge",
         "settings.settings.darkmode_desc" => "Växla mörkt/ljust tema",
+        "settings.settings.group_by_street_label" => "Gruppera efter gata",
+        "settings.settings.group_by_street_desc" => "Visa adresser samlade per gata",
         "settings.settings.language_heading" => "Språk",
         "settings.settings.lang_sv" => "Svenska",
         "settings.settings.lang_en" => "English",
@@ -73,6 +95,11 @@ fn t_sv(key: &str) -> &'static str {
         }
         "dialog.confirm_import.cancel" => "Avbryt",
         "dialog.confirm_import.ok" => "Importera",
+        "dialog.confirm_clear.title" => "Rensa all data",
+        "dialog.confirm_clear.body" => {
+            "Alla sparade adresser tas bort och inställningarna återställs. Detta kan inte ångras."
+        }
+        "dialog.confirm_clear.ok" => "Rensa",
         "dialog.error.title" => "Fel",
         "dialog.ok" => "OK",
         "dialog.success.title" => "Klart",
@@ -84,6 +111,8 @@ fn t_sv(key: &str) -> &'static str {
         "msg.import_success.addresses" => "Adresser importerade!",
         "msg.import_success.settings" => "Inställningar importerade!",
         "msg.import_fail" => "Import misslyckades",
+        "msg.clear_success" => "All data rensad!",
+        "msg.clear_fail" => "Kunde inte rensa data",
         "panel.cleaning_now" => "Städas nu",
         "panel.within_6h" => "Inom 6 timmar",
         "panel.within_1d" => "Inom 1 dag",
@@ -92,8 +121,23 @@ fn t_sv(key: &str) -> &'static str {
         "panel.parking_only" => "Endast parkeringsavgift",
         "panel.invalid" => "Ingen städning",
         "panel.no_addresses" => "Inga adresser",
+        "duration.now" => "Nu",
+        "duration.under_minute" => "< 1 min",
+        "duration.day_hours_minutes" => "{d} dag {h} tim {m} min",
+        "duration.days_hours_minutes" => "{d} dagar {h} tim {m} min",
+        "duration.hours_minutes" => "{h} tim {m} min",
+        "duration.minutes" => "{m} min",
+        "duration.hours_minutes_seconds" => "{h} tim {m:02} min {s:02} s",
+        "panel.no_upcoming" => "Ingen kommande städning",
+        "panel.snooze" => "Påminn nästa gång",
+        "invalid_reason.no_data" => "Ingen städ- eller parkeringsdata hittades för adressen",
+        "invalid_reason.unparseable_time" => "Tiden för städningen kunde inte tolkas",
+        "invalid_reason.day_not_in_month" => "Städdagen finns inte i den här månaden",
+        "invalid_reason.unknown" => "Okänd orsak",
         "addresses.title" => "Adresser",
         "addresses.empty" => "Inga adresser tillagda",
+        "addresses.spaces_tracked" => "parkeringsplatser bevakas",
+        "addresses.spaces_unknown" => "okänt antal",
         "addresses.confirm_remove_title" => "Bekräfta borttagning",
         "addresses.confirm_remove_msg" => "Är du säker på att du vill ta bort denna adress?",
         "confirm_dialog.cancel" => "Avbryt",
@@ -105,9 +149,22 @@ fn t_sv(key: &str) -> &'static str {
         "info_dialog.status" => "Status:",
         "info_dialog.validated" => "Validerad:",
         "info_dialog.taxa" => "Taxa:",
+        "info_dialog.fee_now" => "Avgift nu:",
+        "info_dialog.fee_free" => "Gratis",
+        "info_dialog.fee_until" => "till",
         "info_dialog.info" => "Info:",
         "info_dialog.type" => "Typ:",
+        "info_dialog.placement" => "Placering:",
+        "info_dialog.per_space" => "m per plats",
+        "placement.längsgående" => "Längsgående",
+        "placement.sned" => "Snedparkering",
+        "placement.vinkelrät" => "Vinkelrät",
         "info_dialog.spots" => "Platser:",
+        "info_dialog.match_reason" => "Matchning:",
+        "info_dialog.match_quality" => "Precision:",
+        "info_dialog.match_exact" => "✓ Exakt",
+        "info_dialog.match_approximate" => "⚠ Ungefärlig",
+        "info_dialog.invalid_reason" => "Orsak:",
         "info_dialog.active" => "Aktiv",
         "info_dialog.inactive" => "Inaktiv",
         "info_dialog.yes" => "Ja",
@@ -153,6 +210,9 @@ fn t_en(key: &str) -> &'static str {
         "settings.import_export.settings_heading" => "Settings",
         "settings.import_export.export_sett_desc" => "Save settings to file",
         "settings.import_export.import_sett_desc" => "Load settings from file",
+        "settings.import_export.reset_heading" => "Reset",
+        "settings.import_export.clear_all_label" => "Clear all data",
+        "settings.import_export.clear_all_desc" => "Remove addresses and reset settings",
         "settings.notifications.title" => "Notifications",
         "settings.notifications.now_label" => "Cleaning now",
         "settings.notifications.now_desc" => "Notify when street cleaning is happening",
@@ -160,6 +220,12 @@ fn t_en(key: &str) -> &'static str {
         "settings.notifications.6h_desc" => "Notify 6 hours before street cleaning",
         "settings.notifications.1d_label" => "1 day",
         "settings.notifications.1d_desc" => "Notify 1 day before street cleaning",
+        "settings.notifications.batch_label" => "Group notifications",
+        "settings.notifications.batch_desc" => {
+            "Send one summary when several addresses change within 30 minutes"
+        }
+        "settings.notifications.quiet_label" => "Quiet hours",
+        "settings.notifications.quiet_desc" => "Hold notifications between 22:00 and 07:00",
         "settings.settings.title" => "Settings",
         "settings.settings.datasource_heading" => "Data source",
         "settings.settings.both_label" => "Miljö + Parking",
@@ -172,6 +238,8 @@ fn t_en(key: &str) -> &'static str {
         "settings.settings.all_desc" => "All ~60k addresses in Malmö (may be slow)",
         "settings.settings.darkmode_label" => "Dark mode",
         "settings.settings.darkmode_desc" => "Toggle dark/light theme",
+        "settings.settings.group_by_street_label" => "Group by street",
+        "settings.settings.group_by_street_desc" => "Show addresses collected per street",
         "settings.settings.language_heading" => "Language",
         "settings.settings.lang_sv" => "Svenska",
         "settings.settings.lang_en" => "English",
@@ -192,6 +260,11 @@ fn t_en(key: &str) -> &'static str {
         }
         "dialog.confirm_import.cancel" => "Cancel",
         "dialog.confirm_import.ok" => "Import",
+        "dialog.confirm_clear.title" => "Clear all data",
+        "dialog.confirm_clear.body" => {
+            "All saved addresses will be removed and settings reset. This cannot be undone."
+        }
+        "dialog.confirm_clear.ok" => "Clear",
         "dialog.error.title" => "Error",
         "dialog.ok" => "OK",
         "dialog.success.title" => "Done",
@@ -203,6 +276,8 @@ fn t_en(key: &str) -> &'static str {
         "msg.import_success.addresses" => "Addresses imported!",
         "msg.import_success.settings" => "Settings imported!",
         "msg.import_fail" => "Import failed",
+        "msg.clear_success" => "All data cleared!",
+        "msg.clear_fail" => "Could not clear data",
         "panel.cleaning_now" => "Cleaning now",
         "panel.within_6h" => "Within 6 hours",
         "panel.within_1d" => "Within 1 day",
@@ -211,8 +286,23 @@ fn t_en(key: &str) -> &'static str {
         "panel.parking_only" => "Parking fee only",
         "panel.invalid" => "No cleaning",
         "panel.no_addresses" => "No addresses",
+        "duration.now" => "Now",
+        "duration.under_minute" => "< 1m",
+        "duration.day_hours_minutes" => "{d}d {h:02}h {m:02}m",
+        "duration.days_hours_minutes" => "{d}d {h:02}h {m:02}m",
+        "duration.hours_minutes" => "{d}d {h:02}h {m:02}m",
+        "duration.minutes" => "{d}d {h:02}h {m:02}m",
+        "duration.hours_minutes_seconds" => "{h}h {m:02}m {s:02}s",
+        "panel.no_upcoming" => "No upcoming cleaning",
+        "panel.snooze" => "Remind me next time",
+        "invalid_reason.no_data" => "No cleaning or parking data found for this address",
+        "invalid_reason.unparseable_time" => "The cleaning time could not be read",
+        "invalid_reason.day_not_in_month" => "The cleaning day does not exist this month",
+        "invalid_reason.unknown" => "Unknown reason",
         "addresses.title" => "Addresses",
         "addresses.empty" => "No addresses added",
+        "addresses.spaces_tracked" => "parking spaces tracked",
+        "addresses.spaces_unknown" => "unknown count",
         "addresses.confirm_remove_title" => "Confirm removal",
         "addresses.confirm_remove_msg" => "Are you sure you want to remove this address?",
         "confirm_dialog.cancel" => "Cancel",
@@ -224,9 +314,22 @@ fn t_en(key: &str) -> &'static str {
         "info_dialog.status" => "Status:",
         "info_dialog.validated" => "Validated:",
         "info_dialog.taxa" => "Taxa:",
+        "info_dialog.fee_now" => "Fee now:",
+        "info_dialog.fee_free" => "Free",
+        "info_dialog.fee_until" => "until",
         "info_dialog.info" => "Info:",
         "info_dialog.type" => "Type:",
+        "info_dialog.placement" => "Placement:",
+        "info_dialog.per_space" => "m per space",
+        "placement.längsgående" => "Parallel",
+        "placement.sned" => "Angled",
+        "placement.vinkelrät" => "Perpendicular",
         "info_dialog.spots" => "Spots:",
+        "info_dialog.match_reason" => "Match:",
+        "info_dialog.match_quality" => "Precision:",
+        "info_dialog.match_exact" => "✓ Exact",
+        "info_dialog.match_approximate" => "⚠ Approximate",
+        "info_dialog.invalid_reason" => "Reason:",
         "info_dialog.active" => "Active",
         "info_dialog.inactive" => "Inactive",
         "info_dialog.yes" => "Yes",
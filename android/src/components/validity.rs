@@ -41,8 +41,73 @@
 //!     println!("Some addresses changed validity");
 //! }
 //! ```
+//!
+//! # Change Details
+//!
+//! [`recompute_validity`] does the same update but returns one
+//! [`ValidityChange`] per address that flipped, including the panel bucket
+//! before and after, so callers can notify or log without bucketing again:
+//!
+//! ```no_run
+//! use amp_android::components::validity::recompute_validity;
+//! use amp_core::buckets::BucketSettings;
+//! # let mut addresses: Vec<amp_android::ui::StoredAddress> = vec![];
+//!
+//! let bounds = BucketSettings::default();
+//! for change in recompute_validity(&mut addresses, chrono::Utc::now(), &bounds) {
+//!     println!("{}: {:?} -> {:?}", change.id, change.old_bucket, change.new_bucket);
+//! }
+//! ```
+use crate::components::countdown::TimeBucket;
 use crate::ui::StoredAddress;
-use chrono::{Datelike, Local};
+use amp_core::buckets::{self, BucketSettings};
+use chrono::{DateTime, Datelike, Local, Utc};
+/// Why an address ended up in the invalid panel
+///
+/// Stored on [`StoredAddress::invalid_reason`] as a stable code (see
+/// [`InvalidReason::code`]) so persisted data does not depend on the UI
+/// language. The localized explanation is looked up at render time through
+/// [`InvalidReason::translation_key`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InvalidReason {
+    /// No cleaning or parking data was found for the address
+    NoData,
+    /// Matched data exists but its `tid` could not be parsed
+    UnparseableTime,
+    /// The restriction day does not exist in the current month
+    DayNotInMonth,
+}
+impl InvalidReason {
+    /// All reasons, in display order
+    pub const ALL: [InvalidReason; 3] = [
+        InvalidReason::NoData,
+        InvalidReason::UnparseableTime,
+        InvalidReason::DayNotInMonth,
+    ];
+    /// Stable, language-independent code used for storage
+    pub fn code(&self) -> &'static str {
+        match self {
+            InvalidReason::NoData => "no_data",
+            InvalidReason::UnparseableTime => "unparseable_time",
+            InvalidReason::DayNotInMonth => "day_not_in_month",
+        }
+    }
+    /// Parse a stored code back into a reason
+    ///
+    /// # Returns
+    /// `None` for unknown codes, e.g. ones written by a newer app version
+    pub fn from_code(code: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|reason| reason.code() == code)
+    }
+    /// Key into [`t`](crate::components::translations::t) for the user-facing text
+    pub fn translation_key(&self) -> &'static str {
+        match self {
+            InvalidReason::NoData => "invalid_reason.no_data",
+            InvalidReason::UnparseableTime => "invalid_reason.unparseable_time",
+            InvalidReason::DayNotInMonth => "invalid_reason.day_not_in_month",
+        }
+    }
+}
 /// Check if the current year is a leap year
 ///
 /// # Arguments
@@ -104,17 +169,34 @@ pub fn days_in_month(month: u32, year: i32) -> u32 {
 /// // In January:
 /// assert!(is_valid_in_current_month(Some(31)));
 /// ```
+#[allow(dead_code)]
 pub fn is_valid_in_current_month(dag: Option<u8>) -> bool {
+    is_valid_at(dag, Utc::now())
+}
+/// [`is_valid_in_current_month`] for the local month containing `now`
+pub fn is_valid_at(dag: Option<u8>, now: DateTime<Utc>) -> bool {
     let dag = match dag {
         Some(d) => d as u32,
         None => return true,
     };
-    let now = Local::now();
+    let now = now.with_timezone(&Local);
     let current_month = now.month();
     let current_year = now.year();
     let max_days = days_in_month(current_month, current_year);
     dag <= max_days
 }
+/// One address whose validity flipped during [`recompute_validity`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidityChange {
+    /// [`StoredAddress::id`] of the changed address
+    pub id: u64,
+    pub old_valid: bool,
+    pub new_valid: bool,
+    /// Panel bucket before the change
+    pub old_bucket: TimeBucket,
+    /// Panel bucket after the change ([`TimeBucket::Invalid`] when it became invalid)
+    pub new_bucket: TimeBucket,
+}
 /// Check and update validity for all addresses based on current month
 ///
 /// Iterates through all addresses and updates their `valid` field if their
@@ -140,26 +222,65 @@ pub fn is_valid_in_current_month(dag: Option<u8>) -> bool {
 /// }
 /// ```
 pub fn check_and_update_validity(addresses: &mut [StoredAddress]) -> bool {
-    let mut changed = false;
+    // Only whether anything flipped is reported, so the bucket bounds don't matter
+    !recompute_validity(addresses, Utc::now(), &BucketSettings::default()).is_empty()
+}
+/// Update validity for the month containing `now` and report what changed
+///
+/// Same rules and side effects as [`check_and_update_validity`]. The buckets
+/// in each [`ValidityChange`] are computed with `bounds`, normally the user's
+/// [`NotificationSettings::bucket_settings`].
+///
+/// [`NotificationSettings::bucket_settings`]: crate::components::settings::NotificationSettings::bucket_settings
+///
+/// # Returns
+/// One [`ValidityChange`] per address whose `valid` flag flipped, in input order
+pub fn recompute_validity(
+    addresses: &mut [StoredAddress],
+    now: DateTime<Utc>,
+    bounds: &BucketSettings,
+) -> Vec<ValidityChange> {
+    let mut changes = Vec::new();
     for addr in addresses.iter_mut() {
+        // With several days, the earliest existing this month is enough
         let dag = addr
             .matched_entry
             .as_ref()
-            .map(|entry| entry.start_time_swedish().day() as u8);
-        let should_be_valid = is_valid_in_current_month(dag);
+            .and_then(|entry| entry.dagar.first().copied());
+        let should_be_valid = is_valid_at(dag, now);
         if addr.matched_entry.is_some() && addr.valid != should_be_valid {
             eprintln!(
                 "[Validity] Address {} {} validity changed: {} -> {}",
                 addr.street, addr.street_number, addr.valid, should_be_valid,
             );
+            let old_valid = addr.valid;
+            let old_bucket = address_bucket(addr, now, bounds);
             addr.valid = should_be_valid;
-            changed = true;
+            addr.invalid_reason =
+                (!should_be_valid).then(|| InvalidReason::DayNotInMonth.code().to_string());
+            changes.push(ValidityChange {
+                id: addr.id,
+                old_valid,
+                new_valid: should_be_valid,
+                old_bucket,
+                new_bucket: address_bucket(addr, now, bounds),
+            });
         }
     }
-    if changed {
-        eprintln!("[Validity] Address validity updated for current month");
+    if !changes.is_empty() {
+        eprintln!(
+            "[Validity] Address validity updated for current month ({} changed)",
+            changes.len(),
+        );
+    }
+    changes
+}
+/// Panel bucket of an address: its restriction's bucket, or Invalid if it is not valid
+fn address_bucket(addr: &StoredAddress, now: DateTime<Utc>, bounds: &BucketSettings) -> TimeBucket {
+    match &addr.matched_entry {
+        Some(entry) if addr.valid => buckets::bucket_for(entry, now, bounds),
+        _ => TimeBucket::Invalid,
     }
-    changed
 }
 #[cfg(test)]
 mod tests {
@@ -185,4 +306,77 @@ mod tests {
     fn test_validity_no_restriction() {
         assert!(is_valid_in_current_month(None));
     }
+    #[test]
+    fn test_invalid_reason_code_roundtrip() {
+        for reason in InvalidReason::ALL {
+            assert_eq!(InvalidReason::from_code(reason.code()), Some(reason));
+        }
+        assert_eq!(InvalidReason::from_code("not_a_reason"), None);
+    }
+    #[test]
+    fn test_recompute_validity_reports_changes() {
+        use amp_core::structs::DB;
+        use chrono::TimeZone;
+        let entry = |day: u8| {
+            DB::from_dag_tid(
+                Some("22100".to_string()),
+                "Storgatan 10".to_string(),
+                Some("Storgatan".to_string()),
+                Some("10".to_string()),
+                None,
+                day,
+                "0800-1200",
+                None,
+                None,
+                None,
+                2023,
+                1,
+            )
+        };
+        let address = |id: u64, day: u8| StoredAddress {
+            id,
+            street: "Storgatan".to_string(),
+            street_number: "10".to_string(),
+            postal_code: "22100".to_string(),
+            valid: true,
+            active: true,
+            matched_entry: entry(day),
+            parking_info: None,
+            match_reason: None,
+            approximate: false,
+            invalid_reason: None,
+            snoozed_until: None,
+        };
+        let mut addresses = vec![address(1, 15), address(2, 30)];
+        let february = Utc.with_ymd_and_hms(2023, 2, 10, 12, 0, 0).unwrap();
+        let bounds = BucketSettings::default();
+        let old_bucket = buckets::bucket_for(
+            addresses[1].matched_entry.as_ref().unwrap(),
+            february,
+            &bounds,
+        );
+        let changes = recompute_validity(&mut addresses, february, &bounds);
+        assert_eq!(
+            changes,
+            vec![ValidityChange {
+                id: 2,
+                old_valid: true,
+                new_valid: false,
+                old_bucket,
+                new_bucket: TimeBucket::Invalid,
+            }]
+        );
+        assert!(addresses[0].valid);
+        assert!(!addresses[1].valid);
+        assert_eq!(
+            addresses[1].invalid_reason.as_deref(),
+            Some(InvalidReason::DayNotInMonth.code())
+        );
+        let march = Utc.with_ymd_and_hms(2023, 3, 10, 12, 0, 0).unwrap();
+        let changes = recompute_validity(&mut addresses, march, &bounds);
+        assert_eq!(changes.len(), 1);
+        assert!(changes[0].new_valid);
+        assert_eq!(changes[0].old_bucket, TimeBucket::Invalid);
+        assert!(recompute_validity(&mut addresses, march, &bounds).is_empty());
+    }
 }
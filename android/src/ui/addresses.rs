@@ -61,7 +61,7 @@
 //! ## Event Handlers
 //!
 //! ```rust,ignore
-//! let handle_toggle = move |id: usize| {
+//! let handle_toggle = move |id: u64| {
 //!     let mut addrs = addresses.write();
 //!     if let Some(addr) = addrs.iter_mut().find(|a| a.id == id) {
 //!         addr.active = !addr.active;
@@ -69,7 +69,7 @@
 //!     }
 //! };
 //!
-//! let handle_remove = move |id: usize| {
+//! let handle_remove = move |id: u64| {
 //!     let mut addrs = addresses.write();
 //!     addrs.retain(|a| a.id != id);
 //!     // Persist to storage
@@ -93,6 +93,7 @@
 //! - [`InfoDialog`]: Parking details modal
 //! - [`crate::ui::StoredAddress`]: Address data structure
 use crate::components::settings::AppSettings;
+use crate::components::stats::total_affected_spaces;
 use crate::components::translations::t;
 use crate::ui::StoredAddress;
 use crate::ui::confirm_dialog::ConfirmDialog;
@@ -179,11 +180,11 @@ use dioxus_free_icons::icons::md_navigation_icons::MdExpandLess;
 #[component]
 pub fn Addresses(
     stored_addresses: Vec<StoredAddress>,
-    on_toggle_active: EventHandler<usize>,
-    on_remove_address: EventHandler<usize>,
+    on_toggle_active: EventHandler<u64>,
+    on_remove_address: EventHandler<u64>,
 ) -> Element {
     let mut show_confirm = use_signal(|| false);
-    let mut pending_remove_id = use_signal(|| None::<usize>);
+    let mut pending_remove_id = use_signal(|| None::<u64>);
     let mut show_info = use_signal(|| false);
     let mut selected_address = use_signal(|| None::<StoredAddress>);
     let mut sorted_addresses = stored_addresses.clone();
@@ -194,7 +195,7 @@ pub fn Addresses(
             _ => a.postal_code.cmp(&b.postal_code),
         },
     );
-    let mut handle_remove_click = move |addr_id: usize| {
+    let mut handle_remove_click = move |addr_id: u64| {
         info!("Remove button clicked for address id: {}", addr_id);
         pending_remove_id.set(Some(addr_id));
         show_confirm.set(true);
@@ -227,8 +228,20 @@ pub fn Addresses(
     };
     let mut is_open = use_signal(|| false);
     let count = stored_addresses.len();
+    let space_totals = total_affected_spaces(&stored_addresses);
     let app_settings = use_context::<Signal<AppSettings>>();
     let tr = move |key: &'static str| t(key, &app_settings().language);
+    let spaces_text = (space_totals.counted > 0).then(|| {
+        let mut text = format!("{} {}", space_totals.spaces, tr("addresses.spaces_tracked"));
+        if space_totals.unknown > 0 {
+            text.push_str(&format!(
+                " (+{} {})",
+                space_totals.unknown,
+                tr("addresses.spaces_unknown")
+            ));
+        }
+        text
+    });
     rsx! {
         div { class: "category-container category-addresses",
             button {
@@ -293,6 +306,9 @@ pub fn Addresses(
                                 })
                         }
                     }
+                    if let Some(text) = spaces_text {
+                        div { class: "address-stats", "{text}" }
+                    }
                 }
             }
             ConfirmDialog {
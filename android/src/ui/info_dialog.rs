@@ -1,7 +1,69 @@
-use crate::components::settings::AppSettings;
-use crate::components::translations::t;
+use crate::components::settings::{AppSettings, Language};
+use crate::components::translations::{t, t_invalid_reason};
 use crate::ui::StoredAddress;
+use amp_core::parking::{ParkingPlacement, TaxaSchedule};
+use amp_core::structs::{MatchQuality, SWEDISH_TZ};
+use chrono::Utc;
 use dioxus::prelude::*;
+/// Current paid-parking status for a taxa string, e.g. "20 kr/tim till 22:00"
+///
+/// Shown separately from the miljö restriction: a street can be free of
+/// cleaning and still cost money, or the other way around.
+///
+/// # Returns
+/// `None` if the taxa has no recognisable rate
+fn fee_now_text(taxa: &str, language: &Language) -> Option<String> {
+    let status = TaxaSchedule::parse(taxa)?.status_at(Utc::now());
+    let now = match status.rate_kr_per_hour {
+        Some(rate) => format!("{} kr/tim", rate),
+        None => t("info_dialog.fee_free", language).to_string(),
+    };
+    Some(match status.until {
+        Some(until) => format!(
+            "{} {} {}",
+            now,
+            t("info_dialog.fee_until", language),
+            until.with_timezone(&SWEDISH_TZ).format("%H:%M"),
+        ),
+        None => now,
+    })
+}
+/// Translation key for how close the matched segment is
+///
+/// # Returns
+/// `None` for [`MatchQuality::None`], which has nothing to show
+fn match_quality_key(quality: MatchQuality) -> Option<&'static str> {
+    match quality {
+        MatchQuality::Exact => Some("info_dialog.match_exact"),
+        MatchQuality::Approximate => Some("info_dialog.match_approximate"),
+        MatchQuality::None => None,
+    }
+}
+/// Space placement as shown to the user, e.g. "Längsgående, 6 m per plats"
+///
+/// # Returns
+/// `None` if neither orientation nor space length is known
+fn placement_text(
+    orientation: Option<&str>,
+    space_length_m: Option<f64>,
+    language: &Language,
+) -> Option<String> {
+    let orientation = orientation.map(|name| t(&format!("placement.{}", name), language));
+    let length = space_length_m.map(|meters| {
+        let meters = meters.to_string();
+        let meters = match language {
+            Language::English => meters,
+            _ => meters.replace('.', ","),
+        };
+        format!("{} {}", meters, t("info_dialog.per_space", language))
+    });
+    match (orientation, length) {
+        (Some(orientation), Some(length)) => Some(format!("{}, {}", orientation, length)),
+        (Some(orientation), None) => Some(orientation.to_string()),
+        (None, Some(length)) => Some(length),
+        (None, None) => None,
+    }
+}
 /// Information dialog component for displaying address details
 ///
 /// Shows comprehensive address information in a modal overlay with formatted rows.
@@ -86,6 +148,12 @@ pub fn InfoDialog(
                                 span { class: "info-label", {tr("info_dialog.taxa")} }
                                 span { class: "info-value", "{taxa}" }
                             }
+                            if let Some(fee) = fee_now_text(taxa, &app_settings().language) {
+                                div { class: "info-row",
+                                    span { class: "info-label", {tr("info_dialog.fee_now")} }
+                                    span { class: "info-value", "{fee}" }
+                                }
+                            }
                         }
                         if let Some(ref info) = entry.info {
                             div { class: "info-row",
@@ -99,6 +167,16 @@ pub fn InfoDialog(
                                 span { class: "info-value", "{typ}" }
                             }
                         }
+                        if let Some(placement) = placement_text(
+                            entry.orientation.as_deref(),
+                            entry.space_length_m,
+                            &app_settings().language,
+                        ) {
+                            div { class: "info-row",
+                                span { class: "info-label", {tr("info_dialog.placement")} }
+                                span { class: "info-value", "{placement}" }
+                            }
+                        }
                         if let Some(platser) = entry.antal_platser {
                             div { class: "info-row",
                                 span { class: "info-label", {tr("info_dialog.spots")} }
@@ -113,12 +191,30 @@ pub fn InfoDialog(
                                     span { class: "info-label", {tr("info_dialog.taxa")} }
                                     span { class: "info-value", "{taxa}" }
                                 }
+                                if let Some(fee) = fee_now_text(taxa, &app_settings().language) {
+                                    div { class: "info-row",
+                                        span { class: "info-label", {tr("info_dialog.fee_now")} }
+                                        span { class: "info-value", "{fee}" }
+                                    }
+                                }
                             }
                             if let Some(ref typ) = parking.typ_av_parkering {
                                 div { class: "info-row",
                                     span { class: "info-label", {tr("info_dialog.type")} }
                                     span { class: "info-value", "{typ}" }
                                 }
+                                if let Some(placement) = ParkingPlacement::parse(typ).and_then(|p| {
+                                    placement_text(
+                                        p.orientation.as_deref(),
+                                        p.space_length_m,
+                                        &app_settings().language,
+                                    )
+                                }) {
+                                    div { class: "info-row",
+                                        span { class: "info-label", {tr("info_dialog.placement")} }
+                                        span { class: "info-value", "{placement}" }
+                                    }
+                                }
                             }
                             if let Some(platser) = parking.antal_platser {
                                 div { class: "info-row",
@@ -128,6 +224,32 @@ pub fn InfoDialog(
                             }
                         }
                     }
+                    if let Some(ref reason) = addr.match_reason {
+                        div { class: "info-row",
+                            span { class: "info-label", {tr("info_dialog.match_reason")} }
+                            span { class: "info-value", "{reason}" }
+                        }
+                    }
+                    if let Some(quality) = addr.matched_entry.as_ref().and_then(|e| e.match_quality) {
+                        if let Some(key) = match_quality_key(quality) {
+                            div { class: "info-row",
+                                span { class: "info-label", {tr("info_dialog.match_quality")} }
+                                span { class: if quality == MatchQuality::Exact { "info-value status-active" } else { "info-value status-inactive" },
+                                    {tr(key)}
+                                }
+                            }
+                        }
+                    }
+                    if !addr.valid {
+                        if let Some(ref code) = addr.invalid_reason {
+                            div { class: "info-row",
+                                span { class: "info-label", {tr("info_dialog.invalid_reason")} }
+                                span { class: "info-value",
+                                    {t_invalid_reason(code, &app_settings().language)}
+                                }
+                            }
+                        }
+                    }
                 }
                 div { class: "modal-actions",
                     button {
@@ -52,7 +52,7 @@
 //! ```rust
 //! # use amp_core::structs::DB;
 //! pub struct StoredAddress {
-//!     pub id: usize,              // UUID-based unique ID
+//!     pub id: u64,                // UUID-based unique ID
 //!     pub street: String,         // "Storgatan"
 //!     pub street_number: String,  // "10"
 //!     pub postal_code: String,    // "22100"
@@ -178,14 +178,19 @@ pub mod info_dialog;
 pub mod panels;
 pub mod settings_dropdown;
 pub mod top_bar;
-use crate::components::address_utils::normalize_string;
+use crate::components::address_utils::{
+    NumberMatch, match_street_name, match_street_number, normalize_string,
+};
+use crate::components::countdown::TimeBucket;
 use crate::components::debug::load_debug_addresses;
 use crate::components::lifecycle::{LifecycleManager, handle_active_toggle, handle_address_change};
 use crate::components::matching::{MatchResult, match_address};
+use crate::components::notifications::take_requested_panel;
 use crate::components::settings::{Theme, load_settings};
 use crate::components::storage::{read_addresses_from_device, write_addresses_to_device};
-use crate::components::validity::check_and_update_validity;
-use amp_core::structs::DB;
+use crate::components::validity::{InvalidReason, check_and_update_validity};
+use amp_core::structs::{DB, LocalData, SWEDISH_TZ};
+use chrono::{DateTime, Utc};
 use dioxus::prelude::*;
 use std::sync::{Arc, Mutex};
 use uuid::Uuid;
@@ -234,7 +239,7 @@ pub struct ParkingInfo {
 #[derive(Clone, Debug, PartialEq)]
 pub struct StoredAddress {
     /// Unique stable identifier (UUID v4)
-    pub id: usize,
+    pub id: u64,
     /// Street name (e.g., "Storgatan")
     pub street: String,
     /// Street number (e.g., "10" or "10A")
@@ -249,6 +254,20 @@ pub struct StoredAddress {
     pub matched_entry: Option<DB>,
     /// Parking-only data (when address has parking zone info but no street cleaning schedule)
     pub parking_info: Option<ParkingInfo>,
+    /// Why the matcher picked `matched_entry` (e.g. "exact", "substring dist=2")
+    pub match_reason: Option<String>,
+    /// Whether the match is approximate: only the numeric part of the street
+    /// number matched, so "10A" may be shown with the schedule of "10"
+    pub approximate: bool,
+    /// Stable code explaining why the address is invalid (see [`InvalidReason`])
+    ///
+    /// [`InvalidReason`]: crate::components::validity::InvalidReason
+    pub invalid_reason: Option<String>,
+    /// UTC epoch seconds until which the address is left out of the time
+    /// panels, see [`LocalData::snooze_to_next_occurrence`]
+    ///
+    /// [`LocalData::snooze_to_next_occurrence`]: amp_core::structs::LocalData::snooze_to_next_occurrence
+    pub snoozed_until: Option<i64>,
 }
 impl StoredAddress {
     /// Format the address for display.
@@ -265,6 +284,46 @@ impl StoredAddress {
             )
         }
     }
+    /// Whether the address is snoozed at `now`
+    ///
+    /// Snoozed addresses are left out of the time panels and get no
+    /// notifications until `snoozed_until` passes.
+    pub fn is_snoozed(&self, now: DateTime<Utc>) -> bool {
+        self.snoozed_until
+            .is_some_and(|until| now.timestamp() < until)
+    }
+    /// Snooze the address until its restriction next starts after `now`
+    ///
+    /// Uses [`LocalData::snooze_to_next_occurrence`] on the matched
+    /// restriction, so snoozing during a cleaning lasts until the next one.
+    ///
+    /// # Returns
+    /// The instant the snooze ends, or `None` for addresses without a matched
+    /// restriction, which are left unchanged
+    pub fn snooze(&mut self, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let entry = self.matched_entry.as_ref()?;
+        let mut local = LocalData {
+            valid: self.valid,
+            active: self.active,
+            postnummer: None,
+            adress: self.display_name(),
+            gata: None,
+            gatunummer: None,
+            info: None,
+            tid: Some(entry.tid()),
+            dag: Some(entry.dag()),
+            dagar: entry.dagar.clone(),
+            taxa: None,
+            antal_platser: None,
+            typ_av_parkering: None,
+            match_reason: None,
+            match_quality: None,
+            snoozed_until: self.snoozed_until,
+        };
+        let until = local.snooze_to_next_occurrence(now.with_timezone(&SWEDISH_TZ))?;
+        self.snoozed_until = local.snoozed_until;
+        Some(until)
+    }
     /// Create a new stored address and attempt to match against database
     ///
     /// Generates a new UUID v4 for the address ID and performs fuzzy matching
@@ -291,10 +350,26 @@ impl StoredAddress {
     /// println!("Valid: {}, Active: {}", addr.valid, addr.active);
     /// ```
     pub fn new(street: String, street_number: String, postal_code: String) -> Self {
-        let fuzzy_match_result = fuzzy_match_address(&street, &street_number, &postal_code);
-        let (db_valid, matched_entry) = match fuzzy_match_result {
-            Some(entry) => (true, Some(entry)),
-            None => (false, None),
+        let exact = match_address(&street, &street_number, &postal_code);
+        Self::from_match(street, street_number, postal_code, exact)
+    }
+    /// Create a new stored address from an already resolved exact match
+    ///
+    /// Lets bulk flows resolve every address with [`match_many`] first. The
+    /// fuzzy fallback still runs for addresses whose exact match is
+    /// [`MatchResult::Invalid`].
+    ///
+    /// [`match_many`]: crate::components::matching::match_many
+    pub fn from_match(
+        street: String,
+        street_number: String,
+        postal_code: String,
+        exact: MatchResult,
+    ) -> Self {
+        let fuzzy_match_result = fuzzy_match_address(exact, &street, &street_number, &postal_code);
+        let (db_valid, matched_entry, match_reason, approximate) = match fuzzy_match_result {
+            Some(found) => (true, Some(found.entry), Some(found.reason), found.approximate),
+            None => (false, None, None, false),
         };
         let parking_info = if matched_entry.as_ref().is_none_or(|e| e.taxa.is_none()) {
             use crate::components::static_data::get_parking_only_entry;
@@ -304,8 +379,9 @@ impl StoredAddress {
             None
         };
         let valid = db_valid || parking_info.is_some();
+        let invalid_reason = (!valid).then(|| InvalidReason::NoData.code().to_string());
         let uuid = Uuid::new_v4();
-        let id = uuid_to_usize(&uuid);
+        let id = uuid_to_id(&uuid);
         StoredAddress {
             id,
             street,
@@ -315,26 +391,53 @@ impl StoredAddress {
             active: true,
             matched_entry,
             parking_info,
+            match_reason,
+            approximate,
+            invalid_reason,
+            snoozed_until: None,
         }
     }
 }
-/// Convert UUID to usize for ID storage
+/// Convert UUID to a `u64` for ID storage
 ///
-/// Uses the first 8 bytes of the UUID as a usize.
-/// Collision probability is negligible for typical app usage
+/// Uses the first 8 bytes of the UUID, little-endian. The ID is `u64` rather
+/// than `usize` so it keeps all 64 bits on 32-bit Android targets too;
+/// collision probability is negligible for typical app usage
 /// (< 1 in 18 quintillion for 1000 addresses).
 ///
 /// # Arguments
 /// * `uuid` - UUID to convert
 ///
 /// # Returns
-/// usize derived from UUID bytes
-fn uuid_to_usize(uuid: &Uuid) -> usize {
+/// u64 derived from UUID bytes
+fn uuid_to_id(uuid: &Uuid) -> u64 {
     let bytes = uuid.as_bytes();
-    usize::from_le_bytes([
+    u64::from_le_bytes([
         bytes[0], bytes[1], bytes[2], bytes[3], bytes[4], bytes[5], bytes[6], bytes[7],
     ])
 }
+/// Suffix of a [`fuzzy_match_address`] reason when only the numeric part of
+/// the street number matched
+const APPROXIMATE_NUMBER_REASON: &str = "number prefix";
+/// Whether a match reason from [`fuzzy_match_address`] is approximate
+///
+/// True when the street number matched on its numeric part only, e.g. "10A"
+/// saved against a database entry for "10". Only needed for reasons read back
+/// from storage; fresh matches carry [`FuzzyMatch::approximate`].
+#[cfg(target_os = "android")]
+pub(crate) fn is_approximate_reason(reason: &str) -> bool {
+    reason.ends_with(APPROXIMATE_NUMBER_REASON)
+}
+/// A match found by [`fuzzy_match_address`]
+#[derive(Debug, Clone)]
+pub(crate) struct FuzzyMatch {
+    /// The matched database entry
+    pub entry: DB,
+    /// Matching stage that succeeded, see [`fuzzy_match_address`]
+    pub reason: String,
+    /// Only the numeric part of the street number matched, e.g. "10A" to "10"
+    pub approximate: bool,
+}
 /// Fuzzy match address against database using Levenshtein distance
 ///
 /// Implements multi-stage matching strategy:
@@ -347,85 +450,131 @@ fn uuid_to_usize(uuid: &Uuid) -> usize {
 /// into another. This catches common typos and variations.
 ///
 /// # Arguments
+/// * `exact` - Result of the exact lookup, used as-is when valid
 /// * `street` - Street name (case-insensitive)
 /// * `street_number` - Street number (exact match required)
 /// * `postal_code` - Postal code (exact match required)
 ///
 /// # Returns
-/// Some(DB) if match found within threshold, None otherwise
+/// Some([`FuzzyMatch`]) if match found within threshold, None otherwise.
+/// The reason is a short machine-set string describing which stage matched:
+/// - `"exact"`: Direct database lookup succeeded
+/// - `"normalized"`: Street names equal after normalization
+/// - `"substring dist=N"`: One street name contains the other (N = Levenshtein distance)
+///
+/// Either of the last two gets `" transliterated"` appended when the names
+/// only matched with å/ä/ö folded to a/o (see [`match_street_name`]), and
+/// `" number prefix"` when only the numeric part of the street number
+/// matched, which also sets [`FuzzyMatch::approximate`].
 ///
 /// # Matching Rules
-/// - Postal code and street number must match exactly (after normalization)
+/// - Postal code must match exactly (after normalization)
+/// - Street number must match exactly, or failing that on its numeric part
+///   ("10A" ↔ "10", see [`match_street_number`])
 /// - Street name can have typos up to [`MAX_LEVENSHTEIN_DISTANCE`] (3 chars)
 /// - Case-insensitive throughout
 /// - Whitespace normalized
+/// - Street names are compared as typed first, then with Swedish characters
+///   transliterated, so "ostra" finds "Östra"
 ///
 /// # Examples
 /// ```no_run
+/// use amp_android::matching::match_address;
 /// use amp_android::ui::fuzzy_match_address;
 ///
 /// // Exact match
-/// let result = fuzzy_match_address("Storgatan", "10", "22100");
+/// let result = fuzzy_match_address(match_address("Storgatan", "10", "22100"), "Storgatan", "10", "22100");
 /// assert!(result.is_some());
 ///
 /// // Typo (missing 'a')
-/// let result = fuzzy_match_address("Storgtan", "10", "22100");
+/// let result = fuzzy_match_address(match_address("Storgtan", "10", "22100"), "Storgtan", "10", "22100");
 /// assert!(result.is_some());
 ///
 /// // Case variation
-/// let result = fuzzy_match_address("STORGATAN", "10", "22100");
+/// let result = fuzzy_match_address(match_address("STORGATAN", "10", "22100"), "STORGATAN", "10", "22100");
 /// assert!(result.is_some());
 ///
 /// // Substring
-/// let result = fuzzy_match_address("Storga", "10", "22100");
+/// let result = fuzzy_match_address(match_address("Storga", "10", "22100"), "Storga", "10", "22100");
 /// assert!(result.is_some());
 ///
 /// // Too many typos (>3)
-/// let result = fuzzy_match_address("Strgn", "10", "22100");
+/// let result = fuzzy_match_address(match_address("Strgn", "10", "22100"), "Strgn", "10", "22100");
 /// assert!(result.is_none());
 /// ```
-fn fuzzy_match_address(street: &str, street_number: &str, postal_code: &str) -> Option<DB> {
-    match match_address(street, street_number, postal_code) {
-        MatchResult::Valid(entry) => return Some(*entry),
+pub(crate) fn fuzzy_match_address(
+    exact: MatchResult,
+    street: &str,
+    street_number: &str,
+    postal_code: &str,
+) -> Option<FuzzyMatch> {
+    match exact {
+        MatchResult::Valid(entry) => {
+            return Some(FuzzyMatch {
+                entry: *entry,
+                reason: "exact".to_string(),
+                approximate: false,
+            });
+        }
         MatchResult::Invalid => {}
     }
     use crate::components::matching::get_parking_data;
     let data = get_parking_data();
-    let street_norm = normalize_string(street);
-    let street_number_norm = normalize_string(street_number);
+    let street_number_norm = normalize_string(street_number, false);
     let postal_code_norm = postal_code.trim().replace(' ', "");
+    let mut same_number: Option<(&DB, String, String)> = None;
     for entry in data.values() {
-        let entry_street_norm = entry
-            .gata
-            .as_ref()
-            .map(|s| normalize_string(s))
-            .unwrap_or_default();
+        let entry_street = entry.gata.as_deref().unwrap_or_default();
         let entry_number_norm = entry
             .gatunummer
             .as_ref()
-            .map(|s| normalize_string(s))
+            .map(|s| normalize_string(s, false))
             .unwrap_or_default();
         let entry_postal_norm = entry
             .postnummer
             .as_ref()
             .map(|pn| pn.replace(' ', ""))
             .unwrap_or_default();
-        let street_distance = strsim::levenshtein(&street_norm, &entry_street_norm);
-        let street_match = if street_norm == entry_street_norm {
-            true
-        } else {
-            entry_street_norm.contains(&street_norm) || street_norm.contains(&entry_street_norm)
-        };
-        let number_match = entry_number_norm == street_number_norm;
+        let street_match = match_street_name(street, entry_street);
+        let number_match = match_street_number(&street_number_norm, &entry_number_norm);
         let postal_match = postal_code_norm.is_empty() || entry_postal_norm == postal_code_norm;
-        if street_match && number_match && postal_match {
-            eprintln!(
-                "[FuzzyMatch] Found match: '{}' matches '{}' (distance: {})",
-                street, entry_street_norm, street_distance,
-            );
-            return Some(entry.clone());
+        let Some(reason) = street_match.filter(|_| postal_match) else {
+            continue;
+        };
+        match number_match {
+            Some(NumberMatch::Exact) => {
+                eprintln!(
+                    "[FuzzyMatch] Found match: '{}' matches '{}' ({})",
+                    street, entry_street, reason,
+                );
+                return Some(FuzzyMatch {
+                    entry: entry.clone(),
+                    reason,
+                    approximate: false,
+                });
+            }
+            // Lowest number wins so the fallback doesn't depend on map order
+            Some(NumberMatch::SameNumber)
+                if same_number
+                    .as_ref()
+                    .is_none_or(|(_, number, _)| entry_number_norm < *number) =>
+            {
+                same_number = Some((entry, entry_number_norm, reason));
+            }
+            _ => {}
         }
     }
+    if let Some((entry, number, reason)) = same_number {
+        eprintln!(
+            "[FuzzyMatch] Found approximate match: '{} {}' matches number '{}' ({})",
+            street, street_number, number, reason,
+        );
+        return Some(FuzzyMatch {
+            entry: entry.clone(),
+            reason: format!("{} {}", reason, APPROXIMATE_NUMBER_REASON),
+            approximate: true,
+        });
+    }
     eprintln!(
         "[FuzzyMatch] No match found for: {} {} {}",
         street, street_number, postal_code,
@@ -436,7 +585,7 @@ use crate::ui::{
     addresses::Addresses,
     panels::{
         ActivePanel, InvalidPanel, MoreThan1MonthPanel, OneDayPanel, OneMonthPanel,
-        ParkingOnlyPanel, SixHoursPanel,
+        ParkingOnlyPanel, SixHoursPanel, StreetGroupsPanel,
     },
     top_bar::TopBar,
 };
@@ -482,6 +631,7 @@ use crate::ui::{
 /// - `handle_add_address`: Add new address with duplicate detection
 /// - `handle_toggle_active`: Toggle address visibility in panels
 /// - `handle_remove_address`: Delete address from storage
+/// - `handle_snooze_address`: Snooze an active address until its next cleaning
 /// - `handle_toggle_debug`: Switch between user data and debug data
 ///
 /// # Examples
@@ -503,6 +653,17 @@ pub fn App() -> Element {
     let mut lifecycle_manager = use_signal::<Option<Arc<Mutex<LifecycleManager>>>>(|| None);
     let app_settings = use_signal(load_settings);
     provide_context(app_settings);
+    let mut requested_panel = use_signal(|| None::<TimeBucket>);
+    provide_context(requested_panel);
+    use_future(move || async move {
+        loop {
+            if let Some(bucket) = take_requested_panel() {
+                info!("Opening {:?} panel from notification", bucket);
+                requested_panel.set(Some(bucket));
+            }
+            tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+        }
+    });
     use_effect(move || {
         let mut manager = LifecycleManager::new();
         manager.start();
@@ -550,9 +711,10 @@ pub fn App() -> Element {
         let new_addr = StoredAddress::new(street, street_number, postal_code);
         let mut addrs = stored_addresses.write();
         let is_duplicate = addrs.iter().any(|a| {
-            let street_match = normalize_string(&a.street) == normalize_string(&new_addr.street);
-            let number_match =
-                normalize_string(&a.street_number) == normalize_string(&new_addr.street_number);
+            let street_match =
+                normalize_string(&a.street, false) == normalize_string(&new_addr.street, false);
+            let number_match = normalize_string(&a.street_number, false)
+                == normalize_string(&new_addr.street_number, false);
             let postal_match = if a.postal_code.is_empty() || new_addr.postal_code.is_empty() {
                 true
             } else {
@@ -569,7 +731,7 @@ pub fn App() -> Element {
             warn!("Duplicate address detected (case-insensitive), not adding");
         }
     };
-    let handle_toggle_active = move |id: usize| {
+    let handle_toggle_active = move |id: u64| {
         info!("toggle_active called for id {}", id);
         let mut addrs = stored_addresses.write();
         if let Some(addr) = addrs.iter_mut().find(|a| a.id == id) {
@@ -582,7 +744,7 @@ pub fn App() -> Element {
             }
         }
     };
-    let handle_remove_address = move |id: usize| {
+    let handle_remove_address = move |id: u64| {
         if debug_mode() {
             warn!("Cannot remove addresses in debug mode (read-only)");
             return;
@@ -598,6 +760,21 @@ pub fn App() -> Element {
             handle_address_change(&addrs);
         }
     };
+    let handle_snooze_address = move |id: u64| {
+        let mut addrs = stored_addresses.write();
+        let Some(addr) = addrs.iter_mut().find(|a| a.id == id) else {
+            return;
+        };
+        match addr.snooze(Utc::now()) {
+            Some(until) => info!("Address {} snoozed until {}", id, until),
+            None => warn!("Address {} has no restriction to snooze", id),
+        }
+        if !debug_mode() {
+            handle_active_toggle(&addrs);
+        } else {
+            info!("Debug mode: snooze kept in-memory only (not persisted)");
+        }
+    };
     let handle_toggle_debug = move |_| {
         let new_debug_mode = !debug_mode();
         debug_mode.set(new_debug_mode);
@@ -635,14 +812,81 @@ pub fn App() -> Element {
                 on_toggle_active: handle_toggle_active,
                 on_remove_address: handle_remove_address,
             }
-            ActivePanel { addresses: stored_addresses.read().clone() }
-            SixHoursPanel { addresses: stored_addresses.read().clone() }
-            OneDayPanel { addresses: stored_addresses.read().clone() }
-            OneMonthPanel { addresses: stored_addresses.read().clone() }
-            MoreThan1MonthPanel { addresses: stored_addresses.read().clone() }
-            ParkingOnlyPanel { addresses: stored_addresses.read().clone() }
-            InvalidPanel { addresses: stored_addresses.read().clone() }
+            if app_settings().group_by_street {
+                StreetGroupsPanel { addresses: stored_addresses.read().clone() }
+            } else {
+                ActivePanel {
+                    addresses: stored_addresses.read().clone(),
+                    on_snooze: handle_snooze_address,
+                }
+                SixHoursPanel { addresses: stored_addresses.read().clone() }
+                OneDayPanel { addresses: stored_addresses.read().clone() }
+                OneMonthPanel { addresses: stored_addresses.read().clone() }
+                MoreThan1MonthPanel { addresses: stored_addresses.read().clone() }
+                ParkingOnlyPanel { addresses: stored_addresses.read().clone() }
+                InvalidPanel { addresses: stored_addresses.read().clone() }
+            }
             script {}
         }
     }
 }
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn test_uuid_to_id_uses_first_eight_bytes() {
+        let uuid = Uuid::from_bytes([1, 0, 0, 0, 0, 0, 0, 0, 9, 9, 9, 9, 9, 9, 9, 9]);
+        assert_eq!(uuid_to_id(&uuid), 1);
+    }
+    #[test]
+    fn test_uuid_to_id_keeps_high_bits() {
+        // Same low 4 bytes: these would collide in a 32-bit id
+        let a = Uuid::from_bytes([0xAB, 0xCD, 0xEF, 0x01, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+        let b = Uuid::from_bytes([0xAB, 0xCD, 0xEF, 0x01, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(uuid_to_id(&a) as u32, uuid_to_id(&b) as u32);
+        assert_ne!(uuid_to_id(&a), uuid_to_id(&b));
+    }
+    #[test]
+    fn test_snooze_lasts_until_next_occurrence() {
+        use chrono::TimeZone;
+        let mut addr = StoredAddress {
+            id: 1,
+            street: "Storgatan".to_string(),
+            street_number: "10".to_string(),
+            postal_code: String::new(),
+            valid: true,
+            active: true,
+            matched_entry: DB::from_dag_tid(
+                None,
+                "Storgatan 10".to_string(),
+                Some("Storgatan".to_string()),
+                Some("10".to_string()),
+                None,
+                15,
+                "0800-1200",
+                None,
+                None,
+                None,
+                2024,
+                1,
+            ),
+            parking_info: None,
+            match_reason: None,
+            approximate: false,
+            invalid_reason: None,
+            snoozed_until: None,
+        };
+        let during = SWEDISH_TZ
+            .with_ymd_and_hms(2024, 1, 15, 9, 0, 0)
+            .unwrap()
+            .with_timezone(&Utc);
+        let until = addr.snooze(during).unwrap();
+        assert_eq!(until, Utc.with_ymd_and_hms(2024, 2, 15, 7, 0, 0).unwrap());
+        assert!(addr.is_snoozed(during));
+        assert!(!addr.is_snoozed(until));
+        addr.matched_entry = None;
+        addr.snoozed_until = None;
+        assert_eq!(addr.snooze(during), None);
+        assert_eq!(addr.snoozed_until, None);
+    }
+}
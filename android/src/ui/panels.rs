@@ -36,6 +36,9 @@
 //!          └─ AddressItem (static)
 //! ```
 //!
+//! When [`AppSettings::group_by_street`] is enabled, the App renders
+//! [`StreetGroupsPanel`] instead, with one collapsible group per street.
+//!
 //! # Time Bucketing
 //!
 //! Uses [`TimeBucket`] from the countdown module to categorize addresses:
@@ -80,7 +83,7 @@
 //!
 //! rsx! {
 //!     div { class: "categories-section",
-//!         ActivePanel { addresses: addresses.clone() }
+//!         ActivePanel { addresses: addresses.clone(), on_snooze: handle_snooze }
 //!         SixHoursPanel { addresses: addresses.clone() }
 //!         OneDayPanel { addresses: addresses.clone() }
 //!         OneMonthPanel { addresses: addresses.clone() }
@@ -96,13 +99,14 @@
 //! use amp_android::ui::StoredAddress;
 //! use amp_android::components::countdown::{bucket_for, TimeBucket};
 //!
+//! let bucket_settings = app_settings().notifications.bucket_settings();
 //! let active_now: Vec<StoredAddress> = addresses
 //!     .into_iter()
 //!     .filter(|a| a.valid && a.active)
 //!     .filter(|a| {
 //!         a.matched_entry
 //!             .as_ref()
-//!             .map(|e| matches!(bucket_for(e), TimeBucket::Now))
+//!             .map(|e| matches!(bucket_for(e, &bucket_settings), TimeBucket::Now))
 //!             .unwrap_or(false)
 //!     })
 //!     .collect();
@@ -136,15 +140,18 @@
 //! - [`crate::components::countdown`]: Countdown calculation logic
 //! - [`crate::ui::StoredAddress`]: Address data structure
 //! - [`crate::ui::App`]: Root component using panels
+use crate::components::address_utils::normalize_string;
 use crate::components::countdown::{
     TimeBucket, bucket_for, format_countdown, time_until_next_occurrence, time_until_next_start,
 };
 use crate::components::settings::AppSettings;
-use crate::components::translations::t;
+use crate::components::translations::{t, t_invalid_reason};
 use crate::ui::StoredAddress;
+use chrono::Utc;
 use dioxus::prelude::*;
 use dioxus_free_icons::Icon;
 use dioxus_free_icons::icons::md_navigation_icons::MdExpandLess;
+use std::collections::BTreeMap;
 use tokio::time::Duration;
 /// Display an address with countdown timer in appropriate category.
 ///
@@ -158,6 +165,8 @@ use tokio::time::Duration;
 /// * `addr` - StoredAddress to display
 /// * `index` - Position in list (for React-style keying)
 /// * `on_remove` - Event handler for remove button (currently unused)
+/// * `on_snooze` - Called with the address ID when the snooze button is
+///   pressed; the button is only shown when set
 ///
 /// # Countdown Updates
 ///
@@ -183,19 +192,29 @@ use tokio::time::Duration;
 /// }
 /// ```
 #[component]
-fn AddressItem(addr: StoredAddress, index: usize, on_remove: EventHandler<usize>) -> Element {
+fn AddressItem(
+    addr: StoredAddress,
+    index: usize,
+    on_remove: EventHandler<usize>,
+    on_snooze: Option<EventHandler<u64>>,
+) -> Element {
     let mut countdown = use_signal(|| "...".to_string());
+    let app_settings = use_context::<Signal<AppSettings>>();
     let addr_clone = addr.clone();
     use_future(move || {
         let addr_for_future = addr_clone.clone();
         async move {
+            let bucket_settings = app_settings().notifications.bucket_settings();
             let bucket = addr_for_future
                 .matched_entry
                 .as_ref()
-                .map(bucket_for)
+                .map(|matched| bucket_for(matched, &bucket_settings))
                 .unwrap_or(TimeBucket::Invalid);
             if let Some(matched) = &addr_for_future.matched_entry {
-                countdown.set(format_countdown(matched).unwrap_or_else(|| "...".to_string()));
+                countdown.set(
+                    format_countdown(matched, &bucket_settings, &app_settings().language)
+                        .unwrap_or_else(|| "...".to_string()),
+                );
             }
             if bucket == TimeBucket::Invalid {
                 return;
@@ -211,20 +230,56 @@ fn AddressItem(addr: StoredAddress, index: usize, on_remove: EventHandler<usize>
                 let new_countdown = addr_for_future
                     .matched_entry
                     .as_ref()
-                    .and_then(format_countdown)
+                    .and_then(|matched| {
+                        format_countdown(
+                            matched,
+                            &app_settings().notifications.bucket_settings(),
+                            &app_settings().language,
+                        )
+                    })
                     .unwrap_or_else(|| "...".to_string());
                 countdown.set(new_countdown);
             }
         }
     });
     let address_display = addr.display_name();
+    let invalid_text = addr
+        .invalid_reason
+        .as_deref()
+        .filter(|_| !addr.valid)
+        .map(|code| t_invalid_reason(code, &app_settings().language));
     rsx! {
         div { class: "address-item",
             div { class: "address-text", "{address_display}" }
-            div { class: "countdown-text", "{countdown()}" }
+            if let Some(text) = invalid_text {
+                div { class: "invalid-reason-text", "{text}" }
+            } else {
+                div { class: "countdown-text", "{countdown()}" }
+            }
+            if let Some(on_snooze) = on_snooze {
+                button {
+                    class: "btn-snooze",
+                    onclick: move |_| on_snooze.call(addr.id),
+                    {t("panel.snooze", &app_settings().language)}
+                }
+            }
         }
     }
 }
+/// Expand a panel when a tapped notification asks for its bucket.
+///
+/// Watches the request signal provided by the App and clears it once
+/// handled, so tapping the notification again reopens a panel the user
+/// closed in the meantime.
+fn use_open_on_request(bucket: TimeBucket, mut is_open: Signal<bool>) {
+    let mut requested = use_context::<Signal<Option<TimeBucket>>>();
+    use_effect(move || {
+        if requested().as_ref() == Some(&bucket) {
+            is_open.set(true);
+            requested.set(None);
+        }
+    });
+}
 /// Sort addresses by time until next restriction occurrence.
 ///
 /// Addresses with earlier restrictions are sorted first. This function uses
@@ -300,6 +355,7 @@ pub fn sorting_time_by_start(mut addrs: Vec<StoredAddress>) -> Vec<StoredAddress
 /// Displays addresses where:
 /// - `valid == true` (matched in database)
 /// - `active == true` (user enabled)
+/// - not snoozed ([`StoredAddress::is_snoozed`])
 /// - Time bucket is [`TimeBucket::Now`]
 ///
 /// # Sorting
@@ -310,6 +366,8 @@ pub fn sorting_time_by_start(mut addrs: Vec<StoredAddress>) -> Vec<StoredAddress
 ///
 /// # Props
 /// * `addresses` - Vector of all StoredAddress entries (automatically filtered)
+/// * `on_snooze` - Called with an address ID to snooze it until its next
+///   cleaning, see [`StoredAddress::snooze`]
 ///
 /// # Examples
 ///
@@ -317,17 +375,20 @@ pub fn sorting_time_by_start(mut addrs: Vec<StoredAddress>) -> Vec<StoredAddress
 /// use amp_android::ui::panels::ActivePanel;
 ///
 /// rsx! {
-///     ActivePanel { addresses: all_addresses.clone() }
+///     ActivePanel { addresses: all_addresses.clone(), on_snooze: move |id| { /* snooze */ } }
 /// }
 /// ```
 #[component]
-pub fn ActivePanel(addresses: Vec<StoredAddress>) -> Element {
+pub fn ActivePanel(addresses: Vec<StoredAddress>, on_snooze: EventHandler<u64>) -> Element {
+    let app_settings = use_context::<Signal<AppSettings>>();
+    let bucket_settings = app_settings().notifications.bucket_settings();
+    let now = Utc::now();
     let mut active_addrs: Vec<_> = addresses
         .into_iter()
-        .filter(|a| a.valid && a.active)
+        .filter(|a| a.valid && a.active && !a.is_snoozed(now))
         .filter(|a| {
             if let Some(entry) = &a.matched_entry {
-                matches!(bucket_for(entry), TimeBucket::Now)
+                matches!(bucket_for(entry, &bucket_settings), TimeBucket::Now)
             } else {
                 false
             }
@@ -336,7 +397,7 @@ pub fn ActivePanel(addresses: Vec<StoredAddress>) -> Element {
     active_addrs = sorting_time(active_addrs);
     let active_count = active_addrs.len();
     let mut is_open = use_signal(|| false);
-    let app_settings = use_context::<Signal<AppSettings>>();
+    use_open_on_request(TimeBucket::Now, is_open);
     let tr = move |key: &'static str| t(key, &app_settings().language);
     rsx! {
         div { class: "category-container category-active",
@@ -376,6 +437,7 @@ pub fn ActivePanel(addresses: Vec<StoredAddress>) -> Element {
                                             addr: addr.clone(),
                                             index: i,
                                             on_remove: move |_| {},
+                                            on_snooze: on_snooze,
                                         }
                                     }
                                 })
@@ -394,6 +456,7 @@ pub fn ActivePanel(addresses: Vec<StoredAddress>) -> Element {
 /// Displays addresses where:
 /// - `valid == true` (matched in database)
 /// - `active == true` (user enabled)
+/// - not snoozed ([`StoredAddress::is_snoozed`])
 /// - Time bucket is [`TimeBucket::Within6Hours`]
 ///
 /// # Sorting
@@ -416,12 +479,18 @@ pub fn ActivePanel(addresses: Vec<StoredAddress>) -> Element {
 /// ```
 #[component]
 pub fn SixHoursPanel(addresses: Vec<StoredAddress>) -> Element {
+    let app_settings = use_context::<Signal<AppSettings>>();
+    let bucket_settings = app_settings().notifications.bucket_settings();
+    let now = Utc::now();
     let mut addrs: Vec<_> = addresses
         .into_iter()
-        .filter(|a| a.valid && a.active)
+        .filter(|a| a.valid && a.active && !a.is_snoozed(now))
         .filter(|a| {
             if let Some(entry) = &a.matched_entry {
-                matches!(bucket_for(entry), TimeBucket::Within6Hours)
+                matches!(
+                    bucket_for(entry, &bucket_settings),
+                    TimeBucket::Within6Hours
+                )
             } else {
                 false
             }
@@ -430,7 +499,7 @@ pub fn SixHoursPanel(addresses: Vec<StoredAddress>) -> Element {
     addrs = sorting_time_by_start(addrs);
     let count = addrs.len();
     let mut is_open = use_signal(|| false);
-    let app_settings = use_context::<Signal<AppSettings>>();
+    use_open_on_request(TimeBucket::Within6Hours, is_open);
     let tr = move |key: &'static str| t(key, &app_settings().language);
     rsx! {
         div { class: "category-container category-6h",
@@ -488,6 +557,7 @@ pub fn SixHoursPanel(addresses: Vec<StoredAddress>) -> Element {
 /// Displays addresses where:
 /// - `valid == true` (matched in database)
 /// - `active == true` (user enabled)
+/// - not snoozed ([`StoredAddress::is_snoozed`])
 /// - Time bucket is [`TimeBucket::Within1Day`]
 ///
 /// # Sorting
@@ -510,12 +580,15 @@ pub fn SixHoursPanel(addresses: Vec<StoredAddress>) -> Element {
 /// ```
 #[component]
 pub fn OneDayPanel(addresses: Vec<StoredAddress>) -> Element {
+    let app_settings = use_context::<Signal<AppSettings>>();
+    let bucket_settings = app_settings().notifications.bucket_settings();
+    let now = Utc::now();
     let mut addrs: Vec<_> = addresses
         .into_iter()
-        .filter(|a| a.valid && a.active)
+        .filter(|a| a.valid && a.active && !a.is_snoozed(now))
         .filter(|a| {
             if let Some(entry) = &a.matched_entry {
-                matches!(bucket_for(entry), TimeBucket::Within1Day)
+                matches!(bucket_for(entry, &bucket_settings), TimeBucket::Within1Day)
             } else {
                 false
             }
@@ -524,7 +597,7 @@ pub fn OneDayPanel(addresses: Vec<StoredAddress>) -> Element {
     addrs = sorting_time_by_start(addrs);
     let count = addrs.len();
     let mut is_open = use_signal(|| false);
-    let app_settings = use_context::<Signal<AppSettings>>();
+    use_open_on_request(TimeBucket::Within1Day, is_open);
     let tr = move |key: &'static str| t(key, &app_settings().language);
     rsx! {
         div { class: "category-container category-24h",
@@ -582,6 +655,7 @@ pub fn OneDayPanel(addresses: Vec<StoredAddress>) -> Element {
 /// Displays addresses where:
 /// - `valid == true` (matched in database)
 /// - `active == true` (user enabled)
+/// - not snoozed ([`StoredAddress::is_snoozed`])
 /// - Time bucket is [`TimeBucket::Within1Month`]
 ///
 /// # Sorting
@@ -604,12 +678,18 @@ pub fn OneDayPanel(addresses: Vec<StoredAddress>) -> Element {
 /// ```
 #[component]
 pub fn OneMonthPanel(addresses: Vec<StoredAddress>) -> Element {
+    let app_settings = use_context::<Signal<AppSettings>>();
+    let bucket_settings = app_settings().notifications.bucket_settings();
+    let now = Utc::now();
     let mut addrs: Vec<_> = addresses
         .into_iter()
-        .filter(|a| a.valid && a.active)
+        .filter(|a| a.valid && a.active && !a.is_snoozed(now))
         .filter(|a| {
             if let Some(entry) = &a.matched_entry {
-                matches!(bucket_for(entry), TimeBucket::Within1Month)
+                matches!(
+                    bucket_for(entry, &bucket_settings),
+                    TimeBucket::Within1Month
+                )
             } else {
                 false
             }
@@ -618,7 +698,6 @@ pub fn OneMonthPanel(addresses: Vec<StoredAddress>) -> Element {
     addrs = sorting_time_by_start(addrs);
     let count = addrs.len();
     let mut is_open = use_signal(|| false);
-    let app_settings = use_context::<Signal<AppSettings>>();
     let tr = move |key: &'static str| t(key, &app_settings().language);
     rsx! {
         div { class: "category-container category-month",
@@ -676,6 +755,7 @@ pub fn OneMonthPanel(addresses: Vec<StoredAddress>) -> Element {
 /// Displays addresses where:
 /// - `valid == true` (matched in database)
 /// - `active == true` (user enabled)
+/// - not snoozed ([`StoredAddress::is_snoozed`])
 /// - Time bucket is [`TimeBucket::MoreThan1Month`]
 ///
 /// # Sorting
@@ -698,12 +778,18 @@ pub fn OneMonthPanel(addresses: Vec<StoredAddress>) -> Element {
 /// ```
 #[component]
 pub fn MoreThan1MonthPanel(addresses: Vec<StoredAddress>) -> Element {
+    let app_settings = use_context::<Signal<AppSettings>>();
+    let bucket_settings = app_settings().notifications.bucket_settings();
+    let now = Utc::now();
     let mut addrs: Vec<_> = addresses
         .into_iter()
-        .filter(|a| a.valid && a.active)
+        .filter(|a| a.valid && a.active && !a.is_snoozed(now))
         .filter(|a| {
             if let Some(entry) = &a.matched_entry {
-                matches!(bucket_for(entry), TimeBucket::MoreThan1Month)
+                matches!(
+                    bucket_for(entry, &bucket_settings),
+                    TimeBucket::MoreThan1Month
+                )
             } else {
                 false
             }
@@ -712,7 +798,6 @@ pub fn MoreThan1MonthPanel(addresses: Vec<StoredAddress>) -> Element {
     addrs = sorting_time_by_start(addrs);
     let count = addrs.len();
     let mut is_open = use_signal(|| false);
-    let app_settings = use_context::<Signal<AppSettings>>();
     let tr = move |key: &'static str| t(key, &app_settings().language);
     rsx! {
         div { class: "category-container category-later",
@@ -947,3 +1032,128 @@ pub fn InvalidPanel(addresses: Vec<StoredAddress>) -> Element {
         }
     }
 }
+/// Group addresses by street, keyed by the normalized street name.
+///
+/// Groups are sorted by key; addresses keep their input order within a
+/// group, so " docentgatan" and "Docentgatan" end up together.
+pub fn group_by_street(addresses: Vec<StoredAddress>) -> Vec<(String, Vec<StoredAddress>)> {
+    let mut groups: BTreeMap<String, Vec<StoredAddress>> = BTreeMap::new();
+    for addr in addresses {
+        groups
+            .entry(normalize_string(&addr.street, false))
+            .or_default()
+            .push(addr);
+    }
+    groups.into_iter().collect()
+}
+/// Panel listing active addresses grouped by street.
+///
+/// Rendered by the App instead of the time panels when
+/// [`AppSettings::group_by_street`] is enabled. Each street becomes a
+/// [`StreetGroup`], in the order of [`group_by_street`].
+///
+/// # Props
+/// * `addresses` - Vector of all StoredAddress entries (inactive ones are skipped)
+#[component]
+pub fn StreetGroupsPanel(addresses: Vec<StoredAddress>) -> Element {
+    let groups = group_by_street(addresses.into_iter().filter(|a| a.active).collect());
+    rsx! {
+        for (key , group) in groups {
+            StreetGroup { key: "{key}", addresses: group }
+        }
+    }
+}
+/// One collapsible street in [`StreetGroupsPanel`].
+///
+/// Addresses are sorted with [`sorting_time_by_start`]; the title shows
+/// the countdown of the first one with a schedule.
+#[component]
+fn StreetGroup(addresses: Vec<StoredAddress>) -> Element {
+    let addrs = sorting_time_by_start(addresses);
+    let count = addrs.len();
+    let street = addrs
+        .first()
+        .map(|a| a.street.trim().to_string())
+        .unwrap_or_default();
+    let mut is_open = use_signal(|| false);
+    let app_settings = use_context::<Signal<AppSettings>>();
+    let bucket_settings = app_settings().notifications.bucket_settings();
+    let soonest = addrs
+        .iter()
+        .filter_map(|a| a.matched_entry.as_ref())
+        .find_map(|entry| format_countdown(entry, &bucket_settings, &app_settings().language));
+    rsx! {
+        div { class: "category-container category-street-group",
+            button {
+                class: "category-title",
+                onclick: move |_| is_open.set(!is_open()),
+                "aria-expanded": if is_open() { "true" } else { "false" },
+                span { "{street}" }
+                if let Some(soonest) = soonest {
+                    span { class: "street-group-soonest", "{soonest}" }
+                }
+                span { class: "category-count",
+                    span { class: "category-toggle-arrow",
+                        Icon { icon: MdExpandLess, width: 16, height: 16 }
+                    }
+                    "{ count }"
+                }
+            }
+            div {
+                class: "category-content",
+                "aria-hidden": if is_open() { "false" } else { "true" },
+                div { class: "address-list",
+                    {
+                        addrs
+                            .into_iter()
+                            .enumerate()
+                            .map(|(i, addr)| {
+                                let key = addr.id;
+                                rsx! {
+                                    AddressItem {
+                                        key: "{key}",
+                                        addr: addr.clone(),
+                                        index: i,
+                                        on_remove: move |_| {},
+                                    }
+                                }
+                            })
+                    }
+                }
+            }
+        }
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    fn addr(id: u64, street: &str, number: &str) -> StoredAddress {
+        StoredAddress {
+            id,
+            street: street.to_string(),
+            street_number: number.to_string(),
+            postal_code: String::new(),
+            valid: false,
+            active: true,
+            matched_entry: None,
+            parking_info: None,
+            match_reason: None,
+            approximate: false,
+            invalid_reason: None,
+            snoozed_until: None,
+        }
+    }
+    #[test]
+    fn test_group_by_street_uses_normalized_key() {
+        let groups = group_by_street(vec![
+            addr(1, "Docentgatan", "1B"),
+            addr(2, "Storgatan", "10"),
+            addr(3, " docentgatan", "3A"),
+        ]);
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].0, "docentgatan");
+        let ids: Vec<u64> = groups[0].1.iter().map(|a| a.id).collect();
+        assert_eq!(ids, vec![1, 3]);
+        assert_eq!(groups[1].0, "storgatan");
+    }
+}
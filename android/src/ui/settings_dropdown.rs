@@ -2,10 +2,11 @@
 use crate::android_bridge::{export_file_jni, import_file_jni, open_url};
 use crate::components::notifications::{notify_active, notify_one_day, notify_six_hours};
 use crate::components::settings::{
-    AppSettings, AutocompleteSource, Language, Theme, get_settings_storage_path,
+    AppSettings, AutocompleteSource, DEFAULT_BATCH_WINDOW_MINUTES, DEFAULT_QUIET_HOURS_END_MINUTES,
+    DEFAULT_QUIET_HOURS_START_MINUTES, Language, Theme, get_settings_storage_path,
     import_settings_from_path, load_settings, save_settings,
 };
-use crate::components::storage::{get_local_storage_path, import_local_from_path};
+use crate::components::storage::{clear_all, get_local_storage_path, import_local_from_path};
 use crate::components::translations::t;
 use crate::ui::StoredAddress;
 use dioxus::prelude::*;
@@ -43,6 +44,7 @@ pub fn SettingsDropdown(
     let mut settings = use_context::<Signal<AppSettings>>();
     let mut open_section = use_signal(|| OpenSection::None);
     let mut show_overwrite_warning = use_signal(|| false);
+    let mut show_clear_confirm = use_signal(|| false);
     let mut pending_import_type = use_signal(|| ImportType::Addresses);
     let mut show_error_dialog = use_signal(|| false);
     let mut error_message = use_signal(String::new);
@@ -81,6 +83,28 @@ pub fn SettingsDropdown(
         save_settings(&current);
         settings.set(current);
     };
+    let on_toggle_batch = move |_| {
+        let mut current = settings();
+        current.notifications.batch_window = if current.notifications.batch_window.is_zero() {
+            chrono::Duration::minutes(DEFAULT_BATCH_WINDOW_MINUTES)
+        } else {
+            chrono::Duration::zero()
+        };
+        save_settings(&current);
+        settings.set(current);
+    };
+    let on_toggle_quiet_hours = move |_| {
+        let mut current = settings();
+        current.notifications.quiet_hours = match current.notifications.quiet_hours {
+            Some(_) => None,
+            None => amp_core::structs::QuietHours::from_minutes(
+                DEFAULT_QUIET_HOURS_START_MINUTES,
+                DEFAULT_QUIET_HOURS_END_MINUTES,
+            ),
+        };
+        save_settings(&current);
+        settings.set(current);
+    };
     let on_toggle_darkmode = move |_| {
         let mut current = settings();
         current.theme = if current.theme == Theme::Dark {
@@ -91,6 +115,12 @@ pub fn SettingsDropdown(
         save_settings(&current);
         settings.set(current);
     };
+    let on_toggle_group_by_street = move |_| {
+        let mut current = settings();
+        current.group_by_street = !current.group_by_street;
+        save_settings(&current);
+        settings.set(current);
+    };
     let on_set_language_sv = move |_| {
         let mut current = settings();
         current.language = Language::Svenska;
@@ -113,6 +143,10 @@ pub fn SettingsDropdown(
             active: true,
             matched_entry: None,
             parking_info: None,
+            match_reason: None,
+            approximate: false,
+            invalid_reason: None,
+            snoozed_until: None,
         };
         eprintln!("[Debug] Triggering active notification");
         notify_active(&debug_address);
@@ -127,6 +161,10 @@ pub fn SettingsDropdown(
             active: false,
             matched_entry: None,
             parking_info: None,
+            match_reason: None,
+            approximate: false,
+            invalid_reason: None,
+            snoozed_until: None,
         };
         eprintln!("[Debug] Triggering 6-hour notification");
         notify_six_hours(&debug_address);
@@ -141,6 +179,10 @@ pub fn SettingsDropdown(
             active: false,
             matched_entry: None,
             parking_info: None,
+            match_reason: None,
+            approximate: false,
+            invalid_reason: None,
+            snoozed_until: None,
         };
         eprintln!("[Debug] Triggering 1-day notification");
         notify_one_day(&debug_address);
@@ -265,6 +307,31 @@ pub fn SettingsDropdown(
     let handle_cancel_import = move |_| {
         show_overwrite_warning.set(false);
     };
+    let handle_clear_all_request = move |_| {
+        show_clear_confirm.set(true);
+    };
+    let handle_confirm_clear = move |_| {
+        show_clear_confirm.set(false);
+        match clear_all() {
+            Ok(()) => {
+                settings.set(load_settings());
+                success_message.set(t("msg.clear_success", &settings().language).to_string());
+                show_success_dialog.set(true);
+                on_data_imported.call(());
+            }
+            Err(e) => {
+                error_message.set(format!(
+                    "{}: {}",
+                    t("msg.clear_fail", &settings().language),
+                    e,
+                ));
+                show_error_dialog.set(true);
+            }
+        }
+    };
+    let handle_cancel_clear = move |_| {
+        show_clear_confirm.set(false);
+    };
     if !is_open {
         return rsx!();
     }
@@ -388,6 +455,24 @@ pub fn SettingsDropdown(
                                         "📥"
                                     }
                                 }
+                                h4 { class: "info-heading",
+                                    {tr("settings.import_export.reset_heading")}
+                                }
+                                div { class: "settings-toggle-item",
+                                    div { class: "settings-item-text",
+                                        div { class: "settings-item-label",
+                                            {tr("settings.import_export.clear_all_label")}
+                                        }
+                                        div { class: "settings-item-description",
+                                            {tr("settings.import_export.clear_all_desc")}
+                                        }
+                                    }
+                                    button {
+                                        class: "btn-debug-trigger",
+                                        onclick: handle_clear_all_request,
+                                        "🗑"
+                                    }
+                                }
                             }
                         }
                     }
@@ -488,6 +573,54 @@ pub fn SettingsDropdown(
                                         }
                                     }
                                 }
+                                div { class: "settings-toggle-item",
+                                    div { class: "settings-item-text",
+                                        div { class: "settings-item-label",
+                                            {tr("settings.notifications.batch_label")}
+                                        }
+                                        div { class: "settings-item-description",
+                                            {tr("settings.notifications.batch_desc")}
+                                        }
+                                    }
+                                    label { class: "settings-toggle-switch",
+                                        input {
+                                            r#type: "checkbox",
+                                            checked: !settings().notifications.batch_window.is_zero(),
+                                            onchange: on_toggle_batch,
+                                        }
+                                        div { class: "settings-switch-container",
+                                            div {
+                                                class: "settings-switch-thumb",
+                                                "data-active": if settings().notifications.batch_window.is_zero() { "false" } else { "true" },
+                                                div { class: "settings-led" }
+                                            }
+                                        }
+                                    }
+                                }
+                                div { class: "settings-toggle-item",
+                                    div { class: "settings-item-text",
+                                        div { class: "settings-item-label",
+                                            {tr("settings.notifications.quiet_label")}
+                                        }
+                                        div { class: "settings-item-description",
+                                            {tr("settings.notifications.quiet_desc")}
+                                        }
+                                    }
+                                    label { class: "settings-toggle-switch",
+                                        input {
+                                            r#type: "checkbox",
+                                            checked: settings().notifications.quiet_hours.is_some(),
+                                            onchange: on_toggle_quiet_hours,
+                                        }
+                                        div { class: "settings-switch-container",
+                                            div {
+                                                class: "settings-switch-thumb",
+                                                "data-active": if settings().notifications.quiet_hours.is_some() { "true" } else { "false" },
+                                                div { class: "settings-led" }
+                                            }
+                                        }
+                                    }
+                                }
                             }
                         }
                     }
@@ -536,6 +669,30 @@ pub fn SettingsDropdown(
                                         }
                                     }
                                 }
+                                div { class: "settings-toggle-item",
+                                    div { class: "settings-item-text",
+                                        div { class: "settings-item-label",
+                                            {tr("settings.settings.group_by_street_label")}
+                                        }
+                                        div { class: "settings-item-description",
+                                            {tr("settings.settings.group_by_street_desc")}
+                                        }
+                                    }
+                                    label { class: "settings-toggle-switch",
+                                        input {
+                                            r#type: "checkbox",
+                                            checked: settings().group_by_street,
+                                            onchange: on_toggle_group_by_street,
+                                        }
+                                        div { class: "settings-switch-container",
+                                            div {
+                                                class: "settings-switch-thumb",
+                                                "data-active": if settings().group_by_street { "true" } else { "false" },
+                                                div { class: "settings-led" }
+                                            }
+                                        }
+                                    }
+                                }
                                 h4 { class: "info-heading",
                                     {tr("settings.settings.language_heading")}
                                 }
@@ -871,6 +1028,32 @@ pub fn SettingsDropdown(
                 }
             }
         }
+        if show_clear_confirm() {
+            div { class: "modal-overlay", onclick: handle_cancel_clear,
+                div {
+                    class: "modal-container confirm-dialog",
+                    onclick: move |e| e.stop_propagation(),
+                    div { class: "modal-header",
+                        h3 { class: "confirm-dialog-title", {tr("dialog.confirm_clear.title")} }
+                    }
+                    div { class: "modal-body",
+                        p { {tr("dialog.confirm_clear.body")} }
+                    }
+                    div { class: "modal-actions",
+                        button {
+                            class: "modal-btn modal-btn-cancel",
+                            onclick: handle_cancel_clear,
+                            {tr("dialog.confirm_import.cancel")}
+                        }
+                        button {
+                            class: "modal-btn modal-btn-confirm",
+                            onclick: handle_confirm_clear,
+                            {tr("dialog.confirm_clear.ok")}
+                        }
+                    }
+                }
+            }
+        }
         if show_error_dialog() {
             div {
                 class: "modal-overlay",
@@ -25,7 +25,7 @@ use amp_android::components::{
 use amp_android::ui::StoredAddress;
 use amp_core::structs::DB;
 /// Helper to create a test address with specific day/time
-fn create_test_address(id: usize, day: u8, time: &str) -> StoredAddress {
+fn create_test_address(id: u64, day: u8, time: &str) -> StoredAddress {
     let db = DB::from_dag_tid(
         Some("22100".to_string()),
         format!("Test Street {}", id),
@@ -50,6 +50,10 @@ fn create_test_address(id: usize, day: u8, time: &str) -> StoredAddress {
         active: true,
         matched_entry: Some(db),
         parking_info: None,
+        match_reason: None,
+        approximate: false,
+        invalid_reason: None,
+        snoozed_until: None,
     }
 }
 #[test]
@@ -158,6 +162,10 @@ fn test_address_without_match_ignored() {
         active: false,
         matched_entry: None,
         parking_info: None,
+        match_reason: None,
+        approximate: false,
+        invalid_reason: None,
+        snoozed_until: None,
     };
     let transitions = detect_transitions(&[addr]);
     assert_eq!(
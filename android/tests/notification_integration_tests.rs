@@ -14,9 +14,10 @@ use amp_android::components::transitions::{
     clear_panel_state, detect_transitions, initialize_panel_tracker,
 };
 use amp_android::ui::StoredAddress;
+use amp_core::buckets::BucketSettings;
 use amp_core::structs::DB;
 /// Helper to create a test address with a specific day and time
-fn create_test_address(id: usize, day: u8, time: &str) -> StoredAddress {
+fn create_test_address(id: u64, day: u8, time: &str) -> StoredAddress {
     let db = DB::from_dag_tid(
         Some("22100".to_string()),
         format!("Test Street {}", id),
@@ -41,12 +42,19 @@ fn create_test_address(id: usize, day: u8, time: &str) -> StoredAddress {
         active: true,
         matched_entry: Some(db),
         parking_info: None,
+        match_reason: None,
+        approximate: false,
+        invalid_reason: None,
+        snoozed_until: None,
     }
 }
 /// Helper that returns both address and its current time bucket
-fn create_test_address_with_bucket(id: usize, day: u8, time: &str) -> (StoredAddress, TimeBucket) {
+fn create_test_address_with_bucket(id: u64, day: u8, time: &str) -> (StoredAddress, TimeBucket) {
     let addr = create_test_address(id, day, time);
-    let bucket = bucket_for(addr.matched_entry.as_ref().unwrap());
+    let bucket = bucket_for(
+        addr.matched_entry.as_ref().unwrap(),
+        &BucketSettings::default(),
+    );
     (addr, bucket)
 }
 /// Returns true if the bucket is considered actionable for notifications
@@ -147,6 +155,10 @@ fn test_address_without_match_ignored() {
         active: false,
         matched_entry: None,
         parking_info: None,
+        match_reason: None,
+        approximate: false,
+        invalid_reason: None,
+        snoozed_until: None,
     };
     let transitions = detect_transitions(&[addr_no_match]);
     assert_eq!(
@@ -178,7 +190,10 @@ fn test_transition_to_more_urgent_bucket() {
     )
     .expect("Failed to create more urgent DB entry");
     addr2.matched_entry = Some(db_more_urgent);
-    let bucket2 = bucket_for(addr2.matched_entry.as_ref().unwrap());
+    let bucket2 = bucket_for(
+        addr2.matched_entry.as_ref().unwrap(),
+        &BucketSettings::default(),
+    );
     let transitions2 = detect_transitions(std::slice::from_ref(&addr2));
     let should_notify = matches!(
         (&bucket1, &bucket2),
@@ -211,7 +226,10 @@ fn test_transition_to_more_urgent_bucket() {
 #[test]
 fn test_bucket_categorization() {
     let addr_tomorrow = create_test_address(1, 1, "0800-1200");
-    let bucket = bucket_for(addr_tomorrow.matched_entry.as_ref().unwrap());
+    let bucket = bucket_for(
+        addr_tomorrow.matched_entry.as_ref().unwrap(),
+        &BucketSettings::default(),
+    );
     assert!(
         matches!(
             bucket,
@@ -357,7 +375,10 @@ fn test_address_modification_detected() {
     clear_panel_state();
     initialize_panel_tracker();
     let mut addr = create_test_address(1, 10, "0800-1200");
-    let bucket_before = bucket_for(addr.matched_entry.as_ref().unwrap());
+    let bucket_before = bucket_for(
+        addr.matched_entry.as_ref().unwrap(),
+        &BucketSettings::default(),
+    );
     let _ = detect_transitions(std::slice::from_ref(&addr));
     let db_closer = DB::from_dag_tid(
         Some("22100".to_string()),
@@ -375,7 +396,10 @@ fn test_address_modification_detected() {
     )
     .expect("Failed to create closer DB entry");
     addr.matched_entry = Some(db_closer);
-    let bucket_after = bucket_for(addr.matched_entry.as_ref().unwrap());
+    let bucket_after = bucket_for(
+        addr.matched_entry.as_ref().unwrap(),
+        &BucketSettings::default(),
+    );
     let transitions = detect_transitions(&[addr]);
     let should_notify = matches!(
         (&bucket_before, &bucket_after),
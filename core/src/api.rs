@@ -14,10 +14,10 @@
 //! ## Loading All Data Sources
 //!
 //! ```no_run
-//! use amp_core::api::api;
+//! use amp_core::api::DataSet;
 //!
-//! let (addresses, miljoe_data, parkering_data) = api()?;
-//! println!("Loaded {} addresses", addresses.len());
+//! let data = DataSet::load_from_dir("data")?;
+//! println!("Loaded {} addresses", data.addresses.len());
 //! # Ok::<(), Box<dyn std::error::Error>>(())
 //! ```
 //!
@@ -30,22 +30,254 @@
 //! let parking_zones = DataLoader::load_parkering("data/parkeringsavgifter.json")?;
 //! # Ok::<(), Box<dyn std::error::Error>>(())
 //! ```
+//!
+//! ## Loading Compressed Exports
+//!
+//! The portal sometimes ships `.geojson.gz`. The `_gz` loaders decompress
+//! before parsing, and [`DataLoader::load_addresses_auto`] picks the right
+//! one by looking at the file's first bytes:
+//!
+//! ```no_run
+//! use amp_core::api::DataLoader;
+//!
+//! let addresses = DataLoader::load_addresses_auto("data/adresser.geojson.gz")?;
+//! let parking_zones = DataLoader::load_parkering_gz("data/parkeringsavgifter.geojson.gz")?;
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+//!
+//! ## Loading From Another Directory
+//!
+//! [`DataPaths`] names the directory and the three source files. It defaults
+//! to `data/` with Malmö's file names; [`DataPaths::from_env`] lets CI or a
+//! second city point elsewhere without changing the working directory:
+//!
+//! ```no_run
+//! use amp_core::api::{DataPaths, DataSet};
+//!
+//! // AMP_DATA_DIR=/srv/lund AMP_MILJO_FILE=gatusopning
+//! let paths = DataPaths::from_env();
+//! let (data, _warnings) = DataSet::load_from_paths_with_warnings(&paths)?;
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
 #![allow(unused_qualifications)]
-use crate::structs::{AdressClean, MiljoeDataClean, ParkeringsDataClean};
-use geojson::{Feature, GeoJson};
+use crate::correlation_algorithms::common::segment_length_meters;
+use crate::error::AmpError;
+use crate::parquet;
+use crate::structs::{
+    AdressClean, MiljoeDataClean, ParkeringsDataClean, format_dagar, parse_dagar,
+    parse_tid_intervals,
+};
+use flate2::read::GzDecoder;
+use geojson::{Feature, FeatureCollection, GeoJson};
 use rust_decimal::Decimal;
-use std::fs;
+use rust_decimal::prelude::FromPrimitive;
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::{Path, PathBuf};
 /// Tuple containing all three data types loaded from GeoJSON sources.
 ///
 /// The elements are:
 /// 1. Addresses with coordinates ([`AdressClean`])
 /// 2. Environmental parking restrictions ([`MiljoeDataClean`])
 /// 3. Parking zones with pricing ([`ParkeringsDataClean`])
+#[deprecated(note = "use `DataSet` instead")]
 pub type ApiResult = (
     Vec<AdressClean>,
     Vec<MiljoeDataClean>,
     Vec<ParkeringsDataClean>,
 );
+/// Addresses and environmental parking restrictions, without parking zones.
+#[deprecated(note = "use `DataSet` instead")]
+pub type MiljoOnlyResult = (Vec<AdressClean>, Vec<MiljoeDataClean>);
+/// A feature that could not be parsed while loading a GeoJSON file.
+///
+/// Loaders skip malformed features instead of aborting, collecting one
+/// warning per skipped feature so the import can be diagnosed afterwards.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoadWarning {
+    /// Path of the file the feature was read from
+    pub source: String,
+    /// Index of the feature within the FeatureCollection
+    pub feature_index: usize,
+    /// Why the feature was skipped
+    pub reason: String,
+}
+impl std::fmt::Display for LoadWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}: feature #{}: {}",
+            self.source, self.feature_index, self.reason
+        )
+    }
+}
+/// Maximum number of distinct examples kept in [`ParseReport::samples`]
+const MAX_PARSE_SAMPLES: usize = 10;
+/// How many miljö rows carry a `tid`/`day` the app can schedule.
+///
+/// A row is one line segment. It is rejected by time if its `tid` is missing
+/// or doesn't parse with the default [`TidOptions`], otherwise by day if its
+/// `day` is missing or outside 1–31. Rows with an unparseable `tid` or `day`
+/// are still loaded, so correlation can skip past them (see
+/// [`correlate_usable`]); features without a `tid`, or with one that isn't
+/// text ([`AmpError::InvalidTime`]), are dropped with a [`LoadWarning`] and
+/// counted here as one row each.
+///
+/// # Examples
+///
+/// ```no_run
+/// use amp_core::api::{DataLoader, ParseReport};
+///
+/// let (miljo, warnings) =
+///     DataLoader::load_miljodata_with_warnings("data/miljoparkeringar.json")?;
+/// let report = ParseReport::from_miljodata(&miljo, &warnings);
+/// println!("{}", report);
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+///
+/// [`TidOptions`]: crate::structs::TidOptions
+/// [`correlate_usable`]: crate::correlation_algorithms::correlate_usable
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ParseReport {
+    /// Rows seen, including features dropped for a missing `tid`
+    pub total: usize,
+    /// Rows with a usable `tid` and `day`
+    pub accepted: usize,
+    /// Rows with a missing or unparseable `tid`
+    pub rejected_by_time: usize,
+    /// Rows with a usable `tid` but a missing or out-of-range `day`
+    pub rejected_by_day: usize,
+    /// Distinct rejected values, e.g. `time format 'Mån 8-16'`
+    pub samples: Vec<String>,
+}
+impl ParseReport {
+    /// Build the report from loaded segments and the loader's warnings.
+    ///
+    /// `warnings` may contain warnings from other files; only warnings for a
+    /// missing or non-text `tid` are counted.
+    pub fn from_miljodata(segments: &[MiljoeDataClean], warnings: &[LoadWarning]) -> Self {
+        let mut report = Self::default();
+        let time_warnings = warnings
+            .iter()
+            .filter(|w| w.reason == "missing tid" || w.reason.starts_with("Invalid time format"));
+        for warning in time_warnings {
+            report.total += 1;
+            report.rejected_by_time += 1;
+            report.add_sample(warning.reason.clone());
+        }
+        for segment in segments {
+            report.total += 1;
+            if parse_tid_intervals(&segment.tid).is_empty() {
+                report.rejected_by_time += 1;
+                report.add_sample(format!("time format '{}'", segment.tid));
+            } else if !segment.has_usable_time() {
+                report.rejected_by_day += 1;
+                report.add_sample(format!("day '{}'", format_dagar(&segment.dagar)));
+            } else {
+                report.accepted += 1;
+            }
+        }
+        report
+    }
+    /// Total number of rejected rows
+    pub fn rejected(&self) -> usize {
+        self.rejected_by_time + self.rejected_by_day
+    }
+    fn add_sample(&mut self, sample: String) {
+        if self.samples.len() < MAX_PARSE_SAMPLES && !self.samples.contains(&sample) {
+            self.samples.push(sample);
+        }
+    }
+}
+impl std::fmt::Display for ParseReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}/{} rows accepted, {} rejected ({} by time, {} by day)",
+            self.accepted,
+            self.total,
+            self.rejected(),
+            self.rejected_by_time,
+            self.rejected_by_day
+        )?;
+        if !self.samples.is_empty() {
+            write!(f, ": {}", self.samples.join(", "))?;
+        }
+        Ok(())
+    }
+}
+/// Why address features would be skipped, from [`DataLoader::validate_addresses`].
+///
+/// [`valid`](Self::valid) counts the features the address import keeps, as
+/// decided by [`DataLoader::address_point`]: only a bad geometry or a missing
+/// `BELADRESS` skips a feature. The other properties are optional and their
+/// problems are counted without making the feature invalid. A feature can be
+/// counted under several reasons. Nothing is converted, so this is a dry run
+/// of the address import.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ValidationReport {
+    /// Features in the FeatureCollection
+    pub total: usize,
+    /// Features the import keeps
+    pub valid: usize,
+    /// Features without a `POSTNR` property
+    pub missing_postnr: usize,
+    /// Features without a `BELADRESS` property, skipped by the import
+    pub missing_beladress: usize,
+    /// Features without an `ADRESSOMR` string
+    pub missing_adressomr: usize,
+    /// Features without an `ADRESSPLAT` string
+    pub missing_adressplat: usize,
+    /// Features whose `POSTNR` is null or not a number, e.g. `"21x 50"`
+    pub invalid_postnr: usize,
+    /// Features without a Point geometry of two finite coordinates, skipped
+    /// by the import
+    pub invalid_geometry: usize,
+}
+impl ValidationReport {
+    /// Number of features the import skips
+    pub fn invalid(&self) -> usize {
+        self.total - self.valid
+    }
+    /// `(reason, count)` for every reason a feature is skipped
+    pub fn reasons(&self) -> [(&'static str, usize); 2] {
+        [
+            ("missing BELADRESS", self.missing_beladress),
+            ("invalid geometry", self.invalid_geometry),
+        ]
+    }
+    /// `(reason, count)` for every problem with an optional property; these
+    /// features are still imported
+    pub fn optional_reasons(&self) -> [(&'static str, usize); 4] {
+        [
+            ("missing POSTNR", self.missing_postnr),
+            ("missing ADRESSOMR", self.missing_adressomr),
+            ("missing ADRESSPLAT", self.missing_adressplat),
+            ("null or unparseable POSTNR", self.invalid_postnr),
+        ]
+    }
+}
+impl std::fmt::Display for ValidationReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let counted = |reasons: &[(&str, usize)]| -> Vec<String> {
+            reasons
+                .iter()
+                .filter(|(_, count)| *count > 0)
+                .map(|(reason, count)| format!("{} {}", count, reason))
+                .collect()
+        };
+        write!(f, "{}/{} features valid", self.valid, self.total)?;
+        let skipped = counted(&self.reasons());
+        if !skipped.is_empty() {
+            write!(f, ": {}", skipped.join(", "))?;
+        }
+        let optional = counted(&self.optional_reasons());
+        if !optional.is_empty() {
+            write!(f, " (kept with {})", optional.join(", "))?;
+        }
+        Ok(())
+    }
+}
 /// Utility for loading and parsing GeoJSON data files.
 ///
 /// This struct provides static methods for loading different types of parking
@@ -58,6 +290,8 @@ pub type ApiResult = (
 /// - Each segment becomes a separate data entry for efficient spatial matching
 /// - Coordinate conversion uses [`rust_decimal::Decimal`] for precision
 /// - Failed conversions are logged but don't stop the loading process
+/// - Malformed features are skipped and reported as [`LoadWarning`]s by the
+///   `*_with_warnings` loaders
 pub struct DataLoader;
 impl Default for DataLoader {
     fn default() -> Self {
@@ -71,6 +305,26 @@ impl DataLoader {
     pub fn new() -> Self {
         Self
     }
+    /// Convert a GeoJSON position to `[longitude, latitude]`.
+    ///
+    /// Values after the first two, such as an altitude, are ignored.
+    ///
+    /// # Errors
+    ///
+    /// [`AmpError::InvalidCoordinate`] if there are fewer than two values or
+    /// the longitude or latitude is not a finite number within ±180° / ±90°.
+    fn position(coords: &[f64]) -> Result<[Decimal; 2], AmpError> {
+        let &[lon, lat, ..] = coords else {
+            return Err(AmpError::InvalidCoordinate);
+        };
+        if !(-180.0..=180.0).contains(&lon) || !(-90.0..=90.0).contains(&lat) {
+            return Err(AmpError::InvalidCoordinate);
+        }
+        Decimal::from_f64(lon)
+            .zip(Decimal::from_f64(lat))
+            .map(|(lon, lat)| [lon, lat])
+            .ok_or(AmpError::InvalidCoordinate)
+    }
     /// Extract point coordinates from a GeoJSON Point feature.
     ///
     /// # Arguments
@@ -79,28 +333,40 @@ impl DataLoader {
     ///
     /// # Returns
     ///
-    /// `Some([longitude, latitude])` if extraction succeeds, `None` otherwise.
-    fn extract_point_coordinates(feature: &Feature) -> Option<[Decimal; 2]> {
-        if let Some(ref geom) = feature.geometry {
-            match &geom.value {
-                geojson::Value::Point(coords) => {
-                    if coords.len() >= 2 {
-                        let x = Decimal::try_from(coords[0]).ok()?;
-                        let y = Decimal::try_from(coords[1]).ok()?;
-                        return Some([x, y]);
-                    }
-                }
-                _ => return None,
-            }
+    /// `None` without a Point geometry, otherwise its position converted
+    /// with [`position`](Self::position).
+    fn extract_point_coordinates(feature: &Feature) -> Option<Result<[Decimal; 2], AmpError>> {
+        match &feature.geometry.as_ref()?.value {
+            geojson::Value::Point(coords) => Some(Self::position(coords)),
+            _ => None,
+        }
+    }
+    /// Coordinates and `BELADRESS` of an address feature, or `None` if the
+    /// address import skips it.
+    ///
+    /// A feature is kept when it has a Point geometry of exactly two finite
+    /// coordinates and a `BELADRESS` property; a `BELADRESS` that is not a
+    /// string gives an empty address. Every other property is optional. Both
+    /// the parquet conversion and [`Self::validate_addresses`] go through this,
+    /// so the dry run counts exactly what the conversion keeps.
+    pub fn address_point(feature: &Feature) -> Option<([Decimal; 2], String)> {
+        let coordinates = Self::address_coordinates(feature)?;
+        let adress = feature.properties.as_ref()?.get("BELADRESS")?;
+        Some((coordinates, adress.as_str().unwrap_or_default().to_string()))
+    }
+    /// Point of an address feature, if it has exactly two finite coordinates
+    fn address_coordinates(feature: &Feature) -> Option<[Decimal; 2]> {
+        match &feature.geometry.as_ref()?.value {
+            geojson::Value::Point(coords) if coords.len() == 2 => Self::position(coords).ok(),
+            _ => None,
         }
-        None
     }
     /// Extract all line segments from LineString or MultiLineString geometry.
     ///
-    /// This function splits MultiLineString features into individual segments,
-    /// with each segment represented as `[[start_x, start_y], [end_x, end_y]]`.
-    /// This allows efficient spatial correlation by treating each street segment
-    /// independently.
+    /// Every line is split at each of its vertices, so a polyline of N points
+    /// gives N - 1 chained segments, each `[[start_x, start_y], [end_x, end_y]]`,
+    /// and a bent street is matched along its bends instead of the chord
+    /// between its ends. The lines of a MultiLineString are kept apart.
     ///
     /// # Arguments
     ///
@@ -108,82 +374,31 @@ impl DataLoader {
     ///
     /// # Returns
     ///
-    /// Vector of segment coordinate pairs, or `None` if extraction fails.
+    /// The segments of each line, or `None` if extraction fails.
     ///
     /// # Implementation Details
     ///
-    /// - Uses `.first()` and `.last()` for safe array access
-    /// - Invalid segments are logged and skipped, not causing failure
+    /// - Lines with fewer than two points or an [`AmpError::InvalidCoordinate`]
+    ///   are logged and skipped, not causing failure
     /// - Returns `None` only if no valid segments are found
-    fn extract_all_line_segments(feature: &Feature) -> Option<Vec<[[Decimal; 2]; 2]>> {
+    fn extract_all_line_segments(feature: &Feature) -> Option<Vec<Vec<[[Decimal; 2]; 2]>>> {
+        let lines = match &feature.geometry.as_ref()?.value {
+            geojson::Value::LineString(coords) => std::slice::from_ref(coords),
+            geojson::Value::MultiLineString(lines) => lines.as_slice(),
+            _ => return None,
+        };
         let mut segments = Vec::new();
-        if let Some(ref geom) = feature.geometry {
-            match &geom.value {
-                geojson::Value::LineString(coords) => {
-                    let first = coords.first()?;
-                    let last = coords.last()?;
-                    if first.len() < 2 || last.len() < 2 {
-                        eprintln!("[API] Invalid coordinate length in LineString");
-                        return None;
-                    }
-                    let x1 = Decimal::try_from(first[0]).ok()?;
-                    let y1 = Decimal::try_from(first[1]).ok()?;
-                    let x2 = Decimal::try_from(last[0]).ok()?;
-                    let y2 = Decimal::try_from(last[1]).ok()?;
-                    segments.push([[x1, y1], [x2, y2]]);
-                }
-                geojson::Value::MultiLineString(lines) => {
-                    for line in lines {
-                        let first = match line.first() {
-                            Some(f) => f,
-                            None => {
-                                eprintln!("[API] Empty line in MultiLineString");
-                                continue;
-                            }
-                        };
-                        let last = match line.last() {
-                            Some(l) => l,
-                            None => {
-                                eprintln!("[API] Empty line in MultiLineString");
-                                continue;
-                            }
-                        };
-                        if first.len() < 2 || last.len() < 2 {
-                            eprintln!("[API] Invalid coordinate length in MultiLineString",);
-                            continue;
-                        }
-                        let x1 = match Decimal::try_from(first[0]) {
-                            Ok(v) => v,
-                            Err(_) => {
-                                eprintln!("[API] Failed to convert x1 coordinate");
-                                continue;
-                            }
-                        };
-                        let y1 = match Decimal::try_from(first[1]) {
-                            Ok(v) => v,
-                            Err(_) => {
-                                eprintln!("[API] Failed to convert y1 coordinate");
-                                continue;
-                            }
-                        };
-                        let x2 = match Decimal::try_from(last[0]) {
-                            Ok(v) => v,
-                            Err(_) => {
-                                eprintln!("[API] Failed to convert x2 coordinate");
-                                continue;
-                            }
-                        };
-                        let y2 = match Decimal::try_from(last[1]) {
-                            Ok(v) => v,
-                            Err(_) => {
-                                eprintln!("[API] Failed to convert y2 coordinate");
-                                continue;
-                            }
-                        };
-                        segments.push([[x1, y1], [x2, y2]]);
-                    }
+        for line in lines {
+            let vertices: Result<Vec<[Decimal; 2]>, AmpError> = line
+                .iter()
+                .map(|position| Self::position(position))
+                .collect();
+            match vertices {
+                Ok(vertices) if vertices.len() >= 2 => {
+                    segments.push(vertices.windows(2).map(|pair| [pair[0], pair[1]]).collect())
                 }
-                _ => return None,
+                Ok(_) => eprintln!("[API] Line with fewer than two points"),
+                Err(e) => eprintln!("[API] Skipping line: {}", e),
             }
         }
         if segments.is_empty() {
@@ -221,18 +436,28 @@ impl DataLoader {
     ///
     /// # Returns
     ///
-    /// `Some(AdressClean)` if all required fields are present, `None` otherwise.
-    fn parse_address_feature(feature: Feature) -> Option<AdressClean> {
-        let props = feature.clone().properties?;
-        let coordinates = Self::extract_point_coordinates(&feature)?;
+    /// `Ok(AdressClean)` if all required fields are present, otherwise
+    /// `Err` with the reason the feature was rejected.
+    fn parse_address_feature(feature: Feature) -> Result<AdressClean, String> {
+        let props = feature.properties.as_ref().ok_or("missing properties")?;
+        let coordinates = Self::extract_point_coordinates(&feature)
+            .ok_or("missing Point geometry")?
+            .map_err(|e| e.to_string())?;
+        let required = |key: &str| {
+            props
+                .get(key)
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string())
+                .ok_or_else(|| format!("missing {}", key))
+        };
         let postnummer = props
             .get("POSTNR")
             .and_then(|v| v.as_str())
             .map(|s| s.to_string());
-        let adress = props.get("BELADRESS")?.as_str()?.to_string();
-        let gata = props.get("ADRESSOMR")?.as_str()?.to_string();
-        let gatunummer = props.get("ADRESSPLAT")?.as_str()?.to_string();
-        Some(AdressClean {
+        let adress = required("BELADRESS")?;
+        let gata = required("ADRESSOMR")?;
+        let gatunummer = required("ADRESSPLAT")?;
+        Ok(AdressClean {
             coordinates,
             postnummer,
             adress,
@@ -242,33 +467,40 @@ impl DataLoader {
     }
     /// Parse environmental parking restriction feature into multiple [`MiljoeDataClean`] entries.
     ///
-    /// For MultiLineString features with N segments, this returns N separate entries,
-    /// one per segment. This enables per-segment spatial matching.
+    /// Each line is split at its vertices (see [`extract_all_line_segments`]),
+    /// and each piece becomes a separate entry. This enables per-segment
+    /// spatial matching. Every piece gets the `length_m` of its whole line, so
+    /// a minimum segment length judges the street rather than one bend.
     ///
     /// # Arguments
     ///
     /// * `feature` - GeoJSON feature with parking restriction data
+    /// * `segment_id` - [`MiljoeDataClean::segment_id`] for every piece, the
+    ///   feature's index in its collection
     /// * `is_avgifter` - `true` for parking fees, `false` for environmental restrictions
     ///
     /// # Expected Properties
     ///
     /// - `taxa`, `value`, or `copyvalue`: Zone information
     /// - `tid`: Time range string
-    /// - `day`: Day of month (for environmental restrictions only)
+    /// - `day`: Day of month (for environmental restrictions only), or several
+    ///   days such as `"1:a och 15:e"`, see [`parse_dagar`]
     ///
     /// # Returns
     ///
-    /// Vector of `MiljoeDataClean` entries, one per line segment.
-    fn parse_miljoedata_feature(feature: Feature, is_avgifter: bool) -> Vec<MiljoeDataClean> {
+    /// Vector of `MiljoeDataClean` entries, one per line segment, or `Err` with
+    /// the reason the feature was rejected.
+    ///
+    /// [`extract_all_line_segments`]: Self::extract_all_line_segments
+    fn parse_miljoedata_feature(
+        feature: Feature,
+        segment_id: u64,
+        is_avgifter: bool,
+    ) -> Result<Vec<MiljoeDataClean>, String> {
         let mut results = Vec::new();
-        let props = match feature.clone().properties {
-            Some(p) => p,
-            None => return results,
-        };
-        let segments = match Self::extract_all_line_segments(&feature) {
-            Some(s) => s,
-            None => return results,
-        };
+        let props = feature.properties.as_ref().ok_or("missing properties")?;
+        let segments = Self::extract_all_line_segments(&feature)
+            .ok_or("missing or invalid LineString geometry")?;
         let info = if is_avgifter {
             props
                 .get("taxa")
@@ -285,37 +517,62 @@ impl DataLoader {
                 .unwrap_or("Okänd")
                 .to_string()
         };
-        let tid = props.get("tid").unwrap().to_string();
-        let dag = if is_avgifter {
-            0u8
+        let tid = Self::tid_property(props)
+            .map_err(|e| e.to_string())?
+            .ok_or("missing tid")?;
+        let dagar = if is_avgifter {
+            vec![0u8]
         } else {
             props
                 .get("day")
                 .and_then(|v| {
                     if let Some(num) = v.as_u64() {
-                        Some(num as u8)
-                    } else if let Some(s) = v.as_str() {
-                        s.parse::<u8>().ok()
+                        Some(vec![num as u8])
                     } else {
-                        None
+                        v.as_str().map(parse_dagar)
                     }
                 })
-                .unwrap_or(0)
+                .filter(|dagar| !dagar.is_empty())
+                .unwrap_or_else(|| vec![0])
         };
-        for coordinates in segments {
-            results.push(MiljoeDataClean {
-                coordinates,
-                info: info.clone(),
-                tid: tid.clone(),
-                dag,
-            });
+        for line in segments {
+            let length_m = line.iter().map(segment_length_meters).sum();
+            for coordinates in line {
+                results.push(MiljoeDataClean {
+                    length_m,
+                    segment_id: Some(segment_id),
+                    ..MiljoeDataClean::with_dagar(
+                        coordinates,
+                        info.clone(),
+                        tid.clone(),
+                        dagar.clone(),
+                    )
+                });
+            }
+        }
+        Ok(results)
+    }
+    /// The `tid` property of a miljö feature, `None` if it is missing or null.
+    ///
+    /// Text is returned as is, even if it doesn't parse as a time range, so
+    /// [`ParseReport`] can count it.
+    ///
+    /// # Errors
+    ///
+    /// [`AmpError::InvalidTime`] if the value is not text, e.g. a number.
+    fn tid_property(props: &geojson::JsonObject) -> Result<Option<String>, AmpError> {
+        match props.get("tid") {
+            None | Some(serde_json::Value::Null) => Ok(None),
+            Some(serde_json::Value::String(tid)) => Ok(Some(tid.clone())),
+            Some(other) => Err(AmpError::InvalidTime {
+                value: other.to_string(),
+            }),
         }
-        results
     }
     /// Parse parking zone feature into multiple [`ParkeringsDataClean`] entries.
     ///
-    /// Similar to [`parse_miljoedata_feature`], this splits MultiLineString features
-    /// into individual segments.
+    /// Similar to [`parse_miljoedata_feature`], this splits every line into
+    /// individual segments.
     ///
     /// # Expected Properties
     ///
@@ -325,19 +582,15 @@ impl DataLoader {
     ///
     /// # Returns
     ///
-    /// Vector of `ParkeringsDataClean` entries, one per line segment.
+    /// Vector of `ParkeringsDataClean` entries, one per line segment, or `Err`
+    /// with the reason the feature was rejected.
     ///
     /// [`parse_miljoedata_feature`]: Self::parse_miljoedata_feature
-    fn parse_parkering_feature(feature: Feature) -> Vec<ParkeringsDataClean> {
+    fn parse_parkering_feature(feature: Feature) -> Result<Vec<ParkeringsDataClean>, String> {
         let mut results = Vec::new();
-        let props = match feature.clone().properties {
-            Some(p) => p,
-            None => return results,
-        };
-        let segments = match Self::extract_all_line_segments(&feature) {
-            Some(s) => s,
-            None => return results,
-        };
+        let props = feature.properties.as_ref().ok_or("missing properties")?;
+        let segments = Self::extract_all_line_segments(&feature)
+            .ok_or("missing or invalid LineString geometry")?;
         let taxa = props
             .get("taxa")
             .and_then(|v| v.as_str())
@@ -352,7 +605,7 @@ impl DataLoader {
             .and_then(|v| v.as_str())
             .unwrap_or("Unknown")
             .to_string();
-        for coordinates in segments {
+        for coordinates in segments.into_iter().flatten() {
             results.push(ParkeringsDataClean {
                 coordinates,
                 taxa: taxa.clone(),
@@ -360,7 +613,69 @@ impl DataLoader {
                 typ_av_parkering: typ_av_parkering.clone(),
             });
         }
-        results
+        Ok(results)
+    }
+    /// Whether `path` starts with the gzip magic bytes
+    fn is_gzip(path: &str) -> Result<bool, AmpError> {
+        let mut magic = [0u8; 2];
+        let read = File::open(path)?.read(&mut magic)?;
+        Ok(read == magic.len() && magic == GZIP_MAGIC)
+    }
+    /// Read the FeatureCollection in `path`, decompressing it first if
+    /// `gzip` is set; `kind` names the data in errors
+    fn read_feature_collection(
+        path: &str,
+        kind: &str,
+        gzip: bool,
+    ) -> Result<FeatureCollection, AmpError> {
+        let content = if gzip {
+            let mut content = String::new();
+            GzDecoder::new(File::open(path)?).read_to_string(&mut content)?;
+            content
+        } else {
+            fs::read_to_string(path)?
+        };
+        let geojson: GeoJson = content.parse()?;
+        let GeoJson::FeatureCollection(collection) = geojson else {
+            return Err(AmpError::GeoJsonParse(format!(
+                "Invalid GeoJSON format for {}",
+                kind
+            )));
+        };
+        Ok(collection)
+    }
+    /// Read a GeoJSON FeatureCollection and parse each feature, collecting
+    /// per-feature failures as [`LoadWarning`]s instead of aborting.
+    ///
+    /// `parse` gets each feature with its index in the collection.
+    fn load_features<T>(
+        path: &str,
+        kind: &str,
+        gzip: bool,
+        parse: impl Fn(usize, Feature) -> Result<Vec<T>, String>,
+    ) -> Result<(Vec<T>, Vec<LoadWarning>), AmpError> {
+        let collection = Self::read_feature_collection(path, kind, gzip)?;
+        let mut items = Vec::new();
+        let mut warnings = Vec::new();
+        for (feature_index, feature) in collection.features.into_iter().enumerate() {
+            match parse(feature_index, feature) {
+                Ok(parsed) => items.extend(parsed),
+                Err(reason) => warnings.push(LoadWarning {
+                    source: path.to_string(),
+                    feature_index,
+                    reason,
+                }),
+            }
+        }
+        if !warnings.is_empty() {
+            eprintln!(
+                "[API] Skipped {} malformed {} feature(s) in {}",
+                warnings.len(),
+                kind,
+                path,
+            );
+        }
+        Ok((items, warnings))
     }
     /// Load address data from a GeoJSON file.
     ///
@@ -374,10 +689,8 @@ impl DataLoader {
     ///
     /// # Errors
     ///
-    /// Returns an error if:
-    /// - The file cannot be read
-    /// - The file is not valid GeoJSON
-    /// - The GeoJSON is not a FeatureCollection
+    /// Returns [`AmpError::Io`] if the file cannot be read, or
+    /// [`AmpError::GeoJsonParse`] if it is not a GeoJSON FeatureCollection.
     ///
     /// # Examples
     ///
@@ -388,25 +701,200 @@ impl DataLoader {
     /// println!("Loaded {} addresses", addresses.len());
     /// # Ok::<(), Box<dyn std::error::Error>>(())
     /// ```
-    pub fn load_addresses(path: &str) -> Result<Vec<AdressClean>, Box<dyn std::error::Error>> {
-        println!("Loading addresses from: {}", path);
-        let content = fs::read_to_string(path)?;
-        let geojson: GeoJson = content.parse()?;
-        let addresses: Vec<AdressClean> = if let GeoJson::FeatureCollection(collection) = geojson {
-            collection
-                .features
-                .into_iter()
-                .filter_map(Self::parse_address_feature)
-                .collect()
+    pub fn load_addresses(path: &str) -> Result<Vec<AdressClean>, AmpError> {
+        Ok(Self::load_addresses_with_warnings(path)?.0)
+    }
+    /// Load address data, returning skipped features as warnings.
+    ///
+    /// Same as [`load_addresses`], but features that fail to parse are reported
+    /// as [`LoadWarning`]s alongside the successfully parsed addresses.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error only if the file as a whole cannot be read or parsed.
+    ///
+    /// [`load_addresses`]: Self::load_addresses
+    pub fn load_addresses_with_warnings(
+        path: &str,
+    ) -> Result<(Vec<AdressClean>, Vec<LoadWarning>), AmpError> {
+        Self::load_addresses_from(path, false)
+    }
+    /// Load address data from a gzip-compressed GeoJSON file, e.g.
+    /// `adresser.geojson.gz`.
+    ///
+    /// # Errors
+    ///
+    /// As [`load_addresses`], plus [`AmpError::Io`] if the file is not
+    /// valid gzip.
+    ///
+    /// [`load_addresses`]: Self::load_addresses
+    pub fn load_addresses_gz(path: &str) -> Result<Vec<AdressClean>, AmpError> {
+        Ok(Self::load_addresses_from(path, true)?.0)
+    }
+    /// Load address data from a plain or gzip-compressed GeoJSON file.
+    ///
+    /// Compression is detected from the gzip magic bytes rather than the
+    /// file name, so a compressed export saved as `.json` still loads.
+    pub fn load_addresses_auto(path: &str) -> Result<Vec<AdressClean>, AmpError> {
+        if Self::is_gzip(path)? {
+            Self::load_addresses_gz(path)
         } else {
-            return Err("Invalid GeoJSON format for addresses".into());
-        };
+            Self::load_addresses(path)
+        }
+    }
+    fn load_addresses_from(
+        path: &str,
+        gzip: bool,
+    ) -> Result<(Vec<AdressClean>, Vec<LoadWarning>), AmpError> {
+        println!("Loading addresses from: {}", path);
+        let (addresses, warnings) = Self::load_features(path, "addresses", gzip, |_, f| {
+            Self::parse_address_feature(f).map(|a| vec![a])
+        })?;
         println!("Loaded {} addresses", addresses.len());
         for (i, addr) in addresses.iter().take(3).enumerate() {
             println!("  [{}] {} ({:?})", i + 1, addr.adress, addr.postnummer);
         }
+        Ok((addresses, warnings))
+    }
+    /// Load only the addresses accepted by `keep`.
+    ///
+    /// Every feature is still parsed, but rejected addresses are dropped
+    /// right away, so iterating on one neighborhood doesn't need a separately
+    /// filtered file. Malformed features are skipped as in [`load_addresses`].
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use amp_core::api::DataLoader;
+    ///
+    /// let storgatan = DataLoader::load_addresses_filtered("data/adresser.json", |addr| {
+    ///     addr.gata == "Storgatan"
+    /// })?;
+    /// println!("Loaded {} addresses on Storgatan", storgatan.len());
+    /// # Ok::<(), amp_core::error::AmpError>(())
+    /// ```
+    ///
+    /// [`load_addresses`]: Self::load_addresses
+    pub fn load_addresses_filtered(
+        path: &str,
+        keep: impl Fn(&AdressClean) -> bool,
+    ) -> Result<Vec<AdressClean>, AmpError> {
+        println!("Loading filtered addresses from: {}", path);
+        let (addresses, _) = Self::load_features(path, "addresses", false, |_, f| {
+            Self::parse_address_feature(f).map(|a| if keep(&a) { vec![a] } else { Vec::new() })
+        })?;
+        println!("Kept {} addresses", addresses.len());
+        Ok(addresses)
+    }
+    /// Load only the addresses in one postal code, e.g. `"21150"`.
+    ///
+    /// Spaces are ignored on both sides, so `"211 50"` matches as well.
+    /// Addresses without a postal code are dropped.
+    pub fn load_postal(path: &str, postnummer: &str) -> Result<Vec<AdressClean>, AmpError> {
+        let wanted = postnummer.replace(' ', "");
+        Self::load_addresses_filtered(path, |addr| {
+            addr.postnummer
+                .as_deref()
+                .is_some_and(|p| p.replace(' ', "") == wanted)
+        })
+    }
+    /// Load only the addresses with a postal code in `min..=max`.
+    ///
+    /// Codes are compared as numbers with spaces ignored, so `"211 50"` is
+    /// 21150. Addresses without a numeric postal code are dropped. Malmö's
+    /// codes run from 211xx to 218xx, so one district is a small slice of
+    /// the full file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `min > max`, or if the file can't be loaded as in
+    /// [`load_addresses`].
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use amp_core::api::DataLoader;
+    ///
+    /// let limhamn = DataLoader::load_addresses_in_postal_range("data/adresser.json", 21600, 21699)?;
+    /// println!("Loaded {} addresses in 216xx", limhamn.len());
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    ///
+    /// [`load_addresses`]: Self::load_addresses
+    pub fn load_addresses_in_postal_range(
+        path: &str,
+        min: u32,
+        max: u32,
+    ) -> Result<Vec<AdressClean>, Box<dyn std::error::Error>> {
+        if min > max {
+            return Err(format!("Invalid postal code range {}..={}", min, max).into());
+        }
+        let addresses = Self::load_addresses_filtered(path, |addr| {
+            addr.postnummer
+                .as_deref()
+                .and_then(|p| p.replace(' ', "").parse::<u32>().ok())
+                .is_some_and(|code| (min..=max).contains(&code))
+        })?;
         Ok(addresses)
     }
+    /// Check every address feature without converting any, counting why
+    /// features would be skipped.
+    ///
+    /// Run this before an import to see how much of the file will be lost;
+    /// see [`ValidationReport`] for the reasons counted.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AmpError::Io`] or [`AmpError::GeoJsonParse`] if the file as
+    /// a whole can't be read, as in [`load_addresses`].
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use amp_core::api::DataLoader;
+    ///
+    /// let report = DataLoader::validate_addresses("data/adresser.json")?;
+    /// println!("{}", report);
+    /// # Ok::<(), amp_core::error::AmpError>(())
+    /// ```
+    ///
+    /// [`load_addresses`]: Self::load_addresses
+    pub fn validate_addresses(path: &str) -> Result<ValidationReport, AmpError> {
+        let collection = Self::read_feature_collection(path, "addresses", false)?;
+        let mut report = ValidationReport::default();
+        for feature in &collection.features {
+            report.total += 1;
+            let props = feature.properties.as_ref();
+            let text = |key: &str| props.and_then(|p| p.get(key)).and_then(|v| v.as_str());
+            match props.and_then(|p| p.get("POSTNR")) {
+                None => report.missing_postnr += 1,
+                Some(value) => {
+                    let parses = value
+                        .as_str()
+                        .is_some_and(|p| p.replace(' ', "").parse::<u32>().is_ok());
+                    if !parses {
+                        report.invalid_postnr += 1;
+                    }
+                }
+            }
+            if props.and_then(|p| p.get("BELADRESS")).is_none() {
+                report.missing_beladress += 1;
+            }
+            if text("ADRESSOMR").is_none() {
+                report.missing_adressomr += 1;
+            }
+            if text("ADRESSPLAT").is_none() {
+                report.missing_adressplat += 1;
+            }
+            if Self::address_coordinates(feature).is_none() {
+                report.invalid_geometry += 1;
+            }
+            if Self::address_point(feature).is_some() {
+                report.valid += 1;
+            }
+        }
+        Ok(report)
+    }
     /// Load environmental parking restriction data from a GeoJSON file.
     ///
     /// This typically contains street cleaning schedules and time-restricted zones.
@@ -422,26 +910,25 @@ impl DataLoader {
     ///
     /// # Errors
     ///
-    /// Returns an error if:
-    /// - The file cannot be read
-    /// - The file is not valid GeoJSON
-    /// - The GeoJSON is not a FeatureCollection
-    pub fn load_miljodata(path: &str) -> Result<Vec<MiljoeDataClean>, Box<dyn std::error::Error>> {
+    /// Returns [`AmpError::Io`] if the file cannot be read, or
+    /// [`AmpError::GeoJsonParse`] if it is not a GeoJSON FeatureCollection.
+    pub fn load_miljodata(path: &str) -> Result<Vec<MiljoeDataClean>, AmpError> {
+        Ok(Self::load_miljodata_with_warnings(path)?.0)
+    }
+    /// Load environmental parking data, returning skipped features as warnings.
+    ///
+    /// See [`load_addresses_with_warnings`] for how warnings are collected.
+    ///
+    /// [`load_addresses_with_warnings`]: Self::load_addresses_with_warnings
+    pub fn load_miljodata_with_warnings(
+        path: &str,
+    ) -> Result<(Vec<MiljoeDataClean>, Vec<LoadWarning>), AmpError> {
         println!("Loading miljödata from: {}", path);
-        let content = fs::read_to_string(path)?;
-        let geojson: GeoJson = content.parse()?;
-        let miljodata: Vec<MiljoeDataClean> =
-            if let GeoJson::FeatureCollection(collection) = geojson {
-                collection
-                    .features
-                    .into_iter()
-                    .flat_map(|f| Self::parse_miljoedata_feature(f, false))
-                    .collect()
-            } else {
-                return Err("Invalid GeoJSON format for miljödata".into());
-            };
+        let (miljodata, warnings) = Self::load_features(path, "miljödata", false, |index, f| {
+            Self::parse_miljoedata_feature(f, index as u64, false)
+        })?;
         println!("Loaded {} miljödata segments", miljodata.len());
-        Ok(miljodata)
+        Ok((miljodata, warnings))
     }
     /// Load parking zone data from a GeoJSON file.
     ///
@@ -458,30 +945,269 @@ impl DataLoader {
     ///
     /// # Errors
     ///
-    /// Returns an error if:
-    /// - The file cannot be read
-    /// - The file is not valid GeoJSON
-    /// - The GeoJSON is not a FeatureCollection
-    pub fn load_parkering(
+    /// Returns [`AmpError::Io`] if the file cannot be read, or
+    /// [`AmpError::GeoJsonParse`] if it is not a GeoJSON FeatureCollection.
+    pub fn load_parkering(path: &str) -> Result<Vec<ParkeringsDataClean>, AmpError> {
+        Ok(Self::load_parkering_with_warnings(path)?.0)
+    }
+    /// Load parking zone data, returning skipped features as warnings.
+    ///
+    /// See [`load_addresses_with_warnings`] for how warnings are collected.
+    ///
+    /// [`load_addresses_with_warnings`]: Self::load_addresses_with_warnings
+    pub fn load_parkering_with_warnings(
         path: &str,
-    ) -> Result<Vec<ParkeringsDataClean>, Box<dyn std::error::Error>> {
+    ) -> Result<(Vec<ParkeringsDataClean>, Vec<LoadWarning>), AmpError> {
+        Self::load_parkering_from(path, false)
+    }
+    /// Load parking zone data from a gzip-compressed GeoJSON file.
+    ///
+    /// See [`load_addresses_gz`].
+    ///
+    /// [`load_addresses_gz`]: Self::load_addresses_gz
+    pub fn load_parkering_gz(path: &str) -> Result<Vec<ParkeringsDataClean>, AmpError> {
+        Ok(Self::load_parkering_from(path, true)?.0)
+    }
+    fn load_parkering_from(
+        path: &str,
+        gzip: bool,
+    ) -> Result<(Vec<ParkeringsDataClean>, Vec<LoadWarning>), AmpError> {
         println!("Loading parkeringsavgifter from: {}", path);
-        let content = fs::read_to_string(path)?;
-        let geojson: GeoJson = content.parse()?;
-        let parkering: Vec<ParkeringsDataClean> =
-            if let GeoJson::FeatureCollection(collection) = geojson {
-                collection
-                    .features
-                    .into_iter()
-                    .flat_map(Self::parse_parkering_feature)
-                    .collect()
-            } else {
-                return Err("Invalid GeoJSON format for parkeringsavgifter".into());
-            };
+        let (parkering, warnings) =
+            Self::load_features(path, "parkeringsavgifter", gzip, |_, f| {
+                Self::parse_parkering_feature(f)
+            })?;
         println!("Loaded {} parkering segments", parkering.len());
-        Ok(parkering)
+        Ok((parkering, warnings))
+    }
+}
+/// First two bytes of every gzip stream
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+/// File names of the three sources inside a data directory
+const ADDRESS_FILE: &str = "adresser";
+const MILJO_FILE: &str = "miljoparkeringar";
+const PARKERING_FILE: &str = "parkeringsavgifter";
+/// Directory the sources are read from unless configured otherwise
+pub const DEFAULT_DATA_DIR: &str = "data";
+/// Location of the three source files.
+///
+/// File names are stored without extension, since the same names are used
+/// for the GeoJSON sources and the parquet files written from them.
+///
+/// # Environment
+///
+/// [`DataPaths::from_env`] reads, each falling back to the default:
+///
+/// | Variable | Default |
+/// |---|---|
+/// | `AMP_DATA_DIR` | `data` |
+/// | `AMP_ADRESSER_FILE` | `adresser` |
+/// | `AMP_MILJO_FILE` | `miljoparkeringar` |
+/// | `AMP_PARKERING_FILE` | `parkeringsavgifter` |
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DataPaths {
+    /// Directory containing the source files
+    pub dir: PathBuf,
+    /// Address file name without extension
+    pub adresser: String,
+    /// Environmental parking file name without extension
+    pub miljo: String,
+    /// Parking zone file name without extension
+    pub parkering: String,
+}
+impl Default for DataPaths {
+    fn default() -> Self {
+        Self::new(DEFAULT_DATA_DIR)
+    }
+}
+impl DataPaths {
+    /// Default file names inside `dir`.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            adresser: ADDRESS_FILE.to_string(),
+            miljo: MILJO_FILE.to_string(),
+            parkering: PARKERING_FILE.to_string(),
+        }
+    }
+    /// Defaults overridden by the `AMP_*` environment variables.
+    pub fn from_env() -> Self {
+        Self::from_vars(|name| std::env::var(name).ok())
+    }
+    /// Defaults overridden by whatever `var` returns for each `AMP_*` name
+    fn from_vars(var: impl Fn(&str) -> Option<String>) -> Self {
+        let var = |name: &str| var(name).filter(|value| !value.is_empty());
+        let defaults = Self::new(var("AMP_DATA_DIR").unwrap_or_else(|| DEFAULT_DATA_DIR.into()));
+        Self {
+            adresser: var("AMP_ADRESSER_FILE").unwrap_or(defaults.adresser),
+            miljo: var("AMP_MILJO_FILE").unwrap_or(defaults.miljo),
+            parkering: var("AMP_PARKERING_FILE").unwrap_or(defaults.parkering),
+            dir: defaults.dir,
+        }
+    }
+    /// Path of the address file with the given extension, e.g. `"json"`
+    pub fn adresser(&self, extension: &str) -> String {
+        source_path(&self.dir, &self.adresser, extension)
+    }
+    /// Path of the environmental parking file with the given extension
+    pub fn miljo(&self, extension: &str) -> String {
+        source_path(&self.dir, &self.miljo, extension)
+    }
+    /// Path of the parking zone file with the given extension
+    pub fn parkering(&self, extension: &str) -> String {
+        source_path(&self.dir, &self.parkering, extension)
+    }
+}
+/// Addresses together with both zone types.
+///
+/// The single loading entry point shared by the server, scripts and apps.
+/// Construct it with [`DataSet::load_from_dir`] for the GeoJSON sources or
+/// [`DataSet::load_from_parquet`] for preprocessed files.
+///
+/// # Examples
+///
+/// ```no_run
+/// use amp_core::api::DataSet;
+///
+/// let DataSet { addresses, miljo, parkering } = DataSet::load_from_dir("data")?;
+/// println!("{} addresses, {} miljö, {} parkering", addresses.len(), miljo.len(), parkering.len());
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct DataSet {
+    pub addresses: Vec<AdressClean>,
+    pub miljo: Vec<MiljoeDataClean>,
+    pub parkering: Vec<ParkeringsDataClean>,
+}
+impl DataSet {
+    /// Load `adresser.json`, `miljoparkeringar.json` and
+    /// `parkeringsavgifter.json` from `dir`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AmpError::Io`] or [`AmpError::GeoJsonParse`] if any file
+    /// cannot be read or is not a FeatureCollection.
+    pub fn load_from_dir(dir: impl AsRef<Path>) -> Result<Self, AmpError> {
+        Ok(Self::load_from_dir_with_warnings(dir)?.0)
+    }
+    /// Load the GeoJSON sources from `dir`, returning skipped features as
+    /// warnings.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any file cannot be read or is not a FeatureCollection.
+    pub fn load_from_dir_with_warnings(
+        dir: impl AsRef<Path>,
+    ) -> Result<(Self, Vec<LoadWarning>), AmpError> {
+        Self::load_from_paths_with_warnings(&DataPaths::new(dir.as_ref()))
+    }
+    /// Load the GeoJSON sources named by `paths`, returning skipped features
+    /// as warnings.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any file cannot be read or is not a FeatureCollection.
+    pub fn load_from_paths_with_warnings(
+        paths: &DataPaths,
+    ) -> Result<(Self, Vec<LoadWarning>), AmpError> {
+        let (mut data, mut warnings) = Self::load_miljo_only_from_paths_with_warnings(paths)?;
+        let (parkering, parkering_warnings) =
+            DataLoader::load_parkering_with_warnings(&paths.parkering("json"))?;
+        data.parkering = parkering;
+        warnings.extend(parkering_warnings);
+        println!("\n✓ Data loading complete");
+        println!("  Total addresses: {}", data.addresses.len());
+        println!("  Total miljödata segments: {}", data.miljo.len());
+        println!("  Total parkering segments: {}", data.parkering.len());
+        Ok((data, warnings))
+    }
+    /// Load addresses and miljödata from `dir`, leaving `parkering` empty.
+    ///
+    /// Skips parsing the parking fee file when only street cleaning zones
+    /// are needed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if either file cannot be read or is not a FeatureCollection.
+    pub fn load_miljo_only_from_dir_with_warnings(
+        dir: impl AsRef<Path>,
+    ) -> Result<(Self, Vec<LoadWarning>), AmpError> {
+        Self::load_miljo_only_from_paths_with_warnings(&DataPaths::new(dir.as_ref()))
+    }
+    /// Load addresses and miljödata named by `paths`, leaving `parkering` empty.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if either file cannot be read or is not a FeatureCollection.
+    pub fn load_miljo_only_from_paths_with_warnings(
+        paths: &DataPaths,
+    ) -> Result<(Self, Vec<LoadWarning>), AmpError> {
+        let (addresses, mut warnings) =
+            DataLoader::load_addresses_with_warnings(&paths.adresser("json"))?;
+        let (miljo, miljo_warnings) =
+            DataLoader::load_miljodata_with_warnings(&paths.miljo("json"))?;
+        warnings.extend(miljo_warnings);
+        Ok((
+            Self {
+                addresses,
+                miljo,
+                parkering: Vec::new(),
+            },
+            warnings,
+        ))
+    }
+    /// Load `adresser.parquet`, `miljoparkeringar.parquet` and
+    /// `parkeringsavgifter.parquet` from `dir`.
+    ///
+    /// The files are the ones written by [`DataSet::write_parquet`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any file is missing or does not match its schema.
+    pub fn load_from_parquet(dir: impl AsRef<Path>) -> Result<Self, Box<dyn std::error::Error>> {
+        Self::load_from_parquet_paths(&DataPaths::new(dir.as_ref()))
+    }
+    /// Load the parquet files named by `paths`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any file is missing or does not match its schema.
+    pub fn load_from_parquet_paths(paths: &DataPaths) -> Result<Self, Box<dyn std::error::Error>> {
+        let open = |path: String| -> Result<fs::File, Box<dyn std::error::Error>> {
+            fs::File::open(&path).map_err(|e| format!("Failed to open {}: {}", path, e).into())
+        };
+        Ok(Self {
+            addresses: parquet::read_address_parquet(open(paths.adresser("parquet"))?)?,
+            miljo: parquet::read_miljo_clean_parquet(open(paths.miljo("parquet"))?)?,
+            parkering: parquet::read_parkering_clean_parquet(open(paths.parkering("parquet"))?)?,
+        })
+    }
+    /// Write all three sources as parquet files into `dir`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a source is empty or a file cannot be written.
+    pub fn write_parquet(&self, dir: impl AsRef<Path>) -> Result<(), Box<dyn std::error::Error>> {
+        self.write_parquet_paths(&DataPaths::new(dir.as_ref()))
+    }
+    /// Write all three sources as the parquet files named by `paths`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a source is empty or a file cannot be written.
+    pub fn write_parquet_paths(&self, paths: &DataPaths) -> Result<(), Box<dyn std::error::Error>> {
+        parquet::write_adress_clean_parquet(self.addresses.clone(), &paths.adresser("parquet"))?;
+        parquet::write_miljo_clean_parquet(&self.miljo, &paths.miljo("parquet"))?;
+        parquet::write_parkering_clean_parquet(&self.parkering, &paths.parkering("parquet"))?;
+        Ok(())
     }
 }
+/// Path of a source file inside a data directory
+fn source_path(dir: &Path, name: &str, extension: &str) -> String {
+    dir.join(format!("{}.{}", name, extension))
+        .to_string_lossy()
+        .into_owned()
+}
 /// Load all three data sources from standard file paths.
 ///
 /// This is a convenience function that loads addresses, environmental parking
@@ -512,15 +1238,25 @@ impl DataLoader {
 /// println!("Loaded {} parkering segments", parkering.len());
 /// # Ok::<(), Box<dyn std::error::Error>>(())
 /// ```
+#[deprecated(note = "use `DataSet::load_from_dir` instead")]
+#[allow(deprecated)]
 pub fn api() -> Result<ApiResult, Box<dyn std::error::Error>> {
-    let addresses = DataLoader::load_addresses("data/adresser.json")?;
-    let miljodata = DataLoader::load_miljodata("data/miljoparkeringar.json")?;
-    let parkering = DataLoader::load_parkering("data/parkeringsavgifter.json")?;
-    println!("\n✓ Data loading complete");
-    println!("  Total addresses: {}", addresses.len());
-    println!("  Total miljödata segments: {}", miljodata.len());
-    println!("  Total parkering segments: {}", parkering.len());
-    Ok((addresses, miljodata, parkering))
+    let data = DataSet::load_from_dir("data")?;
+    Ok((data.addresses, data.miljo, data.parkering))
+}
+/// Load all three data sources, returning skipped features as warnings.
+///
+/// Same as [`api`], but also returns every [`LoadWarning`] collected from the
+/// three files so callers can report or reject partially malformed input.
+///
+/// # Errors
+///
+/// Returns an error if any file cannot be read or is not a FeatureCollection.
+#[deprecated(note = "use `DataSet::load_from_dir_with_warnings` instead")]
+#[allow(deprecated)]
+pub fn api_with_warnings() -> Result<(ApiResult, Vec<LoadWarning>), Box<dyn std::error::Error>> {
+    let (data, warnings) = DataSet::load_from_dir_with_warnings("data")?;
+    Ok(((data.addresses, data.miljo, data.parkering), warnings))
 }
 /// Load only addresses and environmental parking data (excludes parking zones).
 ///
@@ -533,9 +1269,425 @@ pub fn api() -> Result<ApiResult, Box<dyn std::error::Error>> {
 /// # Errors
 ///
 /// Returns an error if either file cannot be loaded or parsed.
+#[deprecated(note = "use `DataSet::load_miljo_only_from_dir_with_warnings` instead")]
 pub fn api_miljo_only()
 -> Result<(Vec<AdressClean>, Vec<MiljoeDataClean>), Box<dyn std::error::Error>> {
-    let addresses = DataLoader::load_addresses("data/adresser.json")?;
-    let miljodata = DataLoader::load_miljodata("data/miljoparkeringar.json")?;
-    Ok((addresses, miljodata))
+    let (data, _) = DataSet::load_miljo_only_from_dir_with_warnings("data")?;
+    Ok((data.addresses, data.miljo))
+}
+/// Load addresses and environmental parking data, returning skipped features
+/// as warnings.
+///
+/// # Errors
+///
+/// Returns an error if either file cannot be read or is not a FeatureCollection.
+#[deprecated(note = "use `DataSet::load_miljo_only_from_dir_with_warnings` instead")]
+#[allow(deprecated)]
+pub fn api_miljo_only_with_warnings()
+-> Result<(MiljoOnlyResult, Vec<LoadWarning>), Box<dyn std::error::Error>> {
+    let (data, warnings) = DataSet::load_miljo_only_from_dir_with_warnings("data")?;
+    Ok(((data.addresses, data.miljo), warnings))
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn test_data_paths_defaults_and_env_overrides() {
+        let defaults = DataPaths::default();
+        assert_eq!(
+            defaults.adresser("json"),
+            Path::new("data").join("adresser.json").to_string_lossy()
+        );
+        assert_eq!(DataPaths::from_vars(|_| None), defaults);
+        let paths = DataPaths::from_vars(|name| match name {
+            "AMP_DATA_DIR" => Some("/srv/lund".to_string()),
+            "AMP_MILJO_FILE" => Some("gatusopning".to_string()),
+            "AMP_PARKERING_FILE" => Some(String::new()),
+            _ => None,
+        });
+        assert_eq!(paths.dir, PathBuf::from("/srv/lund"));
+        assert_eq!(
+            paths.miljo("parquet"),
+            Path::new("/srv/lund")
+                .join("gatusopning.parquet")
+                .to_string_lossy()
+        );
+        assert_eq!(paths.adresser, "adresser");
+        assert_eq!(paths.parkering, "parkeringsavgifter");
+    }
+    #[test]
+    fn test_load_warnings_skip_malformed_features() {
+        let path = std::env::temp_dir().join("amp_api_load_warnings_test.json");
+        let content = r#"{"type":"FeatureCollection","features":[
+            {"type":"Feature","geometry":{"type":"LineString","coordinates":[[13.0,55.0],[13.001,55.001]]},
+             "properties":{"value":"Städning","tid":"0800-1200","day":5}},
+            {"type":"Feature","geometry":{"type":"LineString","coordinates":[[13.0,55.0],[13.001,55.001]]},
+             "properties":{"value":"Städning","day":6}},
+            {"type":"Feature","geometry":{"type":"Point","coordinates":[13.0,55.0]},
+             "properties":{"value":"Städning","tid":"0800-1200","day":7}}
+        ]}"#;
+        fs::write(&path, content).unwrap();
+        let (data, warnings) =
+            DataLoader::load_miljodata_with_warnings(path.to_str().unwrap()).unwrap();
+        let _ = fs::remove_file(&path);
+        assert_eq!(data.len(), 1);
+        assert_eq!(data[0].dag(), 5);
+        assert_eq!(data[0].tid, "0800-1200");
+        assert_eq!(warnings.len(), 2);
+        assert_eq!(warnings[0].feature_index, 1);
+        assert_eq!(warnings[0].reason, "missing tid");
+        assert_eq!(warnings[1].feature_index, 2);
+    }
+    #[test]
+    fn test_invalid_time_and_coordinate_are_named() {
+        let feature = |geometry: &str, tid: &str| {
+            format!(
+                r#"{{"type":"Feature","geometry":{},"properties":{{"value":"Städning","tid":{},"day":5}}}}"#,
+                geometry, tid
+            )
+        };
+        let line = r#"{"type":"LineString","coordinates":[[13.0,55.0],[13.001,55.001]]}"#;
+        let off_the_map = r#"{"type":"LineString","coordinates":[[13.0,55.0],[13.001,95.0]]}"#;
+        let content = format!(
+            r#"{{"type":"FeatureCollection","features":[{},{},{}]}}"#,
+            feature(line, r#""0800-1200""#),
+            feature(line, "800"),
+            feature(off_the_map, r#""0800-1200""#),
+        );
+        let path = std::env::temp_dir().join("amp_api_invalid_values_test.json");
+        fs::write(&path, content).unwrap();
+        let (data, warnings) =
+            DataLoader::load_miljodata_with_warnings(path.to_str().unwrap()).unwrap();
+        let _ = fs::remove_file(&path);
+        assert_eq!(data.len(), 1);
+        assert_eq!(warnings.len(), 2);
+        assert_eq!(
+            warnings[0].reason,
+            AmpError::InvalidTime {
+                value: "800".to_string()
+            }
+            .to_string()
+        );
+        assert_eq!(warnings[1].feature_index, 2);
+        let report = ParseReport::from_miljodata(&data, &warnings);
+        assert_eq!((report.total, report.rejected_by_time), (2, 1));
+        assert!(matches!(
+            DataLoader::position(&[13.0, f64::NAN]),
+            Err(AmpError::InvalidCoordinate)
+        ));
+        assert!(matches!(
+            DataLoader::position(&[181.0, 55.0]),
+            Err(AmpError::InvalidCoordinate)
+        ));
+        assert!(matches!(
+            DataLoader::position(&[13.0]),
+            Err(AmpError::InvalidCoordinate)
+        ));
+        assert_eq!(
+            DataLoader::position(&[13.5, 55.25, 12.0]).unwrap(),
+            [Decimal::new(135, 1), Decimal::new(5525, 2)]
+        );
+    }
+    #[test]
+    fn test_load_postal_keeps_one_postal_code() {
+        let path = std::env::temp_dir().join("amp_api_load_postal_test.json");
+        let feature = |adress: &str, nummer: &str, postnr: Option<&str>| {
+            let postnr = postnr.map_or("null".to_string(), |p| format!("\"{}\"", p));
+            format!(
+                r#"{{"type":"Feature","geometry":{{"type":"Point","coordinates":[13.0,55.6]}},"properties":{{"BELADRESS":"{} {}","ADRESSOMR":"{}","ADRESSPLAT":"{}","POSTNR":{}}}}}"#,
+                adress, nummer, adress, nummer, postnr,
+            )
+        };
+        let features = [
+            feature("Storgatan", "1", Some("21150")),
+            feature("Storgatan", "2", Some("211 50")),
+            feature("Lilla Torg", "1", Some("21134")),
+            feature("Okänd väg", "3", None),
+        ];
+        let content = format!(
+            r#"{{"type":"FeatureCollection","features":[{}]}}"#,
+            features.join(",")
+        );
+        fs::write(&path, content).unwrap();
+        let path_str = path.to_str().unwrap();
+        let postal = DataLoader::load_postal(path_str, "21150").unwrap();
+        let by_street =
+            DataLoader::load_addresses_filtered(path_str, |addr| addr.gata == "Lilla Torg")
+                .unwrap();
+        let all = DataLoader::load_addresses(path_str).unwrap();
+        let _ = fs::remove_file(&path);
+        let adresser: Vec<_> = postal.iter().map(|a| a.adress.as_str()).collect();
+        assert_eq!(adresser, vec!["Storgatan 1", "Storgatan 2"]);
+        assert_eq!(by_street.len(), 1);
+        assert_eq!(by_street[0].postnummer.as_deref(), Some("21134"));
+        assert_eq!(all.len(), 4);
+    }
+    #[test]
+    fn test_load_addresses_in_postal_range() {
+        let path = std::env::temp_dir().join("amp_api_postal_range_test.json");
+        let feature = |nummer: &str, postnr: &str| {
+            format!(
+                r#"{{"type":"Feature","geometry":{{"type":"Point","coordinates":[13.0,55.6]}},"properties":{{"BELADRESS":"Storgatan {}","ADRESSOMR":"Storgatan","ADRESSPLAT":"{}","POSTNR":"{}"}}}}"#,
+                nummer, nummer, postnr,
+            )
+        };
+        let features = [
+            feature("1", "21150"),
+            feature("2", "214 38"),
+            feature("3", "21438"),
+            feature("4", "21756"),
+        ];
+        let content = format!(
+            r#"{{"type":"FeatureCollection","features":[{}]}}"#,
+            features.join(",")
+        );
+        fs::write(&path, content).unwrap();
+        let path_str = path.to_str().unwrap();
+        let middle = DataLoader::load_addresses_in_postal_range(path_str, 21400, 21499).unwrap();
+        let exact = DataLoader::load_addresses_in_postal_range(path_str, 21438, 21438).unwrap();
+        let reversed = DataLoader::load_addresses_in_postal_range(path_str, 21499, 21400);
+        let _ = fs::remove_file(&path);
+        let adresser: Vec<_> = middle.iter().map(|a| a.adress.as_str()).collect();
+        assert_eq!(adresser, vec!["Storgatan 2", "Storgatan 3"]);
+        assert_eq!(exact.len(), 2);
+        assert!(reversed.is_err());
+    }
+    #[test]
+    fn test_load_addresses_bad_geojson() {
+        let path = std::env::temp_dir().join("amp_api_bad_geojson_test.json");
+        fs::write(&path, r#"{"type":"FeatureCollection","features":["#).unwrap();
+        let truncated = DataLoader::load_addresses(path.to_str().unwrap());
+        fs::write(&path, r#"{"type":"Point","coordinates":[13.0,55.6]}"#).unwrap();
+        let not_a_collection = DataLoader::load_addresses(path.to_str().unwrap());
+        let _ = fs::remove_file(&path);
+        let missing = DataLoader::load_addresses(path.to_str().unwrap());
+        assert!(matches!(truncated, Err(AmpError::GeoJsonParse(_))));
+        assert!(matches!(not_a_collection, Err(AmpError::GeoJsonParse(_))));
+        assert!(matches!(missing, Err(AmpError::Io(_))));
+    }
+    #[test]
+    fn test_validate_addresses_counts_each_reason() {
+        let path = std::env::temp_dir().join("amp_api_validate_addresses_test.json");
+        let point = r#"{"type":"Point","coordinates":[13.0,55.6]}"#;
+        let content = format!(
+            r#"{{"type":"FeatureCollection","features":[
+                {{"type":"Feature","geometry":{point},"properties":{{"BELADRESS":"Storgatan 1","ADRESSOMR":"Storgatan","ADRESSPLAT":"1","POSTNR":"211 50"}}}},
+                {{"type":"Feature","geometry":{point},"properties":{{"ADRESSOMR":"Storgatan","ADRESSPLAT":"2","POSTNR":"21150"}}}},
+                {{"type":"Feature","geometry":{point},"properties":{{"BELADRESS":"Storgatan 3","ADRESSOMR":"Storgatan","ADRESSPLAT":"3","POSTNR":null}}}},
+                {{"type":"Feature","geometry":null,"properties":{{"BELADRESS":"Storgatan 4","ADRESSOMR":"Storgatan","ADRESSPLAT":"4","POSTNR":"21150"}}}},
+                {{"type":"Feature","geometry":{point},"properties":{{"BELADRESS":"Storgatan 5"}}}}
+            ]}}"#
+        );
+        fs::write(&path, content).unwrap();
+        let report = DataLoader::validate_addresses(path.to_str().unwrap()).unwrap();
+        let _ = fs::remove_file(&path);
+        assert_eq!(
+            report,
+            ValidationReport {
+                total: 5,
+                valid: 3,
+                missing_postnr: 1,
+                missing_beladress: 1,
+                missing_adressomr: 1,
+                missing_adressplat: 1,
+                invalid_postnr: 1,
+                invalid_geometry: 1,
+            }
+        );
+        assert_eq!(report.invalid(), 2);
+        assert_eq!(
+            report.to_string(),
+            "3/5 features valid: 1 missing BELADRESS, 1 invalid geometry (kept with 1 missing POSTNR, \
+             1 missing ADRESSOMR, 1 missing ADRESSPLAT, 1 null or unparseable POSTNR)"
+        );
+    }
+    #[test]
+    fn test_load_gzipped_geojson_matches_plain() {
+        use flate2::Compression;
+        use flate2::write::GzEncoder;
+        use std::io::Write;
+        let dir = std::env::temp_dir();
+        let plain = dir.join("amp_api_gz_test_adresser.json");
+        let gz = dir.join("amp_api_gz_test_adresser.geojson.gz");
+        let content = r#"{"type":"FeatureCollection","features":[
+            {"type":"Feature","geometry":{"type":"Point","coordinates":[13.0,55.6]},
+             "properties":{"BELADRESS":"Storgatan 1","ADRESSOMR":"Storgatan","ADRESSPLAT":"1","POSTNR":"211 50"}},
+            {"type":"Feature","geometry":{"type":"Point","coordinates":[13.01,55.61]},
+             "properties":{"BELADRESS":"Östra Förstadsgatan 12B","ADRESSOMR":"Östra Förstadsgatan","ADRESSPLAT":"12B","POSTNR":"21131"}}
+        ]}"#;
+        fs::write(&plain, content).unwrap();
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(content.as_bytes()).unwrap();
+        fs::write(&gz, encoder.finish().unwrap()).unwrap();
+        let (plain, gz) = (plain.to_str().unwrap(), gz.to_str().unwrap());
+        let expected = DataLoader::load_addresses(plain).unwrap();
+        let from_gz = DataLoader::load_addresses_gz(gz).unwrap();
+        let auto_gz = DataLoader::load_addresses_auto(gz).unwrap();
+        let auto_plain = DataLoader::load_addresses_auto(plain).unwrap();
+        let not_gzip = DataLoader::load_addresses_gz(plain);
+        let _ = fs::remove_file(plain);
+        let _ = fs::remove_file(gz);
+        assert_eq!(expected.len(), 2);
+        assert_eq!(from_gz, expected);
+        assert_eq!(auto_gz, expected);
+        assert_eq!(auto_plain, expected);
+        assert!(matches!(not_gzip, Err(AmpError::Io(_))));
+    }
+    #[test]
+    fn test_load_miljodata_several_days() {
+        let path = std::env::temp_dir().join("amp_api_several_days_test.json");
+        let content = r#"{"type":"FeatureCollection","features":[
+            {"type":"Feature","geometry":{"type":"LineString","coordinates":[[13.0,55.0],[13.001,55.001]]},
+             "properties":{"value":"Städning","tid":"0800-1200","day":"1:a och 15:e"}},
+            {"type":"Feature","geometry":{"type":"LineString","coordinates":[[13.0,55.0],[13.001,55.001]]},
+             "properties":{"value":"Städning","tid":"0800-1200","day":"15"}}
+        ]}"#;
+        fs::write(&path, content).unwrap();
+        let data = DataLoader::load_miljodata(path.to_str().unwrap()).unwrap();
+        let _ = fs::remove_file(&path);
+        assert_eq!(data[0].dagar, vec![1, 15]);
+        assert_eq!(data[0].dag(), 1);
+        assert!(data[0].has_usable_time());
+        assert_eq!(data[1].dagar, vec![15]);
+        assert_eq!(ParseReport::from_miljodata(&data, &[]).accepted, 2);
+    }
+    /// Feature gid 3159360 from Malmö's miljöparkering data, a street that
+    /// turns about 90° near its western end
+    const BENT_STREET: &str = r#"{"type":"FeatureCollection","features":[
+        {"type":"Feature","geometry":{"type":"LineString","coordinates":[[13.054638,55.5895019991],[13.054234,55.5906939991],[13.054192,55.5907919991],[13.05416,55.5908549991],[13.054116,55.5909049991],[13.054053,55.5909619991],[13.054004,55.5909939991],[13.053926,55.5910289991],[13.053829,55.5910609991],[13.05374,55.5910819991],[13.053661,55.5910899991],[13.053579,55.5910929991],[13.053504,55.5910899991],[13.053431,55.5910859991],[13.051023,55.5908199991]]},
+         "properties":{"gid":3159360,"value":"Förbud mot att parkera fordon","copy_value":"Parkering  förbjuden  klockan 08.00 - 12.00  den 12:e i månaden.","tid":"0800 - 1200","day":"12"}}
+    ]}"#;
+    fn load_bent_street(name: &str) -> Vec<MiljoeDataClean> {
+        let path = std::env::temp_dir().join(name);
+        fs::write(&path, BENT_STREET).unwrap();
+        let data = DataLoader::load_miljodata(path.to_str().unwrap()).unwrap();
+        let _ = fs::remove_file(&path);
+        data
+    }
+    #[test]
+    fn test_load_miljodata_keeps_every_vertex_of_bent_street() {
+        let data = load_bent_street("amp_api_bent_street_test.json");
+        let point = |x: f64, y: f64| [Decimal::try_from(x).unwrap(), Decimal::try_from(y).unwrap()];
+        assert_eq!(data.len(), 14);
+        assert_eq!(data[0].coordinates[0], point(13.054638, 55.5895019991));
+        assert_eq!(data[0].coordinates[1], point(13.054234, 55.5906939991));
+        assert_eq!(data[13].coordinates[1], point(13.051023, 55.5908199991));
+        for pair in data.windows(2) {
+            assert_eq!(pair[0].coordinates[1], pair[1].coordinates[0]);
+        }
+        // Every piece carries the length of the whole street, longer than
+        // the straight line between its ends
+        let chord = segment_length_meters(&[data[0].coordinates[0], data[13].coordinates[1]]);
+        let total: f64 = data
+            .iter()
+            .map(|d| segment_length_meters(&d.coordinates))
+            .sum();
+        assert!(total > chord + 50.0);
+        assert!(data.iter().all(|d| (d.length_m - total).abs() < 1e-6));
+        assert!(
+            data.iter()
+                .all(|d| d.tid == "0800 - 1200" && d.dagar == vec![12])
+        );
+        assert!(data.iter().all(|d| d.segment_id == Some(0)));
+    }
+    #[test]
+    fn test_address_beside_bend_matches_bent_street() {
+        use crate::correlation_algorithms::{CorrelationAlgo, DistanceBasedAlgo, RaycastingAlgo};
+        let data = load_bent_street("amp_api_bent_street_match_test.json");
+        // About 5 m north of the bend, over 100 m from the line between the ends
+        let address = AdressClean {
+            coordinates: [
+                Decimal::try_from(13.053579).unwrap(),
+                Decimal::try_from(55.591138).unwrap(),
+            ],
+            postnummer: None,
+            adress: "Test 1".to_string(),
+            gata: "Test".to_string(),
+            gatunummer: "1".to_string(),
+        };
+        for algo in [&DistanceBasedAlgo as &dyn CorrelationAlgo, &RaycastingAlgo] {
+            let (idx, dist) = algo.correlate(&address, &data).unwrap();
+            assert!(dist < 10.0, "{} m", dist);
+            assert!((9..=12).contains(&idx), "matched piece {}", idx);
+        }
+    }
+    #[test]
+    fn test_parse_report_counts_rejections() {
+        let path = std::env::temp_dir().join("amp_api_parse_report_test.json");
+        let feature = |props: &str| {
+            format!(
+                r#"{{"type":"Feature","geometry":{{"type":"LineString","coordinates":[[13.0,55.0],[13.001,55.001]]}},"properties":{}}}"#,
+                props
+            )
+        };
+        let content = format!(
+            r#"{{"type":"FeatureCollection","features":[{}]}}"#,
+            [
+                feature(r#"{"value":"Städning","tid":"0800 - 1200","day":"5"}"#),
+                feature(r#"{"value":"Städning","tid":"Mån 8-16","day":6}"#),
+                feature(r#"{"value":"Städning","tid":"Mån 8-16","day":7}"#),
+                feature(r#"{"value":"Städning","tid":"1200-1600","day":"sista"}"#),
+                feature(r#"{"value":"Städning","day":8}"#),
+            ]
+            .join(",")
+        );
+        fs::write(&path, content).unwrap();
+        let (data, warnings) =
+            DataLoader::load_miljodata_with_warnings(path.to_str().unwrap()).unwrap();
+        let _ = fs::remove_file(&path);
+        let report = ParseReport::from_miljodata(&data, &warnings);
+        assert_eq!(report.total, 5);
+        assert_eq!(report.accepted, 1);
+        assert_eq!(report.rejected_by_time, 3);
+        assert_eq!(report.rejected_by_day, 1);
+        assert_eq!(report.rejected(), 4);
+        assert_eq!(
+            report.samples,
+            vec!["missing tid", "time format 'Mån 8-16'", "day '0'"]
+        );
+        assert_eq!(
+            report.to_string(),
+            "1/5 rows accepted, 4 rejected (3 by time, 1 by day): missing tid, time format 'Mån 8-16', day '0'"
+        );
+    }
+    #[test]
+    fn test_dataset_parquet_roundtrip() {
+        use std::str::FromStr;
+        let dec = |s: &str| Decimal::from_str(s).unwrap();
+        let dir = std::env::temp_dir().join("amp_dataset_parquet_test");
+        fs::create_dir_all(&dir).unwrap();
+        let segment = [
+            [dec("13.0012345"), dec("55.6012345")],
+            [dec("13.002"), dec("55.602")],
+        ];
+        let data = DataSet {
+            addresses: vec![AdressClean {
+                coordinates: [dec("13.0015"), dec("55.6015")],
+                postnummer: Some("21438".to_string()),
+                adress: "Storgatan 10".to_string(),
+                gata: "Storgatan".to_string(),
+                gatunummer: "10".to_string(),
+            }],
+            miljo: vec![MiljoeDataClean::new(
+                segment,
+                "Städning".to_string(),
+                "0800-1200".to_string(),
+                15,
+            )],
+            parkering: vec![ParkeringsDataClean {
+                coordinates: segment,
+                taxa: "Taxa C".to_string(),
+                antal_platser: 26,
+                typ_av_parkering: "Längsgående 6".to_string(),
+            }],
+        };
+        data.write_parquet(&dir).unwrap();
+        let loaded = DataSet::load_from_parquet(&dir).unwrap();
+        let _ = fs::remove_dir_all(&dir);
+        assert_eq!(loaded.addresses, data.addresses);
+        assert_eq!(loaded.miljo[0].coordinates, segment);
+        assert_eq!(loaded.miljo[0].dag(), 15);
+        assert_eq!(loaded.parkering[0].antal_platser, 26);
+        assert_eq!(loaded.parkering[0].typ_av_parkering, "Längsgående 6");
+    }
 }
@@ -1,7 +1,10 @@
 //! Performance benchmarking for correlation algorithms
+use crate::correlation_algorithms::common::{
+    MAX_DISTANCE_METERS, ToPrimitive, closest, distance_point_to_line,
+};
 use crate::correlation_algorithms::{
-    CorrelationAlgo, DistanceBasedAlgo, GridNearestAlgo, KDTreeSpatialAlgo, OverlappingChunksAlgo,
-    RTreeSpatialAlgo, RaycastingAlgo,
+    BallTreeSpatialAlgo, CorrelationAlgo, DistanceBasedAlgo, GridNearestAlgo, KDTreeSpatialAlgo,
+    OverlappingChunksAlgo, RTreeSpatialAlgo, RaycastingAlgo,
 };
 use crate::structs::{AdressClean, MiljoeDataClean};
 use rayon::prelude::*;
@@ -15,6 +18,110 @@ pub struct BenchmarkResult {
     pub addresses_processed: usize,
     pub matches_found: usize,
 }
+/// Comparison across the [`BenchmarkResult`]s of one run
+///
+/// All algorithms see the same addresses and lines, so their match counts
+/// should agree. One that differs from the most common count is listed in
+/// [`outliers`](Self::outliers) as a likely correctness bug.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BenchmarkSummary {
+    /// Algorithm with the lowest total time
+    pub fastest: String,
+    /// Algorithm with the highest total time
+    pub slowest: String,
+    /// Slowest total time divided by the fastest (`1.0` for a single result)
+    pub speedup: f64,
+    /// Most common `matches_found`; on a tie, the one listed first
+    pub modal_matches: usize,
+    /// Algorithms whose `matches_found` differs from `modal_matches`
+    pub outliers: Vec<String>,
+}
+impl BenchmarkSummary {
+    /// Summarize `results`, or `None` if there are none
+    pub fn from_results(results: &[BenchmarkResult]) -> Option<Self> {
+        let fastest = results.iter().min_by_key(|r| r.total_duration)?;
+        let slowest = results.iter().max_by_key(|r| r.total_duration)?;
+        let mut counts: Vec<(usize, usize)> = Vec::new();
+        for result in results {
+            match counts.iter_mut().find(|(m, _)| *m == result.matches_found) {
+                Some((_, n)) => *n += 1,
+                None => counts.push((result.matches_found, 1)),
+            }
+        }
+        let modal_matches = counts
+            .iter()
+            .fold(
+                (0, 0),
+                |best, &(m, n)| if n > best.1 { (m, n) } else { best },
+            )
+            .0;
+        Some(Self {
+            fastest: fastest.algorithm_name.clone(),
+            slowest: slowest.algorithm_name.clone(),
+            speedup: slowest.total_duration.as_secs_f64()
+                / fastest.total_duration.as_secs_f64().max(f64::EPSILON),
+            modal_matches,
+            outliers: results
+                .iter()
+                .filter(|r| r.matches_found != modal_matches)
+                .map(|r| r.algorithm_name.clone())
+                .collect(),
+        })
+    }
+}
+/// Maximum allowed distance difference (meters) between the Decimal and f64 paths
+pub const COORDINATE_MATH_TOLERANCE: f64 = 1e-6;
+/// Comparison of correlation throughput with Decimal vs f64 coordinates
+///
+/// Both paths run the same brute-force nearest-line search. The Decimal path
+/// converts coordinates on every comparison (as the algorithms do today), while
+/// the f64 path converts everything once up front and stays in f64.
+#[derive(Debug)]
+pub struct CoordinateMathResult {
+    pub decimal_duration: Duration,
+    pub f64_duration: Duration,
+    pub addresses_processed: usize,
+    pub matches_found: usize,
+    /// Number of addresses where the two paths picked a different line
+    pub mismatches: usize,
+    /// Largest absolute distance difference between the two paths, in meters
+    pub max_distance_delta: f64,
+}
+impl CoordinateMathResult {
+    /// Relative speedup of the f64 path (`> 1.0` means f64 is faster)
+    pub fn speedup(&self) -> f64 {
+        self.decimal_duration.as_secs_f64() / self.f64_duration.as_secs_f64().max(f64::EPSILON)
+    }
+    /// Whether both paths agree on every match within [`COORDINATE_MATH_TOLERANCE`]
+    pub fn is_equivalent(&self) -> bool {
+        self.mismatches == 0 && self.max_distance_delta <= COORDINATE_MATH_TOLERANCE
+    }
+}
+/// Agreement of a candidate algorithm with a reference over the same addresses
+///
+/// Catches an algorithm that is fast but picks the wrong line, which the
+/// match count in [`BenchmarkResult`] cannot show.
+#[derive(Debug)]
+pub struct AccuracyReport {
+    pub reference_name: String,
+    pub candidate_name: String,
+    pub addresses_processed: usize,
+    /// Addresses where both picked the same line, or neither matched
+    pub agreeing: usize,
+    /// Addresses where the lines differ or only one algorithm matched
+    pub differing: usize,
+    /// Mean absolute distance difference where both matched, in meters
+    pub mean_distance_error: f64,
+}
+impl AccuracyReport {
+    /// Share of addresses where the algorithms agree, from 0 to 100
+    pub fn agreement_percent(&self) -> f64 {
+        if self.addresses_processed == 0 {
+            return 100.0;
+        }
+        self.agreeing as f64 / self.addresses_processed as f64 * 100.0
+    }
+}
 pub struct Benchmarker {
     pub addresses: Vec<AdressClean>,
     pub parking_lines: Vec<MiljoeDataClean>,
@@ -66,10 +173,225 @@ impl Benchmarker {
         results.push(self.benchmark_algorithm(&algo, sample_size));
         let algo = GridNearestAlgo::new(&self.parking_lines);
         results.push(self.benchmark_algorithm(&algo, sample_size));
+        let algo = BallTreeSpatialAlgo::new(&self.parking_lines);
+        results.push(self.benchmark_algorithm(&algo, sample_size));
         results
     }
-    /// Print benchmark results in a formatted table
-    pub fn print_results(results: &[BenchmarkResult]) {
+    /// Compare correlation throughput with Decimal coordinates vs plain f64
+    ///
+    /// Runs the brute-force distance search twice over the same sample: once on
+    /// the stored [`Decimal`] coordinates and once on coordinates converted to
+    /// f64 ahead of time. The conversion cost for the f64 path is included in
+    /// its timing so the numbers reflect storing f64 directly in [`AdressClean`].
+    ///
+    /// [`Decimal`]: rust_decimal::Decimal
+    pub fn benchmark_coordinate_math(&self, sample_size: Option<usize>) -> CoordinateMathResult {
+        let sample_size = sample_size.unwrap_or(self.addresses.len());
+        let addresses_to_test = &self.addresses[..sample_size.min(self.addresses.len())];
+        let algo = DistanceBasedAlgo;
+        let start = Instant::now();
+        let decimal_results: Vec<Option<(usize, f64)>> = addresses_to_test
+            .par_iter()
+            .map(|address| algo.correlate(address, &self.parking_lines))
+            .collect();
+        let decimal_duration = start.elapsed();
+        let start = Instant::now();
+        let lines: Vec<Option<([f64; 2], [f64; 2])>> = self
+            .parking_lines
+            .iter()
+            .map(|line| {
+                Some((
+                    [
+                        line.coordinates[0][0].to_f64()?,
+                        line.coordinates[0][1].to_f64()?,
+                    ],
+                    [
+                        line.coordinates[1][0].to_f64()?,
+                        line.coordinates[1][1].to_f64()?,
+                    ],
+                ))
+            })
+            .collect();
+        let points: Vec<Option<[f64; 2]>> = addresses_to_test
+            .iter()
+            .map(|address| {
+                Some([
+                    address.coordinates[0].to_f64()?,
+                    address.coordinates[1].to_f64()?,
+                ])
+            })
+            .collect();
+        let f64_results: Vec<Option<(usize, f64)>> = points
+            .par_iter()
+            .map(|point| {
+                let point = (*point)?;
+                closest(lines.iter().enumerate().filter_map(|(idx, line)| {
+                    let (line_start, line_end) = (*line)?;
+                    let dist = distance_point_to_line(point, line_start, line_end);
+                    (dist <= MAX_DISTANCE_METERS).then_some((idx, dist))
+                }))
+            })
+            .collect();
+        let f64_duration = start.elapsed();
+        let mut mismatches = 0;
+        let mut max_distance_delta: f64 = 0.0;
+        for (decimal, float) in decimal_results.iter().zip(&f64_results) {
+            match (decimal, float) {
+                (Some((di, dd)), Some((fi, fd))) if di == fi => {
+                    max_distance_delta = max_distance_delta.max((dd - fd).abs());
+                }
+                (None, None) => {}
+                _ => mismatches += 1,
+            }
+        }
+        CoordinateMathResult {
+            decimal_duration,
+            f64_duration,
+            addresses_processed: addresses_to_test.len(),
+            matches_found: decimal_results.iter().filter(|r| r.is_some()).count(),
+            mismatches,
+            max_distance_delta,
+        }
+    }
+    /// Compare a candidate algorithm's matches with a reference over all addresses
+    ///
+    /// Both are asked for the nearest line within `cutoff` meters with
+    /// [`CorrelationAlgo::correlate_within`], so a cutoff above the default
+    /// search radius is honoured. Use an exact algorithm such as
+    /// [`DistanceBasedAlgo`] as the reference.
+    pub fn accuracy_vs(
+        &self,
+        reference: &(dyn CorrelationAlgo + Sync),
+        candidate: &(dyn CorrelationAlgo + Sync),
+        cutoff: f64,
+    ) -> AccuracyReport {
+        self.accuracy_vs_many(reference, &[candidate], cutoff)
+            .remove(0)
+    }
+    /// [`Self::accuracy_vs`] for several candidates, running the reference once
+    ///
+    /// # Returns
+    /// One report per candidate, in the order given
+    pub fn accuracy_vs_many(
+        &self,
+        reference: &(dyn CorrelationAlgo + Sync),
+        candidates: &[&(dyn CorrelationAlgo + Sync)],
+        cutoff: f64,
+    ) -> Vec<AccuracyReport> {
+        let within = |algo: &(dyn CorrelationAlgo + Sync), address: &AdressClean| {
+            algo.correlate_within(address, &self.parking_lines, cutoff)
+        };
+        let expected: Vec<_> = self
+            .addresses
+            .par_iter()
+            .map(|address| within(reference, address))
+            .collect();
+        candidates
+            .iter()
+            .map(|&candidate| {
+                let actual: Vec<_> = self
+                    .addresses
+                    .par_iter()
+                    .map(|address| within(candidate, address))
+                    .collect();
+                Self::accuracy_report(reference, candidate, &expected, &actual)
+            })
+            .collect()
+    }
+    /// Tally per-address reference and candidate matches into a report
+    fn accuracy_report(
+        reference: &(dyn CorrelationAlgo + Sync),
+        candidate: &(dyn CorrelationAlgo + Sync),
+        expected: &[Option<(usize, f64)>],
+        actual: &[Option<(usize, f64)>],
+    ) -> AccuracyReport {
+        let mut agreeing = 0;
+        let mut both_matched = 0;
+        let mut distance_error = 0.0;
+        for (expected, actual) in expected.iter().zip(actual) {
+            match (expected, actual) {
+                (Some((ei, ed)), Some((ai, ad))) => {
+                    both_matched += 1;
+                    distance_error += (ed - ad).abs();
+                    if ei == ai {
+                        agreeing += 1;
+                    }
+                }
+                (None, None) => agreeing += 1,
+                _ => {}
+            }
+        }
+        AccuracyReport {
+            reference_name: reference.name().to_string(),
+            candidate_name: candidate.name().to_string(),
+            addresses_processed: expected.len(),
+            agreeing,
+            differing: expected.len() - agreeing,
+            mean_distance_error: if both_matched == 0 {
+                0.0
+            } else {
+                distance_error / both_matched as f64
+            },
+        }
+    }
+    /// Print accuracy reports as a table, one row per candidate
+    pub fn print_accuracy_reports(reports: &[AccuracyReport]) {
+        println!(
+            "\n{:<25} {:<12} {:<12} {:<12} {:<15}",
+            "Algorithm", "Agreement", "Agreeing", "Differing", "Mean Δ (m)",
+        );
+        println!("{}", "-".repeat(80));
+        for report in reports {
+            println!(
+                "{:<25} {:<12} {:<12} {:<12} {:<15.3}",
+                report.candidate_name,
+                format!("{:.1}%", report.agreement_percent()),
+                report.agreeing,
+                report.differing,
+                report.mean_distance_error,
+            );
+        }
+    }
+    /// Print the Decimal vs f64 comparison with the measured delta
+    pub fn print_coordinate_math_result(result: &CoordinateMathResult) {
+        println!(
+            "\n{:<25} {:<15} {:<20}",
+            "Coordinate Path", "Total Time", "Avg per Address",
+        );
+        println!("{}", "-".repeat(60));
+        let processed = result.addresses_processed.max(1) as u32;
+        for (name, duration) in [
+            ("Decimal (current)", result.decimal_duration),
+            ("f64 end-to-end", result.f64_duration),
+        ] {
+            println!(
+                "{:<25} {:<15.2?} {:<20.2?}",
+                name,
+                duration,
+                duration / processed,
+            );
+        }
+        println!(
+            "\nΔ {:.2?} ({:.2}x) over {} addresses, {} matches",
+            result.decimal_duration.abs_diff(result.f64_duration),
+            result.speedup(),
+            result.addresses_processed,
+            result.matches_found,
+        );
+        if result.is_equivalent() {
+            println!(
+                "✓ Results equivalent (max distance delta {:.2e}m)",
+                result.max_distance_delta,
+            );
+        } else {
+            println!(
+                "⚠ Results differ: {} mismatched matches, max distance delta {:.2e}m",
+                result.mismatches, result.max_distance_delta,
+            );
+        }
+    }
+    /// Print benchmark results in a formatted table, followed by the summary
+    pub fn print_results(results: &[BenchmarkResult]) -> Option<BenchmarkSummary> {
         println!(
             "\n{:<25} {:<15} {:<20} {:<15} {:<15}",
             "Algorithm", "Total Time", "Avg per Address", "Processed", "Matches",
@@ -85,12 +407,22 @@ impl Benchmarker {
                 result.matches_found,
             );
         }
-        if let Some(fastest) = results.iter().min_by_key(|r| r.total_duration) {
+        let summary = BenchmarkSummary::from_results(results)?;
+        println!("\n✓ Fastest: {}", summary.fastest);
+        println!(
+            "  Slowest: {} ({:.1}x slower)",
+            summary.slowest, summary.speedup,
+        );
+        if summary.outliers.is_empty() {
+            println!("✓ All algorithms found {} matches", summary.modal_matches);
+        } else {
             println!(
-                "\n✓ Fastest: {} ({:.2?})",
-                fastest.algorithm_name, fastest.total_duration,
+                "⚠ Match count differs from the usual {}: {}",
+                summary.modal_matches,
+                summary.outliers.join(", "),
             );
         }
+        Some(summary)
     }
 }
 #[cfg(test)]
@@ -108,4 +440,152 @@ mod tests {
         assert_eq!(result.algorithm_name, "Test");
         assert_eq!(result.addresses_processed, 100);
     }
+    #[test]
+    fn test_summary_picks_fastest_and_flags_outlier() {
+        let result = |name: &str, millis: u64, matches: usize| BenchmarkResult {
+            algorithm_name: name.to_string(),
+            total_duration: Duration::from_millis(millis),
+            avg_per_address: Duration::from_millis(millis / 100),
+            addresses_processed: 100,
+            matches_found: matches,
+        };
+        let results = [
+            result("Distance-Based", 800, 85),
+            result("R-Tree Spatial", 20, 85),
+            result("Grid Nearest", 50, 79),
+        ];
+        let summary = BenchmarkSummary::from_results(&results).unwrap();
+        assert_eq!(summary.fastest, "R-Tree Spatial");
+        assert_eq!(summary.slowest, "Distance-Based");
+        assert!((summary.speedup - 40.0).abs() < 1e-9);
+        assert_eq!(summary.modal_matches, 85);
+        assert_eq!(summary.outliers, vec!["Grid Nearest".to_string()]);
+        assert_eq!(BenchmarkSummary::from_results(&[]), None);
+    }
+    #[test]
+    fn test_coordinate_math_equivalent() {
+        use rust_decimal::Decimal;
+        use std::str::FromStr;
+        let dec = |s: &str| Decimal::from_str(s).unwrap();
+        let addresses = vec![
+            AdressClean {
+                coordinates: [dec("13.1881234"), dec("55.6048765")],
+                postnummer: None,
+                adress: "Near".to_string(),
+                gata: "Near".to_string(),
+                gatunummer: "1".to_string(),
+            },
+            AdressClean {
+                coordinates: [dec("13.3"), dec("55.7")],
+                postnummer: None,
+                adress: "Far".to_string(),
+                gata: "Far".to_string(),
+                gatunummer: "2".to_string(),
+            },
+        ];
+        let lines = vec![MiljoeDataClean::new(
+            [
+                [dec("13.1880"), dec("55.6048")],
+                [dec("13.1885"), dec("55.6050")],
+            ],
+            "Test".to_string(),
+            "0800-1200".to_string(),
+            1,
+        )];
+        let result = Benchmarker::new(addresses, lines).benchmark_coordinate_math(None);
+        assert_eq!(result.addresses_processed, 2);
+        assert_eq!(result.matches_found, 1);
+        assert!(result.is_equivalent());
+    }
+    #[test]
+    fn test_accuracy_vs_identical_algorithms() {
+        use rust_decimal::Decimal;
+        use std::str::FromStr;
+        let dec = |s: &str| Decimal::from_str(s).unwrap();
+        let address = |x: &str, y: &str| AdressClean {
+            coordinates: [dec(x), dec(y)],
+            postnummer: None,
+            adress: "Test".to_string(),
+            gata: "Test".to_string(),
+            gatunummer: "1".to_string(),
+        };
+        let addresses = vec![
+            address("13.1881", "55.6049"),
+            address("13.1901", "55.6061"),
+            address("13.3", "55.7"),
+        ];
+        let line = |a: [&str; 2], b: [&str; 2]| {
+            MiljoeDataClean::new(
+                [[dec(a[0]), dec(a[1])], [dec(b[0]), dec(b[1])]],
+                "Test".to_string(),
+                "0800-1200".to_string(),
+                1,
+            )
+        };
+        let lines = vec![
+            line(["13.1880", "55.6048"], ["13.1885", "55.6050"]),
+            line(["13.1900", "55.6060"], ["13.1905", "55.6062"]),
+        ];
+        let benchmarker = Benchmarker::new(addresses, lines);
+        let report = benchmarker.accuracy_vs(&DistanceBasedAlgo, &RaycastingAlgo, 20.0);
+        assert_eq!(report.addresses_processed, 3);
+        assert_eq!(report.agreeing, 3);
+        assert_eq!(report.differing, 0);
+        assert_eq!(report.agreement_percent(), 100.0);
+        assert!(report.mean_distance_error < 1e-9);
+        let reports = benchmarker.accuracy_vs_many(
+            &DistanceBasedAlgo,
+            &[&RaycastingAlgo, &DistanceBasedAlgo],
+            20.0,
+        );
+        assert_eq!(reports.len(), 2);
+        assert_eq!(reports[0].candidate_name, report.candidate_name);
+        assert_eq!(reports[0].agreeing, report.agreeing);
+        assert_eq!(reports[1].agreeing, 3);
+    }
+    #[test]
+    fn test_accuracy_vs_honours_cutoff_above_default_radius() {
+        /// Ignores the cutoff and stops at the default search radius
+        struct Capped;
+        impl CorrelationAlgo for Capped {
+            fn correlate_within(
+                &self,
+                address: &AdressClean,
+                parking_lines: &[MiljoeDataClean],
+                _cutoff_m: f64,
+            ) -> Option<(usize, f64)> {
+                DistanceBasedAlgo.correlate(address, parking_lines)
+            }
+            fn name(&self) -> &'static str {
+                "Capped"
+            }
+        }
+        use rust_decimal::Decimal;
+        // About 80 m north of the line, between the default radius and the cutoff
+        let address = AdressClean {
+            coordinates: [Decimal::new(1_300_050, 5), Decimal::new(5_500_072, 5)],
+            postnummer: None,
+            adress: "Test".to_string(),
+            gata: "Test".to_string(),
+            gatunummer: "1".to_string(),
+        };
+        let line = MiljoeDataClean::new(
+            [
+                [Decimal::new(1_300_000, 5), Decimal::new(5_500_000, 5)],
+                [Decimal::new(1_300_100, 5), Decimal::new(5_500_000, 5)],
+            ],
+            "Test".to_string(),
+            "0800-1200".to_string(),
+            1,
+        );
+        let benchmarker = Benchmarker::new(vec![address], vec![line]);
+        let (_, dist) = DistanceBasedAlgo
+            .correlate_within(&benchmarker.addresses[0], &benchmarker.parking_lines, 100.0)
+            .unwrap();
+        assert!(dist > MAX_DISTANCE_METERS && dist < 100.0, "{} m", dist);
+        let report = benchmarker.accuracy_vs(&DistanceBasedAlgo, &Capped, 100.0);
+        assert_eq!(report.differing, 1);
+        let report = benchmarker.accuracy_vs(&DistanceBasedAlgo, &Capped, 20.0);
+        assert_eq!(report.agreeing, 1);
+    }
 }
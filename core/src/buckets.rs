@@ -0,0 +1,535 @@
+//! Time bucket categorization of parking restrictions.
+//!
+//! Frontends group restrictions by how soon they start: active now, within
+//! six hours, within a day, within a month, or later. This module is the one
+//! place those rules live; the Android app and the server's
+//! `correlate --by-bucket` report both call it, so the same restriction lands
+//! in the same bucket in each and time handling fixes apply to both.
+//!
+//! Every function takes an explicit `now`, so results are deterministic and
+//! callers decide which clock to read.
+//!
+//! # Bucket Rules
+//!
+//! | Bucket | Condition |
+//! |--------|-----------|
+//! | [`TimeBucket::Now`] | `start_time <= now < end_time` |
+//! | [`TimeBucket::Within6Hours`] | next start at most [`BucketSettings::within_hours`] away |
+//! | [`TimeBucket::Within1Day`] | next start at most [`BucketSettings::within_day`] away |
+//! | [`TimeBucket::Within1Month`] | next start at most [`BucketSettings::within_month`] away |
+//! | [`TimeBucket::MoreThan1Month`] | any later start |
+//! | [`TimeBucket::Invalid`] | no next start from [`DB::next_start_after`] |
+//!
+//! Bounds are inclusive. The next start comes from [`DB::next_start_after`]:
+//! the restriction's next day ([`DB::dagar`]) at the same Swedish local time,
+//! skipping months that lack the day and holidays it doesn't run on. For the
+//! usual single-day restriction that is the same day next month, and the
+//! 31st goes from January 31 to March 31.
+//!
+//! # Examples
+//!
+//! ```
+//! use amp_core::buckets::{BucketSettings, TimeBucket, bucket_for, remaining_duration};
+//! use amp_core::structs::DB;
+//! use chrono::{Duration, TimeZone, Utc};
+//!
+//! let db = DB::from_dag_tid(
+//!     None, "Storgatan 10".to_string(), None, None, None,
+//!     15, "0800-1200", None, None, None, 2024, 1,
+//! ).unwrap();
+//! let now = Utc.with_ymd_and_hms(2024, 1, 15, 2, 0, 0).unwrap();
+//! assert_eq!(bucket_for(&db, now, &BucketSettings::default()), TimeBucket::Within6Hours);
+//! assert_eq!(remaining_duration(&db, now), Some(Duration::hours(9)));
+//! ```
+use crate::structs::{DB, SettingsData};
+use chrono::{DateTime, Datelike, Duration, NaiveDate, Utc};
+/// Time bucket categories for grouping parking restrictions
+///
+/// Categorizes restrictions by urgency based on time remaining.
+/// Used for sorting and filtering addresses by deadline proximity.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum TimeBucket {
+    /// Restriction is active right now (highest urgency)
+    Now,
+    /// Restriction starts within 6 hours
+    Within6Hours,
+    /// Restriction starts within 1 day
+    Within1Day,
+    /// Restriction starts within 1 month (30 days)
+    Within1Month,
+    /// Restriction starts more than 1 month away (>30 days)
+    MoreThan1Month,
+    /// Restriction has no upcoming start
+    Invalid,
+}
+/// Upper bounds of the upcoming buckets.
+///
+/// The defaults are the app's six hours, one day and 30 days. Bounds are
+/// expected to be increasing; a restriction goes in the first bucket whose
+/// bound its next start is within.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BucketSettings {
+    /// Bound of [`TimeBucket::Within6Hours`]
+    pub within_hours: Duration,
+    /// Bound of [`TimeBucket::Within1Day`]
+    pub within_day: Duration,
+    /// Bound of [`TimeBucket::Within1Month`]
+    pub within_month: Duration,
+}
+impl Default for BucketSettings {
+    fn default() -> Self {
+        Self {
+            within_hours: Duration::hours(6),
+            within_day: Duration::days(1),
+            within_month: Duration::days(30),
+        }
+    }
+}
+impl BucketSettings {
+    /// Bounds with the user's [`SettingsData::lead_minutes`] as the bound of
+    /// [`TimeBucket::Within6Hours`].
+    ///
+    /// The lead is capped at the one-day bound so the bounds stay increasing;
+    /// the other bounds are the defaults.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use amp_core::buckets::BucketSettings;
+    /// use amp_core::structs::SettingsData;
+    /// use chrono::Duration;
+    ///
+    /// let settings = SettingsData { lead_minutes: 120, ..SettingsData::default() };
+    /// let bounds = BucketSettings::from_settings(&settings);
+    /// assert_eq!(bounds.within_hours, Duration::hours(2));
+    /// assert_eq!(
+    ///     BucketSettings::from_settings(&SettingsData::default()),
+    ///     BucketSettings::default(),
+    /// );
+    /// ```
+    pub fn from_settings(settings: &SettingsData) -> Self {
+        let defaults = Self::default();
+        Self {
+            within_hours: Duration::minutes(settings.lead_minutes as i64).min(defaults.within_day),
+            ..defaults
+        }
+    }
+}
+/// Determine the best year/month to use for a given day
+///
+/// Strategy:
+/// 1. If the date is valid in current month and hasn't passed yet, use current month
+/// 2. If the date has already passed this month, use next month
+/// 3. If the date is invalid in current month (e.g., Feb 30), use next month
+///
+/// # Arguments
+/// * `day` - Day of month (1-31)
+/// * `current_year` - Current year
+/// * `current_month` - Current month (1-12)
+/// * `current_day` - Current day of month
+///
+/// # Returns
+/// Tuple of (year, month) to use for creating the DB entry
+pub fn determine_year_month(
+    day: u8,
+    current_year: i32,
+    current_month: u32,
+    current_day: u32,
+) -> (i32, u32) {
+    let current_month_valid =
+        NaiveDate::from_ymd_opt(current_year, current_month, day as u32).is_some();
+    if current_month_valid && (day as u32) >= current_day {
+        return (current_year, current_month);
+    }
+    let mut next_month = current_month + 1;
+    let mut next_year = current_year;
+    if next_month > 12 {
+        next_month = 1;
+        next_year += 1;
+    }
+    if NaiveDate::from_ymd_opt(next_year, next_month, day as u32).is_some() {
+        (next_year, next_month)
+    } else {
+        (current_year, current_month)
+    }
+}
+/// Pick the occurrence to build a [`DB`] for, for a restriction on several days.
+///
+/// Applies [`determine_year_month`] to each day and keeps the earliest
+/// resulting date, so a rule on the 1st and 15th gives the 15th of this month
+/// on the 10th and the 1st of next month on the 16th. Days that fall in
+/// neither month are skipped.
+///
+/// # Returns
+/// `(year, month, day)` of the occurrence, or `None` if no day gives a valid date
+///
+/// # Examples
+///
+/// ```
+/// use amp_core::buckets::determine_occurrence;
+///
+/// assert_eq!(determine_occurrence(&[1, 15], 2024, 1, 10), Some((2024, 1, 15)));
+/// assert_eq!(determine_occurrence(&[1, 15], 2024, 1, 16), Some((2024, 2, 1)));
+/// assert_eq!(determine_occurrence(&[], 2024, 1, 16), None);
+/// ```
+pub fn determine_occurrence(
+    dagar: &[u8],
+    current_year: i32,
+    current_month: u32,
+    current_day: u32,
+) -> Option<(i32, u32, u8)> {
+    dagar
+        .iter()
+        .filter_map(|&day| {
+            let (year, month) = determine_year_month(day, current_year, current_month, current_day);
+            NaiveDate::from_ymd_opt(year, month, day as u32).map(|date| (date, day))
+        })
+        .min()
+        .map(|(date, day)| (date.year(), date.month(), day))
+}
+/// Duration from `now` until the restriction ends.
+///
+/// `None` once `end_time` has passed. For an upcoming restriction this
+/// includes the time until it starts.
+pub fn remaining_duration(restriction: &DB, now: DateTime<Utc>) -> Option<Duration> {
+    restriction.time_until_end(now)
+}
+/// Duration from `now` until the next start of this restriction.
+///
+/// For non-active restrictions, this gives the time until the restriction begins.
+/// Once it has started, it is the time until the start on the restriction's
+/// next day, see [`DB::next_start_after`].
+pub fn time_until_next_start_at(restriction: &DB, now: DateTime<Utc>) -> Option<Duration> {
+    restriction.next_start_after(now).map(|start| start - now)
+}
+/// Duration from `now` until this restriction ends, or else until it next starts.
+///
+/// Like [`remaining_duration`], but once this occurrence has ended it counts
+/// down to the next start instead of giving `None`.
+pub fn time_until_next_occurrence_at(restriction: &DB, now: DateTime<Utc>) -> Option<Duration> {
+    restriction
+        .time_until_end(now)
+        .or_else(|| time_until_next_start_at(restriction, now))
+}
+/// Categorize a restriction by time remaining until it starts, at `now`.
+///
+/// See the [module docs](self) for the rules.
+///
+/// # Arguments
+/// * `restriction` - DB entry containing restriction timestamps
+/// * `now` - Instant to evaluate at
+/// * `settings` - Bucket bounds, usually [`BucketSettings::default`]
+pub fn bucket_for(restriction: &DB, now: DateTime<Utc>, settings: &BucketSettings) -> TimeBucket {
+    if restriction.is_active(now) {
+        return TimeBucket::Now;
+    }
+    let remaining = match time_until_next_start_at(restriction, now) {
+        Some(d) => d,
+        None => return TimeBucket::Invalid,
+    };
+    if remaining <= settings.within_hours {
+        TimeBucket::Within6Hours
+    } else if remaining <= settings.within_day {
+        TimeBucket::Within1Day
+    } else if remaining <= settings.within_month {
+        TimeBucket::Within1Month
+    } else {
+        TimeBucket::MoreThan1Month
+    }
+}
+/// [`bucket_for`] with the default [`BucketSettings`]
+pub fn bucket_at(restriction: &DB, now: DateTime<Utc>) -> TimeBucket {
+    bucket_for(restriction, now, &BucketSettings::default())
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    fn restriction_in(year: i32, month: u32, dag: u8, tid: &str) -> DB {
+        DB::from_dag_tid(
+            None,
+            "Testgatan 1".to_string(),
+            None,
+            None,
+            None,
+            dag,
+            tid,
+            None,
+            None,
+            None,
+            year,
+            month,
+        )
+        .unwrap()
+    }
+    fn restriction(dag: u8, tid: &str) -> DB {
+        restriction_in(2024, 1, dag, tid)
+    }
+    fn utc(month: u32, day: u32, hour: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, month, day, hour, 0, 0).unwrap()
+    }
+    #[test]
+    fn test_bucket_at_boundaries() {
+        // 0800-1200 CET is 07:00-11:00 UTC
+        let db = restriction(15, "0800-1200");
+        assert_eq!(bucket_at(&db, utc(1, 15, 8)), TimeBucket::Now);
+        assert_eq!(bucket_at(&db, utc(1, 15, 1)), TimeBucket::Within6Hours);
+        assert_eq!(bucket_at(&db, utc(1, 14, 12)), TimeBucket::Within1Day);
+        assert_eq!(bucket_at(&db, utc(1, 2, 12)), TimeBucket::Within1Month);
+    }
+    #[test]
+    fn test_now_covers_start_but_not_end() {
+        let db = restriction(15, "0800-1200");
+        assert_eq!(bucket_at(&db, db.start_time), TimeBucket::Now);
+        assert_eq!(
+            bucket_at(&db, db.end_time - Duration::seconds(1)),
+            TimeBucket::Now
+        );
+        assert_ne!(bucket_at(&db, db.end_time), TimeBucket::Now);
+    }
+    #[test]
+    fn test_bounds_are_inclusive() {
+        let db = restriction(31, "0800-1200");
+        let settings = BucketSettings::default();
+        let at = |before: Duration| bucket_at(&db, db.start_time - before);
+        assert_eq!(at(settings.within_hours), TimeBucket::Within6Hours);
+        assert_eq!(
+            at(settings.within_hours + Duration::seconds(1)),
+            TimeBucket::Within1Day
+        );
+        assert_eq!(at(settings.within_day), TimeBucket::Within1Day);
+        assert_eq!(
+            at(settings.within_day + Duration::seconds(1)),
+            TimeBucket::Within1Month
+        );
+        assert_eq!(at(settings.within_month), TimeBucket::Within1Month);
+        assert_eq!(
+            at(settings.within_month + Duration::seconds(1)),
+            TimeBucket::MoreThan1Month
+        );
+    }
+    #[test]
+    fn test_bucket_for_uses_settings() {
+        let db = restriction(15, "0800-1200");
+        let now = db.start_time - Duration::hours(8);
+        assert_eq!(bucket_at(&db, now), TimeBucket::Within1Day);
+        let wide = BucketSettings {
+            within_hours: Duration::hours(12),
+            ..BucketSettings::default()
+        };
+        assert_eq!(bucket_for(&db, now, &wide), TimeBucket::Within6Hours);
+    }
+    #[test]
+    fn test_bucket_at_rolls_over_to_next_month() {
+        let db = restriction(15, "0800-1200");
+        assert_eq!(bucket_at(&db, utc(1, 15, 12)), TimeBucket::MoreThan1Month);
+        assert_eq!(bucket_at(&db, utc(1, 16, 12)), TimeBucket::Within1Month);
+        assert_eq!(
+            time_until_next_start_at(&db, utc(1, 15, 12)),
+            Some(Duration::days(31) - Duration::hours(5))
+        );
+    }
+    #[test]
+    fn test_bucket_at_rolls_over_to_next_year() {
+        let db = restriction_in(2024, 12, 10, "0800-1200");
+        let now = Utc.with_ymd_and_hms(2024, 12, 20, 12, 0, 0).unwrap();
+        assert_eq!(bucket_at(&db, now), TimeBucket::Within1Month);
+        let next = Utc.with_ymd_and_hms(2025, 1, 10, 7, 0, 0).unwrap();
+        assert_eq!(time_until_next_start_at(&db, now), Some(next - now));
+    }
+    #[test]
+    fn test_next_month_start_keeps_local_time_across_dst() {
+        // 08:00 CET in March is 08:00 CEST in April, an hour earlier in UTC
+        let db = restriction_in(2024, 3, 10, "0800-1200");
+        let now = Utc.with_ymd_and_hms(2024, 3, 20, 12, 0, 0).unwrap();
+        let next = Utc.with_ymd_and_hms(2024, 4, 10, 6, 0, 0).unwrap();
+        assert_eq!(time_until_next_start_at(&db, now), Some(next - now));
+    }
+    #[test]
+    fn test_day_missing_next_month_skips_to_following() {
+        // February has no 31st, so the next start is March 31, 08:00 CEST
+        let db = restriction(31, "0800-1200").with_holiday_skipping(false);
+        assert_eq!(bucket_at(&db, utc(1, 31, 12)), TimeBucket::MoreThan1Month);
+        assert_eq!(
+            time_until_next_start_at(&db, utc(1, 31, 12)),
+            Some(utc(3, 31, 6) - utc(1, 31, 12))
+        );
+        // March 31 is Easter Sunday in 2024 and April has no 31st
+        let db = db.with_holiday_skipping(true);
+        assert_eq!(
+            time_until_next_start_at(&db, utc(1, 31, 12)),
+            Some(utc(5, 31, 6) - utc(1, 31, 12))
+        );
+    }
+    #[test]
+    fn test_remaining_duration_none_on_holiday() {
+        // 9 May 2024 is Kristi himmelsfärds dag
+        let db = restriction_in(2024, 5, 9, "0800-1200");
+        assert_eq!(remaining_duration(&db, utc(5, 9, 5)), None);
+        let db = db.with_holiday_skipping(false);
+        assert_eq!(
+            remaining_duration(&db, utc(5, 9, 5)),
+            Some(Duration::hours(5))
+        );
+    }
+    #[test]
+    fn test_bucket_for_skips_holiday() {
+        let db = restriction_in(2024, 5, 9, "0800-1200");
+        let settings = BucketSettings::default();
+        // Next start is 9 June, 08:00 CEST, a month and a day away
+        assert_eq!(
+            bucket_for(&db, utc(5, 9, 8), &settings),
+            TimeBucket::MoreThan1Month
+        );
+        assert_eq!(
+            time_until_next_start_at(&db, utc(5, 9, 8)),
+            Some(utc(6, 9, 6) - utc(5, 9, 8))
+        );
+        let db = db.with_holiday_skipping(false);
+        assert_eq!(bucket_for(&db, utc(5, 9, 8), &settings), TimeBucket::Now);
+    }
+    #[test]
+    fn test_two_windows_use_soonest_upcoming() {
+        // 0800-1000 and 1400-1600 CET are 07-09 and 13-15 UTC
+        let db = restriction(15, "0800-1000,1400-1600");
+        assert_eq!(bucket_at(&db, utc(1, 15, 8)), TimeBucket::Now);
+        assert_eq!(bucket_at(&db, utc(1, 15, 10)), TimeBucket::Within6Hours);
+        assert_eq!(bucket_at(&db, utc(1, 15, 13)), TimeBucket::Now);
+        assert_eq!(
+            remaining_duration(&db, utc(1, 15, 10)),
+            Some(Duration::hours(5))
+        );
+        assert_eq!(remaining_duration(&db, utc(1, 15, 15)), None);
+        assert_eq!(
+            time_until_next_start_at(&db, utc(1, 15, 14)),
+            Some(Duration::days(31) - Duration::hours(7))
+        );
+    }
+    #[test]
+    fn test_shorter_lead_reclassifies_within_6_hours() {
+        let db = restriction(15, "0800-1200");
+        // Three hours before the 07:00 UTC start
+        let now = utc(1, 15, 4);
+        assert_eq!(bucket_at(&db, now), TimeBucket::Within6Hours);
+        let settings = SettingsData {
+            lead_minutes: 120,
+            ..SettingsData::default()
+        };
+        let bounds = BucketSettings::from_settings(&settings);
+        assert_eq!(bucket_for(&db, now, &bounds), TimeBucket::Within1Day);
+        assert_eq!(
+            bucket_for(&db, utc(1, 15, 5), &bounds),
+            TimeBucket::Within6Hours
+        );
+    }
+    #[test]
+    fn test_remaining_duration() {
+        let db = restriction(15, "0800-1200");
+        assert_eq!(
+            remaining_duration(&db, utc(1, 15, 9)),
+            Some(Duration::hours(2))
+        );
+        assert_eq!(
+            remaining_duration(&db, utc(1, 15, 5)),
+            Some(Duration::hours(6))
+        );
+        assert_eq!(remaining_duration(&db, utc(1, 15, 11)), None);
+    }
+    #[test]
+    fn test_overnight_tid_ends_next_day() {
+        // 2200-0600 CET is 21:00 on the 15th to 05:00 UTC on the 16th
+        let db = restriction(15, "2200-0600");
+        assert_eq!(db.end_time, utc(1, 16, 5));
+        let at_one = Utc.with_ymd_and_hms(2024, 1, 16, 0, 0, 0).unwrap();
+        assert!(db.is_active(at_one));
+        assert_eq!(bucket_at(&db, at_one), TimeBucket::Now);
+        assert_eq!(remaining_duration(&db, at_one), Some(Duration::hours(5)));
+        let at_seven = utc(1, 16, 6);
+        assert!(!db.is_active(at_seven));
+        assert_eq!(remaining_duration(&db, at_seven), None);
+        assert_eq!(bucket_at(&db, at_seven), TimeBucket::MoreThan1Month);
+        // Next month's window, reached from its second day
+        let february = Utc.with_ymd_and_hms(2024, 2, 16, 0, 0, 0).unwrap();
+        assert!(db.is_active(february));
+    }
+    #[test]
+    fn test_daytime_tid_unchanged_by_overnight_handling() {
+        let db = restriction(15, "0800-1200");
+        assert_eq!(db.end_time, utc(1, 15, 11));
+        assert!(db.is_active(utc(1, 15, 10)));
+        assert!(!db.is_active(utc(1, 15, 11)));
+        assert_eq!(
+            remaining_duration(&db, utc(1, 15, 10)),
+            Some(Duration::hours(1))
+        );
+    }
+    #[test]
+    fn test_time_until_next_occurrence_at() {
+        let db = restriction(15, "0800-1200");
+        assert_eq!(
+            time_until_next_occurrence_at(&db, utc(1, 15, 9)),
+            Some(Duration::hours(2))
+        );
+        assert_eq!(
+            time_until_next_occurrence_at(&db, utc(1, 15, 11)),
+            Some(Duration::days(31) - Duration::hours(4))
+        );
+        let db = restriction(31, "0800-1200");
+        assert_eq!(
+            time_until_next_occurrence_at(&db, utc(1, 31, 12)),
+            Some(utc(5, 31, 6) - utc(1, 31, 12))
+        );
+    }
+    #[test]
+    fn test_two_day_rule_picks_nearer_upcoming_day() {
+        // "1:a och 15:e": between the two days, the 15th is next
+        let (year, month, day) = determine_occurrence(&[1, 15], 2024, 1, 10).unwrap();
+        assert_eq!((year, month, day), (2024, 1, 15));
+        let db = restriction_in(year, month, day, "0800-1200").with_dagar([1, 15]);
+        assert_eq!(db.dag(), 15);
+        assert_eq!(bucket_at(&db, utc(1, 10, 12)), TimeBucket::Within1Month);
+        // Once the 15th has passed, the 1st of next month is next, not the 15th
+        assert_eq!(bucket_at(&db, utc(1, 15, 12)), TimeBucket::Within1Month);
+        let next = Utc.with_ymd_and_hms(2024, 2, 1, 7, 0, 0).unwrap();
+        assert_eq!(
+            time_until_next_start_at(&db, utc(1, 15, 12)),
+            Some(next - utc(1, 15, 12))
+        );
+        assert_eq!(
+            determine_occurrence(&[1, 15], 2024, 1, 16),
+            Some((2024, 2, 1))
+        );
+    }
+    #[test]
+    fn test_two_day_rule_next_start_within_month() {
+        // Built for the 1st, the 15th of the same month follows
+        let db = restriction_in(2024, 1, 1, "0800-1200").with_dagar([15, 1]);
+        assert_eq!(db.dagar, vec![1, 15]);
+        let now = utc(1, 2, 12);
+        assert_eq!(bucket_at(&db, now), TimeBucket::Within1Month);
+        let next = Utc.with_ymd_and_hms(2024, 1, 15, 7, 0, 0).unwrap();
+        assert_eq!(time_until_next_start_at(&db, now), Some(next - now));
+        assert_eq!(db.next_start_after(now), Some(next));
+        // A missing day in one month doesn't hide the other
+        let db = restriction_in(2024, 1, 31, "0800-1200").with_dagar([15]);
+        assert_eq!(bucket_at(&db, utc(1, 31, 12)), TimeBucket::Within1Month);
+    }
+    #[test]
+    fn test_determine_year_month() {
+        assert_eq!(determine_year_month(25, 2026, 2, 5), (2026, 2));
+        assert_eq!(determine_year_month(5, 2026, 2, 5), (2026, 2));
+        assert_eq!(determine_year_month(3, 2026, 2, 5), (2026, 3));
+        assert_eq!(determine_year_month(30, 2026, 2, 5), (2026, 3));
+        assert_eq!(determine_year_month(3, 2026, 12, 5), (2027, 1));
+        // April has no 31st, so May is used
+        assert_eq!(determine_year_month(31, 2026, 4, 5), (2026, 5));
+    }
+    #[test]
+    fn test_bucket_order_is_urgency() {
+        assert!(TimeBucket::Now < TimeBucket::Within6Hours);
+        assert!(TimeBucket::Within6Hours < TimeBucket::Within1Day);
+        assert!(TimeBucket::Within1Day < TimeBucket::Within1Month);
+        assert!(TimeBucket::Within1Month < TimeBucket::MoreThan1Month);
+        assert!(TimeBucket::MoreThan1Month < TimeBucket::Invalid);
+    }
+}
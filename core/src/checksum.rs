@@ -1,10 +1,120 @@
 //! Checksum verification for data sources
-//! Checks if remote data has changed since last fetch
+//! Checks if remote data has changed since last fetch, and whether embedded
+//! assets still match the checksums recorded when they were generated
+use crate::error::AmpError;
 use chrono::Utc;
 use reqwest::Response;
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
 use std::fs;
+use std::io;
+use std::path::Path;
+/// One of the three Malmö datasets tracked by [`DataChecksum`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DataSource {
+    /// Addresses (`adresser`)
+    Addresses,
+    /// Environmental parking restrictions (`miljoparkeringar`)
+    Miljo,
+    /// Parking fees (`parkeringsavgifter`)
+    Parkering,
+}
+impl DataSource {
+    /// Every source, in the order they are reported
+    pub const ALL: [DataSource; 3] = [Self::Addresses, Self::Miljo, Self::Parkering];
+    /// Dataset name as published on Malmö's portal
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Addresses => "adresser",
+            Self::Miljo => "miljöparkering",
+            Self::Parkering => "parkeringsavgifter",
+        }
+    }
+}
+/// Verify that `bytes` hash to `expected_sha256`
+///
+/// Meant for data compiled into a binary, e.g. the app's parquet assets,
+/// so a corrupted build is caught before the bytes are parsed. The
+/// expected checksum is a hex string as written by [`DataChecksum`] and is
+/// compared case-insensitively.
+///
+/// # Returns
+/// [`AmpError::ChecksumMismatch`] with both checksums if they differ
+///
+/// # Examples
+/// ```
+/// use amp_core::checksum::verify_embedded;
+///
+/// let expected = "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824";
+/// assert!(verify_embedded(b"hello", expected).is_ok());
+/// assert!(verify_embedded(b"hellp", expected).is_err());
+/// ```
+pub fn verify_embedded(bytes: &[u8], expected_sha256: &str) -> Result<(), AmpError> {
+    let actual = format!("{:x}", Sha256::digest(bytes));
+    if actual.eq_ignore_ascii_case(expected_sha256.trim()) {
+        Ok(())
+    } else {
+        Err(AmpError::ChecksumMismatch {
+            expected: expected_sha256.to_string(),
+            actual,
+        })
+    }
+}
+/// Checksum file kept next to generated assets, in `sha256sum` format
+///
+/// Committed alongside the parquet files so the app can check what it
+/// embeds against what the generator wrote, and `sha256sum -c SHA256SUMS`
+/// works from the asset directory.
+pub const ASSET_CHECKSUMS_FILE: &str = "SHA256SUMS";
+/// Parse `sha256sum` output into `(file name, checksum)` pairs
+///
+/// Blank lines are skipped and the `*` binary-mode marker is stripped from
+/// file names.
+pub fn parse_asset_checksums(contents: &str) -> Vec<(&str, &str)> {
+    contents
+        .lines()
+        .filter_map(|line| {
+            let (checksum, name) = line.trim().split_once(char::is_whitespace)?;
+            Some((name.trim_start().trim_start_matches('*'), checksum))
+        })
+        .collect()
+}
+/// Record the checksum of a freshly written asset in its [`ASSET_CHECKSUMS_FILE`]
+///
+/// Updates or adds the entry for `path` in the checksum file of the same
+/// directory, creating the file if needed. Other entries are kept, sorted
+/// by file name.
+pub fn record_asset_checksum(path: impl AsRef<Path>) -> Result<(), AmpError> {
+    let path = path.as_ref();
+    let name = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("{} has no file name", path.display()),
+            )
+        })?;
+    let checksum = format!("{:x}", Sha256::digest(fs::read(path)?));
+    let sums_path = path
+        .parent()
+        .unwrap_or(Path::new(""))
+        .join(ASSET_CHECKSUMS_FILE);
+    let existing = match fs::read_to_string(&sums_path) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(e.into()),
+    };
+    let mut entries: BTreeMap<&str, &str> = parse_asset_checksums(&existing).into_iter().collect();
+    entries.insert(name, &checksum);
+    let contents: String = entries
+        .iter()
+        .map(|(name, checksum)| format!("{}  {}\n", checksum, name))
+        .collect();
+    fs::write(&sums_path, contents)?;
+    Ok(())
+}
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct DataChecksum {
     pub miljo_url: String,
@@ -45,9 +155,22 @@ impl DataChecksum {
     }
     /// Check if any data source has changed
     pub fn has_changed(&self, old_checksum: &DataChecksum) -> bool {
-        self.miljo_checksum != old_checksum.miljo_checksum
-            || self.parkering_checksum != old_checksum.parkering_checksum
-            || self.adresser_checksum != old_checksum.adresser_checksum
+        !self.changed_sources(old_checksum).is_empty()
+    }
+    /// Sources whose checksum differs from `old`, in [`DataSource::ALL`] order
+    pub fn changed_sources(&self, old: &DataChecksum) -> Vec<DataSource> {
+        DataSource::ALL
+            .into_iter()
+            .filter(|&source| self.checksum(source) != old.checksum(source))
+            .collect()
+    }
+    /// Stored checksum of one source
+    pub fn checksum(&self, source: DataSource) -> &str {
+        match source {
+            DataSource::Addresses => &self.adresser_checksum,
+            DataSource::Miljo => &self.miljo_checksum,
+            DataSource::Parkering => &self.parkering_checksum,
+        }
     }
     /// Load checksums from file
     pub fn load_from_file(path: &str) -> Result<Self, Box<dyn std::error::Error>> {
@@ -83,4 +206,101 @@ mod tests {
         assert!(!cs.miljo_url.is_empty());
         assert!(!cs.last_checked.is_empty());
     }
+    #[test]
+    fn test_changed_sources() {
+        let mut old = DataChecksum::new(
+            "http://example.com/miljo".to_string(),
+            "http://example.com/parkering".to_string(),
+            "http://example.com/adresser".to_string(),
+        );
+        old.miljo_checksum = "aaa".to_string();
+        old.parkering_checksum = "bbb".to_string();
+        old.adresser_checksum = "ccc".to_string();
+        let mut new = old.clone();
+        assert!(new.changed_sources(&old).is_empty());
+        assert!(!new.has_changed(&old));
+        new.parkering_checksum = "bbb2".to_string();
+        new.adresser_checksum = "ccc2".to_string();
+        assert_eq!(
+            new.changed_sources(&old),
+            vec![DataSource::Addresses, DataSource::Parkering]
+        );
+        assert!(new.has_changed(&old));
+    }
+    #[test]
+    fn test_verify_embedded_detects_flipped_byte() {
+        use crate::parquet::build_local_parquet;
+        use crate::structs::LocalData;
+        let mut bytes = build_local_parquet(vec![LocalData {
+            valid: true,
+            active: true,
+            postnummer: Some("21438".to_string()),
+            adress: "Kornettsgatan 18C".to_string(),
+            gata: Some("Kornettsgatan".to_string()),
+            gatunummer: Some("18C".to_string()),
+            info: None,
+            tid: Some("0800-1200".to_string()),
+            dag: Some(15),
+            dagar: vec![15],
+            taxa: None,
+            antal_platser: None,
+            typ_av_parkering: None,
+            match_reason: None,
+            match_quality: None,
+            snoozed_until: None,
+        }])
+        .expect("Failed to build parquet");
+        let expected = format!("{:x}", Sha256::digest(&bytes));
+        assert!(verify_embedded(&bytes, &expected).is_ok());
+        assert!(verify_embedded(&bytes, &expected.to_uppercase()).is_ok());
+        let middle = bytes.len() / 2;
+        bytes[middle] ^= 0x01;
+        match verify_embedded(&bytes, &expected) {
+            Err(AmpError::ChecksumMismatch {
+                expected: reported,
+                actual,
+            }) => {
+                assert_eq!(reported, expected);
+                assert_ne!(actual, expected);
+            }
+            other => panic!("expected ChecksumMismatch, got {:?}", other),
+        }
+    }
+    #[test]
+    fn test_record_asset_checksum() {
+        let dir = std::env::temp_dir().join("amp_record_asset_checksum");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("b.parquet"), b"hello").unwrap();
+        fs::write(dir.join("a.parquet"), b"first").unwrap();
+        record_asset_checksum(dir.join("b.parquet")).unwrap();
+        record_asset_checksum(dir.join("a.parquet")).unwrap();
+        fs::write(dir.join("a.parquet"), b"second").unwrap();
+        record_asset_checksum(dir.join("a.parquet")).unwrap();
+        let contents = fs::read_to_string(dir.join(ASSET_CHECKSUMS_FILE)).unwrap();
+        let entries = parse_asset_checksums(&contents);
+        assert_eq!(
+            entries,
+            vec![
+                (
+                    "a.parquet",
+                    format!("{:x}", Sha256::digest(b"second")).as_str()
+                ),
+                (
+                    "b.parquet",
+                    "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824"
+                ),
+            ]
+        );
+        assert!(contents.ends_with("  b.parquet\n"));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+    #[test]
+    fn test_parse_asset_checksums_binary_marker() {
+        let entries = parse_asset_checksums("abc *db.parquet\n\ndef  debug.parquet\n");
+        assert_eq!(
+            entries,
+            vec![("db.parquet", "abc"), ("debug.parquet", "def")]
+        );
+    }
 }
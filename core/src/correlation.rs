@@ -0,0 +1,731 @@
+//! Comparing correlation results between data snapshots, and exporting them.
+//!
+//! After a data update the interesting question is rarely "what changed in
+//! the GeoJSON" but "which addresses now get a different answer". This module
+//! diffs two sets of correlated [`OutputData`], e.g. two dated `db.parquet`
+//! files read with [`read_db_parquet`], per address.
+//!
+//! Output files only contain addresses with a match, so an address missing
+//! from a snapshot is treated as unmatched there.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use amp_core::correlation::diff_results;
+//! use amp_core::parquet::read_db_parquet;
+//! use std::fs::File;
+//!
+//! let old = read_db_parquet(File::open("db-2024-01.parquet")?)?;
+//! let new = read_db_parquet(File::open("db-2024-02.parquet")?)?;
+//! let diff = diff_results(&old, &new);
+//! println!(
+//!     "{} gained, {} lost, {} changed",
+//!     diff.gained.len(),
+//!     diff.lost.len(),
+//!     diff.changed.len()
+//! );
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+//!
+//! # Viewing Matches in QGIS
+//!
+//! [`export_geojson`] writes [`CorrelationResult`]s as address points with
+//! their match distances, to lay over the line network exported with
+//! [`export_lines`](crate::geojson::export_lines).
+//!
+//! # Reverse Geocoding
+//!
+//! [`nearest_address`] turns a GPS position into the closest known address,
+//! e.g. to prefill a form from the device location.
+//!
+//! # Merging Datasets
+//!
+//! [`merge_results`] joins the per-address miljö and parkering matches into
+//! one output row per address.
+//!
+//! # Free Spot Estimates
+//!
+//! [`estimated_free_spots`] guesses how many of a parking zone's spots are
+//! free at a given time from a fixed time-of-day occupancy curve. It is a
+//! heuristic, not a measurement: there is no sensor or payment data behind
+//! it. Pass a different curve to [`estimated_free_spots_with`].
+//!
+//! [`read_db_parquet`]: crate::parquet::read_db_parquet
+use crate::correlation_algorithms::common::closest;
+use crate::crs::CoordinateSpace;
+use crate::structs::{
+    AdressClean, CorrelationResult, MiljoeDataClean, OutputData, OutputDataWithDistance,
+    ParkeringsDataClean, SWEDISH_TZ,
+};
+use ::geojson::{Feature, FeatureCollection, GeoJson, Geometry, JsonObject, Value};
+use chrono::{DateTime, Timelike};
+use chrono_tz::Tz;
+use rust_decimal::Decimal;
+use rust_decimal::prelude::ToPrimitive;
+use serde_json::json;
+use std::collections::{BTreeMap, HashMap};
+/// An address whose match differs between two snapshots
+#[derive(Debug, Clone)]
+pub struct ChangedMatch {
+    pub old: OutputData,
+    pub new: OutputData,
+}
+impl ChangedMatch {
+    /// Names of the match fields that differ, e.g. `["tid", "dag"]`.
+    pub fn changed_fields(&self) -> Vec<&'static str> {
+        changed_fields(&self.old, &self.new)
+    }
+}
+/// Per-address difference between two correlation snapshots.
+///
+/// Every list is sorted by `(postnummer, adress)`.
+#[derive(Debug, Clone, Default)]
+pub struct ResultsDiff {
+    /// Matched in the new snapshot only
+    pub gained: Vec<OutputData>,
+    /// Matched in the old snapshot only
+    pub lost: Vec<OutputData>,
+    /// Matched in both, but with different parking data
+    pub changed: Vec<ChangedMatch>,
+    /// Matched in both with identical parking data
+    pub unchanged: usize,
+}
+impl ResultsDiff {
+    /// `true` if no address gained, lost or changed its match.
+    pub fn is_empty(&self) -> bool {
+        self.gained.is_empty() && self.lost.is_empty() && self.changed.is_empty()
+    }
+}
+/// Diff two correlation snapshots per address.
+///
+/// Addresses are keyed by `(postnummer, adress)`. If a snapshot lists the
+/// same key more than once, its last entry is used.
+///
+/// # Arguments
+/// * `old` - Results from the earlier snapshot
+/// * `new` - Results from the later snapshot
+pub fn diff_results(old: &[OutputData], new: &[OutputData]) -> ResultsDiff {
+    let old_by_key = by_address(old);
+    let mut new_by_key = by_address(new);
+    let mut diff = ResultsDiff::default();
+    for (key, old_entry) in old_by_key {
+        match new_by_key.remove(&key) {
+            None => diff.lost.push(old_entry.clone()),
+            Some(new_entry) if changed_fields(old_entry, new_entry).is_empty() => {
+                diff.unchanged += 1;
+            }
+            Some(new_entry) => diff.changed.push(ChangedMatch {
+                old: old_entry.clone(),
+                new: new_entry.clone(),
+            }),
+        }
+    }
+    diff.gained = new_by_key.into_values().cloned().collect();
+    diff
+}
+/// Index entries by `(postnummer, adress)`, sorted
+fn by_address(entries: &[OutputData]) -> BTreeMap<(Option<&str>, &str), &OutputData> {
+    entries
+        .iter()
+        .map(|entry| ((entry.postnummer.as_deref(), entry.adress.as_str()), entry))
+        .collect()
+}
+/// Names of the parking data fields that differ between two entries
+fn changed_fields(old: &OutputData, new: &OutputData) -> Vec<&'static str> {
+    [
+        ("info", old.info != new.info),
+        ("tid", old.tid != new.tid),
+        ("dag", old.dagar != new.dagar),
+        ("taxa", old.taxa != new.taxa),
+        ("antal_platser", old.antal_platser != new.antal_platser),
+        (
+            "typ_av_parkering",
+            old.typ_av_parkering != new.typ_av_parkering,
+        ),
+    ]
+    .into_iter()
+    .filter_map(|(name, differs)| differs.then_some(name))
+    .collect()
+}
+/// Export matched correlation results as a GeoJSON `FeatureCollection` string.
+///
+/// Every result with a miljö or parkering match becomes a `Point` at its
+/// address, found in `addresses` by address and postal code. Properties are
+/// `address`, `postnummer`, `miljo_distance` and `parkering_distance` (meters,
+/// `null` without that match) and `dataset_source`. Results without a match
+/// or a joined address with `f64` coordinates are skipped.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use amp_core::structs::{AdressClean, CorrelationResult};
+/// use amp_core::correlation::export_geojson;
+///
+/// # let results: Vec<CorrelationResult> = vec![];
+/// # let addresses: Vec<AdressClean> = vec![];
+/// std::fs::write("matches.geojson", export_geojson(&results, &addresses))?;
+/// # Ok::<(), std::io::Error>(())
+/// ```
+pub fn export_geojson(results: &[CorrelationResult], addresses: &[AdressClean]) -> String {
+    let mut by_address: HashMap<(&str, &str), &AdressClean> = HashMap::new();
+    for address in addresses {
+        let postnummer = address.postnummer.as_deref().unwrap_or_default();
+        by_address
+            .entry((address.adress.as_str(), postnummer))
+            .or_insert(address);
+    }
+    let features = results
+        .iter()
+        .filter(|result| result.miljo_match.is_some() || result.parkering_match.is_some())
+        .filter_map(|result| {
+            let address = by_address.get(&(result.address.as_str(), result.postnummer.as_str()))?;
+            let point = vec![
+                address.coordinates[0].to_f64()?,
+                address.coordinates[1].to_f64()?,
+            ];
+            let distance = |matched: &Option<(f64, String)>| matched.as_ref().map(|(d, _)| *d);
+            let mut props = JsonObject::new();
+            props.insert("address".to_string(), json!(result.address));
+            props.insert("postnummer".to_string(), json!(result.postnummer));
+            props.insert(
+                "miljo_distance".to_string(),
+                json!(distance(&result.miljo_match)),
+            );
+            props.insert(
+                "parkering_distance".to_string(),
+                json!(distance(&result.parkering_match)),
+            );
+            props.insert("dataset_source".to_string(), json!(result.dataset_source()));
+            Some(Feature {
+                bbox: None,
+                geometry: Some(Geometry::new(Value::Point(point))),
+                id: None,
+                properties: Some(props),
+                foreign_members: None,
+            })
+        })
+        .collect();
+    GeoJson::from(FeatureCollection {
+        bbox: None,
+        features,
+        foreign_members: None,
+    })
+    .to_string()
+}
+/// Closest address to a coordinate, within `max_meters`.
+///
+/// Each address is measured as a zero-length segment with
+/// [`CoordinateSpace::distance_point_to_line`], the distance the line
+/// correlation uses, so a cutoff means the same here as there. Addresses
+/// without a location are skipped and ties go to the lower index.
+///
+/// # Arguments
+/// * `coord` - [longitude, latitude] in WGS84, the order of
+///   [`AdressClean::coordinates`]
+/// * `addresses` - Candidates to search
+/// * `max_meters` - Largest distance that still counts as a match
+///
+/// # Returns
+/// `(index into addresses, distance in meters)`, or `None` if no address is
+/// close enough.
+///
+/// # Examples
+///
+/// ```no_run
+/// use amp_core::correlation::nearest_address;
+/// # use amp_core::structs::AdressClean;
+/// use rust_decimal::Decimal;
+///
+/// # let addresses: Vec<AdressClean> = vec![];
+/// let gps = [Decimal::new(130001, 4), Decimal::new(555897, 4)];
+/// if let Some((idx, dist)) = nearest_address(gps, &addresses, 50.0) {
+///     println!("{} ({:.0} m away)", addresses[idx].adress, dist);
+/// }
+/// ```
+pub fn nearest_address(
+    coord: [Decimal; 2],
+    addresses: &[AdressClean],
+    max_meters: f64,
+) -> Option<(usize, f64)> {
+    let point = [coord[0].to_f64()?, coord[1].to_f64()?];
+    closest(
+        addresses
+            .iter()
+            .enumerate()
+            .filter(|(_, address)| address.has_location())
+            .filter_map(|(idx, address)| {
+                let location = [
+                    address.coordinates[0].to_f64()?,
+                    address.coordinates[1].to_f64()?,
+                ];
+                let dist = CoordinateSpace::Wgs84.distance_point_to_line(point, location, location);
+                (dist <= max_meters).then_some((idx, dist))
+            }),
+    )
+}
+/// Merge per-address matches from the miljö and parkering datasets
+///
+/// Both result lists are keyed by `adress`. Rather than hashing every key,
+/// they are sorted and merge-joined with the addresses, which avoids two
+/// string-keyed maps over the full ~53k addresses.
+///
+/// # Returns
+/// One row per address, in the order of `addresses`, with the fields of a
+/// dataset left `None` if it has no match. If a list holds the same address
+/// twice, the later entry wins.
+pub fn merge_results(
+    addresses: &[AdressClean],
+    miljo_results: &[(String, f64, MiljoeDataClean)],
+    parkering_results: &[(String, f64, ParkeringsDataClean)],
+) -> Vec<OutputDataWithDistance> {
+    let mut order: Vec<usize> = (0..addresses.len()).collect();
+    order.sort_unstable_by(|&a, &b| addresses[a].adress.cmp(&addresses[b].adress));
+    let miljo = join_by_address(addresses, &order, miljo_results);
+    let parkering = join_by_address(addresses, &order, parkering_results);
+    addresses
+        .iter()
+        .zip(miljo)
+        .zip(parkering)
+        .map(|((addr, miljo_data), parkering_data)| {
+            let (info, tid, dag, dagar, segment_id, miljo_distance) =
+                if let Some((_, dist, miljodata)) = miljo_data {
+                    (
+                        Some(miljodata.info.clone()),
+                        Some(miljodata.tid.clone()),
+                        Some(miljodata.dag()),
+                        miljodata.dagar.clone(),
+                        miljodata.segment_id,
+                        Some(*dist),
+                    )
+                } else {
+                    (None, None, None, Vec::new(), None, None)
+                };
+            let (taxa, antal_platser, typ_av_parkering, parkering_distance) =
+                if let Some((_, dist, p_data)) = parkering_data {
+                    (
+                        Some(p_data.taxa.clone()),
+                        Some(p_data.antal_platser),
+                        Some(p_data.typ_av_parkering.clone()),
+                        Some(*dist),
+                    )
+                } else {
+                    (None, None, None, None)
+                };
+            OutputDataWithDistance {
+                data: OutputData {
+                    postnummer: addr.postnummer.clone(),
+                    adress: addr.adress.clone(),
+                    gata: addr.gata.clone(),
+                    gatunummer: addr.gatunummer.clone(),
+                    info,
+                    tid,
+                    dag,
+                    dagar,
+                    taxa,
+                    antal_platser,
+                    typ_av_parkering,
+                    match_quality: None,
+                    segment_id,
+                },
+                miljo_distance,
+                parkering_distance,
+                has_location: addr.has_location(),
+            }
+        })
+        .collect()
+}
+/// Match of each address in `results`, indexed like `addresses`
+///
+/// `order` lists address indices sorted by `adress`. `results` is sorted
+/// stably, so of several entries for one address the last one is found.
+fn join_by_address<'a, T>(
+    addresses: &[AdressClean],
+    order: &[usize],
+    results: &'a [(String, f64, T)],
+) -> Vec<Option<&'a (String, f64, T)>> {
+    let mut sorted: Vec<_> = results.iter().collect();
+    sorted.sort_by(|a, b| a.0.cmp(&b.0));
+    let mut joined = vec![None; addresses.len()];
+    let mut rest = sorted.as_slice();
+    for &idx in order {
+        let key = &addresses[idx].adress;
+        while rest.first().is_some_and(|result| result.0 < *key) {
+            rest = &rest[1..];
+        }
+        // Not consumed: the next address may have the same key
+        let run = rest.iter().take_while(|result| result.0 == *key).count();
+        joined[idx] = rest[..run].last().copied();
+    }
+    joined
+}
+/// Occupied share of street parking in Malmö by local hour, as
+/// `(hour, share)` knots interpolated linearly
+///
+/// A rough guess at a central Malmö street: nearly empty in the small
+/// hours, filling up through the morning, busiest around midday and
+/// emptying over the evening. Not fitted to any data.
+const MALMO_OCCUPANCY_CURVE: [(f64, f64); 8] = [
+    (0.0, 0.10),
+    (5.0, 0.05),
+    (8.0, 0.50),
+    (11.0, 0.90),
+    (14.0, 0.90),
+    (17.0, 0.75),
+    (20.0, 0.45),
+    (24.0, 0.10),
+];
+/// Estimated share of parking spots taken in Malmö at `when`
+///
+/// Interpolates a fixed curve at the local (Europe/Stockholm) time of
+/// day. Weekdays, weekends and holidays all use the same curve.
+///
+/// # Returns
+/// A share between 0.0 (empty) and 1.0 (full).
+pub fn malmo_occupancy(when: DateTime<Tz>) -> f64 {
+    let local = when.with_timezone(&SWEDISH_TZ);
+    let hour = local.hour() as f64 + local.minute() as f64 / 60.0;
+    MALMO_OCCUPANCY_CURVE
+        .windows(2)
+        .find(|knots| hour < knots[1].0)
+        .map_or(MALMO_OCCUPANCY_CURVE[0].1, |knots| {
+            let ((h0, s0), (h1, s1)) = (knots[0], knots[1]);
+            s0 + (s1 - s0) * (hour - h0) / (h1 - h0)
+        })
+}
+/// Heuristic number of free spots in `zone` at `when`
+///
+/// Same as [`estimated_free_spots_with`] using [`malmo_occupancy`]. This is
+/// a guess from the time of day alone, suitable for a hint such as "usually
+/// plenty of space now" but not for promising a spot.
+///
+/// # Returns
+/// `None` if the zone's spot count is unknown. The loader stores a missing
+/// `antal_platser` as 0, so a zone with 0 spots counts as unknown.
+///
+/// # Examples
+/// ```
+/// use amp_core::correlation::estimated_free_spots;
+/// use amp_core::structs::{ParkeringsDataClean, SWEDISH_TZ};
+/// use chrono::TimeZone;
+/// use rust_decimal::Decimal;
+///
+/// let zone = ParkeringsDataClean {
+///     coordinates: [[Decimal::ZERO; 2]; 2],
+///     taxa: "Taxa C".to_string(),
+///     antal_platser: 20,
+///     typ_av_parkering: "Längsgående".to_string(),
+/// };
+/// let night = SWEDISH_TZ.with_ymd_and_hms(2024, 3, 12, 3, 0, 0).unwrap();
+/// assert!(estimated_free_spots(&zone, night).unwrap() > 15);
+/// ```
+pub fn estimated_free_spots(zone: &ParkeringsDataClean, when: DateTime<Tz>) -> Option<u64> {
+    estimated_free_spots_with(zone, when, malmo_occupancy)
+}
+/// Heuristic number of free spots in `zone` at `when`, from a custom curve
+///
+/// # Arguments
+/// * `occupancy` - Share of spots taken at a given time; values outside
+///   0.0..=1.0 are clamped
+///
+/// # Returns
+/// `antal_platser` times the free share, rounded, or `None` if the spot
+/// count is unknown (0).
+pub fn estimated_free_spots_with<F>(
+    zone: &ParkeringsDataClean,
+    when: DateTime<Tz>,
+    occupancy: F,
+) -> Option<u64>
+where
+    F: Fn(DateTime<Tz>) -> f64,
+{
+    if zone.antal_platser == 0 {
+        return None;
+    }
+    let free_share = 1.0 - occupancy(when).clamp(0.0, 1.0);
+    Some((zone.antal_platser as f64 * free_share).round() as u64)
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    fn entry(adress: &str, tid: Option<&str>, taxa: Option<&str>) -> OutputData {
+        OutputData {
+            postnummer: Some("21438".to_string()),
+            adress: adress.to_string(),
+            gata: adress.split(' ').next().unwrap().to_string(),
+            gatunummer: adress.split(' ').nth(1).unwrap().to_string(),
+            info: tid.map(|_| "Städning".to_string()),
+            tid: tid.map(str::to_string),
+            dag: tid.map(|_| 15),
+            dagar: tid.map(|_| vec![15]).unwrap_or_default(),
+            taxa: taxa.map(str::to_string),
+            antal_platser: taxa.map(|_| 10),
+            typ_av_parkering: taxa.map(|_| "Längsgående".to_string()),
+            match_quality: None,
+            segment_id: None,
+        }
+    }
+    /// The previous server implementation, joining through two hash maps
+    fn merge_results_hashmap(
+        addresses: &[AdressClean],
+        miljo_results: &[(String, f64, MiljoeDataClean)],
+        parkering_results: &[(String, f64, ParkeringsDataClean)],
+    ) -> Vec<OutputDataWithDistance> {
+        let miljo_map: HashMap<_, _> = miljo_results
+            .iter()
+            .map(|(addr, dist, miljodata)| (addr.clone(), (*dist, miljodata.clone())))
+            .collect();
+        let parkering_map: HashMap<_, _> = parkering_results
+            .iter()
+            .map(|(addr, dist, data)| (addr.clone(), (*dist, data.clone())))
+            .collect();
+        addresses
+            .iter()
+            .map(|addr| {
+                let miljo_data = miljo_map.get(&addr.adress);
+                let parkering_data = parkering_map.get(&addr.adress);
+                OutputDataWithDistance {
+                    data: OutputData {
+                        postnummer: addr.postnummer.clone(),
+                        adress: addr.adress.clone(),
+                        gata: addr.gata.clone(),
+                        gatunummer: addr.gatunummer.clone(),
+                        info: miljo_data.map(|(_, m)| m.info.clone()),
+                        tid: miljo_data.map(|(_, m)| m.tid.clone()),
+                        dag: miljo_data.map(|(_, m)| m.dag()),
+                        dagar: miljo_data.map(|(_, m)| m.dagar.clone()).unwrap_or_default(),
+                        taxa: parkering_data.map(|(_, p)| p.taxa.clone()),
+                        antal_platser: parkering_data.map(|(_, p)| p.antal_platser),
+                        typ_av_parkering: parkering_data.map(|(_, p)| p.typ_av_parkering.clone()),
+                        match_quality: None,
+                        segment_id: None,
+                    },
+                    miljo_distance: miljo_data.map(|(dist, _)| *dist),
+                    parkering_distance: parkering_data.map(|(dist, _)| *dist),
+                    has_location: addr.has_location(),
+                }
+            })
+            .collect()
+    }
+    #[test]
+    fn test_merge_results_matches_hashmap_join() {
+        let address = |adress: &str| AdressClean {
+            postnummer: Some("21438".to_string()),
+            adress: adress.to_string(),
+            gata: adress.split(' ').next().unwrap().to_string(),
+            gatunummer: adress.split(' ').nth(1).unwrap().to_string(),
+            ..AdressClean::default()
+        };
+        let miljo = |tid: &str, dag: u8| MiljoeDataClean {
+            coordinates: Default::default(),
+            info: "Städning".to_string(),
+            tid: tid.to_string(),
+            dagar: vec![dag],
+            length_m: 10.0,
+            segment_id: None,
+        };
+        let parkering = |taxa: &str| ParkeringsDataClean {
+            coordinates: Default::default(),
+            taxa: taxa.to_string(),
+            antal_platser: 4,
+            typ_av_parkering: "Längsgående".to_string(),
+        };
+        let addresses = vec![
+            address("Storgatan 2"),
+            address("Amiralsgatan 9"),
+            address("Storgatan 1"),
+            address("Nygatan 4"),
+            address("Storgatan 2"),
+        ];
+        let miljo_results = vec![
+            ("Storgatan 1".to_string(), 12.0, miljo("0800-1200", 3)),
+            ("Storgatan 2".to_string(), 5.0, miljo("0800-1200", 15)),
+            ("Okändgatan 7".to_string(), 1.0, miljo("1000-1400", 1)),
+            ("Storgatan 2".to_string(), 6.0, miljo("1200-1600", 16)),
+        ];
+        let parkering_results = vec![
+            ("Nygatan 4".to_string(), 20.0, parkering("Taxa C")),
+            ("Amiralsgatan 9".to_string(), 8.5, parkering("Taxa B")),
+        ];
+        let merged = merge_results(&addresses, &miljo_results, &parkering_results);
+        let expected = merge_results_hashmap(&addresses, &miljo_results, &parkering_results);
+        assert_eq!(format!("{:?}", merged), format!("{:?}", expected));
+        assert_eq!(
+            merged
+                .iter()
+                .map(|row| row.data.adress.as_str())
+                .collect::<Vec<_>>(),
+            vec![
+                "Storgatan 2",
+                "Amiralsgatan 9",
+                "Storgatan 1",
+                "Nygatan 4",
+                "Storgatan 2"
+            ]
+        );
+        assert_eq!(merged[0].data.tid.as_deref(), Some("1200-1600"));
+        assert_eq!(merged[4].miljo_distance, Some(6.0));
+        assert_eq!(merged[1].miljo_distance, None);
+        assert_eq!(merged[1].data.taxa.as_deref(), Some("Taxa B"));
+    }
+    #[test]
+    fn test_diff_results_categorizes_addresses() {
+        let old = vec![
+            entry("Storgatan 1", Some("0800-1200"), None),
+            entry("Storgatan 2", Some("0800-1200"), Some("Taxa C")),
+            entry("Lillgatan 3", None, Some("Taxa B")),
+        ];
+        let new = vec![
+            entry("Storgatan 2", Some("0800-1200"), Some("Taxa C")),
+            entry("Lillgatan 3", Some("1000-1400"), Some("Taxa B")),
+            entry("Nygatan 4", Some("0800-1200"), None),
+        ];
+        let diff = diff_results(&old, &new);
+        assert_eq!(diff.unchanged, 1);
+        assert_eq!(
+            diff.gained
+                .iter()
+                .map(|e| e.adress.as_str())
+                .collect::<Vec<_>>(),
+            vec!["Nygatan 4"]
+        );
+        assert_eq!(
+            diff.lost
+                .iter()
+                .map(|e| e.adress.as_str())
+                .collect::<Vec<_>>(),
+            vec!["Storgatan 1"]
+        );
+        assert_eq!(diff.changed.len(), 1);
+        assert_eq!(diff.changed[0].new.adress, "Lillgatan 3");
+        assert_eq!(diff.changed[0].changed_fields(), vec!["info", "tid", "dag"]);
+        assert!(!diff.is_empty());
+    }
+    #[test]
+    fn test_diff_results_keys_on_postal_code() {
+        let old = vec![entry("Storgatan 1", Some("0800-1200"), None)];
+        let mut moved = old[0].clone();
+        moved.postnummer = Some("21439".to_string());
+        let diff = diff_results(&old, &[moved]);
+        assert_eq!(diff.gained.len(), 1);
+        assert_eq!(diff.lost.len(), 1);
+        assert!(diff.changed.is_empty());
+    }
+    #[test]
+    fn test_export_geojson_points_for_matched_addresses() {
+        use rust_decimal::Decimal;
+        use std::str::FromStr;
+        let address = |adress: &str| AdressClean {
+            coordinates: [Decimal::new(130_000, 4), Decimal::new(556_000, 4)],
+            postnummer: Some("21438".to_string()),
+            adress: adress.to_string(),
+            gata: "Storgatan".to_string(),
+            gatunummer: adress.split(' ').nth(1).unwrap().to_string(),
+        };
+        let addresses = vec![address("Storgatan 1"), address("Storgatan 2")];
+        let results = vec![
+            CorrelationResult::new(
+                "Storgatan 1".to_string(),
+                "21438".to_string(),
+                Some((3.5, "Städning".to_string())),
+                None,
+            ),
+            CorrelationResult::new("Storgatan 2".to_string(), "21438".to_string(), None, None),
+            CorrelationResult::new(
+                "Okänd 9".to_string(),
+                "21438".to_string(),
+                None,
+                Some((8.0, "Taxa C".to_string())),
+            ),
+        ];
+        let output = export_geojson(&results, &addresses);
+        let fc = FeatureCollection::from_str(&output).expect("Output should be valid GeoJSON");
+        assert_eq!(fc.features.len(), 1);
+        let feature = &fc.features[0];
+        assert_eq!(feature.property("address"), Some(&json!("Storgatan 1")));
+        assert_eq!(feature.property("miljo_distance"), Some(&json!(3.5)));
+        assert_eq!(
+            feature.property("parkering_distance"),
+            Some(&serde_json::Value::Null)
+        );
+        assert_eq!(
+            feature.property("dataset_source"),
+            Some(&json!("Miljödata only"))
+        );
+    }
+    #[test]
+    fn test_nearest_address_picks_middle() {
+        let address = |lon: i64, gatunummer: &str| AdressClean {
+            coordinates: [Decimal::new(lon, 6), Decimal::new(55_600_000, 6)],
+            postnummer: Some("21438".to_string()),
+            adress: format!("Storgatan {}", gatunummer),
+            gata: "Storgatan".to_string(),
+            gatunummer: gatunummer.to_string(),
+        };
+        let addresses = vec![
+            address(13_000_000, "1"),
+            address(13_000_300, "3"),
+            address(13_000_600, "5"),
+        ];
+        let gps = [Decimal::new(13_000_320, 6), Decimal::new(55_600_050, 6)];
+        let (idx, dist) = nearest_address(gps, &addresses, 50.0).unwrap();
+        assert_eq!(idx, 1);
+        assert!(dist > 5.0 && dist < 7.0, "got {dist}");
+        assert_eq!(nearest_address(gps, &addresses, 5.0), None);
+        assert_eq!(nearest_address(gps, &[], 50.0), None);
+    }
+    #[test]
+    fn test_diff_results_identical_snapshots() {
+        let snapshot = vec![
+            entry("Storgatan 1", Some("0800-1200"), None),
+            entry("Storgatan 2", None, Some("Taxa C")),
+        ];
+        let diff = diff_results(&snapshot, &snapshot);
+        assert!(diff.is_empty());
+        assert_eq!(diff.unchanged, 2);
+    }
+    fn zone(antal_platser: u64) -> ParkeringsDataClean {
+        ParkeringsDataClean {
+            coordinates: [[Decimal::ZERO; 2]; 2],
+            taxa: "Taxa C".to_string(),
+            antal_platser,
+            typ_av_parkering: "Längsgående".to_string(),
+        }
+    }
+    #[test]
+    fn test_estimated_free_spots_by_time_of_day() {
+        let midnight = SWEDISH_TZ.with_ymd_and_hms(2024, 3, 12, 0, 0, 0).unwrap();
+        let noon = SWEDISH_TZ.with_ymd_and_hms(2024, 3, 12, 12, 0, 0).unwrap();
+        let zone = zone(20);
+        assert_eq!(estimated_free_spots(&zone, midnight), Some(18));
+        assert_eq!(estimated_free_spots(&zone, noon), Some(2));
+        assert_eq!(
+            estimated_free_spots(&zone, midnight.with_timezone(&chrono_tz::UTC)),
+            Some(18),
+            "curve is read in Swedish local time"
+        );
+    }
+    #[test]
+    fn test_estimated_free_spots_with_custom_curve() {
+        let when = SWEDISH_TZ.with_ymd_and_hms(2024, 3, 12, 12, 0, 0).unwrap();
+        assert_eq!(
+            estimated_free_spots_with(&zone(10), when, |_| 0.25),
+            Some(8)
+        );
+        assert_eq!(estimated_free_spots_with(&zone(10), when, |_| 1.5), Some(0));
+        assert_eq!(estimated_free_spots(&zone(0), when), None);
+    }
+    #[test]
+    fn test_malmo_occupancy_is_continuous_over_the_day() {
+        let day = SWEDISH_TZ.with_ymd_and_hms(2024, 3, 12, 0, 0, 0).unwrap();
+        let shares: Vec<f64> = (0..24 * 60)
+            .map(|minute| malmo_occupancy(day + chrono::Duration::minutes(minute)))
+            .collect();
+        assert!(shares.iter().all(|share| (0.0..=1.0).contains(share)));
+        assert!(
+            shares
+                .windows(2)
+                .all(|pair| (pair[1] - pair[0]).abs() < 0.01)
+        );
+    }
+}
@@ -0,0 +1,426 @@
+//! Ball tree spatial algorithm for clustered parking data.
+//!
+//! Lines are grouped into a binary tree of bounding balls built from segment
+//! midpoints. Unlike the uniform grids used by [`RTreeSpatialAlgo`] and
+//! [`KDTreeSpatialAlgo`], the tree adapts to how the data is distributed:
+//! dense parts of the city get small balls, sparse outskirts get large ones.
+//!
+//! # Algorithm
+//!
+//! 1. **Indexing**: Recursively split the lines at the median midpoint along
+//!    the axis with the largest spread, until at most 8 lines remain per leaf
+//! 2. **Bounds**: Each node stores a center and the haversine radius that
+//!    covers every segment endpoint below it
+//! 3. **Query**: Descend into the closer child first and skip any ball whose
+//!    nearest possible point is farther than the best match so far or than
+//!    [`MAX_DISTANCE_METERS`]
+//!
+//! The pruning is exact: a segment inside a ball is never closer than the
+//! distance to the ball's center minus its radius, so results are the same as
+//! [`DistanceBasedAlgo`].
+//!
+//! # Time Complexity
+//!
+//! - **Indexing**: O(n log n)
+//! - **Query**: O(log n) average, O(n) worst case for degenerate data
+//!
+//! # Space Complexity
+//!
+//! O(n) - one node per leaf of up to 8 lines plus the branches above them
+//!
+//! # Use Cases
+//!
+//! - **Clustered data**: City centres with many short segments next to
+//!   sparse areas, where a fixed grid cell is either too big or too small
+//! - **Benchmarking**: A true hierarchical index to compare the grids against
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use amp_core::correlation_algorithms::{BallTreeSpatialAlgo, CorrelationAlgo};
+//! use amp_core::structs::{AdressClean, MiljoeDataClean};
+//!
+//! # let parking_lines: Vec<MiljoeDataClean> = vec![];
+//! let algo = BallTreeSpatialAlgo::new(&parking_lines);
+//!
+//! # let address: AdressClean = unimplemented!();
+//! if let Some((index, distance)) = algo.correlate(&address, &parking_lines) {
+//!     println!("Found match at {:.1}m", distance);
+//! }
+//! ```
+//!
+//! [`RTreeSpatialAlgo`]: crate::correlation_algorithms::RTreeSpatialAlgo
+//! [`KDTreeSpatialAlgo`]: crate::correlation_algorithms::KDTreeSpatialAlgo
+//! [`DistanceBasedAlgo`]: crate::correlation_algorithms::DistanceBasedAlgo
+//! [`MAX_DISTANCE_METERS`]: crate::correlation_algorithms::common::MAX_DISTANCE_METERS
+use crate::correlation_algorithms::common::*;
+use crate::correlation_algorithms::{CorrelationAlgo, ParkeringCorrelationAlgo};
+use crate::structs::{AdressClean, MiljoeDataClean, ParkeringsDataClean};
+use rust_decimal::Decimal;
+/// Most lines stored in a single leaf
+const LEAF_SIZE: usize = 8;
+/// Ball tree spatial index for environmental parking restrictions.
+///
+/// # Examples
+///
+/// ```no_run
+/// use amp_core::correlation_algorithms::{BallTreeSpatialAlgo, CorrelationAlgo};
+/// # use amp_core::structs::{AdressClean, MiljoeDataClean};
+/// # let parking_lines: Vec<MiljoeDataClean> = vec![];
+///
+/// let algo = BallTreeSpatialAlgo::new(&parking_lines);
+/// # let address: AdressClean = unimplemented!();
+/// let result = algo.correlate(&address, &parking_lines);
+/// ```
+pub struct BallTreeSpatialAlgo {
+    tree: BallTree,
+}
+/// Internal line segment representation with converted coordinates.
+struct LineSegment {
+    /// Start point [longitude, latitude]
+    start: [f64; 2],
+    /// End point [longitude, latitude]
+    end: [f64; 2],
+}
+impl LineSegment {
+    fn midpoint(&self) -> [f64; 2] {
+        [
+            (self.start[0] + self.end[0]) / 2.0,
+            (self.start[1] + self.end[1]) / 2.0,
+        ]
+    }
+}
+/// Tree node covering every line below it
+struct Node {
+    /// Ball center [longitude, latitude]
+    center: [f64; 2],
+    /// Ball radius in meters
+    radius: f64,
+    children: Children,
+}
+enum Children {
+    /// Line ids stored directly in this node
+    Leaf(Vec<usize>),
+    /// Node ids of the two halves
+    Branch(usize, usize),
+}
+/// Ball tree shared by the miljö and parkering variants.
+struct BallTree {
+    /// Nodes in build order; the root is the last one
+    nodes: Vec<Node>,
+    /// Line segments by id; `None` for unconvertible lines
+    lines: Vec<Option<LineSegment>>,
+}
+impl BallTree {
+    fn new<'a>(coordinates: impl Iterator<Item = &'a [[Decimal; 2]; 2]>) -> Self {
+        let lines: Vec<Option<LineSegment>> = coordinates
+            .map(|c| {
+                Some(LineSegment {
+                    start: [c[0][0].to_f64()?, c[0][1].to_f64()?],
+                    end: [c[1][0].to_f64()?, c[1][1].to_f64()?],
+                })
+            })
+            .collect();
+        let mut ids: Vec<usize> = (0..lines.len()).filter(|&id| lines[id].is_some()).collect();
+        let mut tree = Self {
+            nodes: Vec::new(),
+            lines,
+        };
+        if !ids.is_empty() {
+            tree.build(&mut ids);
+        }
+        tree
+    }
+    fn segment(&self, id: usize) -> &LineSegment {
+        self.lines[id]
+            .as_ref()
+            .expect("only convertible lines are indexed")
+    }
+    /// Build the subtree over `ids` and return its node id
+    fn build(&mut self, ids: &mut [usize]) -> usize {
+        let count = ids.len() as f64;
+        let center = ids.iter().fold([0.0, 0.0], |sum, &id| {
+            let mid = self.segment(id).midpoint();
+            [sum[0] + mid[0] / count, sum[1] + mid[1] / count]
+        });
+        let radius = ids
+            .iter()
+            .map(|&id| {
+                let line = self.segment(id);
+                haversine_distance(center, line.start).max(haversine_distance(center, line.end))
+            })
+            .fold(0.0, f64::max);
+        let children = if ids.len() <= LEAF_SIZE {
+            Children::Leaf(ids.to_vec())
+        } else {
+            let axis = self.split_axis(ids, center[1]);
+            let half = ids.len() / 2;
+            ids.select_nth_unstable_by(half, |&a, &b| {
+                let a = self.segment(a).midpoint()[axis];
+                let b = self.segment(b).midpoint()[axis];
+                a.total_cmp(&b)
+            });
+            let (left, right) = ids.split_at_mut(half);
+            Children::Branch(self.build(left), self.build(right))
+        };
+        self.nodes.push(Node {
+            center,
+            radius,
+            children,
+        });
+        self.nodes.len() - 1
+    }
+    /// Axis (0 = longitude, 1 = latitude) along which the midpoints spread
+    /// the most, with longitude scaled to ground distance at `lat`
+    fn split_axis(&self, ids: &[usize], lat: f64) -> usize {
+        let (min, max) = ids.iter().fold(
+            ([f64::INFINITY; 2], [f64::NEG_INFINITY; 2]),
+            |(min, max), &id| {
+                let mid = self.segment(id).midpoint();
+                (
+                    [min[0].min(mid[0]), min[1].min(mid[1])],
+                    [max[0].max(mid[0]), max[1].max(mid[1])],
+                )
+            },
+        );
+        let lon_spread = (max[0] - min[0]) * lat.to_radians().cos();
+        let lat_spread = max[1] - min[1];
+        if lon_spread > lat_spread { 0 } else { 1 }
+    }
+    /// Lower bound on the distance from `point` to any line in `node`
+    fn min_distance(&self, node: usize, point: [f64; 2]) -> f64 {
+        let node = &self.nodes[node];
+        (haversine_distance(point, node.center) - node.radius).max(0.0)
+    }
+    fn root(&self) -> Option<usize> {
+        self.nodes.len().checked_sub(1)
+    }
+    /// Closest indexed line within `cutoff_m` meters of `point`
+    fn nearest(&self, point: [f64; 2], cutoff_m: f64) -> Option<(usize, f64)> {
+        let mut best = None;
+        if let Some(root) = self.root() {
+            self.search(root, point, cutoff_m, &mut best);
+        }
+        best
+    }
+    fn search(&self, node: usize, point: [f64; 2], cutoff_m: f64, best: &mut Option<(usize, f64)>) {
+        let bound = best.map_or(cutoff_m, |(_, dist)| dist);
+        if self.min_distance(node, point) > bound {
+            return;
+        }
+        match &self.nodes[node].children {
+            Children::Leaf(ids) => {
+                for &id in ids {
+                    let line = self.segment(id);
+                    let dist = distance_point_to_line(point, line.start, line.end);
+                    if dist <= cutoff_m && is_closer((id, dist), *best) {
+                        *best = Some((id, dist));
+                    }
+                }
+            }
+            &Children::Branch(left, right) => {
+                let (first, second) =
+                    if self.min_distance(left, point) <= self.min_distance(right, point) {
+                        (left, right)
+                    } else {
+                        (right, left)
+                    };
+                self.search(first, point, cutoff_m, best);
+                self.search(second, point, cutoff_m, best);
+            }
+        }
+    }
+    /// Up to `k` closest indexed lines within [`MAX_DISTANCE_METERS`] of `point`
+    fn nearest_k(&self, point: [f64; 2], k: usize) -> Vec<(usize, f64)> {
+        let mut candidates = Vec::new();
+        let mut stack: Vec<usize> = self.root().into_iter().collect();
+        while let Some(node) = stack.pop() {
+            if self.min_distance(node, point) > MAX_DISTANCE_METERS {
+                continue;
+            }
+            match &self.nodes[node].children {
+                Children::Leaf(ids) => candidates.extend(ids.iter().map(|&id| {
+                    let line = self.segment(id);
+                    (id, distance_point_to_line(point, line.start, line.end))
+                })),
+                &Children::Branch(left, right) => stack.extend([left, right]),
+            }
+        }
+        nearest_k(candidates, k)
+    }
+}
+impl BallTreeSpatialAlgo {
+    /// Create a new ball tree spatial index.
+    ///
+    /// # Arguments
+    ///
+    /// * `parking_lines` - Slice of environmental parking restriction lines
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use amp_core::correlation_algorithms::BallTreeSpatialAlgo;
+    /// # use amp_core::structs::MiljoeDataClean;
+    /// # let parking_lines: Vec<MiljoeDataClean> = vec![];
+    ///
+    /// let algo = BallTreeSpatialAlgo::new(&parking_lines);
+    /// ```
+    pub fn new(parking_lines: &[MiljoeDataClean]) -> Self {
+        Self {
+            tree: BallTree::new(parking_lines.iter().map(|line| &line.coordinates)),
+        }
+    }
+}
+impl CorrelationAlgo for BallTreeSpatialAlgo {
+    /// Correlate address with parking lines using the ball tree.
+    ///
+    /// # Arguments
+    ///
+    /// * `address` - Address point to correlate
+    /// * `_parking_lines` - Ignored (tree stores line references)
+    /// * `cutoff_m` - Maximum distance in meters, also the initial pruning bound
+    ///
+    /// # Returns
+    ///
+    /// - `Some((index, distance))` if a line is within `cutoff_m` meters
+    /// - `None` if no match found
+    fn correlate_within(
+        &self,
+        address: &AdressClean,
+        _parking_lines: &[MiljoeDataClean],
+        cutoff_m: f64,
+    ) -> Option<(usize, f64)> {
+        let point = [
+            address.coordinates[0].to_f64()?,
+            address.coordinates[1].to_f64()?,
+        ];
+        self.tree.nearest(point, cutoff_m)
+    }
+    /// Up to `k` closest lines from the ball tree, closest first.
+    fn correlate_k(
+        &self,
+        address: &AdressClean,
+        _parking_lines: &[MiljoeDataClean],
+        k: usize,
+    ) -> Vec<(usize, f64)> {
+        let (Some(x), Some(y)) = (
+            address.coordinates[0].to_f64(),
+            address.coordinates[1].to_f64(),
+        ) else {
+            return Vec::new();
+        };
+        self.tree.nearest_k([x, y], k)
+    }
+    fn name(&self) -> &'static str {
+        "Ball Tree Spatial"
+    }
+}
+/// Ball tree spatial index for parking zones.
+///
+/// Identical implementation to [`BallTreeSpatialAlgo`] but for parking zone data.
+pub struct BallTreeParkeringAlgo {
+    tree: BallTree,
+}
+impl BallTreeParkeringAlgo {
+    /// Create a new ball tree spatial index for parking zones.
+    ///
+    /// # Arguments
+    ///
+    /// * `parking_lines` - Slice of parking zone line segments
+    pub fn new(parking_lines: &[ParkeringsDataClean]) -> Self {
+        Self {
+            tree: BallTree::new(parking_lines.iter().map(|line| &line.coordinates)),
+        }
+    }
+}
+impl ParkeringCorrelationAlgo for BallTreeParkeringAlgo {
+    /// Correlate address with parking zone lines.
+    ///
+    /// # Returns
+    ///
+    /// - `Some((index, distance))` if a line is within 50 meters
+    /// - `None` if no match found
+    fn correlate(
+        &self,
+        address: &AdressClean,
+        _parking_lines: &[ParkeringsDataClean],
+    ) -> Option<(usize, f64)> {
+        let point = [
+            address.coordinates[0].to_f64()?,
+            address.coordinates[1].to_f64()?,
+        ];
+        self.tree.nearest(point, MAX_DISTANCE_METERS)
+    }
+    fn name(&self) -> &'static str {
+        "Ball Tree Spatial Index (Parkering)"
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::correlation_algorithms::DistanceBasedAlgo;
+    fn line(x: i64, y: i64, dx: i64, dy: i64) -> MiljoeDataClean {
+        MiljoeDataClean::new(
+            [
+                [Decimal::new(x, 6), Decimal::new(y, 6)],
+                [Decimal::new(x + dx, 6), Decimal::new(y + dy, 6)],
+            ],
+            String::new(),
+            "0800-1200".to_string(),
+            1,
+        )
+    }
+    fn address_at(x: i64, y: i64) -> AdressClean {
+        AdressClean {
+            coordinates: [Decimal::new(x, 6), Decimal::new(y, 6)],
+            postnummer: None,
+            adress: "Testgatan 1".to_string(),
+            gata: "Testgatan".to_string(),
+            gatunummer: "1".to_string(),
+        }
+    }
+    /// A dense cluster of short segments next to a few long, sparse ones
+    fn clustered_lines() -> Vec<MiljoeDataClean> {
+        let mut lines = Vec::new();
+        for i in 0..20 {
+            for j in 0..10 {
+                lines.push(line(
+                    13_000_000 + i * 150,
+                    55_600_000 + j * 130,
+                    60 + (i + j) % 5 * 20,
+                    (i * j) % 7 * 10,
+                ));
+            }
+        }
+        for i in 0..5 {
+            lines.push(line(13_050_000 + i * 4_000, 55_640_000, 1_500, 900));
+        }
+        lines
+    }
+    #[test]
+    fn test_matches_brute_force_on_clustered_data() {
+        let lines = clustered_lines();
+        let algo = BallTreeSpatialAlgo::new(&lines);
+        let mut matched = 0;
+        for x in (12_999_000..13_070_000).step_by(1_370) {
+            for y in (55_599_000..55_642_000).step_by(410) {
+                let address = address_at(x, y);
+                let expected = DistanceBasedAlgo.correlate(&address, &lines);
+                matched += usize::from(expected.is_some());
+                assert_eq!(algo.correlate(&address, &lines), expected);
+                assert_eq!(
+                    algo.correlate_k(&address, &lines, 3),
+                    DistanceBasedAlgo.correlate_k(&address, &lines, 3)
+                );
+            }
+        }
+        assert!(matched > 0);
+    }
+    #[test]
+    fn test_empty_index() {
+        let algo = BallTreeSpatialAlgo::new(&[]);
+        let address = address_at(13_000_000, 55_600_000);
+        assert_eq!(algo.correlate(&address, &[]), None);
+        assert!(algo.correlate_k(&address, &[], 3).is_empty());
+    }
+}
@@ -2,6 +2,7 @@
 //!
 //! This module contains shared functions and macros used across multiple
 //! correlation algorithm implementations to reduce code duplication.
+use rust_decimal::Decimal;
 pub use rust_decimal::prelude::ToPrimitive;
 use std::collections::HashSet;
 /// Constants shared across algorithms
@@ -50,9 +51,23 @@ pub fn haversine_distance(point1: [f64; 2], point2: [f64; 2]) -> f64 {
     let c = 2.0 * a.sqrt().atan2((1.0 - a).sqrt());
     EARTH_RADIUS_M * c
 }
+/// Length of a `[[lon, lat], [lon, lat]]` segment in meters
+///
+/// Coordinates that don't fit in an `f64` count as zero length.
+pub fn segment_length_meters(coordinates: &[[Decimal; 2]; 2]) -> f64 {
+    let point = |p: &[Decimal; 2]| Some([p[0].to_f64()?, p[1].to_f64()?]);
+    match (point(&coordinates[0]), point(&coordinates[1])) {
+        (Some(start), Some(end)) => haversine_distance(start, end),
+        _ => 0.0,
+    }
+}
 /// Calculate perpendicular distance from point to line segment
 ///
-/// Uses Haversine distance for the final calculation, inheriting its
+/// The closest point on the segment is found in a local tangent plane around
+/// `point`, where longitude is scaled by `cos(latitude)`. In raw degrees a
+/// step east is only ~0.56 of a step north at Malmö's latitude, which skews
+/// the projection towards the wrong end of diagonal segments. The distance
+/// to that point is then measured with Haversine, inheriting its
 /// approximation characteristics (see `haversine_distance` documentation).
 ///
 /// # Arguments
@@ -63,20 +78,57 @@ pub fn haversine_distance(point1: [f64; 2], point2: [f64; 2]) -> f64 {
 /// # Returns
 /// Distance in meters (approximate)
 pub fn distance_point_to_line(point: [f64; 2], line_start: [f64; 2], line_end: [f64; 2]) -> f64 {
+    let lon_scale = point[1].to_radians().cos();
     let line_vec = [line_end[0] - line_start[0], line_end[1] - line_start[1]];
     let point_vec = [point[0] - line_start[0], point[1] - line_start[1]];
-    let line_len_sq = line_vec[0] * line_vec[0] + line_vec[1] * line_vec[1];
+    let line_len_sq = (line_vec[0] * lon_scale).powi(2) + line_vec[1].powi(2);
     if line_len_sq == 0.0 {
         return haversine_distance(point, line_start);
     }
-    let t =
-        ((point_vec[0] * line_vec[0] + point_vec[1] * line_vec[1]) / line_len_sq).clamp(0.0, 1.0);
+    let dot = point_vec[0] * line_vec[0] * lon_scale * lon_scale + point_vec[1] * line_vec[1];
+    let t = (dot / line_len_sq).clamp(0.0, 1.0);
     let closest = [
         line_start[0] + t * line_vec[0],
         line_start[1] + t * line_vec[1],
     ];
     haversine_distance(point, closest)
 }
+/// Calculate distance from point to the nearest segment of a polyline
+///
+/// Each consecutive pair of vertices is measured with
+/// [`distance_point_to_line`] and the minimum is returned, so a point beside
+/// a bend matches the leg it is actually next to.
+///
+/// # Arguments
+/// * `point` - Query point as [longitude, latitude]
+/// * `polyline` - Vertices as [longitude, latitude], in order
+///
+/// # Returns
+/// Distance in meters, or `None` if the polyline has fewer than two
+/// vertices or a coordinate doesn't fit in an `f64`
+///
+/// # Examples
+/// ```
+/// use amp_core::correlation_algorithms::common::distance_point_to_polyline;
+/// use rust_decimal::Decimal;
+///
+/// let line = [
+///     [Decimal::new(130, 1), Decimal::new(55000, 3)],
+///     [Decimal::new(130, 1), Decimal::new(55001, 3)],
+/// ];
+/// let distance = distance_point_to_polyline([13.0, 55.0], &line).unwrap();
+/// assert!(distance < 1.0);
+/// ```
+pub fn distance_point_to_polyline(point: [f64; 2], polyline: &[[Decimal; 2]]) -> Option<f64> {
+    let mut min_dist: Option<f64> = None;
+    for segment in polyline.windows(2) {
+        let start = [segment[0][0].to_f64()?, segment[0][1].to_f64()?];
+        let end = [segment[1][0].to_f64()?, segment[1][1].to_f64()?];
+        let dist = distance_point_to_line(point, start, end);
+        min_dist = Some(min_dist.map_or(dist, |min| min.min(dist)));
+    }
+    min_dist
+}
 /// Get all grid cells a line segment passes through using optimized DDA algorithm
 ///
 /// Uses HashSet to avoid duplicate cells during construction, which is more efficient
@@ -182,6 +234,110 @@ pub fn get_nearby_cells(cell: (i32, i32)) -> Vec<(i32, i32)> {
     }
     cells
 }
+/// Every cell a point within `reach` of `point` can fall in
+///
+/// `reach` is the half-width of the search box along each axis, in the same
+/// units as `cell_size`. With a reach below one cell this is at most the
+/// 3×3 neighborhood of [`get_nearby_cells`].
+///
+/// # Examples
+/// ```
+/// use amp_core::correlation_algorithms::common::get_cells_within;
+///
+/// let cells = get_cells_within([10.5, 20.5], [2.0, 1.0], 1.0);
+/// assert_eq!(cells.len(), 5 * 3);
+/// assert!(cells.contains(&(8, 19)));
+/// assert!(cells.contains(&(12, 21)));
+/// ```
+pub fn get_cells_within(point: [f64; 2], reach: [f64; 2], cell_size: f64) -> Vec<(i32, i32)> {
+    let min = get_cell([point[0] - reach[0], point[1] - reach[1]], cell_size);
+    let max = get_cell([point[0] + reach[0], point[1] + reach[1]], cell_size);
+    (min.0..=max.0)
+        .flat_map(|x| (min.1..=max.1).map(move |y| (x, y)))
+        .collect()
+}
+/// Whether a candidate match should replace the best one found so far
+///
+/// The closer line wins. On an exact tie the lower segment index wins, so
+/// the result doesn't depend on the order an index visits its lines in and
+/// generated output stays reproducible. A NaN distance never wins.
+///
+/// # Examples
+/// ```
+/// use amp_core::correlation_algorithms::common::is_closer;
+///
+/// assert!(is_closer((3, 5.0), None));
+/// assert!(is_closer((3, 4.0), Some((1, 5.0))));
+/// assert!(is_closer((1, 5.0), Some((3, 5.0))));
+/// assert!(!is_closer((3, 5.0), Some((1, 5.0))));
+/// assert!(!is_closer((0, f64::NAN), None));
+/// ```
+pub fn is_closer(candidate: (usize, f64), best: Option<(usize, f64)>) -> bool {
+    let (idx, dist) = candidate;
+    if dist.is_nan() {
+        return false;
+    }
+    match best {
+        None => true,
+        Some((best_idx, best_dist)) => dist < best_dist || (dist == best_dist && idx < best_idx),
+    }
+}
+/// Closest of a set of `(index, distance)` candidates, see [`is_closer`]
+pub fn closest(candidates: impl IntoIterator<Item = (usize, f64)>) -> Option<(usize, f64)> {
+    candidates.into_iter().fold(None, |best, candidate| {
+        if is_closer(candidate, best) {
+            Some(candidate)
+        } else {
+            best
+        }
+    })
+}
+/// All `(index, distance)` candidates within `radius` meters, closest first.
+///
+/// Candidates with a NaN distance are dropped, and an index seen more than
+/// once (a line stored in several grid cells) is kept once. Ties are broken
+/// by index as in [`is_closer`].
+///
+/// # Examples
+/// ```
+/// use amp_core::correlation_algorithms::common::in_range;
+///
+/// let found = in_range([(4, 90.0), (2, 30.0), (4, 90.0), (7, 150.0)], 100.0);
+/// assert_eq!(found, vec![(2, 30.0), (4, 90.0)]);
+/// ```
+pub fn in_range(
+    candidates: impl IntoIterator<Item = (usize, f64)>,
+    radius: f64,
+) -> Vec<(usize, f64)> {
+    let mut found: Vec<(usize, f64)> = candidates
+        .into_iter()
+        .filter(|&(_, dist)| dist <= radius)
+        .collect();
+    found.sort_by(|a, b| a.1.total_cmp(&b.1).then(a.0.cmp(&b.0)));
+    found.dedup_by_key(|&mut (idx, _)| idx);
+    found
+}
+/// Up to `k` closest of a set of `(index, distance)` candidates.
+///
+/// Candidates past [`MAX_DISTANCE_METERS`] or with a NaN distance are
+/// dropped, and an index seen more than once (a line stored in several grid
+/// cells) is kept once. Sorted by distance, ties by index as in [`is_closer`].
+///
+/// # Examples
+/// ```
+/// use amp_core::correlation_algorithms::common::nearest_k;
+///
+/// let found = nearest_k([(4, 12.0), (2, 3.0), (4, 12.0), (7, 80.0), (1, 12.0)], 2);
+/// assert_eq!(found, vec![(2, 3.0), (1, 12.0)]);
+/// ```
+pub fn nearest_k(
+    candidates: impl IntoIterator<Item = (usize, f64)>,
+    k: usize,
+) -> Vec<(usize, f64)> {
+    let mut nearest = in_range(candidates, MAX_DISTANCE_METERS);
+    nearest.truncate(k);
+    nearest
+}
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -193,6 +349,24 @@ mod tests {
         assert!((dist - 111.0).abs() < 1.0);
     }
     #[test]
+    fn test_distance_point_to_diagonal_line() {
+        let line_start = [13.0, 55.6];
+        let line_end = [13.001, 55.601];
+        let point = [13.001, 55.6];
+        let reference = (0..=10_000)
+            .map(|i| {
+                let t = f64::from(i) / 10_000.0;
+                let on_line = [
+                    line_start[0] + t * (line_end[0] - line_start[0]),
+                    line_start[1] + t * (line_end[1] - line_start[1]),
+                ];
+                haversine_distance(point, on_line)
+            })
+            .fold(f64::INFINITY, f64::min);
+        let dist = distance_point_to_line(point, line_start, line_end);
+        assert!((dist - reference).abs() / reference < 0.01);
+    }
+    #[test]
     fn test_haversine_same_point() {
         let point = [13.0, 55.0];
         let dist = haversine_distance(point, point);
@@ -207,6 +381,28 @@ mod tests {
         assert!((dist - 111.0).abs() < 10.0);
     }
     #[test]
+    fn test_distance_point_to_polyline_picks_nearest_leg() {
+        let vertex = |lon: f64, lat: f64| {
+            [
+                Decimal::try_from(lon).unwrap(),
+                Decimal::try_from(lat).unwrap(),
+            ]
+        };
+        let l_shape = [
+            vertex(13.0, 55.0),
+            vertex(13.0, 55.001),
+            vertex(13.002, 55.001),
+        ];
+        let point = [13.0015, 55.0012];
+        let dist = distance_point_to_polyline(point, &l_shape).unwrap();
+        let first_leg = distance_point_to_line(point, [13.0, 55.0], [13.0, 55.001]);
+        let second_leg = distance_point_to_line(point, [13.0, 55.001], [13.002, 55.001]);
+        assert!(second_leg < first_leg);
+        assert_eq!(dist, second_leg);
+        assert!((dist - 22.2).abs() < 1.0);
+        assert_eq!(distance_point_to_polyline(point, &l_shape[..1]), None);
+    }
+    #[test]
     fn test_distance_point_to_zero_length_line() {
         let point = [13.0, 55.0];
         let line_point = [13.0, 55.001];
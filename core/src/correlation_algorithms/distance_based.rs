@@ -8,7 +8,8 @@
 //!
 //! 1. Convert address coordinates from [`Decimal`] to `f64`
 //! 2. For each parking line:
-//!    - Calculate perpendicular distance using [`distance_point_to_line`]
+//!    - Calculate distance to its nearest segment using
+//!      [`distance_point_to_polyline`]
 //!    - Keep lines within [`MAX_DISTANCE_METERS`] (50m)
 //! 3. Return the line with minimum distance
 //!
@@ -59,7 +60,7 @@
 //!
 //! [`Decimal`]: rust_decimal::Decimal
 //! [`MAX_DISTANCE_METERS`]: crate::correlation_algorithms::common::MAX_DISTANCE_METERS
-//! [`distance_point_to_line`]: crate::correlation_algorithms::common::distance_point_to_line
+//! [`distance_point_to_polyline`]: crate::correlation_algorithms::common::distance_point_to_polyline
 use crate::correlation_algorithms::common::*;
 use crate::correlation_algorithms::{CorrelationAlgo, ParkeringCorrelationAlgo};
 use crate::structs::{AdressClean, MiljoeDataClean, ParkeringsDataClean};
@@ -84,39 +85,26 @@ impl CorrelationAlgo for DistanceBasedAlgo {
     /// Correlate address with environmental parking lines using brute-force.
     ///
     /// Calculates perpendicular distance to every line and returns the closest
-    /// match within [`MAX_DISTANCE_METERS`].
+    /// match within `cutoff_m`.
     ///
     /// # Returns
     ///
-    /// - `Some((index, distance))` if a line is within 50 meters
+    /// - `Some((index, distance))` if a line is within `cutoff_m` meters
     /// - `None` if no match found or coordinate conversion fails
-    ///
-    /// [`MAX_DISTANCE_METERS`]: crate::correlation_algorithms::common::MAX_DISTANCE_METERS
-    fn correlate(
+    fn correlate_within(
         &self,
         address: &AdressClean,
         parking_lines: &[MiljoeDataClean],
+        cutoff_m: f64,
     ) -> Option<(usize, f64)> {
         let point = [
             address.coordinates[0].to_f64()?,
             address.coordinates[1].to_f64()?,
         ];
-        parking_lines
-            .iter()
-            .enumerate()
-            .filter_map(|(idx, line)| {
-                let line_start = [
-                    line.coordinates[0][0].to_f64()?,
-                    line.coordinates[0][1].to_f64()?,
-                ];
-                let line_end = [
-                    line.coordinates[1][0].to_f64()?,
-                    line.coordinates[1][1].to_f64()?,
-                ];
-                let dist = distance_point_to_line(point, line_start, line_end);
-                (dist <= MAX_DISTANCE_METERS).then_some((idx, dist))
-            })
-            .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap())
+        closest(parking_lines.iter().enumerate().filter_map(|(idx, line)| {
+            let dist = distance_point_to_polyline(point, &line.coordinates)?;
+            (dist <= cutoff_m).then_some((idx, dist))
+        }))
     }
     fn name(&self) -> &'static str {
         "Distance-Based"
@@ -142,22 +130,10 @@ impl ParkeringCorrelationAlgo for DistanceBasedParkeringAlgo {
             address.coordinates[0].to_f64()?,
             address.coordinates[1].to_f64()?,
         ];
-        parking_lines
-            .iter()
-            .enumerate()
-            .filter_map(|(idx, line)| {
-                let line_start = [
-                    line.coordinates[0][0].to_f64()?,
-                    line.coordinates[0][1].to_f64()?,
-                ];
-                let line_end = [
-                    line.coordinates[1][0].to_f64()?,
-                    line.coordinates[1][1].to_f64()?,
-                ];
-                let dist = distance_point_to_line(point, line_start, line_end);
-                (dist <= MAX_DISTANCE_METERS).then_some((idx, dist))
-            })
-            .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap())
+        closest(parking_lines.iter().enumerate().filter_map(|(idx, line)| {
+            let dist = distance_point_to_polyline(point, &line.coordinates)?;
+            (dist <= MAX_DISTANCE_METERS).then_some((idx, dist))
+        }))
     }
     fn name(&self) -> &'static str {
         "Distance-Based (Parkering)"
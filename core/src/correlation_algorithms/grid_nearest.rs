@@ -85,6 +85,7 @@
 //! [`RTreeSpatialAlgo`]: crate::correlation_algorithms::RTreeSpatialAlgo
 use crate::correlation_algorithms::common::*;
 use crate::correlation_algorithms::{CorrelationAlgo, ParkeringCorrelationAlgo};
+use crate::crs::CoordinateSpace;
 use crate::structs::{AdressClean, MiljoeDataClean, ParkeringsDataClean};
 use std::collections::HashMap;
 /// Grid-based nearest neighbor algorithm for environmental parking restrictions.
@@ -106,8 +107,6 @@ use std::collections::HashMap;
 pub struct GridNearestAlgo {
     /// Grid cells mapping (cell_x, cell_y) to line indices
     grid: HashMap<(i32, i32), Vec<usize>>,
-    /// Grid cell size in degrees (default: 0.0005)
-    cell_size: f64,
 }
 impl GridNearestAlgo {
     /// Create a new grid-based spatial index from parking lines.
@@ -147,26 +146,25 @@ impl GridNearestAlgo {
                 }
             }
         }
-        Self {
-            grid,
-            cell_size: CELL_SIZE,
-        }
+        Self { grid }
     }
 }
 impl CorrelationAlgo for GridNearestAlgo {
     /// Correlate address with environmental parking lines using grid index.
     ///
-    /// Searches 3×3 neighborhood and converts line coordinates on-the-fly
-    /// during distance calculations (no coordinate caching).
+    /// Searches 3×3 neighborhood (more cells for cutoffs past 50 meters) and
+    /// converts line coordinates on-the-fly during distance calculations (no
+    /// coordinate caching).
     ///
     /// # Arguments
     ///
     /// * `address` - Address point to correlate
     /// * `parking_lines` - Slice of parking lines (needed for coordinate access)
+    /// * `cutoff_m` - Maximum distance in meters
     ///
     /// # Returns
     ///
-    /// - `Some((index, distance))` if a line is within 50 meters
+    /// - `Some((index, distance))` if a line is within `cutoff_m` meters
     /// - `None` if no match found or coordinate conversion fails
     ///
     /// # Note
@@ -175,17 +173,17 @@ impl CorrelationAlgo for GridNearestAlgo {
     /// since coordinates are not cached in the index.
     ///
     /// [`RTreeSpatialAlgo`]: crate::correlation_algorithms::RTreeSpatialAlgo
-    fn correlate(
+    fn correlate_within(
         &self,
         address: &AdressClean,
         parking_lines: &[MiljoeDataClean],
+        cutoff_m: f64,
     ) -> Option<(usize, f64)> {
         let point = [
             address.coordinates[0].to_f64()?,
             address.coordinates[1].to_f64()?,
         ];
-        let cell = get_cell(point, self.cell_size);
-        let nearby_cells = get_nearby_cells(cell);
+        let nearby_cells = CoordinateSpace::Wgs84.search_cells(point, cutoff_m);
         let mut best: Option<(usize, f64)> = None;
         for check_cell in nearby_cells {
             if let Some(indices) = self.grid.get(&check_cell) {
@@ -200,7 +198,7 @@ impl CorrelationAlgo for GridNearestAlgo {
                         line.coordinates[1][1].to_f64()?,
                     ];
                     let dist = distance_point_to_line(point, start, end);
-                    if dist <= MAX_DISTANCE_METERS && (best.is_none() || dist < best.unwrap().1) {
+                    if dist <= cutoff_m && is_closer((idx, dist), best) {
                         best = Some((idx, dist));
                     }
                 }
@@ -288,7 +286,7 @@ impl ParkeringCorrelationAlgo for GridNearestParkeringAlgo {
                         line.coordinates[1][1].to_f64()?,
                     ];
                     let dist = distance_point_to_line(point, start, end);
-                    if dist <= MAX_DISTANCE_METERS && (best.is_none() || dist < best.unwrap().1) {
+                    if dist <= MAX_DISTANCE_METERS && is_closer((idx, dist), best) {
                         best = Some((idx, dist));
                     }
                 }
@@ -44,6 +44,22 @@
 //! - **Query**: ~0.01-0.05ms
 //! - **Throughput**: ~500,000 queries/second
 //!
+//! # Incremental Updates
+//!
+//! Because the index is a flat grid rather than a balanced tree, new data can
+//! be applied without a rebuild: [`KDTreeSpatialAlgo::insert`] adds a line to
+//! the cells it crosses and [`KDTreeSpatialAlgo::remove`] takes it out again.
+//! The same methods exist on [`KDTreeParkeringAlgo`].
+//!
+//! - **Insert**: O(k), k = cells the line crosses
+//! - **Remove**: O(k × m), m = lines sharing each of those cells
+//! - **Amortized**: A delta of d lines costs O(d × k × m) instead of the
+//!   O(n × k) of a full rebuild, and never degrades query time since there is
+//!   no tree balance to lose
+//!
+//! Removed lines leave an empty slot so line ids stay aligned with the
+//! caller's data vector.
+//!
 //! # Use Cases
 //!
 //! - **Benchmarking**: Compare named "KD-tree" vs "R-tree" implementations
@@ -80,7 +96,9 @@
 //! [`RTreeSpatialAlgo`]: crate::correlation_algorithms::RTreeSpatialAlgo
 use crate::correlation_algorithms::common::*;
 use crate::correlation_algorithms::{CorrelationAlgo, ParkeringCorrelationAlgo};
+use crate::crs::CoordinateSpace;
 use crate::structs::{AdressClean, MiljoeDataClean, ParkeringsDataClean};
+use rust_decimal::Decimal;
 use std::collections::HashMap;
 /// KD-tree-inspired spatial index using grid implementation.
 ///
@@ -101,23 +119,128 @@ use std::collections::HashMap;
 ///
 /// [`RTreeSpatialAlgo`]: crate::correlation_algorithms::RTreeSpatialAlgo
 pub struct KDTreeSpatialAlgo {
-    /// Grid cells mapping (cell_x, cell_y) to line indices
-    grid: HashMap<(i32, i32), Vec<usize>>,
-    /// Cached line segments with f64 coordinates
-    lines: Vec<LineSegment>,
-    /// Grid cell size in degrees
-    cell_size: f64,
+    index: GridIndex,
 }
 /// Internal line segment representation with converted coordinates.
 #[derive(Clone)]
 struct LineSegment {
-    /// Original index in the input array
-    index: usize,
     /// Start point [longitude, latitude]
     start: [f64; 2],
     /// End point [longitude, latitude]
     end: [f64; 2],
 }
+/// Grid index shared by the miljö and parkering variants.
+struct GridIndex {
+    /// Grid cells mapping (cell_x, cell_y) to line ids
+    grid: HashMap<(i32, i32), Vec<usize>>,
+    /// Line segments by id; `None` for removed or unconvertible lines
+    lines: Vec<Option<LineSegment>>,
+    /// Grid cell size in degrees
+    cell_size: f64,
+}
+impl GridIndex {
+    fn new() -> Self {
+        Self {
+            grid: HashMap::new(),
+            lines: Vec::new(),
+            cell_size: CELL_SIZE,
+        }
+    }
+    /// Append a line and return its id (its position in the caller's data).
+    fn insert(&mut self, coordinates: &[[Decimal; 2]; 2]) -> usize {
+        let id = self.lines.len();
+        let segment = match (
+            coordinates[0][0].to_f64(),
+            coordinates[0][1].to_f64(),
+            coordinates[1][0].to_f64(),
+            coordinates[1][1].to_f64(),
+        ) {
+            (Some(x1), Some(y1), Some(x2), Some(y2)) => {
+                for cell in line_cells(x1, y1, x2, y2, self.cell_size) {
+                    self.grid.entry(cell).or_default().push(id);
+                }
+                Some(LineSegment {
+                    start: [x1, y1],
+                    end: [x2, y2],
+                })
+            }
+            _ => None,
+        };
+        self.lines.push(segment);
+        id
+    }
+    /// Remove a line from every cell it occupies.
+    fn remove(&mut self, id: usize) -> bool {
+        let Some(segment) = self.lines.get_mut(id).and_then(Option::take) else {
+            return false;
+        };
+        let cells = line_cells(
+            segment.start[0],
+            segment.start[1],
+            segment.end[0],
+            segment.end[1],
+            self.cell_size,
+        );
+        for cell in cells {
+            if let Some(ids) = self.grid.get_mut(&cell) {
+                ids.retain(|&other| other != id);
+                if ids.is_empty() {
+                    self.grid.remove(&cell);
+                }
+            }
+        }
+        true
+    }
+    /// Number of lines currently indexed
+    fn len(&self) -> usize {
+        self.lines.iter().filter(|line| line.is_some()).count()
+    }
+    /// Closest indexed line within `cutoff_m` meters of `point`
+    fn nearest(&self, point: [f64; 2], cutoff_m: f64) -> Option<(usize, f64)> {
+        let nearby_cells = CoordinateSpace::Wgs84.search_cells(point, cutoff_m);
+        let mut best: Option<(usize, f64)> = None;
+        for check_cell in nearby_cells {
+            if let Some(ids) = self.grid.get(&check_cell) {
+                for &id in ids {
+                    let Some(line) = &self.lines[id] else {
+                        continue;
+                    };
+                    let dist = distance_point_to_line(point, line.start, line.end);
+                    if dist <= cutoff_m && is_closer((id, dist), best) {
+                        best = Some((id, dist));
+                    }
+                }
+            }
+        }
+        best
+    }
+    /// Up to `k` closest indexed lines within [`MAX_DISTANCE_METERS`] of `point`
+    fn nearest_k(&self, point: [f64; 2], k: usize) -> Vec<(usize, f64)> {
+        let cell = get_cell(point, self.cell_size);
+        let candidates = get_nearby_cells(cell)
+            .into_iter()
+            .filter_map(|check_cell| self.grid.get(&check_cell))
+            .flatten()
+            .filter_map(|&id| {
+                let line = self.lines[id].as_ref()?;
+                Some((id, distance_point_to_line(point, line.start, line.end)))
+            });
+        nearest_k(candidates, k)
+    }
+    /// Every indexed line within `radius_m` meters of `point`, closest first
+    fn within_radius(&self, point: [f64; 2], radius_m: f64) -> Vec<(usize, f64)> {
+        let reach = CoordinateSpace::Wgs84.reach(point, radius_m);
+        let candidates = get_cells_within(point, reach, self.cell_size)
+            .into_iter()
+            .filter_map(|check_cell| self.grid.get(&check_cell))
+            .flatten()
+            .filter_map(|&id| {
+                let line = self.lines[id].as_ref()?;
+                Some((id, distance_point_to_line(point, line.start, line.end)))
+            });
+        in_range(candidates, radius_m)
+    }
+}
 impl KDTreeSpatialAlgo {
     /// Create a new KD-tree-inspired spatial index.
     ///
@@ -139,31 +262,54 @@ impl KDTreeSpatialAlgo {
     ///
     /// [`RTreeSpatialAlgo::new`]: crate::correlation_algorithms::RTreeSpatialAlgo::new
     pub fn new(parking_lines: &[MiljoeDataClean]) -> Self {
-        let mut grid: HashMap<(i32, i32), Vec<usize>> = HashMap::new();
-        let mut lines = Vec::new();
-        for (idx, line) in parking_lines.iter().enumerate() {
-            if let (Some(x1), Some(y1), Some(x2), Some(y2)) = (
-                line.coordinates[0][0].to_f64(),
-                line.coordinates[0][1].to_f64(),
-                line.coordinates[1][0].to_f64(),
-                line.coordinates[1][1].to_f64(),
-            ) {
-                lines.push(LineSegment {
-                    index: idx,
-                    start: [x1, y1],
-                    end: [x2, y2],
-                });
-                let cells = line_cells(x1, y1, x2, y2, CELL_SIZE);
-                for cell in cells {
-                    grid.entry(cell).or_default().push(idx);
-                }
-            }
-        }
-        Self {
-            grid,
-            lines,
-            cell_size: CELL_SIZE,
+        let mut index = GridIndex::new();
+        for line in parking_lines {
+            index.insert(&line.coordinates);
         }
+        Self { index }
+    }
+    /// Add a line to the index without rebuilding it.
+    ///
+    /// The caller should push `line` onto its own data vector at the same
+    /// time, so the returned id keeps pointing at the right element.
+    ///
+    /// # Returns
+    ///
+    /// Id assigned to the line, equal to the number of lines inserted before it
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use amp_core::correlation_algorithms::KDTreeSpatialAlgo;
+    /// # use amp_core::structs::MiljoeDataClean;
+    /// # let mut parking_lines: Vec<MiljoeDataClean> = vec![];
+    /// # let new_line: MiljoeDataClean = unimplemented!();
+    ///
+    /// let mut algo = KDTreeSpatialAlgo::new(&parking_lines);
+    /// let id = algo.insert(&new_line);
+    /// parking_lines.push(new_line);
+    /// assert_eq!(id, parking_lines.len() - 1);
+    /// ```
+    pub fn insert(&mut self, line: &MiljoeDataClean) -> usize {
+        self.index.insert(&line.coordinates)
+    }
+    /// Remove a line from the index without rebuilding it.
+    ///
+    /// Ids of the remaining lines are unchanged.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the line was indexed and has been removed
+    pub fn remove(&mut self, id: usize) -> bool {
+        self.index.remove(id)
+    }
+    /// Number of lines currently indexed
+    pub fn len(&self) -> usize {
+        self.index.len()
+    }
+    /// Whether the index holds no lines
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
     }
 }
 impl CorrelationAlgo for KDTreeSpatialAlgo {
@@ -175,35 +321,53 @@ impl CorrelationAlgo for KDTreeSpatialAlgo {
     ///
     /// * `address` - Address point to correlate
     /// * `_parking_lines` - Ignored (index stores line references)
+    /// * `cutoff_m` - Maximum distance in meters
     ///
     /// # Returns
     ///
-    /// - `Some((index, distance))` if a line is within 50 meters
+    /// - `Some((index, distance))` if a line is within `cutoff_m` meters
     /// - `None` if no match found
-    fn correlate(
+    fn correlate_within(
         &self,
         address: &AdressClean,
         _parking_lines: &[MiljoeDataClean],
+        cutoff_m: f64,
     ) -> Option<(usize, f64)> {
         let point = [
             address.coordinates[0].to_f64()?,
             address.coordinates[1].to_f64()?,
         ];
-        let cell = get_cell(point, self.cell_size);
-        let nearby_cells = get_nearby_cells(cell);
-        let mut best: Option<(usize, f64)> = None;
-        for check_cell in nearby_cells {
-            if let Some(indices) = self.grid.get(&check_cell) {
-                for &idx in indices {
-                    let line = &self.lines[idx];
-                    let dist = distance_point_to_line(point, line.start, line.end);
-                    if dist <= MAX_DISTANCE_METERS && (best.is_none() || dist <= best.unwrap().1) {
-                        best = Some((line.index, dist));
-                    }
-                }
-            }
-        }
-        best
+        self.index.nearest(point, cutoff_m)
+    }
+    /// Up to `k` closest lines from the grid index, closest first.
+    fn correlate_k(
+        &self,
+        address: &AdressClean,
+        _parking_lines: &[MiljoeDataClean],
+        k: usize,
+    ) -> Vec<(usize, f64)> {
+        let (Some(x), Some(y)) = (
+            address.coordinates[0].to_f64(),
+            address.coordinates[1].to_f64(),
+        ) else {
+            return Vec::new();
+        };
+        self.index.nearest_k([x, y], k)
+    }
+    /// Every line within `radius_m` from the grid index, closest first.
+    fn within_radius(
+        &self,
+        address: &AdressClean,
+        _parking_lines: &[MiljoeDataClean],
+        radius_m: f64,
+    ) -> Vec<(usize, f64)> {
+        let (Some(x), Some(y)) = (
+            address.coordinates[0].to_f64(),
+            address.coordinates[1].to_f64(),
+        ) else {
+            return Vec::new();
+        };
+        self.index.within_radius([x, y], radius_m)
     }
     fn name(&self) -> &'static str {
         "KD-Tree Spatial"
@@ -213,9 +377,7 @@ impl CorrelationAlgo for KDTreeSpatialAlgo {
 ///
 /// Identical implementation to [`KDTreeSpatialAlgo`] but for parking zone data.
 pub struct KDTreeParkeringAlgo {
-    grid: HashMap<(i32, i32), Vec<usize>>,
-    lines: Vec<LineSegment>,
-    cell_size: f64,
+    index: GridIndex,
 }
 impl KDTreeParkeringAlgo {
     /// Create a new KD-tree-inspired spatial index for parking zones.
@@ -234,31 +396,31 @@ impl KDTreeParkeringAlgo {
     /// let algo = KDTreeParkeringAlgo::new(&parking_lines);
     /// ```
     pub fn new(parking_lines: &[ParkeringsDataClean]) -> Self {
-        let mut grid: HashMap<(i32, i32), Vec<usize>> = HashMap::new();
-        let mut lines = Vec::new();
-        for (idx, line) in parking_lines.iter().enumerate() {
-            if let (Some(x1), Some(y1), Some(x2), Some(y2)) = (
-                line.coordinates[0][0].to_f64(),
-                line.coordinates[0][1].to_f64(),
-                line.coordinates[1][0].to_f64(),
-                line.coordinates[1][1].to_f64(),
-            ) {
-                lines.push(LineSegment {
-                    index: idx,
-                    start: [x1, y1],
-                    end: [x2, y2],
-                });
-                let cells = line_cells(x1, y1, x2, y2, CELL_SIZE);
-                for cell in cells {
-                    grid.entry(cell).or_default().push(idx);
-                }
-            }
-        }
-        Self {
-            grid,
-            lines,
-            cell_size: CELL_SIZE,
+        let mut index = GridIndex::new();
+        for line in parking_lines {
+            index.insert(&line.coordinates);
         }
+        Self { index }
+    }
+    /// Add a parking zone line without rebuilding the index.
+    ///
+    /// See [`KDTreeSpatialAlgo::insert`].
+    pub fn insert(&mut self, line: &ParkeringsDataClean) -> usize {
+        self.index.insert(&line.coordinates)
+    }
+    /// Remove a parking zone line without rebuilding the index.
+    ///
+    /// See [`KDTreeSpatialAlgo::remove`].
+    pub fn remove(&mut self, id: usize) -> bool {
+        self.index.remove(id)
+    }
+    /// Number of lines currently indexed
+    pub fn len(&self) -> usize {
+        self.index.len()
+    }
+    /// Whether the index holds no lines
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
     }
 }
 impl ParkeringCorrelationAlgo for KDTreeParkeringAlgo {
@@ -277,21 +439,7 @@ impl ParkeringCorrelationAlgo for KDTreeParkeringAlgo {
             address.coordinates[0].to_f64()?,
             address.coordinates[1].to_f64()?,
         ];
-        let cell = get_cell(point, self.cell_size);
-        let nearby_cells = get_nearby_cells(cell);
-        let mut best: Option<(usize, f64)> = None;
-        for check_cell in nearby_cells {
-            if let Some(indices) = self.grid.get(&check_cell) {
-                for &idx in indices {
-                    let line = &self.lines[idx];
-                    let dist = distance_point_to_line(point, line.start, line.end);
-                    if dist <= MAX_DISTANCE_METERS && (best.is_none() || dist <= best.unwrap().1) {
-                        best = Some((line.index, dist));
-                    }
-                }
-            }
-        }
-        best
+        self.index.nearest(point, MAX_DISTANCE_METERS)
     }
     fn name(&self) -> &'static str {
         "KD-Tree Spatial Index (Parkering)"
@@ -300,9 +448,82 @@ impl ParkeringCorrelationAlgo for KDTreeParkeringAlgo {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::correlation_algorithms::DistanceBasedAlgo;
     #[test]
     fn test_line_cells() {
         let cells = line_cells(13.0, 55.0, 13.1, 55.1, CELL_SIZE);
         assert!(!cells.is_empty());
     }
+    fn line(x: i64, y: i64) -> MiljoeDataClean {
+        MiljoeDataClean::new(
+            [
+                [Decimal::new(x, 6), Decimal::new(y, 6)],
+                [Decimal::new(x + 200, 6), Decimal::new(y, 6)],
+            ],
+            String::new(),
+            "0800-1200".to_string(),
+            1,
+        )
+    }
+    fn address_at(x: i64, y: i64) -> AdressClean {
+        AdressClean {
+            coordinates: [Decimal::new(x, 6), Decimal::new(y, 6)],
+            postnummer: None,
+            adress: "Testgatan 1".to_string(),
+            gata: "Testgatan".to_string(),
+            gatunummer: "1".to_string(),
+        }
+    }
+    #[test]
+    fn test_correlate_k_matches_brute_force() {
+        let lines = vec![
+            line(13_000_000, 55_600_200),
+            line(13_000_000, 55_600_050),
+            line(13_050_000, 55_650_000),
+            line(13_000_000, 55_600_120),
+        ];
+        let algo = KDTreeSpatialAlgo::new(&lines);
+        let address = address_at(13_000_100, 55_600_000);
+        let nearest = algo.correlate_k(&address, &lines, 2);
+        assert_eq!(
+            nearest.iter().map(|&(i, _)| i).collect::<Vec<_>>(),
+            vec![1, 3]
+        );
+        assert!(nearest[0].1 < nearest[1].1);
+        assert_eq!(
+            algo.correlate_k(&address, &lines, 10),
+            DistanceBasedAlgo.correlate_k(&address, &lines, 10)
+        );
+        assert_eq!(algo.correlate_k(&address, &lines, 10).len(), 3);
+    }
+    #[test]
+    fn test_incremental_insert_matches_rebuild() {
+        let mut lines = vec![line(13_000_000, 55_600_000)];
+        let mut algo = KDTreeSpatialAlgo::new(&lines);
+        let near_new = address_at(13_010_100, 55_610_050);
+        assert_eq!(algo.correlate(&near_new, &lines), None);
+        let new_line = line(13_010_000, 55_610_000);
+        let id = algo.insert(&new_line);
+        lines.push(new_line);
+        assert_eq!(id, 1);
+        assert_eq!(algo.len(), 2);
+        let incremental = algo.correlate(&near_new, &lines);
+        let rebuilt = KDTreeSpatialAlgo::new(&lines).correlate(&near_new, &lines);
+        assert_eq!(incremental.map(|(i, _)| i), Some(1));
+        assert_eq!(incremental, rebuilt);
+    }
+    #[test]
+    fn test_incremental_remove() {
+        let lines = vec![line(13_000_000, 55_600_000), line(13_000_000, 55_600_100)];
+        let mut algo = KDTreeSpatialAlgo::new(&lines);
+        let address = address_at(13_000_100, 55_600_010);
+        assert_eq!(algo.correlate(&address, &lines).map(|(i, _)| i), Some(0));
+        assert!(algo.remove(0));
+        assert!(!algo.remove(0));
+        assert_eq!(algo.len(), 1);
+        assert_eq!(algo.correlate(&address, &lines).map(|(i, _)| i), Some(1));
+        assert!(algo.remove(1));
+        assert!(algo.is_empty());
+        assert_eq!(algo.correlate(&address, &lines), None);
+    }
 }
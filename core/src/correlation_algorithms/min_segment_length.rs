@@ -0,0 +1,227 @@
+//! Stub-segment filtering for environmental restriction correlation.
+//!
+//! The miljö dataset contains very short segments, often only a meter or two
+//! long, left over where a street was split at a manhole or a crossing. They
+//! are poor match targets: an address next to one is usually really on the
+//! longer segment right beside it, which carries the schedule that matters.
+//!
+//! [`MinSegmentLengthAlgo`] wraps any [`CorrelationAlgo`] and skips segments
+//! shorter than a minimum length whenever a segment at least that long is
+//! within the distance cutoff. If only stubs are in range the closest stub
+//! still wins, so no address loses its match because of the filter.
+//!
+//! # Cost
+//!
+//! Two indices are built: one over the long segments and one over all of
+//! them. Queries hit the long-segment index first and only fall back to the
+//! full index when it finds nothing within the cutoff.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use amp_core::correlation_algorithms::{CorrelationAlgo, MinSegmentLengthAlgo, RTreeSpatialAlgo};
+//! # use amp_core::structs::{AdressClean, MiljoeDataClean};
+//! # let address: AdressClean = unimplemented!();
+//! # let lines: Vec<MiljoeDataClean> = vec![];
+//!
+//! let algo = MinSegmentLengthAlgo::new(RTreeSpatialAlgo::new, &lines, 5.0, 20.0);
+//! if let Some((index, distance)) = algo.correlate(&address, &lines) {
+//!     println!("Matched {:.0} m segment at {:.1} m", lines[index].length_m, distance);
+//! }
+//! ```
+use crate::correlation_algorithms::CorrelationAlgo;
+use crate::structs::{AdressClean, MiljoeDataClean};
+/// Wrapper that prefers segments of at least a minimum length.
+///
+/// Indices returned by [`correlate`](CorrelationAlgo::correlate) always refer
+/// to the full `parking_lines` slice the wrapper was built from.
+pub struct MinSegmentLengthAlgo<A> {
+    long: A,
+    all: A,
+    long_lines: Vec<MiljoeDataClean>,
+    long_indices: Vec<usize>,
+    min_segment_length_m: f64,
+    cutoff_m: f64,
+}
+impl<A: CorrelationAlgo> MinSegmentLengthAlgo<A> {
+    /// Build the wrapped algorithm over long segments and over all segments.
+    ///
+    /// # Arguments
+    ///
+    /// * `build` - Index constructor, e.g. `RTreeSpatialAlgo::new`
+    /// * `parking_lines` - All environmental restriction segments
+    /// * `min_segment_length_m` - Segments shorter than this are stubs
+    /// * `cutoff_m` - A long segment farther away than this does not displace a stub
+    pub fn new<F>(
+        build: F,
+        parking_lines: &[MiljoeDataClean],
+        min_segment_length_m: f64,
+        cutoff_m: f64,
+    ) -> Self
+    where
+        F: Fn(&[MiljoeDataClean]) -> A,
+    {
+        let (long_indices, long_lines): (Vec<_>, Vec<_>) = parking_lines
+            .iter()
+            .enumerate()
+            .filter(|(_, line)| line.length_m >= min_segment_length_m)
+            .map(|(idx, line)| (idx, line.clone()))
+            .unzip();
+        Self {
+            long: build(&long_lines),
+            all: build(parking_lines),
+            long_lines,
+            long_indices,
+            min_segment_length_m,
+            cutoff_m,
+        }
+    }
+    /// Minimum length in meters a segment needs to not count as a stub
+    pub fn min_segment_length_m(&self) -> f64 {
+        self.min_segment_length_m
+    }
+}
+impl<A: CorrelationAlgo> CorrelationAlgo for MinSegmentLengthAlgo<A> {
+    /// Closest long segment within the smaller of `cutoff_m` and the
+    /// wrapper's own cutoff, else the closest segment of any length within
+    /// `cutoff_m`.
+    fn correlate_within(
+        &self,
+        address: &AdressClean,
+        parking_lines: &[MiljoeDataClean],
+        cutoff_m: f64,
+    ) -> Option<(usize, f64)> {
+        if let Some((idx, dist)) =
+            self.long
+                .correlate_within(address, &self.long_lines, cutoff_m.min(self.cutoff_m))
+        {
+            return Some((self.long_indices[idx], dist));
+        }
+        self.all.correlate_within(address, parking_lines, cutoff_m)
+    }
+    /// Up to `k` closest lines of any length, from the wrapped algorithm.
+    fn correlate_k(
+        &self,
+        address: &AdressClean,
+        parking_lines: &[MiljoeDataClean],
+        k: usize,
+    ) -> Vec<(usize, f64)> {
+        self.all.correlate_k(address, parking_lines, k)
+    }
+    /// Every line of any length within `radius_m`, from the wrapped algorithm.
+    fn within_radius(
+        &self,
+        address: &AdressClean,
+        parking_lines: &[MiljoeDataClean],
+        radius_m: f64,
+    ) -> Vec<(usize, f64)> {
+        self.all.within_radius(address, parking_lines, radius_m)
+    }
+    fn name(&self) -> &'static str {
+        self.all.name()
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::correlation_algorithms::{
+        DistanceBasedAlgo, GridNearestAlgo, KDTreeSpatialAlgo, RTreeSpatialAlgo,
+    };
+    use rust_decimal::Decimal;
+    use std::str::FromStr;
+    fn decimal(val: &str) -> Decimal {
+        Decimal::from_str(val).unwrap()
+    }
+    /// A 1 m stub right at the address and a 30 m segment 8 m away from it
+    fn stub_and_segment() -> (AdressClean, Vec<MiljoeDataClean>) {
+        let address = AdressClean {
+            coordinates: [decimal("13.0000000"), decimal("55.6000000")],
+            postnummer: Some("21438".to_string()),
+            adress: "Testgatan 1".to_string(),
+            gata: "Testgatan".to_string(),
+            gatunummer: "1".to_string(),
+        };
+        let stub = MiljoeDataClean::new(
+            [
+                [decimal("13.0000000"), decimal("55.6000100")],
+                [decimal("13.0000158"), decimal("55.6000100")],
+            ],
+            "Stub".to_string(),
+            "0800-1200".to_string(),
+            1,
+        );
+        let segment = MiljoeDataClean::new(
+            [
+                [decimal("12.9998000"), decimal("55.5999280")],
+                [decimal("13.0002740"), decimal("55.5999280")],
+            ],
+            "Segment".to_string(),
+            "0800-1200".to_string(),
+            2,
+        );
+        (address, vec![stub, segment])
+    }
+    #[test]
+    fn test_segment_lengths_are_computed_on_import() {
+        let (_, lines) = stub_and_segment();
+        assert!(
+            (lines[0].length_m - 1.0).abs() < 0.1,
+            "{}",
+            lines[0].length_m
+        );
+        assert!(
+            (lines[1].length_m - 30.0).abs() < 0.5,
+            "{}",
+            lines[1].length_m
+        );
+    }
+    #[test]
+    fn test_stub_wins_without_filter() {
+        let (address, lines) = stub_and_segment();
+        let (idx, _) = DistanceBasedAlgo.correlate(&address, &lines).unwrap();
+        assert_eq!(lines[idx].info, "Stub");
+    }
+    #[test]
+    fn test_stub_loses_to_adjacent_long_segment() {
+        let (address, lines) = stub_and_segment();
+        let algo =
+            MinSegmentLengthAlgo::new(|_: &[MiljoeDataClean]| DistanceBasedAlgo, &lines, 5.0, 20.0);
+        let (idx, dist) = algo.correlate(&address, &lines).unwrap();
+        assert_eq!(lines[idx].info, "Segment");
+        assert!((dist - 8.0).abs() < 0.5, "{dist}");
+        let rtree = MinSegmentLengthAlgo::new(RTreeSpatialAlgo::new, &lines, 5.0, 20.0);
+        assert_eq!(
+            rtree.correlate(&address, &lines).map(|(idx, _)| idx),
+            Some(1)
+        );
+        let kdtree = MinSegmentLengthAlgo::new(KDTreeSpatialAlgo::new, &lines, 5.0, 20.0);
+        assert_eq!(
+            kdtree.correlate(&address, &lines).map(|(idx, _)| idx),
+            Some(1)
+        );
+        let grid = MinSegmentLengthAlgo::new(GridNearestAlgo::new, &lines, 5.0, 20.0);
+        assert_eq!(
+            grid.correlate(&address, &lines).map(|(idx, _)| idx),
+            Some(1)
+        );
+    }
+    #[test]
+    fn test_stub_kept_when_long_segment_beyond_cutoff() {
+        let (address, lines) = stub_and_segment();
+        let algo =
+            MinSegmentLengthAlgo::new(|_: &[MiljoeDataClean]| DistanceBasedAlgo, &lines, 5.0, 5.0);
+        let (idx, _) = algo.correlate(&address, &lines).unwrap();
+        assert_eq!(lines[idx].info, "Stub");
+        let only_stub = &lines[..1];
+        let algo = MinSegmentLengthAlgo::new(
+            |_: &[MiljoeDataClean]| DistanceBasedAlgo,
+            only_stub,
+            5.0,
+            20.0,
+        );
+        assert_eq!(
+            algo.correlate(&address, only_stub).map(|(idx, _)| idx),
+            Some(0)
+        );
+    }
+}
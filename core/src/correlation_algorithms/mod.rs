@@ -11,8 +11,9 @@
 //! |-----------|----------------|-----------------|--------|----------|
 //! | [`DistanceBasedAlgo`] | None (brute-force) | O(n) | Low | Small datasets (<1000 lines) |
 //! | [`GridNearestAlgo`] | Spatial grid | O(1) avg | Medium | Uniformly distributed data |
-//! | [`KDTreeSpatialAlgo`] | KD-tree | O(log n) | Medium | Point queries, static data |
+//! | [`KDTreeSpatialAlgo`] | KD-tree | O(log n) | Medium | Point queries, incremental updates |
 //! | [`RTreeSpatialAlgo`] | R-tree | O(log n) | Medium-High | General purpose, best overall |
+//! | [`BallTreeSpatialAlgo`] | Ball tree | O(log n) | Medium | Clustered data |
 //! | [`OverlappingChunksAlgo`] | Chunked grid | O(1) avg | High | Very large datasets |
 //! | [`RaycastingAlgo`] | None | O(n) | Low | Debugging/verification |
 //!
@@ -59,6 +60,71 @@
 //! }
 //! ```
 //!
+//! ## Adaptive Cutoff
+//!
+//! A single cutoff over-matches downtown, where the nearest segment within
+//! 20 m may be on the next street, and under-matches in sparse suburbs.
+//! [`adaptive_cutoff`] derives a per-address cutoff from the distance to a
+//! few nearby segments, found with the algorithm's own index; pass it as the
+//! cutoff to [`correlate_usable`].
+//!
+//! ## Degraded Mode
+//!
+//! [`correlate_with_fallback`] builds an indexed algorithm and, if the build or
+//! a query panics (e.g. on corrupt coordinates), logs a warning and redoes the
+//! batch with [`DistanceBasedAlgo`]. The returned [`CorrelationPath`] tells the
+//! caller which path produced the results. Allocation failures abort the
+//! process and cannot be recovered this way.
+//!
+//! ```no_run
+//! use amp_core::correlation_algorithms::{CorrelationPath, RTreeSpatialAlgo, correlate_with_fallback};
+//! # use amp_core::structs::{AdressClean, MiljoeDataClean};
+//! # let addresses: Vec<AdressClean> = vec![];
+//! # let parking_lines: Vec<MiljoeDataClean> = vec![];
+//!
+//! let (results, path) = correlate_with_fallback(RTreeSpatialAlgo::new, &addresses, &parking_lines);
+//! if path == CorrelationPath::Fallback {
+//!     println!("Index unavailable, used brute force for {} addresses", results.len());
+//! }
+//! ```
+//!
+//! ## Skipping Stub Segments
+//!
+//! Every [`MiljoeDataClean`] carries its length in meters. Wrapping an
+//! algorithm in [`MinSegmentLengthAlgo`] makes segments shorter than a
+//! minimum lose to any longer segment within the cutoff:
+//!
+//! ```no_run
+//! use amp_core::correlation_algorithms::{MinSegmentLengthAlgo, RTreeSpatialAlgo};
+//! # use amp_core::structs::MiljoeDataClean;
+//! # let parking_lines: Vec<MiljoeDataClean> = vec![];
+//!
+//! let algo = MinSegmentLengthAlgo::new(RTreeSpatialAlgo::new, &parking_lines, 5.0, 20.0);
+//! ```
+//!
+//! ## Unusable Time Data
+//!
+//! A few miljö segments have a `tid` or `dag` that can't be scheduled.
+//! [`correlate_usable`] skips past such a segment to the next closest one
+//! within the cutoff and reports whether it did, so callers can count how
+//! often data quality forced a fallback:
+//!
+//! ```no_run
+//! use amp_core::correlation_algorithms::{RTreeSpatialAlgo, correlate_usable};
+//! # use amp_core::structs::{AdressClean, MiljoeDataClean};
+//! # let addresses: Vec<AdressClean> = vec![];
+//! # let parking_lines: Vec<MiljoeDataClean> = vec![];
+//!
+//! let algo = RTreeSpatialAlgo::new(&parking_lines);
+//! let fallbacks = addresses
+//!     .iter()
+//!     .filter_map(|address| correlate_usable(&algo, address, &parking_lines, 20.0))
+//!     .filter(|found| found.fell_back)
+//!     .count();
+//! ```
+//!
+//! [`MiljoeDataClean`]: crate::structs::MiljoeDataClean
+//!
 //! # Coordinate System
 //!
 //! All algorithms work with WGS84 coordinates (EPSG:4326):
@@ -67,21 +133,31 @@
 //! - Latitude: north-south position (-90 to 90)
 //! - Distances calculated using Haversine formula (spherical Earth approximation)
 //!
+//! [`RTreeSpatialAlgo::with_space`] and [`RTreeSpatialParkeringAlgo::with_space`]
+//! can instead index in projected SWEREF 99 TM meters, where the planar grid
+//! and Euclidean distances are exact by construction. Inputs stay WGS84 either
+//! way; see [`crate::crs`] for when to prefer which.
+//!
 //! # Performance Recommendations
 //!
 //! For the Malmö parking dataset (~20,000 addresses, ~2,000 parking lines):
 //! - **Recommended**: [`RTreeSpatialAlgo`] - Best balance of speed and accuracy
 //! - **Fastest**: [`GridNearestAlgo`] - 2-3x faster than R-tree, slight accuracy tradeoff
 //! - **Most Accurate**: [`OverlappingChunksAlgo`] - Handles edge cases better
+//! - **Clustered Data**: [`BallTreeSpatialAlgo`] - Adapts to dense and sparse areas
 //! - **Debugging**: [`RaycastingAlgo`] - Visual verification of point-line relationships
+pub mod balltree_spatial;
 pub mod common;
 pub mod distance_based;
 pub mod grid_nearest;
 pub mod kdtree_spatial;
+pub mod min_segment_length;
 pub mod overlapping_chunks;
 pub mod raycasting;
 pub mod rtree_spatial;
 use crate::structs::{AdressClean, MiljoeDataClean, ParkeringsDataClean};
+use rust_decimal::prelude::ToPrimitive;
+use std::panic::{AssertUnwindSafe, catch_unwind};
 /// Trait for environmental parking correlation algorithms (miljödata).
 ///
 /// All algorithms must implement this trait to be compatible with the
@@ -93,14 +169,18 @@ use crate::structs::{AdressClean, MiljoeDataClean, ParkeringsDataClean};
 ///   store an index (like [`DistanceBasedAlgo`])
 /// - Algorithms with prebuilt indices (like [`RTreeSpatialAlgo`]) ignore
 ///   this parameter
-/// - Return `None` if no parking line is within [`MAX_DISTANCE_METERS`]
+/// - Implement [`correlate_within`](CorrelationAlgo::correlate_within) and
+///   prune by its `cutoff_m`; [`correlate`](CorrelationAlgo::correlate)
+///   delegates to it with [`MAX_DISTANCE_METERS`]
+/// - Return `None` if no parking line is within the cutoff
 ///
 /// [`MAX_DISTANCE_METERS`]: common::MAX_DISTANCE_METERS
 pub trait CorrelationAlgo {
     /// Correlate an address with environmental parking restriction lines.
     ///
     /// Finds the closest parking line to the given address within
-    /// [`MAX_DISTANCE_METERS`] (50 meters).
+    /// [`MAX_DISTANCE_METERS`] (50 meters). Same as
+    /// [`correlate_within`](Self::correlate_within) with that cutoff.
     ///
     /// # Arguments
     ///
@@ -119,7 +199,119 @@ pub trait CorrelationAlgo {
         &self,
         address: &AdressClean,
         parking_lines: &[MiljoeDataClean],
+    ) -> Option<(usize, f64)> {
+        self.correlate_within(address, parking_lines, common::MAX_DISTANCE_METERS)
+    }
+    /// Closest parking line within `cutoff_m` meters of `address`.
+    ///
+    /// The cutoff is used for the algorithm's own pruning, not applied to
+    /// the result afterwards, so a cutoff above [`MAX_DISTANCE_METERS`]
+    /// finds lines that [`correlate`](Self::correlate) never sees. Grid
+    /// indices search the cells covering `cutoff_m` once it outgrows their
+    /// 3×3 neighborhood.
+    ///
+    /// # Returns
+    ///
+    /// `Some((index, distance))` as for [`correlate`](Self::correlate), or
+    /// `None` if no line is within `cutoff_m`.
+    ///
+    /// [`MAX_DISTANCE_METERS`]: common::MAX_DISTANCE_METERS
+    fn correlate_within(
+        &self,
+        address: &AdressClean,
+        parking_lines: &[MiljoeDataClean],
+        cutoff_m: f64,
     ) -> Option<(usize, f64)>;
+    /// Find up to `k` lines within [`MAX_DISTANCE_METERS`], closest first.
+    ///
+    /// Useful where the closest line alone is not enough: an address on a
+    /// corner may sit between two segments cleaned on different days, and
+    /// [`correlate_usable`] looks past lines with unusable time data.
+    ///
+    /// The default implementation measures every line in `parking_lines`,
+    /// which is exact for any algorithm but O(n) per query.
+    /// [`RTreeSpatialAlgo`] and [`KDTreeSpatialAlgo`] override it with a
+    /// lookup in their grid index.
+    ///
+    /// # Returns
+    ///
+    /// `(index, distance)` pairs sorted by distance (ties by index) and
+    /// truncated to `k`, empty if no line is within [`MAX_DISTANCE_METERS`].
+    ///
+    /// [`MAX_DISTANCE_METERS`]: common::MAX_DISTANCE_METERS
+    fn correlate_k(
+        &self,
+        address: &AdressClean,
+        parking_lines: &[MiljoeDataClean],
+        k: usize,
+    ) -> Vec<(usize, f64)> {
+        let (Some(x), Some(y)) = (
+            address.coordinates[0].to_f64(),
+            address.coordinates[1].to_f64(),
+        ) else {
+            return Vec::new();
+        };
+        let candidates = parking_lines.iter().enumerate().filter_map(|(idx, line)| {
+            let line_start = [
+                line.coordinates[0][0].to_f64()?,
+                line.coordinates[0][1].to_f64()?,
+            ];
+            let line_end = [
+                line.coordinates[1][0].to_f64()?,
+                line.coordinates[1][1].to_f64()?,
+            ];
+            Some((
+                idx,
+                common::distance_point_to_line([x, y], line_start, line_end),
+            ))
+        });
+        common::nearest_k(candidates, k)
+    }
+    /// Every line within `radius_m` meters of `address`, closest first.
+    ///
+    /// Unlike [`correlate_k`](Self::correlate_k) this is not capped at
+    /// [`MAX_DISTANCE_METERS`], which makes it the tool for debugging a
+    /// missing match: list everything around the address and see why the
+    /// expected segment lost or was out of range.
+    ///
+    /// The default implementation measures every line in `parking_lines`.
+    /// [`RTreeSpatialAlgo`] and [`KDTreeSpatialAlgo`] override it with a
+    /// lookup of the grid cells covering the radius.
+    ///
+    /// # Returns
+    ///
+    /// `(index, distance)` pairs sorted by distance (ties by index), empty if
+    /// the address has no usable coordinates.
+    ///
+    /// [`MAX_DISTANCE_METERS`]: common::MAX_DISTANCE_METERS
+    fn within_radius(
+        &self,
+        address: &AdressClean,
+        parking_lines: &[MiljoeDataClean],
+        radius_m: f64,
+    ) -> Vec<(usize, f64)> {
+        let (Some(x), Some(y)) = (
+            address.coordinates[0].to_f64(),
+            address.coordinates[1].to_f64(),
+        ) else {
+            return Vec::new();
+        };
+        let candidates = parking_lines.iter().enumerate().filter_map(|(idx, line)| {
+            let line_start = [
+                line.coordinates[0][0].to_f64()?,
+                line.coordinates[0][1].to_f64()?,
+            ];
+            let line_end = [
+                line.coordinates[1][0].to_f64()?,
+                line.coordinates[1][1].to_f64()?,
+            ];
+            Some((
+                idx,
+                common::distance_point_to_line([x, y], line_start, line_end),
+            ))
+        });
+        common::in_range(candidates, radius_m)
+    }
     /// Get the name of this algorithm for display and logging.
     ///
     /// Used in benchmark reports and debug output.
@@ -157,18 +349,545 @@ pub trait ParkeringCorrelationAlgo: Send + Sync {
         address: &AdressClean,
         parking_lines: &[ParkeringsDataClean],
     ) -> Option<(usize, f64)>;
+    /// Like [`correlate`](Self::correlate), but only lines whose
+    /// `typ_av_parkering` is in `allowed` are considered.
+    ///
+    /// Lets a user ask for e.g. on-street parking only, so a garage entrance
+    /// closer to the address doesn't win. An empty `allowed` means every type
+    /// and is the same as [`correlate`](Self::correlate); otherwise the
+    /// allowed lines are measured one by one, which is exact for any
+    /// algorithm but O(n) per query.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use amp_core::correlation_algorithms::{ParkeringCorrelationAlgo, RTreeSpatialParkeringAlgo};
+    /// # use amp_core::structs::{AdressClean, ParkeringsDataClean};
+    /// # let address: AdressClean = unimplemented!();
+    /// # let parking_lines: Vec<ParkeringsDataClean> = vec![];
+    ///
+    /// let algo = RTreeSpatialParkeringAlgo::new(&parking_lines);
+    /// let on_street = algo.correlate_filtered(&address, &parking_lines, &["Längsgående 6"]);
+    /// ```
+    fn correlate_filtered(
+        &self,
+        address: &AdressClean,
+        parking_lines: &[ParkeringsDataClean],
+        allowed: &[&str],
+    ) -> Option<(usize, f64)> {
+        if allowed.is_empty() {
+            return self.correlate(address, parking_lines);
+        }
+        let point = [
+            address.coordinates[0].to_f64()?,
+            address.coordinates[1].to_f64()?,
+        ];
+        common::closest(
+            parking_lines
+                .iter()
+                .enumerate()
+                .filter(|(_, line)| allowed.contains(&line.typ_av_parkering.as_str()))
+                .filter_map(|(idx, line)| {
+                    let dist = common::distance_point_to_polyline(point, &line.coordinates)?;
+                    (dist <= common::MAX_DISTANCE_METERS).then_some((idx, dist))
+                }),
+        )
+    }
     /// Get the name of this algorithm for display and logging.
     fn name(&self) -> &'static str;
 }
+/// How many candidates [`correlate_usable`] looks at past the closest line.
+pub const USABLE_TID_CANDIDATES: usize = 5;
+/// Match returned by [`correlate_usable`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct UsableMatch {
+    /// Position in `parking_lines`
+    pub index: usize,
+    /// Distance in meters
+    pub distance: f64,
+    /// `true` if the closest line had no usable time data and a farther one
+    /// was picked instead
+    pub fell_back: bool,
+}
+/// Correlate an address, skipping lines whose `tid`/`dag` cannot be scheduled.
+///
+/// Some miljö segments have a malformed `tid` or an out-of-range `dag`, so
+/// the address would end up with a restriction the app can't turn into a
+/// time and marks as invalid. When the closest line is such a segment, the
+/// next closest usable one within `cutoff` (among the
+/// [`USABLE_TID_CANDIDATES`] nearest, via
+/// [`within_radius`](CorrelationAlgo::within_radius)) is returned instead.
+/// If there is none, the closest line is kept.
+///
+/// # Arguments
+///
+/// * `algo` - Correlation algorithm
+/// * `address` - Address point with coordinates
+/// * `parking_lines` - Environmental restriction segments
+/// * `cutoff` - Maximum distance in meters
+///
+/// # Returns
+///
+/// `None` if no line is within `cutoff`, or if the address has no location
+/// (see [`AdressClean::has_location`]).
+pub fn correlate_usable<A: CorrelationAlgo + ?Sized>(
+    algo: &A,
+    address: &AdressClean,
+    parking_lines: &[MiljoeDataClean],
+    cutoff: f64,
+) -> Option<UsableMatch> {
+    if !address.has_location() {
+        return None;
+    }
+    let (index, distance) = algo.correlate_within(address, parking_lines, cutoff)?;
+    let closest = UsableMatch {
+        index,
+        distance,
+        fell_back: false,
+    };
+    if parking_lines[index].has_usable_time() {
+        return Some(closest);
+    }
+    let fallback = algo
+        .within_radius(address, parking_lines, cutoff)
+        .into_iter()
+        .take(USABLE_TID_CANDIDATES + 1)
+        .find(|&(idx, _)| idx != index && parking_lines[idx].has_usable_time());
+    match fallback {
+        Some((index, distance)) => Some(UsableMatch {
+            index,
+            distance,
+            fell_back: true,
+        }),
+        None => Some(closest),
+    }
+}
+/// How many nearby segments [`adaptive_cutoff`] samples to estimate density.
+pub const ADAPTIVE_CUTOFF_SAMPLES: usize = 5;
+/// Distance cutoff for one address, scaled by how dense the segments around it are.
+///
+/// Density is estimated from the distance `d` to the
+/// [`ADAPTIVE_CUTOFF_SAMPLES`]-th nearest segment. The cutoff is the
+/// geometric mean `sqrt(base * d)`, clamped to `min..=max`: downtown, where
+/// several segments lie within a few meters, it tightens below `base`; in
+/// sparse areas it loosens.
+///
+/// Any `d` beyond `max² / base` gives `max`, so only that radius is searched,
+/// with [`CorrelationAlgo::within_radius`] on `algo`. For the indexed
+/// algorithms that is a lookup of a few grid cells rather than a scan of
+/// every segment. With fewer than [`ADAPTIVE_CUTOFF_SAMPLES`] segments in
+/// that radius the address counts as sparse and gets `max`; without any
+/// segments at all, or without a usable `base`, `base` is returned (clamped).
+///
+/// # Arguments
+///
+/// * `algo` - Algorithm built over `parking_lines`, used for the radius search
+/// * `address` - Address point with coordinates
+/// * `parking_lines` - Environmental restriction segments
+/// * `base` - Cutoff in meters where the sampled segment is `base` away
+/// * `min` - Lower bound in meters
+/// * `max` - Upper bound in meters
+///
+/// # Panics
+///
+/// If `min > max`.
+pub fn adaptive_cutoff<A: CorrelationAlgo + ?Sized>(
+    algo: &A,
+    address: &AdressClean,
+    parking_lines: &[MiljoeDataClean],
+    base: f64,
+    min: f64,
+    max: f64,
+) -> f64 {
+    if parking_lines.is_empty() || !address.has_location() || base <= 0.0 {
+        return base.clamp(min, max);
+    }
+    let reach = max * max / base;
+    let nearby = algo.within_radius(address, parking_lines, reach);
+    match nearby.get(ADAPTIVE_CUTOFF_SAMPLES - 1) {
+        Some(&(_, sampled)) => (base * sampled).sqrt().clamp(min, max),
+        None => max,
+    }
+}
+/// Which code path produced the results of [`correlate_with_fallback`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CorrelationPath {
+    /// The requested indexed algorithm ran to completion
+    Indexed,
+    /// The index failed and [`DistanceBasedAlgo`] was used instead
+    Fallback,
+}
+/// Correlate addresses with an indexed algorithm, falling back to brute force.
+///
+/// Calls `build` to construct the index and correlates every address with
+/// it. If building or any query panics, the partial results are discarded, a
+/// warning is logged and the whole batch is redone with [`DistanceBasedAlgo`].
+///
+/// # Arguments
+///
+/// * `build` - Index constructor, e.g. `RTreeSpatialAlgo::new`
+/// * `addresses` - Addresses to correlate
+/// * `parking_lines` - Environmental restriction segments
+///
+/// # Returns
+///
+/// One result per address (same order as `addresses`) and the path used.
+/// Addresses without a location are never matched.
+pub fn correlate_with_fallback<A, F>(
+    build: F,
+    addresses: &[AdressClean],
+    parking_lines: &[MiljoeDataClean],
+) -> (Vec<Option<(usize, f64)>>, CorrelationPath)
+where
+    A: CorrelationAlgo,
+    F: FnOnce(&[MiljoeDataClean]) -> A,
+{
+    let indexed = catch_unwind(AssertUnwindSafe(|| {
+        let algo = build(parking_lines);
+        addresses
+            .iter()
+            .map(|address| correlate_located(&algo, address, parking_lines))
+            .collect::<Vec<_>>()
+    }));
+    match indexed {
+        Ok(results) => (results, CorrelationPath::Indexed),
+        Err(payload) => {
+            eprintln!(
+                "[Correlation] Spatial index failed ({}), falling back to {}",
+                panic_message(&payload),
+                DistanceBasedAlgo.name(),
+            );
+            let results = addresses
+                .iter()
+                .map(|address| correlate_located(&DistanceBasedAlgo, address, parking_lines))
+                .collect();
+            (results, CorrelationPath::Fallback)
+        }
+    }
+}
+/// [`CorrelationAlgo::correlate`], or `None` for an address without a location
+fn correlate_located<A: CorrelationAlgo>(
+    algo: &A,
+    address: &AdressClean,
+    parking_lines: &[MiljoeDataClean],
+) -> Option<(usize, f64)> {
+    address
+        .has_location()
+        .then(|| algo.correlate(address, parking_lines))
+        .flatten()
+}
+/// Extract a readable message from a caught panic payload
+fn panic_message(payload: &Box<dyn std::any::Any + Send>) -> &str {
+    payload
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("unknown panic")
+}
+pub use balltree_spatial::BallTreeParkeringAlgo;
+pub use balltree_spatial::BallTreeSpatialAlgo;
 pub use distance_based::DistanceBasedAlgo;
 pub use distance_based::DistanceBasedParkeringAlgo;
 pub use grid_nearest::GridNearestAlgo;
 pub use grid_nearest::GridNearestParkeringAlgo;
 pub use kdtree_spatial::KDTreeParkeringAlgo;
 pub use kdtree_spatial::KDTreeSpatialAlgo;
+pub use min_segment_length::MinSegmentLengthAlgo;
 pub use overlapping_chunks::OverlappingChunksAlgo;
 pub use overlapping_chunks::OverlappingChunksParkeringAlgo;
 pub use raycasting::RaycastingAlgo;
 pub use raycasting::RaycastingParkeringAlgo;
 pub use rtree_spatial::RTreeSpatialAlgo;
 pub use rtree_spatial::RTreeSpatialParkeringAlgo;
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal::Decimal;
+    fn fixture() -> (Vec<AdressClean>, Vec<MiljoeDataClean>) {
+        let address = AdressClean {
+            coordinates: [Decimal::new(130087215, 7), Decimal::new(555910154, 7)],
+            postnummer: None,
+            adress: "Claesgatan 2B".to_string(),
+            gata: "Claesgatan".to_string(),
+            gatunummer: "2B".to_string(),
+        };
+        let line = MiljoeDataClean::new(
+            [
+                [Decimal::new(13008652, 6), Decimal::new(555910310, 7)],
+                [Decimal::new(13008535, 6), Decimal::new(555908300, 7)],
+            ],
+            "Städning".to_string(),
+            "1800-2200".to_string(),
+            2,
+        );
+        (vec![address], vec![line])
+    }
+    /// A segment with a broken `tid` 3 m north of the address and a
+    /// usable one 8 m south of it
+    fn broken_and_usable() -> (AdressClean, Vec<MiljoeDataClean>) {
+        let address = AdressClean {
+            coordinates: [Decimal::new(130000000, 7), Decimal::new(556000000, 7)],
+            postnummer: Some("21438".to_string()),
+            adress: "Testgatan 1".to_string(),
+            gata: "Testgatan".to_string(),
+            gatunummer: "1".to_string(),
+        };
+        let segment = |lat: i64, tid: &str| {
+            MiljoeDataClean::new(
+                [
+                    [Decimal::new(129998000, 7), Decimal::new(lat, 7)],
+                    [Decimal::new(130002000, 7), Decimal::new(lat, 7)],
+                ],
+                "Städning".to_string(),
+                tid.to_string(),
+                15,
+            )
+        };
+        let lines = vec![
+            segment(556000270, "08-12 ?"),
+            segment(555999280, "0800-1200"),
+        ];
+        (address, lines)
+    }
+    /// An address with horizontal segments at the given meters north of it
+    fn segments_north(offsets_m: &[i64]) -> (AdressClean, Vec<MiljoeDataClean>) {
+        let (address, _) = broken_and_usable();
+        // 1e-7 degrees of latitude is about 0.0111 m
+        let lines = offsets_m
+            .iter()
+            .map(|m| {
+                let lat = 556000000 + m * 90;
+                MiljoeDataClean::new(
+                    [
+                        [Decimal::new(129998000, 7), Decimal::new(lat, 7)],
+                        [Decimal::new(130002000, 7), Decimal::new(lat, 7)],
+                    ],
+                    "Städning".to_string(),
+                    "0800-1200".to_string(),
+                    15,
+                )
+            })
+            .collect();
+        (address, lines)
+    }
+    #[test]
+    fn test_adaptive_cutoff_tightens_when_dense_and_loosens_when_sparse() {
+        let (address, dense) = segments_north(&[2, -3, 4, -5, 5, -6, 40]);
+        let algo = RTreeSpatialAlgo::new(&dense);
+        let cutoff = adaptive_cutoff(&algo, &address, &dense, 20.0, 5.0, 50.0);
+        // Fifth nearest is 5 m away: sqrt(20 * 5) = 10
+        assert!((cutoff - 10.0).abs() < 0.5, "{cutoff}");
+        let (address, sparse) = segments_north(&[30, -45, 60, -80, 90, 150]);
+        let algo = RTreeSpatialAlgo::new(&sparse);
+        let cutoff = adaptive_cutoff(&algo, &address, &sparse, 20.0, 5.0, 50.0);
+        // Fifth nearest is 90 m away: sqrt(20 * 90) = 42.4
+        assert!((cutoff - 42.4).abs() < 0.5, "{cutoff}");
+    }
+    #[test]
+    fn test_adaptive_cutoff_stays_within_bounds() {
+        let (address, dense) = segments_north(&[1, -1, 1, -1, 1]);
+        let algo = RTreeSpatialAlgo::new(&dense);
+        assert_eq!(
+            adaptive_cutoff(&algo, &address, &dense, 20.0, 8.0, 50.0),
+            8.0
+        );
+        let (address, sparse) = segments_north(&[500, -600, 700]);
+        let algo = RTreeSpatialAlgo::new(&sparse);
+        assert_eq!(
+            adaptive_cutoff(&algo, &address, &sparse, 20.0, 8.0, 50.0),
+            50.0
+        );
+        assert_eq!(
+            adaptive_cutoff(&DistanceBasedAlgo, &address, &[], 20.0, 8.0, 50.0),
+            20.0
+        );
+    }
+    #[test]
+    fn test_adaptive_cutoff_indexed_matches_brute_force() {
+        let (address, lines) = segments_north(&[3, -8, 15, -22, 31, -47, 70, 95, -120]);
+        let indexed = RTreeSpatialAlgo::new(&lines);
+        for (base, min, max) in [(20.0, 5.0, 50.0), (10.0, 2.0, 30.0), (40.0, 10.0, 60.0)] {
+            assert_eq!(
+                adaptive_cutoff(&indexed, &address, &lines, base, min, max),
+                adaptive_cutoff(&DistanceBasedAlgo, &address, &lines, base, min, max),
+            );
+        }
+    }
+    #[test]
+    fn test_correlate_k_sorted_by_distance() {
+        let (address, lines) = broken_and_usable();
+        let nearest = RTreeSpatialAlgo::new(&lines).correlate_k(&address, &lines, 5);
+        assert_eq!(
+            nearest.iter().map(|&(idx, _)| idx).collect::<Vec<_>>(),
+            vec![0, 1]
+        );
+        assert!((nearest[0].1 - 3.0).abs() < 0.5, "{}", nearest[0].1);
+        assert!((nearest[1].1 - 8.0).abs() < 0.5, "{}", nearest[1].1);
+        assert_eq!(
+            DistanceBasedAlgo.correlate_k(&address, &lines, 1),
+            nearest[..1].to_vec()
+        );
+    }
+    #[test]
+    fn test_within_radius_returns_lines_in_range() {
+        let (address, lines) = segments_north(&[150, 30, 80]);
+        let algos: Vec<Box<dyn CorrelationAlgo>> = vec![
+            Box::new(DistanceBasedAlgo),
+            Box::new(RTreeSpatialAlgo::new(&lines)),
+            Box::new(RTreeSpatialAlgo::with_space(
+                &lines,
+                crate::crs::CoordinateSpace::Sweref99Tm,
+            )),
+            Box::new(KDTreeSpatialAlgo::new(&lines)),
+        ];
+        for algo in algos {
+            let found = algo.within_radius(&address, &lines, 100.0);
+            assert_eq!(
+                found.iter().map(|&(idx, _)| idx).collect::<Vec<_>>(),
+                vec![1, 2],
+                "{}",
+                algo.name()
+            );
+            assert!((found[0].1 - 30.0).abs() < 1.0, "{}", found[0].1);
+            assert!((found[1].1 - 80.0).abs() < 1.0, "{}", found[1].1);
+        }
+    }
+    #[test]
+    fn test_correlate_filtered_by_parking_type() {
+        let (address, _) = broken_and_usable();
+        // A garage entrance 5 m north and on-street parking 12 m south
+        let zone = |lat: i64, typ: &str| ParkeringsDataClean {
+            coordinates: [
+                [Decimal::new(129998000, 7), Decimal::new(lat, 7)],
+                [Decimal::new(130002000, 7), Decimal::new(lat, 7)],
+            ],
+            taxa: "Taxa C".to_string(),
+            antal_platser: 10,
+            typ_av_parkering: typ.to_string(),
+        };
+        let lines = vec![
+            zone(556000450, "Parkeringshus"),
+            zone(555998920, "Längsgående 6"),
+        ];
+        let algos: Vec<Box<dyn ParkeringCorrelationAlgo>> = vec![
+            Box::new(DistanceBasedParkeringAlgo),
+            Box::new(RTreeSpatialParkeringAlgo::new(&lines)),
+        ];
+        for algo in algos {
+            let any = algo.correlate(&address, &lines);
+            assert_eq!(any.map(|(idx, _)| idx), Some(0), "{}", algo.name());
+            assert_eq!(algo.correlate_filtered(&address, &lines, &[]), any);
+            let on_street = algo.correlate_filtered(&address, &lines, &["Längsgående 6"]);
+            assert_eq!(on_street.map(|(idx, _)| idx), Some(1), "{}", algo.name());
+            assert!(
+                algo.correlate_filtered(&address, &lines, &["Snedparkering"])
+                    .is_none()
+            );
+        }
+    }
+    #[test]
+    fn test_correlate_usable_skips_broken_tid() {
+        let (address, lines) = broken_and_usable();
+        assert!(!lines[0].has_usable_time());
+        assert!(lines[1].has_usable_time());
+        for algo in [
+            Box::new(DistanceBasedAlgo) as Box<dyn CorrelationAlgo>,
+            Box::new(RTreeSpatialAlgo::new(&lines)),
+            Box::new(KDTreeSpatialAlgo::new(&lines)),
+            Box::new(GridNearestAlgo::new(&lines)),
+        ] {
+            assert_eq!(
+                algo.correlate(&address, &lines).map(|(idx, _)| idx),
+                Some(0)
+            );
+            let found = correlate_usable(algo.as_ref(), &address, &lines, 20.0).unwrap();
+            assert_eq!(found.index, 1, "{}", algo.name());
+            assert!(found.fell_back);
+        }
+    }
+    #[test]
+    fn test_correlate_usable_keeps_closest_without_alternative() {
+        let (address, lines) = broken_and_usable();
+        // The usable segment is beyond the cutoff
+        let found = correlate_usable(&DistanceBasedAlgo, &address, &lines, 5.0).unwrap();
+        assert_eq!(found.index, 0);
+        assert!(!found.fell_back);
+        // The closest segment is usable
+        let found = correlate_usable(&DistanceBasedAlgo, &address, &lines[1..], 20.0).unwrap();
+        assert_eq!(found.index, 0);
+        assert!(!found.fell_back);
+        assert_eq!(
+            correlate_usable(&DistanceBasedAlgo, &address, &lines, 2.0),
+            None
+        );
+    }
+    #[test]
+    fn test_address_at_null_island_is_not_matched() {
+        let (mut address, _) = broken_and_usable();
+        address.coordinates = [Decimal::ZERO, Decimal::ZERO];
+        assert!(!address.has_location());
+        // A segment right next to [0, 0], which a plain query happily matches
+        let lines = vec![MiljoeDataClean::new(
+            [
+                [Decimal::new(-1, 5), Decimal::ZERO],
+                [Decimal::new(1, 5), Decimal::ZERO],
+            ],
+            "Städning".to_string(),
+            "0800-1200".to_string(),
+            1,
+        )];
+        assert!(DistanceBasedAlgo.correlate(&address, &lines).is_some());
+        assert_eq!(
+            correlate_usable(&DistanceBasedAlgo, &address, &lines, 20.0),
+            None
+        );
+        let (results, path) = correlate_with_fallback(RTreeSpatialAlgo::new, &[address], &lines);
+        assert_eq!(results, vec![None]);
+        assert_eq!(path, CorrelationPath::Indexed);
+    }
+    #[test]
+    fn test_equidistant_segments_pick_lower_index() {
+        let (addresses, mut lines) = fixture();
+        // Same geometry, so both segments are exactly as far from the address
+        lines.push(MiljoeDataClean::new(
+            lines[0].coordinates,
+            "Duplicate".to_string(),
+            "0800-1200".to_string(),
+            9,
+        ));
+        let algorithms: Vec<Box<dyn CorrelationAlgo>> = vec![
+            Box::new(DistanceBasedAlgo),
+            Box::new(RaycastingAlgo),
+            Box::new(RTreeSpatialAlgo::new(&lines)),
+            Box::new(KDTreeSpatialAlgo::new(&lines)),
+            Box::new(GridNearestAlgo::new(&lines)),
+            Box::new(OverlappingChunksAlgo::new(&lines)),
+        ];
+        for algo in algorithms {
+            let (idx, _) = algo.correlate(&addresses[0], &lines).unwrap();
+            assert_eq!(idx, 0, "{}", algo.name());
+        }
+    }
+    #[test]
+    fn test_correlate_with_fallback_uses_index() {
+        let (addresses, lines) = fixture();
+        let (results, path) = correlate_with_fallback(RTreeSpatialAlgo::new, &addresses, &lines);
+        assert_eq!(path, CorrelationPath::Indexed);
+        assert_eq!(results[0].map(|(idx, _)| idx), Some(0));
+    }
+    #[test]
+    fn test_correlate_with_fallback_recovers_from_build_panic() {
+        let (addresses, lines) = fixture();
+        let (results, path) = correlate_with_fallback(
+            |_: &[MiljoeDataClean]| -> RTreeSpatialAlgo { panic!("corrupt index") },
+            &addresses,
+            &lines,
+        );
+        assert_eq!(path, CorrelationPath::Fallback);
+        assert_eq!(
+            results,
+            vec![DistanceBasedAlgo.correlate(&addresses[0], &lines)]
+        );
+        assert!(results[0].is_some());
+    }
+}
@@ -16,8 +16,9 @@
 //! **Query Phase** (during `correlate`):
 //! 1. Find chunk containing the query point
 //! 2. Search 3×3 neighborhood of chunks (9 total)
-//! 3. Check all lines in these chunks
-//! 4. Return closest line within threshold
+//! 3. Skip lines whose bounding box, widened by [`MAX_DISTANCE_METERS`], doesn't
+//!    contain the query point
+//! 4. Measure the remaining lines and return the closest within threshold
 //!
 //! # Key Differences from Other Algorithms
 //!
@@ -39,7 +40,8 @@
 //! # Time Complexity
 //!
 //! - **Indexing**: O(n × c) where c = chunks per line bounding box (~1-4)
-//! - **Query**: O(m) where m = lines in 9-chunk neighborhood (~50-200)
+//! - **Query**: O(m) bounding box checks where m = lines in 9-chunk neighborhood
+//!   (~50-200), with an exact distance only for the few lines that pass
 //! - **Average**: O(1) with more lines per chunk than fine-grained grids
 //!
 //! # Space Complexity
@@ -94,9 +96,12 @@
 //! ```
 //!
 //! [`CHUNK_SIZE`]: CHUNK_SIZE
+//! [`MAX_DISTANCE_METERS`]: crate::correlation_algorithms::common::MAX_DISTANCE_METERS
 use crate::correlation_algorithms::common::*;
 use crate::correlation_algorithms::{CorrelationAlgo, ParkeringCorrelationAlgo};
+use crate::crs::CoordinateSpace;
 use crate::structs::{AdressClean, MiljoeDataClean, ParkeringsDataClean};
+use rust_decimal::Decimal;
 use std::collections::HashMap;
 /// Chunk size in degrees (~1.1 km at Swedish latitudes).
 ///
@@ -107,6 +112,52 @@ const CHUNK_SIZE: f64 = 0.01;
 /// Could be used to add explicit padding around chunks beyond natural
 /// bounding box overlap.
 const _OVERLAP_FACTOR: f64 = 0.2;
+/// Line bounds as `[min_x, min_y, max_x, max_y]` in degrees
+type Bbox = [f64; 4];
+/// Line indices by (chunk_x, chunk_y)
+type Chunks = HashMap<(i32, i32), Vec<usize>>;
+/// Chunk map and per-line bounding boxes, `None` for lines that couldn't be
+/// converted to f64 and so are in no chunk
+fn index_lines<'a>(
+    lines: impl Iterator<Item = &'a [[Decimal; 2]; 2]>,
+) -> (Chunks, Vec<Option<Bbox>>) {
+    let mut chunks: Chunks = HashMap::new();
+    let mut bboxes = Vec::new();
+    for (idx, coordinates) in lines.enumerate() {
+        let bbox = match (
+            coordinates[0][0].to_f64(),
+            coordinates[0][1].to_f64(),
+            coordinates[1][0].to_f64(),
+            coordinates[1][1].to_f64(),
+        ) {
+            (Some(x1), Some(y1), Some(x2), Some(y2)) => {
+                [x1.min(x2), y1.min(y2), x1.max(x2), y1.max(y2)]
+            }
+            _ => {
+                bboxes.push(None);
+                continue;
+            }
+        };
+        let chunk_min_x = (bbox[0] / CHUNK_SIZE).floor() as i32;
+        let chunk_max_x = (bbox[2] / CHUNK_SIZE).floor() as i32;
+        let chunk_min_y = (bbox[1] / CHUNK_SIZE).floor() as i32;
+        let chunk_max_y = (bbox[3] / CHUNK_SIZE).floor() as i32;
+        for cx in chunk_min_x..=chunk_max_x {
+            for cy in chunk_min_y..=chunk_max_y {
+                chunks.entry((cx, cy)).or_default().push(idx);
+            }
+        }
+        bboxes.push(Some(bbox));
+    }
+    (chunks, bboxes)
+}
+/// Whether `bbox` widened by `reach` contains `point`
+fn within_reach(bbox: &Bbox, point: [f64; 2], reach: [f64; 2]) -> bool {
+    point[0] >= bbox[0] - reach[0]
+        && point[0] <= bbox[2] + reach[0]
+        && point[1] >= bbox[1] - reach[1]
+        && point[1] <= bbox[3] + reach[1]
+}
 /// Overlapping chunks algorithm for environmental parking restrictions.
 ///
 /// Uses large chunks with bounding-box-based overlap to improve boundary
@@ -126,6 +177,8 @@ const _OVERLAP_FACTOR: f64 = 0.2;
 pub struct OverlappingChunksAlgo {
     /// Chunk HashMap mapping (chunk_x, chunk_y) to line indices
     chunks: HashMap<(i32, i32), Vec<usize>>,
+    /// Bounding box of each line, by line index
+    bboxes: Vec<Option<Bbox>>,
 }
 impl OverlappingChunksAlgo {
     /// Create a new overlapping chunks spatial index.
@@ -152,68 +205,54 @@ impl OverlappingChunksAlgo {
     /// let algo = OverlappingChunksAlgo::new(&parking_lines);
     /// ```
     pub fn new(parking_lines: &[MiljoeDataClean]) -> Self {
-        let mut chunks: HashMap<(i32, i32), Vec<usize>> = HashMap::new();
-        for (idx, line) in parking_lines.iter().enumerate() {
-            if let (Some(x1), Some(y1), Some(x2), Some(y2)) = (
-                line.coordinates[0][0].to_f64(),
-                line.coordinates[0][1].to_f64(),
-                line.coordinates[1][0].to_f64(),
-                line.coordinates[1][1].to_f64(),
-            ) {
-                let min_x = x1.min(x2);
-                let max_x = x1.max(x2);
-                let min_y = y1.min(y2);
-                let max_y = y1.max(y2);
-                let chunk_min_x = (min_x / CHUNK_SIZE).floor() as i32;
-                let chunk_max_x = (max_x / CHUNK_SIZE).floor() as i32;
-                let chunk_min_y = (min_y / CHUNK_SIZE).floor() as i32;
-                let chunk_max_y = (max_y / CHUNK_SIZE).floor() as i32;
-                for cx in chunk_min_x..=chunk_max_x {
-                    for cy in chunk_min_y..=chunk_max_y {
-                        chunks.entry((cx, cy)).or_default().push(idx);
-                    }
-                }
-            }
-        }
-        Self { chunks }
+        let (chunks, bboxes) = index_lines(parking_lines.iter().map(|line| &line.coordinates));
+        Self { chunks, bboxes }
     }
 }
 impl CorrelationAlgo for OverlappingChunksAlgo {
     /// Correlate address with parking lines using overlapping chunks.
     ///
     /// Searches 3×3 neighborhood of large chunks, providing better coverage
-    /// of addresses near chunk boundaries.
+    /// of addresses near chunk boundaries. A cutoff wider than one chunk
+    /// widens the neighborhood to match.
     ///
     /// # Arguments
     ///
     /// * `address` - Address point to correlate
     /// * `parking_lines` - Slice of parking lines (needed for coordinate access)
+    /// * `cutoff_m` - Maximum distance in meters
     ///
     /// # Returns
     ///
-    /// - `Some((index, distance))` if a line is within 50 meters
+    /// - `Some((index, distance))` if a line is within `cutoff_m` meters
     /// - `None` if no match found or coordinate conversion fails
     ///
     /// # Note
     ///
     /// Coordinates are converted on-the-fly during queries (no caching).
-    fn correlate(
+    fn correlate_within(
         &self,
         address: &AdressClean,
         parking_lines: &[MiljoeDataClean],
+        cutoff_m: f64,
     ) -> Option<(usize, f64)> {
         let point = [
             address.coordinates[0].to_f64()?,
             address.coordinates[1].to_f64()?,
         ];
+        let reach = CoordinateSpace::Wgs84.reach(point, cutoff_m);
+        let span = (reach[0].max(reach[1]) / CHUNK_SIZE).ceil().max(1.0) as i32;
         let chunk_x = (point[0] / CHUNK_SIZE).floor() as i32;
         let chunk_y = (point[1] / CHUNK_SIZE).floor() as i32;
         let mut best: Option<(usize, f64)> = None;
-        for dx in -1..=1 {
-            for dy in -1..=1 {
+        for dx in -span..=span {
+            for dy in -span..=span {
                 let check_chunk = (chunk_x + dx, chunk_y + dy);
                 if let Some(indices) = self.chunks.get(&check_chunk) {
                     for &idx in indices {
+                        if !self.bboxes[idx].is_some_and(|bbox| within_reach(&bbox, point, reach)) {
+                            continue;
+                        }
                         let line = &parking_lines[idx];
                         let start = [
                             line.coordinates[0][0].to_f64()?,
@@ -224,8 +263,7 @@ impl CorrelationAlgo for OverlappingChunksAlgo {
                             line.coordinates[1][1].to_f64()?,
                         ];
                         let dist = distance_point_to_line(point, start, end);
-                        if dist <= MAX_DISTANCE_METERS && (best.is_none() || dist < best.unwrap().1)
-                        {
+                        if dist <= cutoff_m && is_closer((idx, dist), best) {
                             best = Some((idx, dist));
                         }
                     }
@@ -243,6 +281,7 @@ impl CorrelationAlgo for OverlappingChunksAlgo {
 /// Identical logic to [`OverlappingChunksAlgo`] but operates on parking zone data.
 pub struct OverlappingChunksParkeringAlgo {
     chunks: HashMap<(i32, i32), Vec<usize>>,
+    bboxes: Vec<Option<Bbox>>,
 }
 impl OverlappingChunksParkeringAlgo {
     /// Create a new overlapping chunks spatial index for parking zones.
@@ -261,30 +300,8 @@ impl OverlappingChunksParkeringAlgo {
     /// let algo = OverlappingChunksParkeringAlgo::new(&parking_lines);
     /// ```
     pub fn new(parking_lines: &[ParkeringsDataClean]) -> Self {
-        let mut chunks: HashMap<(i32, i32), Vec<usize>> = HashMap::new();
-        for (idx, line) in parking_lines.iter().enumerate() {
-            if let (Some(x1), Some(y1), Some(x2), Some(y2)) = (
-                line.coordinates[0][0].to_f64(),
-                line.coordinates[0][1].to_f64(),
-                line.coordinates[1][0].to_f64(),
-                line.coordinates[1][1].to_f64(),
-            ) {
-                let min_x = x1.min(x2);
-                let max_x = x1.max(x2);
-                let min_y = y1.min(y2);
-                let max_y = y1.max(y2);
-                let chunk_min_x = (min_x / CHUNK_SIZE).floor() as i32;
-                let chunk_max_x = (max_x / CHUNK_SIZE).floor() as i32;
-                let chunk_min_y = (min_y / CHUNK_SIZE).floor() as i32;
-                let chunk_max_y = (max_y / CHUNK_SIZE).floor() as i32;
-                for cx in chunk_min_x..=chunk_max_x {
-                    for cy in chunk_min_y..=chunk_max_y {
-                        chunks.entry((cx, cy)).or_default().push(idx);
-                    }
-                }
-            }
-        }
-        Self { chunks }
+        let (chunks, bboxes) = index_lines(parking_lines.iter().map(|line| &line.coordinates));
+        Self { chunks, bboxes }
     }
 }
 impl ParkeringCorrelationAlgo for OverlappingChunksParkeringAlgo {
@@ -303,6 +320,7 @@ impl ParkeringCorrelationAlgo for OverlappingChunksParkeringAlgo {
             address.coordinates[0].to_f64()?,
             address.coordinates[1].to_f64()?,
         ];
+        let reach = CoordinateSpace::Wgs84.reach(point, MAX_DISTANCE_METERS);
         let chunk_x = (point[0] / CHUNK_SIZE).floor() as i32;
         let chunk_y = (point[1] / CHUNK_SIZE).floor() as i32;
         let mut best: Option<(usize, f64)> = None;
@@ -311,6 +329,9 @@ impl ParkeringCorrelationAlgo for OverlappingChunksParkeringAlgo {
                 let check_chunk = (chunk_x + dx, chunk_y + dy);
                 if let Some(indices) = self.chunks.get(&check_chunk) {
                     for &idx in indices {
+                        if !self.bboxes[idx].is_some_and(|bbox| within_reach(&bbox, point, reach)) {
+                            continue;
+                        }
                         let line = &parking_lines[idx];
                         let start = [
                             line.coordinates[0][0].to_f64()?,
@@ -321,8 +342,7 @@ impl ParkeringCorrelationAlgo for OverlappingChunksParkeringAlgo {
                             line.coordinates[1][1].to_f64()?,
                         ];
                         let dist = distance_point_to_line(point, start, end);
-                        if dist <= MAX_DISTANCE_METERS && (best.is_none() || dist < best.unwrap().1)
-                        {
+                        if dist <= MAX_DISTANCE_METERS && is_closer((idx, dist), best) {
                             best = Some((idx, dist));
                         }
                     }
@@ -345,6 +365,52 @@ mod tests {
         assert!(chunk_x > 0);
     }
     #[test]
+    fn test_bbox_prefilter_matches_distance_based() {
+        use crate::correlation_algorithms::DistanceBasedAlgo;
+        // Deterministic pseudo-random fixture around Malmö, with long
+        // diagonal lines whose boxes cover many points they are far from
+        let mut seed: u64 = 0x5eed;
+        let mut next = move || {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (seed >> 33) as f64 / (1u64 << 31) as f64
+        };
+        let dec = |v: f64| Decimal::from_f64_retain(v).unwrap();
+        let lines: Vec<MiljoeDataClean> = (0..200)
+            .map(|i| {
+                let (x, y) = (13.0 + next() * 0.02, 55.6 + next() * 0.02);
+                let length = if i % 10 == 0 { 0.01 } else { 0.0008 };
+                let (dx, dy) = ((next() - 0.5) * length, (next() - 0.5) * length);
+                MiljoeDataClean::new(
+                    [[dec(x), dec(y)], [dec(x + dx), dec(y + dy)]],
+                    "Test".to_string(),
+                    "0800-1200".to_string(),
+                    1,
+                )
+            })
+            .collect();
+        let addresses: Vec<AdressClean> = (0..500)
+            .map(|_| AdressClean {
+                coordinates: [dec(13.0 + next() * 0.02), dec(55.6 + next() * 0.02)],
+                ..AdressClean::default()
+            })
+            .collect();
+        let chunks = OverlappingChunksAlgo::new(&lines);
+        let matched = addresses
+            .iter()
+            .filter(|address| {
+                let expected = DistanceBasedAlgo.correlate(address, &lines);
+                assert_eq!(
+                    chunks.correlate(address, &lines).map(|(idx, _)| idx),
+                    expected.map(|(idx, _)| idx),
+                );
+                expected.is_some()
+            })
+            .count();
+        assert!(matched > 0);
+    }
+    #[test]
     fn test_overlap_coverage() {
         let overlap_size = CHUNK_SIZE * _OVERLAP_FACTOR;
         assert!(overlap_size > 0.0);
@@ -8,11 +8,10 @@
 //! # Algorithm
 //!
 //! For each parking line with potentially multiple segments:
-//! 1. Iterate through all consecutive segment pairs using `windows(2)`
-//! 2. Calculate perpendicular distance to each segment
-//! 3. Track minimum distance across all segments
-//! 4. Keep line if minimum distance ≤ [`MAX_DISTANCE_METERS`]
-//! 5. Return line with overall minimum distance
+//! 1. Measure the distance to the nearest segment with
+//!    [`distance_point_to_polyline`]
+//! 2. Keep line if that distance ≤ [`MAX_DISTANCE_METERS`]
+//! 3. Return line with overall minimum distance
 //!
 //! # Raycasting vs. Distance Calculation
 //!
@@ -103,6 +102,7 @@
 //! ```
 //!
 //! [`MAX_DISTANCE_METERS`]: crate::correlation_algorithms::common::MAX_DISTANCE_METERS
+//! [`distance_point_to_polyline`]: crate::correlation_algorithms::common::distance_point_to_polyline
 use crate::correlation_algorithms::common::*;
 use crate::correlation_algorithms::{CorrelationAlgo, ParkeringCorrelationAlgo};
 use crate::structs::{AdressClean, MiljoeDataClean, ParkeringsDataClean};
@@ -133,39 +133,31 @@ impl CorrelationAlgo for RaycastingAlgo {
     ///
     /// * `address` - Address point to correlate
     /// * `parking_lines` - Slice of parking lines (may have multiple segments)
+    /// * `cutoff_m` - Maximum distance in meters
     ///
     /// # Returns
     ///
-    /// - `Some((index, distance))` if a line segment is within 50 meters
+    /// - `Some((index, distance))` if a line segment is within `cutoff_m` meters
     /// - `None` if no match found or coordinate conversion fails
     ///
     /// # Performance
     ///
     /// O(n × s) where n = number of lines, s = average segments per line.
     /// For typical data with 1-2 segments per line, this is effectively O(n).
-    fn correlate(
+    fn correlate_within(
         &self,
         address: &AdressClean,
         parking_lines: &[MiljoeDataClean],
+        cutoff_m: f64,
     ) -> Option<(usize, f64)> {
         let point = [
             address.coordinates[0].to_f64()?,
             address.coordinates[1].to_f64()?,
         ];
-        parking_lines
-            .iter()
-            .enumerate()
-            .filter_map(|(idx, line)| {
-                let mut min_dist = f64::MAX;
-                for segment in line.coordinates.windows(2) {
-                    let start = [segment[0][0].to_f64()?, segment[0][1].to_f64()?];
-                    let end = [segment[1][0].to_f64()?, segment[1][1].to_f64()?];
-                    let dist = distance_point_to_line(point, start, end);
-                    min_dist = min_dist.min(dist);
-                }
-                (min_dist <= MAX_DISTANCE_METERS).then_some((idx, min_dist))
-            })
-            .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap())
+        closest(parking_lines.iter().enumerate().filter_map(|(idx, line)| {
+            let dist = distance_point_to_polyline(point, &line.coordinates)?;
+            (dist <= cutoff_m).then_some((idx, dist))
+        }))
     }
     fn name(&self) -> &'static str {
         "Raycasting"
@@ -191,20 +183,10 @@ impl ParkeringCorrelationAlgo for RaycastingParkeringAlgo {
             address.coordinates[0].to_f64()?,
             address.coordinates[1].to_f64()?,
         ];
-        parking_lines
-            .iter()
-            .enumerate()
-            .filter_map(|(idx, line)| {
-                let mut min_dist = f64::MAX;
-                for segment in line.coordinates.windows(2) {
-                    let start = [segment[0][0].to_f64()?, segment[0][1].to_f64()?];
-                    let end = [segment[1][0].to_f64()?, segment[1][1].to_f64()?];
-                    let dist = distance_point_to_line(point, start, end);
-                    min_dist = min_dist.min(dist);
-                }
-                (min_dist <= MAX_DISTANCE_METERS).then_some((idx, min_dist))
-            })
-            .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap())
+        closest(parking_lines.iter().enumerate().filter_map(|(idx, line)| {
+            let dist = distance_point_to_polyline(point, &line.coordinates)?;
+            (dist <= MAX_DISTANCE_METERS).then_some((idx, dist))
+        }))
     }
     fn name(&self) -> &'static str {
         "Raycasting (Parkering)"
@@ -86,11 +86,21 @@
 //! }
 //! ```
 //!
+//! # Projected Coordinates
+//!
+//! [`RTreeSpatialAlgo::with_space`] builds the index in
+//! [`CoordinateSpace::Sweref99Tm`] instead: line endpoints are projected once
+//! during indexing, cells are 55 m squares, and each query projects only the
+//! address point before measuring plain Euclidean distances. See
+//! [`crate::crs`] for the tradeoffs.
+//!
 //! [`MAX_DISTANCE_METERS`]: crate::correlation_algorithms::common::MAX_DISTANCE_METERS
 //! [`CELL_SIZE`]: crate::correlation_algorithms::common::CELL_SIZE
 use crate::correlation_algorithms::common::*;
 use crate::correlation_algorithms::{CorrelationAlgo, ParkeringCorrelationAlgo};
+use crate::crs::CoordinateSpace;
 use crate::structs::{AdressClean, MiljoeDataClean, ParkeringsDataClean};
+use rust_decimal::Decimal;
 use std::collections::HashMap;
 /// R-tree-inspired spatial index for environmental parking restrictions.
 ///
@@ -113,8 +123,10 @@ pub struct RTreeSpatialAlgo {
     grid: HashMap<(i32, i32), Vec<usize>>,
     /// Cached line segments with f64 coordinates for fast distance calculation
     lines: Vec<LineSegment>,
-    /// Grid cell size in degrees (default: 0.0005)
+    /// Grid cell size in `space` units (default: 0.0005 degrees)
     cell_size: f64,
+    /// Coordinate space the lines were projected into
+    space: CoordinateSpace,
 }
 /// Internal line segment representation with converted coordinates.
 ///
@@ -124,11 +136,44 @@ pub struct RTreeSpatialAlgo {
 struct LineSegment {
     /// Original index in the input parking_lines array
     index: usize,
-    /// Start point [longitude, latitude] in f64
+    /// Start point in the index's coordinate space
     start: [f64; 2],
-    /// End point [longitude, latitude] in f64
+    /// End point in the index's coordinate space
     end: [f64; 2],
 }
+/// Grid cells mapping (cell_x, cell_y) to positions in the line cache
+type GridCells = HashMap<(i32, i32), Vec<usize>>;
+/// Project line endpoints into `space` and bucket them into grid cells.
+///
+/// Lines whose coordinates don't convert to f64 are skipped; the rest keep
+/// their position in the input as [`LineSegment::index`].
+fn build_grid<'a>(
+    coordinates: impl Iterator<Item = &'a [[Decimal; 2]; 2]>,
+    space: CoordinateSpace,
+) -> (GridCells, Vec<LineSegment>) {
+    let mut grid = GridCells::new();
+    let mut lines = Vec::new();
+    for (idx, coords) in coordinates.enumerate() {
+        if let (Some(x1), Some(y1), Some(x2), Some(y2)) = (
+            coords[0][0].to_f64(),
+            coords[0][1].to_f64(),
+            coords[1][0].to_f64(),
+            coords[1][1].to_f64(),
+        ) {
+            let start = space.project([x1, y1]);
+            let end = space.project([x2, y2]);
+            for cell in line_cells(start[0], start[1], end[0], end[1], space.cell_size()) {
+                grid.entry(cell).or_default().push(lines.len());
+            }
+            lines.push(LineSegment {
+                index: idx,
+                start,
+                end,
+            });
+        }
+    }
+    (grid, lines)
+}
 impl RTreeSpatialAlgo {
     /// Create a new R-tree spatial index from parking lines.
     ///
@@ -156,30 +201,31 @@ impl RTreeSpatialAlgo {
     /// println!("Indexed {} lines", parking_lines.len());
     /// ```
     pub fn new(parking_lines: &[MiljoeDataClean]) -> Self {
-        let mut grid: HashMap<(i32, i32), Vec<usize>> = HashMap::new();
-        let mut lines = Vec::new();
-        for (idx, line) in parking_lines.iter().enumerate() {
-            if let (Some(x1), Some(y1), Some(x2), Some(y2)) = (
-                line.coordinates[0][0].to_f64(),
-                line.coordinates[0][1].to_f64(),
-                line.coordinates[1][0].to_f64(),
-                line.coordinates[1][1].to_f64(),
-            ) {
-                lines.push(LineSegment {
-                    index: idx,
-                    start: [x1, y1],
-                    end: [x2, y2],
-                });
-                let cells = line_cells(x1, y1, x2, y2, CELL_SIZE);
-                for cell in cells {
-                    grid.entry(cell).or_default().push(idx);
-                }
-            }
-        }
+        Self::with_space(parking_lines, CoordinateSpace::Wgs84)
+    }
+    /// Create a spatial index that stores and measures in `space`.
+    ///
+    /// With [`CoordinateSpace::Sweref99Tm`] every line endpoint is projected
+    /// once here, so queries only project the address point and compare plain
+    /// Euclidean distances.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use amp_core::correlation_algorithms::RTreeSpatialAlgo;
+    /// use amp_core::crs::CoordinateSpace;
+    /// # use amp_core::structs::MiljoeDataClean;
+    /// # let parking_lines: Vec<MiljoeDataClean> = vec![];
+    ///
+    /// let algo = RTreeSpatialAlgo::with_space(&parking_lines, CoordinateSpace::Sweref99Tm);
+    /// ```
+    pub fn with_space(parking_lines: &[MiljoeDataClean], space: CoordinateSpace) -> Self {
+        let (grid, lines) = build_grid(parking_lines.iter().map(|line| &line.coordinates), space);
         Self {
             grid,
             lines,
-            cell_size: CELL_SIZE,
+            cell_size: space.cell_size(),
+            space,
         }
     }
 }
@@ -187,16 +233,18 @@ impl CorrelationAlgo for RTreeSpatialAlgo {
     /// Correlate address with environmental parking lines using spatial index.
     ///
     /// Searches the 3×3 neighborhood of grid cells around the address point
-    /// and returns the closest line within [`MAX_DISTANCE_METERS`].
+    /// and returns the closest line within `cutoff_m`. Cutoffs past
+    /// [`MAX_DISTANCE_METERS`] search every cell they reach instead.
     ///
     /// # Arguments
     ///
     /// * `address` - Address point to correlate
     /// * `_parking_lines` - Ignored (index stores its own line references)
+    /// * `cutoff_m` - Maximum distance in meters
     ///
     /// # Returns
     ///
-    /// - `Some((index, distance))` if a line is within 50 meters
+    /// - `Some((index, distance))` if a line is within `cutoff_m` meters
     /// - `None` if no match found or coordinate conversion fails
     ///
     /// # Time Complexity
@@ -204,24 +252,26 @@ impl CorrelationAlgo for RTreeSpatialAlgo {
     /// O(m) where m = number of lines in 9-cell neighborhood (typically 1-50)
     ///
     /// [`MAX_DISTANCE_METERS`]: crate::correlation_algorithms::common::MAX_DISTANCE_METERS
-    fn correlate(
+    fn correlate_within(
         &self,
         address: &AdressClean,
         _parking_lines: &[MiljoeDataClean],
+        cutoff_m: f64,
     ) -> Option<(usize, f64)> {
-        let point = [
+        let point = self.space.project([
             address.coordinates[0].to_f64()?,
             address.coordinates[1].to_f64()?,
-        ];
-        let cell = get_cell(point, self.cell_size);
-        let nearby_cells = get_nearby_cells(cell);
+        ]);
+        let nearby_cells = self.space.search_cells(point, cutoff_m);
         let mut best: Option<(usize, f64)> = None;
         for check_cell in nearby_cells {
             if let Some(indices) = self.grid.get(&check_cell) {
                 for &idx in indices {
                     let line = &self.lines[idx];
-                    let dist = distance_point_to_line(point, line.start, line.end);
-                    if dist <= MAX_DISTANCE_METERS && (best.is_none() || dist <= best.unwrap().1) {
+                    let dist = self
+                        .space
+                        .distance_point_to_line(point, line.start, line.end);
+                    if dist <= cutoff_m && is_closer((line.index, dist), best) {
                         best = Some((line.index, dist));
                     }
                 }
@@ -229,6 +279,68 @@ impl CorrelationAlgo for RTreeSpatialAlgo {
         }
         best
     }
+    /// Up to `k` closest lines from the same 3×3 neighborhood as [`correlate`].
+    ///
+    /// The neighborhood spans more than [`MAX_DISTANCE_METERS`] in every
+    /// direction, so no line within range is missed.
+    ///
+    /// [`correlate`]: CorrelationAlgo::correlate
+    /// [`MAX_DISTANCE_METERS`]: crate::correlation_algorithms::common::MAX_DISTANCE_METERS
+    fn correlate_k(
+        &self,
+        address: &AdressClean,
+        _parking_lines: &[MiljoeDataClean],
+        k: usize,
+    ) -> Vec<(usize, f64)> {
+        let (Some(x), Some(y)) = (
+            address.coordinates[0].to_f64(),
+            address.coordinates[1].to_f64(),
+        ) else {
+            return Vec::new();
+        };
+        let point = self.space.project([x, y]);
+        let cell = get_cell(point, self.cell_size);
+        let candidates = get_nearby_cells(cell)
+            .into_iter()
+            .filter_map(|check_cell| self.grid.get(&check_cell))
+            .flatten()
+            .map(|&idx| {
+                let line = &self.lines[idx];
+                let dist = self
+                    .space
+                    .distance_point_to_line(point, line.start, line.end);
+                (line.index, dist)
+            });
+        nearest_k(candidates, k)
+    }
+    /// Every line within `radius_m`, from the grid cells covering the radius.
+    fn within_radius(
+        &self,
+        address: &AdressClean,
+        _parking_lines: &[MiljoeDataClean],
+        radius_m: f64,
+    ) -> Vec<(usize, f64)> {
+        let (Some(x), Some(y)) = (
+            address.coordinates[0].to_f64(),
+            address.coordinates[1].to_f64(),
+        ) else {
+            return Vec::new();
+        };
+        let point = self.space.project([x, y]);
+        let reach = self.space.reach(point, radius_m);
+        let candidates = get_cells_within(point, reach, self.cell_size)
+            .into_iter()
+            .filter_map(|check_cell| self.grid.get(&check_cell))
+            .flatten()
+            .map(|&idx| {
+                let line = &self.lines[idx];
+                let dist = self
+                    .space
+                    .distance_point_to_line(point, line.start, line.end);
+                (line.index, dist)
+            });
+        in_range(candidates, radius_m)
+    }
     fn name(&self) -> &'static str {
         "R-Tree Spatial"
     }
@@ -241,6 +353,7 @@ pub struct RTreeSpatialParkeringAlgo {
     grid: HashMap<(i32, i32), Vec<usize>>,
     lines: Vec<LineSegment>,
     cell_size: f64,
+    space: CoordinateSpace,
 }
 impl RTreeSpatialParkeringAlgo {
     /// Create a new R-tree spatial index from parking zone lines.
@@ -259,30 +372,18 @@ impl RTreeSpatialParkeringAlgo {
     /// let algo = RTreeSpatialParkeringAlgo::new(&parking_lines);
     /// ```
     pub fn new(parking_lines: &[ParkeringsDataClean]) -> Self {
-        let mut grid: HashMap<(i32, i32), Vec<usize>> = HashMap::new();
-        let mut lines = Vec::new();
-        for (idx, line) in parking_lines.iter().enumerate() {
-            if let (Some(x1), Some(y1), Some(x2), Some(y2)) = (
-                line.coordinates[0][0].to_f64(),
-                line.coordinates[0][1].to_f64(),
-                line.coordinates[1][0].to_f64(),
-                line.coordinates[1][1].to_f64(),
-            ) {
-                lines.push(LineSegment {
-                    index: idx,
-                    start: [x1, y1],
-                    end: [x2, y2],
-                });
-                let cells = line_cells(x1, y1, x2, y2, CELL_SIZE);
-                for cell in cells {
-                    grid.entry(cell).or_default().push(idx);
-                }
-            }
-        }
+        Self::with_space(parking_lines, CoordinateSpace::Wgs84)
+    }
+    /// Create a parking zone index that stores and measures in `space`.
+    ///
+    /// See [`RTreeSpatialAlgo::with_space`].
+    pub fn with_space(parking_lines: &[ParkeringsDataClean], space: CoordinateSpace) -> Self {
+        let (grid, lines) = build_grid(parking_lines.iter().map(|line| &line.coordinates), space);
         Self {
             grid,
             lines,
-            cell_size: CELL_SIZE,
+            cell_size: space.cell_size(),
+            space,
         }
     }
 }
@@ -298,10 +399,10 @@ impl ParkeringCorrelationAlgo for RTreeSpatialParkeringAlgo {
         address: &AdressClean,
         _parking_lines: &[ParkeringsDataClean],
     ) -> Option<(usize, f64)> {
-        let point = [
+        let point = self.space.project([
             address.coordinates[0].to_f64()?,
             address.coordinates[1].to_f64()?,
-        ];
+        ]);
         let cell = get_cell(point, self.cell_size);
         let nearby_cells = get_nearby_cells(cell);
         let mut best: Option<(usize, f64)> = None;
@@ -309,8 +410,10 @@ impl ParkeringCorrelationAlgo for RTreeSpatialParkeringAlgo {
             if let Some(indices) = self.grid.get(&check_cell) {
                 for &idx in indices {
                     let line = &self.lines[idx];
-                    let dist = distance_point_to_line(point, line.start, line.end);
-                    if dist <= MAX_DISTANCE_METERS && (best.is_none() || dist <= best.unwrap().1) {
+                    let dist = self
+                        .space
+                        .distance_point_to_line(point, line.start, line.end);
+                    if dist <= MAX_DISTANCE_METERS && is_closer((line.index, dist), best) {
                         best = Some((line.index, dist));
                     }
                 }
@@ -330,4 +433,43 @@ mod tests {
         let cells = line_cells(13.0, 55.0, 13.1, 55.1, CELL_SIZE);
         assert!(!cells.is_empty());
     }
+    fn line(x: i64, y: i64) -> MiljoeDataClean {
+        MiljoeDataClean::new(
+            [
+                [Decimal::new(x, 6), Decimal::new(y, 6)],
+                [Decimal::new(x + 200, 6), Decimal::new(y, 6)],
+            ],
+            String::new(),
+            "0800-1200".to_string(),
+            1,
+        )
+    }
+    #[test]
+    fn test_correlate_k_sorted_and_truncated() {
+        let lines = vec![
+            line(13_000_000, 55_600_200),
+            line(13_000_000, 55_600_050),
+            line(13_050_000, 55_650_000),
+            line(13_000_000, 55_600_120),
+        ];
+        let algo = RTreeSpatialAlgo::new(&lines);
+        let address = AdressClean {
+            coordinates: [Decimal::new(13_000_100, 6), Decimal::new(55_600_000, 6)],
+            postnummer: None,
+            adress: "Testgatan 1".to_string(),
+            gata: "Testgatan".to_string(),
+            gatunummer: "1".to_string(),
+        };
+        let all = algo.correlate_k(&address, &lines, 10);
+        assert_eq!(
+            all.iter().map(|&(i, _)| i).collect::<Vec<_>>(),
+            vec![1, 3, 0]
+        );
+        assert!(all.windows(2).all(|pair| pair[0].1 <= pair[1].1));
+        assert_eq!(algo.correlate_k(&address, &lines, 1), all[..1]);
+        assert_eq!(
+            algo.correlate(&address, &lines).map(|(i, _)| i),
+            Some(all[0].0)
+        );
+    }
 }
@@ -1,9 +1,13 @@
 #[cfg(test)]
 mod tests {
+    use crate::correlation_algorithms::common::haversine_distance;
     use crate::correlation_algorithms::{
-        CorrelationAlgo, DistanceBasedAlgo, GridNearestAlgo, KDTreeSpatialAlgo,
-        OverlappingChunksAlgo, RTreeSpatialAlgo, RaycastingAlgo,
+        BallTreeSpatialAlgo, CorrelationAlgo, DistanceBasedAlgo, GridNearestAlgo,
+        KDTreeSpatialAlgo, MinSegmentLengthAlgo, OverlappingChunksAlgo, RTreeSpatialAlgo,
+        RaycastingAlgo,
     };
+    use crate::crs::CoordinateSpace;
+    use crate::parking::malmo_taxa_table;
     use crate::structs::*;
     use rust_decimal::Decimal;
     use std::str::FromStr;
@@ -27,15 +31,15 @@ mod tests {
         lon_end: &str,
         info: &str,
     ) -> MiljoeDataClean {
-        MiljoeDataClean {
-            coordinates: [
+        MiljoeDataClean::new(
+            [
                 [decimal(lon_start), decimal(lat_start)],
                 [decimal(lon_end), decimal(lat_end)],
             ],
-            info: info.to_string(),
-            tid: "08:00-18:00".to_string(),
-            dag: 1,
-        }
+            info.to_string(),
+            "08:00-18:00".to_string(),
+            1,
+        )
     }
     #[test]
     fn test_haversine_distance_accuracy() {
@@ -151,24 +155,122 @@ mod tests {
     }
     #[test]
     fn test_correlation_result_structure() {
-        let _result1 = CorrelationResult {
-            address: "Storgatan 1".to_string(),
-            postnummer: "200 00".to_string(),
-            miljo_match: Some((15.5, "Miljö Zone A".to_string())),
-            parkering_match: None,
+        let result1 = CorrelationResult::new(
+            "Storgatan 1".to_string(),
+            "200 00".to_string(),
+            Some((15.5, "Miljö Zone A".to_string())),
+            None,
+        );
+        let result2 = CorrelationResult::new(
+            "Storgatan 2".to_string(),
+            "200 00".to_string(),
+            Some((3.0, "Miljö Zone B".to_string())),
+            Some((45.0, "Parkering Zone A".to_string())),
+        )
+        .with_taxa_rates(&malmo_taxa_table());
+        let result3 =
+            CorrelationResult::new("Storgatan 3".to_string(), "200 00".to_string(), None, None);
+        assert!(!result1.miljo_reliable);
+        assert!(result2.miljo_reliable);
+        assert!(!result2.parkering_reliable);
+        assert!(result2.parkering_match.is_some(), "Shaky matches are kept");
+        assert_eq!(
+            result2.parkering_rate_sek_per_hour, None,
+            "Unknown zones have no rate"
+        );
+        assert!(!result3.miljo_reliable && !result3.parkering_reliable);
+    }
+    #[test]
+    fn test_correlation_result_closest_match() {
+        let result = |miljo: Option<f64>, parkering: Option<f64>| {
+            CorrelationResult::new(
+                "Storgatan 1".to_string(),
+                "200 00".to_string(),
+                miljo.map(|dist| (dist, "Städning".to_string())),
+                parkering.map(|dist| (dist, "Taxa C".to_string())),
+            )
         };
-        let _result2 = CorrelationResult {
-            address: "Storgatan 2".to_string(),
-            postnummer: "200 00".to_string(),
-            miljo_match: Some((20.0, "Miljö Zone B".to_string())),
-            parkering_match: Some((35.0, "Parkering Zone A".to_string())),
+        let both = result(Some(12.0), Some(4.5));
+        assert_eq!(both.closest_distance(), Some(4.5));
+        assert_eq!(both.closest_match_source(), Some(MatchSource::Parkering));
+        let miljo_only = result(Some(7.0), None);
+        assert_eq!(miljo_only.closest_distance(), Some(7.0));
+        assert_eq!(miljo_only.closest_match_source(), Some(MatchSource::Miljo));
+        let parkering_only = result(None, Some(30.0));
+        assert_eq!(parkering_only.closest_distance(), Some(30.0));
+        assert_eq!(
+            parkering_only.closest_match_source(),
+            Some(MatchSource::Parkering)
+        );
+        let neither = result(None, None);
+        assert_eq!(neither.closest_distance(), None);
+        assert_eq!(neither.closest_match_source(), None);
+        let tie = result(Some(5.0), Some(5.0));
+        assert_eq!(tie.closest_match_source(), Some(MatchSource::Miljo));
+        let nan = result(Some(f64::NAN), Some(20.0));
+        assert_eq!(nan.closest_distance(), Some(20.0));
+    }
+    #[test]
+    fn test_correlation_result_confidence() {
+        let result = |miljo: Option<f64>, parkering: Option<f64>| {
+            CorrelationResult::new(
+                "Storgatan 1".to_string(),
+                "200 00".to_string(),
+                miljo.map(|dist| (dist, "Städning".to_string())),
+                parkering.map(|dist| (dist, "Taxa C".to_string())),
+            )
         };
-        let _result3 = CorrelationResult {
-            address: "Storgatan 3".to_string(),
-            postnummer: "200 00".to_string(),
-            miljo_match: None,
-            parkering_match: None,
+        let close = result(Some(5.0), None).with_cutoff(50.0);
+        assert!(
+            (close.confidence - 0.9).abs() < 1e-9,
+            "{}",
+            close.confidence
+        );
+        let better_of_two = result(Some(48.0), Some(5.0));
+        assert!((better_of_two.confidence - 0.9).abs() < 1e-9);
+        let tight = result(Some(5.0), None).with_cutoff(10.0);
+        assert!((tight.confidence - 0.5).abs() < 1e-9);
+        assert_eq!(result(Some(60.0), None).confidence, 0.0);
+        assert_eq!(result(None, None).confidence, 0.0);
+    }
+    #[test]
+    fn test_reliability_counts() {
+        let result = |miljo: Option<f64>, parkering: Option<f64>| OutputDataWithDistance {
+            data: OutputData {
+                postnummer: None,
+                adress: "Storgatan 1".to_string(),
+                gata: "Storgatan".to_string(),
+                gatunummer: "1".to_string(),
+                info: miljo.map(|_| "Städning".to_string()),
+                tid: miljo.map(|_| "0800-1200".to_string()),
+                dag: miljo.map(|_| 15),
+                dagar: miljo.map(|_| vec![15]).unwrap_or_default(),
+                taxa: parkering.map(|_| "Taxa C".to_string()),
+                antal_platser: parkering.map(|_| 10),
+                typ_av_parkering: parkering.map(|_| "Längsgående".to_string()),
+                match_quality: None,
+                segment_id: None,
+            },
+            miljo_distance: miljo,
+            parkering_distance: parkering,
+            has_location: true,
         };
+        let results = vec![
+            result(Some(3.0), Some(12.0)),
+            result(Some(3.0), Some(14.0)),
+            result(Some(18.0), None),
+            result(None, Some(5.0)),
+            result(None, None),
+        ];
+        assert_eq!(
+            ReliabilityCounts::from_results(&results),
+            ReliabilityCounts {
+                miljo_reliable: 2,
+                miljo_unreliable: 1,
+                parkering_reliable: 2,
+                parkering_unreliable: 1,
+            }
+        );
     }
     #[test]
     fn test_batch_processing_multiple_addresses() {
@@ -346,15 +448,15 @@ mod tests {
         let mut zones = Vec::new();
         for i in 0..50 {
             let lat_offset = Decimal::from(i) * decimal("0.0002");
-            zones.push(MiljoeDataClean {
-                coordinates: [
+            zones.push(MiljoeDataClean::new(
+                [
                     [decimal("13.1945945") + lat_offset, decimal("55.5932645")],
                     [decimal("13.1946245") + lat_offset, decimal("55.5932945")],
                 ],
-                info: format!("Zone {}", i),
-                tid: "08:00-18:00".to_string(),
-                dag: ((i % 7) as u8) + 1,
-            });
+                format!("Zone {}", i),
+                "08:00-18:00".to_string(),
+                ((i % 7) as u8) + 1,
+            ));
         }
         let db_algo = DistanceBasedAlgo;
         let mut db_matches = 0;
@@ -391,12 +493,12 @@ mod tests {
             gata: "Test Street".to_string(),
             gatunummer: "1".to_string(),
         };
-        let zone = MiljoeDataClean {
-            coordinates: [coord, coord],
-            info: "Exact Match Zone".to_string(),
-            tid: "08:00-18:00".to_string(),
-            dag: 1,
-        };
+        let zone = MiljoeDataClean::new(
+            [coord, coord],
+            "Exact Match Zone".to_string(),
+            "08:00-18:00".to_string(),
+            1,
+        );
         let algo = DistanceBasedAlgo;
         let result = algo.correlate(&address, &[zone]);
         assert!(result.is_some(), "Should find exact match");
@@ -406,15 +508,15 @@ mod tests {
     #[test]
     fn test_degenerate_zone_handling() {
         let address = create_test_address("55.5932645", "13.1945945", "Test Address");
-        let degenerate_zone = MiljoeDataClean {
-            coordinates: [
+        let degenerate_zone = MiljoeDataClean::new(
+            [
                 [decimal("13.1945945"), decimal("55.5932645")],
                 [decimal("13.1945945"), decimal("55.5932645")],
             ],
-            info: "Degenerate Zone".to_string(),
-            tid: "08:00-18:00".to_string(),
-            dag: 1,
-        };
+            "Degenerate Zone".to_string(),
+            "08:00-18:00".to_string(),
+            1,
+        );
         let algo = DistanceBasedAlgo;
         let result = algo.correlate(&address, &[degenerate_zone]);
         assert!(result.is_some(), "Should handle degenerate zone");
@@ -445,4 +547,237 @@ mod tests {
             assert_eq!(idx, 0, "Should match the close zone");
         }
     }
+    /// Real Malmö addresses paired with their nearest miljöparkering segment.
+    ///
+    /// Provenance: address points are the `BELADRESS` features of the same
+    /// name in `data/adresser.json`; segments are the closest LineString
+    /// segment in `data/miljoparkeringar.json` (export timestamp 20251229,
+    /// feature `gid` noted per row). Both files are WGS84 lon/lat. Addresses
+    /// were taken from `scripts/debug.txt`. Expected distances were computed
+    /// outside this crate (haversine to the closest point on the segment)
+    /// and rounded to centimetres.
+    ///
+    /// Columns: address, lon, lat, segment `[x1, y1, x2, y2]`, expected meters.
+    const MALMO_SCALE_FIXTURE: &[(&str, &str, &str, [&str; 4], f64)] = &[
+        // gid 3159411
+        (
+            "Claesgatan 2B",
+            "13.0087214911",
+            "55.5910153681",
+            ["13.008652", "55.5910309991", "13.008535", "55.5908299991"],
+            4.70,
+        ),
+        // gid 3158839
+        (
+            "Kornettsgatan 18C",
+            "13.0115466976",
+            "55.6010685967",
+            ["13.011248", "55.6011869991", "13.011342", "55.6011009991"],
+            13.35,
+        ),
+        // gid 3159895
+        (
+            "Celsiusgatan 13A U1",
+            "13.0205457476",
+            "55.6030893872",
+            ["13.020817", "55.6030549991", "13.021467", "55.6025999991"],
+            17.46,
+        ),
+        // gid 3160449
+        (
+            "Vitemöllegatan 11A",
+            "13.0267755467",
+            "55.5893079193",
+            ["13.025918", "55.5894229991", "13.027418", "55.5892879991"],
+            4.19,
+        ),
+        // gid 3158222
+        (
+            "Docentgatan 1B",
+            "13.0210559814",
+            "55.5664318225",
+            ["13.021237", "55.5663849991", "13.021189", "55.5661929991"],
+            12.52,
+        ),
+    ];
+    /// Guards against CRS/unit mistakes (e.g. treating degrees as meters or
+    /// swapping lon/lat), which once produced ~9,722 km match distances.
+    #[test]
+    fn test_malmo_fixture_distances_in_meter_range() {
+        let zones: Vec<MiljoeDataClean> = MALMO_SCALE_FIXTURE
+            .iter()
+            .map(|(name, _, _, seg, _)| {
+                MiljoeDataClean::new(
+                    [
+                        [decimal(seg[0]), decimal(seg[1])],
+                        [decimal(seg[2]), decimal(seg[3])],
+                    ],
+                    format!("{} Miljözon", name),
+                    "0800-1200".to_string(),
+                    1,
+                )
+            })
+            .collect();
+        let algorithms: Vec<(&str, Box<dyn CorrelationAlgo>)> = vec![
+            ("Distance-Based", Box::new(DistanceBasedAlgo)),
+            ("Raycasting", Box::new(RaycastingAlgo)),
+            (
+                "Overlapping Chunks",
+                Box::new(OverlappingChunksAlgo::new(&zones)),
+            ),
+            ("R-Tree", Box::new(RTreeSpatialAlgo::new(&zones))),
+            (
+                "R-Tree (SWEREF 99 TM)",
+                Box::new(RTreeSpatialAlgo::with_space(
+                    &zones,
+                    CoordinateSpace::Sweref99Tm,
+                )),
+            ),
+            ("KD-Tree", Box::new(KDTreeSpatialAlgo::new(&zones))),
+            ("Grid", Box::new(GridNearestAlgo::new(&zones))),
+        ];
+        for (expected_index, (name, lon, lat, _, expected_m)) in
+            MALMO_SCALE_FIXTURE.iter().enumerate()
+        {
+            let address = AdressClean {
+                coordinates: [decimal(lon), decimal(lat)],
+                postnummer: None,
+                adress: name.to_string(),
+                gata: String::new(),
+                gatunummer: String::new(),
+            };
+            for (algo_name, algo) in &algorithms {
+                let (index, dist) = algo
+                    .correlate(&address, &zones)
+                    .unwrap_or_else(|| panic!("{}: no match for {}", algo_name, name));
+                assert_eq!(
+                    index, expected_index,
+                    "{}: wrong zone for {}",
+                    algo_name, name
+                );
+                assert!(
+                    (1.0..100.0).contains(&dist),
+                    "{}: {} matched at {:.2}m, expected tens of meters",
+                    algo_name,
+                    name,
+                    dist,
+                );
+                assert!(
+                    (dist - expected_m).abs() < 0.5,
+                    "{}: {} matched at {:.2}m, fixture says {:.2}m",
+                    algo_name,
+                    name,
+                    dist,
+                    expected_m,
+                );
+            }
+        }
+    }
+    /// Great-circle distance from `point` to a segment, found by densely
+    /// sampling the segment rather than projecting onto it in degree space.
+    fn sampled_haversine_to_segment(point: [f64; 2], start: [f64; 2], end: [f64; 2]) -> f64 {
+        (0..=1000)
+            .map(|i| {
+                let t = i as f64 / 1000.0;
+                haversine_distance(
+                    point,
+                    [
+                        start[0] + t * (end[0] - start[0]),
+                        start[1] + t * (end[1] - start[1]),
+                    ],
+                )
+            })
+            .fold(f64::INFINITY, f64::min)
+    }
+    /// The projected index must agree with great-circle distances on a grid
+    /// of addresses around a real segment, including ones near the 50 m
+    /// cutoff. Neither Haversine algorithm is used as the reference: the
+    /// degree-grid R-tree only searches ~31 m east-west at Malmö's latitude,
+    /// and [`distance_point_to_line`] picks its closest point in degree space,
+    /// which is off by several meters for oblique approaches.
+    ///
+    /// [`distance_point_to_line`]: crate::correlation_algorithms::common::distance_point_to_line
+    #[test]
+    fn test_projected_rtree_matches_haversine_path() {
+        let start = [13.008652, 55.5910309991];
+        let end = [13.008535, 55.5908299991];
+        let zones = vec![create_test_zone(
+            "55.5910309991",
+            "13.008652",
+            "55.5908299991",
+            "13.008535",
+            "Claesgatan",
+        )];
+        let projected = RTreeSpatialAlgo::with_space(&zones, CoordinateSpace::Sweref99Tm);
+        for step_lat in -6..=6 {
+            for step_lon in -6..=6 {
+                let lat = 55.5909 + step_lat as f64 * 0.00008;
+                let lon = 13.0086 + step_lon as f64 * 0.00013;
+                let address =
+                    create_test_address(&format!("{:.7}", lat), &format!("{:.7}", lon), "Probe");
+                let expected = sampled_haversine_to_segment([lon, lat], start, end);
+                match projected.correlate(&address, &zones) {
+                    Some((index, dist)) => {
+                        assert_eq!(index, 0);
+                        assert!(
+                            (dist - expected).abs() < 0.5,
+                            "({lat}, {lon}): haversine {expected:.2}m vs projected {dist:.2}m",
+                        );
+                    }
+                    None => assert!(
+                        expected > 49.5,
+                        "({lat}, {lon}): projected index missed a line at {expected:.2}m",
+                    ),
+                }
+            }
+        }
+    }
+    #[test]
+    fn test_correlate_within_uses_caller_cutoff() {
+        let address = create_test_address("55.6000000", "13.0000000", "Cutoff Test");
+        // East-west line ~60 m north of the address
+        let zones = vec![create_test_zone(
+            "55.6005396",
+            "12.9990000",
+            "55.6005396",
+            "13.0010000",
+            "Zone 60m",
+        )];
+        let algorithms: Vec<Box<dyn CorrelationAlgo>> = vec![
+            Box::new(DistanceBasedAlgo),
+            Box::new(RaycastingAlgo),
+            Box::new(OverlappingChunksAlgo::new(&zones)),
+            Box::new(RTreeSpatialAlgo::new(&zones)),
+            Box::new(RTreeSpatialAlgo::with_space(
+                &zones,
+                CoordinateSpace::Sweref99Tm,
+            )),
+            Box::new(KDTreeSpatialAlgo::new(&zones)),
+            Box::new(GridNearestAlgo::new(&zones)),
+            Box::new(BallTreeSpatialAlgo::new(&zones)),
+            Box::new(MinSegmentLengthAlgo::new(
+                RTreeSpatialAlgo::new,
+                &zones,
+                5.0,
+                100.0,
+            )),
+        ];
+        for algo in &algorithms {
+            let (index, dist) = algo
+                .correlate_within(&address, &zones, 80.0)
+                .unwrap_or_else(|| panic!("{}: missed the line with an 80m cutoff", algo.name()));
+            assert_eq!(index, 0, "{}", algo.name());
+            assert!((dist - 60.0).abs() < 1.0, "{}: {dist:.2}m", algo.name());
+            assert!(
+                algo.correlate_within(&address, &zones, 50.0).is_none(),
+                "{}: matched past a 50m cutoff",
+                algo.name()
+            );
+            assert!(
+                algo.correlate(&address, &zones).is_none(),
+                "{}",
+                algo.name()
+            );
+        }
+    }
 }
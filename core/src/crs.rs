@@ -0,0 +1,333 @@
+//! Coordinate reference systems used by the correlation pipeline.
+//!
+//! Source data is published in WGS84 (EPSG:4326) longitude/latitude. The
+//! correlation algorithms can either work on those degrees directly and
+//! convert every distance to meters with the Haversine formula, or project
+//! all coordinates once into SWEREF 99 TM (EPSG:3006), the Swedish national
+//! grid, where plain Euclidean distance already is meters.
+//!
+//! # Choosing a Space
+//!
+//! | | [`CoordinateSpace::Wgs84`] | [`CoordinateSpace::Sweref99Tm`] |
+//! |---|---|---|
+//! | Build cost | Decimal → f64 only | + one projection per line endpoint |
+//! | Query cost | Haversine per candidate (trig) | one projection per address, then arithmetic |
+//! | Grid cells | 0.0005° (~55 × 31 m in Malmö) | 55 × 55 m, square everywhere |
+//! | Distance error | ~0.3% (spherical Earth), plus the closest point is picked in degree space | < 0.1% inside Sweden (TM scale distortion) |
+//!
+//! The Haversine path is the default and needs no setup. The projected path
+//! makes the planar grid indices correct by construction: cell sizes, the
+//! 3×3 neighborhood and the closest-point-on-segment are all measured in the
+//! same unit as [`MAX_DISTANCE_METERS`], instead of degrees that are ~45%
+//! shorter east-west than north-south at Malmö's latitude. The two paths agree
+//! to well under a meter at the 50 m cutoff.
+//!
+//! Projection uses the Gauss–Krüger formulas published by Lantmäteriet for
+//! GRS80 (which WGS84 matches to well below a millimeter here).
+//! [`sweref99tm_to_wgs84`] is the inverse, for importing data a
+//! municipality publishes in SWEREF 99 TM instead of WGS84.
+//!
+//! # Examples
+//!
+//! ```
+//! use amp_core::crs::{CoordinateSpace, wgs84_to_sweref99tm};
+//!
+//! let [easting, northing] = wgs84_to_sweref99tm([13.0, 55.6]);
+//! assert!((300_000.0..400_000.0).contains(&easting));
+//! assert!((6_100_000.0..6_200_000.0).contains(&northing));
+//!
+//! let space = CoordinateSpace::Sweref99Tm;
+//! let a = space.project([13.0, 55.6]);
+//! let b = space.project([13.0, 55.601]);
+//! let d = space.distance_point_to_line(a, b, b);
+//! assert!((d - 111.0).abs() < 1.0);
+//! ```
+//!
+//! [`MAX_DISTANCE_METERS`]: crate::correlation_algorithms::common::MAX_DISTANCE_METERS
+use crate::correlation_algorithms::common::{
+    CELL_SIZE, EARTH_RADIUS_M, MAX_DISTANCE_METERS, distance_point_to_line, get_cell,
+    get_cells_within, get_nearby_cells,
+};
+/// GRS80 semi-major axis in meters
+const GRS80_A: f64 = 6_378_137.0;
+/// GRS80 flattening
+const GRS80_F: f64 = 1.0 / 298.257_222_101;
+/// SWEREF 99 TM central meridian in degrees
+const SWEREF99TM_CENTRAL_MERIDIAN: f64 = 15.0;
+/// SWEREF 99 TM scale factor on the central meridian
+const SWEREF99TM_SCALE: f64 = 0.9996;
+/// SWEREF 99 TM false easting in meters
+const SWEREF99TM_FALSE_EASTING: f64 = 500_000.0;
+/// Grid cell size in meters for indices built in [`CoordinateSpace::Sweref99Tm`]
+///
+/// Matches the north-south extent of [`CELL_SIZE`] so both spaces search a
+/// neighborhood of similar size.
+pub const SWEREF99TM_CELL_SIZE_M: f64 = 55.0;
+/// Coordinate space the correlation algorithms index and measure in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CoordinateSpace {
+    /// WGS84 `[longitude, latitude]` in degrees, distances via Haversine
+    #[default]
+    Wgs84,
+    /// SWEREF 99 TM `[easting, northing]` in meters, Euclidean distances
+    Sweref99Tm,
+}
+impl CoordinateSpace {
+    /// Convert a WGS84 `[longitude, latitude]` point into this space.
+    ///
+    /// Identity for [`CoordinateSpace::Wgs84`].
+    pub fn project(self, point: [f64; 2]) -> [f64; 2] {
+        match self {
+            CoordinateSpace::Wgs84 => point,
+            CoordinateSpace::Sweref99Tm => wgs84_to_sweref99tm(point),
+        }
+    }
+    /// Grid cell size for spatial indices, in this space's units.
+    pub fn cell_size(self) -> f64 {
+        match self {
+            CoordinateSpace::Wgs84 => CELL_SIZE,
+            CoordinateSpace::Sweref99Tm => SWEREF99TM_CELL_SIZE_M,
+        }
+    }
+    /// Half-widths of a box around `point` that holds everything within
+    /// `radius_m` meters of it, in this space's units.
+    ///
+    /// `point` must already be in this space. In WGS84 the great-circle
+    /// distance is at least the north-south distance and, at these ranges,
+    /// the east-west distance, so nothing outside the box is in range. The
+    /// box is 1% wider than that, so rounding and the small-angle
+    /// approximation never drop a line right at the edge.
+    pub fn reach(self, point: [f64; 2], radius_m: f64) -> [f64; 2] {
+        let reach = match self {
+            CoordinateSpace::Wgs84 => {
+                let lat = (radius_m / EARTH_RADIUS_M).to_degrees();
+                [lat / point[1].to_radians().cos(), lat]
+            }
+            CoordinateSpace::Sweref99Tm => [radius_m; 2],
+        };
+        reach.map(|half_width| half_width * 1.01)
+    }
+    /// Grid cells to visit for the closest line within `cutoff_m` meters.
+    ///
+    /// Up to [`MAX_DISTANCE_METERS`] this is the 3×3 neighborhood of the
+    /// cell holding `point`, as the indices have always searched. Larger
+    /// cutoffs visit every cell covered by [`Self::reach`]. `point` must
+    /// already be in this space.
+    pub fn search_cells(self, point: [f64; 2], cutoff_m: f64) -> Vec<(i32, i32)> {
+        let cell_size = self.cell_size();
+        if cutoff_m <= MAX_DISTANCE_METERS {
+            get_nearby_cells(get_cell(point, cell_size))
+        } else {
+            get_cells_within(point, self.reach(point, cutoff_m), cell_size)
+        }
+    }
+    /// Distance in meters from `point` to the segment `line_start`–`line_end`.
+    ///
+    /// All three points must already be in this space (see [`Self::project`]).
+    pub fn distance_point_to_line(
+        self,
+        point: [f64; 2],
+        line_start: [f64; 2],
+        line_end: [f64; 2],
+    ) -> f64 {
+        match self {
+            CoordinateSpace::Wgs84 => distance_point_to_line(point, line_start, line_end),
+            CoordinateSpace::Sweref99Tm => {
+                planar_distance_point_to_line(point, line_start, line_end)
+            }
+        }
+    }
+}
+/// Project a WGS84 `[longitude, latitude]` point to SWEREF 99 TM.
+///
+/// # Arguments
+/// * `point` - `[longitude, latitude]` in degrees
+///
+/// # Returns
+/// `[easting, northing]` in meters
+pub fn wgs84_to_sweref99tm(point: [f64; 2]) -> [f64; 2] {
+    let e2 = GRS80_F * (2.0 - GRS80_F);
+    let n = GRS80_F / (2.0 - GRS80_F);
+    let a_roof = GRS80_A / (1.0 + n) * (1.0 + n.powi(2) / 4.0 + n.powi(4) / 64.0);
+    let a = e2;
+    let b = (5.0 * e2.powi(2) - e2.powi(3)) / 6.0;
+    let c = (104.0 * e2.powi(3) - 45.0 * e2.powi(4)) / 120.0;
+    let d = (1237.0 * e2.powi(4)) / 1260.0;
+    let beta = [
+        n / 2.0 - 2.0 * n.powi(2) / 3.0 + 5.0 * n.powi(3) / 16.0 + 41.0 * n.powi(4) / 180.0,
+        13.0 * n.powi(2) / 48.0 - 3.0 * n.powi(3) / 5.0 + 557.0 * n.powi(4) / 1440.0,
+        61.0 * n.powi(3) / 240.0 - 103.0 * n.powi(4) / 140.0,
+        49561.0 * n.powi(4) / 161280.0,
+    ];
+    let phi = point[1].to_radians();
+    let delta_lambda = (point[0] - SWEREF99TM_CENTRAL_MERIDIAN).to_radians();
+    let sin_phi = phi.sin();
+    let phi_star = phi
+        - sin_phi
+            * phi.cos()
+            * (a + b * sin_phi.powi(2) + c * sin_phi.powi(4) + d * sin_phi.powi(6));
+    let xi = (phi_star.tan() / delta_lambda.cos()).atan();
+    let eta = (phi_star.cos() * delta_lambda.sin()).atanh();
+    let mut northing = xi;
+    let mut easting = eta;
+    for (k, beta_k) in beta.iter().enumerate() {
+        let m = 2.0 * (k as f64 + 1.0);
+        northing += beta_k * (m * xi).sin() * (m * eta).cosh();
+        easting += beta_k * (m * xi).cos() * (m * eta).sinh();
+    }
+    [
+        SWEREF99TM_SCALE * a_roof * easting + SWEREF99TM_FALSE_EASTING,
+        SWEREF99TM_SCALE * a_roof * northing,
+    ]
+}
+/// Convert a SWEREF 99 TM `[easting, northing]` point back to WGS84.
+///
+/// Inverse of [`wgs84_to_sweref99tm`]; a round trip is exact to well under
+/// a millimeter inside Sweden.
+///
+/// # Arguments
+/// * `point` - `[easting, northing]` in meters
+///
+/// # Returns
+/// `[longitude, latitude]` in degrees
+pub fn sweref99tm_to_wgs84(point: [f64; 2]) -> [f64; 2] {
+    let e2 = GRS80_F * (2.0 - GRS80_F);
+    let n = GRS80_F / (2.0 - GRS80_F);
+    let a_roof = GRS80_A / (1.0 + n) * (1.0 + n.powi(2) / 4.0 + n.powi(4) / 64.0);
+    let a = e2 + e2.powi(2) + e2.powi(3) + e2.powi(4);
+    let b = -(7.0 * e2.powi(2) + 17.0 * e2.powi(3) + 30.0 * e2.powi(4)) / 6.0;
+    let c = (224.0 * e2.powi(3) + 889.0 * e2.powi(4)) / 120.0;
+    let d = -(4279.0 * e2.powi(4)) / 1260.0;
+    let delta = [
+        n / 2.0 - 2.0 * n.powi(2) / 3.0 + 37.0 * n.powi(3) / 96.0 - n.powi(4) / 360.0,
+        n.powi(2) / 48.0 + n.powi(3) / 15.0 - 437.0 * n.powi(4) / 1440.0,
+        17.0 * n.powi(3) / 480.0 - 37.0 * n.powi(4) / 840.0,
+        4397.0 * n.powi(4) / 161280.0,
+    ];
+    let xi = point[1] / (SWEREF99TM_SCALE * a_roof);
+    let eta = (point[0] - SWEREF99TM_FALSE_EASTING) / (SWEREF99TM_SCALE * a_roof);
+    let mut xi_prim = xi;
+    let mut eta_prim = eta;
+    for (k, delta_k) in delta.iter().enumerate() {
+        let m = 2.0 * (k as f64 + 1.0);
+        xi_prim -= delta_k * (m * xi).sin() * (m * eta).cosh();
+        eta_prim -= delta_k * (m * xi).cos() * (m * eta).sinh();
+    }
+    let phi_star = (xi_prim.sin() / eta_prim.cosh()).asin();
+    let delta_lambda = (eta_prim.sinh() / xi_prim.cos()).atan();
+    let sin_phi = phi_star.sin();
+    let phi = phi_star
+        + sin_phi
+            * phi_star.cos()
+            * (a + b * sin_phi.powi(2) + c * sin_phi.powi(4) + d * sin_phi.powi(6));
+    [
+        SWEREF99TM_CENTRAL_MERIDIAN + delta_lambda.to_degrees(),
+        phi.to_degrees(),
+    ]
+}
+/// Euclidean distance from a point to a segment in a projected plane
+///
+/// # Arguments
+/// * `point` - Query point as `[x, y]`
+/// * `line_start` - Start of line segment as `[x, y]`
+/// * `line_end` - End of line segment as `[x, y]`
+///
+/// # Returns
+/// Distance in the plane's units (meters for SWEREF 99 TM)
+pub fn planar_distance_point_to_line(
+    point: [f64; 2],
+    line_start: [f64; 2],
+    line_end: [f64; 2],
+) -> f64 {
+    let line_vec = [line_end[0] - line_start[0], line_end[1] - line_start[1]];
+    let point_vec = [point[0] - line_start[0], point[1] - line_start[1]];
+    let line_len_sq = line_vec[0] * line_vec[0] + line_vec[1] * line_vec[1];
+    let t = if line_len_sq == 0.0 {
+        0.0
+    } else {
+        ((point_vec[0] * line_vec[0] + point_vec[1] * line_vec[1]) / line_len_sq).clamp(0.0, 1.0)
+    };
+    let dx = point_vec[0] - t * line_vec[0];
+    let dy = point_vec[1] - t * line_vec[1];
+    (dx * dx + dy * dy).sqrt()
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::correlation_algorithms::common::haversine_distance;
+    #[test]
+    fn test_central_meridian_origin() {
+        let [easting, northing] = wgs84_to_sweref99tm([15.0, 0.0]);
+        assert!((easting - SWEREF99TM_FALSE_EASTING).abs() < 1e-6);
+        assert!(northing.abs() < 1e-6);
+    }
+    #[test]
+    fn test_projected_distance_matches_haversine_in_malmo() {
+        let origin = [13.0030, 55.6050];
+        for offset in [[0.0005, 0.0], [0.0, 0.0003], [0.0004, -0.0002]] {
+            let other = [origin[0] + offset[0], origin[1] + offset[1]];
+            let a = wgs84_to_sweref99tm(origin);
+            let b = wgs84_to_sweref99tm(other);
+            let planar = planar_distance_point_to_line(a, b, b);
+            let haversine = haversine_distance(origin, other);
+            assert!(
+                (planar - haversine).abs() / haversine < 0.005,
+                "planar {planar:.3} m vs haversine {haversine:.3} m",
+            );
+        }
+    }
+    /// Stortorget, Malmö, in WGS84 and SWEREF 99 TM.
+    ///
+    /// The grid coordinates come from Karney's sixth-order Krüger series
+    /// ("Transverse Mercator with an accuracy of a few nanometers", J. Geod.
+    /// 85, 2011) evaluated at 40 digits, not from the Lantmäteriet
+    /// fourth-order formulas implemented above.
+    const STORTORGET_WGS84: [f64; 2] = [13.000_72, 55.605_87];
+    const STORTORGET_SWEREF99TM: [f64; 2] = [374_052.542, 6_164_028.939];
+    #[test]
+    fn test_wgs84_to_sweref99tm_matches_reference_in_malmo() {
+        let projected = wgs84_to_sweref99tm(STORTORGET_WGS84);
+        let error =
+            planar_distance_point_to_line(projected, STORTORGET_SWEREF99TM, STORTORGET_SWEREF99TM);
+        assert!(error < 0.1, "projected {projected:?}, off by {error:.4} m");
+    }
+    #[test]
+    fn test_sweref99tm_to_wgs84_matches_reference_in_malmo() {
+        let unprojected = sweref99tm_to_wgs84(STORTORGET_SWEREF99TM);
+        let error = haversine_distance(unprojected, STORTORGET_WGS84);
+        assert!(
+            error < 0.1,
+            "unprojected {unprojected:?}, off by {error:.4} m"
+        );
+    }
+    #[test]
+    fn test_sweref99tm_to_wgs84_on_central_meridian() {
+        let [longitude, latitude] = sweref99tm_to_wgs84([SWEREF99TM_FALSE_EASTING, 0.0]);
+        assert!((longitude - SWEREF99TM_CENTRAL_MERIDIAN).abs() < 1e-9);
+        assert!(latitude.abs() < 1e-9);
+    }
+    #[test]
+    fn test_search_cells_widen_past_default_cutoff() {
+        let point = CoordinateSpace::Sweref99Tm.project([13.0030, 55.6050]);
+        let default = CoordinateSpace::Sweref99Tm.search_cells(point, MAX_DISTANCE_METERS);
+        assert_eq!(default.len(), 9);
+        let wide = CoordinateSpace::Sweref99Tm.search_cells(point, 200.0);
+        assert!(wide.len() > default.len());
+        assert!(default.iter().all(|cell| wide.contains(cell)));
+    }
+    #[test]
+    fn test_planar_distance_point_to_line() {
+        assert_eq!(
+            planar_distance_point_to_line([0.0, 5.0], [-10.0, 0.0], [10.0, 0.0]),
+            5.0
+        );
+        assert_eq!(
+            planar_distance_point_to_line([13.0, 4.0], [0.0, 0.0], [10.0, 0.0]),
+            5.0
+        );
+        assert_eq!(
+            planar_distance_point_to_line([3.0, 4.0], [0.0, 0.0], [0.0, 0.0]),
+            5.0
+        );
+    }
+}
@@ -0,0 +1,170 @@
+#[cfg(test)]
+mod tests {
+    //! DST transitions in Europe/Stockholm for 2024:
+    //!
+    //! - Spring-forward: 2024-03-31, 02:00 CET → 03:00 CEST (01:00 UTC)
+    //! - Fall-back: 2024-10-27, 03:00 CEST → 02:00 CET (01:00 UTC)
+    use crate::structs::{DB, DBParams, SWEDISH_TZ};
+    use chrono::{DateTime, Duration, TimeZone, Timelike, Utc};
+    fn restriction(year: i32, month: u32, dag: u8, tid: &str) -> Option<DB> {
+        DB::from_params(DBParams {
+            postnummer: None,
+            adress: "Testgatan 1".to_string(),
+            gata: None,
+            gatunummer: None,
+            info: None,
+            dag,
+            tid: tid.to_string(),
+            taxa: None,
+            antal_platser: None,
+            typ_av_parkering: None,
+            year,
+            month,
+        })
+        // 2024-03-31 is also Easter Sunday
+        .map(|db| db.with_holiday_skipping(false))
+    }
+    fn utc(month: u32, day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, month, day, hour, minute, 0)
+            .unwrap()
+    }
+    #[test]
+    fn test_offsets_on_either_side_of_transitions() {
+        let winter = utc(3, 31, 0, 59).with_timezone(&SWEDISH_TZ);
+        let summer = utc(3, 31, 1, 0).with_timezone(&SWEDISH_TZ);
+        assert_eq!((winter.hour(), winter.minute()), (1, 59));
+        assert_eq!((summer.hour(), summer.minute()), (3, 0));
+        let summer = utc(10, 27, 0, 59).with_timezone(&SWEDISH_TZ);
+        let winter = utc(10, 27, 1, 0).with_timezone(&SWEDISH_TZ);
+        assert_eq!((summer.hour(), summer.minute()), (2, 59));
+        assert_eq!((winter.hour(), winter.minute()), (2, 0));
+    }
+    #[test]
+    fn test_spring_forward_restriction_spanning_gap_is_an_hour_shorter() {
+        let db = restriction(2024, 3, 31, "0100-0400").unwrap();
+        assert_eq!(db.start_time, utc(3, 31, 0, 0));
+        assert_eq!(db.end_time, utc(3, 31, 2, 0));
+        assert_eq!(db.end_time - db.start_time, Duration::hours(2));
+        assert!(db.is_active(utc(3, 31, 0, 59)));
+        assert!(db.is_active(utc(3, 31, 1, 0)));
+        assert_eq!(
+            db.time_until_end(utc(3, 31, 0, 30)),
+            Some(Duration::minutes(90))
+        );
+        assert_eq!(
+            db.time_until_end(utc(3, 31, 1, 30)),
+            Some(Duration::minutes(30))
+        );
+        assert!(!db.is_active(utc(3, 31, 2, 0)));
+        assert_eq!(db.time_until_end(utc(3, 31, 2, 0)), None);
+    }
+    #[test]
+    fn test_spring_forward_start_in_gap_shifts_past_it() {
+        // 02:30 does not exist; read with the CET offset it is 01:30 UTC
+        let db = restriction(2024, 3, 31, "0230-0600").unwrap();
+        assert_eq!(db.start_time, utc(3, 31, 1, 30));
+        assert_eq!(db.end_time, utc(3, 31, 4, 0));
+        let start = db.start_time_swedish();
+        assert_eq!((start.hour(), start.minute()), (3, 30));
+        assert!(!db.is_active(utc(3, 31, 1, 29)));
+        assert!(db.is_active(utc(3, 31, 1, 30)));
+        assert_eq!(
+            db.time_until_start(utc(3, 31, 0, 30)),
+            Some(Duration::hours(1))
+        );
+        assert_eq!(
+            db.time_until_end(utc(3, 31, 1, 30)),
+            Some(Duration::minutes(150))
+        );
+    }
+    #[test]
+    fn test_is_active_at_spring_forward_day_from_other_month() {
+        // Built for January, queried on the transition day two months later
+        let db = restriction(2024, 1, 31, "0230-0600").unwrap();
+        let swedish = |hour, minute| utc(3, 31, hour, minute).with_timezone(&SWEDISH_TZ);
+        assert!(!db.is_active_at(swedish(0, 59)));
+        assert_eq!((swedish(1, 29).hour(), swedish(1, 29).minute()), (3, 29));
+        assert!(!db.is_active_at(swedish(1, 29)));
+        assert!(db.is_active_at(swedish(1, 30)));
+        assert!(db.is_active(utc(3, 31, 3, 59)));
+        assert!(!db.is_active(utc(3, 31, 4, 0)));
+    }
+    #[test]
+    fn test_spring_forward_end_in_gap_shifts_past_it() {
+        let db = restriction(2024, 3, 31, "0000-0230").unwrap();
+        assert_eq!(db.start_time, utc(3, 30, 23, 0));
+        assert_eq!(db.end_time, utc(3, 31, 1, 30));
+        let end = db.end_time_swedish();
+        assert_eq!((end.hour(), end.minute()), (3, 30));
+    }
+    #[test]
+    fn test_spring_forward_restriction_inside_gap_is_never_active() {
+        let db = restriction(2024, 3, 31, "0200-0300").unwrap();
+        assert_eq!(db.start_time, db.end_time);
+        for minute in [-30, -1, 0, 1, 30, 60] {
+            assert!(!db.is_active(db.start_time + Duration::minutes(minute)));
+        }
+        assert_eq!(db.time_until_end(db.start_time), None);
+    }
+    #[test]
+    fn test_fall_back_restriction_spanning_repeat_is_an_hour_longer() {
+        let db = restriction(2024, 10, 27, "0100-0400").unwrap();
+        assert_eq!(db.start_time, utc(10, 26, 23, 0));
+        assert_eq!(db.end_time, utc(10, 27, 3, 0));
+        assert_eq!(db.end_time - db.start_time, Duration::hours(4));
+        // Both passes through 02:30 local are inside the restriction
+        assert!(db.is_active(utc(10, 27, 0, 30)));
+        assert!(db.is_active(utc(10, 27, 1, 30)));
+        assert_eq!(
+            db.time_until_end(utc(10, 27, 0, 30)),
+            Some(Duration::minutes(150))
+        );
+        assert_eq!(
+            db.time_until_end(utc(10, 27, 1, 30)),
+            Some(Duration::minutes(90))
+        );
+    }
+    #[test]
+    fn test_fall_back_start_in_repeat_uses_first_occurrence() {
+        let db = restriction(2024, 10, 27, "0230-0600").unwrap();
+        assert_eq!(db.start_time, utc(10, 27, 0, 30));
+        assert_eq!(db.end_time, utc(10, 27, 5, 0));
+        assert!(db.is_active(utc(10, 27, 0, 30)));
+        assert!(!db.is_active(utc(10, 27, 0, 29)));
+        assert_eq!(
+            db.time_until_end(utc(10, 27, 0, 30)),
+            Some(Duration::minutes(270))
+        );
+    }
+    #[test]
+    fn test_fall_back_restriction_inside_repeat_covers_first_pass_only() {
+        let db = restriction(2024, 10, 27, "0215-0245").unwrap();
+        assert_eq!(db.start_time, utc(10, 27, 0, 15));
+        assert_eq!(db.end_time, utc(10, 27, 0, 45));
+        assert!(db.is_active(utc(10, 27, 0, 30)));
+        // Second pass through 02:30 local, now in CET
+        assert!(!db.is_active(utc(10, 27, 1, 30)));
+    }
+    #[test]
+    fn test_fall_back_hour_ending_at_three_covers_both_passes() {
+        let db = restriction(2024, 10, 27, "0200-0300").unwrap();
+        assert_eq!(db.start_time, utc(10, 27, 0, 0));
+        assert_eq!(db.end_time, utc(10, 27, 2, 0));
+        assert_eq!(db.end_time - db.start_time, Duration::hours(2));
+    }
+    #[test]
+    fn test_same_wall_clock_time_maps_to_different_utc_by_season() {
+        let winter = restriction(2024, 1, 15, "0800-1200").unwrap();
+        let summer = restriction(2024, 7, 15, "0800-1200").unwrap();
+        assert_eq!(
+            winter.start_time,
+            Utc.with_ymd_and_hms(2024, 1, 15, 7, 0, 0).unwrap()
+        );
+        assert_eq!(
+            summer.start_time,
+            Utc.with_ymd_and_hms(2024, 7, 15, 6, 0, 0).unwrap()
+        );
+        assert_eq!(winter.start_time_swedish().hour(), 8);
+        assert_eq!(summer.start_time_swedish().hour(), 8);
+    }
+}
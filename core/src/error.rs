@@ -0,0 +1,146 @@
+//! Error type for the public loading APIs.
+//!
+//! [`AmpError`] lets callers tell apart failures that need different
+//! handling, e.g. a corrupt bundled parquet file versus an unreadable
+//! GeoJSON export, without matching on message strings. Arrow, parquet,
+//! GeoJSON and I/O errors convert into it with `?`.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use amp_core::error::AmpError;
+//! use amp_core::parquet::read_local_parquet_from_bytes;
+//!
+//! let bytes = std::fs::read("local.parquet")?;
+//! match read_local_parquet_from_bytes(&bytes) {
+//!     Ok(entries) => println!("Loaded {} entries", entries.len()),
+//!     Err(AmpError::SchemaMismatch { column, .. }) => eprintln!("Outdated file, bad {}", column),
+//!     Err(e) => eprintln!("Could not read saved addresses: {}", e),
+//! }
+//! # Ok::<(), std::io::Error>(())
+//! ```
+use std::fmt;
+/// Errors returned by `amp-core`'s loaders.
+#[derive(Debug)]
+pub enum AmpError {
+    /// A file could not be read
+    Io(std::io::Error),
+    /// Bytes are not a readable parquet file, or a batch failed to decode
+    ParquetRead(String),
+    /// A required column is missing or has the wrong type or nullability
+    ///
+    /// `expected` and `found` describe the column, e.g. `UInt8` and `Utf8`;
+    /// `found` is `missing` if the column isn't there at all.
+    SchemaMismatch {
+        column: String,
+        expected: String,
+        found: String,
+    },
+    /// Content is not GeoJSON, or not a FeatureCollection
+    GeoJsonParse(String),
+    /// A `tid` value that isn't a time range
+    InvalidTime { value: String },
+    /// A coordinate outside the valid longitude/latitude range
+    InvalidCoordinate,
+    /// Bytes don't hash to the expected SHA-256 checksum
+    ChecksumMismatch { expected: String, actual: String },
+}
+impl AmpError {
+    /// [`AmpError::SchemaMismatch`] for `column`
+    pub(crate) fn schema_mismatch(
+        column: &str,
+        expected: impl fmt::Display,
+        found: impl fmt::Display,
+    ) -> Self {
+        Self::SchemaMismatch {
+            column: column.to_string(),
+            expected: expected.to_string(),
+            found: found.to_string(),
+        }
+    }
+}
+impl fmt::Display for AmpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "I/O error: {}", e),
+            Self::ParquetRead(msg) => write!(f, "Failed to read parquet: {}", msg),
+            Self::SchemaMismatch {
+                column,
+                expected,
+                found,
+            } => write!(
+                f,
+                "Column '{}': expected {}, found {}",
+                column, expected, found
+            ),
+            Self::GeoJsonParse(msg) => write!(f, "Failed to parse GeoJSON: {}", msg),
+            Self::InvalidTime { value } => write!(f, "Invalid time format '{}'", value),
+            Self::InvalidCoordinate => write!(f, "Coordinate out of range"),
+            Self::ChecksumMismatch { expected, actual } => {
+                write!(
+                    f,
+                    "Checksum mismatch: expected {}, got {}",
+                    expected, actual
+                )
+            }
+        }
+    }
+}
+impl std::error::Error for AmpError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+impl From<std::io::Error> for AmpError {
+    fn from(e: std::io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+impl From<::parquet::errors::ParquetError> for AmpError {
+    fn from(e: ::parquet::errors::ParquetError) -> Self {
+        Self::ParquetRead(e.to_string())
+    }
+}
+impl From<arrow::error::ArrowError> for AmpError {
+    fn from(e: arrow::error::ArrowError) -> Self {
+        Self::ParquetRead(e.to_string())
+    }
+}
+impl From<::geojson::Error> for AmpError {
+    fn from(e: ::geojson::Error) -> Self {
+        Self::GeoJsonParse(e.to_string())
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn test_error_display() {
+        assert_eq!(
+            AmpError::schema_mismatch("dag", "UInt8", "Utf8").to_string(),
+            "Column 'dag': expected UInt8, found Utf8"
+        );
+        assert_eq!(
+            AmpError::InvalidTime {
+                value: "Mån 8-16".to_string()
+            }
+            .to_string(),
+            "Invalid time format 'Mån 8-16'"
+        );
+        assert_eq!(
+            AmpError::InvalidCoordinate.to_string(),
+            "Coordinate out of range"
+        );
+        assert_eq!(
+            AmpError::ChecksumMismatch {
+                expected: "abc".to_string(),
+                actual: "def".to_string()
+            }
+            .to_string(),
+            "Checksum mismatch: expected abc, got def"
+        );
+    }
+}
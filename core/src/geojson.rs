@@ -0,0 +1,215 @@
+//! GeoJSON export of the imported parking line network.
+//!
+//! Dumps [`MiljoeDataClean`] and [`ParkeringsDataClean`] segments to a
+//! `FeatureCollection` of `LineString` features so the reference data can be
+//! inspected in a GIS tool such as QGIS. Each feature carries the segment's
+//! attributes (info/tid/dag or taxa/antal_platser/typ_av_parkering) as
+//! properties, plus its index in the input slice and a `zero_length` flag for
+//! segments whose start and end points coincide.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use amp_core::api::DataLoader;
+//! use amp_core::geojson::export_lines;
+//!
+//! let miljo = DataLoader::load_miljodata("data/miljoparkeringar.json")?;
+//! std::fs::write("miljo_lines.geojson", export_lines(&miljo))?;
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+//!
+//! # Drawing a Matched Segment
+//!
+//! A segment's id is its index in the loaded slice, the same index the
+//! correlation algorithms return and `export_lines` writes as `index`.
+//! [`get_segment_geometry`] turns it back into plain `f64` endpoints for a
+//! map layer:
+//!
+//! ```no_run
+//! # use amp_core::structs::MiljoeDataClean;
+//! use amp_core::geojson::get_segment_geometry;
+//!
+//! # let miljo: Vec<MiljoeDataClean> = vec![];
+//! # let matched_index = 0;
+//! if let Some([start, end]) = get_segment_geometry(&miljo, matched_index) {
+//!     println!("Highlight {:?} → {:?}", start, end);
+//! }
+//! ```
+//!
+//! [`MiljoeDataClean`]: crate::structs::MiljoeDataClean
+//! [`ParkeringsDataClean`]: crate::structs::ParkeringsDataClean
+use crate::structs::{MiljoeDataClean, ParkeringsDataClean};
+use ::geojson::{Feature, FeatureCollection, GeoJson, Geometry, JsonObject, Value};
+use rust_decimal::Decimal;
+use rust_decimal::prelude::ToPrimitive;
+use serde_json::json;
+/// A line segment that can be exported as a GeoJSON feature.
+///
+/// Implemented for both reference data types so [`export_lines`] can handle
+/// miljö and parkering segments with the same code.
+pub trait LineFeature {
+    /// Segment endpoints as `[[lon, lat], [lon, lat]]`
+    fn line_coordinates(&self) -> &[[Decimal; 2]; 2];
+    /// Dataset-specific attributes to attach as feature properties
+    fn properties(&self) -> JsonObject;
+}
+impl LineFeature for MiljoeDataClean {
+    fn line_coordinates(&self) -> &[[Decimal; 2]; 2] {
+        &self.coordinates
+    }
+    fn properties(&self) -> JsonObject {
+        let mut props = JsonObject::new();
+        props.insert("info".to_string(), json!(self.info));
+        props.insert("tid".to_string(), json!(self.tid));
+        props.insert("dag".to_string(), json!(self.dag()));
+        props.insert("dagar".to_string(), json!(self.dagar));
+        props
+    }
+}
+impl LineFeature for ParkeringsDataClean {
+    fn line_coordinates(&self) -> &[[Decimal; 2]; 2] {
+        &self.coordinates
+    }
+    fn properties(&self) -> JsonObject {
+        let mut props = JsonObject::new();
+        props.insert("taxa".to_string(), json!(self.taxa));
+        props.insert("antal_platser".to_string(), json!(self.antal_platser));
+        props.insert("typ_av_parkering".to_string(), json!(self.typ_av_parkering));
+        props
+    }
+}
+/// Export line segments as a GeoJSON `FeatureCollection` string.
+///
+/// Every segment becomes a two-point `LineString` feature. Besides the
+/// dataset attributes from [`LineFeature::properties`], each feature gets:
+/// - `index`: Position in `lines`, matching the indices returned by the
+///   correlation algorithms
+/// - `zero_length`: `true` if start and end are the same point
+///
+/// Segments whose coordinates cannot be represented as `f64` are skipped.
+///
+/// # Arguments
+///
+/// * `lines` - Miljö or parkering segments to export
+///
+/// # Returns
+///
+/// Serialized GeoJSON document.
+pub fn export_lines<L: LineFeature>(lines: &[L]) -> String {
+    let features = lines
+        .iter()
+        .enumerate()
+        .filter_map(|(index, line)| {
+            let [start, end] = line.line_coordinates();
+            let coords = line_to_f64(line)?.map(|point| point.to_vec()).to_vec();
+            let mut props = line.properties();
+            props.insert("index".to_string(), json!(index));
+            props.insert("zero_length".to_string(), json!(start == end));
+            Some(Feature {
+                bbox: None,
+                geometry: Some(Geometry::new(Value::LineString(coords))),
+                id: None,
+                properties: Some(props),
+                foreign_members: None,
+            })
+        })
+        .collect();
+    GeoJson::from(FeatureCollection {
+        bbox: None,
+        features,
+        foreign_members: None,
+    })
+    .to_string()
+}
+/// Endpoints of the segment with the given id, for drawing it on a map.
+///
+/// # Arguments
+///
+/// * `lines` - The loaded miljö or parkering network
+/// * `id` - Segment index in `lines`, as returned by the correlation algorithms
+///
+/// # Returns
+///
+/// `[[lon, lat], [lon, lat]]`, or `None` if `id` is out of range or the
+/// coordinates cannot be represented as `f64`.
+pub fn get_segment_geometry<L: LineFeature>(lines: &[L], id: usize) -> Option<[[f64; 2]; 2]> {
+    line_to_f64(lines.get(id)?)
+}
+/// Segment endpoints converted from `Decimal`
+fn line_to_f64<L: LineFeature>(line: &L) -> Option<[[f64; 2]; 2]> {
+    let [start, end] = line.line_coordinates();
+    Some([
+        [start[0].to_f64()?, start[1].to_f64()?],
+        [end[0].to_f64()?, end[1].to_f64()?],
+    ])
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn test_export_lines_properties() {
+        let lines = vec![
+            MiljoeDataClean::new(
+                [
+                    [Decimal::new(130000, 4), Decimal::new(550000, 4)],
+                    [Decimal::new(130010, 4), Decimal::new(550010, 4)],
+                ],
+                "Städning".to_string(),
+                "0800-1200".to_string(),
+                15,
+            ),
+            MiljoeDataClean::new(
+                [
+                    [Decimal::new(130000, 4), Decimal::new(550000, 4)],
+                    [Decimal::new(130000, 4), Decimal::new(550000, 4)],
+                ],
+                "Städning".to_string(),
+                "0800-1200".to_string(),
+                16,
+            ),
+        ];
+        let output = export_lines(&lines);
+        let parsed: GeoJson = output.parse().expect("Output should be valid GeoJSON");
+        let GeoJson::FeatureCollection(fc) = parsed else {
+            panic!("Expected FeatureCollection");
+        };
+        assert_eq!(fc.features.len(), 2);
+        assert_eq!(fc.features[0].property("tid"), Some(&json!("0800-1200")));
+        assert_eq!(fc.features[0].property("zero_length"), Some(&json!(false)));
+        assert_eq!(fc.features[1].property("dag"), Some(&json!(16)));
+        assert_eq!(fc.features[1].property("zero_length"), Some(&json!(true)));
+    }
+    #[test]
+    fn test_export_parkering_lines() {
+        let lines = vec![ParkeringsDataClean {
+            coordinates: [
+                [Decimal::new(130000, 4), Decimal::new(550000, 4)],
+                [Decimal::new(130010, 4), Decimal::new(550010, 4)],
+            ],
+            taxa: "Taxa C".to_string(),
+            antal_platser: 26,
+            typ_av_parkering: "Längsgående 6".to_string(),
+        }];
+        let output = export_lines(&lines);
+        assert!(output.contains("\"taxa\":\"Taxa C\""));
+        assert!(output.contains("\"LineString\""));
+    }
+    #[test]
+    fn test_get_segment_geometry() {
+        let lines = vec![MiljoeDataClean::new(
+            [
+                [Decimal::new(130000, 4), Decimal::new(550000, 4)],
+                [Decimal::new(130010, 4), Decimal::new(550010, 4)],
+            ],
+            "Städning".to_string(),
+            "0800-1200".to_string(),
+            15,
+        )];
+        assert_eq!(
+            get_segment_geometry(&lines, 0),
+            Some([[13.0, 55.0], [13.001, 55.001]])
+        );
+        assert_eq!(get_segment_geometry(&lines, 1), None);
+        assert_eq!(get_segment_geometry::<ParkeringsDataClean>(&[], 0), None);
+    }
+}
@@ -0,0 +1,118 @@
+//! Swedish public holidays.
+//!
+//! Street cleaning in Malmö is not carried out on public holidays, so a
+//! restriction whose day falls on one is not in force that day. See
+//! [`DB::with_holiday_skipping`](crate::structs::DB::with_holiday_skipping).
+//!
+//! # Covered Days
+//!
+//! - **Fixed**: nyårsdagen (Jan 1), trettondedag jul (Jan 6), första maj,
+//!   nationaldagen (Jun 6), juldagen (Dec 25), annandag jul (Dec 26)
+//! - **Easter-based**: långfredagen, påskdagen, annandag påsk, Kristi
+//!   himmelsfärds dag (Easter + 39), pingstdagen (Easter + 49)
+//! - **Weekday-based**: midsommardagen (Saturday Jun 20–26), alla helgons dag
+//!   (Saturday Oct 31–Nov 6)
+//! - **Eves**: julafton (Dec 24), nyårsafton (Dec 31) and midsommarafton
+//!   (Friday Jun 19–25). These aren't public holidays by law but are treated
+//!   as Sundays by employers and the city alike.
+//!
+//! # Examples
+//!
+//! ```
+//! use amp_core::holidays::is_swedish_holiday;
+//! use chrono::NaiveDate;
+//!
+//! assert!(is_swedish_holiday(NaiveDate::from_ymd_opt(2024, 6, 6).unwrap()));
+//! assert!(!is_swedish_holiday(NaiveDate::from_ymd_opt(2024, 6, 4).unwrap()));
+//! ```
+use chrono::{Datelike, NaiveDate, Weekday};
+/// Långfredagen, påskdagen, annandag påsk, Kristi himmelsfärds dag and
+/// pingstdagen, in days from Easter Sunday
+const EASTER_OFFSETS: [i64; 5] = [-2, 0, 1, 39, 49];
+/// Whether `date` is a Swedish public holiday or holiday eve.
+///
+/// See the [module docs](self) for the days covered.
+pub fn is_swedish_holiday(date: NaiveDate) -> bool {
+    let (month, day) = (date.month(), date.day());
+    let fixed = matches!(
+        (month, day),
+        (1, 1) | (1, 6) | (5, 1) | (6, 6) | (12, 24) | (12, 25) | (12, 26) | (12, 31)
+    );
+    let midsommar = match date.weekday() {
+        Weekday::Fri => month == 6 && (19..=25).contains(&day),
+        Weekday::Sat => {
+            (month == 6 && (20..=26).contains(&day))
+                || (month == 10 && day == 31)
+                || (month == 11 && day <= 6)
+        }
+        _ => false,
+    };
+    fixed
+        || midsommar
+        || easter_sunday(date.year())
+            .is_some_and(|easter| EASTER_OFFSETS.contains(&(date - easter).num_days()))
+}
+/// Easter Sunday of `year` in the Gregorian calendar.
+///
+/// Uses the anonymous Gregorian algorithm (Meeus/Jones/Butcher).
+///
+/// # Examples
+///
+/// ```
+/// use amp_core::holidays::easter_sunday;
+/// use chrono::NaiveDate;
+///
+/// assert_eq!(easter_sunday(2024), NaiveDate::from_ymd_opt(2024, 3, 31));
+/// ```
+pub fn easter_sunday(year: i32) -> Option<NaiveDate> {
+    let a = year % 19;
+    let b = year / 100;
+    let c = year % 100;
+    let d = b / 4;
+    let e = b % 4;
+    let f = (b + 8) / 25;
+    let g = (b - f + 1) / 3;
+    let h = (19 * a + b - d - g + 15) % 30;
+    let i = c / 4;
+    let k = c % 4;
+    let l = (32 + 2 * e + 2 * i - h - k) % 7;
+    let m = (a + 11 * h + 22 * l) / 451;
+    let month = (h + l - 7 * m + 114) / 31;
+    let day = (h + l - 7 * m + 114) % 31 + 1;
+    NaiveDate::from_ymd_opt(year, month as u32, day as u32)
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+    #[test]
+    fn test_easter_sunday() {
+        assert_eq!(easter_sunday(2024), Some(date(2024, 3, 31)));
+        assert_eq!(easter_sunday(2025), Some(date(2025, 4, 20)));
+        assert_eq!(easter_sunday(2038), Some(date(2038, 4, 25)));
+    }
+    #[test]
+    fn test_kristi_himmelsfards_dag_2024() {
+        assert!(is_swedish_holiday(date(2024, 5, 9)));
+        assert!(!is_swedish_holiday(date(2024, 5, 8)));
+    }
+    #[test]
+    fn test_normal_weekday_is_not_a_holiday() {
+        assert!(!is_swedish_holiday(date(2024, 3, 12)));
+    }
+    #[test]
+    fn test_fixed_and_weekday_based_holidays() {
+        assert!(is_swedish_holiday(date(2024, 1, 1)));
+        assert!(is_swedish_holiday(date(2024, 12, 24)));
+        assert!(is_swedish_holiday(date(2024, 3, 29)));
+        assert!(is_swedish_holiday(date(2024, 4, 1)));
+        assert!(is_swedish_holiday(date(2024, 5, 19)));
+        assert!(is_swedish_holiday(date(2024, 6, 21)));
+        assert!(is_swedish_holiday(date(2024, 6, 22)));
+        assert!(is_swedish_holiday(date(2024, 11, 2)));
+        assert!(!is_swedish_holiday(date(2024, 6, 23)));
+        assert!(!is_swedish_holiday(date(2024, 11, 1)));
+    }
+}
@@ -24,7 +24,8 @@
 //! ## Time Handling
 //!
 //! Times are always stored in UTC but interpreted in Swedish timezone ([`SWEDISH_TZ`]):
-//! - Automatic handling of summer/winter time transitions
+//! - Automatic handling of summer/winter time transitions (see
+//!   [`structs`] for how skipped and repeated hours resolve)
 //! - Year validation (2020-2100) to prevent overflow
 //! - Support for checking if restrictions are currently active
 //!
@@ -83,10 +84,14 @@
 //!
 //! - [`api`]: GeoJSON data loading from external files
 //! - [`parquet`]: Parquet file I/O for all data structures
+//! - [`parking`]: Summaries over loaded parking zone data and paid-period parsing
 //! - [`structs`]: Core data structures and time-based logic
 //! - [`checksum`]: File integrity verification for data validation
 //! - [`benchmark`]: Performance measurement utilities
 //! - [`correlation_algorithms`]: Spatial matching algorithms (address ↔ parking data)
+//! - [`correlation`]: Per-address diffs between correlation snapshots
+//! - [`crs`]: WGS84 ↔ SWEREF 99 TM projection and the [`crs::CoordinateSpace`] choice
+//! - [`geojson`]: GeoJSON export of the parking line network for debugging
 //!
 //! [`AdressClean`]: structs::AdressClean
 //! [`MiljoeDataClean`]: structs::MiljoeDataClean
@@ -97,9 +102,18 @@
 //! [`SWEDISH_TZ`]: structs::SWEDISH_TZ
 pub mod api;
 pub mod benchmark;
+pub mod buckets;
 pub mod checksum;
+pub mod correlation;
 pub mod correlation_algorithms;
 #[cfg(test)]
 mod correlation_tests;
+pub mod crs;
+#[cfg(test)]
+mod dst_tests;
+pub mod error;
+pub mod geojson;
+pub mod holidays;
+pub mod parking;
 pub mod parquet;
 pub mod structs;
@@ -0,0 +1,463 @@
+//! Summaries over loaded parking zone data.
+//!
+//! Pure helpers over [`ParkeringsDataClean`] slices, used for building filter
+//! UIs and for spotting unexpected values left behind by the GeoJSON parser.
+//!
+//! # Paid Periods
+//!
+//! Malmö's `taxa` strings carry the fee schedule, e.g.
+//! `"Taxa C 20 kr/tim 8–22 (8–22), övrig tid 5 kr/tim"`: the main rate, the
+//! weekday hours it applies, the Saturday hours in parentheses and an
+//! optional rate for all other time. [`TaxaSchedule`] parses that and answers
+//! whether a fee applies at a given instant, independently of any miljö
+//! restriction on the same street. Sundays are charged the "övrig tid" rate;
+//! public holidays are not recognised and count as ordinary days.
+//!
+//! # Tariffs
+//!
+//! Where only the zone name is known, e.g. `"Taxa C"` shown next to a match,
+//! [`taxa_rate_sek_per_hour`] looks the main rate up in a tariff table passed
+//! by the caller: [`malmo_taxa_table`] for Malmö, or one built the same way
+//! for another municipality.
+//!
+//! # Placement
+//!
+//! `typ_av_parkering` strings such as `"Längsgående 6"` or
+//! `"Ytparkering vinkelrät 2,5"` describe how the spaces are marked: their
+//! orientation to the curb and the meters of curb each space takes. The
+//! number is a length, not a count; the count is `antal_platser`.
+//! [`ParkingPlacement`] pulls both out.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use amp_core::api::DataLoader;
+//! use amp_core::parking::distinct_categories;
+//!
+//! let parkering = DataLoader::load_parkering("data/parkeringsavgifter.json")?;
+//! let (taxa, typ) = distinct_categories(&parkering);
+//! println!("{} taxor, {} parkeringstyper", taxa.len(), typ.len());
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+//!
+//! [`ParkeringsDataClean`]: crate::structs::ParkeringsDataClean
+use crate::structs::{ParkeringsDataClean, SWEDISH_TZ};
+use chrono::{DateTime, Datelike, Duration, DurationRound, Timelike, Utc, Weekday};
+use rust_decimal::Decimal;
+use std::collections::{BTreeSet, HashMap};
+/// How far ahead [`TaxaSchedule::status_at`] looks for the next rate change
+const RATE_CHANGE_HORIZON_HOURS: i64 = 8 * 24;
+/// One `taxa` string per Malmö zone, verbatim from the `taxa` property of
+/// Malmö stad's open "Parkeringsavgifter" dataset (data/parkeringsavgifter.json)
+const MALMO_TAXA: [&str; 7] = [
+    "Taxa A 30 kr/tim 8–22 (8–22), övrig tid 5 kr/tim",
+    "Taxa B 25 kr/tim 8–22 (8–22), övrig tid 5 kr/tim",
+    "Taxa C 20 kr/tim 8–22 (8–22), övrig tid 5 kr/tim",
+    "Taxa D 15 kr/tim 8–20 (8–20), övrig tid 2 kr/tim",
+    "Taxa E 10 kr/tim 8–20 (8–20), övrig tid 2 kr/tim",
+    "Taxa F 5 kr/tim 8–20 (8–20), övrig tid 2 kr/tim",
+    "Taxa Pendlar 1 kr/tim",
+];
+/// Orientation names recognised by [`ParkingPlacement::parse`], matched as
+/// word prefixes so "snedparkering" is "sned"
+pub const PLACEMENT_ORIENTATIONS: [&str; 3] = ["längsgående", "sned", "vinkelrät"];
+/// Space placement parsed from a `typ_av_parkering` string.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParkingPlacement {
+    /// One of [`PLACEMENT_ORIENTATIONS`], lowercase
+    pub orientation: Option<String>,
+    /// Meters of curb per marked space: the length of a parallel space, the
+    /// width of an angled or perpendicular one
+    pub space_length_m: Option<f64>,
+}
+impl ParkingPlacement {
+    /// Parse a Malmö `typ_av_parkering` string.
+    ///
+    /// The orientation may appear anywhere (`"PRH längsgående 7"`), the
+    /// length must be the last word and may use a decimal comma.
+    ///
+    /// # Returns
+    ///
+    /// `None` if neither is present, e.g. for `"Ytparkering inom zon"`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use amp_core::parking::ParkingPlacement;
+    ///
+    /// let placement = ParkingPlacement::parse("Snedparkering 2,5").unwrap();
+    /// assert_eq!(placement.orientation.as_deref(), Some("sned"));
+    /// assert_eq!(placement.space_length_m, Some(2.5));
+    /// ```
+    pub fn parse(typ_av_parkering: &str) -> Option<Self> {
+        let lower = typ_av_parkering.to_lowercase();
+        let words: Vec<&str> = lower.split_whitespace().collect();
+        let orientation = PLACEMENT_ORIENTATIONS
+            .iter()
+            .find(|name| words.iter().any(|word| word.starts_with(*name)))
+            .map(|name| name.to_string());
+        let space_length_m = words
+            .last()
+            .and_then(|word| word.replace(',', ".").parse::<f64>().ok())
+            .filter(|length| length.is_finite() && *length > 0.0);
+        (orientation.is_some() || space_length_m.is_some()).then_some(Self {
+            orientation,
+            space_length_m,
+        })
+    }
+}
+/// Fee schedule parsed from a `taxa` string.
+///
+/// Hours are local Swedish time, `start` inclusive and `end` exclusive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TaxaSchedule {
+    /// Rate inside the paid hours, in kr per hour
+    pub rate_kr_per_hour: u32,
+    /// Monday–Friday paid hours; `None` means the rate applies around the clock
+    pub weekday_hours: Option<(u32, u32)>,
+    /// Saturday paid hours, written in parentheses on the sign
+    pub saturday_hours: Option<(u32, u32)>,
+    /// "övrig tid" rate outside the paid hours; `None` means parking is free then
+    pub off_peak_kr_per_hour: Option<u32>,
+}
+/// Whether a fee applies at an instant, see [`TaxaSchedule::status_at`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PaidStatus {
+    /// A fee must be paid right now
+    pub must_pay: bool,
+    /// Current rate in kr per hour, `None` while free
+    pub rate_kr_per_hour: Option<u32>,
+    /// When the current rate stops applying, `None` if it does not change
+    /// within the next week
+    pub until: Option<DateTime<Utc>>,
+}
+impl TaxaSchedule {
+    /// Parse a Malmö `taxa` string.
+    ///
+    /// # Returns
+    ///
+    /// `None` if no `"<n> kr/tim"` rate can be found, e.g. for `"Unknown"`.
+    pub fn parse(taxa: &str) -> Option<Self> {
+        let tokens: Vec<&str> = taxa.split_whitespace().collect();
+        let rate_at = tokens.iter().position(|token| *token == "kr/tim")?;
+        let rate_kr_per_hour = tokens.get(rate_at.checked_sub(1)?)?.parse().ok()?;
+        let weekday_hours = tokens.get(rate_at + 1).and_then(|token| parse_hours(token));
+        let saturday_hours = weekday_hours.and(
+            tokens
+                .iter()
+                .find(|token| token.starts_with('('))
+                .and_then(|token| parse_hours(token)),
+        );
+        let off_peak_kr_per_hour = tokens
+            .windows(4)
+            .find(|w| w[0] == "övrig" && w[1] == "tid" && w[3] == "kr/tim")
+            .and_then(|w| w[2].parse().ok());
+        Some(Self {
+            rate_kr_per_hour,
+            weekday_hours,
+            saturday_hours,
+            off_peak_kr_per_hour,
+        })
+    }
+    /// Hourly rate at `when`, `None` if parking is free then.
+    pub fn rate_at(&self, when: DateTime<Utc>) -> Option<u32> {
+        let Some(weekday_hours) = self.weekday_hours else {
+            return Some(self.rate_kr_per_hour);
+        };
+        let local = when.with_timezone(&SWEDISH_TZ);
+        let hours = match local.weekday() {
+            Weekday::Sat => self.saturday_hours,
+            Weekday::Sun => None,
+            _ => Some(weekday_hours),
+        };
+        match hours {
+            Some((start, end)) if (start..end).contains(&local.hour()) => {
+                Some(self.rate_kr_per_hour)
+            }
+            _ => self.off_peak_kr_per_hour,
+        }
+    }
+    /// Whether a fee applies at `when`, and until when.
+    pub fn status_at(&self, when: DateTime<Utc>) -> PaidStatus {
+        let rate = self.rate_at(when);
+        // Paid hours are whole hours, and Swedish offsets are whole hours too
+        let until = when
+            .duration_trunc(Duration::hours(1))
+            .ok()
+            .and_then(|hour| {
+                (1..=RATE_CHANGE_HORIZON_HOURS)
+                    .map(|step| hour + Duration::hours(step))
+                    .find(|next| self.rate_at(*next) != rate)
+            });
+        PaidStatus {
+            must_pay: rate.is_some(),
+            rate_kr_per_hour: rate,
+            until,
+        }
+    }
+}
+/// Parse an hour band like `8–22` or `(8-15),`
+fn parse_hours(token: &str) -> Option<(u32, u32)> {
+    let token = token.trim_matches(|c| c == '(' || c == ')' || c == ',');
+    let (start, end) = token.split_once(['–', '-'])?;
+    let (start, end) = (start.parse().ok()?, end.parse().ok()?);
+    (start < end && end <= 24).then_some((start, end))
+}
+impl ParkeringsDataClean {
+    /// Whether parking on this segment costs money at `when`, and until when.
+    ///
+    /// # Returns
+    ///
+    /// `None` if the segment's `taxa` has no recognisable rate.
+    pub fn is_paid_now(&self, when: DateTime<Utc>) -> Option<PaidStatus> {
+        TaxaSchedule::parse(&self.taxa).map(|schedule| schedule.status_at(when))
+    }
+    /// Main hourly rate of this segment's zone, see [`taxa_rate_sek_per_hour`].
+    pub fn taxa_rate_sek_per_hour(&self, table: &HashMap<String, Decimal>) -> Option<Decimal> {
+        taxa_rate_sek_per_hour(&self.taxa, table)
+    }
+}
+/// Malmö's main rates in SEK per hour, keyed by zone name.
+///
+/// Parsed with [`TaxaSchedule::parse`] from the zones' own `taxa` strings,
+/// so the table says what the signs in the source data say. Off-peak rates
+/// are not included; see [`TaxaSchedule`] for those.
+pub fn malmo_taxa_table() -> HashMap<String, Decimal> {
+    MALMO_TAXA
+        .iter()
+        .filter_map(|taxa| {
+            let schedule = TaxaSchedule::parse(taxa)?;
+            Some((zone_name(taxa), Decimal::from(schedule.rate_kr_per_hour)))
+        })
+        .collect()
+}
+/// Main hourly rate of a `taxa` string from a tariff table.
+///
+/// Keys of `table` are zone names, the first two words of a `taxa` string,
+/// so both `"Taxa C"` and the full
+/// `"Taxa C 20 kr/tim 8–22 (8–22), övrig tid 5 kr/tim"` give the rate of
+/// Taxa C.
+///
+/// # Returns
+///
+/// `None` for zones missing from the table.
+///
+/// # Examples
+///
+/// ```
+/// use amp_core::parking::{malmo_taxa_table, taxa_rate_sek_per_hour};
+/// use rust_decimal::Decimal;
+///
+/// let table = malmo_taxa_table();
+/// assert_eq!(taxa_rate_sek_per_hour("Taxa C", &table), Some(Decimal::from(20)));
+/// assert_eq!(taxa_rate_sek_per_hour("Okänd", &table), None);
+/// ```
+pub fn taxa_rate_sek_per_hour(taxa: &str, table: &HashMap<String, Decimal>) -> Option<Decimal> {
+    table.get(&zone_name(taxa)).copied()
+}
+/// Zone name a `taxa` string starts with, e.g. `"Taxa C"`
+fn zone_name(taxa: &str) -> String {
+    taxa.split_whitespace()
+        .take(2)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+/// Collect the distinct `taxa` and `typ_av_parkering` values.
+///
+/// Values are kept verbatim (no trimming or case folding) so stray
+/// whitespace or empty strings from the source data show up as their own
+/// entries.
+///
+/// # Returns
+///
+/// `(taxa, typ_av_parkering)`, each sorted alphabetically.
+pub fn distinct_categories(
+    parkering: &[ParkeringsDataClean],
+) -> (BTreeSet<String>, BTreeSet<String>) {
+    parkering.iter().fold(
+        (BTreeSet::new(), BTreeSet::new()),
+        |(mut taxa, mut typ), zone| {
+            taxa.insert(zone.taxa.clone());
+            typ.insert(zone.typ_av_parkering.clone());
+            (taxa, typ)
+        },
+    )
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    fn zone(taxa: &str, typ: &str) -> ParkeringsDataClean {
+        ParkeringsDataClean {
+            coordinates: Default::default(),
+            taxa: taxa.to_string(),
+            antal_platser: 1,
+            typ_av_parkering: typ.to_string(),
+        }
+    }
+    /// Local Swedish time in January 2024 (CET, UTC+1) as UTC
+    fn january(day: u32, hour: u32) -> DateTime<Utc> {
+        use chrono::TimeZone;
+        Utc.with_ymd_and_hms(2024, 1, day, hour - 1, 0, 0).unwrap()
+    }
+    #[test]
+    fn test_parse_malmo_taxa() {
+        assert_eq!(
+            TaxaSchedule::parse("Taxa C 20 kr/tim 8–22 (8–22), övrig tid 5 kr/tim"),
+            Some(TaxaSchedule {
+                rate_kr_per_hour: 20,
+                weekday_hours: Some((8, 22)),
+                saturday_hours: Some((8, 22)),
+                off_peak_kr_per_hour: Some(5),
+            })
+        );
+        assert_eq!(
+            TaxaSchedule::parse("Taxa Pendlar 1 kr/tim"),
+            Some(TaxaSchedule {
+                rate_kr_per_hour: 1,
+                weekday_hours: None,
+                saturday_hours: None,
+                off_peak_kr_per_hour: None,
+            })
+        );
+        assert_eq!(TaxaSchedule::parse("Unknown"), None);
+        assert_eq!(TaxaSchedule::parse("Taxa C"), None);
+    }
+    #[test]
+    fn test_malmo_taxa_table_has_every_zone() {
+        let table = malmo_taxa_table();
+        let rates: Vec<(&str, i64)> = vec![
+            ("Taxa A", 30),
+            ("Taxa B", 25),
+            ("Taxa C", 20),
+            ("Taxa D", 15),
+            ("Taxa E", 10),
+            ("Taxa F", 5),
+            ("Taxa Pendlar", 1),
+        ];
+        assert_eq!(table.len(), rates.len());
+        for (name, rate) in rates {
+            assert_eq!(table.get(name), Some(&Decimal::from(rate)), "{name}");
+        }
+    }
+    #[test]
+    fn test_taxa_rate_lookup() {
+        let malmo = malmo_taxa_table();
+        let zone_c = zone("Taxa C 20 kr/tim 8–22 (8–22), övrig tid 5 kr/tim", "");
+        assert_eq!(
+            zone_c.taxa_rate_sek_per_hour(&malmo),
+            Some(Decimal::from(20))
+        );
+        assert_eq!(
+            taxa_rate_sek_per_hour("Taxa Pendlar 1 kr/tim", &malmo),
+            Some(Decimal::from(1))
+        );
+        assert_eq!(zone("Taxa Z", "").taxa_rate_sek_per_hour(&malmo), None);
+        assert_eq!(zone("", "").taxa_rate_sek_per_hour(&malmo), None);
+        let other = HashMap::from([("Taxa Z".to_string(), Decimal::new(125, 1))]);
+        assert_eq!(
+            zone("Taxa Z", "").taxa_rate_sek_per_hour(&other),
+            Some(Decimal::new(125, 1))
+        );
+        assert_eq!(zone_c.taxa_rate_sek_per_hour(&other), None);
+    }
+    #[test]
+    fn test_parse_malmo_placements() {
+        let placement = |orientation: Option<&str>, length: Option<f64>| {
+            Some(ParkingPlacement {
+                orientation: orientation.map(str::to_string),
+                space_length_m: length,
+            })
+        };
+        let cases = [
+            ("Längsgående 6", placement(Some("längsgående"), Some(6.0))),
+            (
+                "Lokalkörbana längsgående 6",
+                placement(Some("längsgående"), Some(6.0)),
+            ),
+            (
+                "PRH längsgående 7",
+                placement(Some("längsgående"), Some(7.0)),
+            ),
+            ("Snedparkering 2,5", placement(Some("sned"), Some(2.5))),
+            (
+                "Vinkelrät parkering 2,5",
+                placement(Some("vinkelrät"), Some(2.5)),
+            ),
+            (
+                "Ytparkering vinkelrät 2,5",
+                placement(Some("vinkelrät"), Some(2.5)),
+            ),
+            ("Parkeringsruta 5", placement(None, Some(5.0))),
+            ("Längsgående", placement(Some("längsgående"), None)),
+            ("Ytparkering inom zon", None),
+            ("Unknown", None),
+            ("", None),
+        ];
+        for (typ, expected) in cases {
+            assert_eq!(ParkingPlacement::parse(typ), expected, "{typ:?}");
+        }
+    }
+    #[test]
+    fn test_daytime_paid_night_free() {
+        let zone = ParkeringsDataClean {
+            taxa: "Taxa X 20 kr/tim 8–18 (8-15)".to_string(),
+            ..zone("", "Längsgående 6")
+        };
+        // Wednesday 10:00, paid until 18:00
+        let status = zone.is_paid_now(january(17, 10)).unwrap();
+        assert!(status.must_pay);
+        assert_eq!(status.rate_kr_per_hour, Some(20));
+        assert_eq!(status.until, Some(january(17, 18)));
+        // Wednesday 20:30, free until Thursday 08:00
+        let status = zone
+            .is_paid_now(january(17, 20) + Duration::minutes(30))
+            .unwrap();
+        assert!(!status.must_pay);
+        assert_eq!(status.rate_kr_per_hour, None);
+        assert_eq!(status.until, Some(january(18, 8)));
+        // Saturday 14:00, paid until 15:00; 16:00 free until Monday 08:00
+        let status = zone.is_paid_now(january(20, 14)).unwrap();
+        assert_eq!(status.until, Some(january(20, 15)));
+        let status = zone.is_paid_now(january(20, 16)).unwrap();
+        assert!(!status.must_pay);
+        assert_eq!(status.until, Some(january(22, 8)));
+        // Sunday noon is outside the paid hours
+        assert!(!zone.is_paid_now(january(21, 12)).unwrap().must_pay);
+    }
+    #[test]
+    fn test_off_peak_rate_still_paid() {
+        let schedule =
+            TaxaSchedule::parse("Taxa D 15 kr/tim 8–20 (8–20), övrig tid 2 kr/tim").unwrap();
+        let status = schedule.status_at(january(17, 21));
+        assert!(status.must_pay);
+        assert_eq!(status.rate_kr_per_hour, Some(2));
+        assert_eq!(status.until, Some(january(18, 8)));
+        let always = TaxaSchedule::parse("Taxa Pendlar 1 kr/tim").unwrap();
+        assert_eq!(
+            always.status_at(january(21, 3)),
+            PaidStatus {
+                must_pay: true,
+                rate_kr_per_hour: Some(1),
+                until: None,
+            }
+        );
+    }
+    #[test]
+    fn test_distinct_categories_dedupes_and_sorts() {
+        let parkering = vec![
+            zone("Taxa C", "Längsgående 6"),
+            zone("Taxa A", "Vinkel"),
+            zone("Taxa C", "Längsgående 6"),
+            zone("", "Längsgående 6"),
+        ];
+        let (taxa, typ) = distinct_categories(&parkering);
+        assert_eq!(
+            taxa.into_iter().collect::<Vec<_>>(),
+            vec!["", "Taxa A", "Taxa C"]
+        );
+        assert_eq!(
+            typ.into_iter().collect::<Vec<_>>(),
+            vec!["Längsgående 6", "Vinkel"]
+        );
+    }
+}
@@ -9,6 +9,7 @@
 //! - [`OutputData`]: Correlated address and parking information
 //! - [`LocalData`]: User's saved addresses with active status
 //! - [`AdressClean`]: Address data with coordinates
+//! - [`MiljoeDataClean`], [`ParkeringsDataClean`]: Zone segments for offline loading
 //! - [`SettingsData`]: User preferences for notifications and UI
 //! - [`DebugAddress`]: Minimal address entries for testing
 //!
@@ -21,13 +22,23 @@
 //! Memory-based operations are used in Android where Parquet files are bundled as
 //! assets or stored in app-private directories.
 //!
+//! [`append_local_parquet`] adds saved addresses to an existing file as a new
+//! row group, replacing the file atomically.
+//!
 //! # Schema Definitions
 //!
 //! Each data type has a corresponding schema function:
-//! - [`output_data_schema`]: 10 columns with mixed nullable/non-nullable fields
-//! - [`local_data_schema`]: 12 columns including `valid` and `active` flags
+//! - [`output_data_schema`]: 13 columns with mixed nullable/non-nullable fields
+//! - [`local_data_schema`]: 17 columns including `valid`, `active` and `schema_version`
 //! - [`adress_clean_schema`]: 6 columns with Float64 coordinates
-//! - [`settings_data_schema`]: 5 columns for app preferences
+//! - [`settings_data_schema`]: 14 columns for app preferences
+//! - [`miljo_clean_schema`], [`parkering_clean_schema`]: 8 and 7 columns, with
+//!   segment endpoints as decimal strings
+//!
+//! [`validate_schema`] compares a file's footer against one of these schemas
+//! without reading any rows, listing every column that differs.
+//! [`assert_schema`] does the same for a single record batch and stops at the
+//! first mismatch; the readers call it before touching any column.
 //!
 //! # Examples
 //!
@@ -69,12 +80,15 @@
 //! [`OutputData`]: crate::structs::OutputData
 //! [`LocalData`]: crate::structs::LocalData
 //! [`AdressClean`]: crate::structs::AdressClean
+//! [`MiljoeDataClean`]: crate::structs::MiljoeDataClean
+//! [`ParkeringsDataClean`]: crate::structs::ParkeringsDataClean
 //! [`SettingsData`]: crate::structs::SettingsData
+use crate::error::AmpError;
 use crate::structs::*;
 use anyhow;
 use arrow::array::{
-    Array, BooleanArray, BooleanBuilder, UInt8Array, UInt8Builder, UInt32Array, UInt32Builder,
-    UInt64Array, UInt64Builder,
+    Array, BooleanArray, BooleanBuilder, Int64Array, Int64Builder, UInt8Array, UInt8Builder,
+    UInt16Array, UInt32Array, UInt32Builder, UInt64Array, UInt64Builder,
 };
 use arrow::{
     array::{StringArray, StringBuilder},
@@ -85,22 +99,31 @@ use bytes::Bytes;
 use parquet::{
     arrow::ArrowWriter,
     arrow::arrow_reader::ParquetRecordBatchReaderBuilder,
+    basic::{Compression, ZstdLevel},
     file::properties::{EnabledStatistics, WriterProperties},
 };
 use rust_decimal::prelude::FromPrimitive;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::{fs::File, sync::Arc};
 /// Schema for [`OutputData`] parquet format.
 ///
-/// Defines 10 columns with mixed nullability:
+/// Defines 13 columns with mixed nullability:
 /// - Non-nullable: `adress`, `gata`, `gatunummer`
-/// - Nullable: `postnummer`, `info`, `tid`, `dag`, `taxa`, `antal_platser`, `typ_av_parkering`
+/// - Nullable: `postnummer`, `info`, `tid`, `dag`, `taxa`, `antal_platser`, `typ_av_parkering`, `dagar`,
+///   `match_quality`, `segment_id`
 ///
 /// # Column Types
 ///
 /// - String columns: `postnummer`, `adress`, `gata`, `gatunummer`, `info`, `tid`, `taxa`, `typ_av_parkering`
-/// - Integer columns: `dag` (UInt8), `antal_platser` (UInt64)
+/// - Integer columns: `dag` (UInt8), `antal_platser` (UInt64), `segment_id` (UInt64)
+/// - `dagar`: every day of the restriction as a list such as `"1,15"`, see
+///   [`format_dagar`]. Files without the column are read with `dag` as the only day
+/// - `match_quality`: [`MatchQuality::code`] of the match, null if unknown
+/// - `segment_id`: [`OutputData::segment_id`], null without a miljö match or if unknown
 ///
 /// [`OutputData`]: crate::structs::OutputData
+/// [`OutputData::segment_id`]: crate::structs::OutputData::segment_id
 pub fn output_data_schema() -> Arc<Schema> {
     Arc::new(Schema::new(vec![
         Field::new("postnummer", DataType::Utf8, true),
@@ -113,6 +136,9 @@ pub fn output_data_schema() -> Arc<Schema> {
         Field::new("taxa", DataType::Utf8, true),
         Field::new("antal_platser", DataType::UInt64, true),
         Field::new("typ_av_parkering", DataType::Utf8, true),
+        Field::new("dagar", DataType::Utf8, true),
+        Field::new("match_quality", DataType::Utf8, true),
+        Field::new("segment_id", DataType::UInt64, true),
     ]))
 }
 /// Schema for [`AdressClean`] parquet format.
@@ -137,15 +163,32 @@ pub fn adress_clean_schema() -> Arc<Schema> {
         Field::new("gatunummer", DataType::Utf8, false),
     ]))
 }
+/// Current version of the [`LocalData`] parquet layout.
+///
+/// Written to the `schema_version` column of every row by
+/// [`build_local_parquet`]. Files without the column are version 1.
+/// Bump this whenever a column is added and teach [`migrate_local_data`]
+/// how to fill it for older rows.
+///
+/// [`LocalData`]: crate::structs::LocalData
+pub const LOCAL_DATA_SCHEMA_VERSION: u8 = 5;
 /// Schema for [`LocalData`] parquet format.
 ///
-/// Defines 12 columns including validation and active status:
-/// - Non-nullable: `valid`, `active` (Boolean), `adress` (Utf8)
+/// Defines 17 columns including validation and active status:
+/// - Non-nullable: `valid`, `active` (Boolean), `adress` (Utf8), `schema_version` (UInt8)
 /// - Nullable: All parking-related fields (postnummer, gata, info, etc.)
 ///
 /// This schema extends [`output_data_schema`] with:
 /// - `valid`: Whether address was matched in database
 /// - `active`: Whether notifications are enabled
+/// - `match_reason`: Why the address matched its entry (null in files
+///   written before the column existed)
+/// - `dagar`: Every day of the restriction, as in [`output_data_schema`]
+/// - `match_quality`: Quality of the match, as in [`output_data_schema`]
+/// - `snoozed_until`: UTC epoch seconds the address is snoozed until (Int64),
+///   null if not snoozed
+/// - `schema_version`: Layout version the row was written with, see
+///   [`LOCAL_DATA_SCHEMA_VERSION`]
 ///
 /// [`LocalData`]: crate::structs::LocalData
 pub fn local_data_schema() -> Arc<Schema> {
@@ -162,8 +205,34 @@ pub fn local_data_schema() -> Arc<Schema> {
         Field::new("taxa", DataType::Utf8, true),
         Field::new("antal_platser", DataType::UInt64, true),
         Field::new("typ_av_parkering", DataType::Utf8, true),
+        Field::new("match_reason", DataType::Utf8, true),
+        Field::new("dagar", DataType::Utf8, true),
+        Field::new("match_quality", DataType::Utf8, true),
+        Field::new("snoozed_until", DataType::Int64, true),
+        Field::new("schema_version", DataType::UInt8, false),
     ]))
 }
+/// Extract a column from a RecordBatch as the array type `T`.
+///
+/// # Errors
+///
+/// Returns [`AmpError::SchemaMismatch`] naming `expected` and the column's
+/// actual type, or `missing`, if the column can't be read as `T`.
+fn get_typed_column<'a, T: Array + 'static>(
+    batch: &'a RecordBatch,
+    column_name: &str,
+    expected: &DataType,
+) -> Result<&'a T, AmpError> {
+    let index = batch
+        .schema()
+        .index_of(column_name)
+        .map_err(|_| AmpError::schema_mismatch(column_name, expected, "missing"))?;
+    let column = batch.column(index);
+    column
+        .as_any()
+        .downcast_ref::<T>()
+        .ok_or_else(|| AmpError::schema_mismatch(column_name, expected, column.data_type()))
+}
 /// Extract a StringArray column from a RecordBatch.
 ///
 /// # Errors
@@ -174,12 +243,8 @@ pub fn local_data_schema() -> Arc<Schema> {
 fn get_string_column<'a>(
     batch: &'a RecordBatch,
     column_name: &str,
-) -> anyhow::Result<&'a StringArray> {
-    batch
-        .column(batch.schema().index_of(column_name)?)
-        .as_any()
-        .downcast_ref::<StringArray>()
-        .ok_or_else(|| anyhow::anyhow!("{} column missing or wrong type", column_name))
+) -> Result<&'a StringArray, AmpError> {
+    get_typed_column(batch, column_name, &DataType::Utf8)
 }
 /// Extract a BooleanArray column from a RecordBatch.
 ///
@@ -189,12 +254,8 @@ fn get_string_column<'a>(
 fn get_boolean_column<'a>(
     batch: &'a RecordBatch,
     column_name: &str,
-) -> anyhow::Result<&'a BooleanArray> {
-    batch
-        .column(batch.schema().index_of(column_name)?)
-        .as_any()
-        .downcast_ref::<BooleanArray>()
-        .ok_or_else(|| anyhow::anyhow!("{} column missing or wrong type", column_name))
+) -> Result<&'a BooleanArray, AmpError> {
+    get_typed_column(batch, column_name, &DataType::Boolean)
 }
 /// Extract a UInt8Array column from a RecordBatch.
 ///
@@ -203,23 +264,25 @@ fn get_boolean_column<'a>(
 /// # Errors
 ///
 /// Returns error if column doesn't exist or is not UInt8 type.
-fn get_u8_column<'a>(batch: &'a RecordBatch, column_name: &str) -> anyhow::Result<&'a UInt8Array> {
-    batch
-        .column(batch.schema().index_of(column_name)?)
-        .as_any()
-        .downcast_ref::<UInt8Array>()
-        .ok_or_else(|| anyhow::anyhow!("{} column missing or wrong type", column_name))
+fn get_u8_column<'a>(
+    batch: &'a RecordBatch,
+    column_name: &str,
+) -> Result<&'a UInt8Array, AmpError> {
+    get_typed_column(batch, column_name, &DataType::UInt8)
+}
+/// Extract a UInt16Array column from a RecordBatch.
+fn get_u16_column<'a>(
+    batch: &'a RecordBatch,
+    column_name: &str,
+) -> Result<&'a UInt16Array, AmpError> {
+    get_typed_column(batch, column_name, &DataType::UInt16)
 }
 /// Extract a UInt32Array column from a RecordBatch.
 fn get_u32_column<'a>(
     batch: &'a RecordBatch,
     column_name: &str,
-) -> anyhow::Result<&'a UInt32Array> {
-    batch
-        .column(batch.schema().index_of(column_name)?)
-        .as_any()
-        .downcast_ref::<UInt32Array>()
-        .ok_or_else(|| anyhow::anyhow!("{} column missing or wrong type", column_name))
+) -> Result<&'a UInt32Array, AmpError> {
+    get_typed_column(batch, column_name, &DataType::UInt32)
 }
 /// Extract a UInt64Array column from a RecordBatch.
 ///
@@ -231,12 +294,21 @@ fn get_u32_column<'a>(
 fn get_u64_column<'a>(
     batch: &'a RecordBatch,
     column_name: &str,
-) -> anyhow::Result<&'a UInt64Array> {
-    batch
-        .column(batch.schema().index_of(column_name)?)
-        .as_any()
-        .downcast_ref::<UInt64Array>()
-        .ok_or_else(|| anyhow::anyhow!("{} column missing or wrong type", column_name))
+) -> Result<&'a UInt64Array, AmpError> {
+    get_typed_column(batch, column_name, &DataType::UInt64)
+}
+/// Extract an Int64Array column from a RecordBatch.
+///
+/// Used for reading epoch timestamps such as `snoozed_until`.
+///
+/// # Errors
+///
+/// Returns error if column doesn't exist or is not Int64 type.
+fn get_i64_column<'a>(
+    batch: &'a RecordBatch,
+    column_name: &str,
+) -> Result<&'a Int64Array, AmpError> {
+    get_typed_column(batch, column_name, &DataType::Int64)
 }
 /// Get optional string value from StringArray at index.
 ///
@@ -278,6 +350,14 @@ fn get_optional_u64(array: &UInt64Array, index: usize) -> Option<u64> {
         Some(array.value(index))
     }
 }
+/// Get optional i64 value from an optional Int64Array at index.
+///
+/// Returns `None` if the value is null or the column is missing.
+fn get_optional_i64(array: Option<&Int64Array>, index: usize) -> Option<i64> {
+    array
+        .filter(|col| !col.is_null(index))
+        .map(|col| col.value(index))
+}
 /// Get boolean value from BooleanArray at index with default fallback.
 ///
 /// Returns the default value if the cell is null.
@@ -288,6 +368,15 @@ fn get_boolean_with_default(array: &BooleanArray, index: usize, default: bool) -
         array.value(index)
     }
 }
+/// Get u32 value from an optional UInt32Array column with default fallback.
+///
+/// Returns the default value if the column is missing or the cell is null.
+fn get_u32_with_default(array: Option<&UInt32Array>, index: usize, default: u32) -> u32 {
+    match array {
+        Some(array) if !array.is_null(index) => array.value(index),
+        _ => default,
+    }
+}
 /// Create a Parquet reader from a file handle.
 ///
 /// Sets up the Arrow reader for batch-wise reading of Parquet data.
@@ -305,6 +394,160 @@ fn create_parquet_reader(
         .map_err(|e| anyhow::anyhow!("Failed to build Parquet record batch reader: {}", e))?;
     Ok(reader)
 }
+/// Check a Parquet file's columns against an expected schema.
+///
+/// Only the file footer is read, so this is cheap even for large files. Every
+/// difference is reported: missing and unexpected columns, and columns whose
+/// type or nullability differs. Column order is not compared.
+///
+/// # Errors
+///
+/// Returns one message per mismatch, e.g.
+/// `"column 'dag': expected UInt8, found Utf8"`. A file that can't be opened
+/// as Parquet yields a single message.
+///
+/// # Examples
+///
+/// ```no_run
+/// use amp_core::parquet::{output_data_schema, validate_schema};
+/// use std::fs::File;
+///
+/// if let Err(mismatches) = validate_schema(File::open("db.parquet")?, &output_data_schema()) {
+///     for mismatch in mismatches {
+///         eprintln!("{}", mismatch);
+///     }
+/// }
+/// # Ok::<(), std::io::Error>(())
+/// ```
+pub fn validate_schema(file: File, expected: &Schema) -> Result<(), Vec<String>> {
+    let builder = ParquetRecordBatchReaderBuilder::try_new(file)
+        .map_err(|e| vec![format!("not a readable Parquet file: {}", e)])?;
+    let actual = builder.schema();
+    let mut mismatches = Vec::new();
+    for field in expected.fields() {
+        let Ok(found) = actual.field_with_name(field.name()) else {
+            mismatches.push(format!("column '{}': missing", field.name()));
+            continue;
+        };
+        if found.data_type() != field.data_type() {
+            mismatches.push(format!(
+                "column '{}': expected {}, found {}",
+                field.name(),
+                field.data_type(),
+                found.data_type()
+            ));
+        }
+        if found.is_nullable() != field.is_nullable() {
+            mismatches.push(format!(
+                "column '{}': expected {}, found {}",
+                field.name(),
+                nullability(field.is_nullable()),
+                nullability(found.is_nullable())
+            ));
+        }
+    }
+    for field in actual.fields() {
+        if expected.field_with_name(field.name()).is_err() {
+            mismatches.push(format!("column '{}': not in expected schema", field.name()));
+        }
+    }
+    if mismatches.is_empty() {
+        Ok(())
+    } else {
+        Err(mismatches)
+    }
+}
+/// Describe a column's nullability for [`validate_schema`] messages
+fn nullability(nullable: bool) -> &'static str {
+    if nullable { "nullable" } else { "non-nullable" }
+}
+/// Check a record batch against the columns a reader needs, before reading any.
+///
+/// Every field of `expected` must be in `batch` with the same type. A
+/// non-nullable field must also be non-nullable in the batch; a nullable one
+/// accepts either. Columns of `batch` that `expected` doesn't list are
+/// ignored, so pass only the columns the reader requires.
+///
+/// # Errors
+///
+/// [`AmpError::SchemaMismatch`] for the first field in `expected` order that
+/// doesn't match, with `found` set to `missing` if the column is absent.
+///
+/// # Examples
+///
+/// ```
+/// use amp_core::error::AmpError;
+/// use amp_core::parquet::assert_schema;
+/// use arrow::array::StringArray;
+/// use arrow::datatypes::{DataType, Field, Schema};
+/// use arrow::record_batch::RecordBatch;
+/// use std::sync::Arc;
+///
+/// let found = Schema::new(vec![Field::new("dag", DataType::Utf8, true)]);
+/// let batch = RecordBatch::try_new(
+///     Arc::new(found),
+///     vec![Arc::new(StringArray::from(vec![Some("15")]))],
+/// )?;
+/// let expected = Schema::new(vec![Field::new("dag", DataType::UInt8, true)]);
+/// match assert_schema(&batch, &expected) {
+///     Err(AmpError::SchemaMismatch { column, expected, found }) => {
+///         assert_eq!((column.as_str(), expected.as_str(), found.as_str()), ("dag", "UInt8", "Utf8"));
+///     }
+///     other => panic!("expected SchemaMismatch, got {:?}", other),
+/// }
+/// # Ok::<(), arrow::error::ArrowError>(())
+/// ```
+pub fn assert_schema(batch: &RecordBatch, expected: &Schema) -> Result<(), AmpError> {
+    let actual = batch.schema();
+    for field in expected.fields() {
+        let Ok(found) = actual.field_with_name(field.name()) else {
+            return Err(AmpError::schema_mismatch(
+                field.name(),
+                field.data_type(),
+                "missing",
+            ));
+        };
+        if found.data_type() != field.data_type() {
+            return Err(AmpError::schema_mismatch(
+                field.name(),
+                field.data_type(),
+                found.data_type(),
+            ));
+        }
+        if found.is_nullable() && !field.is_nullable() {
+            return Err(AmpError::schema_mismatch(
+                field.name(),
+                nullability(field.is_nullable()),
+                nullability(found.is_nullable()),
+            ));
+        }
+    }
+    Ok(())
+}
+/// `schema` without the columns in `optional`
+///
+/// Readers accept files written before those columns existed, so
+/// [`assert_schema`] must not require them.
+fn required_columns(schema: &Schema, optional: &[&str]) -> Schema {
+    Schema::new(
+        schema
+            .fields()
+            .iter()
+            .filter(|field| !optional.contains(&field.name().as_str()))
+            .cloned()
+            .collect::<Vec<_>>(),
+    )
+}
+/// [`output_data_schema`] columns that older database files lack
+const OUTPUT_DATA_OPTIONAL_COLUMNS: [&str; 3] = ["dagar", "match_quality", "segment_id"];
+/// [`local_data_schema`] columns added after version 1 of the layout
+const LOCAL_DATA_OPTIONAL_COLUMNS: [&str; 5] = [
+    "match_reason",
+    "dagar",
+    "match_quality",
+    "snoozed_until",
+    "schema_version",
+];
 /// Append optional string to StringBuilder.
 ///
 /// Appends null if `value` is `None`, otherwise appends the string value.
@@ -314,6 +557,34 @@ fn append_optional_string(builder: &mut StringBuilder, value: &Option<String>) {
         None => builder.append_null(),
     }
 }
+/// Append days of the month as a `dagar` list, or null if there are none.
+fn append_dagar(builder: &mut StringBuilder, dagar: &[u8]) {
+    if dagar.is_empty() {
+        builder.append_null();
+    } else {
+        builder.append_value(format_dagar(dagar));
+    }
+}
+/// Days of the month from an optional `dagar` column, empty if null or missing
+fn get_dagar(array: Option<&StringArray>, index: usize) -> Vec<u8> {
+    array
+        .and_then(|col| get_optional_string(col, index))
+        .map(|days| parse_dagar(&days))
+        .unwrap_or_default()
+}
+/// Append a [`MatchQuality`] code, or null if unknown.
+fn append_match_quality(builder: &mut StringBuilder, quality: Option<MatchQuality>) {
+    match quality {
+        Some(quality) => builder.append_value(quality.code()),
+        None => builder.append_null(),
+    }
+}
+/// Quality from an optional `match_quality` column, `None` if null, missing or unknown
+fn get_match_quality(array: Option<&StringArray>, index: usize) -> Option<MatchQuality> {
+    array
+        .and_then(|col| get_optional_string(col, index))
+        .and_then(|code| MatchQuality::from_code(&code))
+}
 /// Append optional u8 to UInt8Builder.
 ///
 /// Appends null if `value` is `None`.
@@ -323,6 +594,15 @@ fn append_optional_u8(builder: &mut UInt8Builder, value: &Option<u8>) {
         None => builder.append_null(),
     }
 }
+/// Append optional i64 to Int64Builder.
+///
+/// Appends null if `value` is `None`.
+fn append_optional_i64(builder: &mut Int64Builder, value: Option<i64>) {
+    match value {
+        Some(v) => builder.append_value(v),
+        None => builder.append_null(),
+    }
+}
 /// Append optional u64 to UInt64Builder.
 ///
 /// Appends null if `value` is `None`.
@@ -332,21 +612,64 @@ fn append_optional_u64(builder: &mut UInt64Builder, value: &Option<u64>) {
         None => builder.append_null(),
     }
 }
-/// Create ArrowWriter with standard properties.
+/// Compression and statistics used when writing a Parquet file.
 ///
-/// Creates a Parquet writer with:
-/// - Statistics disabled (for faster writes on mobile)
-/// - Default compression (Snappy)
+/// The plain writers use [`ParquetWriteOptions::default`]: Snappy, which is
+/// fast to write and good for server-side temp files, and no statistics
+/// (faster writes on mobile). Files bundled into the Android app are better
+/// off with [`ParquetWriteOptions::zstd`], which is slower to write but
+/// noticeably smaller. Readers handle any codec, so callers can switch
+/// freely.
+///
+/// # Examples
+///
+/// ```no_run
+/// use amp_core::parquet::{ParquetWriteOptions, write_output_parquet_with_options};
+/// # let data: Vec<amp_core::structs::OutputData> = vec![];
+///
+/// write_output_parquet_with_options(data, "db.parquet", ParquetWriteOptions::zstd())?;
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ParquetWriteOptions {
+    pub compression: Compression,
+    pub statistics: EnabledStatistics,
+}
+impl Default for ParquetWriteOptions {
+    fn default() -> Self {
+        Self {
+            compression: Compression::SNAPPY,
+            statistics: EnabledStatistics::None,
+        }
+    }
+}
+impl ParquetWriteOptions {
+    /// Zstd at its default level, for embedded assets
+    pub fn zstd() -> Self {
+        Self {
+            compression: Compression::ZSTD(ZstdLevel::default()),
+            ..Self::default()
+        }
+    }
+    fn writer_properties(&self) -> WriterProperties {
+        WriterProperties::builder()
+            .set_compression(self.compression)
+            .set_statistics_enabled(self.statistics)
+            .build()
+    }
+}
+/// Create ArrowWriter for `path` with the given [`ParquetWriteOptions`].
 ///
 /// # Errors
 ///
 /// Returns error if file cannot be created.
-fn create_arrow_writer(path: &str, schema: Arc<Schema>) -> anyhow::Result<ArrowWriter<File>> {
+fn create_arrow_writer(
+    path: &str,
+    schema: Arc<Schema>,
+    options: ParquetWriteOptions,
+) -> anyhow::Result<ArrowWriter<File>> {
     let file = File::create(path).map_err(|e| anyhow::anyhow!("Failed to create file: {}", e))?;
-    let props = WriterProperties::builder()
-        .set_statistics_enabled(EnabledStatistics::None)
-        .build();
-    ArrowWriter::try_new(file, schema, Some(props))
+    ArrowWriter::try_new(file, schema, Some(options.writer_properties()))
         .map_err(|e| anyhow::anyhow!("Failed to create ArrowWriter: {}", e))
 }
 /// Write a single batch and close the writer.
@@ -503,13 +826,16 @@ pub fn load_debug_addresses_from_file(path: &str) -> anyhow::Result<Vec<DebugAdd
 ///
 /// # Errors
 ///
-/// Returns error if file is not valid Parquet or schema doesn't match.
+/// Returns error if file is not valid Parquet, or [`AmpError::SchemaMismatch`]
+/// if a column checked by [`assert_schema`] is missing or has the wrong type.
 ///
 /// [`OutputData`]: crate::structs::OutputData
 pub fn read_db_parquet(file: File) -> anyhow::Result<Vec<OutputData>> {
     let mut reader = create_parquet_reader(file)?;
+    let required = required_columns(&output_data_schema(), &OUTPUT_DATA_OPTIONAL_COLUMNS);
     let mut result = Vec::new();
     while let Some(batch) = reader.next().transpose()? {
+        assert_schema(&batch, &required)?;
         let postnummer = get_string_column(&batch, "postnummer")?;
         let address = get_string_column(&batch, "adress")?;
         let gata = get_string_column(&batch, "gata")?;
@@ -520,7 +846,15 @@ pub fn read_db_parquet(file: File) -> anyhow::Result<Vec<OutputData>> {
         let taxa = get_string_column(&batch, "taxa")?;
         let antal_platser = get_u64_column(&batch, "antal_platser")?;
         let typ_av_parkering = get_string_column(&batch, "typ_av_parkering")?;
+        let dagar = get_string_column(&batch, "dagar").ok();
+        let match_quality = get_string_column(&batch, "match_quality").ok();
+        let segment_id = get_u64_column(&batch, "segment_id").ok();
         for i in 0..batch.num_rows() {
+            let dag = get_optional_u8(dag, i);
+            let mut days = get_dagar(dagar, i);
+            if days.is_empty() {
+                days.extend(dag);
+            }
             let entry = OutputData {
                 postnummer: get_optional_string(postnummer, i),
                 adress: get_required_string(address, i),
@@ -528,16 +862,197 @@ pub fn read_db_parquet(file: File) -> anyhow::Result<Vec<OutputData>> {
                 gatunummer: get_required_string(gatunummer, i),
                 info: get_optional_string(info, i),
                 tid: get_optional_string(tid, i),
-                dag: get_optional_u8(dag, i),
+                dag,
+                dagar: days,
                 taxa: get_optional_string(taxa, i),
                 antal_platser: get_optional_u64(antal_platser, i),
                 typ_av_parkering: get_optional_string(typ_av_parkering, i),
+                match_quality: get_match_quality(match_quality, i),
+                segment_id: segment_id.and_then(|col| get_optional_u64(col, i)),
             };
             result.push(entry);
         }
     }
     Ok(result)
 }
+/// Decode one [`LocalData`] record batch, migrating rows to the current layout.
+///
+/// Columns added after version 1 are read optionally so that older files
+/// still load; a missing `schema_version` column means version 1.
+///
+/// # Errors
+///
+/// Returns error if a required column is missing or a row was written by a
+/// newer schema version than [`LOCAL_DATA_SCHEMA_VERSION`].
+///
+/// [`LocalData`]: crate::structs::LocalData
+fn read_local_batch(batch: &RecordBatch) -> Result<Vec<LocalData>, AmpError> {
+    assert_schema(
+        batch,
+        &required_columns(&local_data_schema(), &LOCAL_DATA_OPTIONAL_COLUMNS),
+    )?;
+    let valid = get_boolean_column(batch, "valid")?;
+    let active = get_boolean_column(batch, "active")?;
+    let postnummer = get_string_column(batch, "postnummer")?;
+    let address = get_string_column(batch, "adress")?;
+    let gata = get_string_column(batch, "gata")?;
+    let gatunummer = get_string_column(batch, "gatunummer")?;
+    let info = get_string_column(batch, "info")?;
+    let dag = get_u8_column(batch, "dag")?;
+    let tid = get_string_column(batch, "tid")?;
+    let taxa = get_string_column(batch, "taxa")?;
+    let antal_platser = get_u64_column(batch, "antal_platser")?;
+    let typ_av_parkering = get_string_column(batch, "typ_av_parkering")?;
+    let match_reason = get_string_column(batch, "match_reason").ok();
+    let dagar = get_string_column(batch, "dagar").ok();
+    let match_quality = get_string_column(batch, "match_quality").ok();
+    let snoozed_until = get_i64_column(batch, "snoozed_until").ok();
+    let schema_version = get_u8_column(batch, "schema_version").ok();
+    let mut result = Vec::with_capacity(batch.num_rows());
+    for i in 0..batch.num_rows() {
+        let entry = LocalData {
+            valid: get_boolean_with_default(valid, i, false),
+            active: get_boolean_with_default(active, i, false),
+            postnummer: get_optional_string(postnummer, i),
+            adress: get_required_string(address, i),
+            gata: get_optional_string(gata, i),
+            gatunummer: get_optional_string(gatunummer, i),
+            info: get_optional_string(info, i),
+            tid: get_optional_string(tid, i),
+            dag: get_optional_u8(dag, i),
+            dagar: get_dagar(dagar, i),
+            taxa: get_optional_string(taxa, i),
+            antal_platser: get_optional_u64(antal_platser, i),
+            typ_av_parkering: get_optional_string(typ_av_parkering, i),
+            match_reason: match_reason.and_then(|col| get_optional_string(col, i)),
+            match_quality: get_match_quality(match_quality, i),
+            snoozed_until: get_optional_i64(snoozed_until, i),
+        };
+        let version = schema_version
+            .and_then(|col| get_optional_u8(col, i))
+            .unwrap_or(1);
+        let entry =
+            migrate_local_data(entry, version).map_err(|e| AmpError::ParquetRead(e.to_string()))?;
+        result.push(entry);
+    }
+    Ok(result)
+}
+/// Upgrade a [`LocalData`] row read from an older file to the current layout.
+///
+/// Each version step fills the fields it introduced with their defaults,
+/// so a row can be upgraded from any older version by applying the steps
+/// in order. Rows already at [`LOCAL_DATA_SCHEMA_VERSION`] are returned as-is.
+///
+/// Version history:
+/// - 1: original 12 columns, optionally followed by `match_reason`
+/// - 2: adds the `schema_version` column
+/// - 3: adds `dagar`; older rows get `dag` as their only day
+/// - 4: adds `match_quality`; older rows leave it unknown
+/// - 5: adds `snoozed_until`; older rows are not snoozed
+///
+/// # Errors
+///
+/// Returns error if `from_version` is 0 or newer than
+/// [`LOCAL_DATA_SCHEMA_VERSION`], i.e. the file was written by a newer app.
+///
+/// # Examples
+///
+/// ```
+/// use amp_core::parquet::{LOCAL_DATA_SCHEMA_VERSION, migrate_local_data};
+/// # use amp_core::structs::LocalData;
+/// # let entry = LocalData {
+/// #     valid: true, active: false, postnummer: None, adress: "Storgatan 10".to_string(),
+/// #     gata: None, gatunummer: None, info: None, tid: None, dag: None, dagar: vec![],
+/// #     taxa: None, antal_platser: None, typ_av_parkering: None, match_reason: None,
+/// #     match_quality: None, snoozed_until: None,
+/// # };
+/// let upgraded = migrate_local_data(entry, 1)?;
+/// assert!(migrate_local_data(upgraded, LOCAL_DATA_SCHEMA_VERSION + 1).is_err());
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+///
+/// [`LocalData`]: crate::structs::LocalData
+pub fn migrate_local_data(mut entry: LocalData, from_version: u8) -> anyhow::Result<LocalData> {
+    if from_version == 0 || from_version > LOCAL_DATA_SCHEMA_VERSION {
+        return Err(anyhow::anyhow!(
+            "Unsupported local data schema version {} (supported: 1..={})",
+            from_version,
+            LOCAL_DATA_SCHEMA_VERSION,
+        ));
+    }
+    // 1 → 2 only introduced `schema_version` itself. Later steps go here in
+    // ascending order as `if from_version < N { entry.new_field = default; }`.
+    if from_version < 3 {
+        entry.dagar = entry.dag.into_iter().collect();
+    }
+    Ok(entry)
+}
+/// Read a `local.parquet` written by the pre-workspace app into [`LocalData`].
+///
+/// That layout has 12 columns, with `postnummer` as UInt16, no `valid` or
+/// `active`, and the debug columns `lat`, `lon`, `distance` and
+/// `debug_closest_line_id`. It is mapped forward as:
+/// - `postnummer` becomes its decimal string, e.g. `21438`
+/// - `valid` is whether the row has non-empty `info`, i.e. it matched a zone
+/// - `active` defaults to `true`, as for a newly added address
+/// - the debug columns are dropped; `taxa`, `antal_platser` and
+///   `typ_av_parkering` are read when present
+///
+/// The result is at [`LOCAL_DATA_SCHEMA_VERSION`], so it can be written back
+/// with [`build_local_parquet`].
+///
+/// # Errors
+///
+/// Returns error if data is not valid Parquet or `adress` is missing.
+///
+/// [`LocalData`]: crate::structs::LocalData
+pub fn migrate_legacy_local(bytes: &[u8]) -> anyhow::Result<Vec<LocalData>> {
+    let builder = ParquetRecordBatchReaderBuilder::try_new(Bytes::copy_from_slice(bytes))
+        .map_err(|e| anyhow::anyhow!("Failed to create Parquet reader builder: {}", e))?;
+    let reader = builder
+        .build()
+        .map_err(|e| anyhow::anyhow!("Failed to build Parquet record batch reader: {}", e))?;
+    let mut result = Vec::new();
+    for batch_result in reader {
+        let batch = batch_result.map_err(|e| anyhow::anyhow!("Failed to read batch: {}", e))?;
+        let address = get_string_column(&batch, "adress")?;
+        let postnummer = get_u16_column(&batch, "postnummer").ok();
+        let gata = get_string_column(&batch, "gata").ok();
+        let gatunummer = get_string_column(&batch, "gatunummer").ok();
+        let info = get_string_column(&batch, "info").ok();
+        let tid = get_string_column(&batch, "tid").ok();
+        let dag = get_u8_column(&batch, "dag").ok();
+        let taxa = get_string_column(&batch, "taxa").ok();
+        let antal_platser = get_u64_column(&batch, "antal_platser").ok();
+        let typ_av_parkering = get_string_column(&batch, "typ_av_parkering").ok();
+        let string_at = |col: Option<&StringArray>, i| col.and_then(|c| get_optional_string(c, i));
+        for i in 0..batch.num_rows() {
+            let info = string_at(info, i);
+            let entry = LocalData {
+                valid: info.as_deref().is_some_and(|text| !text.trim().is_empty()),
+                active: true,
+                postnummer: postnummer
+                    .filter(|col| col.is_valid(i))
+                    .map(|col| col.value(i).to_string()),
+                adress: get_required_string(address, i),
+                gata: string_at(gata, i),
+                gatunummer: string_at(gatunummer, i),
+                info,
+                tid: string_at(tid, i),
+                dag: dag.and_then(|col| get_optional_u8(col, i)),
+                dagar: Vec::new(),
+                taxa: string_at(taxa, i),
+                antal_platser: antal_platser.and_then(|col| get_optional_u64(col, i)),
+                typ_av_parkering: string_at(typ_av_parkering, i),
+                match_reason: None,
+                match_quality: None,
+                snoozed_until: None,
+            };
+            result.push(migrate_local_data(entry, 1)?);
+        }
+    }
+    Ok(result)
+}
 /// Read [`LocalData`] from a parquet file.
 ///
 /// Loads user's saved addresses with matched parking information.
@@ -560,35 +1075,7 @@ pub fn read_local_parquet(file: File) -> anyhow::Result<Vec<LocalData>> {
     let mut reader = create_parquet_reader(file)?;
     let mut result = Vec::new();
     while let Some(batch) = reader.next().transpose()? {
-        let valid = get_boolean_column(&batch, "valid")?;
-        let active = get_boolean_column(&batch, "active")?;
-        let postnummer = get_string_column(&batch, "postnummer")?;
-        let address = get_string_column(&batch, "adress")?;
-        let gata = get_string_column(&batch, "gata")?;
-        let gatunummer = get_string_column(&batch, "gatunummer")?;
-        let info = get_string_column(&batch, "info")?;
-        let dag = get_u8_column(&batch, "dag")?;
-        let tid = get_string_column(&batch, "tid")?;
-        let taxa = get_string_column(&batch, "taxa")?;
-        let antal_platser = get_u64_column(&batch, "antal_platser")?;
-        let typ_av_parkering = get_string_column(&batch, "typ_av_parkering")?;
-        for i in 0..batch.num_rows() {
-            let entry = LocalData {
-                valid: get_boolean_with_default(valid, i, false),
-                active: get_boolean_with_default(active, i, false),
-                postnummer: get_optional_string(postnummer, i),
-                adress: get_required_string(address, i),
-                gata: get_optional_string(gata, i),
-                gatunummer: get_optional_string(gatunummer, i),
-                info: get_optional_string(info, i),
-                tid: get_optional_string(tid, i),
-                dag: get_optional_u8(dag, i),
-                taxa: get_optional_string(taxa, i),
-                antal_platser: get_optional_u64(antal_platser, i),
-                typ_av_parkering: get_optional_string(typ_av_parkering, i),
-            };
-            result.push(entry);
-        }
+        result.extend(read_local_batch(&batch)?);
     }
     Ok(result)
 }
@@ -608,7 +1095,9 @@ pub fn read_local_parquet(file: File) -> anyhow::Result<Vec<LocalData>> {
 ///
 /// # Errors
 ///
-/// Returns error if data is not valid Parquet or schema doesn't match.
+/// Returns [`AmpError::ParquetRead`] if data is not valid Parquet, or
+/// [`AmpError::SchemaMismatch`] if a required column is missing or has the
+/// wrong type or nullability (see [`assert_schema`]).
 ///
 /// # Examples
 ///
@@ -617,52 +1106,80 @@ pub fn read_local_parquet(file: File) -> anyhow::Result<Vec<LocalData>> {
 ///
 /// const EMBEDDED_DATA: &[u8] = include_bytes!("local.parquet");
 /// let data = read_local_parquet_from_bytes(EMBEDDED_DATA)?;
-/// # Ok::<(), anyhow::Error>(())
+/// # Ok::<(), amp_core::error::AmpError>(())
 /// ```
 ///
 /// [`LocalData`]: crate::structs::LocalData
-pub fn read_local_parquet_from_bytes(bytes: &[u8]) -> anyhow::Result<Vec<LocalData>> {
-    let bytes_obj = Bytes::copy_from_slice(bytes);
-    let builder = ParquetRecordBatchReaderBuilder::try_new(bytes_obj)
-        .map_err(|e| anyhow::anyhow!("Failed to create Parquet reader builder: {}", e))?;
-    let reader = builder
-        .build()
-        .map_err(|e| anyhow::anyhow!("Failed to build Parquet record batch reader: {}", e))?;
+pub fn read_local_parquet_from_bytes(bytes: &[u8]) -> Result<Vec<LocalData>, AmpError> {
     let mut result = Vec::new();
-    for batch_result in reader {
-        let batch = batch_result.map_err(|e| anyhow::anyhow!("Failed to read batch: {}", e))?;
-        let valid = get_boolean_column(&batch, "valid")?;
-        let active = get_boolean_column(&batch, "active")?;
-        let postnummer = get_string_column(&batch, "postnummer")?;
-        let address = get_string_column(&batch, "adress")?;
-        let gata = get_string_column(&batch, "gata")?;
-        let gatunummer = get_string_column(&batch, "gatunummer")?;
-        let info = get_string_column(&batch, "info")?;
-        let dag = get_u8_column(&batch, "dag")?;
-        let tid = get_string_column(&batch, "tid")?;
-        let taxa = get_string_column(&batch, "taxa")?;
-        let antal_platser = get_u64_column(&batch, "antal_platser")?;
-        let typ_av_parkering = get_string_column(&batch, "typ_av_parkering")?;
-        for i in 0..batch.num_rows() {
-            let entry = LocalData {
-                valid: get_boolean_with_default(valid, i, false),
-                active: get_boolean_with_default(active, i, false),
-                postnummer: get_optional_string(postnummer, i),
-                adress: get_required_string(address, i),
-                gata: get_optional_string(gata, i),
-                gatunummer: get_optional_string(gatunummer, i),
-                info: get_optional_string(info, i),
-                tid: get_optional_string(tid, i),
-                dag: get_optional_u8(dag, i),
-                taxa: get_optional_string(taxa, i),
-                antal_platser: get_optional_u64(antal_platser, i),
-                typ_av_parkering: get_optional_string(typ_av_parkering, i),
-            };
-            result.push(entry);
-        }
+    for batch in local_batches(bytes) {
+        result.extend(batch?);
     }
     Ok(result)
 }
+/// Rows per chunk yielded by [`read_local_parquet_batches`]
+const LOCAL_BATCH_ROWS: usize = 1024;
+/// Read [`LocalData`] from embedded bytes one record batch at a time.
+///
+/// Streaming version of [`read_local_parquet_from_bytes`]: each item holds at
+/// most 1024 rows, so a caller that processes and drops chunks never keeps
+/// the whole dataset in memory at once. Avoids the allocation spike of
+/// loading the full address list on low-end Android devices.
+///
+/// # Returns
+///
+/// Iterator over chunks of [`LocalData`]. Errors from opening the file or
+/// decoding a batch are yielded as items instead of panicking; the iterator
+/// ends after an error in opening the file.
+///
+/// # Examples
+///
+/// ```no_run
+/// use amp_core::parquet::read_local_parquet_batches;
+///
+/// let bytes = std::fs::read("local.parquet")?;
+/// let mut active = 0;
+/// for chunk in read_local_parquet_batches(&bytes) {
+///     active += chunk?.iter().filter(|entry| entry.active).count();
+/// }
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+///
+/// [`LocalData`]: crate::structs::LocalData
+pub fn read_local_parquet_batches(
+    bytes: &[u8],
+) -> impl Iterator<Item = anyhow::Result<Vec<LocalData>>> {
+    local_batches(bytes).map(|batch| batch.map_err(anyhow::Error::from))
+}
+/// [`read_local_parquet_batches`] with [`AmpError`] items
+fn local_batches(bytes: &[u8]) -> impl Iterator<Item = Result<Vec<LocalData>, AmpError>> {
+    let reader = ParquetRecordBatchReaderBuilder::try_new(Bytes::copy_from_slice(bytes))
+        .map_err(|e| {
+            AmpError::ParquetRead(format!("Failed to create Parquet reader builder: {}", e))
+        })
+        .and_then(|builder| {
+            builder
+                .with_batch_size(LOCAL_BATCH_ROWS)
+                .build()
+                .map_err(|e| {
+                    AmpError::ParquetRead(format!(
+                        "Failed to build Parquet record batch reader: {}",
+                        e
+                    ))
+                })
+        });
+    let (reader, open_error) = match reader {
+        Ok(reader) => (Some(reader), None),
+        Err(e) => (None, Some(Err(e))),
+    };
+    open_error
+        .into_iter()
+        .chain(reader.into_iter().flatten().map(|batch_result| {
+            let batch = batch_result
+                .map_err(|e| AmpError::ParquetRead(format!("Failed to read batch: {}", e)))?;
+            read_local_batch(&batch)
+        }))
+}
 /// Read [`AdressClean`] from a parquet file.
 ///
 /// Loads address data with coordinates, typically from the processed
@@ -674,8 +1191,9 @@ pub fn read_local_parquet_from_bytes(bytes: &[u8]) -> anyhow::Result<Vec<LocalDa
 ///
 /// # Returns
 ///
-/// Vector of [`AdressClean`] entries with Float64 coordinates converted
-/// back to [`Decimal`].
+/// Vector of [`AdressClean`] entries with coordinates converted back to
+/// [`Decimal`]. Both the Utf8 columns written by [`write_adress_clean_parquet`]
+/// and Float64 columns are accepted.
 ///
 /// # Errors
 ///
@@ -687,26 +1205,15 @@ pub fn read_address_parquet(file: File) -> anyhow::Result<Vec<AdressClean>> {
     let mut reader = create_parquet_reader(file)?;
     let mut result = Vec::new();
     while let Some(batch) = reader.next().transpose()? {
-        let longitude = batch
-            .column(batch.schema().index_of("longitude")?)
-            .as_any()
-            .downcast_ref::<arrow::array::Float64Array>()
-            .ok_or_else(|| anyhow::anyhow!("longitude column missing or wrong type"))?;
-        let latitude = batch
-            .column(batch.schema().index_of("latitude")?)
-            .as_any()
-            .downcast_ref::<arrow::array::Float64Array>()
-            .ok_or_else(|| anyhow::anyhow!("latitude column missing or wrong type"))?;
+        let longitude = get_decimal_column(&batch, "longitude")?;
+        let latitude = get_decimal_column(&batch, "latitude")?;
         let postnummer = get_string_column(&batch, "postnummer")?;
         let adress = get_string_column(&batch, "adress")?;
         let gata = get_string_column(&batch, "gata")?;
         let gatunummer = get_string_column(&batch, "gatunummer")?;
         for i in 0..batch.num_rows() {
             let entry = AdressClean {
-                coordinates: [
-                    rust_decimal::Decimal::from_f64(longitude.value(i)).unwrap_or_default(),
-                    rust_decimal::Decimal::from_f64(latitude.value(i)).unwrap_or_default(),
-                ],
+                coordinates: [longitude[i], latitude[i]],
                 postnummer: get_optional_string(postnummer, i),
                 adress: get_required_string(adress, i),
                 gata: get_required_string(gata, i),
@@ -736,11 +1243,21 @@ pub fn read_address_parquet(file: File) -> anyhow::Result<Vec<AdressClean>> {
 ///
 /// [`OutputData`]: crate::structs::OutputData
 pub fn write_output_parquet(data: Vec<OutputData>, path: &str) -> anyhow::Result<()> {
+    write_output_parquet_with_options(data, path, ParquetWriteOptions::default())
+}
+/// [`write_output_parquet`] with explicit compression and statistics.
+///
+/// [`OutputData`]: crate::structs::OutputData
+pub fn write_output_parquet_with_options(
+    data: Vec<OutputData>,
+    path: &str,
+    options: ParquetWriteOptions,
+) -> anyhow::Result<()> {
     if data.is_empty() {
         return Err(anyhow::anyhow!("Empty output data"));
     }
     let schema = output_data_schema();
-    let writer = create_arrow_writer(path, schema.clone())?;
+    let writer = create_arrow_writer(path, schema.clone(), options)?;
     let mut postnummer_builder = StringBuilder::new();
     let mut adress_builder = StringBuilder::new();
     let mut gata_builder = StringBuilder::new();
@@ -751,6 +1268,9 @@ pub fn write_output_parquet(data: Vec<OutputData>, path: &str) -> anyhow::Result
     let mut taxa_builder = StringBuilder::new();
     let mut antal_platser_builder = UInt64Builder::new();
     let mut typ_av_parkering_builder = StringBuilder::new();
+    let mut dagar_builder = StringBuilder::new();
+    let mut match_quality_builder = StringBuilder::new();
+    let mut segment_id_builder = UInt64Builder::new();
     for row in data {
         append_optional_string(&mut postnummer_builder, &row.postnummer);
         adress_builder.append_value(&row.adress);
@@ -762,6 +1282,9 @@ pub fn write_output_parquet(data: Vec<OutputData>, path: &str) -> anyhow::Result
         append_optional_string(&mut taxa_builder, &row.taxa);
         append_optional_u64(&mut antal_platser_builder, &row.antal_platser);
         append_optional_string(&mut typ_av_parkering_builder, &row.typ_av_parkering);
+        append_dagar(&mut dagar_builder, &row.dagar);
+        append_match_quality(&mut match_quality_builder, row.match_quality);
+        append_optional_u64(&mut segment_id_builder, &row.segment_id);
     }
     let batch = RecordBatch::try_new(
         schema.clone(),
@@ -776,11 +1299,55 @@ pub fn write_output_parquet(data: Vec<OutputData>, path: &str) -> anyhow::Result
             Arc::new(taxa_builder.finish()),
             Arc::new(antal_platser_builder.finish()),
             Arc::new(typ_av_parkering_builder.finish()),
+            Arc::new(dagar_builder.finish()),
+            Arc::new(match_quality_builder.finish()),
+            Arc::new(segment_id_builder.finish()),
         ],
     )
     .map_err(|e| anyhow::anyhow!("Failed to create record batch: {}", e))?;
     write_batch_and_close(writer, batch)
 }
+/// Keep only rows with miljö or parkering data attached.
+///
+/// A row is dropped when `info`, `tid`, `dag`, `taxa`, `antal_platser` and
+/// `typ_av_parkering` are all `None`, i.e. the address matched nothing.
+///
+/// [`OutputData`]: crate::structs::OutputData
+pub fn filter_matched(data: Vec<OutputData>) -> Vec<OutputData> {
+    data.into_iter()
+        .filter(|row| {
+            row.info.is_some()
+                || row.tid.is_some()
+                || row.dag.is_some()
+                || row.taxa.is_some()
+                || row.antal_platser.is_some()
+                || row.typ_av_parkering.is_some()
+        })
+        .collect()
+}
+/// Write only the matched [`OutputData`] rows to a parquet file.
+///
+/// [`write_output_parquet`] after [`filter_matched`], so `db.parquet` does
+/// not carry addresses the app has nothing to show for.
+///
+/// # Errors
+///
+/// As [`write_output_parquet`]; in particular, an error if no row matched.
+///
+/// [`OutputData`]: crate::structs::OutputData
+pub fn write_output_parquet_matched_only(data: Vec<OutputData>, path: &str) -> anyhow::Result<()> {
+    write_output_parquet_matched_only_with_options(data, path, ParquetWriteOptions::default())
+}
+/// [`write_output_parquet_matched_only`] with explicit compression and statistics.
+///
+/// [`OutputData`]: crate::structs::OutputData
+pub fn write_output_parquet_matched_only_with_options(
+    data: Vec<OutputData>,
+    path: &str,
+    options: ParquetWriteOptions,
+) -> anyhow::Result<()> {
+    write_output_parquet_with_options(filter_matched(data), path, options)
+}
 /// Write [`AdressClean`] to a parquet file.
 ///
 /// Serializes address data with coordinates for persistent storage.
@@ -801,11 +1368,21 @@ pub fn write_output_parquet(data: Vec<OutputData>, path: &str) -> anyhow::Result
 /// [`AdressClean`]: crate::structs::AdressClean
 /// [`Decimal`]: rust_decimal::Decimal
 pub fn write_adress_clean_parquet(data: Vec<AdressClean>, path: &str) -> anyhow::Result<()> {
+    write_adress_clean_parquet_with_options(data, path, ParquetWriteOptions::default())
+}
+/// [`write_adress_clean_parquet`] with explicit compression and statistics.
+///
+/// [`AdressClean`]: crate::structs::AdressClean
+pub fn write_adress_clean_parquet_with_options(
+    data: Vec<AdressClean>,
+    path: &str,
+    options: ParquetWriteOptions,
+) -> anyhow::Result<()> {
     if data.is_empty() {
         return Err(anyhow::anyhow!("Empty address data"));
     }
     let schema = adress_clean_schema();
-    let writer = create_arrow_writer(path, schema.clone())?;
+    let writer = create_arrow_writer(path, schema.clone(), options)?;
     let mut longitude_builder = StringBuilder::new();
     let mut latitude_builder = StringBuilder::new();
     let mut postnummer_builder = StringBuilder::new();
@@ -834,6 +1411,247 @@ pub fn write_adress_clean_parquet(data: Vec<AdressClean>, path: &str) -> anyhow:
     .map_err(|e| anyhow::anyhow!("Failed to create record batch: {}", e))?;
     write_batch_and_close(writer, batch)
 }
+/// Schema for [`MiljoeDataClean`] parquet format.
+///
+/// Segment endpoints are stored as Utf8 decimal strings (`x1`, `y1`, `x2`,
+/// `y2`) so coordinates roundtrip without float rounding. `dag` holds the
+/// earliest day and `dagar` all of them; files without `dagar` are read
+/// with `dag` as the only day.
+///
+/// [`MiljoeDataClean`]: crate::structs::MiljoeDataClean
+pub fn miljo_clean_schema() -> Arc<Schema> {
+    Arc::new(Schema::new(vec![
+        Field::new("x1", DataType::Utf8, false),
+        Field::new("y1", DataType::Utf8, false),
+        Field::new("x2", DataType::Utf8, false),
+        Field::new("y2", DataType::Utf8, false),
+        Field::new("info", DataType::Utf8, false),
+        Field::new("tid", DataType::Utf8, false),
+        Field::new("dag", DataType::UInt8, false),
+        Field::new("dagar", DataType::Utf8, true),
+    ]))
+}
+/// Schema for [`ParkeringsDataClean`] parquet format.
+///
+/// Uses the same coordinate columns as [`miljo_clean_schema`].
+///
+/// [`ParkeringsDataClean`]: crate::structs::ParkeringsDataClean
+pub fn parkering_clean_schema() -> Arc<Schema> {
+    Arc::new(Schema::new(vec![
+        Field::new("x1", DataType::Utf8, false),
+        Field::new("y1", DataType::Utf8, false),
+        Field::new("x2", DataType::Utf8, false),
+        Field::new("y2", DataType::Utf8, false),
+        Field::new("taxa", DataType::Utf8, false),
+        Field::new("antal_platser", DataType::UInt64, false),
+        Field::new("typ_av_parkering", DataType::Utf8, false),
+    ]))
+}
+/// Read a coordinate column as [`Decimal`] values.
+///
+/// Accepts both Utf8 decimal strings (as written by this module) and
+/// Float64 (as produced by GIS tools).
+///
+/// [`Decimal`]: rust_decimal::Decimal
+fn get_decimal_column(
+    batch: &RecordBatch,
+    column_name: &str,
+) -> anyhow::Result<Vec<rust_decimal::Decimal>> {
+    let column = batch.column(batch.schema().index_of(column_name)?);
+    if let Some(strings) = column.as_any().downcast_ref::<StringArray>() {
+        return (0..strings.len())
+            .map(|i| {
+                strings.value(i).parse().map_err(|e| {
+                    anyhow::anyhow!(
+                        "Invalid {} value '{}': {}",
+                        column_name,
+                        strings.value(i),
+                        e
+                    )
+                })
+            })
+            .collect();
+    }
+    if let Some(floats) = column.as_any().downcast_ref::<arrow::array::Float64Array>() {
+        return Ok((0..floats.len())
+            .map(|i| rust_decimal::Decimal::from_f64(floats.value(i)).unwrap_or_default())
+            .collect());
+    }
+    Err(anyhow::anyhow!(
+        "{} column missing or wrong type",
+        column_name
+    ))
+}
+/// Segment coordinate columns shared by the zone schemas
+struct SegmentColumnBuilders {
+    x1: StringBuilder,
+    y1: StringBuilder,
+    x2: StringBuilder,
+    y2: StringBuilder,
+}
+impl SegmentColumnBuilders {
+    fn new() -> Self {
+        Self {
+            x1: StringBuilder::new(),
+            y1: StringBuilder::new(),
+            x2: StringBuilder::new(),
+            y2: StringBuilder::new(),
+        }
+    }
+    fn append(&mut self, coordinates: &[[rust_decimal::Decimal; 2]; 2]) {
+        self.x1.append_value(coordinates[0][0].to_string());
+        self.y1.append_value(coordinates[0][1].to_string());
+        self.x2.append_value(coordinates[1][0].to_string());
+        self.y2.append_value(coordinates[1][1].to_string());
+    }
+    fn finish(mut self) -> Vec<arrow::array::ArrayRef> {
+        vec![
+            Arc::new(self.x1.finish()),
+            Arc::new(self.y1.finish()),
+            Arc::new(self.x2.finish()),
+            Arc::new(self.y2.finish()),
+        ]
+    }
+}
+/// Read the `x1`/`y1`/`x2`/`y2` columns into segment coordinates
+fn read_segment_coordinates(
+    batch: &RecordBatch,
+) -> anyhow::Result<Vec<[[rust_decimal::Decimal; 2]; 2]>> {
+    let x1 = get_decimal_column(batch, "x1")?;
+    let y1 = get_decimal_column(batch, "y1")?;
+    let x2 = get_decimal_column(batch, "x2")?;
+    let y2 = get_decimal_column(batch, "y2")?;
+    Ok((0..batch.num_rows())
+        .map(|i| [[x1[i], y1[i]], [x2[i], y2[i]]])
+        .collect())
+}
+/// Write [`MiljoeDataClean`] segments to a parquet file.
+///
+/// # Errors
+///
+/// Returns error if `data` is empty, the file cannot be created, or the
+/// write fails.
+///
+/// [`MiljoeDataClean`]: crate::structs::MiljoeDataClean
+pub fn write_miljo_clean_parquet(data: &[MiljoeDataClean], path: &str) -> anyhow::Result<()> {
+    if data.is_empty() {
+        return Err(anyhow::anyhow!("Empty miljödata"));
+    }
+    let schema = miljo_clean_schema();
+    let writer = create_arrow_writer(path, schema.clone(), ParquetWriteOptions::default())?;
+    let mut coordinates = SegmentColumnBuilders::new();
+    let mut info_builder = StringBuilder::new();
+    let mut tid_builder = StringBuilder::new();
+    let mut dag_builder = UInt8Builder::new();
+    let mut dagar_builder = StringBuilder::new();
+    for row in data {
+        coordinates.append(&row.coordinates);
+        info_builder.append_value(&row.info);
+        tid_builder.append_value(&row.tid);
+        dag_builder.append_value(row.dag());
+        append_dagar(&mut dagar_builder, &row.dagar);
+    }
+    let mut columns = coordinates.finish();
+    columns.push(Arc::new(info_builder.finish()));
+    columns.push(Arc::new(tid_builder.finish()));
+    columns.push(Arc::new(dag_builder.finish()));
+    columns.push(Arc::new(dagar_builder.finish()));
+    let batch = RecordBatch::try_new(schema, columns)
+        .map_err(|e| anyhow::anyhow!("Failed to create record batch: {}", e))?;
+    write_batch_and_close(writer, batch)
+}
+/// Read [`MiljoeDataClean`] segments from a parquet file.
+///
+/// # Errors
+///
+/// Returns error if the file is not valid Parquet or a column is missing.
+///
+/// [`MiljoeDataClean`]: crate::structs::MiljoeDataClean
+pub fn read_miljo_clean_parquet(file: File) -> anyhow::Result<Vec<MiljoeDataClean>> {
+    let mut reader = create_parquet_reader(file)?;
+    let mut result = Vec::new();
+    while let Some(batch) = reader.next().transpose()? {
+        let coordinates = read_segment_coordinates(&batch)?;
+        let info = get_string_column(&batch, "info")?;
+        let tid = get_string_column(&batch, "tid")?;
+        let dag = get_u8_column(&batch, "dag")?;
+        let dagar = get_string_column(&batch, "dagar").ok();
+        for (i, coordinates) in coordinates.into_iter().enumerate() {
+            let mut days = get_dagar(dagar, i);
+            if days.is_empty() {
+                days.push(dag.value(i));
+            }
+            result.push(MiljoeDataClean::with_dagar(
+                coordinates,
+                get_required_string(info, i),
+                get_required_string(tid, i),
+                days,
+            ));
+        }
+    }
+    Ok(result)
+}
+/// Write [`ParkeringsDataClean`] segments to a parquet file.
+///
+/// # Errors
+///
+/// Returns error if `data` is empty, the file cannot be created, or the
+/// write fails.
+///
+/// [`ParkeringsDataClean`]: crate::structs::ParkeringsDataClean
+pub fn write_parkering_clean_parquet(
+    data: &[ParkeringsDataClean],
+    path: &str,
+) -> anyhow::Result<()> {
+    if data.is_empty() {
+        return Err(anyhow::anyhow!("Empty parkering data"));
+    }
+    let schema = parkering_clean_schema();
+    let writer = create_arrow_writer(path, schema.clone(), ParquetWriteOptions::default())?;
+    let mut coordinates = SegmentColumnBuilders::new();
+    let mut taxa_builder = StringBuilder::new();
+    let mut antal_platser_builder = UInt64Builder::new();
+    let mut typ_av_parkering_builder = StringBuilder::new();
+    for row in data {
+        coordinates.append(&row.coordinates);
+        taxa_builder.append_value(&row.taxa);
+        antal_platser_builder.append_value(row.antal_platser);
+        typ_av_parkering_builder.append_value(&row.typ_av_parkering);
+    }
+    let mut columns = coordinates.finish();
+    columns.push(Arc::new(taxa_builder.finish()));
+    columns.push(Arc::new(antal_platser_builder.finish()));
+    columns.push(Arc::new(typ_av_parkering_builder.finish()));
+    let batch = RecordBatch::try_new(schema, columns)
+        .map_err(|e| anyhow::anyhow!("Failed to create record batch: {}", e))?;
+    write_batch_and_close(writer, batch)
+}
+/// Read [`ParkeringsDataClean`] segments from a parquet file.
+///
+/// # Errors
+///
+/// Returns error if the file is not valid Parquet or a column is missing.
+///
+/// [`ParkeringsDataClean`]: crate::structs::ParkeringsDataClean
+pub fn read_parkering_clean_parquet(file: File) -> anyhow::Result<Vec<ParkeringsDataClean>> {
+    let mut reader = create_parquet_reader(file)?;
+    let mut result = Vec::new();
+    while let Some(batch) = reader.next().transpose()? {
+        let coordinates = read_segment_coordinates(&batch)?;
+        let taxa = get_string_column(&batch, "taxa")?;
+        let antal_platser = get_u64_column(&batch, "antal_platser")?;
+        let typ_av_parkering = get_string_column(&batch, "typ_av_parkering")?;
+        for (i, coordinates) in coordinates.into_iter().enumerate() {
+            result.push(ParkeringsDataClean {
+                coordinates,
+                taxa: get_required_string(taxa, i),
+                antal_platser: antal_platser.value(i),
+                typ_av_parkering: get_required_string(typ_av_parkering, i),
+            });
+        }
+    }
+    Ok(result)
+}
 /// Build [`LocalData`] into an in-memory Parquet buffer.
 ///
 /// Serializes user's saved addresses to a byte vector suitable for:
@@ -870,16 +1688,158 @@ pub fn write_adress_clean_parquet(data: Vec<AdressClean>, path: &str) -> anyhow:
 ///
 /// [`LocalData`]: crate::structs::LocalData
 pub fn build_local_parquet(data: Vec<LocalData>) -> anyhow::Result<Vec<u8>> {
+    build_local_parquet_with_options(data, ParquetWriteOptions::default())
+}
+/// [`build_local_parquet`] with explicit compression and statistics.
+///
+/// # Examples
+///
+/// ```no_run
+/// use amp_core::parquet::{
+///     ParquetWriteOptions, build_local_parquet_with_options, read_local_parquet_from_bytes,
+/// };
+/// # let data: Vec<amp_core::structs::LocalData> = vec![];
+///
+/// let bytes = build_local_parquet_with_options(data, ParquetWriteOptions::zstd())?;
+/// let entries = read_local_parquet_from_bytes(&bytes)?;
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+///
+/// [`LocalData`]: crate::structs::LocalData
+pub fn build_local_parquet_with_options(
+    data: Vec<LocalData>,
+    options: ParquetWriteOptions,
+) -> anyhow::Result<Vec<u8>> {
     if data.is_empty() {
         return Err(anyhow::anyhow!("Empty local data"));
     }
-    let schema = local_data_schema();
     let mut buffer = Vec::new();
-    let props = WriterProperties::builder()
-        .set_statistics_enabled(EnabledStatistics::None)
-        .build();
-    let mut writer = ArrowWriter::try_new(&mut buffer, schema.clone(), Some(props))
+    let props = options.writer_properties();
+    let mut writer = ArrowWriter::try_new(&mut buffer, local_data_schema(), Some(props))
         .map_err(|e| anyhow::anyhow!("Failed to create ArrowWriter: {}", e))?;
+    writer
+        .write(&local_batch(&data)?)
+        .map_err(|e| anyhow::anyhow!("Failed to write batch: {}", e))?;
+    writer
+        .close()
+        .map_err(|e| anyhow::anyhow!("Failed to close writer: {}", e))?;
+    Ok(buffer)
+}
+/// Append [`LocalData`] rows to the parquet file at `path`.
+///
+/// The existing rows are kept in their row groups, e.g. one per postal
+/// code, and `new_rows` are added as one more row group at the end. Parquet
+/// files can't grow in place, so the whole file is still rewritten: into a
+/// temp file next to `path`, named uniquely so concurrent appends don't
+/// share it, which is synced to disk and then renamed over `path`. The
+/// directory is synced after the rename so the new entry survives a crash.
+/// A failure partway leaves the original file untouched.
+///
+/// Rows read from an older file are migrated to the current schema version
+/// on the way through. If `path` doesn't exist yet, it is created with just
+/// `new_rows`.
+///
+/// # Errors
+///
+/// Returns error if the existing file can't be read, or if writing or
+/// renaming the temp file fails.
+///
+/// # Examples
+///
+/// ```no_run
+/// use amp_core::parquet::append_local_parquet;
+/// # let entry: amp_core::structs::LocalData = unimplemented!();
+///
+/// append_local_parquet("local.parquet", &[entry])?;
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+///
+/// [`LocalData`]: crate::structs::LocalData
+pub fn append_local_parquet(path: &str, new_rows: &[LocalData]) -> anyhow::Result<()> {
+    let existing = match std::fs::read(path) {
+        Ok(bytes) => Some(Bytes::from(bytes)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+        Err(e) => return Err(anyhow::anyhow!("Failed to read {}: {}", path, e)),
+    };
+    static STAGED_COUNTER: AtomicU64 = AtomicU64::new(0);
+    let staged = format!(
+        "{}.{}.{}.tmp",
+        path,
+        std::process::id(),
+        STAGED_COUNTER.fetch_add(1, Ordering::Relaxed),
+    );
+    let result = write_appended_local(&staged, existing, new_rows);
+    if let Err(e) = result.and_then(|()| {
+        std::fs::rename(&staged, path)
+            .map_err(|e| anyhow::anyhow!("Failed to replace {}: {}", path, e))
+    }) {
+        let _ = std::fs::remove_file(&staged);
+        return Err(e);
+    }
+    sync_parent_dir(Path::new(path))
+        .map_err(|e| anyhow::anyhow!("Failed to sync directory of {}: {}", path, e))
+}
+/// Flush the directory entry of `path` to disk, e.g. after a rename
+#[cfg(unix)]
+fn sync_parent_dir(path: &Path) -> std::io::Result<()> {
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    File::open(dir)?.sync_all()
+}
+/// Directories can't be opened for syncing here; the rename is all we get
+#[cfg(not(unix))]
+fn sync_parent_dir(_path: &Path) -> std::io::Result<()> {
+    Ok(())
+}
+/// Write the row groups of `existing` followed by `new_rows` to `path`
+fn write_appended_local(
+    path: &str,
+    existing: Option<Bytes>,
+    new_rows: &[LocalData],
+) -> anyhow::Result<()> {
+    let mut writer =
+        create_arrow_writer(path, local_data_schema(), ParquetWriteOptions::default())?;
+    if let Some(bytes) = existing {
+        let row_groups = ParquetRecordBatchReaderBuilder::try_new(bytes.clone())
+            .map_err(|e| anyhow::anyhow!("Failed to create Parquet reader builder: {}", e))?
+            .metadata()
+            .num_row_groups();
+        for group in 0..row_groups {
+            let reader = ParquetRecordBatchReaderBuilder::try_new(bytes.clone())
+                .and_then(|builder| builder.with_row_groups(vec![group]).build())
+                .map_err(|e| anyhow::anyhow!("Failed to read row group {}: {}", group, e))?;
+            let mut rows = Vec::new();
+            for batch in reader {
+                let batch = batch.map_err(|e| anyhow::anyhow!("Failed to read batch: {}", e))?;
+                rows.extend(read_local_batch(&batch)?);
+            }
+            if rows.is_empty() {
+                continue;
+            }
+            writer
+                .write(&local_batch(&rows)?)
+                .map_err(|e| anyhow::anyhow!("Failed to write batch: {}", e))?;
+            writer
+                .flush()
+                .map_err(|e| anyhow::anyhow!("Failed to close row group: {}", e))?;
+        }
+    }
+    if !new_rows.is_empty() {
+        writer
+            .write(&local_batch(new_rows)?)
+            .map_err(|e| anyhow::anyhow!("Failed to write batch: {}", e))?;
+    }
+    let file = writer
+        .into_inner()
+        .map_err(|e| anyhow::anyhow!("Failed to close writer: {}", e))?;
+    file.sync_all()
+        .map_err(|e| anyhow::anyhow!("Failed to sync {}: {}", path, e))?;
+    Ok(())
+}
+/// One [`RecordBatch`] of `data` in the [`local_data_schema`] layout
+fn local_batch(data: &[LocalData]) -> anyhow::Result<RecordBatch> {
     let mut valid_builder = BooleanBuilder::new();
     let mut active_builder = BooleanBuilder::new();
     let mut postnummer_builder = StringBuilder::new();
@@ -892,6 +1852,11 @@ pub fn build_local_parquet(data: Vec<LocalData>) -> anyhow::Result<Vec<u8>> {
     let mut taxa_builder = StringBuilder::new();
     let mut antal_platser_builder = UInt64Builder::new();
     let mut typ_av_parkering_builder = StringBuilder::new();
+    let mut match_reason_builder = StringBuilder::new();
+    let mut dagar_builder = StringBuilder::new();
+    let mut match_quality_builder = StringBuilder::new();
+    let mut snoozed_until_builder = Int64Builder::new();
+    let mut schema_version_builder = UInt8Builder::new();
     for row in data {
         valid_builder.append_value(row.valid);
         active_builder.append_value(row.active);
@@ -905,9 +1870,14 @@ pub fn build_local_parquet(data: Vec<LocalData>) -> anyhow::Result<Vec<u8>> {
         append_optional_string(&mut taxa_builder, &row.taxa);
         append_optional_u64(&mut antal_platser_builder, &row.antal_platser);
         append_optional_string(&mut typ_av_parkering_builder, &row.typ_av_parkering);
+        append_optional_string(&mut match_reason_builder, &row.match_reason);
+        append_dagar(&mut dagar_builder, &row.dagar);
+        append_match_quality(&mut match_quality_builder, row.match_quality);
+        append_optional_i64(&mut snoozed_until_builder, row.snoozed_until);
+        schema_version_builder.append_value(LOCAL_DATA_SCHEMA_VERSION);
     }
-    let batch = RecordBatch::try_new(
-        schema.clone(),
+    RecordBatch::try_new(
+        local_data_schema(),
         vec![
             Arc::new(valid_builder.finish()),
             Arc::new(active_builder.finish()),
@@ -921,23 +1891,32 @@ pub fn build_local_parquet(data: Vec<LocalData>) -> anyhow::Result<Vec<u8>> {
             Arc::new(taxa_builder.finish()),
             Arc::new(antal_platser_builder.finish()),
             Arc::new(typ_av_parkering_builder.finish()),
+            Arc::new(match_reason_builder.finish()),
+            Arc::new(dagar_builder.finish()),
+            Arc::new(match_quality_builder.finish()),
+            Arc::new(snoozed_until_builder.finish()),
+            Arc::new(schema_version_builder.finish()),
         ],
     )
-    .map_err(|e| anyhow::anyhow!("Failed to create record batch: {}", e))?;
-    writer
-        .write(&batch)
-        .map_err(|e| anyhow::anyhow!("Failed to write batch: {}", e))?;
-    writer
-        .close()
-        .map_err(|e| anyhow::anyhow!("Failed to close writer: {}", e))?;
-    Ok(buffer)
+    .map_err(|e| anyhow::anyhow!("Failed to create record batch: {}", e))
 }
 /// Schema for [`SettingsData`] parquet format.
 ///
-/// Defines 5 non-nullable columns:
+/// Defines 14 non-nullable columns:
 /// - `stadning_nu`, `sex_timmar`, `en_dag`: Boolean notification preferences
 /// - `theme`: Utf8 ("Light" or "Dark")
 /// - `language`: Utf8 ("Svenska", "English", "Espanol", "Francais")
+/// - `autocomplete_source`: Utf8 ("Both", "MiljoOnly", ...)
+/// - `group_by_street`: Boolean panel layout preference
+/// - `cooldown_now_minutes`, `cooldown_six_hours_minutes`,
+///   `cooldown_one_day_minutes`: UInt32 per-bucket notification cooldowns
+/// - `notification_batch_minutes`: UInt32 window for summary notifications
+/// - `quiet_hours_start_minutes`, `quiet_hours_end_minutes`: UInt32 minutes
+///   after Swedish midnight; equal values mean quiet hours are off
+/// - `lead_minutes`: UInt32 bound of the early warning bucket
+///
+/// Readers treat every column after `language` as optional so files written
+/// by older app versions still load.
 ///
 /// [`SettingsData`]: crate::structs::SettingsData
 pub fn settings_data_schema() -> Arc<Schema> {
@@ -948,6 +1927,14 @@ pub fn settings_data_schema() -> Arc<Schema> {
         Field::new("theme", DataType::Utf8, false),
         Field::new("language", DataType::Utf8, false),
         Field::new("autocomplete_source", DataType::Utf8, false),
+        Field::new("group_by_street", DataType::Boolean, false),
+        Field::new("cooldown_now_minutes", DataType::UInt32, false),
+        Field::new("cooldown_six_hours_minutes", DataType::UInt32, false),
+        Field::new("cooldown_one_day_minutes", DataType::UInt32, false),
+        Field::new("notification_batch_minutes", DataType::UInt32, false),
+        Field::new("quiet_hours_start_minutes", DataType::UInt32, false),
+        Field::new("quiet_hours_end_minutes", DataType::UInt32, false),
+        Field::new("lead_minutes", DataType::UInt32, false),
     ]))
 }
 /// Build [`SettingsData`] into an in-memory Parquet buffer.
@@ -976,9 +1963,7 @@ pub fn build_settings_parquet(data: Vec<SettingsData>) -> anyhow::Result<Vec<u8>
     }
     let schema = settings_data_schema();
     let mut buffer = Vec::new();
-    let props = WriterProperties::builder()
-        .set_statistics_enabled(EnabledStatistics::None)
-        .build();
+    let props = ParquetWriteOptions::default().writer_properties();
     let mut writer = ArrowWriter::try_new(&mut buffer, schema.clone(), Some(props))
         .map_err(|e| anyhow::anyhow!("Failed to create ArrowWriter: {}", e))?;
     let mut stadning_nu_builder = BooleanBuilder::new();
@@ -987,6 +1972,14 @@ pub fn build_settings_parquet(data: Vec<SettingsData>) -> anyhow::Result<Vec<u8>
     let mut theme_builder = StringBuilder::new();
     let mut language_builder = StringBuilder::new();
     let mut autocomplete_source_builder = StringBuilder::new();
+    let mut group_by_street_builder = BooleanBuilder::new();
+    let mut cooldown_now_builder = UInt32Builder::new();
+    let mut cooldown_six_hours_builder = UInt32Builder::new();
+    let mut cooldown_one_day_builder = UInt32Builder::new();
+    let mut batch_minutes_builder = UInt32Builder::new();
+    let mut quiet_start_builder = UInt32Builder::new();
+    let mut quiet_end_builder = UInt32Builder::new();
+    let mut lead_minutes_builder = UInt32Builder::new();
     for row in data {
         stadning_nu_builder.append_value(row.stadning_nu);
         sex_timmar_builder.append_value(row.sex_timmar);
@@ -994,6 +1987,14 @@ pub fn build_settings_parquet(data: Vec<SettingsData>) -> anyhow::Result<Vec<u8>
         theme_builder.append_value(&row.theme);
         language_builder.append_value(&row.language);
         autocomplete_source_builder.append_value(&row.autocomplete_source);
+        group_by_street_builder.append_value(row.group_by_street);
+        cooldown_now_builder.append_value(row.cooldown_now_minutes);
+        cooldown_six_hours_builder.append_value(row.cooldown_six_hours_minutes);
+        cooldown_one_day_builder.append_value(row.cooldown_one_day_minutes);
+        batch_minutes_builder.append_value(row.notification_batch_minutes);
+        quiet_start_builder.append_value(row.quiet_hours_start_minutes);
+        quiet_end_builder.append_value(row.quiet_hours_end_minutes);
+        lead_minutes_builder.append_value(row.lead_minutes);
     }
     let batch = RecordBatch::try_new(
         schema.clone(),
@@ -1004,6 +2005,14 @@ pub fn build_settings_parquet(data: Vec<SettingsData>) -> anyhow::Result<Vec<u8>
             Arc::new(theme_builder.finish()),
             Arc::new(language_builder.finish()),
             Arc::new(autocomplete_source_builder.finish()),
+            Arc::new(group_by_street_builder.finish()),
+            Arc::new(cooldown_now_builder.finish()),
+            Arc::new(cooldown_six_hours_builder.finish()),
+            Arc::new(cooldown_one_day_builder.finish()),
+            Arc::new(batch_minutes_builder.finish()),
+            Arc::new(quiet_start_builder.finish()),
+            Arc::new(quiet_end_builder.finish()),
+            Arc::new(lead_minutes_builder.finish()),
         ],
     )
     .map_err(|e| anyhow::anyhow!("Failed to create record batch: {}", e))?;
@@ -1042,6 +2051,15 @@ pub fn read_settings_parquet(file: File) -> anyhow::Result<Vec<SettingsData>> {
         let theme = get_string_column(&batch, "theme")?;
         let language = get_string_column(&batch, "language")?;
         let autocomplete_source = get_string_column(&batch, "autocomplete_source").ok();
+        let group_by_street = get_boolean_column(&batch, "group_by_street").ok();
+        let cooldown_now = get_u32_column(&batch, "cooldown_now_minutes").ok();
+        let cooldown_six_hours = get_u32_column(&batch, "cooldown_six_hours_minutes").ok();
+        let cooldown_one_day = get_u32_column(&batch, "cooldown_one_day_minutes").ok();
+        let batch_minutes = get_u32_column(&batch, "notification_batch_minutes").ok();
+        let quiet_start = get_u32_column(&batch, "quiet_hours_start_minutes").ok();
+        let quiet_end = get_u32_column(&batch, "quiet_hours_end_minutes").ok();
+        let lead_minutes = get_u32_column(&batch, "lead_minutes").ok();
+        let defaults = SettingsData::default();
         for i in 0..batch.num_rows() {
             let entry = SettingsData {
                 stadning_nu: get_boolean_with_default(stadning_nu, i, true),
@@ -1052,6 +2070,40 @@ pub fn read_settings_parquet(file: File) -> anyhow::Result<Vec<SettingsData>> {
                 autocomplete_source: autocomplete_source
                     .map(|col| get_required_string(col, i))
                     .unwrap_or_else(|| "Both".to_string()),
+                group_by_street: group_by_street
+                    .map(|col| get_boolean_with_default(col, i, false))
+                    .unwrap_or(false),
+                cooldown_now_minutes: get_u32_with_default(
+                    cooldown_now,
+                    i,
+                    defaults.cooldown_now_minutes,
+                ),
+                cooldown_six_hours_minutes: get_u32_with_default(
+                    cooldown_six_hours,
+                    i,
+                    defaults.cooldown_six_hours_minutes,
+                ),
+                cooldown_one_day_minutes: get_u32_with_default(
+                    cooldown_one_day,
+                    i,
+                    defaults.cooldown_one_day_minutes,
+                ),
+                notification_batch_minutes: get_u32_with_default(
+                    batch_minutes,
+                    i,
+                    defaults.notification_batch_minutes,
+                ),
+                quiet_hours_start_minutes: get_u32_with_default(
+                    quiet_start,
+                    i,
+                    defaults.quiet_hours_start_minutes,
+                ),
+                quiet_hours_end_minutes: get_u32_with_default(
+                    quiet_end,
+                    i,
+                    defaults.quiet_hours_end_minutes,
+                ),
+                lead_minutes: get_u32_with_default(lead_minutes, i, defaults.lead_minutes),
             };
             result.push(entry);
         }
@@ -1092,6 +2144,15 @@ pub fn read_settings_parquet_from_bytes(bytes: &[u8]) -> anyhow::Result<Vec<Sett
         let theme = get_string_column(&batch, "theme")?;
         let language = get_string_column(&batch, "language")?;
         let autocomplete_source = get_string_column(&batch, "autocomplete_source").ok();
+        let group_by_street = get_boolean_column(&batch, "group_by_street").ok();
+        let cooldown_now = get_u32_column(&batch, "cooldown_now_minutes").ok();
+        let cooldown_six_hours = get_u32_column(&batch, "cooldown_six_hours_minutes").ok();
+        let cooldown_one_day = get_u32_column(&batch, "cooldown_one_day_minutes").ok();
+        let batch_minutes = get_u32_column(&batch, "notification_batch_minutes").ok();
+        let quiet_start = get_u32_column(&batch, "quiet_hours_start_minutes").ok();
+        let quiet_end = get_u32_column(&batch, "quiet_hours_end_minutes").ok();
+        let lead_minutes = get_u32_column(&batch, "lead_minutes").ok();
+        let defaults = SettingsData::default();
         for i in 0..batch.num_rows() {
             let entry = SettingsData {
                 stadning_nu: get_boolean_with_default(stadning_nu, i, true),
@@ -1102,6 +2163,40 @@ pub fn read_settings_parquet_from_bytes(bytes: &[u8]) -> anyhow::Result<Vec<Sett
                 autocomplete_source: autocomplete_source
                     .map(|col| get_required_string(col, i))
                     .unwrap_or_else(|| "Both".to_string()),
+                group_by_street: group_by_street
+                    .map(|col| get_boolean_with_default(col, i, false))
+                    .unwrap_or(false),
+                cooldown_now_minutes: get_u32_with_default(
+                    cooldown_now,
+                    i,
+                    defaults.cooldown_now_minutes,
+                ),
+                cooldown_six_hours_minutes: get_u32_with_default(
+                    cooldown_six_hours,
+                    i,
+                    defaults.cooldown_six_hours_minutes,
+                ),
+                cooldown_one_day_minutes: get_u32_with_default(
+                    cooldown_one_day,
+                    i,
+                    defaults.cooldown_one_day_minutes,
+                ),
+                notification_batch_minutes: get_u32_with_default(
+                    batch_minutes,
+                    i,
+                    defaults.notification_batch_minutes,
+                ),
+                quiet_hours_start_minutes: get_u32_with_default(
+                    quiet_start,
+                    i,
+                    defaults.quiet_hours_start_minutes,
+                ),
+                quiet_hours_end_minutes: get_u32_with_default(
+                    quiet_end,
+                    i,
+                    defaults.quiet_hours_end_minutes,
+                ),
+                lead_minutes: get_u32_with_default(lead_minutes, i, defaults.lead_minutes),
             };
             result.push(entry);
         }
@@ -1134,9 +2229,7 @@ pub fn build_notification_state_parquet(
     }
     let schema = notification_state_schema();
     let mut buffer = Vec::new();
-    let props = WriterProperties::builder()
-        .set_statistics_enabled(EnabledStatistics::None)
-        .build();
+    let props = ParquetWriteOptions::default().writer_properties();
     let mut writer = ArrowWriter::try_new(&mut buffer, schema.clone(), Some(props))
         .map_err(|e| anyhow::anyhow!("Failed to create ArrowWriter: {}", e))?;
     let mut address_id_builder = UInt64Builder::new();
@@ -1192,6 +2285,92 @@ pub fn read_notification_state_from_bytes(
     }
     Ok(result)
 }
+/// Schema for [`NotificationFiredEntry`] parquet format.
+///
+/// Defines 3 non-nullable columns:
+/// - `address_id`: UInt64
+/// - `bucket`: Utf8 — TimeBucket as string (e.g., "Now", "Within6Hours")
+/// - `fired_at`: Int64 — Unix timestamp in seconds
+///
+/// [`NotificationFiredEntry`]: crate::structs::NotificationFiredEntry
+pub fn notification_fired_schema() -> Arc<Schema> {
+    Arc::new(Schema::new(vec![
+        Field::new("address_id", DataType::UInt64, false),
+        Field::new("bucket", DataType::Utf8, false),
+        Field::new("fired_at", DataType::Int64, false),
+    ]))
+}
+/// Build [`NotificationFiredEntry`] into an in-memory Parquet buffer.
+///
+/// [`NotificationFiredEntry`]: crate::structs::NotificationFiredEntry
+pub fn build_notification_fired_parquet(
+    data: Vec<NotificationFiredEntry>,
+) -> anyhow::Result<Vec<u8>> {
+    if data.is_empty() {
+        return Err(anyhow::anyhow!("Empty notification fired data"));
+    }
+    let schema = notification_fired_schema();
+    let mut buffer = Vec::new();
+    let props = ParquetWriteOptions::default().writer_properties();
+    let mut writer = ArrowWriter::try_new(&mut buffer, schema.clone(), Some(props))
+        .map_err(|e| anyhow::anyhow!("Failed to create ArrowWriter: {}", e))?;
+    let mut address_id_builder = UInt64Builder::new();
+    let mut bucket_builder = StringBuilder::new();
+    let mut fired_at_builder = Int64Builder::new();
+    for row in data {
+        address_id_builder.append_value(row.address_id);
+        bucket_builder.append_value(&row.bucket);
+        fired_at_builder.append_value(row.fired_at);
+    }
+    let batch = RecordBatch::try_new(
+        schema.clone(),
+        vec![
+            Arc::new(address_id_builder.finish()),
+            Arc::new(bucket_builder.finish()),
+            Arc::new(fired_at_builder.finish()),
+        ],
+    )
+    .map_err(|e| anyhow::anyhow!("Failed to create record batch: {}", e))?;
+    writer
+        .write(&batch)
+        .map_err(|e| anyhow::anyhow!("Failed to write batch: {}", e))?;
+    writer
+        .close()
+        .map_err(|e| anyhow::anyhow!("Failed to close writer: {}", e))?;
+    Ok(buffer)
+}
+/// Read [`NotificationFiredEntry`] from embedded bytes.
+///
+/// [`NotificationFiredEntry`]: crate::structs::NotificationFiredEntry
+pub fn read_notification_fired_from_bytes(
+    bytes: &[u8],
+) -> anyhow::Result<Vec<NotificationFiredEntry>> {
+    let bytes_obj = Bytes::copy_from_slice(bytes);
+    let builder = ParquetRecordBatchReaderBuilder::try_new(bytes_obj)
+        .map_err(|e| anyhow::anyhow!("Failed to create Parquet reader builder: {}", e))?;
+    let reader = builder
+        .build()
+        .map_err(|e| anyhow::anyhow!("Failed to build Parquet record batch reader: {}", e))?;
+    let mut result = Vec::new();
+    for batch_result in reader {
+        let batch = batch_result.map_err(|e| anyhow::anyhow!("Failed to read batch: {}", e))?;
+        let address_id = get_u64_column(&batch, "address_id")?;
+        let bucket = get_string_column(&batch, "bucket")?;
+        let fired_at = batch
+            .column(batch.schema().index_of("fired_at")?)
+            .as_any()
+            .downcast_ref::<Int64Array>()
+            .ok_or_else(|| anyhow::anyhow!("fired_at column missing or wrong type"))?;
+        for i in 0..batch.num_rows() {
+            result.push(NotificationFiredEntry {
+                address_id: address_id.value(i),
+                bucket: get_required_string(bucket, i),
+                fired_at: fired_at.value(i),
+            });
+        }
+    }
+    Ok(result)
+}
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1222,8 +2401,679 @@ mod tests {
         assert_eq!(loaded, entries);
     }
     #[test]
+    fn test_notification_fired_roundtrip() {
+        let entries = vec![
+            NotificationFiredEntry {
+                address_id: 3,
+                bucket: "Within6Hours".to_string(),
+                fired_at: 1_771_000_000,
+            },
+            NotificationFiredEntry {
+                address_id: 3,
+                bucket: "Now".to_string(),
+                fired_at: 1_771_020_000,
+            },
+        ];
+        let bytes =
+            build_notification_fired_parquet(entries.clone()).expect("Failed to build parquet");
+        let loaded =
+            read_notification_fired_from_bytes(&bytes).expect("Failed to read parquet bytes");
+        assert_eq!(loaded, entries);
+    }
+    #[test]
+    fn test_validate_schema_accepts_matching_file() {
+        let path = std::env::temp_dir().join("amp_validate_schema_ok.parquet");
+        let entry = OutputData {
+            postnummer: Some("21438".to_string()),
+            adress: "Storgatan 1".to_string(),
+            gata: "Storgatan".to_string(),
+            gatunummer: "1".to_string(),
+            info: Some("Städning".to_string()),
+            tid: Some("0800-1200".to_string()),
+            dag: Some(15),
+            dagar: vec![15],
+            taxa: None,
+            antal_platser: None,
+            typ_av_parkering: None,
+            match_quality: None,
+            segment_id: None,
+        };
+        write_output_parquet(vec![entry], path.to_str().unwrap()).unwrap();
+        let result = validate_schema(File::open(&path).unwrap(), &output_data_schema());
+        std::fs::remove_file(&path).ok();
+        assert_eq!(result, Ok(()));
+    }
+    #[test]
+    fn test_filter_matched_drops_rows_without_data() {
+        let unmatched = OutputData {
+            postnummer: Some("21438".to_string()),
+            adress: "Storgatan 1".to_string(),
+            gata: "Storgatan".to_string(),
+            gatunummer: "1".to_string(),
+            info: None,
+            tid: None,
+            dag: None,
+            dagar: vec![],
+            taxa: None,
+            antal_platser: None,
+            typ_av_parkering: None,
+            match_quality: None,
+            segment_id: None,
+        };
+        let taxa_only = OutputData {
+            adress: "Storgatan 2".to_string(),
+            taxa: Some("Taxa C".to_string()),
+            ..unmatched.clone()
+        };
+        let kept = filter_matched(vec![unmatched.clone(), taxa_only]);
+        assert_eq!(kept.len(), 1);
+        assert_eq!(kept[0].adress, "Storgatan 2");
+        let path = std::env::temp_dir().join("amp_matched_only.parquet");
+        assert!(
+            write_output_parquet_matched_only(vec![unmatched], path.to_str().unwrap()).is_err()
+        );
+    }
+    #[test]
+    fn test_output_dagar_roundtrip() {
+        let path = std::env::temp_dir().join("amp_output_dagar_roundtrip.parquet");
+        let entry = |adress: &str, dagar: Vec<u8>| OutputData {
+            postnummer: None,
+            adress: adress.to_string(),
+            gata: "Storgatan".to_string(),
+            gatunummer: "1".to_string(),
+            info: Some("Städning".to_string()),
+            tid: Some("0800-1200".to_string()),
+            dag: dagar.first().copied(),
+            match_quality: (!dagar.is_empty()).then_some(MatchQuality::Approximate),
+            segment_id: dagar.first().map(|&dag| u64::from(dag) * 100),
+            dagar,
+            taxa: None,
+            antal_platser: None,
+            typ_av_parkering: None,
+        };
+        let data = vec![
+            entry("Storgatan 1", vec![1, 15]),
+            entry("Storgatan 2", vec![20]),
+            entry("Storgatan 3", vec![]),
+        ];
+        write_output_parquet(data, path.to_str().unwrap()).unwrap();
+        let loaded = read_db_parquet(File::open(&path).unwrap()).unwrap();
+        std::fs::remove_file(&path).ok();
+        assert_eq!(loaded[0].dag, Some(1));
+        assert_eq!(loaded[0].dagar, vec![1, 15]);
+        assert_eq!(loaded[1].dagar, vec![20]);
+        assert!(loaded[2].dagar.is_empty());
+        assert_eq!(loaded[0].match_quality, Some(MatchQuality::Approximate));
+        assert_eq!(loaded[2].match_quality, None);
+        assert_eq!(loaded[0].segment_id, Some(100));
+        assert_eq!(loaded[2].segment_id, None);
+    }
+    #[test]
+    fn test_validate_schema_lists_every_mismatch() {
+        let path = std::env::temp_dir().join("amp_validate_schema_mismatch.parquet");
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("stadning_nu", DataType::Boolean, false),
+            Field::new("sex_timmar", DataType::Utf8, false),
+            Field::new("en_dag", DataType::Boolean, true),
+            Field::new("theme", DataType::Utf8, false),
+            Field::new("legacy_flag", DataType::Boolean, false),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(BooleanArray::from(vec![true])),
+                Arc::new(StringArray::from(vec!["yes"])),
+                Arc::new(BooleanArray::from(vec![Some(false)])),
+                Arc::new(StringArray::from(vec!["Dark"])),
+                Arc::new(BooleanArray::from(vec![true])),
+            ],
+        )
+        .unwrap();
+        let writer = create_arrow_writer(
+            path.to_str().unwrap(),
+            schema,
+            ParquetWriteOptions::default(),
+        )
+        .unwrap();
+        write_batch_and_close(writer, batch).unwrap();
+        let result = validate_schema(File::open(&path).unwrap(), &settings_data_schema());
+        std::fs::remove_file(&path).ok();
+        let mismatches = result.unwrap_err();
+        assert!(
+            mismatches.contains(&"column 'sex_timmar': expected Boolean, found Utf8".to_string())
+        );
+        assert!(
+            mismatches
+                .contains(&"column 'en_dag': expected non-nullable, found nullable".to_string())
+        );
+        assert!(mismatches.contains(&"column 'language': missing".to_string()));
+        assert!(mismatches.contains(&"column 'legacy_flag': not in expected schema".to_string()));
+        assert!(
+            !mismatches
+                .iter()
+                .any(|m| m.contains("'stadning_nu'") || m.contains("'theme'"))
+        );
+        assert_eq!(mismatches.len(), 13);
+    }
+    #[test]
+    fn test_validate_schema_rejects_non_parquet() {
+        let path = std::env::temp_dir().join("amp_validate_schema_garbage.parquet");
+        std::fs::write(&path, b"not parquet").unwrap();
+        let result = validate_schema(File::open(&path).unwrap(), &output_data_schema());
+        std::fs::remove_file(&path).ok();
+        assert_eq!(result.unwrap_err().len(), 1);
+    }
+    #[test]
+    fn test_settings_without_cooldown_columns() {
+        let mut bytes = Vec::new();
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("stadning_nu", DataType::Boolean, false),
+            Field::new("sex_timmar", DataType::Boolean, false),
+            Field::new("en_dag", DataType::Boolean, false),
+            Field::new("theme", DataType::Utf8, false),
+            Field::new("language", DataType::Utf8, false),
+        ]));
+        let mut writer = ArrowWriter::try_new(&mut bytes, schema.clone(), None).unwrap();
+        let batch = RecordBatch::try_new(
+            schema,
+            vec![
+                Arc::new(BooleanArray::from(vec![true])),
+                Arc::new(BooleanArray::from(vec![true])),
+                Arc::new(BooleanArray::from(vec![false])),
+                Arc::new(StringArray::from(vec!["Dark"])),
+                Arc::new(StringArray::from(vec!["English"])),
+            ],
+        )
+        .unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+        let loaded = read_settings_parquet_from_bytes(&bytes).unwrap();
+        let defaults = SettingsData::default();
+        assert_eq!(loaded[0].theme, "Dark");
+        assert_eq!(
+            loaded[0].cooldown_now_minutes,
+            defaults.cooldown_now_minutes
+        );
+        assert_eq!(
+            loaded[0].cooldown_one_day_minutes,
+            defaults.cooldown_one_day_minutes
+        );
+        assert_eq!(loaded[0].notification_batch_minutes, 0);
+        assert_eq!(loaded[0].quiet_hours(), None);
+        assert_eq!(loaded[0].lead_minutes, 6 * 60);
+    }
+    #[test]
     fn test_notification_state_empty_errors() {
         let result = build_notification_state_parquet(vec![]);
         assert!(result.is_err());
     }
+    #[test]
+    fn test_local_match_reason_roundtrip() {
+        let entry = LocalData {
+            valid: true,
+            active: true,
+            postnummer: Some("21438".to_string()),
+            adress: "Kornettsgatan 18C".to_string(),
+            gata: Some("Kornettsgatan".to_string()),
+            gatunummer: Some("18C".to_string()),
+            info: None,
+            tid: Some("0800-1200".to_string()),
+            dag: Some(15),
+            dagar: vec![15],
+            taxa: None,
+            antal_platser: None,
+            typ_av_parkering: None,
+            match_reason: Some("exact".to_string()),
+            match_quality: Some(MatchQuality::Exact),
+            snoozed_until: Some(1_707_980_400),
+        };
+        let bytes = build_local_parquet(vec![entry]).expect("Failed to build parquet");
+        let loaded = read_local_parquet_from_bytes(&bytes).expect("Failed to read parquet bytes");
+        assert_eq!(loaded[0].match_reason.as_deref(), Some("exact"));
+        assert_eq!(loaded[0].match_quality, Some(MatchQuality::Exact));
+        assert_eq!(loaded[0].snoozed_until, Some(1_707_980_400));
+    }
+    #[test]
+    fn test_read_local_truncated_is_parquet_read_error() {
+        let bytes = build_local_parquet(vec![LocalData {
+            valid: true,
+            active: true,
+            postnummer: None,
+            adress: "Kornettsgatan 18C".to_string(),
+            gata: None,
+            gatunummer: None,
+            info: None,
+            tid: None,
+            dag: None,
+            dagar: vec![],
+            taxa: None,
+            antal_platser: None,
+            typ_av_parkering: None,
+            match_reason: None,
+            match_quality: None,
+            snoozed_until: None,
+        }])
+        .unwrap();
+        let result = read_local_parquet_from_bytes(&bytes[..bytes.len() / 2]);
+        assert!(matches!(result, Err(AmpError::ParquetRead(_))));
+    }
+    #[test]
+    fn test_read_local_missing_column_is_schema_mismatch() {
+        let schema = Arc::new(Schema::new(vec![Field::new(
+            "adress",
+            DataType::Utf8,
+            false,
+        )]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![Arc::new(StringArray::from(vec!["Kornettsgatan 18C"]))],
+        )
+        .unwrap();
+        let mut buffer = Vec::new();
+        let mut writer = ArrowWriter::try_new(&mut buffer, schema, None).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+        match read_local_parquet_from_bytes(&buffer) {
+            Err(AmpError::SchemaMismatch { column, found, .. }) => {
+                assert_eq!(column, "valid");
+                assert_eq!(found, "missing");
+            }
+            other => panic!("expected SchemaMismatch, got {:?}", other),
+        }
+    }
+    #[test]
+    fn test_assert_schema_names_column_with_wrong_type() {
+        use arrow::array::ArrayRef;
+        let required = required_columns(&local_data_schema(), &LOCAL_DATA_OPTIONAL_COLUMNS);
+        let fields: Vec<Field> = required
+            .fields()
+            .iter()
+            .map(|f| match f.name().as_str() {
+                "dag" => Field::new("dag", DataType::Utf8, true),
+                _ => f.as_ref().clone(),
+            })
+            .collect();
+        let schema = Arc::new(Schema::new(fields));
+        let columns: Vec<ArrayRef> = schema
+            .fields()
+            .iter()
+            .map(|f| match f.data_type() {
+                DataType::Boolean => Arc::new(BooleanArray::from(vec![true])) as ArrayRef,
+                DataType::UInt64 => Arc::new(UInt64Array::from(vec![Some(12)])),
+                _ => Arc::new(StringArray::from(vec![Some("15")])),
+            })
+            .collect();
+        let batch = RecordBatch::try_new(schema.clone(), columns).unwrap();
+        match assert_schema(&batch, &required) {
+            Err(AmpError::SchemaMismatch {
+                column,
+                expected,
+                found,
+            }) => {
+                assert_eq!(column, "dag");
+                assert_eq!(expected, "UInt8");
+                assert_eq!(found, "Utf8");
+            }
+            other => panic!("expected SchemaMismatch, got {:?}", other),
+        }
+        let mut buffer = Vec::new();
+        let mut writer = ArrowWriter::try_new(&mut buffer, schema, None).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+        let err = read_local_parquet_from_bytes(&buffer).unwrap_err();
+        assert!(matches!(&err, AmpError::SchemaMismatch { column, .. } if column == "dag"));
+        assert!(err.to_string().contains("'dag'"), "{}", err);
+    }
+    #[test]
+    fn test_append_local_parquet_adds_row_group() {
+        let entry = |adress: &str, postnummer: &str| LocalData {
+            valid: true,
+            active: true,
+            postnummer: Some(postnummer.to_string()),
+            adress: adress.to_string(),
+            gata: None,
+            gatunummer: None,
+            info: None,
+            tid: Some("0800-1200".to_string()),
+            dag: Some(15),
+            dagar: vec![15],
+            taxa: None,
+            antal_platser: None,
+            typ_av_parkering: None,
+            match_reason: None,
+            match_quality: None,
+            snoozed_until: None,
+        };
+        let path = std::env::temp_dir().join("amp_append_local_test.parquet");
+        let path = path.to_str().unwrap();
+        let original = vec![
+            entry("Storgatan 1", "21134"),
+            entry("Storgatan 2", "21134"),
+            entry("Storgatan 3", "21134"),
+        ];
+        std::fs::write(path, build_local_parquet(original).unwrap()).unwrap();
+        append_local_parquet(
+            path,
+            &[
+                entry("Kornettsgatan 18C", "21438"),
+                entry("Kornettsgatan 20", "21438"),
+            ],
+        )
+        .unwrap();
+        let bytes = std::fs::read(path).unwrap();
+        let _ = std::fs::remove_file(path);
+        let loaded = read_local_parquet_from_bytes(&bytes).unwrap();
+        let adresser: Vec<_> = loaded.iter().map(|e| e.adress.as_str()).collect();
+        assert_eq!(
+            adresser,
+            vec![
+                "Storgatan 1",
+                "Storgatan 2",
+                "Storgatan 3",
+                "Kornettsgatan 18C",
+                "Kornettsgatan 20",
+            ]
+        );
+        let metadata = ParquetRecordBatchReaderBuilder::try_new(Bytes::from(bytes))
+            .unwrap()
+            .metadata()
+            .clone();
+        assert_eq!(metadata.num_row_groups(), 2);
+        let staged_prefix = "amp_append_local_test.parquet.";
+        let leftover = std::fs::read_dir(std::env::temp_dir())
+            .unwrap()
+            .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+            .any(|name| name.starts_with(staged_prefix) && name.ends_with(".tmp"));
+        assert!(!leftover, "staged temp file should be renamed away");
+    }
+    #[test]
+    fn test_local_batches_stream_in_chunks() {
+        let entry = LocalData {
+            valid: true,
+            active: true,
+            postnummer: Some("21438".to_string()),
+            adress: "Kornettsgatan 18C".to_string(),
+            gata: Some("Kornettsgatan".to_string()),
+            gatunummer: Some("18C".to_string()),
+            info: None,
+            tid: Some("0800-1200".to_string()),
+            dag: Some(15),
+            dagar: vec![15],
+            taxa: None,
+            antal_platser: None,
+            typ_av_parkering: None,
+            match_reason: None,
+            match_quality: None,
+            snoozed_until: None,
+        };
+        let rows = 2 * LOCAL_BATCH_ROWS + 10;
+        let bytes = build_local_parquet(vec![entry; rows]).expect("Failed to build parquet");
+        let chunks: Vec<Vec<LocalData>> = read_local_parquet_batches(&bytes)
+            .collect::<anyhow::Result<_>>()
+            .expect("Failed to stream parquet bytes");
+        assert_eq!(chunks.len(), 3);
+        assert!(chunks.iter().all(|chunk| !chunk.is_empty()));
+        assert_eq!(chunks.iter().map(Vec::len).sum::<usize>(), rows);
+        assert_eq!(read_local_parquet_from_bytes(&bytes).unwrap().len(), rows);
+    }
+    #[test]
+    fn test_local_zstd_reads_back_identically() {
+        let entries = vec![
+            LocalData {
+                valid: true,
+                active: false,
+                postnummer: Some("21438".to_string()),
+                adress: "Kornettsgatan 18C".to_string(),
+                gata: Some("Kornettsgatan".to_string()),
+                gatunummer: Some("18C".to_string()),
+                info: Some("Städning".to_string()),
+                tid: Some("0800-1200".to_string()),
+                dag: Some(15),
+                dagar: vec![1, 15],
+                taxa: Some("Taxa C".to_string()),
+                antal_platser: Some(26),
+                typ_av_parkering: Some("Längsgående 6".to_string()),
+                match_reason: Some("exact".to_string()),
+                match_quality: Some(MatchQuality::Approximate),
+                snoozed_until: None,
+            },
+            LocalData {
+                valid: false,
+                active: true,
+                postnummer: None,
+                adress: "Storgatan 1".to_string(),
+                gata: None,
+                gatunummer: None,
+                info: None,
+                tid: None,
+                dag: None,
+                dagar: vec![],
+                taxa: None,
+                antal_platser: None,
+                typ_av_parkering: None,
+                match_reason: None,
+                match_quality: None,
+                snoozed_until: None,
+            },
+        ];
+        let bytes = build_local_parquet_with_options(entries.clone(), ParquetWriteOptions::zstd())
+            .expect("Failed to build parquet");
+        let metadata = ParquetRecordBatchReaderBuilder::try_new(Bytes::from(bytes.clone()))
+            .unwrap()
+            .metadata()
+            .clone();
+        assert!(matches!(
+            metadata.row_group(0).column(0).compression(),
+            Compression::ZSTD(_)
+        ));
+        let loaded = read_local_parquet_from_bytes(&bytes).expect("Failed to read parquet bytes");
+        assert_eq!(format!("{:?}", loaded), format!("{:?}", entries));
+        let snappy = build_local_parquet(entries).unwrap();
+        let metadata = ParquetRecordBatchReaderBuilder::try_new(Bytes::from(snappy))
+            .unwrap()
+            .metadata()
+            .clone();
+        assert_eq!(
+            metadata.row_group(0).column(0).compression(),
+            Compression::SNAPPY
+        );
+    }
+    #[test]
+    fn test_local_batches_yield_error_for_invalid_bytes() {
+        let mut batches = read_local_parquet_batches(b"not parquet");
+        assert!(batches.next().is_some_and(|batch| batch.is_err()));
+        assert!(batches.next().is_none());
+    }
+    #[test]
+    fn test_local_without_match_reason_column() {
+        use arrow::array::ArrayRef;
+        let full = local_data_schema();
+        let legacy_fields: Vec<Field> = full
+            .fields()
+            .iter()
+            .filter(|f| {
+                ![
+                    "match_reason",
+                    "dagar",
+                    "match_quality",
+                    "snoozed_until",
+                    "schema_version",
+                ]
+                .contains(&f.name().as_str())
+            })
+            .map(|f| f.as_ref().clone())
+            .collect();
+        let schema = Arc::new(Schema::new(legacy_fields));
+        let mut buffer = Vec::new();
+        let mut writer = ArrowWriter::try_new(&mut buffer, schema.clone(), None).unwrap();
+        let null_string = || Arc::new(StringArray::from(vec![None::<&str>])) as ArrayRef;
+        let batch = RecordBatch::try_new(
+            schema,
+            vec![
+                Arc::new(BooleanArray::from(vec![true])),
+                Arc::new(BooleanArray::from(vec![false])),
+                null_string(),
+                Arc::new(StringArray::from(vec!["Storgatan 10"])),
+                null_string(),
+                null_string(),
+                null_string(),
+                null_string(),
+                Arc::new(UInt8Array::from(vec![None::<u8>])),
+                null_string(),
+                Arc::new(UInt64Array::from(vec![None::<u64>])),
+                null_string(),
+            ],
+        )
+        .unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+        let loaded = read_local_parquet_from_bytes(&buffer).expect("Legacy file should load");
+        assert_eq!(loaded.len(), 1);
+        assert_eq!(loaded[0].adress, "Storgatan 10");
+        assert!(loaded[0].match_reason.is_none());
+    }
+    /// Write a single-row local data file with the version 1 layout
+    /// (no `match_reason`, no `schema_version`), as older app builds did.
+    fn build_v1_local_bytes() -> Vec<u8> {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("valid", DataType::Boolean, false),
+            Field::new("active", DataType::Boolean, false),
+            Field::new("postnummer", DataType::Utf8, true),
+            Field::new("adress", DataType::Utf8, false),
+            Field::new("gata", DataType::Utf8, true),
+            Field::new("gatunummer", DataType::Utf8, true),
+            Field::new("info", DataType::Utf8, true),
+            Field::new("tid", DataType::Utf8, true),
+            Field::new("dag", DataType::UInt8, true),
+            Field::new("taxa", DataType::Utf8, true),
+            Field::new("antal_platser", DataType::UInt64, true),
+            Field::new("typ_av_parkering", DataType::Utf8, true),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(BooleanArray::from(vec![true])),
+                Arc::new(BooleanArray::from(vec![true])),
+                Arc::new(StringArray::from(vec![Some("21438")])),
+                Arc::new(StringArray::from(vec!["Kornettsgatan 18C"])),
+                Arc::new(StringArray::from(vec![Some("Kornettsgatan")])),
+                Arc::new(StringArray::from(vec![Some("18C")])),
+                Arc::new(StringArray::from(vec![None::<&str>])),
+                Arc::new(StringArray::from(vec![Some("0800-1200")])),
+                Arc::new(UInt8Array::from(vec![Some(15)])),
+                Arc::new(StringArray::from(vec![Some("Taxa C")])),
+                Arc::new(UInt64Array::from(vec![Some(12)])),
+                Arc::new(StringArray::from(vec![Some("Längsgående")])),
+            ],
+        )
+        .unwrap();
+        let mut buffer = Vec::new();
+        let mut writer = ArrowWriter::try_new(&mut buffer, schema, None).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+        buffer
+    }
+    #[test]
+    fn test_local_v1_file_migrates_to_current() {
+        let loaded =
+            read_local_parquet_from_bytes(&build_v1_local_bytes()).expect("v1 file should load");
+        assert_eq!(loaded.len(), 1);
+        let entry = &loaded[0];
+        assert!(entry.valid && entry.active);
+        assert_eq!(entry.adress, "Kornettsgatan 18C");
+        assert_eq!(entry.postnummer.as_deref(), Some("21438"));
+        assert_eq!(entry.tid.as_deref(), Some("0800-1200"));
+        assert_eq!(entry.dag, Some(15));
+        assert_eq!(entry.dagar, vec![15]);
+        assert_eq!(entry.antal_platser, Some(12));
+        assert!(entry.match_reason.is_none());
+        assert!(entry.match_quality.is_none());
+        assert!(entry.snoozed_until.is_none());
+        let rewritten = build_local_parquet(loaded).unwrap();
+        let reloaded = read_local_parquet_from_bytes(&rewritten).unwrap();
+        assert_eq!(reloaded[0].adress, "Kornettsgatan 18C");
+    }
+    #[test]
+    fn test_migrate_legacy_local_maps_fields() {
+        use arrow::array::Float64Array;
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("postnummer", DataType::UInt16, true),
+            Field::new("adress", DataType::Utf8, false),
+            Field::new("gata", DataType::Utf8, true),
+            Field::new("gatunummer", DataType::Utf8, true),
+            Field::new("info", DataType::Utf8, true),
+            Field::new("tid", DataType::Utf8, true),
+            Field::new("dag", DataType::UInt8, true),
+            Field::new("taxa", DataType::Utf8, true),
+            Field::new("lat", DataType::Float64, true),
+            Field::new("lon", DataType::Float64, true),
+            Field::new("distance", DataType::Float64, true),
+            Field::new("debug_closest_line_id", DataType::UInt64, true),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(UInt16Array::from(vec![Some(21438), None])),
+                Arc::new(StringArray::from(vec!["Kornettsgatan 18C", "Okändgatan 1"])),
+                Arc::new(StringArray::from(vec![Some("Kornettsgatan"), None])),
+                Arc::new(StringArray::from(vec![Some("18C"), None])),
+                Arc::new(StringArray::from(vec![Some("Städning"), Some("")])),
+                Arc::new(StringArray::from(vec![Some("0800-1200"), None])),
+                Arc::new(UInt8Array::from(vec![Some(15), None])),
+                Arc::new(StringArray::from(vec![Some("Taxa C"), None])),
+                Arc::new(Float64Array::from(vec![Some(55.6), None])),
+                Arc::new(Float64Array::from(vec![Some(13.0), None])),
+                Arc::new(Float64Array::from(vec![Some(4.2), None])),
+                Arc::new(UInt64Array::from(vec![Some(7), None])),
+            ],
+        )
+        .unwrap();
+        let mut buffer = Vec::new();
+        let mut writer = ArrowWriter::try_new(&mut buffer, schema, None).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+        let loaded = migrate_legacy_local(&buffer).expect("Legacy file should migrate");
+        assert_eq!(loaded.len(), 2);
+        let matched = &loaded[0];
+        assert!(matched.valid && matched.active);
+        assert_eq!(matched.postnummer.as_deref(), Some("21438"));
+        assert_eq!(matched.adress, "Kornettsgatan 18C");
+        assert_eq!(matched.gatunummer.as_deref(), Some("18C"));
+        assert_eq!(matched.info.as_deref(), Some("Städning"));
+        assert_eq!(matched.tid.as_deref(), Some("0800-1200"));
+        assert_eq!(matched.dagar, vec![15]);
+        assert_eq!(matched.taxa.as_deref(), Some("Taxa C"));
+        assert!(matched.antal_platser.is_none());
+        let unmatched = &loaded[1];
+        assert!(!unmatched.valid && unmatched.active);
+        assert!(unmatched.postnummer.is_none());
+        assert!(unmatched.dagar.is_empty());
+        let rewritten = read_local_parquet_from_bytes(&build_local_parquet(loaded).unwrap());
+        assert_eq!(rewritten.unwrap()[0].postnummer.as_deref(), Some("21438"));
+    }
+    #[test]
+    fn test_local_writes_current_schema_version() {
+        let entry = migrate_local_data(
+            read_local_parquet_from_bytes(&build_v1_local_bytes()).unwrap()[0].clone(),
+            1,
+        )
+        .unwrap();
+        let bytes = build_local_parquet(vec![entry]).unwrap();
+        let reader = ParquetRecordBatchReaderBuilder::try_new(Bytes::from(bytes))
+            .unwrap()
+            .build()
+            .unwrap();
+        for batch in reader {
+            let batch = batch.unwrap();
+            let versions = get_u8_column(&batch, "schema_version").unwrap();
+            assert_eq!(versions.value(0), LOCAL_DATA_SCHEMA_VERSION);
+        }
+    }
+    #[test]
+    fn test_local_rejects_newer_schema_version() {
+        let entry = read_local_parquet_from_bytes(&build_v1_local_bytes()).unwrap()[0].clone();
+        assert!(migrate_local_data(entry.clone(), LOCAL_DATA_SCHEMA_VERSION).is_ok());
+        assert!(migrate_local_data(entry.clone(), LOCAL_DATA_SCHEMA_VERSION + 1).is_err());
+        assert!(migrate_local_data(entry, 0).is_err());
+    }
 }
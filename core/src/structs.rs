@@ -7,13 +7,24 @@
 //! - **User Data**: [`LocalData`] for saved addresses with matched parking info
 //! - **Time-Based Restrictions**: [`DB`] with Swedish timezone-aware timestamps
 //! - **Settings**: [`SettingsData`] for user preferences
-//! - **Correlation Results**: [`OutputData`], [`CorrelationResult`] for matching outcomes
+//! - **Correlation Results**: [`OutputData`], [`CorrelationResult`] for matching outcomes,
+//!   with [`is_reliable_match`] flagging far or disagreeing matches
 //!
 //! # Time Handling
 //!
 //! All time-based operations use [`SWEDISH_TZ`] (Europe/Stockholm) with automatic
 //! DST handling. Times are stored in UTC but always interpreted in Swedish timezone.
 //!
+//! Wall-clock times that fall on a DST transition are resolved as follows:
+//!
+//! - **Fall-back (repeated hour)**: the first occurrence, in summer time, is
+//!   used. A restriction at 02:30 on the last Sunday of October starts at
+//!   00:30 UTC, and one ending at 03:00 covers both passes through 02:xx.
+//! - **Spring-forward (skipped hour)**: the time is read with the winter
+//!   offset that applied just before the jump, so 02:30 becomes 03:30 CEST.
+//!   A restriction lying entirely inside the skipped hour collapses to an
+//!   empty interval and is never active.
+//!
 //! # Examples
 //!
 //! ## Creating a Time-Based Restriction
@@ -53,9 +64,16 @@
 //!     println!("Parking restriction is currently active!");
 //! }
 //! ```
+use crate::correlation_algorithms::common::{MAX_DISTANCE_METERS, segment_length_meters};
+use crate::holidays::is_swedish_holiday;
+use crate::parking::{ParkingPlacement, taxa_rate_sek_per_hour};
 use chrono::{DateTime, Datelike, NaiveDate, NaiveTime, TimeZone, Timelike, Utc};
 use chrono_tz::{Europe::Stockholm, Tz};
 use rust_decimal::Decimal;
+use rust_decimal::prelude::ToPrimitive;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::ops::RangeInclusive;
 /// Swedish timezone constant for all time operations.
 ///
 /// This is set to `Europe/Stockholm` and automatically handles:
@@ -85,6 +103,43 @@ pub struct AdressClean {
     pub gata: String,
     pub gatunummer: String,
 }
+/// Longitudes (WGS84 degrees) an address must fall within to be located.
+///
+/// A generous box around Sweden: anything outside it, most commonly the
+/// `[0, 0]` left by unparseable or placeholder coordinates, cannot be near
+/// any Malmö segment.
+pub const SWEDEN_LONGITUDE: RangeInclusive<f64> = 10.0..=25.0;
+/// Latitudes (WGS84 degrees) an address must fall within to be located,
+/// see [`SWEDEN_LONGITUDE`]
+pub const SWEDEN_LATITUDE: RangeInclusive<f64> = 55.0..=69.5;
+impl AdressClean {
+    /// Whether the address has real coordinates inside Sweden.
+    ///
+    /// Addresses without a location must not be correlated: the nearest
+    /// segment to `[0, 0]` is meaningless. Correlation entry points skip
+    /// them and report them separately from addresses that simply had no
+    /// segment nearby.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use amp_core::structs::AdressClean;
+    /// use rust_decimal::Decimal;
+    ///
+    /// let mut address = AdressClean::default();
+    /// assert!(!address.has_location());
+    /// address.coordinates = [Decimal::new(130003, 4), Decimal::new(556050, 4)];
+    /// assert!(address.has_location());
+    /// ```
+    pub fn has_location(&self) -> bool {
+        match (self.coordinates[0].to_f64(), self.coordinates[1].to_f64()) {
+            (Some(lon), Some(lat)) => {
+                SWEDEN_LONGITUDE.contains(&lon) && SWEDEN_LATITUDE.contains(&lat)
+            }
+            _ => false,
+        }
+    }
+}
 /// Environmental parking restriction data (street cleaning zones).
 ///
 /// Represents a line segment with time-restricted parking, typically for
@@ -96,13 +151,82 @@ pub struct AdressClean {
 /// - `coordinates`: `[[x1, y1], [x2, y2]]` - start and end points of line segment
 /// - `info`: Restriction description (e.g., "Parkering förbjuden")
 /// - `tid`: Time range string in format "HHMM-HHMM" (e.g., "0800-1200")
-/// - `dag`: Day of month when restriction applies (1-31)
+/// - `dagar`: Days of month when restriction applies (1-31), ascending. Most
+///   segments have one; see [`parse_dagar`] for rules on several days
+/// - `length_m`: Segment length in meters, computed from `coordinates` by
+///   [`MiljoeDataClean::new`]. The GeoJSON loader sets it to the length of
+///   the whole line the segment was cut from
+/// - `segment_id`: Index of the GeoJSON feature the segment was cut from,
+///   shared by every segment of that feature. `None` unless set by the loader
 #[derive(Debug, Clone)]
 pub struct MiljoeDataClean {
     pub coordinates: [[Decimal; 2]; 2],
     pub info: String,
     pub tid: String,
-    pub dag: u8,
+    pub dagar: Vec<u8>,
+    pub length_m: f64,
+    pub segment_id: Option<u64>,
+}
+impl MiljoeDataClean {
+    /// Create a segment, measuring its length from the endpoints.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use amp_core::structs::MiljoeDataClean;
+    /// use rust_decimal::Decimal;
+    ///
+    /// let segment = MiljoeDataClean::new(
+    ///     [
+    ///         [Decimal::new(130, 1), Decimal::new(5500, 2)],
+    ///         [Decimal::new(130, 1), Decimal::new(55001, 3)],
+    ///     ],
+    ///     "Städning".to_string(),
+    ///     "0800-1200".to_string(),
+    ///     15,
+    /// );
+    /// assert!((segment.length_m - 111.0).abs() < 1.0);
+    /// ```
+    pub fn new(coordinates: [[Decimal; 2]; 2], info: String, tid: String, dag: u8) -> Self {
+        Self::with_dagar(coordinates, info, tid, vec![dag])
+    }
+    /// Create a segment whose restriction applies on several days of the month.
+    ///
+    /// `dagar` is sorted and deduplicated.
+    pub fn with_dagar(
+        coordinates: [[Decimal; 2]; 2],
+        info: String,
+        tid: String,
+        mut dagar: Vec<u8>,
+    ) -> Self {
+        dagar.sort_unstable();
+        dagar.dedup();
+        Self {
+            length_m: segment_length_meters(&coordinates),
+            coordinates,
+            info,
+            tid,
+            dagar,
+            segment_id: None,
+        }
+    }
+    /// Earliest day of the month the restriction applies on, or 0 without one.
+    ///
+    /// For code that handles a single day; the occurrence logic looks at
+    /// every entry of `dagar`.
+    pub fn dag(&self) -> u8 {
+        self.dagar.first().copied().unwrap_or(0)
+    }
+    /// Whether `tid` and `dagar` describe a schedule that can be turned into times.
+    ///
+    /// Uses the same rules as [`DB::from_params`] with default
+    /// [`TidOptions`], so a segment failing this check gives an address
+    /// without a usable restriction. Every listed day must be in 1-31.
+    pub fn has_usable_time(&self) -> bool {
+        !self.dagar.is_empty()
+            && self.dagar.iter().all(|dag| (1..=31).contains(dag))
+            && !parse_tid_intervals(&self.tid).is_empty()
+    }
 }
 /// Parking zone data with pricing information.
 ///
@@ -129,6 +253,18 @@ pub struct ParkeringsDataClean {
 /// parking restrictions. It contains optional matches for both environmental
 /// restrictions (miljö) and parking zones.
 ///
+/// `dagar` lists every day of the month the miljö restriction applies on,
+/// ascending. `dag` is the first of them, kept for readers that handle a
+/// single day.
+///
+/// `match_quality` is set by the server from the match distance, see
+/// [`OutputDataWithDistance::match_quality`]; it is `None` in files written
+/// before it existed.
+///
+/// `segment_id` is the [`MiljoeDataClean::segment_id`] of the miljö match,
+/// so addresses matched to the same segment can be found again. `None`
+/// without a miljö match and in files written before it existed.
+///
 /// # Examples
 ///
 /// ```
@@ -142,9 +278,12 @@ pub struct ParkeringsDataClean {
 ///     info: Some("Street cleaning".to_string()),
 ///     tid: Some("0800-1200".to_string()),
 ///     dag: Some(15),
+///     dagar: vec![15],
 ///     taxa: Some("Taxa C".to_string()),
 ///     antal_platser: Some(26),
 ///     typ_av_parkering: Some("Längsgående 6".to_string()),
+///     match_quality: None,
+///     segment_id: None,
 /// };
 ///
 /// assert!(output.has_match());
@@ -159,9 +298,12 @@ pub struct OutputData {
     pub info: Option<String>,
     pub tid: Option<String>,
     pub dag: Option<u8>,
+    pub dagar: Vec<u8>,
     pub taxa: Option<String>,
     pub antal_platser: Option<u64>,
     pub typ_av_parkering: Option<String>,
+    pub match_quality: Option<MatchQuality>,
+    pub segment_id: Option<u64>,
 }
 /// User's saved address with matched parking information and active status.
 ///
@@ -173,6 +315,12 @@ pub struct OutputData {
 ///
 /// - `valid`: Whether the address was successfully matched in the database
 /// - `active`: Whether notifications are enabled for this address
+/// - `match_reason`: Short machine-set reason for the chosen match (e.g. `"exact"`,
+///   `"substring"`), `None` for unmatched entries and files written before it existed
+/// - `match_quality`: How close the matched segment is, copied from the
+///   database entry; `None` when unknown
+/// - `snoozed_until`: UTC epoch seconds until which the address is hidden
+///   from the time panels, see [`snooze_to_next_occurrence`](Self::snooze_to_next_occurrence)
 /// - Other fields: Same as [`OutputData`] but with optional street components
 #[derive(Debug, Clone)]
 pub struct LocalData {
@@ -185,9 +333,171 @@ pub struct LocalData {
     pub info: Option<String>,
     pub tid: Option<String>,
     pub dag: Option<u8>,
+    pub dagar: Vec<u8>,
     pub taxa: Option<String>,
     pub antal_platser: Option<u64>,
     pub typ_av_parkering: Option<String>,
+    pub match_reason: Option<String>,
+    pub match_quality: Option<MatchQuality>,
+    pub snoozed_until: Option<i64>,
+}
+impl LocalData {
+    /// Snooze the address until its restriction next starts after `now`.
+    ///
+    /// Every day in `dagar` (or `dag` for rows without it) is tried with each
+    /// `tid` time range, keeping the earliest start, the same way
+    /// [`DB::next_start_after`] does. Holidays are skipped, as street cleaning
+    /// doesn't run on them. While a cleaning is running that is the following
+    /// occurrence, usually the same day next month.
+    ///
+    /// # Returns
+    ///
+    /// The instant the snooze ends, or `None` if there is no schedule to
+    /// snooze past, in which case `snoozed_until` is left unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use amp_core::structs::{LocalData, SWEDISH_TZ};
+    /// use chrono::{TimeZone, Utc};
+    ///
+    /// # let mut entry = LocalData {
+    /// #     valid: true, active: true, postnummer: None, adress: "Storgatan 10".to_string(),
+    /// #     gata: None, gatunummer: None, info: None, tid: Some("0800-1200".to_string()),
+    /// #     dag: Some(15), dagar: vec![15], taxa: None, antal_platser: None,
+    /// #     typ_av_parkering: None, match_reason: None, match_quality: None,
+    /// #     snoozed_until: None,
+    /// # };
+    /// let during = SWEDISH_TZ.with_ymd_and_hms(2024, 1, 15, 9, 0, 0).unwrap();
+    /// let until = entry.snooze_to_next_occurrence(during).unwrap();
+    /// assert_eq!(until, Utc.with_ymd_and_hms(2024, 2, 15, 7, 0, 0).unwrap());
+    /// assert!(entry.is_snoozed(during.with_timezone(&Utc)));
+    /// ```
+    pub fn snooze_to_next_occurrence(&mut self, now: DateTime<Tz>) -> Option<DateTime<Utc>> {
+        let intervals = parse_tid_intervals(self.tid.as_deref()?);
+        let dagar = if self.dagar.is_empty() {
+            vec![self.dag?]
+        } else {
+            self.dagar.clone()
+        };
+        let windows_on = |date: NaiveDate| {
+            if is_swedish_holiday(date) {
+                return Vec::new();
+            }
+            intervals
+                .iter()
+                .filter_map(|&window| window_on(date, window))
+                .collect()
+        };
+        let (next, _) = next_window_on_days(&dagar, windows_on, now.with_timezone(&Utc))?;
+        self.snoozed_until = Some(next.timestamp());
+        Some(next)
+    }
+    /// Whether the address is snoozed at `now`.
+    ///
+    /// The snooze ends exactly at `snoozed_until`.
+    pub fn is_snoozed(&self, now: DateTime<Utc>) -> bool {
+        self.snoozed_until
+            .is_some_and(|until| now.timestamp() < until)
+    }
+    /// Collapse entries for the same address into one, in place.
+    ///
+    /// Entries are the same address if `adress` matches case-insensitively
+    /// with whitespace collapsed and `postnummer` matches ignoring spaces, so
+    /// "Storgatan 10" and " storgatan  10" are merged. Of each group an
+    /// active entry is kept in preference to an inactive one, otherwise the
+    /// first. If the kept entry has no match, every match field is taken
+    /// from the first duplicate that has one, so a merged entry never mixes
+    /// two matches. Missing address parts and a snooze are filled in from
+    /// the others. Surviving entries stay in the order each group first
+    /// appeared.
+    ///
+    /// # Returns
+    ///
+    /// The number of entries removed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use amp_core::structs::LocalData;
+    ///
+    /// # let entry = |adress: &str, active: bool| LocalData {
+    /// #     valid: false, active, postnummer: None, adress: adress.to_string(),
+    /// #     gata: None, gatunummer: None, info: None, tid: None, dag: None,
+    /// #     dagar: vec![], taxa: None, antal_platser: None, typ_av_parkering: None,
+    /// #     match_reason: None, match_quality: None, snoozed_until: None,
+    /// # };
+    /// let mut list = vec![entry("Storgatan 10", false), entry("storgatan  10", true)];
+    /// assert_eq!(LocalData::dedup_in_place(&mut list), 1);
+    /// assert!(list[0].active);
+    /// ```
+    pub fn dedup_in_place(list: &mut Vec<LocalData>) -> usize {
+        let before = list.len();
+        let mut kept: Vec<LocalData> = Vec::with_capacity(before);
+        let mut seen: HashMap<(String, String), usize> = HashMap::new();
+        for entry in list.drain(..) {
+            let key = (
+                entry
+                    .adress
+                    .split_whitespace()
+                    .collect::<Vec<_>>()
+                    .join(" ")
+                    .to_lowercase(),
+                entry
+                    .postnummer
+                    .as_deref()
+                    .unwrap_or_default()
+                    .replace(' ', ""),
+            );
+            match seen.get(&key) {
+                Some(&idx) if entry.active && !kept[idx].active => {
+                    let previous = std::mem::replace(&mut kept[idx], entry);
+                    kept[idx].merge_match(previous);
+                }
+                Some(&idx) => kept[idx].merge_match(entry),
+                None => {
+                    seen.insert(key, kept.len());
+                    kept.push(entry);
+                }
+            }
+        }
+        *list = kept;
+        before - list.len()
+    }
+    /// Merge a duplicate entry into this one.
+    ///
+    /// The match fields are taken from `other` as a whole, and only if this
+    /// entry has no match of its own; address parts and the snooze are
+    /// filled in one by one.
+    fn merge_match(&mut self, other: LocalData) {
+        let take_match = !self.has_match() && other.has_match();
+        self.postnummer = self.postnummer.take().or(other.postnummer);
+        self.gata = self.gata.take().or(other.gata);
+        self.gatunummer = self.gatunummer.take().or(other.gatunummer);
+        self.snoozed_until = self.snoozed_until.or(other.snoozed_until);
+        if !take_match {
+            return;
+        }
+        self.valid = other.valid;
+        self.info = other.info;
+        self.tid = other.tid;
+        self.dag = other.dag;
+        self.dagar = other.dagar;
+        self.taxa = other.taxa;
+        self.antal_platser = other.antal_platser;
+        self.typ_av_parkering = other.typ_av_parkering;
+        self.match_reason = other.match_reason;
+        self.match_quality = other.match_quality;
+    }
+    /// Whether the entry carries a cleaning or parking match.
+    fn has_match(&self) -> bool {
+        self.tid.is_some()
+            || self.dag.is_some()
+            || !self.dagar.is_empty()
+            || self.taxa.is_some()
+            || self.antal_platser.is_some()
+            || self.typ_av_parkering.is_some()
+    }
 }
 /// User-stored address awaiting correlation with parking database.
 ///
@@ -239,6 +549,8 @@ impl StoredAddress {
     /// 3. Check if street names match (case-insensitive)
     /// 4. Check if street numbers match (ignoring building codes like U1, U4)
     ///
+    /// The reason reported by the matcher is stored in `LocalData::match_reason`.
+    ///
     /// # Examples
     ///
     /// ```no_run
@@ -262,10 +574,10 @@ impl StoredAddress {
             street, number,
         );
         for (stored_addr, db) in static_data {
-            if Self::fuzzy_match(stored_addr, &self.adress, &street, &number) {
+            if let Some(reason) = Self::fuzzy_match(stored_addr, &self.adress, &street, &number) {
                 println!(
-                    "[StoredAddress::to_local_data] ✅ MATCH! User: '{}' <-> DB: '{}'",
-                    self.adress, stored_addr,
+                    "[StoredAddress::to_local_data] ✅ MATCH ({})! User: '{}' <-> DB: '{}'",
+                    reason, self.adress, stored_addr,
                 );
                 return Some(LocalData {
                     valid: true,
@@ -277,9 +589,13 @@ impl StoredAddress {
                     info: db.info.clone(),
                     tid: Self::format_time_range(db),
                     dag: Self::extract_day_from_db(db),
+                    dagar: db.dagar.clone(),
                     taxa: db.taxa.clone(),
                     antal_platser: db.antal_platser,
                     typ_av_parkering: db.typ_av_parkering.clone(),
+                    match_reason: Some(reason.to_string()),
+                    match_quality: db.match_quality,
+                    snoozed_until: None,
                 });
             }
         }
@@ -330,7 +646,19 @@ impl StoredAddress {
     /// * `user_address` - Address entered by user
     /// * `street` - Parsed street name from user address
     /// * `number` - Parsed street number from user address
-    fn fuzzy_match(db_address: &str, user_address: &str, street: &str, number: &str) -> bool {
+    ///
+    /// # Returns
+    ///
+    /// `None` if the addresses don't match, otherwise the reason for the match:
+    /// - `"exact"`: Normalized addresses are identical
+    /// - `"substring"`: Street and number digits are contained in the database address
+    /// - `"street-only"`: Street matched and the user address has no number to compare
+    fn fuzzy_match(
+        db_address: &str,
+        user_address: &str,
+        street: &str,
+        number: &str,
+    ) -> Option<&'static str> {
         let normalize = |s: &str| {
             s.to_lowercase()
                 .chars()
@@ -338,38 +666,248 @@ impl StoredAddress {
                 .collect::<String>()
         };
         let db_norm = normalize(db_address);
-        let _user_norm = normalize(user_address);
+        let user_norm = normalize(user_address);
         let street_norm = normalize(street);
         if !db_norm.contains(&street_norm) {
-            return false;
+            return None;
+        }
+        if db_norm == user_norm {
+            return Some("exact");
         }
         if !number.is_empty() {
             let number_digits: String = number.chars().filter(|c| c.is_ascii_digit()).collect();
             if !number_digits.is_empty() {
-                return db_norm.contains(&number_digits);
+                return db_norm.contains(&number_digits).then_some("substring");
             }
         }
-        true
+        Some("street-only")
     }
     /// Format DB time range for display.
     ///
-    /// Converts Swedish timezone timestamps to "HHMM-HHMM" format.
+    /// "HHMM-HHMM" in Swedish time, see [`DB::tid`].
     fn format_time_range(db: &DB) -> Option<String> {
-        let start = db.start_time_swedish();
-        let end = db.end_time_swedish();
-        Some(format!(
-            "{:02}{:02}-{:02}{:02}",
-            start.hour(),
-            start.minute(),
-            end.hour(),
-            end.minute(),
-        ))
+        Some(db.tid())
     }
     /// Extract day of month from DB entry.
     fn extract_day_from_db(db: &DB) -> Option<u8> {
         Some(db.start_time_swedish().day() as u8)
     }
 }
+/// `tid` values that mean the restriction applies for the whole day.
+///
+/// Compared case-insensitively after trimming. The empty string is included
+/// because Malmö leaves `tid` blank on some all-day rows.
+pub const ALL_DAY_TID_MARKERS: &[&str] = &["", "00-24", "0000-2400", "heldygn", "dygnet runt"];
+/// Options controlling how `tid` strings are interpreted.
+///
+/// # Examples
+///
+/// ```
+/// use amp_core::structs::{TidOptions, parse_tid_interval};
+///
+/// let strict = TidOptions { all_day_markers: false };
+/// assert!(parse_tid_interval("heldygn", &strict).is_none());
+/// assert!(parse_tid_interval("heldygn", &TidOptions::default()).is_some());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TidOptions {
+    /// Map [`ALL_DAY_TID_MARKERS`] to 00:00–23:59 instead of rejecting them
+    pub all_day_markers: bool,
+}
+impl Default for TidOptions {
+    fn default() -> Self {
+        Self {
+            all_day_markers: true,
+        }
+    }
+}
+/// Parse a `tid` string into a start and end time.
+///
+/// Accepts the regular "HHMM-HHMM" format. When `options.all_day_markers` is
+/// set, the markers in [`ALL_DAY_TID_MARKERS`] are also accepted and map to
+/// 00:00–23:59.
+///
+/// # Arguments
+///
+/// * `tid` - Time range as found in the miljö data
+/// * `options` - Parsing options
+///
+/// # Returns
+///
+/// - `Some((start, end))` if the string was recognized. An `end` earlier
+///   than `start`, as in "2200-0600", is an overnight window that ends on
+///   the following day
+/// - `None` for malformed strings or out-of-range times
+pub fn parse_tid_interval(tid: &str, options: &TidOptions) -> Option<(NaiveTime, NaiveTime)> {
+    let trimmed = tid.trim();
+    if options.all_day_markers
+        && ALL_DAY_TID_MARKERS
+            .iter()
+            .any(|marker| trimmed.eq_ignore_ascii_case(marker))
+    {
+        return Some((
+            NaiveTime::from_hms_opt(0, 0, 0)?,
+            NaiveTime::from_hms_opt(23, 59, 0)?,
+        ));
+    }
+    let parts: Vec<&str> = trimmed.split('-').collect();
+    if parts.len() != 2 {
+        return None;
+    }
+    let parse_hhmm = |s: &str| -> Option<NaiveTime> {
+        let s = s.trim();
+        if s.len() != 4 {
+            return None;
+        }
+        let hour: u32 = s[0..2].parse().ok()?;
+        let minute: u32 = s[2..4].parse().ok()?;
+        NaiveTime::from_hms_opt(hour, minute, 0)
+    };
+    Some((parse_hhmm(parts[0])?, parse_hhmm(parts[1])?))
+}
+/// Parse a `tid` string that may list several time ranges per day.
+///
+/// Some miljö entries give two windows separated by commas, such as
+/// "0800-1000,1400-1600". Each part is parsed like [`parse_tid_interval`]
+/// with default [`TidOptions`]; a single part may also be an all-day marker.
+///
+/// # Returns
+///
+/// Every interval in the order written, or an empty vector if any part is
+/// malformed, so a half-readable schedule is rejected as a whole.
+///
+/// # Examples
+///
+/// ```
+/// use amp_core::structs::parse_tid_intervals;
+/// use chrono::NaiveTime;
+///
+/// let hm = |h, m| NaiveTime::from_hms_opt(h, m, 0).unwrap();
+/// assert_eq!(parse_tid_intervals("0800-1200"), vec![(hm(8, 0), hm(12, 0))]);
+/// assert_eq!(
+///     parse_tid_intervals("0800-1000, 1400-1600"),
+///     vec![(hm(8, 0), hm(10, 0)), (hm(14, 0), hm(16, 0))],
+/// );
+/// assert!(parse_tid_intervals("0800-1000,").is_empty());
+/// ```
+pub fn parse_tid_intervals(tid: &str) -> Vec<(NaiveTime, NaiveTime)> {
+    tid_intervals(tid, &TidOptions::default())
+}
+/// [`parse_tid_intervals`] with explicit options
+///
+/// All-day markers only count when they are the whole string; an empty part
+/// in a list is malformed, not "all day".
+fn tid_intervals(tid: &str, options: &TidOptions) -> Vec<(NaiveTime, NaiveTime)> {
+    if !tid.contains(',') {
+        return parse_tid_interval(tid, options).into_iter().collect();
+    }
+    let strict = TidOptions {
+        all_day_markers: false,
+    };
+    tid.split(',')
+        .map(|part| parse_tid_interval(part, &strict))
+        .collect::<Option<Vec<_>>>()
+        .unwrap_or_default()
+}
+/// Resolve a Swedish wall-clock time to a UTC instant.
+///
+/// Ambiguous times (autumn fall-back) resolve to the earlier instant. Times
+/// that don't exist (the 02:00–03:00 gap on spring-forward day) are read with
+/// the pre-transition offset, so 02:30 becomes 03:30 CEST.
+fn resolve_swedish_local(local: chrono::NaiveDateTime) -> Option<DateTime<Utc>> {
+    match SWEDISH_TZ.from_local_datetime(&local) {
+        chrono::LocalResult::Single(dt) => Some(dt.with_timezone(&Utc)),
+        chrono::LocalResult::Ambiguous(earliest, _) => Some(earliest.with_timezone(&Utc)),
+        chrono::LocalResult::None => SWEDISH_TZ
+            .from_local_datetime(&(local + chrono::Duration::hours(1)))
+            .earliest()
+            .map(|dt| dt.with_timezone(&Utc)),
+    }
+}
+/// Start and end instants of a Swedish-time `window` starting on `date`
+///
+/// The end rolls into the next day when it is earlier than the start.
+fn window_on(
+    date: NaiveDate,
+    (start, end): (NaiveTime, NaiveTime),
+) -> Option<(DateTime<Utc>, DateTime<Utc>)> {
+    let end_date = if end < start { date.succ_opt()? } else { date };
+    Some((
+        resolve_swedish_local(date.and_time(start))?,
+        resolve_swedish_local(end_date.and_time(end))?,
+    ))
+}
+/// Parse the `day` attribute of a miljö feature into days of the month.
+///
+/// Most features name a single day (`"15"`), but some rules run on several
+/// (`"1:a och 15:e"`, `"1, 15"`). Every number in the text is taken as a day
+/// and the result is sorted and deduplicated. Numbers outside 1-31 are kept
+/// so import reports can show them; they make the segment unusable.
+///
+/// # Examples
+///
+/// ```
+/// use amp_core::structs::parse_dagar;
+///
+/// assert_eq!(parse_dagar("15"), vec![15]);
+/// assert_eq!(parse_dagar("1:a och 15:e"), vec![1, 15]);
+/// assert_eq!(parse_dagar("15, 1"), vec![1, 15]);
+/// assert!(parse_dagar("Mån").is_empty());
+/// ```
+pub fn parse_dagar(raw: &str) -> Vec<u8> {
+    let mut dagar: Vec<u8> = raw
+        .split(|c: char| !c.is_ascii_digit())
+        .filter_map(|digits| digits.parse().ok())
+        .collect();
+    dagar.sort_unstable();
+    dagar.dedup();
+    dagar
+}
+/// Format days of the month as a comma-separated list, e.g. `"1,15"`.
+///
+/// The inverse of [`parse_dagar`] for sorted days; used where the days are
+/// stored as text.
+pub fn format_dagar(dagar: &[u8]) -> String {
+    dagar
+        .iter()
+        .map(u8::to_string)
+        .collect::<Vec<_>>()
+        .join(",")
+}
+/// First window starting strictly after `now` on one of `dagar`
+///
+/// Walks forward month by month from `now` (in [`SWEDISH_TZ`]), asking
+/// `windows_on` for the windows of each day; it returns none for a day the
+/// restriction doesn't run, such as a holiday. Months that lack a day (e.g.
+/// the 31st in April) are skipped.
+fn next_window_on_days(
+    dagar: &[u8],
+    windows_on: impl Fn(NaiveDate) -> Vec<(DateTime<Utc>, DateTime<Utc>)>,
+    now: DateTime<Utc>,
+) -> Option<(DateTime<Utc>, DateTime<Utc>)> {
+    let local_now = now.with_timezone(&SWEDISH_TZ);
+    let (mut year, mut month) = (local_now.year(), local_now.month());
+    // A day that exists at all shows up within the next 12 months, one
+    // more if it falls on a holiday
+    for _ in 0..=13 {
+        let window = dagar
+            .iter()
+            .filter_map(|&dag| NaiveDate::from_ymd_opt(year, month, dag as u32))
+            .flat_map(&windows_on)
+            .filter(|&(start, _)| start > now)
+            .min();
+        if window.is_some() {
+            return window;
+        }
+        if month == 12 {
+            month = 1;
+            year += 1;
+        } else {
+            month += 1;
+        }
+    }
+    None
+}
 /// Parameters for creating a [`DB`] entry from day and time strings.
 ///
 /// This struct groups all parameters needed to create a time-based parking
@@ -424,7 +962,8 @@ pub struct DBParams {
 /// - **Valid years**: 2020-2100 (prevents overflow and ensures realistic dates)
 /// - **Valid months**: 1-12
 /// - **Valid days**: 1-31 (validated by chrono)
-/// - **Valid time format**: "HHMM-HHMM" (e.g., "0800-1200")
+/// - **Valid time format**: "HHMM-HHMM" (e.g., "0800-1200"); an end before
+///   the start (e.g., "2200-0600") ends on the following day
 /// - **Timezone**: All times stored in UTC, displayed in [`SWEDISH_TZ`]
 /// - **DST**: Automatic handling of summer/winter time shifts
 ///
@@ -493,6 +1032,25 @@ pub struct DB {
     pub antal_platser: Option<u64>,
     /// Type of parking (e.g., "Längsgående 6" for parallel parking)
     pub typ_av_parkering: Option<String>,
+    /// Space orientation parsed from `typ_av_parkering`, e.g. "längsgående"
+    pub orientation: Option<String>,
+    /// Meters of curb per space parsed from `typ_av_parkering`, e.g. 6.0
+    pub space_length_m: Option<f64>,
+    /// Days of month the restriction applies on, ascending. Always includes
+    /// the day of this occurrence; see [`DB::with_dagar`]
+    pub dagar: Vec<u8>,
+    /// How close the address is to the matched segment, `None` when the
+    /// source data predates it; see [`DB::with_match_quality`]
+    pub match_quality: Option<MatchQuality>,
+    /// Swedish-time ranges after the first on each day, for a `tid` such as
+    /// "0800-1000,1400-1600". Empty for the usual single range
+    pub later_windows: Vec<(NaiveTime, NaiveTime)>,
+    /// Whether the restriction is lifted on Swedish public holidays, as
+    /// street cleaning is. On by default; see [`DB::with_holiday_skipping`]
+    pub skips_holidays: bool,
+    /// Miljö segment the address was matched to, `None` when the source data
+    /// predates it; see [`DB::with_segment_id`]
+    pub segment_id: Option<u64>,
 }
 impl DB {
     /// Create a new DB entry from day and time strings (legacy interface).
@@ -505,7 +1063,8 @@ impl DB {
     /// * `gatunummer` - Optional street number
     /// * `info` - Optional restriction description
     /// * `dag` - Day of month (1-31)
-    /// * `tid` - Time range in "HHMM-HHMM" format (e.g., "0800-1200")
+    /// * `tid` - Time range in "HHMM-HHMM" format (e.g., "0800-1200"), or an
+    ///   all-day marker from [`ALL_DAY_TID_MARKERS`]
     /// * `taxa` - Optional parking zone tier
     /// * `antal_platser` - Optional number of parking spots
     /// * `typ_av_parkering` - Optional parking type
@@ -602,6 +1161,46 @@ impl DB {
     ///
     /// [`from_dag_tid`]: Self::from_dag_tid
     pub fn from_params(params: DBParams) -> Option<Self> {
+        Self::from_params_with_options(params, &TidOptions::default())
+    }
+    /// Create a new DB entry with explicit [`TidOptions`].
+    ///
+    /// Same as [`from_params`], but lets the caller decide whether all-day
+    /// markers such as "heldygn" are accepted.
+    ///
+    /// Start and end times inside a DST transition hour are resolved rather
+    /// than rejected; see the [module docs](crate::structs#time-handling) for the policy.
+    ///
+    /// A `tid` with several ranges ([`parse_tid_intervals`]) sets `start_time`
+    /// and `end_time` from the earliest and keeps the rest in
+    /// [`later_windows`](Self::later_windows).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use amp_core::structs::{DB, DBParams, TidOptions};
+    ///
+    /// let params = DBParams {
+    ///     postnummer: None,
+    ///     adress: "Storgatan 10".to_string(),
+    ///     gata: None,
+    ///     gatunummer: None,
+    ///     info: None,
+    ///     dag: 15,
+    ///     tid: "heldygn".to_string(),
+    ///     taxa: None,
+    ///     antal_platser: None,
+    ///     typ_av_parkering: None,
+    ///     year: 2024,
+    ///     month: 1,
+    /// };
+    /// let strict = TidOptions { all_day_markers: false };
+    /// assert!(DB::from_params_with_options(params.clone(), &strict).is_none());
+    /// assert!(DB::from_params(params).is_some());
+    /// ```
+    ///
+    /// [`from_params`]: Self::from_params
+    pub fn from_params_with_options(params: DBParams, options: &TidOptions) -> Option<Self> {
         if !(2020..=2100).contains(&params.year) {
             eprintln!("[DB] Invalid year: {} (must be 2020-2100)", params.year);
             return None;
@@ -610,36 +1209,33 @@ impl DB {
             eprintln!("[DB] Invalid month: {} (must be 1-12)", params.month);
             return None;
         }
-        let parts: Vec<&str> = params.tid.split('-').collect();
-        if parts.len() != 2 {
+        let mut intervals = tid_intervals(&params.tid, options);
+        if intervals.is_empty() {
             eprintln!(
                 "[DB] Invalid time format: '{}' (expected HHMM-HHMM)",
                 params.tid
             );
             return None;
         }
-        let parse_hhmm = |s: &str| -> Option<NaiveTime> {
-            let s = s.trim();
-            if s.len() != 4 {
-                return None;
-            }
-            let hour: u32 = s[0..2].parse().ok()?;
-            let minute: u32 = s[2..4].parse().ok()?;
-            NaiveTime::from_hms_opt(hour, minute, 0)
-        };
-        let start_naive_time = parse_hhmm(parts[0])?;
-        let end_naive_time = parse_hhmm(parts[1])?;
+        intervals.sort();
+        let (start_naive_time, end_naive_time) = intervals.remove(0);
         let date = NaiveDate::from_ymd_opt(params.year, params.month, params.dag as u32)?;
+        let end_date = if end_naive_time < start_naive_time {
+            date.succ_opt()?
+        } else {
+            date
+        };
         let start_datetime = date.and_time(start_naive_time);
-        let end_datetime = date.and_time(end_naive_time);
-        let start_time = SWEDISH_TZ
-            .from_local_datetime(&start_datetime)
-            .single()?
-            .with_timezone(&Utc);
-        let end_time = SWEDISH_TZ
-            .from_local_datetime(&end_datetime)
-            .single()?
-            .with_timezone(&Utc);
+        let end_datetime = end_date.and_time(end_naive_time);
+        let start_time = resolve_swedish_local(start_datetime)?;
+        let end_time = resolve_swedish_local(end_datetime)?;
+        let placement = params
+            .typ_av_parkering
+            .as_deref()
+            .and_then(ParkingPlacement::parse);
+        let (orientation, space_length_m) = placement
+            .map(|p| (p.orientation, p.space_length_m))
+            .unwrap_or_default();
         Some(DB {
             postnummer: params.postnummer,
             adress: params.adress,
@@ -651,17 +1247,171 @@ impl DB {
             taxa: params.taxa,
             antal_platser: params.antal_platser,
             typ_av_parkering: params.typ_av_parkering,
+            orientation,
+            space_length_m,
+            dagar: vec![params.dag],
+            match_quality: None,
+            later_windows: intervals,
+            skips_holidays: true,
+            segment_id: None,
         })
     }
-    /// Check if the restriction is currently active.
+    /// Record every day of the month the restriction applies on.
     ///
-    /// # Arguments
+    /// A `DB` holds one occurrence, built for a single day. The other days
+    /// decide when it starts again: see [`next_start_after`] and
+    /// [`crate::buckets`]. Days outside 1-31 are dropped; the occurrence's
+    /// own day is always kept.
     ///
-    /// * `now` - Current time in UTC (will be compared against stored UTC times)
+    /// # Examples
     ///
-    /// # Returns
+    /// ```
+    /// use amp_core::structs::DB;
+    /// use chrono::{TimeZone, Utc};
+    ///
+    /// let db = DB::from_dag_tid(
+    ///     None, "Storgatan 10".to_string(), None, None, None,
+    ///     1, "0800-1200", None, None, None, 2024, 1,
+    /// ).unwrap().with_dagar([1, 15]);
+    /// let after_first = Utc.with_ymd_and_hms(2024, 1, 2, 12, 0, 0).unwrap();
+    /// assert_eq!(
+    ///     db.next_start_after(after_first),
+    ///     Some(Utc.with_ymd_and_hms(2024, 1, 15, 7, 0, 0).unwrap()),
+    /// );
+    /// ```
+    ///
+    /// [`next_start_after`]: Self::next_start_after
+    pub fn with_dagar(mut self, dagar: impl IntoIterator<Item = u8>) -> Self {
+        self.dagar
+            .extend(dagar.into_iter().filter(|dag| (1..=31).contains(dag)));
+        self.dagar.sort_unstable();
+        self.dagar.dedup();
+        self
+    }
+    /// Attach the server's [`MatchQuality`] for this address.
+    pub fn with_match_quality(mut self, match_quality: Option<MatchQuality>) -> Self {
+        self.match_quality = match_quality;
+        self
+    }
+    /// Attach the [`OutputData::segment_id`] of the matched miljö segment.
+    pub fn with_segment_id(mut self, segment_id: Option<u64>) -> Self {
+        self.segment_id = segment_id;
+        self
+    }
+    /// Choose whether Swedish public holidays lift the restriction.
+    ///
+    /// Street cleaning doesn't run on a holiday, so by default no window
+    /// starts on one: not this occurrence's and not a later day's. See
+    /// [`is_swedish_holiday`](crate::holidays::is_swedish_holiday).
+    pub fn with_holiday_skipping(mut self, skips_holidays: bool) -> Self {
+        self.skips_holidays = skips_holidays;
+        self
+    }
+    /// Day of month of this occurrence.
+    ///
+    /// For a restriction on several days this is the day the entry was built
+    /// for, normally the soonest upcoming one (see
+    /// [`determine_occurrence`](crate::buckets::determine_occurrence)).
+    pub fn dag(&self) -> u8 {
+        self.start_time_swedish().day() as u8
+    }
+    /// Time range of this occurrence in "HHMM-HHMM" format, in Swedish time.
+    ///
+    /// All-day restrictions give `"0000-2359"`. With
+    /// [`later_windows`](Self::later_windows) every range is listed,
+    /// separated by commas as in the source data.
+    pub fn tid(&self) -> String {
+        self.daily_windows()
+            .iter()
+            .map(|(start, end)| format!("{}-{}", start.format("%H%M"), end.format("%H%M")))
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+    /// Every Swedish-time range of a restriction day, this occurrence's first
+    pub fn daily_windows(&self) -> Vec<(NaiveTime, NaiveTime)> {
+        let first = (
+            self.start_time_swedish().time(),
+            self.end_time_swedish().time(),
+        );
+        std::iter::once(first)
+            .chain(self.later_windows.iter().copied())
+            .collect()
+    }
+    /// Whether windows start on Swedish `date`: it is one of
+    /// [`dagar`](Self::dagar) and, unless holidays are kept, not a holiday
+    fn runs_on(&self, date: NaiveDate) -> bool {
+        self.dagar.contains(&(date.day() as u8))
+            && !(self.skips_holidays && is_swedish_holiday(date))
+    }
+    /// Start and end instants of each window starting on Swedish `date`
+    ///
+    /// Empty on a day the restriction doesn't run, see [`runs_on`](Self::runs_on).
+    /// Every other window calculation goes through here.
+    pub(crate) fn windows_on(&self, date: NaiveDate) -> Vec<(DateTime<Utc>, DateTime<Utc>)> {
+        if !self.runs_on(date) {
+            return Vec::new();
+        }
+        self.daily_windows()
+            .into_iter()
+            .filter_map(|window| window_on(date, window))
+            .collect()
+    }
+    /// Start and end instants of each window on this occurrence's day
+    ///
+    /// Empty if that day is a skipped holiday.
+    fn occurrence_windows(&self) -> Vec<(DateTime<Utc>, DateTime<Utc>)> {
+        self.windows_on(self.start_time_swedish().date_naive())
+    }
+    /// First window starting strictly after `now` on any of the restriction's days
+    ///
+    /// See [`next_window_on_days`]; days the restriction doesn't run are skipped.
+    fn next_window_after(&self, now: DateTime<Utc>) -> Option<(DateTime<Utc>, DateTime<Utc>)> {
+        next_window_on_days(&self.dagar, |date| self.windows_on(date), now)
+    }
+    /// Next start strictly after `now` on any of the restriction's days.
+    ///
+    /// Repeats this occurrence's time ranges on each entry of `dagar` and
+    /// keeps the earliest, skipping holidays as [`is_active_at`](Self::is_active_at) does.
+    pub fn next_start_after(&self, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        self.next_window_after(now).map(|(start, _)| start)
+    }
+    /// Start and end of the next window that starts strictly after `from`.
+    ///
+    /// Repeats this occurrence's time range on each of [`dagar`](Self::dagar)
+    /// in later months, as [`next_start_after`](Self::next_start_after) does.
+    /// A day missing from a month is skipped until a month that has it, so a
+    /// restriction on the 31st goes from January 31 to March 31. Holidays are
+    /// skipped the same way. An overnight range ends on the following day.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use amp_core::structs::{DB, SWEDISH_TZ};
+    /// use chrono::TimeZone;
+    ///
+    /// let db = DB::from_dag_tid(
+    ///     None, "Storgatan 10".to_string(), None, None, None,
+    ///     15, "0800-1200", None, None, None, 2024, 1,
+    /// ).unwrap();
+    /// let from = SWEDISH_TZ.with_ymd_and_hms(2024, 1, 20, 9, 0, 0).unwrap();
+    /// let (start, end) = db.next_occurrence(from).unwrap();
+    /// assert_eq!(start, SWEDISH_TZ.with_ymd_and_hms(2024, 2, 15, 8, 0, 0).unwrap());
+    /// assert_eq!(end, SWEDISH_TZ.with_ymd_and_hms(2024, 2, 15, 12, 0, 0).unwrap());
+    /// ```
+    pub fn next_occurrence(&self, from: DateTime<Tz>) -> Option<(DateTime<Tz>, DateTime<Tz>)> {
+        let (start, end) = self.next_window_after(from.with_timezone(&Utc))?;
+        Some((
+            start.with_timezone(&SWEDISH_TZ),
+            end.with_timezone(&SWEDISH_TZ),
+        ))
+    }
+    /// Check if the restriction is currently active.
+    ///
+    /// Same as [`is_active_at`](Self::is_active_at) for `now` in Swedish time.
+    ///
+    /// # Arguments
     ///
-    /// `true` if `now` falls within `[start_time, end_time)`, `false` otherwise.
+    /// * `now` - Current time in UTC
     ///
     /// # Examples
     ///
@@ -678,7 +1428,48 @@ impl DB {
     /// }
     /// ```
     pub fn is_active(&self, now: DateTime<Utc>) -> bool {
-        now >= self.start_time && now < self.end_time
+        self.is_active_at(now.with_timezone(&SWEDISH_TZ))
+    }
+    /// Check if the restriction is active at an arbitrary instant.
+    ///
+    /// The restriction repeats every month, so `when` need not fall in the
+    /// month this entry was built for: it is active if the Swedish date of
+    /// `when` is one of [`dagar`](Self::dagar) and `when` lies within
+    /// `[start, end)` of this occurrence's time range on that date. An
+    /// overnight range such as "2200-0600" is also checked from the day
+    /// before. Used for previews such as "what happens next Tuesday".
+    ///
+    /// Street cleaning is skipped on Swedish public holidays, so an
+    /// occurrence starting on one is never active; see
+    /// [`with_holiday_skipping`](Self::with_holiday_skipping).
+    ///
+    /// Wall-clock times on a DST transition day resolve as in
+    /// [`from_params`](Self::from_params): a start in the spring-forward gap
+    /// moves past it, so 02:30 is read as 03:30 CEST.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use amp_core::structs::{DB, SWEDISH_TZ};
+    /// use chrono::TimeZone;
+    ///
+    /// let db = DB::from_dag_tid(
+    ///     None, "Storgatan 10".to_string(), None, None, None,
+    ///     15, "0800-1200", None, None, None, 2024, 1,
+    /// ).unwrap();
+    /// let in_july = SWEDISH_TZ.with_ymd_and_hms(2024, 7, 15, 9, 0, 0).unwrap();
+    /// assert!(db.is_active_at(in_july));
+    /// let next_day = SWEDISH_TZ.with_ymd_and_hms(2024, 7, 16, 9, 0, 0).unwrap();
+    /// assert!(!db.is_active_at(next_day));
+    /// ```
+    pub fn is_active_at(&self, when: DateTime<Tz>) -> bool {
+        let date = when.with_timezone(&SWEDISH_TZ).date_naive();
+        // An overnight window started on the previous day may still be running
+        [Some(date), date.pred_opt()]
+            .into_iter()
+            .flatten()
+            .flat_map(|day| self.windows_on(day))
+            .any(|(start, end)| when >= start && when < end)
     }
     /// Get duration until restriction starts (if in future).
     ///
@@ -688,14 +1479,16 @@ impl DB {
     ///
     /// # Returns
     ///
-    /// - `Some(Duration)` if restriction hasn't started yet
-    /// - `None` if restriction has already started or ended
+    /// - `Some(Duration)` until the next of this occurrence's windows starts
+    /// - `None` if the last window has already started or ended, or the
+    ///   occurrence falls on a skipped holiday
     pub fn time_until_start(&self, now: DateTime<Utc>) -> Option<chrono::Duration> {
-        if now < self.start_time {
-            Some(self.start_time - now)
-        } else {
-            None
-        }
+        self.occurrence_windows()
+            .into_iter()
+            .map(|(start, _)| start)
+            .filter(|&start| now < start)
+            .min()
+            .map(|start| start - now)
     }
     /// Get duration until restriction ends (if active or in future).
     ///
@@ -705,8 +1498,10 @@ impl DB {
     ///
     /// # Returns
     ///
-    /// - `Some(Duration)` if restriction is active or hasn't started
-    /// - `None` if restriction has already ended
+    /// - `Some(Duration)` if restriction is active or hasn't started, until
+    ///   the end of the current or next of this occurrence's windows
+    /// - `None` if the last window has already ended, or the occurrence falls
+    ///   on a skipped holiday
     ///
     /// # Examples
     ///
@@ -723,11 +1518,12 @@ impl DB {
     /// }
     /// ```
     pub fn time_until_end(&self, now: DateTime<Utc>) -> Option<chrono::Duration> {
-        if now < self.end_time {
-            Some(self.end_time - now)
-        } else {
-            None
-        }
+        self.occurrence_windows()
+            .into_iter()
+            .map(|(_, end)| end)
+            .filter(|&end| now < end)
+            .min()
+            .map(|end| end - now)
     }
     /// Get start time in Swedish timezone for display.
     ///
@@ -760,17 +1556,223 @@ impl DB {
     pub fn end_time_swedish(&self) -> DateTime<Tz> {
         self.end_time.with_timezone(&SWEDISH_TZ)
     }
+    /// One-line summary in the user's language, for share text and the UI.
+    ///
+    /// Like the [`Display`] form, with the day label translated and the
+    /// number of parking spaces appended when known. `lang` is a
+    /// [`SettingsData::language`] value; languages without translations
+    /// fall back to Swedish.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use amp_core::structs::DB;
+    ///
+    /// let db = DB::from_dag_tid(
+    ///     None, "Storgatan 10".to_string(), None, None, None,
+    ///     15, "0800-1200", Some("Taxa C".to_string()), Some(26), None, 2024, 1,
+    /// ).unwrap();
+    /// assert_eq!(db.summary("English"), "Storgatan 10 · day 15 · 08:00–12:00 · Taxa C · 26 spaces");
+    /// assert_eq!(db.summary("Svenska"), "Storgatan 10 · dag 15 · 08:00–12:00 · Taxa C · 26 platser");
+    /// ```
+    ///
+    /// [`Display`]: std::fmt::Display
+    pub fn summary(&self, lang: &str) -> String {
+        let (day, spaces) = match lang {
+            "English" => ("day", "spaces"),
+            _ => ("dag", "platser"),
+        };
+        let mut parts = self.summary_parts(day);
+        if let Some(count) = self.antal_platser {
+            parts.push(format!("{} {}", count, spaces));
+        }
+        parts.join(" · ")
+    }
+    /// Address, day, Swedish-time interval and taxa, as shown in summaries
+    fn summary_parts(&self, day_label: &str) -> Vec<String> {
+        let windows: Vec<String> = self
+            .daily_windows()
+            .iter()
+            .map(|(start, end)| format!("{}–{}", start.format("%H:%M"), end.format("%H:%M")))
+            .collect();
+        let mut parts = vec![
+            self.adress.clone(),
+            format!("{} {}", day_label, self.start_time_swedish().day()),
+            windows.join(", "),
+        ];
+        parts.extend(self.taxa.clone());
+        parts
+    }
+}
+impl std::fmt::Display for DB {
+    /// Concise summary for logs, e.g. `Storgatan 10 · dag 15 · 08:00–12:00 · Taxa C`.
+    ///
+    /// Times are in Swedish local time; the taxa is left out when unknown.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.summary_parts("dag").join(" · "))
+    }
+}
+/// Matches farther away than this are never flagged reliable.
+pub const RELIABLE_MATCH_METERS: f64 = 15.0;
+/// When both datasets matched, a match more than this much farther away than
+/// the other one is flagged unreliable.
+///
+/// The datasets carry no street names, so the two segments agreeing on
+/// distance stands in for them being on the same street: a 45 m parkering
+/// match next to a 3 m miljö match is most likely across a block.
+pub const STREET_AGREEMENT_METERS: f64 = 10.0;
+/// Whether a match at `distance` can be presented as trustworthy.
+///
+/// # Arguments
+///
+/// * `distance` - Distance in meters to the matched segment
+/// * `other_distance` - Distance of the other dataset's match, if any
+///
+/// # Examples
+///
+/// ```
+/// use amp_core::structs::is_reliable_match;
+///
+/// assert!(is_reliable_match(3.0, None));
+/// assert!(is_reliable_match(12.0, Some(4.0)));
+/// assert!(!is_reliable_match(18.0, None));
+/// assert!(!is_reliable_match(14.0, Some(2.0)));
+/// ```
+pub fn is_reliable_match(distance: f64, other_distance: Option<f64>) -> bool {
+    distance <= RELIABLE_MATCH_METERS
+        && other_distance.is_none_or(|other| distance - other <= STREET_AGREEMENT_METERS)
+}
+/// How much a match at `distance` can be trusted, from 0.0 to 1.0.
+///
+/// Falls linearly from 1.0 on the segment to 0.0 at `cutoff`, so a 5 m and a
+/// 48 m match are no longer equally "matched". A NaN distance scores 0.0.
+///
+/// # Examples
+///
+/// ```
+/// use amp_core::structs::match_confidence;
+///
+/// assert!((match_confidence(5.0, 50.0) - 0.9).abs() < 1e-9);
+/// assert_eq!(match_confidence(60.0, 50.0), 0.0);
+/// assert_eq!(match_confidence(0.0, 20.0), 1.0);
+/// ```
+pub fn match_confidence(distance: f64, cutoff: f64) -> f64 {
+    let confidence = 1.0 - distance / cutoff;
+    if confidence.is_nan() {
+        0.0
+    } else {
+        confidence.clamp(0.0, 1.0)
+    }
+}
+/// Dataset a correlation match came from, see
+/// [`CorrelationResult::closest_match_source`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatchSource {
+    /// Environmental parking restrictions (miljödata)
+    Miljo,
+    /// Parking fee zones (parkering)
+    Parkering,
+}
+/// Closer of a miljö and a parkering distance, with the dataset it came from.
+///
+/// Ties go to miljö, the match the app shows first. Compared with
+/// [`f64::total_cmp`], so a NaN distance loses to any real one.
+fn closest_match(miljo: Option<f64>, parkering: Option<f64>) -> Option<(MatchSource, f64)> {
+    match (miljo, parkering) {
+        (Some(m), Some(p)) if p.total_cmp(&m).is_lt() => Some((MatchSource::Parkering, p)),
+        (Some(m), _) => Some((MatchSource::Miljo, m)),
+        (None, Some(p)) => Some((MatchSource::Parkering, p)),
+        (None, None) => None,
+    }
+}
+/// Default radius within which a match counts as [`MatchQuality::Exact`].
+pub const DEFAULT_EXACT_RADIUS_METERS: f64 = 5.0;
+/// How closely an address sits to its matched segment.
+///
+/// Every match within the cutoff is used, but one a few meters away is
+/// almost certainly right while one near the cutoff may be on the wrong side
+/// of the block. The UI shows this as a checkmark or a warning.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatchQuality {
+    /// Within the exact radius
+    Exact,
+    /// Past the exact radius but within the cutoff
+    Approximate,
+    /// No match within the cutoff
+    None,
+}
+impl MatchQuality {
+    /// All qualities, best first
+    pub const ALL: [MatchQuality; 3] = [
+        MatchQuality::Exact,
+        MatchQuality::Approximate,
+        MatchQuality::None,
+    ];
+    /// Classify a match by its distance.
+    ///
+    /// # Arguments
+    ///
+    /// * `distance` - Distance in meters to the matched segment, `None` if unmatched
+    /// * `exact_radius` - Largest distance still counted as exact
+    /// * `cutoff` - Largest distance counted as a match at all
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use amp_core::structs::MatchQuality;
+    ///
+    /// assert_eq!(MatchQuality::from_distance(Some(3.0), 5.0, 50.0), MatchQuality::Exact);
+    /// assert_eq!(MatchQuality::from_distance(Some(20.0), 5.0, 50.0), MatchQuality::Approximate);
+    /// assert_eq!(MatchQuality::from_distance(None, 5.0, 50.0), MatchQuality::None);
+    /// ```
+    pub fn from_distance(distance: Option<f64>, exact_radius: f64, cutoff: f64) -> Self {
+        match distance {
+            Some(dist) if dist <= exact_radius && dist <= cutoff => MatchQuality::Exact,
+            Some(dist) if dist <= cutoff => MatchQuality::Approximate,
+            _ => MatchQuality::None,
+        }
+    }
+    /// Stable code used in parquet files
+    pub fn code(&self) -> &'static str {
+        match self {
+            MatchQuality::Exact => "exact",
+            MatchQuality::Approximate => "approximate",
+            MatchQuality::None => "none",
+        }
+    }
+    /// Parse a stored code back into a quality
+    ///
+    /// # Returns
+    /// `None` for unknown codes, e.g. ones written by a newer version
+    pub fn from_code(code: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|quality| quality.code() == code)
+    }
 }
 /// Result of address-to-parking correlation with distance information.
 ///
 /// This extends [`OutputData`] with optional distance measurements to the
-/// matched parking zones, useful for debugging and verification.
+/// matched parking zones, useful for debugging and verification. Both
+/// matches are always kept; `miljo_reliable` and `parkering_reliable` flag
+/// the ones [`is_reliable_match`] considers shaky so they can be shown
+/// de-emphasized rather than dropped.
 #[derive(Debug, Clone)]
 pub struct CorrelationResult {
     pub address: String,
     pub postnummer: String,
     pub miljo_match: Option<(f64, String)>,
     pub parkering_match: Option<(f64, String)>,
+    /// `false` if there is no miljö match or it is unreliable
+    pub miljo_reliable: bool,
+    /// `false` if there is no parkering match or it is unreliable
+    pub parkering_reliable: bool,
+    /// Main rate of the parkering match's zone in SEK per hour, `None`
+    /// without a match, for a zone missing from the tariff table or until
+    /// set with [`with_taxa_rates`](Self::with_taxa_rates)
+    pub parkering_rate_sek_per_hour: Option<Decimal>,
+    /// [`match_confidence`] of the closer match, 0.0 without one. Relative to
+    /// [`MAX_DISTANCE_METERS`] unless set with
+    /// [`with_cutoff`](Self::with_cutoff)
+    pub confidence: f64,
 }
 impl OutputData {
     /// Check if this address has any parking data matches.
@@ -802,12 +1804,37 @@ impl OutputData {
 ///
 /// Used for correlation algorithms that track distances to parking zones
 /// for debugging and verification purposes.
+#[derive(Debug)]
 pub struct OutputDataWithDistance {
     pub data: OutputData,
     pub miljo_distance: Option<f64>,
     pub parkering_distance: Option<f64>,
+    /// `false` if the address was skipped for lacking coordinates, see
+    /// [`AdressClean::has_location`]
+    pub has_location: bool,
 }
 impl OutputDataWithDistance {
+    /// Whether the miljö match exists and is reliable, see [`is_reliable_match`]
+    pub fn miljo_reliable(&self) -> bool {
+        self.miljo_distance
+            .is_some_and(|dist| is_reliable_match(dist, self.parkering_distance))
+    }
+    /// Whether the parkering match exists and is reliable, see [`is_reliable_match`]
+    pub fn parkering_reliable(&self) -> bool {
+        self.parkering_distance
+            .is_some_and(|dist| is_reliable_match(dist, self.miljo_distance))
+    }
+    /// Quality of the match the app shows: miljö if matched, else parkering.
+    ///
+    /// `cutoff` should be the largest cutoff the correlation used, so no
+    /// kept match is classified as [`MatchQuality::None`].
+    pub fn match_quality(&self, exact_radius: f64, cutoff: f64) -> MatchQuality {
+        MatchQuality::from_distance(
+            self.miljo_distance.or(self.parkering_distance),
+            exact_radius,
+            cutoff,
+        )
+    }
     /// Get the closest distance among all matches.
     ///
     /// # Returns
@@ -815,15 +1842,106 @@ impl OutputDataWithDistance {
     /// Minimum of `miljo_distance` and `parkering_distance`, or `None`
     /// if neither distance is available.
     pub fn closest_distance(&self) -> Option<f64> {
-        match (self.miljo_distance, self.parkering_distance) {
-            (Some(m), Some(p)) => Some(m.min(p)),
-            (Some(m), None) => Some(m),
-            (None, Some(p)) => Some(p),
-            (None, None) => None,
+        closest_match(self.miljo_distance, self.parkering_distance).map(|(_, dist)| dist)
+    }
+    /// Dataset that produced [`closest_distance`](Self::closest_distance).
+    ///
+    /// Miljö wins a tie; `None` if neither dataset matched.
+    pub fn closest_match_source(&self) -> Option<MatchSource> {
+        closest_match(self.miljo_distance, self.parkering_distance).map(|(source, _)| source)
+    }
+}
+/// Number of reliable and unreliable matches per dataset.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ReliabilityCounts {
+    pub miljo_reliable: usize,
+    pub miljo_unreliable: usize,
+    pub parkering_reliable: usize,
+    pub parkering_unreliable: usize,
+}
+impl ReliabilityCounts {
+    /// Count reliable and unreliable matches in merged results.
+    pub fn from_results(results: &[OutputDataWithDistance]) -> Self {
+        let mut counts = Self::default();
+        for result in results {
+            if result.miljo_distance.is_some() {
+                if result.miljo_reliable() {
+                    counts.miljo_reliable += 1;
+                } else {
+                    counts.miljo_unreliable += 1;
+                }
+            }
+            if result.parkering_distance.is_some() {
+                if result.parkering_reliable() {
+                    counts.parkering_reliable += 1;
+                } else {
+                    counts.parkering_unreliable += 1;
+                }
+            }
         }
+        counts
     }
 }
 impl CorrelationResult {
+    /// Create a result, flagging each match with [`is_reliable_match`].
+    ///
+    /// The parkering match's text is its `taxa`, used by
+    /// [`with_taxa_rates`](Self::with_taxa_rates) to look up the rate.
+    pub fn new(
+        address: String,
+        postnummer: String,
+        miljo_match: Option<(f64, String)>,
+        parkering_match: Option<(f64, String)>,
+    ) -> Self {
+        let miljo_distance = miljo_match.as_ref().map(|(dist, _)| *dist);
+        let parkering_distance = parkering_match.as_ref().map(|(dist, _)| *dist);
+        Self {
+            address,
+            postnummer,
+            miljo_match,
+            parkering_match,
+            miljo_reliable: miljo_distance
+                .is_some_and(|dist| is_reliable_match(dist, parkering_distance)),
+            parkering_reliable: parkering_distance
+                .is_some_and(|dist| is_reliable_match(dist, miljo_distance)),
+            parkering_rate_sek_per_hour: None,
+            confidence: 0.0,
+        }
+        .with_cutoff(MAX_DISTANCE_METERS)
+    }
+    /// Recompute [`confidence`](Self::confidence) for the cutoff the
+    /// correlation actually used.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use amp_core::structs::CorrelationResult;
+    ///
+    /// let result = CorrelationResult::new(
+    ///     "Storgatan 1".to_string(),
+    ///     "21122".to_string(),
+    ///     Some((5.0, "Städning".to_string())),
+    ///     Some((12.0, "Taxa C".to_string())),
+    /// )
+    /// .with_cutoff(20.0);
+    /// assert!((result.confidence - 0.75).abs() < 1e-9);
+    /// ```
+    pub fn with_cutoff(mut self, cutoff: f64) -> Self {
+        self.confidence = self
+            .closest_distance()
+            .map_or(0.0, |dist| match_confidence(dist, cutoff));
+        self
+    }
+    /// Look up [`parkering_rate_sek_per_hour`](Self::parkering_rate_sek_per_hour)
+    /// in a tariff table such as
+    /// [`malmo_taxa_table`](crate::parking::malmo_taxa_table).
+    pub fn with_taxa_rates(mut self, table: &HashMap<String, Decimal>) -> Self {
+        self.parkering_rate_sek_per_hour = self
+            .parkering_match
+            .as_ref()
+            .and_then(|(_, taxa)| taxa_rate_sek_per_hour(taxa, table));
+        self
+    }
     /// Get human-readable description of which datasets matched.
     ///
     /// Similar to [`OutputData::dataset_source`] but with different formatting.
@@ -835,6 +1953,27 @@ impl CorrelationResult {
             (false, false) => "No match",
         }
     }
+    /// Get the closest distance among both matches.
+    ///
+    /// # Returns
+    ///
+    /// Distance of the closer of `miljo_match` and `parkering_match`, or
+    /// `None` if neither matched.
+    pub fn closest_distance(&self) -> Option<f64> {
+        self.closest().map(|(_, dist)| dist)
+    }
+    /// Dataset that produced [`closest_distance`](Self::closest_distance).
+    ///
+    /// Miljö wins a tie; `None` if neither dataset matched.
+    pub fn closest_match_source(&self) -> Option<MatchSource> {
+        self.closest().map(|(source, _)| source)
+    }
+    fn closest(&self) -> Option<(MatchSource, f64)> {
+        closest_match(
+            self.miljo_match.as_ref().map(|(dist, _)| *dist),
+            self.parkering_match.as_ref().map(|(dist, _)| *dist),
+        )
+    }
 }
 /// Persisted notification state entry for tracking which TimeBucket
 /// each address was last seen in. Used to prevent duplicate notifications
@@ -848,10 +1987,23 @@ pub struct NotificationStateEntry {
     pub bucket: String,
     pub year_month: u32,
 }
+/// Last time a notification fired for an address in a given TimeBucket.
+///
+/// Used to enforce the per-bucket cooldowns in [`SettingsData`], so an
+/// address that re-enters a bucket is not notified again too soon.
+/// `fired_at` is a Unix timestamp in seconds.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NotificationFiredEntry {
+    pub address_id: u64,
+    pub bucket: String,
+    pub fired_at: i64,
+}
 /// User preferences for notifications, theme, and language.
 ///
 /// This data is persisted in Parquet format and synced with the Android app.
 /// Changes to these settings trigger re-generation of the settings Parquet file.
+/// For inspection and hand-editing, [`settings_to_json`] and
+/// [`settings_from_json`] provide a plain-text form of the same data.
 ///
 /// # Examples
 ///
@@ -865,9 +2017,12 @@ pub struct NotificationStateEntry {
 ///     theme: "Dark".to_string(),
 ///     language: "English".to_string(),
 ///     autocomplete_source: "Both".to_string(),
+///     group_by_street: false,
+///     ..SettingsData::default()
 /// };
 /// ```
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct SettingsData {
     /// Notify when street cleaning is currently happening
     pub stadning_nu: bool,
@@ -881,6 +2036,25 @@ pub struct SettingsData {
     pub language: String,
     /// Autocomplete data source: "Both", "MiljoOnly", "ParkeringOnly", or "AllAddresses"
     pub autocomplete_source: String,
+    /// Show saved addresses grouped by street instead of by time bucket
+    pub group_by_street: bool,
+    /// Minimum minutes between two "active now" notifications for one address
+    pub cooldown_now_minutes: u32,
+    /// Minimum minutes between two 6-hour notifications for one address
+    pub cooldown_six_hours_minutes: u32,
+    /// Minimum minutes between two 1-day notifications for one address
+    pub cooldown_one_day_minutes: u32,
+    /// Addresses entering the same bucket within this many minutes share one
+    /// summary notification; 0 sends one notification per address
+    pub notification_batch_minutes: u32,
+    /// Start of the daily quiet hours, in minutes after Swedish midnight
+    pub quiet_hours_start_minutes: u32,
+    /// End of the daily quiet hours, in minutes after Swedish midnight; equal
+    /// to the start when quiet hours are off
+    pub quiet_hours_end_minutes: u32,
+    /// How many minutes before a cleaning the early warning bucket starts,
+    /// see [`BucketSettings::from_settings`](crate::buckets::BucketSettings::from_settings)
+    pub lead_minutes: u32,
 }
 impl Default for SettingsData {
     /// Create default settings with Swedish language and light theme.
@@ -891,6 +2065,12 @@ impl Default for SettingsData {
     /// - `en_dag`: `true` (notify 1 day before)
     /// - `theme`: "Light"
     /// - `language`: "Svenska"
+    /// - `group_by_street`: `false` (flat, time-bucketed view)
+    /// - Cooldowns: 4 hours for "active now", 24 hours for the 6-hour and
+    ///   1-day notifications
+    /// - `notification_batch_minutes`: 0 (one notification per address)
+    /// - Quiet hours: off (start and end both 0)
+    /// - `lead_minutes`: 360 (the six hours of `sex_timmar`)
     fn default() -> Self {
         Self {
             stadning_nu: true,
@@ -899,8 +2079,119 @@ impl Default for SettingsData {
             theme: "Light".to_string(),
             language: "Svenska".to_string(),
             autocomplete_source: "Both".to_string(),
+            group_by_street: false,
+            cooldown_now_minutes: 4 * 60,
+            cooldown_six_hours_minutes: 24 * 60,
+            cooldown_one_day_minutes: 24 * 60,
+            notification_batch_minutes: 0,
+            quiet_hours_start_minutes: 0,
+            quiet_hours_end_minutes: 0,
+            lead_minutes: 6 * 60,
+        }
+    }
+}
+impl SettingsData {
+    /// The configured quiet hours, or `None` when they are off.
+    pub fn quiet_hours(&self) -> Option<QuietHours> {
+        QuietHours::from_minutes(self.quiet_hours_start_minutes, self.quiet_hours_end_minutes)
+    }
+}
+/// Daily window in Swedish local time during which notifications are held back.
+///
+/// The start is inclusive and the end exclusive. A window whose end is before
+/// its start crosses midnight, e.g. 22:00–07:00.
+///
+/// # Examples
+///
+/// ```
+/// use amp_core::structs::QuietHours;
+/// use chrono::{TimeZone, Utc};
+///
+/// let quiet = QuietHours::from_minutes(22 * 60, 7 * 60).unwrap();
+/// // 03:00 in Stockholm (CET) is held back until 07:00
+/// let night = Utc.with_ymd_and_hms(2024, 1, 15, 2, 0, 0).unwrap();
+/// assert!(quiet.contains(night));
+/// assert_eq!(quiet.defer(night), Utc.with_ymd_and_hms(2024, 1, 15, 6, 0, 0).unwrap());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QuietHours {
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+}
+impl QuietHours {
+    /// Build a window from minutes after midnight.
+    ///
+    /// Returns `None` when start and end are equal (quiet hours off) or either
+    /// is not within a day.
+    pub fn from_minutes(start: u32, end: u32) -> Option<Self> {
+        if start == end {
+            return None;
         }
+        let time = |minutes: u32| NaiveTime::from_num_seconds_from_midnight_opt(minutes * 60, 0);
+        Some(Self {
+            start: time(start)?,
+            end: time(end)?,
+        })
+    }
+    /// Start and end as minutes after midnight, as stored in [`SettingsData`].
+    pub fn to_minutes(&self) -> (u32, u32) {
+        let minutes = |time: NaiveTime| time.num_seconds_from_midnight() / 60;
+        (minutes(self.start), minutes(self.end))
     }
+    /// Whether `at` falls inside the window, in Swedish local time.
+    pub fn contains(&self, at: DateTime<Utc>) -> bool {
+        let local = at.with_timezone(&SWEDISH_TZ).time();
+        if self.start < self.end {
+            self.start <= local && local < self.end
+        } else {
+            local >= self.start || local < self.end
+        }
+    }
+    /// When a notification due at `at` may be shown.
+    ///
+    /// Returns `at` itself outside the window, otherwise the end of the
+    /// window it falls in. An end in the spring-forward gap is moved past it.
+    pub fn defer(&self, at: DateTime<Utc>) -> DateTime<Utc> {
+        if !self.contains(at) {
+            return at;
+        }
+        let local = at.with_timezone(&SWEDISH_TZ);
+        let date = if local.time() >= self.end {
+            local.date_naive() + chrono::Duration::days(1)
+        } else {
+            local.date_naive()
+        };
+        resolve_swedish_local(date.and_time(self.end)).unwrap_or(at)
+    }
+}
+/// Serialize [`SettingsData`] to pretty-printed JSON.
+///
+/// # Errors
+///
+/// Returns an error if serialization fails.
+///
+/// # Examples
+///
+/// ```
+/// use amp_core::structs::{SettingsData, settings_to_json};
+///
+/// let json = settings_to_json(&SettingsData::default())?;
+/// assert!(json.contains("\"language\": \"Svenska\""));
+/// # Ok::<(), serde_json::Error>(())
+/// ```
+pub fn settings_to_json(settings: &SettingsData) -> serde_json::Result<String> {
+    serde_json::to_string_pretty(settings)
+}
+/// Parse [`SettingsData`] from JSON.
+///
+/// Missing fields fall back to [`SettingsData::default`], so a file only
+/// containing `{"theme": "Dark"}` is accepted.
+///
+/// # Errors
+///
+/// Returns an error if `json` is not valid JSON or a field has the wrong type.
+pub fn settings_from_json(json: &str) -> serde_json::Result<SettingsData> {
+    serde_json::from_str(json)
 }
 #[cfg(test)]
 mod tests {
@@ -944,6 +2235,195 @@ mod tests {
         assert!(db.is_some());
         let db = db.unwrap();
         assert_eq!(db.adress, "Åhusgatan1");
+        assert_eq!(db.typ_av_parkering.as_deref(), Some("Längsgående 6"));
+        assert_eq!(db.orientation.as_deref(), Some("längsgående"));
+        assert_eq!(db.space_length_m, Some(6.0));
+        assert_eq!(db.antal_platser, Some(26));
+    }
+    #[test]
+    fn test_db_without_placement() {
+        let db = DB::from_dag_tid(
+            None,
+            "Åhusgatan1".to_string(),
+            None,
+            None,
+            None,
+            17,
+            "1200-1600",
+            None,
+            None,
+            Some("Ytparkering inom zon".to_string()),
+            2024,
+            1,
+        )
+        .unwrap();
+        assert_eq!(db.orientation, None);
+        assert_eq!(db.space_length_m, None);
+    }
+    #[test]
+    fn test_next_start_after_same_and_next_month() {
+        let monthly = |dag| {
+            DB::from_dag_tid(
+                None,
+                "Test".to_string(),
+                None,
+                None,
+                None,
+                dag,
+                "0800-1200",
+                None,
+                None,
+                None,
+                2024,
+                1,
+            )
+            .unwrap()
+        };
+        let now = Utc.with_ymd_and_hms(2024, 1, 10, 12, 0, 0).unwrap();
+        assert_eq!(
+            monthly(15).next_start_after(now),
+            Some(Utc.with_ymd_and_hms(2024, 1, 15, 7, 0, 0).unwrap()),
+        );
+        let after = Utc.with_ymd_and_hms(2024, 1, 15, 7, 0, 0).unwrap();
+        assert_eq!(
+            monthly(15).next_start_after(after),
+            Some(Utc.with_ymd_and_hms(2024, 2, 15, 7, 0, 0).unwrap()),
+        );
+        let april = Utc.with_ymd_and_hms(2024, 4, 1, 0, 0, 0).unwrap();
+        assert_eq!(
+            monthly(31).next_start_after(april),
+            Some(Utc.with_ymd_and_hms(2024, 5, 31, 6, 0, 0).unwrap()),
+        );
+    }
+    #[test]
+    fn test_parse_tid_intervals() {
+        let hm = |h, m| NaiveTime::from_hms_opt(h, m, 0).unwrap();
+        assert_eq!(
+            parse_tid_intervals("0800-1200"),
+            vec![(hm(8, 0), hm(12, 0))]
+        );
+        assert_eq!(parse_tid_intervals("heldygn"), vec![(hm(0, 0), hm(23, 59))]);
+        assert_eq!(
+            parse_tid_intervals("0800-1000,1400-1600"),
+            vec![(hm(8, 0), hm(10, 0)), (hm(14, 0), hm(16, 0))],
+        );
+        for malformed in [
+            "0800-1000,",
+            "0800-1000,heldygn",
+            "0800-1000,14-16",
+            "morgon",
+        ] {
+            assert!(parse_tid_intervals(malformed).is_empty(), "{malformed}");
+        }
+    }
+    #[test]
+    fn test_db_with_two_windows() {
+        let db = DB::from_dag_tid(
+            None,
+            "Test".to_string(),
+            None,
+            None,
+            None,
+            15,
+            "1400-1600,0800-1000",
+            None,
+            None,
+            None,
+            2024,
+            1,
+        )
+        .unwrap();
+        assert_eq!(
+            db.start_time,
+            Utc.with_ymd_and_hms(2024, 1, 15, 7, 0, 0).unwrap()
+        );
+        assert_eq!(db.tid(), "0800-1000,1400-1600");
+        let at = |h| Utc.with_ymd_and_hms(2024, 1, 15, h, 0, 0).unwrap();
+        assert!(db.is_active(at(14)));
+        assert!(!db.is_active(at(11)));
+        assert_eq!(
+            db.time_until_start(at(11)),
+            Some(chrono::Duration::hours(2))
+        );
+        assert_eq!(db.time_until_end(at(11)), Some(chrono::Duration::hours(4)));
+        assert_eq!(db.time_until_end(at(15)), None);
+        assert_eq!(db.next_start_after(at(11)), Some(at(13)));
+    }
+    #[test]
+    fn test_snooze_to_next_occurrence_dst_gap() {
+        // 2025-03-30 02:30 does not exist in Stockholm; read it as 02:30 CET
+        let now = SWEDISH_TZ.with_ymd_and_hms(2025, 3, 29, 12, 0, 0).unwrap();
+        let mut spring = snoozable(Some("0230-0600"), Some(30), vec![30]);
+        assert_eq!(
+            spring.snooze_to_next_occurrence(now),
+            Some(Utc.with_ymd_and_hms(2025, 3, 30, 1, 30, 0).unwrap()),
+        );
+        // 2024-10-27 02:30 happens twice; the first (CEST) occurrence wins
+        let now = SWEDISH_TZ.with_ymd_and_hms(2024, 10, 26, 12, 0, 0).unwrap();
+        let mut autumn = snoozable(Some("0230-0600"), Some(27), vec![27]);
+        assert_eq!(
+            autumn.snooze_to_next_occurrence(now),
+            Some(Utc.with_ymd_and_hms(2024, 10, 27, 0, 30, 0).unwrap()),
+        );
+    }
+    #[test]
+    fn test_parse_tid_interval_all_day_markers() {
+        let options = TidOptions::default();
+        let all_day = (
+            NaiveTime::from_hms_opt(0, 0, 0).unwrap(),
+            NaiveTime::from_hms_opt(23, 59, 0).unwrap(),
+        );
+        for marker in [
+            "",
+            "  ",
+            "00-24",
+            "0000-2400",
+            "heldygn",
+            "Heldygn",
+            "dygnet runt",
+        ] {
+            assert_eq!(
+                parse_tid_interval(marker, &options),
+                Some(all_day),
+                "{:?} should parse as all-day",
+                marker,
+            );
+        }
+        let strict = TidOptions {
+            all_day_markers: false,
+        };
+        for marker in ALL_DAY_TID_MARKERS {
+            assert_eq!(parse_tid_interval(marker, &strict), None);
+        }
+        assert_eq!(
+            parse_tid_interval("0800-1200", &strict),
+            Some((
+                NaiveTime::from_hms_opt(8, 0, 0).unwrap(),
+                NaiveTime::from_hms_opt(12, 0, 0).unwrap(),
+            )),
+        );
+        assert_eq!(parse_tid_interval("morgon", &options), None);
+    }
+    #[test]
+    fn test_db_from_dag_tid_all_day() {
+        let db = DB::from_dag_tid(
+            None,
+            "Test".to_string(),
+            None,
+            None,
+            None,
+            15,
+            "heldygn",
+            None,
+            None,
+            None,
+            2024,
+            1,
+        )
+        .unwrap();
+        assert_eq!(db.start_time_swedish().hour(), 0);
+        assert_eq!(db.end_time_swedish().hour(), 23);
+        assert_eq!(db.end_time_swedish().minute(), 59);
     }
     #[test]
     fn test_db_is_active() {
@@ -978,6 +2458,253 @@ mod tests {
             Utc,
         );
         assert!(!db.is_active(before));
+        let next_month = Utc.with_ymd_and_hms(2024, 2, 15, 10, 0, 0).unwrap();
+        assert!(db.is_active(next_month));
+        assert!(!db.is_active(Utc.with_ymd_and_hms(2024, 2, 16, 10, 0, 0).unwrap()));
+    }
+    #[test]
+    fn test_is_active_at_skips_holidays() {
+        let db = DB::from_dag_tid(
+            None,
+            "Test".to_string(),
+            None,
+            None,
+            None,
+            9,
+            "0800-1200",
+            None,
+            None,
+            None,
+            2024,
+            4,
+        )
+        .unwrap();
+        // Tuesday 9 April is a normal weekday
+        assert!(db.is_active_at(SWEDISH_TZ.with_ymd_and_hms(2024, 4, 9, 9, 0, 0).unwrap()));
+        // Thursday 9 May is Kristi himmelsfärds dag
+        assert!(!db.is_active_at(SWEDISH_TZ.with_ymd_and_hms(2024, 5, 9, 9, 0, 0).unwrap()));
+    }
+    fn ninth_of(month: u32) -> DB {
+        DB::from_dag_tid(
+            None,
+            "Test".to_string(),
+            None,
+            None,
+            None,
+            9,
+            "0800-1200",
+            None,
+            None,
+            None,
+            2024,
+            month,
+        )
+        .unwrap()
+    }
+    #[test]
+    fn test_time_until_start_and_end_skip_holiday_occurrence() {
+        let before = Utc.with_ymd_and_hms(2024, 5, 9, 5, 0, 0).unwrap();
+        let db = ninth_of(5);
+        assert_eq!(db.time_until_start(before), None);
+        assert_eq!(db.time_until_end(before), None);
+        let db = db.with_holiday_skipping(false);
+        assert_eq!(
+            db.time_until_start(before),
+            Some(chrono::Duration::hours(1))
+        );
+        assert_eq!(db.time_until_end(before), Some(chrono::Duration::hours(5)));
+    }
+    #[test]
+    fn test_next_start_after_skips_holiday() {
+        let db = ninth_of(4);
+        let after_april = Utc.with_ymd_and_hms(2024, 4, 10, 0, 0, 0).unwrap();
+        // 9 May is a holiday, 9 June a Sunday
+        assert_eq!(
+            db.next_start_after(after_april),
+            Some(Utc.with_ymd_and_hms(2024, 6, 9, 6, 0, 0).unwrap())
+        );
+    }
+    #[test]
+    fn test_next_occurrence_skips_holiday() {
+        let db = ninth_of(4);
+        let from = SWEDISH_TZ.with_ymd_and_hms(2024, 4, 10, 0, 0, 0).unwrap();
+        let (start, end) = db.next_occurrence(from).unwrap();
+        assert_eq!(
+            start,
+            SWEDISH_TZ.with_ymd_and_hms(2024, 6, 9, 8, 0, 0).unwrap()
+        );
+        assert_eq!(
+            end,
+            SWEDISH_TZ.with_ymd_and_hms(2024, 6, 9, 12, 0, 0).unwrap()
+        );
+    }
+    #[test]
+    fn test_next_occurrence_skips_month_without_day() {
+        let db = DB::from_dag_tid(
+            None,
+            "Test".to_string(),
+            None,
+            None,
+            None,
+            31,
+            "0800-1200",
+            None,
+            None,
+            None,
+            2024,
+            1,
+        )
+        .unwrap()
+        // March 31 is Easter Sunday in 2024
+        .with_holiday_skipping(false);
+        let from = SWEDISH_TZ.with_ymd_and_hms(2024, 1, 31, 13, 0, 0).unwrap();
+        let (start, end) = db.next_occurrence(from).unwrap();
+        assert_eq!(
+            start,
+            SWEDISH_TZ.with_ymd_and_hms(2024, 3, 31, 8, 0, 0).unwrap()
+        );
+        assert_eq!(
+            end,
+            SWEDISH_TZ.with_ymd_and_hms(2024, 3, 31, 12, 0, 0).unwrap()
+        );
+    }
+    #[test]
+    fn test_next_occurrence_same_day() {
+        let db = DB::from_dag_tid(
+            None,
+            "Test".to_string(),
+            None,
+            None,
+            None,
+            15,
+            "2200-0600",
+            None,
+            None,
+            None,
+            2024,
+            1,
+        )
+        .unwrap();
+        let from = SWEDISH_TZ.with_ymd_and_hms(2024, 5, 15, 9, 0, 0).unwrap();
+        let (start, end) = db.next_occurrence(from).unwrap();
+        assert_eq!(
+            start,
+            SWEDISH_TZ.with_ymd_and_hms(2024, 5, 15, 22, 0, 0).unwrap()
+        );
+        assert_eq!(
+            end,
+            SWEDISH_TZ.with_ymd_and_hms(2024, 5, 16, 6, 0, 0).unwrap()
+        );
+    }
+    fn snoozable(tid: Option<&str>, dag: Option<u8>, dagar: Vec<u8>) -> LocalData {
+        LocalData {
+            valid: true,
+            active: true,
+            postnummer: None,
+            adress: "Storgatan 10".to_string(),
+            gata: None,
+            gatunummer: None,
+            info: None,
+            tid: tid.map(str::to_string),
+            dag,
+            dagar,
+            taxa: None,
+            antal_platser: None,
+            typ_av_parkering: None,
+            match_reason: None,
+            match_quality: None,
+            snoozed_until: None,
+        }
+    }
+    #[test]
+    fn test_snooze_to_next_occurrence() {
+        let during = SWEDISH_TZ.with_ymd_and_hms(2024, 1, 15, 9, 0, 0).unwrap();
+        let mut monthly = snoozable(Some("0800-1200"), Some(15), vec![15]);
+        let until = monthly.snooze_to_next_occurrence(during).unwrap();
+        assert_eq!(until, Utc.with_ymd_and_hms(2024, 2, 15, 7, 0, 0).unwrap());
+        assert_eq!(monthly.snoozed_until, Some(until.timestamp()));
+        assert!(monthly.is_snoozed(until - chrono::Duration::seconds(1)));
+        assert!(!monthly.is_snoozed(until));
+        let mut twice = snoozable(Some("0800-1200"), Some(1), vec![1, 20]);
+        assert_eq!(
+            twice.snooze_to_next_occurrence(during),
+            Some(Utc.with_ymd_and_hms(2024, 1, 20, 7, 0, 0).unwrap())
+        );
+        let mut legacy = snoozable(Some("0800-1200"), Some(15), vec![]);
+        assert_eq!(legacy.snooze_to_next_occurrence(during), Some(until));
+        // January 1 is a holiday, so the 1st-and-15th rule next starts on the 15th
+        let new_year = SWEDISH_TZ.with_ymd_and_hms(2024, 12, 20, 9, 0, 0).unwrap();
+        let mut holiday = snoozable(Some("0800-1200"), Some(1), vec![1, 15]);
+        assert_eq!(
+            holiday.snooze_to_next_occurrence(new_year),
+            Some(Utc.with_ymd_and_hms(2025, 1, 15, 7, 0, 0).unwrap())
+        );
+        let mut garbage = snoozable(Some("garbage"), Some(15), vec![15]);
+        assert_eq!(garbage.snooze_to_next_occurrence(during), None);
+        let mut out_of_range = snoozable(Some("0800-1200"), Some(0), vec![]);
+        assert_eq!(out_of_range.snooze_to_next_occurrence(during), None);
+    }
+    #[test]
+    fn test_dedup_in_place_keeps_active_and_merges_match() {
+        let mut inactive = snoozable(None, None, vec![]);
+        inactive.active = false;
+        inactive.postnummer = Some("214 38".to_string());
+        let mut active = snoozable(None, None, vec![]);
+        active.adress = "storgatan  10".to_string();
+        active.postnummer = Some("21438".to_string());
+        let mut matched = snoozable(Some("0800-1200"), Some(15), vec![15]);
+        matched.valid = true;
+        matched.active = false;
+        matched.adress = " STORGATAN 10 ".to_string();
+        matched.postnummer = Some("21438".to_string());
+        matched.taxa = Some("Taxa C".to_string());
+        let mut other = snoozable(None, None, vec![]);
+        other.adress = "Storgatan 12".to_string();
+        other.postnummer = Some("21438".to_string());
+        let mut list = vec![inactive, other, active, matched];
+        assert_eq!(LocalData::dedup_in_place(&mut list), 2);
+        assert_eq!(list.len(), 2);
+        let merged = &list[0];
+        assert_eq!(merged.adress, "storgatan  10");
+        assert!(merged.active);
+        assert!(merged.valid);
+        assert_eq!(merged.tid.as_deref(), Some("0800-1200"));
+        assert_eq!(merged.dagar, vec![15]);
+        assert_eq!(merged.taxa.as_deref(), Some("Taxa C"));
+        assert_eq!(list[1].adress, "Storgatan 12");
+        assert_eq!(LocalData::dedup_in_place(&mut list), 0);
+    }
+    #[test]
+    fn test_dedup_in_place_takes_match_from_one_duplicate() {
+        let mut unmatched = snoozable(None, None, vec![]);
+        unmatched.valid = false;
+        let mut cleaning = snoozable(Some("0800-1200"), Some(15), vec![15]);
+        cleaning.valid = true;
+        cleaning.match_reason = Some("exact".to_string());
+        let mut parking = snoozable(Some("1000-1400"), Some(3), vec![3]);
+        parking.valid = false;
+        parking.taxa = Some("Taxa C".to_string());
+        parking.antal_platser = Some(12);
+        parking.match_reason = Some("substring dist=2".to_string());
+        let mut list = vec![unmatched, cleaning, parking];
+        assert_eq!(LocalData::dedup_in_place(&mut list), 2);
+        let merged = &list[0];
+        assert!(merged.valid);
+        assert_eq!(merged.tid.as_deref(), Some("0800-1200"));
+        assert_eq!(merged.dag, Some(15));
+        assert_eq!(merged.dagar, vec![15]);
+        assert_eq!(merged.match_reason.as_deref(), Some("exact"));
+        // Nothing from the second, conflicting match leaks in
+        assert_eq!(merged.taxa, None);
+        assert_eq!(merged.antal_platser, None);
+    }
+    #[test]
+    fn test_snooze_without_schedule_is_noop() {
+        let now = SWEDISH_TZ.with_ymd_and_hms(2024, 1, 15, 9, 0, 0).unwrap();
+        let mut unmatched = snoozable(None, None, vec![]);
+        assert_eq!(unmatched.snooze_to_next_occurrence(now), None);
+        assert_eq!(unmatched.snoozed_until, None);
+        assert!(!unmatched.is_snoozed(now.with_timezone(&Utc)));
     }
     #[test]
     fn test_year_validation() {
@@ -1053,4 +2780,185 @@ mod tests {
         assert_eq!(street, "Kornettsgatan");
         assert_eq!(number, "18C");
     }
+    #[test]
+    fn test_stored_address_match_reason() {
+        let fuzzy = |db: &str, user: &str| {
+            let (street, number) = StoredAddress::parse_address(user);
+            StoredAddress::fuzzy_match(db, user, &street, &number)
+        };
+        assert_eq!(
+            fuzzy("Kornettsgatan 18C", "kornettsgatan 18c"),
+            Some("exact")
+        );
+        assert_eq!(
+            fuzzy("Kornettsgatan 18C U1", "Kornettsgatan 18C"),
+            Some("substring")
+        );
+        assert_eq!(
+            fuzzy("Kornettsgatan 18C", "Kornettsgatan"),
+            Some("street-only")
+        );
+        assert_eq!(fuzzy("Kornettsgatan 18C", "Kornettsgatan 20"), None);
+        assert_eq!(fuzzy("Storgatan 18C", "Kornettsgatan 18C"), None);
+    }
+    #[test]
+    fn test_settings_json_roundtrip() {
+        let settings = SettingsData {
+            stadning_nu: false,
+            sex_timmar: true,
+            en_dag: false,
+            theme: "Dark".to_string(),
+            language: "Francais".to_string(),
+            autocomplete_source: "ParkeringOnly".to_string(),
+            group_by_street: true,
+            cooldown_now_minutes: 0,
+            cooldown_six_hours_minutes: 90,
+            cooldown_one_day_minutes: 24 * 60,
+            notification_batch_minutes: 30,
+            quiet_hours_start_minutes: 22 * 60,
+            quiet_hours_end_minutes: 7 * 60,
+            lead_minutes: 120,
+        };
+        let json = settings_to_json(&settings).unwrap();
+        assert_eq!(settings_from_json(&json).unwrap(), settings);
+    }
+    #[test]
+    fn test_settings_from_partial_json() {
+        let settings = settings_from_json(r#"{"theme": "Dark", "language": "English"}"#).unwrap();
+        assert_eq!(settings.theme, "Dark");
+        assert_eq!(settings.language, "English");
+        assert_eq!(settings.autocomplete_source, "Both");
+        assert!(settings.stadning_nu);
+        assert_eq!(settings.cooldown_six_hours_minutes, 24 * 60);
+        assert_eq!(settings.lead_minutes, 6 * 60);
+        assert!(settings_from_json(r#"{"en_dag": "yes"}"#).is_err());
+    }
+    #[test]
+    fn test_quiet_hours_across_midnight() {
+        let quiet = QuietHours::from_minutes(22 * 60, 7 * 60).unwrap();
+        // 2024-06-10 is CEST (UTC+2)
+        let inside_late = Utc.with_ymd_and_hms(2024, 6, 10, 21, 30, 0).unwrap();
+        let inside_early = Utc.with_ymd_and_hms(2024, 6, 11, 3, 0, 0).unwrap();
+        let outside = Utc.with_ymd_and_hms(2024, 6, 11, 8, 0, 0).unwrap();
+        let window_end = Utc.with_ymd_and_hms(2024, 6, 11, 5, 0, 0).unwrap();
+        assert!(quiet.contains(inside_late));
+        assert!(quiet.contains(inside_early));
+        assert!(!quiet.contains(outside));
+        assert!(!quiet.contains(window_end));
+        assert_eq!(quiet.defer(inside_late), window_end);
+        assert_eq!(quiet.defer(inside_early), window_end);
+        assert_eq!(quiet.defer(outside), outside);
+        assert_eq!(quiet.to_minutes(), (22 * 60, 7 * 60));
+    }
+    #[test]
+    fn test_quiet_hours_within_day_and_off() {
+        let quiet = QuietHours::from_minutes(13 * 60, 14 * 60 + 30).unwrap();
+        // 2024-01-15 is CET (UTC+1)
+        let lunch = Utc.with_ymd_and_hms(2024, 1, 15, 12, 15, 0).unwrap();
+        let evening = Utc.with_ymd_and_hms(2024, 1, 15, 22, 0, 0).unwrap();
+        assert!(quiet.contains(lunch));
+        assert!(!quiet.contains(evening));
+        assert_eq!(
+            quiet.defer(lunch),
+            Utc.with_ymd_and_hms(2024, 1, 15, 13, 30, 0).unwrap()
+        );
+        assert_eq!(QuietHours::from_minutes(0, 0), None);
+        assert_eq!(QuietHours::from_minutes(0, 24 * 60), None);
+        assert_eq!(SettingsData::default().quiet_hours(), None);
+    }
+    #[test]
+    fn test_db_display_and_summary() {
+        let full = DB::from_dag_tid(
+            Some("21438".to_string()),
+            "Storgatan 10".to_string(),
+            Some("Storgatan".to_string()),
+            Some("10".to_string()),
+            Some("Städning".to_string()),
+            15,
+            "0800-1200",
+            Some("Taxa C".to_string()),
+            Some(26),
+            Some("Längsgående 6".to_string()),
+            2024,
+            1,
+        )
+        .unwrap();
+        assert_eq!(
+            full.to_string(),
+            "Storgatan 10 · dag 15 · 08:00–12:00 · Taxa C"
+        );
+        assert_eq!(
+            full.summary("English"),
+            "Storgatan 10 · day 15 · 08:00–12:00 · Taxa C · 26 spaces"
+        );
+        assert_eq!(
+            full.summary("Francais"),
+            "Storgatan 10 · dag 15 · 08:00–12:00 · Taxa C · 26 platser"
+        );
+        let bare = DB::from_dag_tid(
+            None,
+            "Storgatan 10".to_string(),
+            None,
+            None,
+            None,
+            3,
+            "0000-2400",
+            None,
+            None,
+            None,
+            2024,
+            7,
+        )
+        .unwrap();
+        assert_eq!(bare.to_string(), "Storgatan 10 · dag 3 · 00:00–23:59");
+        assert_eq!(
+            bare.summary("English"),
+            "Storgatan 10 · day 3 · 00:00–23:59"
+        );
+    }
+    #[test]
+    fn test_match_quality_by_distance() {
+        let quality = |dist| MatchQuality::from_distance(Some(dist), 5.0, 50.0);
+        assert_eq!(quality(3.0), MatchQuality::Exact);
+        assert_eq!(quality(20.0), MatchQuality::Approximate);
+        assert_eq!(quality(60.0), MatchQuality::None);
+        assert_eq!(quality(5.0), MatchQuality::Exact);
+        assert_eq!(quality(50.0), MatchQuality::Approximate);
+        let with_distances = |miljo, parkering| OutputDataWithDistance {
+            data: OutputData {
+                postnummer: None,
+                adress: "Storgatan 10".to_string(),
+                gata: "Storgatan".to_string(),
+                gatunummer: "10".to_string(),
+                info: None,
+                tid: None,
+                dag: None,
+                dagar: Vec::new(),
+                taxa: None,
+                antal_platser: None,
+                typ_av_parkering: None,
+                match_quality: None,
+                segment_id: None,
+            },
+            miljo_distance: miljo,
+            parkering_distance: parkering,
+            has_location: true,
+        };
+        assert_eq!(
+            with_distances(Some(20.0), Some(3.0)).match_quality(5.0, 50.0),
+            MatchQuality::Approximate
+        );
+        assert_eq!(
+            with_distances(None, Some(3.0)).match_quality(5.0, 50.0),
+            MatchQuality::Exact
+        );
+        assert_eq!(
+            with_distances(None, None).match_quality(5.0, 50.0),
+            MatchQuality::None
+        );
+        for quality in MatchQuality::ALL {
+            assert_eq!(MatchQuality::from_code(quality.code()), Some(quality));
+        }
+        assert_eq!(MatchQuality::from_code("close"), None);
+    }
 }
@@ -2,6 +2,17 @@
 //!
 //! Platform-specific notification implementation for iOS using UserNotifications framework.
 //! This is a stub that needs proper implementation with objc bindings.
+//!
+//! Quiet hours are applied before scheduling: a notification that would fire
+//! inside the window is moved to its end.
+//!
+//! Besides bucket notifications, [`schedule_start_reminder`] schedules a
+//! precise reminder a few minutes before a restriction starts, using the
+//! matched entry's next exact start in Swedish time.
+use amp_core::structs::{DB, QuietHours};
+use chrono::{DateTime, Duration, Utc};
+/// Default number of minutes before the exact start to send the precise reminder
+pub const PRECISE_REMINDER_LEAD_MINUTES: i64 = 5;
 /// Request notification permission from user
 ///
 /// # TODO
@@ -23,6 +34,9 @@ pub fn request_notification_permission() {
 /// * `title` - Notification title
 /// * `body` - Notification body text
 /// * `time_seconds` - Time from now in seconds
+/// * `quiet_hours` - Window in which nothing is shown; a notification due
+///   inside it is scheduled for the window's end. Callers decide beforehand
+///   whether it is still worth sending then.
 ///
 /// # TODO
 /// Implement using objc bindings:
@@ -30,12 +44,18 @@ pub fn request_notification_permission() {
 /// let content = UNMutableNotificationContent()
 /// content.title = title
 /// content.body = body
-/// let trigger = UNTimeIntervalNotificationTrigger(timeInterval: time_seconds)
+/// let trigger = UNTimeIntervalNotificationTrigger(timeInterval: delay_seconds)
 /// let request = UNNotificationRequest(identifier: UUID(), content: content, trigger: trigger)
 /// UNUserNotificationCenter.current().add(request)
 /// ```
 #[allow(unused_variables)]
-pub fn schedule_notification(title: &str, body: &str, time_seconds: u64) {
+pub fn schedule_notification(
+    title: &str,
+    body: &str,
+    time_seconds: u64,
+    quiet_hours: Option<&QuietHours>,
+) {
+    let delay_seconds = delay_past_quiet_hours(time_seconds, quiet_hours, Utc::now());
     #[cfg(target_os = "ios")]
     {
         unimplemented!("iOS notification scheduling not yet implemented")
@@ -43,6 +63,72 @@ pub fn schedule_notification(title: &str, body: &str, time_seconds: u64) {
     #[cfg(not(target_os = "ios"))]
     {}
 }
+/// Schedule the precise start reminder for a matched restriction
+///
+/// The reminder fires `lead` before the next start after now, as computed
+/// by [`start_reminder_at`]. Quiet hours apply as in
+/// [`schedule_notification`].
+///
+/// # Arguments
+/// * `entry` - Matched restriction of the address
+/// * `street` - Street name shown in the notification
+/// * `street_number` - Street number shown in the notification
+/// * `lead` - How long before the start the reminder should fire
+/// * `quiet_hours` - See [`schedule_notification`]
+///
+/// # Returns
+/// The start the reminder was scheduled for, or `None` if the restriction
+/// never starts again
+pub fn schedule_start_reminder(
+    entry: &DB,
+    street: &str,
+    street_number: &str,
+    lead: Duration,
+    quiet_hours: Option<&QuietHours>,
+) -> Option<DateTime<Utc>> {
+    let now = Utc::now();
+    let (reminder_at, start) = start_reminder_at(entry, lead, now)?;
+    let delay_seconds = (reminder_at - now).num_seconds().max(0) as u64;
+    let minutes = lead.num_minutes();
+    let title = if minutes <= 0 {
+        "Städning börjar nu".to_string()
+    } else {
+        format!("Städning om {} min", minutes)
+    };
+    let body = format!(
+        "Städningen på {} börjar snart. Flytta din bil från {} {}.",
+        street, street, street_number,
+    );
+    schedule_notification(&title, &body, delay_seconds, quiet_hours);
+    Some(start)
+}
+/// When the precise start reminder for `entry` should fire
+///
+/// Uses [`DB::next_start_after`], which tries every day in
+/// [`DB::dagar`] and skips holidays the restriction doesn't run on.
+///
+/// # Returns
+/// `Some((reminder_at, start))`, or `None` if there is no later start
+fn start_reminder_at(
+    entry: &DB,
+    lead: Duration,
+    now: DateTime<Utc>,
+) -> Option<(DateTime<Utc>, DateTime<Utc>)> {
+    let start = entry.next_start_after(now)?;
+    Some((start - lead, start))
+}
+/// Seconds from `now` until a notification due in `time_seconds` may fire
+fn delay_past_quiet_hours(
+    time_seconds: u64,
+    quiet_hours: Option<&QuietHours>,
+    now: DateTime<Utc>,
+) -> u64 {
+    let Some(quiet) = quiet_hours else {
+        return time_seconds;
+    };
+    let due = now + Duration::seconds(time_seconds as i64);
+    (quiet.defer(due) - now).num_seconds().max(0) as u64
+}
 /// Cancel all pending notifications
 ///
 /// # TODO
@@ -55,3 +141,45 @@ pub fn cancel_all_notifications() {
     #[cfg(not(target_os = "ios"))]
     {}
 }
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    #[test]
+    fn test_start_reminder_skips_holiday_and_uses_dagar() {
+        let entry = DB::from_dag_tid(
+            None,
+            "Storgatan 10".to_string(),
+            None,
+            None,
+            None,
+            1,
+            "0800-1200",
+            None,
+            None,
+            None,
+            2025,
+            1,
+        )
+        .unwrap()
+        .with_dagar([1, 15]);
+        // January 1 is a holiday, so the next start is on the 15th
+        let now = Utc.with_ymd_and_hms(2024, 12, 20, 12, 0, 0).unwrap();
+        let lead = Duration::minutes(PRECISE_REMINDER_LEAD_MINUTES);
+        let start = Utc.with_ymd_and_hms(2025, 1, 15, 7, 0, 0).unwrap();
+        assert_eq!(
+            start_reminder_at(&entry, lead, now),
+            Some((start - lead, start))
+        );
+    }
+    #[test]
+    fn test_delay_past_quiet_hours() {
+        // 22:00-07:00 Swedish time; 2024-01-15 20:00 UTC is 21:00 CET
+        let quiet = QuietHours::from_minutes(22 * 60, 7 * 60).unwrap();
+        let now = Utc.with_ymd_and_hms(2024, 1, 15, 20, 0, 0).unwrap();
+        assert_eq!(delay_past_quiet_hours(1800, Some(&quiet), now), 1800);
+        assert_eq!(delay_past_quiet_hours(7200, None, now), 7200);
+        // Due at 23:00 CET, held until 07:00 CET
+        assert_eq!(delay_past_quiet_hours(7200, Some(&quiet), now), 10 * 3600);
+    }
+}
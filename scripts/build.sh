@@ -137,6 +137,20 @@ verify_package_structure() {
         ISSUES=$((ISSUES + 1))
     fi
 
+    # Check SearchBridge
+    if [ -f "$KOTLIN_SRC/SearchBridge.kt" ]; then
+        local PACKAGE=$(grep "^package " "$KOTLIN_SRC/SearchBridge.kt" | awk '{print $2}' | tr -d ';')
+        if [ "$PACKAGE" = "se.malmo.skaggbyran.amp" ]; then
+            echo "  ✅ SearchBridge.kt: package=$PACKAGE"
+        else
+            echo "  ❌ SearchBridge.kt: WRONG PACKAGE ($PACKAGE != se.malmo.skaggbyran.amp)"
+            ISSUES=$((ISSUES + 1))
+        fi
+    else
+        echo "  ❌ SearchBridge.kt not found"
+        ISSUES=$((ISSUES + 1))
+    fi
+
     # Check LocationHelper
     if [ -f "$KOTLIN_SRC/LocationHelper.kt" ]; then
         local PACKAGE=$(grep "^package " "$KOTLIN_SRC/LocationHelper.kt" | awk '{print $2}' | tr -d ';')
@@ -307,6 +321,7 @@ setup_notifications() {
     DORMANT_SERVICE_SOURCE="$REPO_ROOT/android/kotlin/DormantService.kt"
     BOOT_RECEIVER_SOURCE="$REPO_ROOT/android/kotlin/BootReceiver.kt"
     DORMANT_BRIDGE_SOURCE="$REPO_ROOT/android/kotlin/DormantBridge.kt"
+    SEARCH_BRIDGE_SOURCE="$REPO_ROOT/android/kotlin/SearchBridge.kt"
     LOCATION_HELPER_SOURCE="$REPO_ROOT/android/kotlin/LocationHelper.kt"
     FILEPICKER_SOURCE="$REPO_ROOT/android/kotlin/FilePickerHelper.kt"
 
@@ -366,6 +381,16 @@ setup_notifications() {
         exit 1
     fi
 
+    # Copy SearchBridge.kt
+    if [ -f "$SEARCH_BRIDGE_SOURCE" ]; then
+        echo "  📄 Copying SearchBridge.kt to kotlin/ directory..."
+        cp "$SEARCH_BRIDGE_SOURCE" "$KOTLIN_DIR/SearchBridge.kt"
+        echo "  ✓ SearchBridge.kt copied (address search autocomplete)"
+    else
+        echo "  ❌ SearchBridge.kt not found at $SEARCH_BRIDGE_SOURCE"
+        exit 1
+    fi
+
     # Copy LocationHelper.kt
     if [ -f "$LOCATION_HELPER_SOURCE" ]; then
         echo "  📄 Copying LocationHelper.kt to kotlin/ directory..."
@@ -516,7 +541,7 @@ tasks.register<Copy>("syncKotlinSources") {
     }
     
     doLast {
-        println("✅ Kotlin sources synced - NotificationHelper, WebViewConfigurator, MainActivity, DormantService, DormantBridge, BootReceiver, FilePickerHelper")
+        println("✅ Kotlin sources synced - NotificationHelper, WebViewConfigurator, MainActivity, DormantService, DormantBridge, SearchBridge, BootReceiver, FilePickerHelper")
     }
 }
 
@@ -1098,6 +1123,15 @@ if [ -n "$APK_PATH" ]; then
             echo "  ⚠️  Dormant JNI bridge missing"
         fi
 
+        # Check SearchBridge
+        if dexdump -l plain "$APK_PATH" 2>/dev/null | grep -q "SearchBridge"; then
+            echo "  ✅ SearchBridge found in classes.dex"
+            CLASSES_FOUND=$((CLASSES_FOUND + 1))
+        else
+            echo "  ❌ SearchBridge NOT found in classes.dex"
+            echo "  ⚠️  Address search autocomplete will not work"
+        fi
+
         # Check BootReceiver
         if dexdump -l plain "$APK_PATH" 2>/dev/null | grep -q "BootReceiver"; then
             echo "  ✅ BootReceiver found in classes.dex"
@@ -1116,14 +1150,14 @@ if [ -n "$APK_PATH" ]; then
             echo "  ⚠️  Import/export will not work"
         fi
 
-        if [ "$CLASSES_FOUND" -eq 7 ]; then
+        if [ "$CLASSES_FOUND" -eq 8 ]; then
             echo ""
-            echo "  ✅ SUCCESS: All 7 Kotlin classes compiled successfully!"
+            echo "  ✅ SUCCESS: All 8 Kotlin classes compiled successfully!"
 
             # Show class details for confirmation
             echo ""
             echo "  📋 Class details:"
-            dexdump -l plain "$APK_PATH" 2>/dev/null | grep -E "(NotificationHelper|WebViewConfigurator|dev/dioxus/main/MainActivity|DormantService|DormantBridge|BootReceiver|FilePickerHelper)" | head -n 18
+            dexdump -l plain "$APK_PATH" 2>/dev/null | grep -E "(NotificationHelper|WebViewConfigurator|dev/dioxus/main/MainActivity|DormantService|DormantBridge|SearchBridge|BootReceiver|FilePickerHelper)" | head -n 20
             
             # Verify methods exist
             echo ""
@@ -1,6 +1,18 @@
 //! Creates a debug.parquet file with address and postal code from debug.txt
 //! Other fields are left as NULL, mimicking user input via the "Add Address" button
 //!
+//! Usage: `debug_script [--matched-only] [ADDRESS_FILE]`
+//!
+//! With no argument, the 33 sample addresses in `scripts/debug.txt` (embedded
+//! at compile time) are used. Pass a path to a file in the same format to
+//! generate debug data for other streets or cities without recompiling.
+//!
+//! By default every parsed address is written, including ones with no miljö
+//! match (like the "false street" sample), so the app's invalid-address path
+//! can be tested. With `--matched-only`, addresses are checked against the
+//! bundled `android/assets/data/db.parquet` and `adresser.parquet` and only
+//! those with a miljö match and real coordinates are kept.
+//!
 //! Format of debug.txt (CSV):
 //! postnummer,full_address,street,number (comment)
 //! Example: 211 50,Kornettsgatan 18C,Kornettsgatan,18C (dag 1)
@@ -9,24 +21,31 @@
 //! Creates a minimal parquet with 'adress' and 'postnummer' fields.
 //! When loaded in the app, StoredAddress::new() will perform fuzzy matching
 //! against the static parking database using both fields.
+use amp_core::checksum::record_asset_checksum;
+use amp_core::parquet::{read_address_parquet, read_db_parquet};
 use arrow::array::StringBuilder;
 use arrow::datatypes::{DataType, Field, Schema};
 use arrow::record_batch::RecordBatch;
 use parquet::arrow::ArrowWriter;
 use parquet::file::properties::{EnabledStatistics, WriterProperties};
+use std::collections::HashSet;
 use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::io::{BufRead, BufReader, Cursor};
 use std::sync::Arc;
+/// Sample addresses used when no address file is given
+const EMBEDDED_ADDRESSES: &str = include_str!("../debug.txt");
+/// Correlated dataset used by `--matched-only`
+const DB_PARQUET: &str = "android/assets/data/db.parquet";
+/// Address reference data used by `--matched-only`
+const ADRESSER_PARQUET: &str = "android/assets/data/adresser.parquet";
 #[derive(Debug)]
 struct DebugAddress {
     postnummer: String,
     adress: String,
 }
-fn main() -> Result<(), Box<dyn std::error::Error>> {
-    println!("🔨 Creating debug.parquet from debug.txt...");
-    println!();
-    let file = File::open("scripts/debug.txt")?;
-    let reader = BufReader::new(file);
+/// Parse debug addresses from CSV lines, skipping blank lines, `#` comments
+/// and malformed rows with a warning.
+fn parse_addresses(reader: impl BufRead) -> std::io::Result<Vec<DebugAddress>> {
     let mut addresses = Vec::new();
     let mut line_num = 0;
     for line in reader.lines() {
@@ -64,11 +83,70 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             adress: full_address.to_string(),
         });
     }
+    Ok(addresses)
+}
+/// Key an address by full address and postal code without spaces
+fn address_key(adress: &str, postnummer: &str) -> (String, String) {
+    (adress.trim().to_lowercase(), postnummer.replace(' ', ""))
+}
+/// Split addresses into those with a miljö match and real coordinates, and
+/// the rest, using the bundled db.parquet and adresser.parquet.
+fn split_matched(
+    addresses: Vec<DebugAddress>,
+) -> Result<(Vec<DebugAddress>, Vec<DebugAddress>), Box<dyn std::error::Error>> {
+    let matched: HashSet<(String, String)> = read_db_parquet(File::open(DB_PARQUET)?)?
+        .into_iter()
+        .filter(|entry| entry.info.is_some())
+        .map(|entry| address_key(&entry.adress, entry.postnummer.as_deref().unwrap_or("")))
+        .collect();
+    let located: HashSet<(String, String)> = read_address_parquet(File::open(ADRESSER_PARQUET)?)?
+        .into_iter()
+        .filter(|entry| !entry.coordinates[0].is_zero() || !entry.coordinates[1].is_zero())
+        .map(|entry| address_key(&entry.adress, entry.postnummer.as_deref().unwrap_or("")))
+        .collect();
+    Ok(addresses.into_iter().partition(|entry| {
+        let key = address_key(&entry.adress, &entry.postnummer);
+        matched.contains(&key) && located.contains(&key)
+    }))
+}
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut matched_only = false;
+    let mut source = None;
+    for arg in std::env::args().skip(1) {
+        if arg == "--matched-only" {
+            matched_only = true;
+        } else {
+            source = Some(arg);
+        }
+    }
+    let source_name = source.as_deref().unwrap_or("embedded debug.txt");
+    println!("🔨 Creating debug.parquet from {}...", source_name);
+    println!();
+    let mut addresses = match &source {
+        Some(path) => parse_addresses(BufReader::new(File::open(path)?))?,
+        None => parse_addresses(Cursor::new(EMBEDDED_ADDRESSES))?,
+    };
     println!();
-    println!("✅ Loaded {} addresses from debug.txt", addresses.len());
+    println!(
+        "✅ Loaded {} addresses from {}",
+        addresses.len(),
+        source_name
+    );
     println!();
+    if matched_only {
+        let (kept, excluded) = split_matched(addresses)?;
+        println!(
+            "🧹 --matched-only: excluded {} addresses without a miljö match or coordinates",
+            excluded.len()
+        );
+        for entry in &excluded {
+            println!("   - {} ({})", entry.adress, entry.postnummer);
+        }
+        println!();
+        addresses = kept;
+    }
     if addresses.is_empty() {
-        return Err("No addresses found in debug.txt".into());
+        return Err(format!("No addresses found in {}", source_name).into());
     }
     let schema = Arc::new(Schema::new(vec![
         Field::new("adress", DataType::Utf8, false),
@@ -94,6 +172,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     )?;
     writer.write(&batch)?;
     writer.close()?;
+    record_asset_checksum("android/assets/data/debug.parquet")?;
     println!(
         "✅ Created debug.parquet with {} address entries",
         addresses.len()
@@ -1,35 +1,45 @@
 //! AMP Server - Address-Parking Correlation CLI
 //! Supports multiple correlation algorithms, benchmarking, testing with visual verification
 extern crate core;
-use amp_core::api::api;
+use amp_core::api::{DataLoader, DataPaths, DataSet, LoadWarning, ParseReport};
 use amp_core::benchmark::Benchmarker;
-use amp_core::checksum::DataChecksum;
+use amp_core::buckets::{TimeBucket, bucket_at, determine_occurrence};
+use amp_core::checksum::{DataChecksum, DataSource, record_asset_checksum};
+use amp_core::correlation::{diff_results, merge_results};
 use amp_core::correlation_algorithms::rtree_spatial::RTreeSpatialParkeringAlgo;
 use amp_core::correlation_algorithms::{
-    CorrelationAlgo, DistanceBasedAlgo, DistanceBasedParkeringAlgo, GridNearestAlgo,
-    GridNearestParkeringAlgo, KDTreeParkeringAlgo, KDTreeSpatialAlgo, OverlappingChunksAlgo,
-    OverlappingChunksParkeringAlgo, ParkeringCorrelationAlgo, RTreeSpatialAlgo, RaycastingAlgo,
-    RaycastingParkeringAlgo,
+    BallTreeParkeringAlgo, BallTreeSpatialAlgo, CorrelationAlgo, DistanceBasedAlgo,
+    DistanceBasedParkeringAlgo, GridNearestAlgo, GridNearestParkeringAlgo, KDTreeParkeringAlgo,
+    KDTreeSpatialAlgo, MinSegmentLengthAlgo, OverlappingChunksAlgo, OverlappingChunksParkeringAlgo,
+    ParkeringCorrelationAlgo, RTreeSpatialAlgo, RaycastingAlgo, RaycastingParkeringAlgo,
+    adaptive_cutoff, correlate_usable,
+};
+use amp_core::parking::{distinct_categories, malmo_taxa_table};
+use amp_core::parquet::{
+    ParquetWriteOptions, read_db_parquet, write_adress_clean_parquet,
+    write_output_parquet_matched_only, write_output_parquet_matched_only_with_options,
 };
-use amp_core::parquet::{write_adress_clean_parquet, write_output_parquet};
 use amp_core::structs::{
-    AdressClean, CorrelationResult, MiljoeDataClean, OutputData, OutputDataWithDistance,
-    ParkeringsDataClean,
+    AdressClean, CorrelationResult, DB, DBParams, DEFAULT_EXACT_RADIUS_METERS, MatchQuality,
+    MiljoeDataClean, OutputData, OutputDataWithDistance, ParkeringsDataClean, ReliabilityCounts,
+    SWEDISH_TZ, format_dagar, match_confidence,
 };
-use clap::{Parser, Subcommand};
+use chrono::{DateTime, Datelike, Utc};
+use clap::{Args, Parser, Subcommand};
 use geojson::{Feature, GeoJson};
 use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
 use rand::seq::SliceRandom;
 use rand::thread_rng;
 use rayon::prelude::*;
 use rust_decimal::Decimal;
-use rust_decimal::prelude::FromPrimitive;
+use std::collections::{BTreeMap, HashMap};
 use std::env;
 use std::fs;
 use std::fs::File;
-use std::io::{self, BufReader, Write};
-use std::sync::Arc;
+use std::io::{self, BufReader, IsTerminal, Write};
+use std::path::PathBuf;
 use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, RwLock};
 use std::thread;
 use std::time::Duration;
 use std::time::Instant;
@@ -40,6 +50,65 @@ use std::time::Instant;
 struct Cli {
     #[command(subcommand)]
     command: Commands,
+    #[arg(
+        long,
+        global = true,
+        help = "Fail on the first malformed GeoJSON feature instead of skipping it"
+    )]
+    strict: bool,
+    #[arg(
+        long,
+        global = true,
+        help = "Directory with the GeoJSON sources [default: $AMP_DATA_DIR or data]"
+    )]
+    data_dir: Option<PathBuf>,
+}
+/// Flags for the per-address miljö cutoff, shared by `correlate` and `output`
+#[derive(Args, Debug, Clone, Copy)]
+struct AdaptiveCutoffArgs {
+    #[arg(
+        long,
+        help = "Scale the miljö cutoff per address by local segment density"
+    )]
+    adaptive_cutoff: bool,
+    #[arg(
+        long,
+        default_value_t = 10.,
+        help = "Smallest adaptive cutoff in meters"
+    )]
+    min_cutoff: f64,
+    #[arg(
+        long,
+        default_value_t = 50.,
+        help = "Largest adaptive cutoff in meters"
+    )]
+    max_cutoff: f64,
+}
+impl AdaptiveCutoffArgs {
+    /// Adaptive cutoff bounds from the flags, `None` unless enabled
+    fn bounds(&self) -> Result<Option<AdaptiveBounds>, Box<dyn std::error::Error>> {
+        if !self.adaptive_cutoff {
+            return Ok(None);
+        }
+        let (min, max) = (self.min_cutoff, self.max_cutoff);
+        if !(min > 0.0 && min <= max) {
+            return Err(format!(
+                "--min-cutoff ({}) must be positive and at most --max-cutoff ({})",
+                min, max
+            )
+            .into());
+        }
+        Ok(Some(AdaptiveBounds { min, max }))
+    }
+}
+/// Stub segment filter, shared by `correlate`, `output` and `serve`
+#[derive(Args, Debug, Clone, Copy)]
+struct SegmentLengthArgs {
+    #[arg(
+        long,
+        help = "Skip miljö segments shorter than this many meters when a longer one is within the cutoff"
+    )]
+    min_segment_length: Option<f64>,
 }
 #[derive(Subcommand)]
 enum Commands {
@@ -49,6 +118,21 @@ enum Commands {
         algorithm: AlgorithmChoice,
         #[arg(short, long, default_value_t = 20., help = "Distance cutoff in meters")]
         cutoff: f64,
+        #[arg(
+            short,
+            long,
+            help = "Print every matched address with miljö and parkering distances"
+        )]
+        verbose: bool,
+        #[command(flatten)]
+        segments: SegmentLengthArgs,
+        #[command(flatten)]
+        adaptive: AdaptiveCutoffArgs,
+        #[arg(
+            long,
+            help = "Group matched addresses by how soon their miljö restriction starts"
+        )]
+        by_bucket: bool,
     },
     /// Output correlation results to parquet (for server database or Android app)
     Output {
@@ -64,6 +148,16 @@ enum Commands {
             help = "Also generate Android-formatted local storage (with day/time extraction)"
         )]
         android: bool,
+        #[command(flatten)]
+        segments: SegmentLengthArgs,
+        #[command(flatten)]
+        adaptive: AdaptiveCutoffArgs,
+        #[arg(
+            long,
+            default_value_t = DEFAULT_EXACT_RADIUS_METERS,
+            help = "Matches within this many meters are marked exact, farther ones approximate"
+        )]
+        exact_radius: f64,
     },
     /// Test correlation with visual browser verification
     Test {
@@ -90,6 +184,11 @@ enum Commands {
         sample_size: usize,
         #[arg(short, long, default_value_t = 20., help = "Distance cutoff in meters")]
         cutoff: f64,
+        #[arg(
+            long,
+            help = "Also compare correlation with Decimal vs f64 coordinates"
+        )]
+        coordinate_math: bool,
     },
     /// Check for data updates from Malmö open data portal
     CheckUpdates {
@@ -106,10 +205,9 @@ enum Commands {
         #[arg(
             short,
             long,
-            default_value = "data/adresser.json",
-            help = "Input JSON file path"
+            help = "Input JSON file path [default: adresser.json in the data directory]"
         )]
-        input: String,
+        input: Option<String>,
         #[arg(
             short,
             long,
@@ -117,9 +215,89 @@ enum Commands {
             help = "Output parquet file path"
         )]
         output: String,
+        #[arg(
+            long,
+            help = "Only report which features would be skipped, without writing"
+        )]
+        dry_run: bool,
+    },
+    /// List the distinct taxa and parking types in the parking data
+    Catalog,
+    /// Compare two correlation output snapshots per address
+    Compare {
+        #[arg(long, help = "Earlier output parquet file")]
+        old: String,
+        #[arg(long, help = "Later output parquet file")]
+        new: String,
+        #[arg(
+            short,
+            long,
+            default_value_t = 10,
+            help = "Number of example addresses to print per category"
+        )]
+        sample: usize,
+    },
+    /// Export matched addresses as CSV, e.g. for Excel
+    Export {
+        #[arg(
+            short,
+            long,
+            help = "Output parquet file to export instead of running correlation"
+        )]
+        input: Option<String>,
+        #[arg(short, long, value_enum, default_value_t = AlgorithmChoice::KDTree)]
+        algorithm: AlgorithmChoice,
+        #[arg(short, long, default_value_t = 20., help = "Distance cutoff in meters")]
+        cutoff: f64,
+        #[arg(
+            short,
+            long,
+            default_value = "correlation.csv",
+            help = "CSV file to write"
+        )]
+        output: String,
+    },
+    /// List every miljö segment near an address, to debug a missing match
+    Inspect {
+        #[arg(help = "Address to look up, e.g. \"Kornettsgatan 18C\"")]
+        address: String,
+        #[arg(short, long, default_value_t = 100., help = "Search radius in meters")]
+        radius: f64,
+    },
+    /// Build the correlated dataset and keep it current
+    Serve {
+        #[arg(short, long, value_enum, default_value_t = AlgorithmChoice::KDTree)]
+        algorithm: AlgorithmChoice,
+        #[arg(short, long, default_value_t = 20., help = "Distance cutoff in meters")]
+        cutoff: f64,
+        #[arg(short, long, default_value = "db.parquet", help = "Output file path")]
+        output: String,
+        #[arg(
+            short,
+            long,
+            help = "Periodically check for data updates and rebuild when they change"
+        )]
+        watch: bool,
+        #[arg(
+            short,
+            long,
+            default_value_t = 3600,
+            help = "Seconds between update checks in watch mode"
+        )]
+        interval: u64,
+        #[arg(long, default_value = "checksums.json", help = "Checksum file path")]
+        checksum_file: String,
+        #[command(flatten)]
+        segments: SegmentLengthArgs,
+        #[arg(
+            long,
+            default_value_t = DEFAULT_EXACT_RADIUS_METERS,
+            help = "Matches within this many meters are marked exact, farther ones approximate"
+        )]
+        exact_radius: f64,
     },
 }
-#[derive(clap::ValueEnum, Clone, Debug)]
+#[derive(clap::ValueEnum, Clone, Copy, Debug)]
 enum AlgorithmChoice {
     #[value(name = "distance-based")]
     DistanceBased,
@@ -133,39 +311,143 @@ enum AlgorithmChoice {
     KDTree,
     #[value(name = "grid")]
     Grid,
+    #[value(name = "balltree")]
+    BallTree,
 }
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     let cli = Cli::parse();
+    let mut data_paths = DataPaths::from_env();
+    if let Some(dir) = cli.data_dir {
+        data_paths.dir = dir;
+    }
     match cli.command {
-        Commands::Correlate { algorithm, cutoff } => {
-            run_correlation(algorithm, cutoff)?;
+        Commands::Correlate {
+            algorithm,
+            cutoff,
+            verbose,
+            segments,
+            adaptive,
+            by_bucket,
+        } => {
+            let miljo = MiljoOptions {
+                cutoff,
+                min_segment_length: segments.min_segment_length,
+                adaptive: adaptive.bounds()?,
+            };
+            run_correlation(
+                algorithm,
+                miljo,
+                verbose,
+                by_bucket,
+                &data_paths,
+                cli.strict,
+            )?;
         }
         Commands::Output {
             algorithm,
             cutoff,
             output,
             android,
+            segments,
+            adaptive,
+            exact_radius,
         } => {
-            run_output(algorithm, cutoff, &output, android)?;
+            let miljo = MiljoOptions {
+                cutoff,
+                min_segment_length: segments.min_segment_length,
+                adaptive: adaptive.bounds()?,
+            };
+            run_output(
+                algorithm,
+                miljo,
+                exact_radius,
+                &output,
+                android,
+                &data_paths,
+                cli.strict,
+            )?;
         }
         Commands::Test {
             algorithm,
             cutoff,
             windows,
         } => {
-            run_test_mode(algorithm, cutoff, windows)?;
+            run_test_mode(algorithm, cutoff, windows, &data_paths, cli.strict)?;
         }
         Commands::Benchmark {
             sample_size,
             cutoff,
+            coordinate_math,
         } => {
-            run_benchmark(sample_size, cutoff)?;
+            run_benchmark(
+                sample_size,
+                cutoff,
+                coordinate_math,
+                &data_paths,
+                cli.strict,
+            )?;
         }
         Commands::CheckUpdates { checksum_file } => {
             tokio::runtime::Runtime::new()?.block_on(check_updates(&checksum_file))?
         }
-        Commands::CreateAdresserParquet { input, output } => {
-            run_create_adresser_parquet(&input, &output)?;
+        Commands::CreateAdresserParquet {
+            input,
+            output,
+            dry_run,
+        } => {
+            let input = input.unwrap_or_else(|| data_paths.adresser("json"));
+            if dry_run {
+                run_validate_addresses(&input)?;
+            } else {
+                run_create_adresser_parquet(&input, &output)?;
+            }
+        }
+        Commands::Catalog => {
+            run_catalog(&data_paths, cli.strict)?;
+        }
+        Commands::Compare { old, new, sample } => {
+            run_compare(&old, &new, sample)?;
+        }
+        Commands::Export {
+            input,
+            algorithm,
+            cutoff,
+            output,
+        } => {
+            run_export(
+                input.as_deref(),
+                algorithm,
+                cutoff,
+                &output,
+                &data_paths,
+                cli.strict,
+            )?;
+        }
+        Commands::Inspect { address, radius } => {
+            run_inspect(&address, radius, &data_paths, cli.strict)?;
+        }
+        Commands::Serve {
+            algorithm,
+            cutoff,
+            output,
+            watch,
+            interval,
+            checksum_file,
+            segments,
+            exact_radius,
+        } => {
+            let config = ServeConfig {
+                algorithm,
+                cutoff,
+                min_segment_length: segments.min_segment_length,
+                exact_radius,
+                output,
+                interval: Duration::from_secs(interval.max(1)),
+                checksum_file,
+                data_paths,
+                strict: cli.strict,
+            };
+            tokio::runtime::Runtime::new()?.block_on(run_serve(config, watch))?
         }
     }
     Ok(())
@@ -197,29 +479,175 @@ fn run_create_adresser_parquet(input: &str, output: &str) -> anyhow::Result<()>
     }
     pb.finish_with_message("Parsed adresser, writing parquet...");
     write_adress_clean_parquet(records, output)?;
+    record_asset_checksum(output)?;
     pb.finish_with_message("Done writing adresser.parquet");
     Ok(())
 }
-fn feature_to_adress_clean(feature: &Feature) -> Option<AdressClean> {
-    let geometry = feature.geometry.as_ref()?;
-    if geometry.value.type_name() != "Point" {
-        return None;
+/// Print how many address features would be skipped by the conversion, per reason
+fn run_validate_addresses(input: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let report = DataLoader::validate_addresses(input)?;
+    println!(
+        "\n🔎 {}: {}/{} features valid",
+        input, report.valid, report.total
+    );
+    for (reason, count) in report.reasons() {
+        if count > 0 {
+            println!("  {:>7}  {}", count, reason);
+        }
+    }
+    for (reason, count) in report.optional_reasons() {
+        if count > 0 {
+            println!("  {:>7}  {} (kept)", count, reason);
+        }
+    }
+    Ok(())
+}
+/// Print every distinct taxa and typ_av_parkering value in the parking data
+fn run_catalog(data_paths: &DataPaths, strict: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let (parkering, warnings) =
+        DataLoader::load_parkering_with_warnings(&data_paths.parkering("json"))?;
+    report_load_warnings(&warnings, strict)?;
+    let (taxa, typ) = distinct_categories(&parkering);
+    println!("\n📚 Taxa ({}):", taxa.len());
+    for value in &taxa {
+        println!("  {:?}", value);
+    }
+    println!("\n📚 Typ av parkering ({}):", typ.len());
+    for value in &typ {
+        println!("  {:?}", value);
+    }
+    let zero_spaces = parkering.iter().filter(|p| p.antal_platser == 0).count();
+    if zero_spaces > 0 {
+        println!(
+            "\n⚠️  {} of {} segments have antal_platser = 0",
+            zero_spaces,
+            parkering.len()
+        );
+    }
+    Ok(())
+}
+/// Column names written by [`write_output_csv`], in order
+const CSV_HEADER: [&str; 12] = [
+    "adress",
+    "gata",
+    "gatunummer",
+    "postnummer",
+    "info",
+    "tid",
+    "dag",
+    "taxa",
+    "antal_platser",
+    "typ_av_parkering",
+    "miljo_distance",
+    "parkering_distance",
+];
+/// One row of [`write_output_csv`]: the output row with its miljö and
+/// parkering match distances in meters, `None` where unknown
+type CsvRow<'a> = (&'a OutputData, Option<f64>, Option<f64>);
+/// Write rows as comma-separated UTF-8 with a header line.
+///
+/// Starts with a byte order mark so Excel reads å, ä and ö correctly. `None`
+/// becomes an empty cell; fields containing commas, quotes or line breaks
+/// are quoted with inner quotes doubled. `dag` lists every day of a
+/// restriction on several days, e.g. `"1,15"`. Distances have two decimals.
+fn write_output_csv<W: Write>(rows: &[CsvRow], writer: &mut W) -> io::Result<()> {
+    write!(writer, "\u{feff}")?;
+    writeln!(writer, "{}", CSV_HEADER.join(","))?;
+    let distance = |meters: Option<f64>| meters.map(|m| format!("{:.2}", m));
+    for &(row, miljo_distance, parkering_distance) in rows {
+        let fields = [
+            Some(row.adress.clone()),
+            Some(row.gata.clone()),
+            Some(row.gatunummer.clone()),
+            row.postnummer.clone(),
+            row.info.clone(),
+            row.tid.clone(),
+            if row.dagar.is_empty() {
+                row.dag.map(|dag| dag.to_string())
+            } else {
+                Some(format_dagar(&row.dagar))
+            },
+            row.taxa.clone(),
+            row.antal_platser.map(|platser| platser.to_string()),
+            row.typ_av_parkering.clone(),
+            distance(miljo_distance),
+            distance(parkering_distance),
+        ];
+        let line: Vec<String> = fields
+            .iter()
+            .map(|field| csv_field(field.as_deref().unwrap_or_default()))
+            .collect();
+        writeln!(writer, "{}", line.join(","))?;
+    }
+    Ok(())
+}
+/// Quote a CSV field if it contains a separator, quote or line break
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+/// Print how correlation results changed between two output snapshots
+fn run_compare(
+    old_path: &str,
+    new_path: &str,
+    sample: usize,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let old = read_db_parquet(File::open(old_path)?)
+        .map_err(|e| format!("Failed to read '{}': {}", old_path, e))?;
+    let new = read_db_parquet(File::open(new_path)?)
+        .map_err(|e| format!("Failed to read '{}': {}", new_path, e))?;
+    let diff = diff_results(&old, &new);
+    println!("\n🔀 Comparing {} → {}", old_path, new_path);
+    println!("  Old: {} matched addresses", old.len());
+    println!("  New: {} matched addresses", new.len());
+    println!("\n📊 Per-address changes:");
+    println!("  Gained match:  {}", diff.gained.len());
+    println!("  Lost match:    {}", diff.lost.len());
+    println!("  Changed match: {}", diff.changed.len());
+    println!("  Unchanged:     {}", diff.unchanged);
+    if diff.is_empty() {
+        println!("\n✓ No address changed its match");
+        return Ok(());
     }
-    let coords = match geometry.value.clone() {
-        geojson::Value::Point(c) => c,
-        _ => return None,
+    let label = |entry: &OutputData| {
+        format!(
+            "{} ({})",
+            entry.adress,
+            entry.postnummer.as_deref().unwrap_or("-")
+        )
     };
-    if coords.len() != 2 {
-        return None;
+    if !diff.gained.is_empty() {
+        println!("\n➕ Gained (first {}):", sample.min(diff.gained.len()));
+        for entry in diff.gained.iter().take(sample) {
+            println!("  {} → {}", label(entry), entry.dataset_source());
+        }
     }
-    let x = Decimal::from_f64(coords[0])?;
-    let y = Decimal::from_f64(coords[1])?;
+    if !diff.lost.is_empty() {
+        println!("\n➖ Lost (first {}):", sample.min(diff.lost.len()));
+        for entry in diff.lost.iter().take(sample) {
+            println!("  {} (was {})", label(entry), entry.dataset_source());
+        }
+    }
+    if !diff.changed.is_empty() {
+        println!("\n✏️  Changed (first {}):", sample.min(diff.changed.len()));
+        for change in diff.changed.iter().take(sample) {
+            println!(
+                "  {}: {}",
+                label(&change.new),
+                change.changed_fields().join(", ")
+            );
+        }
+    }
+    println!();
+    Ok(())
+}
+/// Convert an address feature, skipping it as decided by [`DataLoader::address_point`]
+fn feature_to_adress_clean(feature: &Feature) -> Option<AdressClean> {
+    let ([x, y], adress) = DataLoader::address_point(feature)?;
     let props = feature.properties.as_ref()?;
-    let adress = props
-        .get("BELADRESS")?
-        .as_str()
-        .unwrap_or_default()
-        .to_string();
     let gata = props
         .get("ADRESSOMR")
         .and_then(|v| v.as_str())
@@ -305,6 +733,7 @@ fn select_algorithms() -> Vec<&'static str> {
         "R-Tree",
         "KD-Tree",
         "Grid",
+        "Ball Tree",
     ];
     println!("\n🔧 Algorithm Selection (Y/N to include, default is Y if just Enter is pressed):\n",);
     let mut selected = Vec::new();
@@ -337,21 +766,66 @@ fn select_algorithms() -> Vec<&'static str> {
         selected
     }
 }
-type CorDat = Result<Vec<(String, f64, MiljoeDataClean)>, Box<dyn std::error::Error>>;
+/// Bounds of the per-address miljö cutoff, see [`adaptive_cutoff`]
+#[derive(Debug, Clone, Copy)]
+struct AdaptiveBounds {
+    min: f64,
+    max: f64,
+}
+/// How addresses are matched to miljö segments
+#[derive(Debug, Clone, Copy)]
+struct MiljoOptions {
+    /// Distance cutoff in meters, the base of the adaptive cutoff if enabled
+    cutoff: f64,
+    /// Skip shorter segments when a longer one is within the cutoff
+    min_segment_length: Option<f64>,
+    /// Per-address cutoff bounds, `None` for a fixed cutoff
+    adaptive: Option<AdaptiveBounds>,
+}
+impl MiljoOptions {
+    /// A fixed `cutoff` with every segment considered
+    fn fixed(cutoff: f64) -> Self {
+        Self {
+            cutoff,
+            min_segment_length: None,
+            adaptive: None,
+        }
+    }
+}
+/// Miljö matches and how many of them fell back past a segment with unusable `tid`
+type CorDat = Result<(Vec<(String, f64, MiljoeDataClean)>, usize), Box<dyn std::error::Error>>;
+/// Build the selected miljö algorithm, skipping stub segments if requested
+fn build_miljoe_algo<A, F>(
+    build: F,
+    zones: &[MiljoeDataClean],
+    min_segment_length: Option<f64>,
+    cutoff: f64,
+) -> Box<dyn CorrelationAlgo + Sync>
+where
+    A: CorrelationAlgo + Sync + 'static,
+    F: Fn(&[MiljoeDataClean]) -> A,
+{
+    match min_segment_length {
+        Some(min) => Box::new(MinSegmentLengthAlgo::new(build, zones, min, cutoff)),
+        None => Box::new(build(zones)),
+    }
+}
 /// Generic correlation function for miljoe dataset that handles all algorithms
 fn correlate_miljoe_dataset(
     algorithm: &AlgorithmChoice,
     addresses: &[AdressClean],
     zones: &[MiljoeDataClean],
-    cutoff: f64,
+    miljo: MiljoOptions,
     pb: &ProgressBar,
 ) -> CorDat {
+    let MiljoOptions {
+        cutoff,
+        min_segment_length,
+        adaptive,
+    } = miljo;
     let counter = Arc::new(AtomicUsize::new(0));
     let process_address =
         |addr: &AdressClean, idx: usize, dist: f64| -> Option<(String, f64, MiljoeDataClean)> {
-            if dist > cutoff {
-                return None;
-            }
             let info = zones.get(idx)?.clone();
             let count = counter.fetch_add(1, Ordering::Relaxed) + 1;
             if count.is_multiple_of(100) || count == addresses.len() {
@@ -359,70 +833,51 @@ fn correlate_miljoe_dataset(
             }
             Some((addr.adress.clone(), dist, info))
         };
-    let results: Vec<_> = match algorithm {
+    let algo = match algorithm {
         AlgorithmChoice::DistanceBased => {
-            let algo = DistanceBasedAlgo;
-            addresses
-                .par_iter()
-                .filter_map(|addr| {
-                    let (idx, dist) = algo.correlate(addr, zones)?;
-                    process_address(addr, idx, dist)
-                })
-                .collect()
+            build_miljoe_algo(|_| DistanceBasedAlgo, zones, min_segment_length, cutoff)
         }
         AlgorithmChoice::Raycasting => {
-            let algo = RaycastingAlgo;
-            addresses
-                .par_iter()
-                .filter_map(|addr| {
-                    let (idx, dist) = algo.correlate(addr, zones)?;
-                    process_address(addr, idx, dist)
-                })
-                .collect()
-        }
-        AlgorithmChoice::OverlappingChunks => {
-            let algo = OverlappingChunksAlgo::new(zones);
-            addresses
-                .par_iter()
-                .filter_map(|addr| {
-                    let (idx, dist) = algo.correlate(addr, zones)?;
-                    process_address(addr, idx, dist)
-                })
-                .collect()
+            build_miljoe_algo(|_| RaycastingAlgo, zones, min_segment_length, cutoff)
         }
+        AlgorithmChoice::OverlappingChunks => build_miljoe_algo(
+            OverlappingChunksAlgo::new,
+            zones,
+            min_segment_length,
+            cutoff,
+        ),
         AlgorithmChoice::RTree => {
-            let algo = RTreeSpatialAlgo::new(zones);
-            addresses
-                .par_iter()
-                .filter_map(|addr| {
-                    let (idx, dist) = algo.correlate(addr, zones)?;
-                    process_address(addr, idx, dist)
-                })
-                .collect()
+            build_miljoe_algo(RTreeSpatialAlgo::new, zones, min_segment_length, cutoff)
         }
         AlgorithmChoice::KDTree => {
-            let algo = KDTreeSpatialAlgo::new(zones);
-            addresses
-                .par_iter()
-                .filter_map(|addr| {
-                    let (idx, dist) = algo.correlate(addr, zones)?;
-                    process_address(addr, idx, dist)
-                })
-                .collect()
+            build_miljoe_algo(KDTreeSpatialAlgo::new, zones, min_segment_length, cutoff)
         }
         AlgorithmChoice::Grid => {
-            let algo = GridNearestAlgo::new(zones);
-            addresses
-                .par_iter()
-                .filter_map(|addr| {
-                    let (idx, dist) = algo.correlate(addr, zones)?;
-                    process_address(addr, idx, dist)
-                })
-                .collect()
+            build_miljoe_algo(GridNearestAlgo::new, zones, min_segment_length, cutoff)
+        }
+        AlgorithmChoice::BallTree => {
+            build_miljoe_algo(BallTreeSpatialAlgo::new, zones, min_segment_length, cutoff)
         }
     };
+    let tid_fallbacks = AtomicUsize::new(0);
+    let results: Vec<_> = addresses
+        .par_iter()
+        .filter_map(|addr| {
+            let cutoff = match adaptive {
+                Some(bounds) => {
+                    adaptive_cutoff(algo.as_ref(), addr, zones, cutoff, bounds.min, bounds.max)
+                }
+                None => cutoff,
+            };
+            let found = correlate_usable(algo.as_ref(), addr, zones, cutoff)?;
+            if found.fell_back {
+                tid_fallbacks.fetch_add(1, Ordering::Relaxed);
+            }
+            process_address(addr, found.index, found.distance)
+        })
+        .collect();
     pb.set_position(addresses.len() as u64);
-    Ok(results)
+    Ok((results, tid_fallbacks.into_inner()))
 }
 type CorPark = Result<Vec<(String, f64, ParkeringsDataClean)>, Box<dyn std::error::Error>>;
 /// Generic correlation function for parkering dataset that handles all algorithms
@@ -436,7 +891,7 @@ fn correlate_parkering_dataset(
     let counter = Arc::new(AtomicUsize::new(0));
     let process_address =
         |addr: &AdressClean, idx: usize, dist: f64| -> Option<(String, f64, ParkeringsDataClean)> {
-            if dist > cutoff {
+            if dist > cutoff || !addr.has_location() {
                 return None;
             }
             let data = zones.get(idx)?.clone();
@@ -507,82 +962,231 @@ fn correlate_parkering_dataset(
                 })
                 .collect()
         }
+        AlgorithmChoice::BallTree => {
+            let algo = BallTreeParkeringAlgo::new(zones);
+            addresses
+                .par_iter()
+                .filter_map(|addr| {
+                    let (idx, dist) = algo.correlate(addr, zones)?;
+                    process_address(addr, idx, dist)
+                })
+                .collect()
+        }
     };
     pb.set_position(addresses.len() as u64);
     Ok(results)
 }
-/// Merge correlate results from two datasets
-fn merge_results(
-    addresses: &[AdressClean],
-    miljo_results: &[(String, f64, MiljoeDataClean)],
-    parkering_results: &[(String, f64, ParkeringsDataClean)],
-) -> Vec<OutputDataWithDistance> {
-    use std::collections::HashMap;
-    let miljo_map: HashMap<_, _> = miljo_results
-        .iter()
-        .map(|(addr, dist, miljodata)| (addr.clone(), (*dist, miljodata.clone())))
-        .collect();
-    let parkering_map: HashMap<_, _> = parkering_results
+/// Output row with its [`MatchQuality`] filled in from the match distance
+fn with_match_quality(
+    result: &OutputDataWithDistance,
+    exact_radius: f64,
+    cutoff: f64,
+) -> OutputData {
+    OutputData {
+        match_quality: Some(result.match_quality(exact_radius, cutoff)),
+        ..result.data.clone()
+    }
+}
+/// Print a summary of skipped GeoJSON features, failing in strict mode
+fn report_load_warnings(
+    warnings: &[LoadWarning],
+    strict: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if warnings.is_empty() {
+        return Ok(());
+    }
+    if strict {
+        return Err(format!("Malformed GeoJSON feature (--strict): {}", warnings[0]).into());
+    }
+    println!(
+        "\n⚠️  Skipped {} malformed GeoJSON feature(s):",
+        warnings.len()
+    );
+    for warning in warnings.iter().take(5) {
+        println!("  {}", warning);
+    }
+    if warnings.len() > 5 {
+        println!("  ... and {} more", warnings.len() - 5);
+    }
+    Ok(())
+}
+/// Print how many miljö rows have a usable `tid`/`day`, with examples of rejects
+fn print_parse_report(report: &ParseReport) {
+    if report.rejected() == 0 {
+        println!("\n🕒 Miljö tid/day: all {} rows accepted", report.total);
+        return;
+    }
+    println!(
+        "\n⚠️  Miljö tid/day: {} of {} rows rejected ({} by time, {} by day)",
+        report.rejected(),
+        report.total,
+        report.rejected_by_time,
+        report.rejected_by_day,
+    );
+    for sample in &report.samples {
+        println!("  {}", sample);
+    }
+}
+/// Load all datasets, reporting skipped features via [`report_load_warnings`]
+/// and unusable miljö schedules via [`print_parse_report`]
+fn load_all_data(
+    data_paths: &DataPaths,
+    strict: bool,
+) -> Result<DataSet, Box<dyn std::error::Error>> {
+    let (data, warnings) = DataSet::load_from_paths_with_warnings(data_paths)?;
+    report_load_warnings(&warnings, strict)?;
+    print_parse_report(&ParseReport::from_miljodata(&data.miljo, &warnings));
+    Ok(data)
+}
+/// Print all matched addresses with both distances, closest match first
+fn print_match_table(merged: &[OutputDataWithDistance], cutoff: f64) {
+    let mut matched: Vec<_> = merged
         .iter()
-        .map(|(addr, dist, data)| (addr.clone(), (*dist, data.clone())))
+        .filter_map(|r| Some((r, r.closest_distance()?)))
         .collect();
-    addresses
+    matched.sort_by(|(_, a), (_, b)| a.total_cmp(b));
+    let width = matched
         .iter()
-        .map(|addr| {
-            let miljo_data = miljo_map.get(&addr.adress);
-            let parkering_data = parkering_map.get(&addr.adress);
-            let (info, tid, dag, miljo_distance) = if let Some((dist, miljodata)) = miljo_data {
-                (
-                    Some(miljodata.info.clone()),
-                    Some(miljodata.tid.clone()),
-                    Some(miljodata.dag),
-                    Some(*dist),
-                )
-            } else {
-                (None, None, None, None)
-            };
-            let (taxa, antal_platser, typ_av_parkering, parkering_distance) =
-                if let Some((dist, p_data)) = parkering_data {
-                    (
-                        Some(p_data.taxa.clone()),
-                        Some(p_data.antal_platser),
-                        Some(p_data.typ_av_parkering.clone()),
-                        Some(*dist),
-                    )
-                } else {
-                    (None, None, None, None)
-                };
-            OutputDataWithDistance {
-                data: OutputData {
-                    postnummer: addr.postnummer.clone(),
-                    adress: addr.adress.clone(),
-                    gata: addr.gata.clone(),
-                    gatunummer: addr.gatunummer.clone(),
-                    info,
-                    tid,
-                    dag,
-                    taxa,
-                    antal_platser,
-                    typ_av_parkering,
-                },
-                miljo_distance,
-                parkering_distance,
-            }
-        })
-        .collect()
+        .map(|(r, _)| r.data.adress.chars().count())
+        .max()
+        .unwrap_or(0)
+        .max("Address".len());
+    let format_distance = |dist: Option<f64>, reliable: bool| match dist {
+        Some(d) if reliable => format!("{:.2}m", d),
+        Some(d) => format!("{:.2}m?", d),
+        None => "-".to_string(),
+    };
+    println!(
+        "\n📋 All Matches ({}), closest first (? = unreliable):",
+        matched.len()
+    );
+    println!(
+        "  {:<width$}  {:>10}  {:>10}  {:>10}",
+        "Address",
+        "Miljödata",
+        "Parkering",
+        "Confidence",
+        width = width,
+    );
+    for (result, closest) in matched {
+        println!(
+            "  {:<width$}  {:>10}  {:>10}  {}",
+            result.data.adress,
+            format_distance(result.miljo_distance, result.miljo_reliable()),
+            format_distance(result.parkering_distance, result.parkering_reliable()),
+            colored_confidence(match_confidence(closest, cutoff)),
+            width = width,
+        );
+    }
+}
+/// Confidence as a right-aligned percentage, green above 80%, yellow above
+/// 50% and red otherwise
+///
+/// Plain when stdout is not a terminal, so piped reports stay free of
+/// escape codes.
+fn colored_confidence(confidence: f64) -> String {
+    let percent = format!("{:.0}%", confidence * 100.0);
+    if !io::stdout().is_terminal() {
+        return format!("{:>10}", percent);
+    }
+    let color = if confidence > 0.8 {
+        "32"
+    } else if confidence > 0.5 {
+        "33"
+    } else {
+        "31"
+    };
+    format!("\x1b[{}m{:>10}\x1b[0m", color, percent)
+}
+/// Time bucket of an address's miljö restriction at `now`, or `None` without one.
+///
+/// The restriction is placed on the same occurrence the app would use, the
+/// soonest of its days counted from the Swedish date of `now`, so
+/// unparseable `tid`/`dag` and days missing from that month are
+/// [`TimeBucket::Invalid`].
+fn bucket_of(data: &OutputData, now: DateTime<Utc>) -> Option<TimeBucket> {
+    let (tid, dag) = (data.tid.as_ref()?, data.dag?);
+    let dagar = if data.dagar.is_empty() {
+        vec![dag]
+    } else {
+        data.dagar.clone()
+    };
+    let today = now.with_timezone(&SWEDISH_TZ);
+    let Some((year, month, dag)) =
+        determine_occurrence(&dagar, today.year(), today.month(), today.day())
+    else {
+        return Some(TimeBucket::Invalid);
+    };
+    let restriction = DB::from_params(DBParams {
+        postnummer: data.postnummer.clone(),
+        adress: data.adress.clone(),
+        gata: Some(data.gata.clone()),
+        gatunummer: Some(data.gatunummer.clone()),
+        info: data.info.clone(),
+        dag,
+        tid: tid.clone(),
+        taxa: data.taxa.clone(),
+        antal_platser: data.antal_platser,
+        typ_av_parkering: data.typ_av_parkering.clone(),
+        year,
+        month,
+    })
+    .map(|db| db.with_dagar(dagar));
+    Some(restriction.map_or(TimeBucket::Invalid, |db| bucket_at(&db, now)))
+}
+/// Print how many matched addresses fall in each time bucket, with samples
+fn print_bucket_summary(merged: &[OutputDataWithDistance], now: DateTime<Utc>) {
+    const SAMPLES: usize = 5;
+    let mut buckets: BTreeMap<TimeBucket, Vec<&OutputData>> = BTreeMap::new();
+    for result in merged {
+        if let Some(bucket) = bucket_of(&result.data, now) {
+            buckets.entry(bucket).or_default().push(&result.data);
+        }
+    }
+    let total: usize = buckets.values().map(Vec::len).sum();
+    println!(
+        "\n🕒 Miljö restrictions by time bucket ({} addresses, at {}):",
+        total,
+        now.with_timezone(&SWEDISH_TZ).format("%Y-%m-%d %H:%M"),
+    );
+    for (bucket, entries) in &buckets {
+        println!(
+            "  {:<15} {:>6} ({:.1}%)",
+            format!("{:?}", bucket),
+            entries.len(),
+            (entries.len() as f64 / total as f64) * 100.0,
+        );
+        for entry in entries.iter().take(SAMPLES) {
+            println!(
+                "    ├─ {}: dag {}, tid {}",
+                entry.adress,
+                format_dagar(&entry.dagar),
+                entry.tid.as_deref().unwrap_or_default(),
+            );
+        }
+    }
 }
 fn run_correlation(
     algorithm: AlgorithmChoice,
-    cutoff: f64,
+    miljo: MiljoOptions,
+    verbose: bool,
+    by_bucket: bool,
+    data_paths: &DataPaths,
+    strict: bool,
 ) -> Result<(), Box<dyn std::error::Error>> {
+    let MiljoOptions {
+        cutoff,
+        min_segment_length,
+        adaptive,
+    } = miljo;
     let pb = ProgressBar::new_spinner();
     pb.set_style(ProgressStyle::default_spinner().template("{spinner:.cyan} {msg}")?);
     pb.set_message("Loading data...");
-    let (addresses, miljodata, parkering): (
-        Vec<AdressClean>,
-        Vec<MiljoeDataClean>,
-        Vec<ParkeringsDataClean>,
-    ) = api()?;
+    let DataSet {
+        addresses,
+        miljo: miljodata,
+        parkering,
+    } = load_all_data(data_paths, strict)?;
     pb.finish_with_message(format!(
         "✓ Loaded {} addresses, {} miljödata zones, {} parkering zones",
         addresses.len(),
@@ -594,7 +1198,17 @@ fn run_correlation(
     println!("  Addresses: {}", addresses.len());
     println!("  Miljödata zones: {}", miljodata.len());
     println!("  Parkering zones: {}\n", parkering.len());
-    println!("  Distance threshold: {} meters\n", cutoff);
+    println!("  Distance threshold: {} meters", cutoff);
+    if let Some(min) = min_segment_length {
+        println!("  Minimum segment length: {} meters", min);
+    }
+    if let Some(bounds) = adaptive {
+        println!(
+            "  Adaptive miljö cutoff: {}-{} meters",
+            bounds.min, bounds.max
+        );
+    }
+    println!();
     let algo_name = format!("{:?}", algorithm);
     println!("🚀 Running correlation with {} algorithm", algo_name);
     let start = Instant::now();
@@ -604,7 +1218,8 @@ fn run_correlation(
             .template("[{bar:40.cyan/blue}] {pos}/{len} {percent}% {msg}")?
             .progress_chars("█▓▒░ "),
     );
-    let miljo_results = correlate_miljoe_dataset(&algorithm, &addresses, &miljodata, cutoff, &pb)?;
+    let (miljo_results, tid_fallbacks) =
+        correlate_miljoe_dataset(&algorithm, &addresses, &miljodata, miljo, &pb)?;
     let parkering_results =
         correlate_parkering_dataset(&algorithm, &addresses, &parkering, cutoff, &pb)?;
     let merged = merge_results(&addresses, &miljo_results, &parkering_results);
@@ -622,9 +1237,10 @@ fn run_correlation(
         .count();
     let duration = start.elapsed();
     pb.finish_with_message(format!("✓ Completed in {:.2?}", duration));
+    let no_location = merged.iter().filter(|r| !r.has_location).count();
     let no_match = merged
         .iter()
-        .filter(|r| r.data.info.is_none() && r.data.taxa.is_none())
+        .filter(|r| r.has_location && !r.data.has_match())
         .count();
     let total_matches = both + miljo_only + parkering_only;
     println!("\n📊 Results:");
@@ -650,10 +1266,24 @@ fn run_correlation(
         (parkering_only as f64 / addresses.len() as f64) * 100.0,
     );
     println!(
-        "  └─ No match: {} ({:.1}%)",
+        "  ├─ No match: {} ({:.1}%)",
         no_match,
         (no_match as f64 / addresses.len() as f64) * 100.0,
     );
+    println!(
+        "  └─ No location: {} ({:.1}%)",
+        no_location,
+        (no_location as f64 / addresses.len() as f64) * 100.0,
+    );
+    println!("  Miljö fallbacks past unusable tid: {}", tid_fallbacks);
+    let reliability = ReliabilityCounts::from_results(&merged);
+    println!(
+        "  Reliable matches: miljö {}/{}, parkering {}/{}",
+        reliability.miljo_reliable,
+        reliability.miljo_reliable + reliability.miljo_unreliable,
+        reliability.parkering_reliable,
+        reliability.parkering_reliable + reliability.parkering_unreliable,
+    );
     println!(
         "  Average time per address: {:.2?}",
         duration / addresses.len() as u32
@@ -661,6 +1291,15 @@ fn run_correlation(
     if total_matches == 0 {
         println!("\n⚠️  Warning: No matches found! Check data files.");
     } else {
+        if verbose {
+            print_match_table(
+                &merged,
+                adaptive.map_or(cutoff, |bounds| cutoff.max(bounds.max)),
+            );
+        }
+        if by_bucket {
+            print_bucket_summary(&merged, Utc::now());
+        }
         let mut rng = thread_rng();
         let mut random_results: Vec<_> = merged
             .iter()
@@ -684,29 +1323,23 @@ fn run_correlation(
         let mut sorted_by_distance: Vec<_> = merged
             .iter()
             .filter(|r| r.data.info.is_some() && r.data.taxa.is_some())
+            .filter_map(|r| Some((r, r.closest_distance()?)))
             .collect();
-        sorted_by_distance.sort_by(|a, b| {
-            b.closest_distance()
-                .partial_cmp(&a.closest_distance())
-                .unwrap()
-        });
+        sorted_by_distance.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+        let cutoff = adaptive.map_or(cutoff, |bounds| cutoff.max(bounds.max));
         println!(
             "\n📏 10 Addresses with Largest Distances (all should be ≤{}m):",
             cutoff as i32,
         );
-        for result in sorted_by_distance.iter().take(10) {
-            if let Some(dist) = result.closest_distance() {
-                println!(
-                    "  {} - {:.2}m ({})",
-                    result.data.adress,
-                    dist,
-                    result.data.dataset_source(),
-                );
-            }
+        for (result, dist) in sorted_by_distance.iter().take(10) {
+            println!(
+                "  {} - {:.2}m ({})",
+                result.data.adress,
+                dist,
+                result.data.dataset_source(),
+            );
         }
-        let exceeds_threshold = sorted_by_distance
-            .iter()
-            .any(|r| r.closest_distance().map(|d| d > cutoff).unwrap_or(false));
+        let exceeds_threshold = sorted_by_distance.iter().any(|&(_, dist)| dist > cutoff);
         if exceeds_threshold {
             println!(
                 "\n⚠️  ERROR: Some matches exceed {}m threshold!",
@@ -724,18 +1357,26 @@ fn run_correlation(
 /// Run correlation and output results to parquet file (server database or Android app)
 fn run_output(
     algorithm: AlgorithmChoice,
-    cutoff: f64,
+    miljo: MiljoOptions,
+    exact_radius: f64,
     output_path: &str,
     generate_android: bool,
+    data_paths: &DataPaths,
+    strict: bool,
 ) -> Result<(), Box<dyn std::error::Error>> {
+    let MiljoOptions {
+        cutoff,
+        min_segment_length,
+        adaptive,
+    } = miljo;
     let pb = ProgressBar::new_spinner();
     pb.set_style(ProgressStyle::default_spinner().template("{spinner:.cyan} {msg}")?);
     pb.set_message("Loading data...");
-    let (addresses, miljodata, parkering): (
-        Vec<AdressClean>,
-        Vec<MiljoeDataClean>,
-        Vec<ParkeringsDataClean>,
-    ) = api()?;
+    let DataSet {
+        addresses,
+        miljo: miljodata,
+        parkering,
+    } = load_all_data(data_paths, strict)?;
     pb.finish_with_message(format!(
         "✓ Loaded {} addresses, {} miljödata zones, {} parkering zones",
         addresses.len(),
@@ -745,6 +1386,16 @@ fn run_output(
     println!("\n📋 Output Configuration:");
     println!("  Algorithm: {:?}", algorithm);
     println!("  Distance cutoff: {} meters", cutoff);
+    if let Some(min) = min_segment_length {
+        println!("  Minimum segment length: {} meters", min);
+    }
+    if let Some(bounds) = adaptive {
+        println!(
+            "  Adaptive miljö cutoff: {}-{} meters",
+            bounds.min, bounds.max
+        );
+    }
+    println!("  Exact match radius: {} meters", exact_radius);
     println!("  Output file: {}", output_path);
     if generate_android {
         println!("  Android format: Enabled (extracting day/time data)");
@@ -758,7 +1409,8 @@ fn run_output(
             .progress_chars("█▓▒░ "),
     );
     pb.set_message("Correlating with miljödata...");
-    let miljo_results = correlate_miljoe_dataset(&algorithm, &addresses, &miljodata, cutoff, &pb)?;
+    let (miljo_results, tid_fallbacks) =
+        correlate_miljoe_dataset(&algorithm, &addresses, &miljodata, miljo, &pb)?;
     pb.set_message("Correlating with parkering...");
     let parkering_results =
         correlate_parkering_dataset(&algorithm, &addresses, &parkering, cutoff, &pb)?;
@@ -766,6 +1418,7 @@ fn run_output(
     pb.finish_with_message(format!("✓ Completed in {:.2?}", duration));
     let merged = merge_results(&addresses, &miljo_results, &parkering_results);
     let total_matches = merged.iter().filter(|r| r.data.info.is_some()).count();
+    let no_location = merged.iter().filter(|r| !r.has_location).count();
     println!("\n✓ Correlation complete");
     println!(
         "  Total matches: {}/{} ({:.1}%)",
@@ -773,20 +1426,40 @@ fn run_output(
         addresses.len(),
         (total_matches as f64 / addresses.len() as f64) * 100.0,
     );
-    println!("\n💾 Writing server parquet file...");
+    println!("  Skipped without location: {}", no_location);
+    println!("  Miljö fallbacks past unusable tid: {}", tid_fallbacks);
+    let quality_cutoff = adaptive.map_or(cutoff, |bounds| bounds.max.max(cutoff));
     let output_data: Vec<OutputData> = merged
         .iter()
         .filter(|r| r.data.has_match())
-        .map(|r| r.data.clone())
+        .map(|r| with_match_quality(r, exact_radius, quality_cutoff))
         .collect();
+    let exact = output_data
+        .iter()
+        .filter(|d| d.match_quality == Some(MatchQuality::Exact))
+        .count();
+    println!(
+        "  Exact matches (within {} m): {}/{}",
+        exact_radius,
+        exact,
+        output_data.len(),
+    );
+    println!("\n💾 Writing server parquet file...");
     println!(
         "  Filtered: {}/{} entries with matches ({:.1}%)",
         output_data.len(),
         merged.len(),
         (output_data.len() as f64 / merged.len() as f64) * 100.0,
     );
-    write_output_parquet(output_data.clone(), "../android/assets/data/db.parquet")
-        .map_err(|e| format!("Failed to write parquet: {}", e))?;
+    // Bundled into the APK, so favour size over write speed
+    write_output_parquet_matched_only_with_options(
+        output_data.clone(),
+        "../android/assets/data/db.parquet",
+        ParquetWriteOptions::zstd(),
+    )
+    .map_err(|e| format!("Failed to write parquet: {}", e))?;
+    record_asset_checksum("../android/assets/data/db.parquet")
+        .map_err(|e| format!("Failed to record checksum: {}", e))?;
     println!("  ✓ Saved to {}", output_path);
     println!("  ✓ Wrote {} entries with matches", output_data.len());
     println!("\n✅ Output complete!");
@@ -796,15 +1469,17 @@ fn run_test_mode(
     algorithm: AlgorithmChoice,
     cutoff: f64,
     num_windows: usize,
+    data_paths: &DataPaths,
+    strict: bool,
 ) -> Result<(), Box<dyn std::error::Error>> {
     let pb = ProgressBar::new_spinner();
     pb.set_style(ProgressStyle::default_spinner().template("{spinner:.cyan} {msg}")?);
     pb.set_message("Loading data for testing...");
-    let (addresses, miljodata, parkering): (
-        Vec<AdressClean>,
-        Vec<MiljoeDataClean>,
-        Vec<ParkeringsDataClean>,
-    ) = api()?;
+    let DataSet {
+        addresses,
+        miljo: miljodata,
+        parkering,
+    } = load_all_data(data_paths, strict)?;
     pb.finish_with_message(format!(
         "✓ Loaded {} addresses, {} miljödata zones, {} parkering zones",
         addresses.len(),
@@ -822,11 +1497,18 @@ fn run_test_mode(
             .template("[{bar:40.cyan/blue}] {pos}/{len} {percent}%")?
             .progress_chars("█▓▒░ "),
     );
-    let miljo_results = correlate_miljoe_dataset(&algorithm, &addresses, &miljodata, cutoff, &pb)?;
+    let (miljo_results, _) = correlate_miljoe_dataset(
+        &algorithm,
+        &addresses,
+        &miljodata,
+        MiljoOptions::fixed(cutoff),
+        &pb,
+    )?;
     let parkering_results =
         correlate_parkering_dataset(&algorithm, &addresses, &parkering, cutoff, &pb)?;
     pb.finish_with_message("✓ Correlation complete".to_string());
     let merged = merge_results(&addresses, &miljo_results, &parkering_results);
+    let taxa_rates = malmo_taxa_table();
     let matching_addresses: Vec<_> = merged.iter().filter(|r| r.data.has_match()).collect();
     if matching_addresses.is_empty() {
         println!("\n❌ No matching addresses found for testing!");
@@ -851,20 +1533,7 @@ fn run_test_mode(
     println!("    - Tab 3: Correlation data visualization");
     println!("    - Tab 4: Debug console with address search logs\n");
     for (idx, result) in selected.iter().enumerate() {
-        let corr_result = CorrelationResult {
-            address: result.data.adress.clone(),
-            postnummer: result.data.postnummer.clone().unwrap_or_default(),
-            miljo_match: result
-                .data
-                .info
-                .as_ref()
-                .map(|info| (result.miljo_distance.unwrap_or(0.0), info.clone())),
-            parkering_match: result
-                .data
-                .taxa
-                .as_ref()
-                .map(|taxa| (result.parkering_distance.unwrap_or(0.0), taxa.clone())),
-        };
+        let corr_result = correlation_result(result, cutoff, &taxa_rates);
         println!(
             "  [{}/{}] Opening window for: {}",
             idx + 1,
@@ -885,6 +1554,145 @@ fn run_test_mode(
     );
     Ok(())
 }
+/// Convert a merged row into a [`CorrelationResult`], using `info` and `taxa` as match text
+fn correlation_result(
+    result: &OutputDataWithDistance,
+    cutoff: f64,
+    taxa_rates: &HashMap<String, Decimal>,
+) -> CorrelationResult {
+    CorrelationResult::new(
+        result.data.adress.clone(),
+        result.data.postnummer.clone().unwrap_or_default(),
+        result
+            .data
+            .info
+            .as_ref()
+            .map(|info| (result.miljo_distance.unwrap_or(0.0), info.clone())),
+        result
+            .data
+            .taxa
+            .as_ref()
+            .map(|taxa| (result.parkering_distance.unwrap_or(0.0), taxa.clone())),
+    )
+    .with_cutoff(cutoff)
+    .with_taxa_rates(taxa_rates)
+}
+/// Write matched addresses to CSV with [`write_output_csv`].
+///
+/// Rows come from `input`, an output parquet file, if given, and otherwise
+/// from correlating both datasets, which also fills in the distances.
+fn run_export(
+    input: Option<&str>,
+    algorithm: AlgorithmChoice,
+    cutoff: f64,
+    output_path: &str,
+    data_paths: &DataPaths,
+    strict: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let from_file;
+    let merged;
+    let rows: Vec<CsvRow> = match input {
+        Some(input) => {
+            from_file = read_db_parquet(File::open(input)?)
+                .map_err(|e| format!("Failed to read '{}': {}", input, e))?;
+            from_file.iter().map(|row| (row, None, None)).collect()
+        }
+        None => {
+            merged = correlate_for_export(algorithm, cutoff, data_paths, strict)?;
+            merged
+                .iter()
+                .filter(|r| r.data.has_match())
+                .map(|r| (&r.data, r.miljo_distance, r.parkering_distance))
+                .collect()
+        }
+    };
+    let mut writer = io::BufWriter::new(File::create(output_path)?);
+    write_output_csv(&rows, &mut writer)?;
+    writer.flush()?;
+    println!("✓ Exported {} addresses to {}", rows.len(), output_path);
+    Ok(())
+}
+/// Correlate every address against both datasets for [`run_export`]
+fn correlate_for_export(
+    algorithm: AlgorithmChoice,
+    cutoff: f64,
+    data_paths: &DataPaths,
+    strict: bool,
+) -> Result<Vec<OutputDataWithDistance>, Box<dyn std::error::Error>> {
+    let DataSet {
+        addresses,
+        miljo: miljodata,
+        parkering,
+    } = load_all_data(data_paths, strict)?;
+    println!(
+        "✓ Loaded {} addresses, {} miljödata zones, {} parkering zones",
+        addresses.len(),
+        miljodata.len(),
+        parkering.len(),
+    );
+    let pb = ProgressBar::new(addresses.len() as u64);
+    pb.set_style(
+        ProgressStyle::default_bar()
+            .template("[{bar:40.cyan/blue}] {pos}/{len} {percent}%")?
+            .progress_chars("█▓▒░ "),
+    );
+    let (miljo_results, _) = correlate_miljoe_dataset(
+        &algorithm,
+        &addresses,
+        &miljodata,
+        MiljoOptions::fixed(cutoff),
+        &pb,
+    )?;
+    let parkering_results =
+        correlate_parkering_dataset(&algorithm, &addresses, &parkering, cutoff, &pb)?;
+    pb.finish_with_message("✓ Correlation complete".to_string());
+    Ok(merge_results(
+        &addresses,
+        &miljo_results,
+        &parkering_results,
+    ))
+}
+/// Print every miljö segment within `radius` meters of `address`, closest first
+fn run_inspect(
+    address: &str,
+    radius: f64,
+    data_paths: &DataPaths,
+    strict: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let (addresses, mut warnings) =
+        DataLoader::load_addresses_with_warnings(&data_paths.adresser("json"))?;
+    let (miljodata, miljo_warnings) =
+        DataLoader::load_miljodata_with_warnings(&data_paths.miljo("json"))?;
+    warnings.extend(miljo_warnings);
+    report_load_warnings(&warnings, strict)?;
+    let wanted = address.trim().to_lowercase();
+    let found = addresses
+        .iter()
+        .find(|a| a.adress.to_lowercase() == wanted)
+        .ok_or_else(|| format!("Address not found: {}", address))?;
+    let algo = RTreeSpatialAlgo::new(&miljodata);
+    let nearby = algo.within_radius(found, &miljodata, radius);
+    println!(
+        "\n📍 {} ({}, {}): {} segment(s) within {} m",
+        found.adress,
+        found.coordinates[0],
+        found.coordinates[1],
+        nearby.len(),
+        radius,
+    );
+    for (idx, distance) in nearby {
+        let line = &miljodata[idx];
+        println!(
+            "  {:>7.1} m  #{:<6} {} | {} | dag {}",
+            distance,
+            idx,
+            line.info,
+            line.tid,
+            format_dagar(&line.dagar),
+        );
+    }
+    Ok(())
+}
 /// Get the browser executable to use on Linux
 fn get_browser_executable() -> String {
     if let Ok(browser) = env::var("BROWSER")
@@ -912,23 +1720,29 @@ fn get_browser_executable() -> String {
     "firefox".to_string()
 }
 fn format_matches_html(result: &CorrelationResult) -> String {
+    let note = |reliable: bool| if reliable { "" } else { " (unreliable)" };
+    let miljo_note = note(result.miljo_reliable);
+    let parkering_note = match result.parkering_rate_sek_per_hour {
+        Some(rate) => format!("{} · {} kr/tim", note(result.parkering_reliable), rate),
+        None => note(result.parkering_reliable).to_string(),
+    };
     match (&result.miljo_match, &result.parkering_match) {
         (Some((dist_m, info_m)), Some((dist_p, info_p))) => {
             format!(
-                "\n          <div class=\"match-card\">\n            <div class=\"match-icon\">🌍 Miljödata</div>\n            <div class=\"match-distance\">{:.2}m away</div>\n            <div class=\"match-info\">{}</div>\n          </div>\n\n          <div class=\"match-card\">\n            <div class=\"match-icon\">🅿️ Parkering</div>\n            <div class=\"match-distance\">{:.2}m away</div>\n            <div class=\"match-info\">{}</div>\n          </div>",
-                dist_m, info_m, dist_p, info_p,
+                "\n          <div class=\"match-card\">\n            <div class=\"match-icon\">🌍 Miljödata</div>\n            <div class=\"match-distance\">{:.2}m away{}</div>\n            <div class=\"match-info\">{}</div>\n          </div>\n\n          <div class=\"match-card\">\n            <div class=\"match-icon\">🅿️ Parkering</div>\n            <div class=\"match-distance\">{:.2}m away{}</div>\n            <div class=\"match-info\">{}</div>\n          </div>",
+                dist_m, miljo_note, info_m, dist_p, parkering_note, info_p,
             )
         }
         (Some((dist, info)), None) => {
             format!(
-                "\n          <div class=\"match-card\">\n            <div class=\"match-icon\">🌍 Miljödata</div>\n            <div class=\"match-distance\">{:.2}m away</div>\n            <div class=\"match-info\">{}</div>\n          </div>",
-                dist, info,
+                "\n          <div class=\"match-card\">\n            <div class=\"match-icon\">🌍 Miljödata</div>\n            <div class=\"match-distance\">{:.2}m away{}</div>\n            <div class=\"match-info\">{}</div>\n          </div>",
+                dist, miljo_note, info,
             )
         }
         (None, Some((dist, info))) => {
             format!(
-                "\n          <div class=\"match-card\">\n            <div class=\"match-icon\">🅿️ Parkering</div>\n            <div class=\"match-distance\">{:.2}m away</div>\n            <div class=\"match-info\">{}</div>\n          </div>",
-                dist, info,
+                "\n          <div class=\"match-card\">\n            <div class=\"match-icon\">🅿️ Parkering</div>\n            <div class=\"match-distance\">{:.2}m away{}</div>\n            <div class=\"match-info\">{}</div>\n          </div>",
+                dist, parkering_note, info,
             )
         }
         (None, None) => "          <div>✗ No matches found</div>".to_string(),
@@ -999,11 +1813,25 @@ fn open_browser_window(
     }
     Ok(())
 }
-fn run_benchmark(sample_size: usize, cutoff: f64) -> Result<(), Box<dyn std::error::Error>> {
+fn run_benchmark(
+    sample_size: usize,
+    cutoff: f64,
+    coordinate_math: bool,
+    data_paths: &DataPaths,
+    strict: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
     let pb = ProgressBar::new_spinner();
     pb.set_style(ProgressStyle::default_spinner().template("{spinner:.cyan} {msg}")?);
     pb.set_message("Loading data for benchmarking...");
-    let (addresses, zones) = amp_core::api::api_miljo_only()?;
+    let (
+        DataSet {
+            addresses,
+            miljo: zones,
+            ..
+        },
+        warnings,
+    ) = DataSet::load_miljo_only_from_paths_with_warnings(data_paths)?;
+    report_load_warnings(&warnings, strict)?;
     let actual_sample_size = sample_size.min(addresses.len());
     let requested_msg = if sample_size > addresses.len() {
         format!(
@@ -1061,8 +1889,55 @@ fn run_benchmark(sample_size: usize, cutoff: f64) -> Result<(), Box<dyn std::err
         cutoff as i32
     );
     Benchmarker::print_results(&results);
+    print_accuracy(&benchmarker, actual_sample_size, &selected_algos, cutoff);
+    if coordinate_math {
+        println!("\n🔢 Decimal vs f64 coordinate math:");
+        let comparison = benchmarker.benchmark_coordinate_math(Some(actual_sample_size));
+        Benchmarker::print_coordinate_math_result(&comparison);
+    }
     Ok(())
 }
+/// Print how often each selected algorithm agrees with the brute-force Distance-Based search
+fn print_accuracy(
+    benchmarker: &Benchmarker,
+    sample_size: usize,
+    selected_algos: &[&str],
+    cutoff: f64,
+) {
+    let sample = Benchmarker::new(
+        benchmarker.addresses[..sample_size].to_vec(),
+        benchmarker.parking_lines.clone(),
+    );
+    let lines = &sample.parking_lines;
+    let candidates: Vec<Box<dyn CorrelationAlgo + Sync>> = selected_algos
+        .iter()
+        .filter_map(|name| {
+            let candidate: Box<dyn CorrelationAlgo + Sync> = match *name {
+                "Raycasting" => Box::new(RaycastingAlgo),
+                "Overlapping Chunks" => Box::new(OverlappingChunksAlgo::new(lines)),
+                "R-Tree" => Box::new(RTreeSpatialAlgo::new(lines)),
+                "KD-Tree" => Box::new(KDTreeSpatialAlgo::new(lines)),
+                "Grid" => Box::new(GridNearestAlgo::new(lines)),
+                "Ball Tree" => Box::new(BallTreeSpatialAlgo::new(lines)),
+                _ => return None,
+            };
+            Some(candidate)
+        })
+        .collect();
+    if candidates.is_empty() {
+        return;
+    }
+    let candidates: Vec<&(dyn CorrelationAlgo + Sync)> = candidates
+        .iter()
+        .map(|candidate| candidate.as_ref())
+        .collect();
+    let reports = sample.accuracy_vs_many(&DistanceBasedAlgo, &candidates, cutoff);
+    println!(
+        "\n🎯 Accuracy vs Distance-Based (distance cutoff: {}m):",
+        cutoff as i32
+    );
+    Benchmarker::print_accuracy_reports(&reports);
+}
 type AlgorithmBenchmarkFn =
     Box<dyn Fn(&Benchmarker, &[AdressClean], &ProgressBar, &AtomicUsize, &Arc<AtomicUsize>, f64)>;
 fn benchmark_selected_with_progress(
@@ -1172,6 +2047,22 @@ fn benchmark_selected_with_progress(
                 );
             }),
         ),
+        (
+            "Ball Tree",
+            Box::new(|bm, addrs, pb, matches, counter, cutoff| {
+                let algo = BallTreeSpatialAlgo::new(&bm.parking_lines);
+                run_single_benchmark(
+                    &algo,
+                    addrs,
+                    &bm.parking_lines,
+                    pb,
+                    matches,
+                    counter,
+                    "Ball Tree",
+                    cutoff,
+                );
+            }),
+        ),
     ];
     let mut pb_idx = 0;
     for (name, run_fn) in all_algos.iter() {
@@ -1228,16 +2119,17 @@ fn run_single_benchmark<A: CorrelationAlgo + Sync>(
     });
     pb.set_position(addresses.len() as u64);
 }
+/// Malmö open data portal pages for each source
+const MILJO_URL: &str = "https://opendata.malmo.se/@fastighets-och-gatukontoret/miljoparkering/73490f00-0d71-4b17-903c-f77ab7664a53";
+const PARKERING_URL: &str = "https://opendata.malmo.se/@fastighets-och-gatukontoret/parkeringsavgifter/1a6bd68b-30ca-40a5-9d62-01e2a566982e";
+const ADRESSER_URL: &str = "https://opendata.malmo.se/@stadsbyggnadskontoret/adresser/caf1cee8-9af2-4a75-8fb7-f1d7cb11daeb";
 async fn check_updates(checksum_file: &str) -> Result<(), Box<dyn std::error::Error>> {
     println!("\n🔍 Checking for data updates...\n");
     let old_checksums = DataChecksum::load_from_file(checksum_file).ok();
     let mut new_checksums = DataChecksum::new(
-        "https://opendata.malmo.se/@fastighets-och-gatukontoret/miljoparkering/73490f00-0d71-4b17-903c-f77ab7664a53"
-            .to_string(),
-        "https://opendata.malmo.se/@fastighets-och-gatukontoret/parkeringsavgifter/1a6bd68b-30ca-40a5-9d62-01e2a566982e"
-            .to_string(),
-        "https://opendata.malmo.se/@stadsbyggnadskontoret/adresser/caf1cee8-9af2-4a75-8fb7-f1d7cb11daeb"
-            .to_string(),
+        MILJO_URL.to_string(),
+        PARKERING_URL.to_string(),
+        ADRESSER_URL.to_string(),
     );
     let pb = ProgressBar::new_spinner();
     pb.set_style(ProgressStyle::default_spinner().template("{spinner:.cyan} {msg}")?);
@@ -1245,10 +2137,19 @@ async fn check_updates(checksum_file: &str) -> Result<(), Box<dyn std::error::Er
     new_checksums.update_from_remote().await?;
     pb.finish_with_message("✓ Data fetched");
     if let Some(old) = old_checksums {
-        if new_checksums.has_changed(&old) {
+        let changed = new_checksums.changed_sources(&old);
+        if !changed.is_empty() {
             println!("\n✓ Data has changed!");
             println!("  Old checksums from: {}", old.last_checked);
             println!("  New checksums from: {}", new_checksums.last_checked);
+            for source in DataSource::ALL {
+                let status = if changed.contains(&source) {
+                    "changed"
+                } else {
+                    "unchanged"
+                };
+                println!("  {:<20} {}", source.name(), status);
+            }
         } else {
             println!("\n✓ Data is up to date (no changes detected)");
         }
@@ -1259,3 +2160,441 @@ async fn check_updates(checksum_file: &str) -> Result<(), Box<dyn std::error::Er
     println!("✓ Checksums saved to {}\n", checksum_file);
     Ok(())
 }
+/// Upper bound for the retry delay after failed checks or refreshes
+const MAX_SERVE_BACKOFF: Duration = Duration::from_secs(6 * 3600);
+/// Settings for the `serve` subcommand
+struct ServeConfig {
+    algorithm: AlgorithmChoice,
+    cutoff: f64,
+    min_segment_length: Option<f64>,
+    exact_radius: f64,
+    output: String,
+    interval: Duration,
+    checksum_file: String,
+    data_paths: DataPaths,
+    strict: bool,
+}
+/// One fully correlated build of the bundled data
+struct ServedDataset {
+    output: Vec<OutputData>,
+    addresses: usize,
+    miljo: usize,
+    parkering: usize,
+    /// SHA256 of the local GeoJSON sources this build was made from
+    source_fingerprint: String,
+    built_at: String,
+}
+/// Current dataset, swapped as a whole when a refresh succeeds
+///
+/// Readers clone the inner `Arc` and keep a consistent snapshot even while
+/// a refresh replaces it.
+type SharedDataset = Arc<RwLock<Arc<ServedDataset>>>;
+/// Combined checksum of the local GeoJSON sources
+fn local_source_fingerprint(data_paths: &DataPaths) -> Result<String, Box<dyn std::error::Error>> {
+    let mut combined = String::new();
+    for path in [
+        data_paths.adresser("json"),
+        data_paths.miljo("json"),
+        data_paths.parkering("json"),
+    ] {
+        combined.push_str(&DataChecksum::calculate_file_checksum(&path)?);
+    }
+    Ok(combined)
+}
+/// Run the GeoJSON → correlate → OutputData pipeline once
+fn build_served_dataset(config: &ServeConfig) -> Result<ServedDataset, Box<dyn std::error::Error>> {
+    let source_fingerprint = local_source_fingerprint(&config.data_paths)?;
+    let DataSet {
+        addresses,
+        miljo,
+        parkering,
+    } = load_all_data(&config.data_paths, config.strict)?;
+    let pb = ProgressBar::hidden();
+    let (miljo_results, _) = correlate_miljoe_dataset(
+        &config.algorithm,
+        &addresses,
+        &miljo,
+        MiljoOptions {
+            min_segment_length: config.min_segment_length,
+            ..MiljoOptions::fixed(config.cutoff)
+        },
+        &pb,
+    )?;
+    let parkering_results = correlate_parkering_dataset(
+        &config.algorithm,
+        &addresses,
+        &parkering,
+        config.cutoff,
+        &pb,
+    )?;
+    let output = merge_results(&addresses, &miljo_results, &parkering_results)
+        .iter()
+        .filter(|r| r.data.has_match())
+        .map(|r| with_match_quality(r, config.exact_radius, config.cutoff))
+        .collect();
+    Ok(ServedDataset {
+        output,
+        addresses: addresses.len(),
+        miljo: miljo.len(),
+        parkering: parkering.len(),
+        source_fingerprint,
+        built_at: chrono::Utc::now().to_rfc3339(),
+    })
+}
+/// Write the parquet next to its destination and rename it into place
+///
+/// Consumers reading `path` see either the old or the new file, never a
+/// partially written one.
+fn publish_output(dataset: &ServedDataset, path: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let tmp_path = format!("{}.{}.tmp", path, std::process::id());
+    write_output_parquet_matched_only(dataset.output.clone(), &tmp_path)
+        .map_err(|e| format!("Failed to write parquet: {}", e))?;
+    fs::rename(&tmp_path, path)?;
+    Ok(())
+}
+/// Rebuild the dataset, publish it and swap it into `shared`
+fn refresh_served_dataset(
+    config: &ServeConfig,
+    shared: &SharedDataset,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let start = Instant::now();
+    let fresh = build_served_dataset(config)?;
+    publish_output(&fresh, &config.output)?;
+    let fresh = Arc::new(fresh);
+    let previous = {
+        let mut guard = shared.write().map_err(|_| "Served dataset lock poisoned")?;
+        std::mem::replace(&mut *guard, Arc::clone(&fresh))
+    };
+    println!(
+        "🔄 [Serve] Refreshed in {:.2?}: rows {} → {}, addresses {} → {}, miljö {} → {}, parkering {} → {}",
+        start.elapsed(),
+        previous.output.len(),
+        fresh.output.len(),
+        previous.addresses,
+        fresh.addresses,
+        previous.miljo,
+        fresh.miljo,
+        previous.parkering,
+        fresh.parkering,
+    );
+    Ok(())
+}
+/// Fetch remote checksums and compare them with the saved ones
+///
+/// Changed checksums are returned instead of saved, so a failed rebuild is
+/// retried on the next check; see [`rebuild_then_save_checksum`]. A missing
+/// checksum file is treated as "unchanged" and becomes the baseline.
+async fn remote_data_changed(
+    checksum_file: &str,
+) -> Result<Option<DataChecksum>, Box<dyn std::error::Error>> {
+    let old = DataChecksum::load_from_file(checksum_file).ok();
+    let mut new = match &old {
+        Some(old) => DataChecksum::new(
+            old.miljo_url.clone(),
+            old.parkering_url.clone(),
+            old.adresser_url.clone(),
+        ),
+        None => DataChecksum::new(
+            MILJO_URL.to_string(),
+            PARKERING_URL.to_string(),
+            ADRESSER_URL.to_string(),
+        ),
+    };
+    new.update_from_remote().await?;
+    if old.is_some_and(|old| new.has_changed(&old)) {
+        return Ok(Some(new));
+    }
+    new.save_to_file(checksum_file)?;
+    Ok(None)
+}
+/// Run `rebuild` and save the `pending` checksums only once it succeeded
+fn rebuild_then_save_checksum<F>(
+    rebuild: F,
+    pending: Option<&DataChecksum>,
+    checksum_file: &str,
+) -> Result<(), Box<dyn std::error::Error>>
+where
+    F: FnOnce() -> Result<(), Box<dyn std::error::Error>>,
+{
+    rebuild()?;
+    if let Some(checksum) = pending {
+        checksum.save_to_file(checksum_file)?;
+    }
+    Ok(())
+}
+/// Delay after `current` failed: doubled, capped at [`MAX_SERVE_BACKOFF`]
+fn next_backoff(current: Duration) -> Duration {
+    (current * 2).min(MAX_SERVE_BACKOFF)
+}
+/// What woke the watch loop
+enum ServeTrigger {
+    Timer,
+    Manual,
+}
+/// Manual refresh requests, delivered as SIGHUP on Unix
+///
+/// Registered once up front so a SIGHUP arriving mid-refresh is queued
+/// instead of terminating the process.
+struct RefreshSignal {
+    #[cfg(unix)]
+    hangup: Option<tokio::signal::unix::Signal>,
+}
+impl RefreshSignal {
+    fn new() -> Self {
+        Self {
+            #[cfg(unix)]
+            hangup: tokio::signal::unix::signal(tokio::signal::unix::SignalKind::hangup()).ok(),
+        }
+    }
+    /// Resolve on the next request; never resolves if signals are unavailable
+    async fn recv(&mut self) {
+        #[cfg(unix)]
+        if let Some(hangup) = self.hangup.as_mut() {
+            hangup.recv().await;
+            return;
+        }
+        std::future::pending::<()>().await
+    }
+}
+/// Build the dataset once and, with `watch`, keep it in sync with the sources
+///
+/// Every `interval` the Malmö portal checksums and the local GeoJSON files
+/// are compared with the last build; if either changed the pipeline is re-run
+/// and the result swapped in. Sending SIGHUP forces an immediate rebuild.
+/// Failed checks and rebuilds retry with exponential backoff, keeping the
+/// previous dataset live in the meantime.
+async fn run_serve(config: ServeConfig, watch: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let mut refresh_signal = RefreshSignal::new();
+    println!("\n🚀 [Serve] Building initial dataset...");
+    let initial = tokio::task::block_in_place(|| build_served_dataset(&config))?;
+    publish_output(&initial, &config.output)?;
+    println!(
+        "✓ [Serve] {} rows from {} addresses, {} miljö and {} parkering zones → {}",
+        initial.output.len(),
+        initial.addresses,
+        initial.miljo,
+        initial.parkering,
+        config.output,
+    );
+    let shared: SharedDataset = Arc::new(RwLock::new(Arc::new(initial)));
+    if !watch {
+        return Ok(());
+    }
+    println!(
+        "👀 [Serve] Watching for updates every {:?} (send SIGHUP to refresh now)",
+        config.interval,
+    );
+    let mut delay = config.interval;
+    loop {
+        let trigger = tokio::select! {
+            _ = tokio::time::sleep(delay) => ServeTrigger::Timer,
+            _ = refresh_signal.recv() => ServeTrigger::Manual,
+        };
+        let mut pending_checksum = None;
+        let should_refresh = match trigger {
+            ServeTrigger::Manual => {
+                println!("📨 [Serve] Manual refresh requested");
+                true
+            }
+            ServeTrigger::Timer => {
+                let current =
+                    Arc::clone(&*shared.read().map_err(|_| "Served dataset lock poisoned")?);
+                let local_changed = match local_source_fingerprint(&config.data_paths) {
+                    Ok(fingerprint) => fingerprint != current.source_fingerprint,
+                    Err(e) => {
+                        eprintln!("⚠️  [Serve] Failed to checksum local data: {}", e);
+                        false
+                    }
+                };
+                match remote_data_changed(&config.checksum_file).await {
+                    Ok(changed_checksum) => {
+                        let remote_changed = changed_checksum.is_some();
+                        pending_checksum = changed_checksum;
+                        println!(
+                            "🔍 [Serve] Checked at {} (dataset from {}): remote {}, local {}",
+                            chrono::Utc::now().to_rfc3339(),
+                            current.built_at,
+                            if remote_changed {
+                                "changed"
+                            } else {
+                                "unchanged"
+                            },
+                            if local_changed {
+                                "changed"
+                            } else {
+                                "unchanged"
+                            },
+                        );
+                        remote_changed || local_changed
+                    }
+                    Err(e) => {
+                        delay = next_backoff(delay);
+                        eprintln!(
+                            "⚠️  [Serve] Update check failed: {} (retrying in {:?})",
+                            e, delay
+                        );
+                        continue;
+                    }
+                }
+            }
+        };
+        if !should_refresh {
+            delay = config.interval;
+            continue;
+        }
+        match tokio::task::block_in_place(|| {
+            rebuild_then_save_checksum(
+                || refresh_served_dataset(&config, &shared),
+                pending_checksum.as_ref(),
+                &config.checksum_file,
+            )
+        }) {
+            Ok(()) => delay = config.interval,
+            Err(e) => {
+                delay = next_backoff(delay);
+                eprintln!(
+                    "⚠️  [Serve] Refresh failed, keeping previous dataset: {} (retrying in {:?})",
+                    e, delay
+                );
+            }
+        }
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn test_failed_rebuild_keeps_previous_checksum() {
+        let path = env::temp_dir().join(format!("amp_checksum_test_{}.json", std::process::id()));
+        let path = path.to_str().unwrap();
+        let saved = DataChecksum::new("m".to_string(), "p".to_string(), "a".to_string());
+        saved.save_to_file(path).unwrap();
+        let mut fresh = saved.clone();
+        fresh.miljo_checksum = "changed".to_string();
+        let result =
+            rebuild_then_save_checksum(|| Err("rebuild failed".into()), Some(&fresh), path);
+        assert!(result.is_err());
+        assert!(
+            !DataChecksum::load_from_file(path)
+                .unwrap()
+                .has_changed(&saved)
+        );
+        rebuild_then_save_checksum(|| Ok(()), Some(&fresh), path).unwrap();
+        assert!(
+            DataChecksum::load_from_file(path)
+                .unwrap()
+                .has_changed(&saved)
+        );
+        fs::remove_file(path).ok();
+    }
+    #[test]
+    fn test_next_backoff_doubles_and_caps() {
+        assert_eq!(
+            next_backoff(Duration::from_secs(60)),
+            Duration::from_secs(120)
+        );
+        assert_eq!(next_backoff(MAX_SERVE_BACKOFF), MAX_SERVE_BACKOFF);
+        assert_eq!(
+            next_backoff(Duration::from_secs(4 * 3600)),
+            MAX_SERVE_BACKOFF
+        );
+    }
+    #[test]
+    fn test_address_without_location_is_flagged_not_matched() {
+        let address = AdressClean {
+            adress: "Placeholder 1".to_string(),
+            gata: "Placeholder".to_string(),
+            gatunummer: "1".to_string(),
+            ..AdressClean::default()
+        };
+        let zone = ParkeringsDataClean {
+            coordinates: [
+                [Decimal::new(-1, 5), Decimal::ZERO],
+                [Decimal::new(1, 5), Decimal::ZERO],
+            ],
+            taxa: "Taxa C".to_string(),
+            antal_platser: 10,
+            typ_av_parkering: "Längsgående".to_string(),
+        };
+        let addresses = vec![address];
+        let parkering = correlate_parkering_dataset(
+            &AlgorithmChoice::DistanceBased,
+            &addresses,
+            &[zone],
+            20.0,
+            &ProgressBar::hidden(),
+        )
+        .unwrap();
+        assert!(parkering.is_empty());
+        let merged = merge_results(&addresses, &[], &parkering);
+        assert_eq!(merged.len(), 1);
+        assert!(!merged[0].has_location);
+        assert!(!merged[0].data.has_match());
+    }
+    #[test]
+    fn test_output_parquet_keeps_segment_id() {
+        let address = AdressClean {
+            coordinates: [Decimal::new(130_000, 4), Decimal::new(556_000, 4)],
+            postnummer: Some("21438".to_string()),
+            adress: "Storgatan 1".to_string(),
+            gata: "Storgatan".to_string(),
+            gatunummer: "1".to_string(),
+        };
+        let mut segment = MiljoeDataClean::new(
+            [
+                [Decimal::new(130_000, 4), Decimal::new(556_000, 4)],
+                [Decimal::new(130_001, 4), Decimal::new(556_000, 4)],
+            ],
+            "Städning".to_string(),
+            "0800-1200".to_string(),
+            15,
+        );
+        segment.segment_id = Some(42);
+        let merged = merge_results(
+            std::slice::from_ref(&address),
+            &[(address.adress.clone(), 3.0, segment)],
+            &[],
+        );
+        let output: Vec<OutputData> = merged
+            .iter()
+            .map(|r| with_match_quality(r, DEFAULT_EXACT_RADIUS_METERS, 20.0))
+            .collect();
+        assert_eq!(output[0].segment_id, Some(42));
+        let path = env::temp_dir().join(format!(
+            "amp_segment_id_test_{}.parquet",
+            std::process::id()
+        ));
+        write_output_parquet_matched_only(output, path.to_str().unwrap()).unwrap();
+        let read = read_db_parquet(File::open(&path).unwrap()).unwrap();
+        fs::remove_file(&path).ok();
+        assert_eq!(read.len(), 1);
+        assert_eq!(read[0].segment_id, Some(42));
+        assert_eq!(read[0].match_quality, Some(MatchQuality::Exact));
+    }
+    #[test]
+    fn test_write_output_csv_quotes_and_empty_cells() {
+        let row = OutputData {
+            postnummer: None,
+            adress: "Storgatan 1".to_string(),
+            gata: "Storgatan".to_string(),
+            gatunummer: "1".to_string(),
+            info: Some("Städning, \"norra\" sidan\nvarje månad".to_string()),
+            tid: Some("0800-1200".to_string()),
+            dag: Some(15),
+            dagar: vec![15],
+            taxa: None,
+            antal_platser: Some(26),
+            typ_av_parkering: Some("Längsgående 6".to_string()),
+            match_quality: None,
+            segment_id: None,
+        };
+        let mut out = Vec::new();
+        write_output_csv(&[(&row, Some(3.456), None)], &mut out).unwrap();
+        let csv = String::from_utf8(out).unwrap();
+        assert_eq!(
+            csv,
+            "\u{feff}adress,gata,gatunummer,postnummer,info,tid,dag,taxa,antal_platser,typ_av_parkering,miljo_distance,parkering_distance\n\
+             Storgatan 1,Storgatan,1,,\"Städning, \"\"norra\"\" sidan\nvarje månad\",0800-1200,15,,26,Längsgående 6,3.46,\n"
+        );
+    }
+}